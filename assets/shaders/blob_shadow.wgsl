@@ -0,0 +1,53 @@
+// Пятно тени под машиной: прямоугольник на земле с мягкими краями
+
+struct Camera {
+    view_proj: mat4x4<f32>,
+    view_position: vec3<f32>,
+};
+
+@group(0) @binding(0)
+var<uniform> camera: Camera;
+
+struct VertexInput {
+    @location(0) position: vec3<f32>,
+    @location(1) tex_coords: vec2<f32>,
+    @location(2) normal: vec3<f32>,
+};
+
+struct InstanceInput {
+    @location(3) center_opacity: vec4<f32>,
+    @location(4) axis_x: vec4<f32>,
+    @location(5) axis_z: vec4<f32>,
+};
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) local: vec2<f32>,
+    @location(1) opacity: f32,
+};
+
+@vertex
+fn vs_main(vertex: VertexInput, instance: InstanceInput) -> VertexOutput {
+    // Квад растительности: x в -0.5..0.5, y в 0..1 -> приводим к -1..1
+    let corner = vec2<f32>(vertex.position.x * 2.0, vertex.position.y * 2.0 - 1.0);
+    let axis_x = instance.axis_x.xyz;
+    let axis_z = instance.axis_z.xyz;
+    let up = normalize(cross(axis_z, axis_x));
+    // Небольшой подъем над землей против z-fighting
+    let world_position = instance.center_opacity.xyz + axis_x * corner.x + axis_z * corner.y + up * 0.02;
+
+    var out: VertexOutput;
+    out.clip_position = camera.view_proj * vec4<f32>(world_position, 1.0);
+    out.local = corner;
+    out.opacity = instance.center_opacity.w;
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    // Скругленный прямоугольник, затухающий к краям
+    let q = abs(in.local);
+    let d = length(max(q - vec2<f32>(0.6, 0.75), vec2<f32>(0.0))) / 0.4;
+    let shade = 1.0 - smoothstep(0.0, 1.0, d);
+    return vec4<f32>(0.0, 0.0, 0.0, shade * in.opacity);
+}
@@ -0,0 +1,68 @@
+// Автоэкспозиция: средняя логарифмическая яркость HDR-кадра и плавная адаптация
+
+struct ExposureParams {
+    manual_exposure: f32,
+    auto_exposure: f32,   // 1 - автоматическая, 0 - ручная
+    key_value: f32,       // Целевая средняя яркость после экспозиции
+    adaptation: f32,      // Доля пути к новой яркости за этот кадр
+    min_luminance: f32,
+    max_luminance: f32,
+};
+
+struct ExposureState {
+    adapted_luminance: f32,
+    exposure: f32,
+};
+
+@group(0) @binding(0)
+var hdr_texture: texture_2d<f32>;
+@group(0) @binding(1)
+var<uniform> params: ExposureParams;
+@group(0) @binding(2)
+var<storage, read_write> state: ExposureState;
+
+const GROUP_SIZE: u32 = 16u;
+const SAMPLES_PER_THREAD: u32 = 4u;
+
+var<workgroup> partial_sums: array<f32, 256>;
+
+// Одна рабочая группа сэмплирует кадр сеткой 64x64 точек
+@compute @workgroup_size(16, 16, 1)
+fn cs_main(@builtin(local_invocation_id) local_id: vec3<u32>, @builtin(local_invocation_index) index: u32) {
+    let size = textureDimensions(hdr_texture);
+    let grid = GROUP_SIZE * SAMPLES_PER_THREAD;
+
+    var sum = 0.0;
+    for (var y = 0u; y < SAMPLES_PER_THREAD; y = y + 1u) {
+        for (var x = 0u; x < SAMPLES_PER_THREAD; x = x + 1u) {
+            let cell = local_id.xy * SAMPLES_PER_THREAD + vec2<u32>(x, y);
+            let coord = (cell * size + size / (2u * grid)) / grid;
+            let color = textureLoad(hdr_texture, min(coord, size - 1u), 0).rgb;
+            let luminance = dot(color, vec3<f32>(0.2126, 0.7152, 0.0722));
+            sum = sum + log(max(luminance, 0.0001));
+        }
+    }
+    partial_sums[index] = sum;
+    workgroupBarrier();
+
+    var stride = 128u;
+    while (stride > 0u) {
+        if (index < stride) {
+            partial_sums[index] = partial_sums[index] + partial_sums[index + stride];
+        }
+        workgroupBarrier();
+        stride = stride / 2u;
+    }
+
+    if (index == 0u) {
+        let average = exp(partial_sums[0] / f32(grid * grid));
+        let target_luminance = clamp(average, params.min_luminance, params.max_luminance);
+        let adapted = mix(state.adapted_luminance, target_luminance, params.adaptation);
+        state.adapted_luminance = adapted;
+        if (params.auto_exposure > 0.5) {
+            state.exposure = params.key_value / max(adapted, 0.0001);
+        } else {
+            state.exposure = params.manual_exposure;
+        }
+    }
+}
@@ -0,0 +1,58 @@
+// Видимые в воздухе конусы света фар
+
+struct Camera {
+    view_proj: mat4x4<f32>,
+    view_position: vec3<f32>,
+};
+
+@group(0) @binding(0)
+var<uniform> camera: Camera;
+
+struct VertexInput {
+    @location(0) position: vec3<f32>,
+    @location(1) tex_coords: vec2<f32>,
+    @location(2) normal: vec3<f32>,
+};
+
+struct InstanceInput {
+    @location(3) model_0: vec4<f32>,
+    @location(4) model_1: vec4<f32>,
+    @location(5) model_2: vec4<f32>,
+    @location(6) model_3: vec4<f32>,
+    @location(7) color: vec4<f32>,
+};
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) along: f32,
+    @location(1) world_normal: vec3<f32>,
+    @location(2) world_position: vec3<f32>,
+    @location(3) color: vec4<f32>,
+};
+
+@vertex
+fn vs_main(vertex: VertexInput, instance: InstanceInput) -> VertexOutput {
+    let model = mat4x4<f32>(instance.model_0, instance.model_1, instance.model_2, instance.model_3);
+    let world_position = model * vec4<f32>(vertex.position, 1.0);
+
+    var out: VertexOutput;
+    out.clip_position = camera.view_proj * world_position;
+    out.along = vertex.tex_coords.x;
+    out.world_normal = normalize((model * vec4<f32>(vertex.normal, 0.0)).xyz);
+    out.world_position = world_position.xyz;
+    out.color = instance.color;
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    // Яркость падает от фары к концу луча
+    let falloff = pow(1.0 - clamp(in.along, 0.0, 1.0), 2.0);
+
+    // Края конуса мягкие: яркость выше, когда смотрим сквозь толщу луча
+    let view_dir = normalize(camera.view_position - in.world_position);
+    let thickness = pow(abs(dot(view_dir, in.world_normal)), 1.5);
+
+    let strength = falloff * thickness * in.color.a;
+    return vec4<f32>(in.color.rgb * strength, 1.0);
+}
@@ -0,0 +1,71 @@
+// Частицы: билборды с предумноженной альфой (аддитивные и обычные)
+
+struct Camera {
+    view_proj: mat4x4<f32>,
+    view_position: vec3<f32>,
+};
+
+@group(0) @binding(0)
+var<uniform> camera: Camera;
+
+struct VertexInput {
+    @location(0) position: vec3<f32>,
+    @location(1) tex_coords: vec2<f32>,
+    @location(2) normal: vec3<f32>,
+};
+
+struct InstanceInput {
+    @location(3) position_size: vec4<f32>,
+    @location(4) color: vec4<f32>,
+    @location(5) params: vec4<f32>,
+};
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) tex_coords: vec2<f32>,
+    @location(1) color: vec4<f32>,
+    @location(2) additive: f32,
+};
+
+@vertex
+fn vs_main(vertex: VertexInput, instance: InstanceInput) -> VertexOutput {
+    let center = instance.position_size.xyz;
+    let size = instance.position_size.w;
+
+    // Базис, повернутый к камере
+    var forward = camera.view_position - center;
+    if (length(forward) < 0.0001) {
+        forward = vec3<f32>(0.0, 0.0, 1.0);
+    }
+    forward = normalize(forward);
+    var right = cross(vec3<f32>(0.0, 1.0, 0.0), forward);
+    if (length(right) < 0.0001) {
+        right = vec3<f32>(1.0, 0.0, 0.0);
+    }
+    right = normalize(right);
+    let up = cross(forward, right);
+
+    // Квад растительности имеет y в диапазоне 0..1, центрируем его
+    let corner = vec2<f32>(vertex.position.x, vertex.position.y - 0.5);
+    let world_position = center + (right * corner.x + up * corner.y) * size * 2.0;
+
+    var out: VertexOutput;
+    out.clip_position = camera.view_proj * vec4<f32>(world_position, 1.0);
+    out.tex_coords = vertex.tex_coords;
+    out.color = instance.color;
+    out.additive = instance.params.x;
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    // Мягкая круглая точка
+    let d = length(in.tex_coords - vec2<f32>(0.5, 0.5)) * 2.0;
+    if (d > 1.0) {
+        discard;
+    }
+    let falloff = 1.0 - d * d;
+    let alpha = in.color.a * falloff;
+    // Аддитивные частицы не перекрывают фон (альфа 0)
+    return vec4<f32>(in.color.rgb * alpha, alpha * (1.0 - in.additive));
+}
@@ -0,0 +1,56 @@
+// Капли дождя на экране (стекло кабины или объектив внешней камеры)
+
+struct Rain {
+    wetness: f32,
+    droplets: f32,
+    time: f32,
+    aspect: f32,
+};
+
+@group(0) @binding(0)
+var<uniform> rain: Rain;
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+};
+
+// Полноэкранный треугольник без вершинного буфера
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32) -> VertexOutput {
+    let uv = vec2<f32>(f32((index << 1u) & 2u), f32(index & 2u));
+    var out: VertexOutput;
+    out.clip_position = vec4<f32>(uv * 2.0 - 1.0, 0.0, 1.0);
+    out.uv = vec2<f32>(uv.x, 1.0 - uv.y);
+    return out;
+}
+
+fn hash2(p: vec2<f32>) -> vec2<f32> {
+    let q = vec2<f32>(dot(p, vec2<f32>(127.1, 311.7)), dot(p, vec2<f32>(269.5, 183.3)));
+    return fract(sin(q) * 43758.5453);
+}
+
+// Один слой капель: каждая ячейка сетки содержит каплю со своим временем жизни
+fn droplet_layer(uv: vec2<f32>, scale: f32, speed: f32) -> f32 {
+    let p = uv * scale;
+    let cell = floor(p);
+    let local = fract(p) - 0.5;
+    let rnd = hash2(cell);
+
+    // Капля появляется, живет и стекает вниз
+    let life = fract(rain.time * speed + rnd.x);
+    let center = (rnd - 0.5) * 0.6 + vec2<f32>(0.0, life * life * 0.3);
+    let radius = 0.12 * (1.0 - life) * step(0.3, rnd.y);
+    let d = length(local - center);
+    return smoothstep(radius, radius * 0.5, d);
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let uv = vec2<f32>(in.uv.x * rain.aspect, in.uv.y);
+    let drops = max(droplet_layer(uv, 9.0, 0.35), droplet_layer(uv + 0.37, 17.0, 0.6) * 0.7);
+
+    // Капля слегка осветляет изображение и выглядит как блик на стекле
+    let alpha = drops * 0.35 * clamp(rain.droplets, 0.0, 1.0);
+    return vec4<f32>(vec3<f32>(0.85, 0.9, 0.95), alpha);
+}
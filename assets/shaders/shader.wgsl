@@ -45,6 +45,9 @@ struct Material {
     metallic: f32,
     roughness: f32,
     ambient_occlusion: f32,
+    emissive: vec3<f32>,
+    terrain: f32,
+    layer_colors: array<vec4<f32>, 3>,
 };
 
 @group(1) @binding(1)
@@ -55,6 +58,32 @@ var t_diffuse: texture_2d<f32>;
 @group(1) @binding(3)
 var s_diffuse: sampler;
 
+// Ландшафт: веса слоев по узлам сетки и детальные текстуры трава/грунт/асфальт
+@group(1) @binding(4)
+var t_splat: texture_2d<f32>;
+@group(1) @binding(5)
+var t_layers: texture_2d_array<f32>;
+@group(1) @binding(6)
+var s_terrain: sampler;
+
+// Размер повтора детальной текстуры слоя, м
+const TERRAIN_DETAIL_TILE: f32 = 8.0;
+
+// Цвет ландшафта: слои смешиваются по карте весов, каждый со своей детальной текстурой
+fn terrain_color(tex_coords: vec2<f32>, world_position: vec3<f32>) -> vec3<f32> {
+    // Узлы сетки лежат в центрах текселей карты весов
+    let size = vec2<f32>(textureDimensions(t_splat));
+    let splat_uv = (tex_coords * (size - 1.0) + 0.5) / size;
+    let weights = textureSample(t_splat, s_terrain, splat_uv).rgb;
+    let detail_uv = world_position.xz / TERRAIN_DETAIL_TILE;
+    var color = vec3<f32>(0.0);
+    for (var i = 0; i < 3; i = i + 1) {
+        let detail = textureSample(t_layers, s_terrain, detail_uv, i).r;
+        color = color + weights[i] * material.layer_colors[i].rgb * detail;
+    }
+    return color / max(weights.r + weights.g + weights.b, 0.001);
+}
+
 struct Light {
     position: vec3<f32>,
     color: vec3<f32>,
@@ -64,25 +93,96 @@ struct Light {
 @group(2) @binding(0)
 var<uniform> light: Light;
 
+struct Fog {
+    color: vec3<f32>,
+    density: f32,
+    height_falloff: f32,
+    base_height: f32,
+};
+
+@group(2) @binding(1)
+var<uniform> fog: Fog;
+
+struct Rain {
+    wetness: f32,
+    droplets: f32,
+    time: f32,
+    aspect: f32,
+};
+
+@group(2) @binding(2)
+var<uniform> rain: Rain;
+
+struct SpotLight {
+    position_range: vec4<f32>,
+    direction_cos_outer: vec4<f32>,
+    color_cos_inner: vec4<f32>,
+};
+
+struct SpotLights {
+    lights: array<SpotLight, 8>,
+    count: u32,
+};
+
+@group(2) @binding(3)
+var<uniform> spot_lights: SpotLights;
+
+// Вклад прожекторов (фар) в освещение точки
+fn spot_lighting(world_position: vec3<f32>, normal: vec3<f32>) -> vec3<f32> {
+    var total = vec3<f32>(0.0);
+    for (var i = 0u; i < min(spot_lights.count, 8u); i = i + 1u) {
+        let light = spot_lights.lights[i];
+        let to_point = world_position - light.position_range.xyz;
+        let distance = length(to_point);
+        let range = light.position_range.w;
+        if (distance > range || distance < 0.0001) {
+            continue;
+        }
+        let dir = to_point / distance;
+        let cos_angle = dot(dir, normalize(light.direction_cos_outer.xyz));
+        let cone = smoothstep(light.direction_cos_outer.w, light.color_cos_inner.w, cos_angle);
+        let attenuation = pow(1.0 - distance / range, 2.0);
+        let lambert = max(dot(normal, -dir), 0.0);
+        total = total + light.color_cos_inner.rgb * cone * attenuation * lambert;
+    }
+    return total;
+}
+
+// Экспоненциальный туман, плотнее у земли
+fn apply_fog(color: vec3<f32>, world_position: vec3<f32>) -> vec3<f32> {
+    let distance = length(world_position - camera.view_position);
+    let height_factor = exp(-max(world_position.y - fog.base_height, 0.0) * fog.height_falloff);
+    let visibility = exp(-distance * fog.density * height_factor);
+    return mix(fog.color, color, visibility);
+}
+
 @fragment
 fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
-    // Базовый цвет из текстуры или униформ
-    let object_color = textureSample(t_diffuse, s_diffuse, in.tex_coords).rgb * material.base_color.rgb;
+    // Мокрыми становятся только обращенные вверх поверхности
+    let wetness = rain.wetness * smoothstep(0.5, 0.9, in.world_normal.y);
+    
+    // Базовый цвет из текстуры или униформ, у ландшафта - из слоев; мокрое покрытие темнее
+    let surface_color = mix(
+        textureSample(t_diffuse, s_diffuse, in.tex_coords).rgb,
+        terrain_color(in.tex_coords, in.world_position),
+        material.terrain,
+    );
+    let object_color = surface_color * material.base_color.rgb * (1.0 - 0.4 * wetness);
     
     // Направление к источнику света
     let light_dir = normalize(light.position - in.world_position);
     
     // Базовое освещение (рассеянное)
     let diffuse_strength = max(dot(in.world_normal, light_dir), 0.0);
-    let diffuse_color = light.color * diffuse_strength * light.intensity;
+    let diffuse_color = light.color * diffuse_strength * light.intensity + spot_lighting(in.world_position, in.world_normal);
     
     // Направление к камере
     let view_dir = normalize(camera.view_position - in.world_position);
     let reflect_dir = reflect(-light_dir, in.world_normal);
     
-    // Бликовое освещение
-    let specular_strength = 0.5;
-    let shininess = 32.0;
+    // Бликовое освещение; на мокром покрытии блики ярче и резче
+    let specular_strength = mix(0.5, 2.0, wetness);
+    let shininess = mix(32.0, 128.0, wetness);
     let spec = pow(max(dot(view_dir, reflect_dir), 0.0), shininess);
     let specular_color = light.color * spec * specular_strength * light.intensity;
     
@@ -91,13 +191,21 @@ fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
     let ambient_color = light.color * ambient_strength;
     
     // Итоговый цвет
-    let result = (ambient_color + diffuse_color + specular_color) * object_color;
+    var result = (ambient_color + diffuse_color + specular_color) * object_color;
+    
+    // Отражение неба в лужах (по Френелю, цвет неба берем из тумана)
+    let fresnel = pow(1.0 - max(dot(view_dir, in.world_normal), 0.0), 5.0);
+    result = mix(result, fog.color * light.intensity, fresnel * wetness * 0.6);
+    
+    // Собственное свечение не зависит от освещения
+    result = result + material.emissive;
     
-    return vec4<f32>(result, material.base_color.a);
+    return vec4<f32>(apply_fog(result, in.world_position), material.base_color.a);
 }
 
 // Базовый шейдер для отрисовки без освещения (на случай, если нужно)
 @fragment
 fn fs_unlit(in: VertexOutput) -> @location(0) vec4<f32> {
-    return textureSample(t_diffuse, s_diffuse, in.tex_coords) * material.base_color;
+    let color = textureSample(t_diffuse, s_diffuse, in.tex_coords) * material.base_color;
+    return vec4<f32>(apply_fog(color.rgb + material.emissive, in.world_position), color.a);
 } 
\ No newline at end of file
@@ -0,0 +1,113 @@
+// Инстансинг растительности: цилиндрические билборды с покачиванием от ветра
+
+struct Camera {
+    view_proj: mat4x4<f32>,
+    view_position: vec3<f32>,
+};
+
+@group(0) @binding(0)
+var<uniform> camera: Camera;
+
+struct Wind {
+    direction: vec2<f32>,
+    strength: f32,
+    time: f32,
+};
+
+@group(1) @binding(0)
+var<uniform> wind: Wind;
+
+struct Fog {
+    color: vec3<f32>,
+    density: f32,
+    height_falloff: f32,
+    base_height: f32,
+};
+
+@group(1) @binding(1)
+var<uniform> fog: Fog;
+
+struct VertexInput {
+    @location(0) position: vec3<f32>,
+    @location(1) tex_coords: vec2<f32>,
+    @location(2) normal: vec3<f32>,
+};
+
+struct InstanceInput {
+    @location(3) position_scale: vec4<f32>,
+    // x - фаза, y - гибкость, z - тип (0 - трава, 1 - дерево)
+    @location(4) params: vec4<f32>,
+    @location(5) color: vec4<f32>,
+};
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) tex_coords: vec2<f32>,
+    @location(1) color: vec4<f32>,
+    @location(2) kind: f32,
+    @location(3) world_position: vec3<f32>,
+};
+
+@vertex
+fn vs_main(vertex: VertexInput, instance: InstanceInput) -> VertexOutput {
+    let base = instance.position_scale.xyz;
+    let scale = instance.position_scale.w;
+    let phase = instance.params.x;
+    let flexibility = instance.params.y;
+
+    // Билборд поворачивается к камере только вокруг вертикальной оси
+    let to_camera = camera.view_position - base;
+    var right = vec3<f32>(to_camera.z, 0.0, -to_camera.x);
+    if (length(right) < 0.0001) {
+        right = vec3<f32>(1.0, 0.0, 0.0);
+    }
+    right = normalize(right);
+
+    // Верхушка качается сильнее основания
+    let height = vertex.position.y;
+    let gust = 0.6 + 0.4 * sin(wind.time * 1.7 + phase);
+    let sway = sin(wind.time * 2.3 + phase) * 0.3 + gust;
+    let bend = wind.strength * flexibility * height * height * sway;
+    let offset = vec3<f32>(wind.direction.x, 0.0, wind.direction.y) * bend;
+
+    let world_position = base + (right * vertex.position.x + vec3<f32>(0.0, height, 0.0) + offset) * scale;
+
+    var out: VertexOutput;
+    out.clip_position = camera.view_proj * vec4<f32>(world_position, 1.0);
+    out.tex_coords = vertex.tex_coords;
+    out.color = instance.color;
+    out.kind = instance.params.z;
+    out.world_position = world_position;
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let h = 1.0 - in.tex_coords.y;
+    let x = abs(in.tex_coords.x - 0.5);
+
+    var color = in.color.rgb;
+    if (in.kind < 0.5) {
+        // Травинка: треугольный силуэт
+        if (x > 0.5 * (1.0 - h)) {
+            discard;
+        }
+    } else {
+        // Дерево: ствол и крона
+        let trunk = x < 0.06 && h < 0.4;
+        let canopy = length(vec2<f32>(x, (h - 0.62) * 0.8)) < 0.38;
+        if (!trunk && !canopy) {
+            discard;
+        }
+        if (!canopy) {
+            color = vec3<f32>(0.35, 0.25, 0.15);
+        }
+    }
+
+    // Затемнение у основания и туман
+    let shaded = color * (0.55 + 0.45 * h);
+    let distance = length(in.world_position - camera.view_position);
+    let height_factor = exp(-max(in.world_position.y - fog.base_height, 0.0) * fog.height_falloff);
+    let visibility = exp(-distance * fog.density * height_factor);
+    return vec4<f32>(mix(fog.color, shaded, visibility), 1.0);
+}
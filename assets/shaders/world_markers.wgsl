@@ -0,0 +1,86 @@
+// Метки в мире: таблички над машинами, стрелки чекпоинтов и знаки въезда в боксы.
+// Билборды проходят тест глубины, поэтому прячутся за геометрией
+
+struct Camera {
+    view_proj: mat4x4<f32>,
+    view_position: vec3<f32>,
+};
+
+@group(0) @binding(0)
+var<uniform> camera: Camera;
+
+struct VertexInput {
+    @location(0) position: vec3<f32>,
+    @location(1) tex_coords: vec2<f32>,
+    @location(2) normal: vec3<f32>,
+};
+
+struct InstanceInput {
+    @location(3) position_size: vec4<f32>,
+    @location(4) color: vec4<f32>,
+    @location(5) params: vec4<f32>,
+};
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) tex_coords: vec2<f32>,
+    @location(1) color: vec4<f32>,
+    @location(2) shape: f32,
+    @location(3) aspect: f32,
+};
+
+@vertex
+fn vs_main(vertex: VertexInput, instance: InstanceInput) -> VertexOutput {
+    let center = instance.position_size.xyz;
+    let size = instance.position_size.w;
+    let aspect = max(instance.params.y, 0.01);
+
+    // Базис, повернутый к камере
+    var forward = camera.view_position - center;
+    if (length(forward) < 0.0001) {
+        forward = vec3<f32>(0.0, 0.0, 1.0);
+    }
+    forward = normalize(forward);
+    var right = cross(vec3<f32>(0.0, 1.0, 0.0), forward);
+    if (length(right) < 0.0001) {
+        right = vec3<f32>(1.0, 0.0, 0.0);
+    }
+    right = normalize(right);
+    let up = cross(forward, right);
+
+    // Квад растительности имеет y в диапазоне 0..1, центрируем его
+    let corner = vec2<f32>(vertex.position.x * aspect, vertex.position.y - 0.5);
+    let world_position = center + (right * corner.x + up * corner.y) * size * 2.0;
+
+    var out: VertexOutput;
+    out.clip_position = camera.view_proj * vec4<f32>(world_position, 1.0);
+    out.tex_coords = vertex.tex_coords;
+    out.color = instance.color;
+    out.shape = instance.params.x;
+    out.aspect = aspect;
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    // Координаты от -1 до 1, y вверх
+    let p = vec2<f32>(in.tex_coords.x * 2.0 - 1.0, 1.0 - in.tex_coords.y * 2.0);
+    var inside = false;
+    if (in.shape < 0.5) {
+        // Табличка: прямоугольник со скругленными углами
+        let radius = 0.35;
+        let half_size = vec2<f32>(in.aspect, 1.0) - vec2<f32>(radius, radius);
+        let q = abs(p * vec2<f32>(in.aspect, 1.0)) - half_size;
+        inside = length(max(q, vec2<f32>(0.0, 0.0))) <= radius;
+    } else if (in.shape < 1.5) {
+        // Стрелка вниз: острие у нижнего края
+        inside = p.y >= -1.0 + abs(p.x) * 2.0 && (p.y <= 0.0 || abs(p.x) <= 0.35);
+    } else {
+        // Ромб
+        inside = abs(p.x) + abs(p.y) <= 1.0;
+    }
+    if (!inside) {
+        discard;
+    }
+    return vec4<f32>(in.color.rgb * in.color.a, in.color.a);
+}
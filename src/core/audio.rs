@@ -1,13 +1,194 @@
 use crate::core::ecs::{Resource};
+use crate::core::physics::TransformComponent;
+use crate::core::renderer::CameraComponent;
+use glam::Vec3;
 use hecs::World;
+use rodio::cpal::traits::{DeviceTrait, HostTrait};
+use rodio::source::{ChannelVolume, SineWave};
 use rodio::{Decoder, OutputStream, OutputStreamHandle, Sink, Source};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs::File;
 use std::io::BufReader;
 use std::path::Path;
+use std::sync::atomic::{AtomicU32, Ordering};
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
 use uuid::Uuid;
 
+/// Путь к сохраненным настройкам звука
+pub const AUDIO_SETTINGS_PATH: &str = "config/audio.json";
+
+/// Скорость звука для эффекта Доплера, м/с
+const SPEED_OF_SOUND: f32 = 343.0;
+
+/// Время плавного перехода слушателя при смене камеры, с
+const LISTENER_CUT_BLEND: f32 = 0.35;
+
+/// Имя зацикленного звука зрителей в библиотеке
+pub const CROWD_SOUND: &str = "crowd_ambience";
+
+/// Шина микширования
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AudioBus {
+    Master,
+    Effects,
+    Music,
+}
+
+impl AudioBus {
+    /// Частота тестового тона, чтобы шины различались на слух
+    pub fn test_tone_frequency(&self) -> f32 {
+        match self {
+            AudioBus::Master => 440.0,
+            AudioBus::Effects => 660.0,
+            AudioBus::Music => 330.0,
+        }
+    }
+}
+
+/// Профиль вывода звука
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SpeakerProfile {
+    Stereo,
+    Headphones,
+    Mono,
+}
+
+impl SpeakerProfile {
+    /// Ширина стереобазы для пространственных звуков (0 - моно, 1 - полное разделение)
+    pub fn spatial_width(&self) -> f32 {
+        match self {
+            SpeakerProfile::Stereo => 0.6,
+            SpeakerProfile::Headphones => 1.0,
+            SpeakerProfile::Mono => 0.0,
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            SpeakerProfile::Stereo => SpeakerProfile::Headphones,
+            SpeakerProfile::Headphones => SpeakerProfile::Mono,
+            SpeakerProfile::Mono => SpeakerProfile::Stereo,
+        }
+    }
+}
+
+/// Настройки звука, сохраняемые между запусками
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AudioSettings {
+    pub master_volume: f32,
+    pub effects_volume: f32,
+    pub music_volume: f32,
+    pub output_device: Option<String>, // None - устройство по умолчанию
+    pub speaker_profile: SpeakerProfile,
+}
+
+impl Default for AudioSettings {
+    fn default() -> Self {
+        Self {
+            master_volume: 1.0,
+            effects_volume: 1.0,
+            music_volume: 0.8,
+            output_device: None,
+            speaker_profile: SpeakerProfile::Stereo,
+        }
+    }
+}
+
+impl AudioSettings {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+        serde_json::from_str(&text).map_err(|e| e.to_string())
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        let text = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        std::fs::write(path, text).map_err(|e| e.to_string())
+    }
+
+    /// Итоговая громкость шины с учетом общей громкости
+    pub fn bus_gain(&self, bus: AudioBus) -> f32 {
+        let bus_volume = match bus {
+            AudioBus::Master => 1.0,
+            AudioBus::Effects => self.effects_volume,
+            AudioBus::Music => self.music_volume,
+        };
+        self.master_volume * bus_volume
+    }
+}
+
+/// Имена доступных устройств вывода
+pub fn list_output_devices() -> Vec<String> {
+    let host = rodio::cpal::default_host();
+    match host.output_devices() {
+        Ok(devices) => devices.filter_map(|device| device.name().ok()).collect(),
+        Err(e) => {
+            eprintln!("Не удалось получить список аудиоустройств: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+/// Открывает выбранное устройство, при ошибке - устройство по умолчанию
+fn open_output_stream(device_name: Option<&str>) -> (OutputStream, OutputStreamHandle) {
+    if let Some(name) = device_name {
+        let host = rodio::cpal::default_host();
+        let device = host
+            .output_devices()
+            .ok()
+            .and_then(|mut devices| devices.find(|device| device.name().ok().as_deref() == Some(name)));
+        match device.map(|device| OutputStream::try_from_device(&device)) {
+            Some(Ok(stream)) => return stream,
+            Some(Err(e)) => eprintln!("Не удалось открыть аудиоустройство {}: {}", name, e),
+            None => eprintln!("Аудиоустройство {} не найдено, используется устройство по умолчанию", name),
+        }
+    }
+    OutputStream::try_default().unwrap()
+}
+
+/// Слои (стемы) адаптивной музыки
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MusicStem {
+    Base,
+    Percussion,
+    Lead,
+}
+
+impl MusicStem {
+    /// Целевая громкость слоя для заданной интенсивности 0..1
+    pub fn volume_for_intensity(&self, intensity: f32) -> f32 {
+        let ramp = |from: f32, to: f32| ((intensity - from) / (to - from)).clamp(0.0, 1.0);
+        match self {
+            MusicStem::Base => 1.0,
+            MusicStem::Percussion => ramp(0.25, 0.55),
+            MusicStem::Lead => ramp(0.6, 0.9),
+        }
+    }
+}
+
+/// Интенсивность гонки для адаптивной музыки (0 - спокойно, 1 - кульминация)
+pub struct MusicIntensity {
+    pub value: f32,
+}
+
+/// Слой адаптивной музыки, играющий синхронно с остальными
+struct MusicLayer {
+    stem: MusicStem,
+    sink: Sink,
+    volume: f32,
+}
+
+/// Адаптивная музыка из нескольких слоев
+struct AdaptiveMusic {
+    layers: Vec<MusicLayer>,
+    volume: f32,
+    fade_speed: f32, // Изменение громкости слоя за секунду
+}
+
 /// Система аудио
 pub struct AudioSystem {
     _stream: OutputStream,
@@ -16,12 +197,33 @@ pub struct AudioSystem {
     sinks: HashMap<String, Arc<Mutex<Sink>>>,
     music_sink: Option<Arc<Mutex<Sink>>>,
     current_music: Option<String>,
-    volume: f32,
+    adaptive_music: Option<AdaptiveMusic>,
+    source_sounds: HashMap<hecs::Entity, String>, // Звуки, запущенные компонентами источников
+    pan_controls: HashMap<String, Arc<AtomicU32>>, // Панорама пространственных звуков (-1 слева, 1 справа)
+    settings: AudioSettings,
+    listener: Option<ListenerState>,
+    source_positions: HashMap<hecs::Entity, Vec3>, // Положения источников в прошлом кадре для оценки скорости
+    crowd_sound: Option<String>,
+}
+
+/// Положение слушателя с учетом плавного перехода между камерами
+struct ListenerState {
+    camera: hecs::Entity,
+    position: Vec3,
+    right: Vec3,
+    velocity: Vec3,
+    from_position: Vec3, // Откуда идет переход после смены камеры
+    from_right: Vec3,
+    blend: f32,          // 0 - старая камера, 1 - переход завершен
 }
 
 impl AudioSystem {
     pub fn new() -> Self {
-        let (stream, stream_handle) = OutputStream::try_default().unwrap();
+        Self::with_settings(AudioSettings::default())
+    }
+    
+    pub fn with_settings(settings: AudioSettings) -> Self {
+        let (stream, stream_handle) = open_output_stream(settings.output_device.as_deref());
         
         Self {
             _stream: stream,
@@ -30,10 +232,50 @@ impl AudioSystem {
             sinks: HashMap::new(),
             music_sink: None,
             current_music: None,
-            volume: 1.0,
+            adaptive_music: None,
+            source_sounds: HashMap::new(),
+            pan_controls: HashMap::new(),
+            settings,
+            listener: None,
+            source_positions: HashMap::new(),
+            crowd_sound: None,
         }
     }
     
+    pub fn settings(&self) -> &AudioSettings {
+        &self.settings
+    }
+    
+    /// Применение настроек; смена устройства перезапускает вывод
+    pub fn apply_settings(&mut self, settings: AudioSettings) {
+        if settings.output_device != self.settings.output_device {
+            // Звуки старого потока перестанут играть, источники перезапустятся сами
+            self.stop_adaptive_music();
+            let _ = self.stop_music();
+            self.sinks.clear();
+            self.pan_controls.clear();
+            self.source_sounds.clear();
+            self.crowd_sound = None;
+            let (stream, stream_handle) = open_output_stream(settings.output_device.as_deref());
+            self._stream = stream;
+            self.stream_handle = stream_handle;
+        }
+        self.settings = settings;
+    }
+    
+    /// Короткий тестовый тон через выбранную шину
+    pub fn play_test_tone(&mut self, bus: AudioBus) -> Result<(), String> {
+        let sink = Sink::try_new(&self.stream_handle).map_err(|e| e.to_string())?;
+        sink.set_volume(self.settings.bus_gain(bus));
+        sink.append(
+            SineWave::new(bus.test_tone_frequency())
+                .take_duration(Duration::from_millis(800))
+                .amplify(0.25),
+        );
+        sink.detach();
+        Ok(())
+    }
+    
     /// Загрузка звука из файла
     pub fn load_sound(&mut self, name: &str, path: &Path) -> Result<(), String> {
         let file = File::open(path).map_err(|e| e.to_string())?;
@@ -47,31 +289,59 @@ impl AudioSystem {
     
     /// Воспроизведение звука
     pub fn play_sound(&mut self, name: &str, volume: f32, looping: bool) -> Result<String, String> {
+        self.start_sound(name, volume, looping, false)
+    }
+    
+    /// Запуск звука на шине эффектов; панорамируемые звуки сводятся в моно и разводятся по каналам
+    fn start_sound(&mut self, name: &str, volume: f32, looping: bool, pannable: bool) -> Result<String, String> {
         let sound_data = self.sound_library
             .get(name)
             .ok_or_else(|| format!("Sound {} not found", name))?
             .clone();
         
         let sink = Sink::try_new(&self.stream_handle).map_err(|e| e.to_string())?;
-        sink.set_volume(volume * self.volume);
+        sink.set_volume(volume * self.settings.bus_gain(AudioBus::Effects));
         
         let sound_cursor = std::io::Cursor::new(sound_data.to_vec());
         let source = Decoder::new(sound_cursor).map_err(|e| e.to_string())?;
+        let source: Box<dyn Source<Item = i16> + Send> = if looping {
+            Box::new(source.repeat_infinite())
+        } else {
+            Box::new(source)
+        };
         
-        if looping {
-            sink.append(source.repeat_infinite());
+        let id = format!("{}_{}", name, Uuid::new_v4().to_string());
+        
+        if pannable {
+            let pan = Arc::new(AtomicU32::new(0.0f32.to_bits()));
+            let pan_control = pan.clone();
+            sink.append(
+                ChannelVolume::new(source, vec![1.0, 1.0]).periodic_access(Duration::from_millis(10), move |channels| {
+                    let pan = f32::from_bits(pan_control.load(Ordering::Relaxed));
+                    channels.set_volume(0, (1.0 - pan).min(1.0));
+                    channels.set_volume(1, (1.0 + pan).min(1.0));
+                }),
+            );
+            self.pan_controls.insert(id.clone(), pan);
         } else {
             sink.append(source);
         }
         
-        let id = format!("{}_{}", name, Uuid::new_v4().to_string());
         self.sinks.insert(id.clone(), Arc::new(Mutex::new(sink)));
         
         Ok(id)
     }
     
+    /// Панорама звука по ID: -1 слева, 1 справа
+    fn set_sound_pan(&mut self, id: &str, pan: f32) {
+        if let Some(control) = self.pan_controls.get(id) {
+            control.store(pan.clamp(-1.0, 1.0).to_bits(), Ordering::Relaxed);
+        }
+    }
+    
     /// Остановка звука по ID
     pub fn stop_sound(&mut self, id: &str) -> Result<(), String> {
+        self.pan_controls.remove(id);
         if let Some(sink) = self.sinks.remove(id) {
             let sink = sink.lock().map_err(|e| e.to_string())?;
             sink.stop();
@@ -83,7 +353,16 @@ impl AudioSystem {
     pub fn set_sound_volume(&mut self, id: &str, volume: f32) -> Result<(), String> {
         if let Some(sink) = self.sinks.get(id) {
             let sink = sink.lock().map_err(|e| e.to_string())?;
-            sink.set_volume(volume * self.volume);
+            sink.set_volume(volume * self.settings.bus_gain(AudioBus::Effects));
+        }
+        Ok(())
+    }
+    
+    /// Установка скорости воспроизведения (высоты тона) звука по ID
+    pub fn set_sound_pitch(&mut self, id: &str, pitch: f32) -> Result<(), String> {
+        if let Some(sink) = self.sinks.get(id) {
+            let sink = sink.lock().map_err(|e| e.to_string())?;
+            sink.set_speed(pitch.max(0.01));
         }
         Ok(())
     }
@@ -95,7 +374,7 @@ impl AudioSystem {
             if current == name {
                 if let Some(sink) = &self.music_sink {
                     let sink = sink.lock().map_err(|e| e.to_string())?;
-                    sink.set_volume(volume * self.volume);
+                    sink.set_volume(volume * self.settings.bus_gain(AudioBus::Music));
                     return Ok(());
                 }
             }
@@ -114,7 +393,7 @@ impl AudioSystem {
             .clone();
         
         let sink = Sink::try_new(&self.stream_handle).map_err(|e| e.to_string())?;
-        sink.set_volume(volume * self.volume);
+        sink.set_volume(volume * self.settings.bus_gain(AudioBus::Music));
         
         let sound_cursor = std::io::Cursor::new(sound_data.to_vec());
         let source = Decoder::new(sound_cursor).map_err(|e| e.to_string())?;
@@ -137,20 +416,83 @@ impl AudioSystem {
         Ok(())
     }
     
+    /// Запуск адаптивной музыки: все слои стартуют одновременно и играют по кругу
+    pub fn play_adaptive_music(&mut self, stems: &[(MusicStem, String)], volume: f32) -> Result<(), String> {
+        self.stop_music()?;
+        self.stop_adaptive_music();
+        
+        let mut layers = Vec::with_capacity(stems.len());
+        for (stem, name) in stems {
+            let sound_data = self.sound_library
+                .get(name)
+                .ok_or_else(|| format!("Music stem {} not found", name))?
+                .clone();
+            
+            let sink = Sink::try_new(&self.stream_handle).map_err(|e| e.to_string())?;
+            let sound_cursor = std::io::Cursor::new(sound_data.to_vec());
+            let source = Decoder::new(sound_cursor).map_err(|e| e.to_string())?;
+            
+            // Слои запускаются на паузе, чтобы начать воспроизведение синхронно
+            sink.pause();
+            sink.append(source.repeat_infinite());
+            
+            let layer_volume = stem.volume_for_intensity(0.0);
+            sink.set_volume(layer_volume * volume * self.settings.bus_gain(AudioBus::Music));
+            layers.push(MusicLayer { stem: *stem, sink, volume: layer_volume });
+        }
+        
+        for layer in &layers {
+            layer.sink.play();
+        }
+        
+        self.adaptive_music = Some(AdaptiveMusic {
+            layers,
+            volume,
+            fade_speed: 0.5,
+        });
+        
+        Ok(())
+    }
+    
+    /// Остановка адаптивной музыки
+    pub fn stop_adaptive_music(&mut self) {
+        if let Some(music) = self.adaptive_music.take() {
+            for layer in music.layers {
+                layer.sink.stop();
+            }
+        }
+    }
+    
+    /// Плавное изменение громкости слоев в соответствии с интенсивностью
+    fn update_adaptive_music(&mut self, intensity: f32, delta_time: f32) {
+        let music_gain = self.settings.bus_gain(AudioBus::Music);
+        if let Some(music) = &mut self.adaptive_music {
+            let max_step = music.fade_speed * delta_time;
+            for layer in &mut music.layers {
+                let target = layer.stem.volume_for_intensity(intensity);
+                layer.volume += (target - layer.volume).clamp(-max_step, max_step);
+                layer.sink.set_volume(layer.volume * music.volume * music_gain);
+            }
+        }
+    }
+    
     /// Установка общей громкости
     pub fn set_master_volume(&mut self, volume: f32) {
-        self.volume = volume;
+        // Громкость звуков пересчитывается относительно прежней общей громкости
+        let previous = self.settings.master_volume.max(0.0001);
+        self.settings.master_volume = volume;
+        let ratio = volume / previous;
         
         // Обновляем громкость для всех звуков и музыки
         for sink in self.sinks.values() {
             if let Ok(sink) = sink.lock() {
-                sink.set_volume(sink.volume() * self.volume);
+                sink.set_volume(sink.volume() * ratio);
             }
         }
         
         if let Some(sink) = &self.music_sink {
             if let Ok(sink) = sink.lock() {
-                sink.set_volume(sink.volume() * self.volume);
+                sink.set_volume(sink.volume() * ratio);
             }
         }
     }
@@ -169,11 +511,12 @@ impl AudioSystem {
         
         for id in to_remove {
             self.sinks.remove(&id);
+            self.pan_controls.remove(&id);
         }
     }
 
     /// Обработка аудио-событий и компонентов звуковых источников
-    pub fn process(&mut self, world: &mut World, _delta_time: f32) {
+    pub fn process(&mut self, world: &mut World, delta_time: f32) {
         // Очистка неактивных звуков
         self.cleanup();
         
@@ -204,19 +547,200 @@ impl AudioSystem {
                     AudioEvent::SetMasterVolume { volume } => {
                         self.set_master_volume(volume);
                     }
+                    AudioEvent::PlayAdaptiveMusic { stems, volume } => {
+                        if let Err(e) = self.play_adaptive_music(&stems, volume) {
+                            eprintln!("Не удалось запустить адаптивную музыку: {}", e);
+                        }
+                    }
+                    AudioEvent::StopAdaptiveMusic => {
+                        self.stop_adaptive_music();
+                    }
+                    AudioEvent::PlayTestTone { bus } => {
+                        if let Err(e) = self.play_test_tone(bus) {
+                            eprintln!("Не удалось воспроизвести тестовый тон: {}", e);
+                        }
+                    }
+                    AudioEvent::ApplySettings { settings } => {
+                        self.apply_settings(settings);
+                    }
                 }
             }
         }
         
+        // Адаптивная музыка следует за интенсивностью гонки
+        let intensity = world.query_mut::<&Resource<MusicIntensity>>()
+            .into_iter()
+            .next()
+            .map(|(_, res)| res.0.value)
+            .unwrap_or(0.0);
+        self.update_adaptive_music(intensity, delta_time);
+        
         // Обработка компонентов звуковых источников
-        // Для простоты пока не реализуем 3D-звук, только базовые функции
-        for (_, audio_source) in world.query_mut::<&mut AudioSourceComponent>() {
+        let doppler_scale = self.update_listener(world, delta_time);
+        let listener = self.listener.as_ref().map(|l| (l.position, l.right, l.velocity));
+        let spatial_width = self.settings.speaker_profile.spatial_width();
+        self.update_crowd(world);
+        
+        let mut active = HashMap::new();
+        let mut positions = HashMap::new();
+        for (entity, (audio_source, transform)) in world.query_mut::<(&mut AudioSourceComponent, Option<&TransformComponent>)>() {
             if audio_source.sound_id.is_none() && !audio_source.sound_name.is_empty() {
                 // Воспроизвести звук, если он еще не воспроизводится
-                if let Ok(id) = self.play_sound(&audio_source.sound_name, audio_source.volume, audio_source.looping) {
+                if let Ok(id) = self.start_sound(&audio_source.sound_name, 0.0, audio_source.looping, audio_source.spatial) {
                     audio_source.sound_id = Some(id);
                 }
             }
+            
+            let Some(id) = audio_source.sound_id.clone() else {
+                continue;
+            };
+            
+            // Затухание с расстоянием до слушателя (камеры) и эффект Доплера
+            let (attenuation, pan, doppler) = match (audio_source.spatial, listener, transform) {
+                (true, Some((listener, right, listener_velocity)), Some(transform)) => {
+                    let offset = transform.position - listener;
+                    let distance = offset.length();
+                    let range = (audio_source.max_distance - audio_source.min_distance).max(0.001);
+                    let attenuation = 1.0 - ((distance - audio_source.min_distance) / range).clamp(0.0, 1.0);
+                    let direction = if distance > 0.001 { offset / distance } else { Vec3::ZERO };
+                    
+                    let source_velocity = match self.source_positions.get(&entity) {
+                        Some(previous) if delta_time > 0.0 => (transform.position - *previous) / delta_time,
+                        _ => Vec3::ZERO,
+                    };
+                    positions.insert(entity, transform.position);
+                    let towards_source = listener_velocity.dot(direction);
+                    let towards_listener = -source_velocity.dot(direction);
+                    let shift = ((SPEED_OF_SOUND + towards_source) / (SPEED_OF_SOUND - towards_listener).max(1.0)).clamp(0.5, 2.0);
+                    
+                    (attenuation, direction.dot(right) * spatial_width, 1.0 + (shift - 1.0) * doppler_scale)
+                }
+                _ => (1.0, 0.0, 1.0),
+            };
+            
+            let _ = self.set_sound_volume(&id, audio_source.volume * attenuation);
+            self.set_sound_pan(&id, pan);
+            let _ = self.set_sound_pitch(&id, audio_source.pitch * doppler);
+            active.insert(entity, id);
+        }
+        self.source_positions = positions;
+        
+        // Звуки удаленных сущностей останавливаются
+        for (entity, id) in self.source_sounds.drain().collect::<Vec<_>>() {
+            if active.get(&entity) != Some(&id) {
+                let _ = self.stop_sound(&id);
+            }
+        }
+        self.source_sounds = active;
+    }
+}
+
+impl AudioSystem {
+    /// Выбор камеры-слушателя и плавный переход при смене камеры; возвращает силу эффекта Доплера
+    fn update_listener(&mut self, world: &mut World, delta_time: f32) -> f32 {
+        let marked = world
+            .query_mut::<(&CameraComponent, &AudioListenerComponent)>()
+            .into_iter()
+            .find(|(_, (_, listener))| listener.active)
+            .map(|(entity, (camera, listener))| (entity, camera.position, camera.target, camera.up, listener.doppler_scale));
+        // Без явного слушателя звук слышен из первой камеры, без эффекта Доплера
+        let camera = marked.or_else(|| {
+            world
+                .query_mut::<&CameraComponent>()
+                .into_iter()
+                .next()
+                .map(|(entity, camera)| (entity, camera.position, camera.target, camera.up, 0.0))
+        });
+        let Some((entity, position, target, up, doppler_scale)) = camera else {
+            self.listener = None;
+            return 0.0;
+        };
+        let right = (target - position).normalize_or_zero().cross(up).normalize_or_zero();
+        
+        let state = self.listener.get_or_insert(ListenerState {
+            camera: entity,
+            position,
+            right,
+            velocity: Vec3::ZERO,
+            from_position: position,
+            from_right: right,
+            blend: 1.0,
+        });
+        
+        // Смена камеры: переходим от прежней точки и сбрасываем скорости, чтобы не было скачка высоты тона
+        if state.camera != entity {
+            state.camera = entity;
+            state.from_position = state.position;
+            state.from_right = state.right;
+            state.blend = 0.0;
+            self.source_positions.clear();
+        }
+        
+        let previous = state.position;
+        state.blend = (state.blend + delta_time / LISTENER_CUT_BLEND).min(1.0);
+        let t = state.blend * state.blend * (3.0 - 2.0 * state.blend);
+        state.position = state.from_position.lerp(position, t);
+        state.right = state.from_right.lerp(right, t).normalize_or_zero();
+        state.velocity = if state.blend >= 1.0 && delta_time > 0.0 {
+            (state.position - previous) / delta_time
+        } else {
+            Vec3::ZERO
+        };
+        
+        if state.blend < 1.0 { 0.0 } else { doppler_scale }
+    }
+    
+    /// Гул зрителей рядом с трибунами, громче вблизи слушателя
+    fn update_crowd(&mut self, world: &mut World) {
+        let Some(listener) = self.listener.as_ref().map(|l| l.position) else {
+            return;
+        };
+        let volume = world
+            .query_mut::<(&GrandstandComponent, &TransformComponent)>()
+            .into_iter()
+            .map(|(_, (stand, transform))| {
+                let falloff = 1.0 - (listener.distance(transform.position) / stand.radius.max(0.001)).clamp(0.0, 1.0);
+                stand.crowd_volume * falloff
+            })
+            .fold(0.0, f32::max);
+        
+        if self.crowd_sound.is_none() && volume > 0.0 && self.sound_library.contains_key(CROWD_SOUND) {
+            self.crowd_sound = self.play_sound(CROWD_SOUND, 0.0, true).ok();
+        }
+        if let Some(id) = self.crowd_sound.clone() {
+            let _ = self.set_sound_volume(&id, volume);
+        }
+    }
+}
+
+/// Камера, из которой слышен звук; режиссер повтора делает активной текущую трассовую камеру
+pub struct AudioListenerComponent {
+    pub active: bool,
+    pub doppler_scale: f32, // 0 - без эффекта Доплера
+}
+
+impl Default for AudioListenerComponent {
+    fn default() -> Self {
+        Self {
+            active: true,
+            doppler_scale: 1.0,
+        }
+    }
+}
+
+/// Трибуна, рядом с которой слышен гул зрителей
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GrandstandComponent {
+    pub radius: f32,
+    pub crowd_volume: f32,
+}
+
+impl Default for GrandstandComponent {
+    fn default() -> Self {
+        Self {
+            radius: 120.0,
+            crowd_volume: 0.6,
         }
     }
 }
@@ -256,4 +780,8 @@ pub enum AudioEvent {
     PlayMusic { name: String, volume: f32 },
     StopMusic,
     SetMasterVolume { volume: f32 },
+    PlayAdaptiveMusic { stems: Vec<(MusicStem, String)>, volume: f32 },
+    StopAdaptiveMusic,
+    PlayTestTone { bus: AudioBus },
+    ApplySettings { settings: AudioSettings },
 } 
\ No newline at end of file
@@ -63,6 +63,11 @@ impl<T> EventQueue<T> {
         self.events.push(event);
     }
 
+    /// Чтение без удаления, когда одни и те же события нужны нескольким системам
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.events.iter()
+    }
+
     pub fn consume<F: FnMut(&T)>(&mut self, mut f: F) {
         for event in &self.events {
             f(event);
@@ -0,0 +1,93 @@
+use crate::core::ecs::System;
+use crate::core::physics::TransformComponent;
+use hecs::World;
+use std::collections::HashMap;
+
+/// Дочерняя сущность: ее мировая трансформация выводится из трансформации родителя
+/// и локальной трансформации относительно него
+#[derive(Debug, Clone)]
+pub struct ParentComponent {
+    pub parent: hecs::Entity,
+    pub local: TransformComponent,
+}
+
+impl ParentComponent {
+    pub fn new(parent: hecs::Entity, local: TransformComponent) -> Self {
+        Self { parent, local }
+    }
+}
+
+/// Мировая трансформация дочерней сущности по трансформации родителя
+pub fn child_world_transform(parent: &TransformComponent, local: &TransformComponent) -> TransformComponent {
+    TransformComponent {
+        position: parent.position + parent.rotation * (parent.scale * local.position),
+        rotation: parent.rotation * local.rotation,
+        scale: parent.scale * local.scale,
+    }
+}
+
+/// Наибольшая глубина вложенности сущностей
+const MAX_HIERARCHY_DEPTH: usize = 16;
+
+/// Мировая трансформация сущности с учетом всей цепочки родителей
+fn resolve_transform(
+    world: &World,
+    entity: hecs::Entity,
+    resolved: &mut HashMap<hecs::Entity, TransformComponent>,
+    depth: usize,
+) -> Option<TransformComponent> {
+    if let Some(transform) = resolved.get(&entity) {
+        return Some(*transform);
+    }
+    let transform = match world.get::<&ParentComponent>(entity) {
+        // Глубина ограничена на случай зацикленной цепочки
+        Ok(link) if depth < MAX_HIERARCHY_DEPTH => {
+            let (parent, local) = (link.parent, link.local);
+            drop(link);
+            let parent_transform = resolve_transform(world, parent, resolved, depth + 1)?;
+            child_world_transform(&parent_transform, &local)
+        }
+        Ok(_) => return None,
+        Err(_) => *world.get::<&TransformComponent>(entity).ok()?,
+    };
+    resolved.insert(entity, transform);
+    Some(transform)
+}
+
+/// Система иерархии: переносит трансформации родителей на детей; дети удаленных родителей удаляются
+pub struct HierarchySystem;
+
+impl System for HierarchySystem {
+    fn update(&mut self, world: &mut World, _delta_time: f32) {
+        let children: Vec<(hecs::Entity, hecs::Entity)> = world
+            .query::<&ParentComponent>()
+            .iter()
+            .map(|(entity, link)| (entity, link.parent))
+            .collect();
+        if children.is_empty() {
+            return;
+        }
+
+        let mut resolved = HashMap::new();
+        let mut updates = Vec::new();
+        let mut orphaned = Vec::new();
+        for (entity, parent) in children {
+            if !world.contains(parent) {
+                orphaned.push(entity);
+                continue;
+            }
+            if let Some(transform) = resolve_transform(world, entity, &mut resolved, 0) {
+                updates.push((entity, transform));
+            }
+        }
+
+        for (entity, world_transform) in updates {
+            if let Ok(mut transform) = world.get::<&mut TransformComponent>(entity) {
+                *transform = world_transform;
+            }
+        }
+        for entity in orphaned {
+            let _ = world.despawn(entity);
+        }
+    }
+}
@@ -1,22 +1,27 @@
 use crate::core::ecs::{EventQueue, Resource};
-use gilrs::{Gilrs, Button};
+use gilrs::{Axis, Button, EventType, Gilrs};
 use hecs::World;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::path::Path;
 use winit::{
     event::*,
     keyboard::{KeyCode, PhysicalKey}
 };
 use winit_input_helper::WinitInputHelper;
 
+/// Путь к сохраненным настройкам поведения действий
+pub const INPUT_BEHAVIOR_PATH: &str = "config/input_behavior.json";
+
 /// Типы событий ввода
 #[derive(Debug, Clone)]
 pub enum InputEvent {
     KeyPressed(KeyCode),
     KeyReleased(KeyCode),
-    MouseMoved(f32, f32),
+    MouseMoved(f32, f32), // Курсор в нормализованных координатах окна, -1..1, y вверх
     MousePressed(MouseButton),
     MouseReleased(MouseButton),
-    MouseWheel(f32),
+    MouseWheel(f32), // Щелчки колеса, от себя - положительные
     GamepadButton(usize, Button, bool),
     GamepadAxis(usize, u32, f32),
     GamepadConnected(usize),
@@ -32,7 +37,7 @@ pub enum InputDevice {
 }
 
 /// Действия ввода для игры
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum InputAction {
     Accelerate,
     Brake,
@@ -41,17 +46,109 @@ pub enum InputAction {
     Handbrake,
     ShiftUp,
     ShiftDown,
+    Clutch,
+    Headlights,
+    HighBeam, // Дальний свет
+    #[serde(alias = "PitLimiter")]
+    SpeedLimiter, // Ограничитель скорости на пит-лейне
+    ResetCar,   // Возврат перевернутой или вылетевшей машины на трассу
+    LookBack,   // Взгляд назад
     ToggleCamera,
     Pause,
+    DebugFrameCapture, // Дамп графа кадра и захват в RenderDoc
+    ToggleAudioSettings,
+    TogglePerfOverlay,  // Переключение уровня оверлея производительности
+    TogglePhysicsDebug, // Каркасы коллайдеров, контакты и лучи подвески
+    PracticeSaveState,  // Снимок физики на тренировке
+    PracticeLoadState,  // Возврат к снимку
+    ToggleTimeTrialMenu,
+    ToggleSaveBrowser,
+    ToggleTuningMenu, // Настройка машины: баланс тормозов и стабилизаторов
+    ToggleTrackEditor,
+    CycleControlScheme, // Следующая упрощенная схема управления для профиля пилота
+    EditorNextTool,
+    EditorDelete,
+    EditorSave,
+    MenuUp,
+    MenuDown,
+    MenuLeft,
+    MenuRight,
+    MenuConfirm,
+    MenuBack,
     // Добавьте другие действия по мере необходимости
 }
 
+/// Упрощенная схема управления для игроков, которым неудобна стандартная раскладка
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ControlScheme {
+    #[default]
+    Standard,
+    AutoThrottle,     // Газ автоматически, игрок только рулит и тормозит
+    SteeringOnly,     // Газ, тормоз и передачи за игрока, остается только руль
+    OneHandedGamepad, // Все управление на левой половине геймпада
+}
+
+impl ControlScheme {
+    /// Схема ведет машину за игрока и потому считается помощником
+    pub fn requires_assists(&self) -> bool {
+        matches!(self, ControlScheme::SteeringOnly)
+    }
+
+    pub fn auto_throttle(&self) -> bool {
+        matches!(self, ControlScheme::AutoThrottle | ControlScheme::SteeringOnly)
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            ControlScheme::Standard => ControlScheme::AutoThrottle,
+            ControlScheme::AutoThrottle => ControlScheme::SteeringOnly,
+            ControlScheme::SteeringOnly => ControlScheme::OneHandedGamepad,
+            ControlScheme::OneHandedGamepad => ControlScheme::Standard,
+        }
+    }
+}
+
+/// Поведение действия: удержание или переключение нажатием
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ActionBehavior {
+    #[default]
+    Hold,   // Активно, пока кнопка нажата
+    Toggle, // Каждое нажатие включает или выключает действие
+}
+
+/// Настройки поведения действий; недостающие действия остаются со значениями по умолчанию
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct InputBehaviorSettings {
+    pub behaviors: HashMap<InputAction, ActionBehavior>,
+}
+
+impl InputBehaviorSettings {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+        serde_json::from_str(&text).map_err(|e| e.to_string())
+    }
+}
+
+/// Подключенные геймпады и геймпад, на который разложено управление игрока
+#[derive(Debug, Clone, Default)]
+pub struct GamepadStatus {
+    pub connected: Vec<usize>,
+    pub assigned: Option<usize>,
+    pub lost: bool,                     // Назначенный геймпад отключился и еще не вернулся
+    pub reassign: Option<InputDevice>, // Запрос игровой логики на перенос управления на другое устройство
+}
+
 /// Система ввода
 pub struct InputSystem {
     input_helper: WinitInputHelper,
     gilrs: Gilrs,
     action_bindings: HashMap<InputAction, Vec<InputBinding>>,
     action_states: HashMap<InputAction, f32>,
+    raw_states: HashMap<InputAction, f32>, // Состояние кнопок до применения поведения
+    behaviors: HashMap<InputAction, ActionBehavior>,
+    control_scheme: ControlScheme, // Схема, под которую сейчас разложен геймпад
+    gamepads: GamepadStatus,
+    window_size: (f32, f32), // Размер окна в пикселях для перевода курсора в координаты окна
 }
 
 /// Привязка ввода к действию
@@ -94,15 +191,27 @@ impl InputSystem {
             eprintln!("Failed to init gilrs, gamepad support disabled");
             Gilrs::new().expect("Failed to initialize gilrs")
         });
+        let connected: Vec<usize> = gilrs.gamepads().map(|(id, _)| usize::from(id)).collect();
+        let gamepads = GamepadStatus {
+            assigned: connected.first().copied(),
+            connected,
+            ..GamepadStatus::default()
+        };
 
         let mut system = Self {
             input_helper: WinitInputHelper::new(),
             gilrs,
             action_bindings: HashMap::new(),
             action_states: HashMap::new(),
+            raw_states: HashMap::new(),
+            behaviors: HashMap::new(),
+            control_scheme: ControlScheme::Standard,
+            gamepads,
+            window_size: (1280.0, 720.0),
         };
 
         system.setup_default_bindings();
+        system.setup_default_behaviors();
         system
     }
 
@@ -144,42 +253,354 @@ impl InputSystem {
             },
         );
 
-        // Геймпад (пример)
         self.bind_action(
-            InputAction::Accelerate,
+            InputAction::ShiftUp,
             InputBinding {
-                device: InputDevice::Gamepad(0),
-                input_type: InputType::GamepadAxis(0, AxisDirection::Positive), // Правый триггер
+                device: InputDevice::Keyboard,
+                input_type: InputType::Key(KeyCode::KeyE),
                 value_scale: 1.0,
             },
         );
 
         self.bind_action(
-            InputAction::Brake,
+            InputAction::ShiftDown,
             InputBinding {
-                device: InputDevice::Gamepad(0),
-                input_type: InputType::GamepadAxis(1, AxisDirection::Positive), // Левый триггер
+                device: InputDevice::Keyboard,
+                input_type: InputType::Key(KeyCode::KeyQ),
                 value_scale: 1.0,
             },
         );
 
         self.bind_action(
-            InputAction::SteerLeft,
+            InputAction::Clutch,
             InputBinding {
-                device: InputDevice::Gamepad(0),
-                input_type: InputType::GamepadAxis(2, AxisDirection::Negative), // Левый стик X-
+                device: InputDevice::Keyboard,
+                input_type: InputType::Key(KeyCode::ShiftLeft),
                 value_scale: 1.0,
             },
         );
 
         self.bind_action(
-            InputAction::SteerRight,
+            InputAction::Handbrake,
+            InputBinding {
+                device: InputDevice::Keyboard,
+                input_type: InputType::Key(KeyCode::Space),
+                value_scale: 1.0,
+            },
+        );
+
+        self.bind_action(
+            InputAction::Headlights,
+            InputBinding {
+                device: InputDevice::Keyboard,
+                input_type: InputType::Key(KeyCode::KeyL),
+                value_scale: 1.0,
+            },
+        );
+
+        self.bind_action(
+            InputAction::HighBeam,
+            InputBinding {
+                device: InputDevice::Keyboard,
+                input_type: InputType::Key(KeyCode::KeyH),
+                value_scale: 1.0,
+            },
+        );
+
+        self.bind_action(
+            InputAction::SpeedLimiter,
+            InputBinding {
+                device: InputDevice::Keyboard,
+                input_type: InputType::Key(KeyCode::KeyP),
+                value_scale: 1.0,
+            },
+        );
+
+        self.bind_action(
+            InputAction::LookBack,
+            InputBinding {
+                device: InputDevice::Keyboard,
+                input_type: InputType::Key(KeyCode::KeyC),
+                value_scale: 1.0,
+            },
+        );
+
+        self.bind_action(
+            InputAction::ToggleCamera,
+            InputBinding {
+                device: InputDevice::Keyboard,
+                input_type: InputType::Key(KeyCode::KeyV),
+                value_scale: 1.0,
+            },
+        );
+
+        self.bind_action(
+            InputAction::DebugFrameCapture,
+            InputBinding {
+                device: InputDevice::Keyboard,
+                input_type: InputType::Key(KeyCode::F12),
+                value_scale: 1.0,
+            },
+        );
+
+        self.bind_action(
+            InputAction::TogglePerfOverlay,
+            InputBinding {
+                device: InputDevice::Keyboard,
+                input_type: InputType::Key(KeyCode::F3),
+                value_scale: 1.0,
+            },
+        );
+
+        self.bind_action(
+            InputAction::TogglePhysicsDebug,
+            InputBinding {
+                device: InputDevice::Keyboard,
+                input_type: InputType::Key(KeyCode::F4),
+                value_scale: 1.0,
+            },
+        );
+
+        self.bind_action(
+            InputAction::PracticeSaveState,
+            InputBinding {
+                device: InputDevice::Keyboard,
+                input_type: InputType::Key(KeyCode::F5),
+                value_scale: 1.0,
+            },
+        );
+
+        self.bind_action(
+            InputAction::PracticeLoadState,
+            InputBinding {
+                device: InputDevice::Keyboard,
+                input_type: InputType::Key(KeyCode::F6),
+                value_scale: 1.0,
+            },
+        );
+
+        self.bind_action(
+            InputAction::ResetCar,
+            InputBinding {
+                device: InputDevice::Keyboard,
+                input_type: InputType::Key(KeyCode::KeyR),
+                value_scale: 1.0,
+            },
+        );
+
+        self.bind_action(
+            InputAction::CycleControlScheme,
+            InputBinding {
+                device: InputDevice::Keyboard,
+                input_type: InputType::Key(KeyCode::F1),
+                value_scale: 1.0,
+            },
+        );
+
+        // Навигация по меню
+        self.bind_action(
+            InputAction::ToggleTimeTrialMenu,
+            InputBinding {
+                device: InputDevice::Keyboard,
+                input_type: InputType::Key(KeyCode::F7),
+                value_scale: 1.0,
+            },
+        );
+
+        self.bind_action(
+            InputAction::ToggleSaveBrowser,
+            InputBinding {
+                device: InputDevice::Keyboard,
+                input_type: InputType::Key(KeyCode::F8),
+                value_scale: 1.0,
+            },
+        );
+
+        self.bind_action(
+            InputAction::ToggleAudioSettings,
+            InputBinding {
+                device: InputDevice::Keyboard,
+                input_type: InputType::Key(KeyCode::F9),
+                value_scale: 1.0,
+            },
+        );
+
+        self.bind_action(
+            InputAction::ToggleTuningMenu,
+            InputBinding {
+                device: InputDevice::Keyboard,
+                input_type: InputType::Key(KeyCode::F10),
+                value_scale: 1.0,
+            },
+        );
+
+        // Редактор трассы
+        self.bind_action(
+            InputAction::ToggleTrackEditor,
+            InputBinding {
+                device: InputDevice::Keyboard,
+                input_type: InputType::Key(KeyCode::F11),
+                value_scale: 1.0,
+            },
+        );
+
+        self.bind_action(
+            InputAction::EditorNextTool,
+            InputBinding {
+                device: InputDevice::Keyboard,
+                input_type: InputType::Key(KeyCode::Tab),
+                value_scale: 1.0,
+            },
+        );
+
+        self.bind_action(
+            InputAction::EditorDelete,
+            InputBinding {
+                device: InputDevice::Keyboard,
+                input_type: InputType::Key(KeyCode::Delete),
+                value_scale: 1.0,
+            },
+        );
+
+        self.bind_action(
+            InputAction::EditorSave,
+            InputBinding {
+                device: InputDevice::Keyboard,
+                input_type: InputType::Key(KeyCode::F2),
+                value_scale: 1.0,
+            },
+        );
+
+        self.bind_action(
+            InputAction::MenuUp,
+            InputBinding {
+                device: InputDevice::Keyboard,
+                input_type: InputType::Key(KeyCode::ArrowUp),
+                value_scale: 1.0,
+            },
+        );
+
+        self.bind_action(
+            InputAction::MenuDown,
+            InputBinding {
+                device: InputDevice::Keyboard,
+                input_type: InputType::Key(KeyCode::ArrowDown),
+                value_scale: 1.0,
+            },
+        );
+
+        self.bind_action(
+            InputAction::MenuLeft,
+            InputBinding {
+                device: InputDevice::Keyboard,
+                input_type: InputType::Key(KeyCode::ArrowLeft),
+                value_scale: 1.0,
+            },
+        );
+
+        self.bind_action(
+            InputAction::MenuRight,
+            InputBinding {
+                device: InputDevice::Keyboard,
+                input_type: InputType::Key(KeyCode::ArrowRight),
+                value_scale: 1.0,
+            },
+        );
+
+        self.bind_action(
+            InputAction::MenuConfirm,
+            InputBinding {
+                device: InputDevice::Keyboard,
+                input_type: InputType::Key(KeyCode::Enter),
+                value_scale: 1.0,
+            },
+        );
+
+        self.bind_action(
+            InputAction::MenuBack,
             InputBinding {
-                device: InputDevice::Gamepad(0),
-                input_type: InputType::GamepadAxis(2, AxisDirection::Positive), // Левый стик X+
+                device: InputDevice::Keyboard,
+                input_type: InputType::Key(KeyCode::Backspace),
                 value_scale: 1.0,
             },
         );
+
+        self.bind_gamepad_layout(ControlScheme::Standard);
+    }
+
+    /// Раскладка геймпада для схемы управления; прежние привязки геймпада снимаются
+    fn bind_gamepad_layout(&mut self, scheme: ControlScheme) {
+        for bindings in self.action_bindings.values_mut() {
+            bindings.retain(|binding| !matches!(binding.device, InputDevice::Gamepad(_)));
+        }
+
+        let device = InputDevice::Gamepad(self.gamepads.assigned.unwrap_or(0));
+        let gamepad = |input_type| InputBinding {
+            device,
+            input_type,
+            value_scale: 1.0,
+        };
+
+        // Руль на левом стике в любой раскладке
+        self.bind_action(InputAction::SteerLeft, gamepad(InputType::GamepadAxis(2, AxisDirection::Negative))); // Левый стик X-
+        self.bind_action(InputAction::SteerRight, gamepad(InputType::GamepadAxis(2, AxisDirection::Positive))); // Левый стик X+
+
+        if scheme == ControlScheme::OneHandedGamepad {
+            // Все под левую руку: газ и тормоз на том же стике, передачи на крестовине
+            self.bind_action(InputAction::Accelerate, gamepad(InputType::GamepadAxis(3, AxisDirection::Positive))); // Левый стик Y+
+            self.bind_action(InputAction::Brake, gamepad(InputType::GamepadAxis(3, AxisDirection::Negative))); // Левый стик Y-
+            self.bind_action(InputAction::ShiftUp, gamepad(InputType::GamepadButton(Button::DPadUp)));
+            self.bind_action(InputAction::ShiftDown, gamepad(InputType::GamepadButton(Button::DPadDown)));
+            self.bind_action(InputAction::Handbrake, gamepad(InputType::GamepadButton(Button::LeftTrigger)));
+            self.bind_action(InputAction::LookBack, gamepad(InputType::GamepadButton(Button::LeftThumb)));
+        } else {
+            self.bind_action(InputAction::Accelerate, gamepad(InputType::GamepadAxis(0, AxisDirection::Positive))); // Правый триггер
+            self.bind_action(InputAction::Brake, gamepad(InputType::GamepadAxis(1, AxisDirection::Positive))); // Левый триггер
+        }
+    }
+
+    fn setup_default_behaviors(&mut self) {
+        // Все работает на удержании: режим фар и ограничитель хранит сама машина,
+        // их действия лишь нажатия
+    }
+
+    /// Задает поведение действия; при смене режима текущее состояние сбрасывается к кнопке
+    pub fn set_action_behavior(&mut self, action: InputAction, behavior: ActionBehavior) {
+        if self.action_behavior(action) != behavior {
+            let raw = *self.raw_states.get(&action).unwrap_or(&0.0);
+            self.action_states.insert(action, raw);
+        }
+        self.behaviors.insert(action, behavior);
+    }
+
+    pub fn action_behavior(&self, action: InputAction) -> ActionBehavior {
+        self.behaviors.get(&action).copied().unwrap_or_default()
+    }
+
+    /// Применяет сохраненные настройки поведения поверх значений по умолчанию
+    pub fn apply_behavior_settings(&mut self, settings: &InputBehaviorSettings) {
+        for (&action, &behavior) in &settings.behaviors {
+            self.set_action_behavior(action, behavior);
+        }
+    }
+
+    /// Записывает состояние кнопки и пересчитывает значение действия по его поведению
+    fn set_raw_state(&mut self, action: InputAction, value: f32) {
+        let was_pressed = *self.raw_states.get(&action).unwrap_or(&0.0) > 0.5;
+        self.raw_states.insert(action, value);
+
+        match self.action_behavior(action) {
+            ActionBehavior::Hold => {
+                self.action_states.insert(action, value);
+            }
+            ActionBehavior::Toggle => {
+                // Переключаем только по фронту нажатия, автоповтор клавиши игнорируется
+                if value > 0.5 && !was_pressed {
+                    let active = self.get_action_value(action) > 0.5;
+                    self.action_states.insert(action, if active { 0.0 } else { 1.0 });
+                }
+            }
+        }
     }
 
     pub fn bind_action(&mut self, action: InputAction, binding: InputBinding) {
@@ -217,20 +638,105 @@ impl InputSystem {
             }
             
             // Обработка мыши упрощена
-            if let WindowEvent::MouseInput { state, button, .. } = event {
-                match state {
+            match event {
+                WindowEvent::MouseInput { state, button, .. } => match state {
                     ElementState::Pressed => input_events.publish(InputEvent::MousePressed(*button)),
                     ElementState::Released => input_events.publish(InputEvent::MouseReleased(*button)),
+                },
+                WindowEvent::Resized(size) if size.width > 0 && size.height > 0 => {
+                    self.window_size = (size.width as f32, size.height as f32);
+                }
+                WindowEvent::CursorMoved { position, .. } => {
+                    let (width, height) = self.window_size;
+                    let x = position.x as f32 / width * 2.0 - 1.0;
+                    let y = 1.0 - position.y as f32 / height * 2.0;
+                    input_events.publish(InputEvent::MouseMoved(x, y));
+                }
+                WindowEvent::MouseWheel { delta, .. } => {
+                    // Тачпад присылает пиксели; примерно 40 пикселей на щелчок
+                    let clicks = match delta {
+                        MouseScrollDelta::LineDelta(_, y) => *y,
+                        MouseScrollDelta::PixelDelta(position) => position.y as f32 / 40.0,
+                    };
+                    input_events.publish(InputEvent::MouseWheel(clicks));
                 }
+                _ => {}
             }
             
             // Другие события мыши и клавиатуры можно добавить по необходимости
         }
         
-        // Обработка событий геймпада - упрощена из-за изменений API
-        while let Some(_gilrs_event) = self.gilrs.next_event() {
-            // Здесь можно добавить обработку событий геймпада
-            // в соответствии с обновленным API gilrs
+        // События геймпадов; аналоговые триггеры идут на оси 0 и 1, левый стик на 2 и 3
+        while let Some(gilrs::Event { id, event, .. }) = self.gilrs.next_event() {
+            let id = usize::from(id);
+            match event {
+                EventType::Connected => input_events.publish(InputEvent::GamepadConnected(id)),
+                EventType::Disconnected => input_events.publish(InputEvent::GamepadDisconnected(id)),
+                EventType::ButtonChanged(Button::RightTrigger2, value, _) => input_events.publish(InputEvent::GamepadAxis(id, 0, value)),
+                EventType::ButtonChanged(Button::LeftTrigger2, value, _) => input_events.publish(InputEvent::GamepadAxis(id, 1, value)),
+                EventType::ButtonPressed(button, _) => input_events.publish(InputEvent::GamepadButton(id, button, true)),
+                EventType::ButtonReleased(button, _) => input_events.publish(InputEvent::GamepadButton(id, button, false)),
+                EventType::AxisChanged(Axis::LeftStickX, value, _) => input_events.publish(InputEvent::GamepadAxis(id, 2, value)),
+                EventType::AxisChanged(Axis::LeftStickY, value, _) => input_events.publish(InputEvent::GamepadAxis(id, 3, value)),
+                _ => {}
+            }
+        }
+    }
+
+    /// Переносит привязки геймпада на другое устройство; с клавиатурой геймпад просто снимается с игрока
+    pub fn reassign_controls(&mut self, device: InputDevice) {
+        self.release_gamepad_actions();
+        self.gamepads.lost = false;
+        match device {
+            InputDevice::Gamepad(id) => {
+                self.gamepads.assigned = Some(id);
+                for binding in self.action_bindings.values_mut().flatten() {
+                    if matches!(binding.device, InputDevice::Gamepad(_)) {
+                        binding.device = device;
+                    }
+                }
+                eprintln!("Управление переназначено на геймпад {}", id);
+            }
+            InputDevice::Keyboard | InputDevice::Mouse => {
+                self.gamepads.assigned = None;
+                eprintln!("Управление переназначено на клавиатуру");
+            }
+        }
+    }
+
+    fn gamepad_connected(&mut self, id: usize) {
+        if !self.gamepads.connected.contains(&id) {
+            self.gamepads.connected.push(id);
+        }
+        match self.gamepads.assigned {
+            None => self.reassign_controls(InputDevice::Gamepad(id)),
+            Some(assigned) if assigned == id && self.gamepads.lost => {
+                self.gamepads.lost = false;
+                eprintln!("Геймпад {} снова подключен", id);
+            }
+            _ => {}
+        }
+    }
+
+    fn gamepad_disconnected(&mut self, id: usize) {
+        self.gamepads.connected.retain(|connected| *connected != id);
+        if self.gamepads.assigned == Some(id) {
+            // Иначе газ или руль, зажатые в момент отключения, так и остались бы нажатыми
+            self.release_gamepad_actions();
+            self.gamepads.lost = true;
+            eprintln!("Геймпад {} отключен", id);
+        }
+    }
+
+    fn release_gamepad_actions(&mut self) {
+        let actions: Vec<InputAction> = self
+            .action_bindings
+            .iter()
+            .filter(|(_, bindings)| bindings.iter().any(|binding| matches!(binding.device, InputDevice::Gamepad(_))))
+            .map(|(action, _)| *action)
+            .collect();
+        for action in actions {
+            self.set_raw_state(action, 0.0);
         }
     }
     
@@ -282,6 +788,7 @@ impl InputSystem {
                         |input_type| if let InputType::MouseButton(b) = input_type { *b == button } else { false }
                     );
                 }
+                InputEvent::GamepadButton(id, _, _) if self.gamepads.assigned != Some(id) => {}
                 InputEvent::GamepadButton(_id, button, pressed) => {
                     let value = if pressed { 1.0 } else { 0.0 };
                     self.update_bindings_for_key_or_button(
@@ -294,6 +801,8 @@ impl InputSystem {
                     // Обновить привязки для осей геймпада
                     self.update_axis_bindings(id, axis, value);
                 }
+                InputEvent::GamepadConnected(id) => self.gamepad_connected(id),
+                InputEvent::GamepadDisconnected(id) => self.gamepad_disconnected(id),
                 // Обработка других типов событий...
                 _ => {}
             }
@@ -306,17 +815,21 @@ impl InputSystem {
         value: f32,
         predicate: impl Fn(&InputType) -> bool,
     ) {
+        let mut updates = Vec::new();
         for (action, bindings) in &self.action_bindings {
             for binding in bindings {
                 if predicate(&binding.input_type) {
-                    let scaled_value = value * binding.value_scale;
-                    self.action_states.insert(*action, scaled_value);
+                    updates.push((*action, value * binding.value_scale));
                 }
             }
         }
+        for (action, value) in updates {
+            self.set_raw_state(action, value);
+        }
     }
 
     fn update_axis_bindings(&mut self, gamepad_id: usize, axis_id: u32, value: f32) {
+        let mut updates = Vec::new();
         for (action, bindings) in &self.action_bindings {
             for binding in bindings {
                 if let InputType::GamepadAxis(axis, direction) = &binding.input_type {
@@ -327,16 +840,40 @@ impl InputSystem {
                             AxisDirection::Both => value.abs(),
                         };
                         
-                        let scaled_value = processed_value * binding.value_scale;
-                        self.action_states.insert(*action, scaled_value);
+                        updates.push((*action, processed_value * binding.value_scale));
                     }
                 }
             }
         }
+        for (action, value) in updates {
+            self.set_raw_state(action, value);
+        }
     }
 
     // Добавим публичный метод process
     pub fn process(&mut self, world: &mut World, _delta_time: f32) {
+        // Схему управления выбирает игровая логика, раскладку геймпада меняем здесь
+        let control_scheme = world
+            .query::<&Resource<ControlScheme>>()
+            .iter()
+            .next()
+            .map(|(_, res)| res.0)
+            .unwrap_or_default();
+        if control_scheme != self.control_scheme {
+            self.control_scheme = control_scheme;
+            self.bind_gamepad_layout(control_scheme);
+        }
+
+        // Игровая логика может попросить перенести управление на другое устройство
+        let reassign = world
+            .query_mut::<&mut Resource<GamepadStatus>>()
+            .into_iter()
+            .next()
+            .and_then(|(_, res)| res.0.reassign.take());
+        if let Some(device) = reassign {
+            self.reassign_controls(device);
+        }
+        
         let input_events = world
             .query_mut::<&mut Resource<EventQueue<InputEvent>>>()
             .into_iter()
@@ -370,5 +907,13 @@ impl InputSystem {
             let resource = Resource(action_states);
             world.spawn((resource,));
         }
+
+        let gamepads = self.gamepads.clone();
+        match world.query_mut::<&mut Resource<GamepadStatus>>().into_iter().next() {
+            Some((_, status)) => status.0 = gamepads,
+            None => {
+                world.spawn((Resource(gamepads),));
+            }
+        }
     }
 } 
\ No newline at end of file
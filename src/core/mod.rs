@@ -1,8 +1,11 @@
 pub mod ecs;
+pub mod hierarchy;
 pub mod physics;
 pub mod renderer;
 pub mod audio;
 pub mod input;
+pub mod random;
+pub mod text;
 
 pub use ecs::*;
 pub use physics::*;
@@ -1,7 +1,12 @@
-use crate::core::ecs::{System, Resource};
+use crate::core::ecs::{EventQueue, System, Resource};
+use crate::game_world::car::{capture_car_states, restore_car_states, CarState};
 use hecs::World;
+use rapier3d::crossbeam::channel::{unbounded, Receiver, Sender};
 use rapier3d::prelude::*;
 use glam::{Vec3, Quat};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
 
 /// Компонент физического тела
 pub struct RigidBodyComponent {
@@ -34,8 +39,163 @@ impl Default for ColliderShapeType {
     }
 }
 
+/// Номер физического материала в реестре; коллайдер хранит его в user_data
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct PhysicsMaterialId(pub u32);
+
+impl PhysicsMaterialId {
+    pub const DEFAULT: Self = Self(0);
+    pub const ASPHALT: Self = Self(1);
+    pub const CONCRETE: Self = Self(2);
+    pub const DIRT: Self = Self(3);
+    pub const GRAVEL: Self = Self(4);
+    pub const GRASS: Self = Self(5);
+    pub const SNOW: Self = Self(6);
+    pub const ICE: Self = Self(7);
+    pub const SAND: Self = Self(8);
+    pub const TERRAIN: Self = Self(9);
+    pub const CAR_BODY: Self = Self(10);
+    pub const BARRIER: Self = Self(11);
+    pub const ROCK: Self = Self(12);
+    pub const STEEL: Self = Self(13);
+    pub const CURB: Self = Self(14);
+}
+
+/// Физический материал: трение и упругость коллайдера, сопротивление качению колес
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PhysicsMaterial {
+    pub name: String,
+    pub friction: f32, // Для покрытий это же и сцепление шин
+    pub restitution: f32,
+    pub rolling_resistance: f32,
+}
+
+impl PhysicsMaterial {
+    pub fn new(name: &str, friction: f32, restitution: f32, rolling_resistance: f32) -> Self {
+        Self {
+            name: name.to_string(),
+            friction,
+            restitution,
+            rolling_resistance,
+        }
+    }
+}
+
+/// Файл с дополнительными материалами трассы
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PhysicsMaterialFile {
+    pub materials: Vec<PhysicsMaterial>,
+}
+
+/// Реестр физических материалов; встроенные занимают номера констант PhysicsMaterialId,
+/// материалы трасс добавляются следом
+#[derive(Debug, Clone)]
+pub struct PhysicsMaterialRegistry {
+    materials: Vec<PhysicsMaterial>,
+}
+
+impl PhysicsMaterialRegistry {
+    pub fn new() -> Self {
+        Self {
+            materials: vec![
+                PhysicsMaterial::new("default", 0.5, 0.0, 0.015),
+                PhysicsMaterial::new("asphalt", 1.0, 0.0, 0.015),
+                PhysicsMaterial::new("concrete", 0.95, 0.0, 0.015),
+                PhysicsMaterial::new("dirt", 0.6, 0.0, 0.05),
+                PhysicsMaterial::new("gravel", 0.4, 0.0, 0.12),
+                PhysicsMaterial::new("grass", 0.3, 0.0, 0.08),
+                PhysicsMaterial::new("snow", 0.2, 0.0, 0.07),
+                PhysicsMaterial::new("ice", 0.1, 0.0, 0.01),
+                PhysicsMaterial::new("sand", 0.4, 0.0, 0.2),
+                PhysicsMaterial::new("terrain", 0.6, 0.0, 0.05),
+                PhysicsMaterial::new("car_body", 0.7, 0.2, 0.0),
+                PhysicsMaterial::new("barrier", 0.4, 0.0, 0.0),
+                PhysicsMaterial::new("rock", 0.8, 0.0, 0.0),
+                PhysicsMaterial::new("steel", 1.0, 0.0, 0.015),
+                PhysicsMaterial::new("curb", 0.85, 0.0, 0.03),
+            ],
+        }
+    }
+
+    /// Материалы из файла трассы; одноименный материал заменяется
+    pub fn load_file(&mut self, path: &Path) -> Result<Vec<PhysicsMaterialId>, String> {
+        let text = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+        let file: PhysicsMaterialFile = serde_json::from_str(&text).map_err(|e| e.to_string())?;
+        file.materials.into_iter().map(|material| self.register(material)).collect()
+    }
+
+    pub fn register(&mut self, material: PhysicsMaterial) -> Result<PhysicsMaterialId, String> {
+        if material.friction < 0.0 || material.restitution < 0.0 || material.rolling_resistance < 0.0 {
+            return Err(format!("Material {} has negative coefficients", material.name));
+        }
+        if let Some(id) = self.find(&material.name) {
+            self.materials[id.0 as usize] = material;
+            return Ok(id);
+        }
+        self.materials.push(material);
+        Ok(PhysicsMaterialId(self.materials.len() as u32 - 1))
+    }
+
+    pub fn find(&self, name: &str) -> Option<PhysicsMaterialId> {
+        self.materials
+            .iter()
+            .position(|material| material.name == name)
+            .map(|index| PhysicsMaterialId(index as u32))
+    }
+
+    /// Неизвестный номер дает материал по умолчанию
+    pub fn get(&self, id: PhysicsMaterialId) -> &PhysicsMaterial {
+        self.materials.get(id.0 as usize).unwrap_or(&self.materials[0])
+    }
+
+    /// Трение и упругость материала для коллайдера; номер остается в user_data
+    pub fn apply(&self, id: PhysicsMaterialId, builder: ColliderBuilder) -> ColliderBuilder {
+        let material = self.get(id);
+        builder
+            .friction(material.friction)
+            .restitution(material.restitution)
+            .user_data(id.0 as u128)
+    }
+}
+
+impl Default for PhysicsMaterialRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Реестр материалов мира; без ресурса действуют встроенные материалы
+pub fn physics_materials(world: &World) -> PhysicsMaterialRegistry {
+    world
+        .query::<&Resource<PhysicsMaterialRegistry>>()
+        .iter()
+        .next()
+        .map(|(_, res)| res.0.clone())
+        .unwrap_or_default()
+}
+
+/// Материал, на который ссылается коллайдер
+pub fn collider_material(collider: &Collider) -> PhysicsMaterialId {
+    PhysicsMaterialId(collider.user_data as u32)
+}
+
+/// Сообщение при обращении к решателю, который сейчас у рабочего потока
+const STEP_IN_FLIGHT: &str = "шаг физики еще идет в рабочем потоке, сначала finish_step";
+
 /// Физическая система
 pub struct PhysicsSystem {
+    simulation: Option<Box<Simulation>>, // None, пока решатель у рабочего потока
+    worker: Option<PhysicsWorker>,       // Запускается при первом begin_step
+    accumulator: f32, // Накопленное, но еще не просчитанное время
+    joints: HashMap<hecs::Entity, ImpulseJointHandle>, // Шарниры rapier, созданные по JointComponent
+    tracked_bodies: HashSet<RigidBodyHandle>,           // Тела и коллайдеры, принадлежавшие сущностям на прошлом кадре
+    tracked_colliders: HashSet<ColliderHandle>,
+    applied_sleep: Option<(PhysicsSleepSettings, usize)>, // Пороги и число тел при последнем применении
+}
+
+/// Решатель rapier со всем состоянием, которое меняет шаг. Вместе с наборами тел и
+/// коллайдеров из ресурса мира целиком переходит к рабочему потоку на время шага
+struct Simulation {
     gravity: Vector<Real>,
     integration_parameters: IntegrationParameters,
     physics_pipeline: PhysicsPipeline,
@@ -45,11 +205,62 @@ pub struct PhysicsSystem {
     impulse_joint_set: ImpulseJointSet,
     multibody_joint_set: MultibodyJointSet,
     ccd_solver: CCDSolver,
-    query_pipeline: QueryPipeline,
+    event_collector: ChannelEventCollector,
+    collision_receiver: Receiver<rapier3d::geometry::CollisionEvent>,
+    _contact_force_receiver: Receiver<ContactForceEvent>,
+    pending_collisions: Vec<PendingCollision>, // События подшагов, еще не привязанные к сущностям
+    step_time: f32,                            // Время в rapier за текущий кадр
 }
 
-impl PhysicsSystem {
-    pub fn new() -> Self {
+/// Шаг кадра: решатель, наборы из ресурса мира и подшаги. Кто держит задание, тот и владеет
+/// физикой; главный поток получает все обратно в finish_step
+struct StepJob {
+    simulation: Box<Simulation>,
+    rigid_body_set: RigidBodySet,
+    collider_set: ColliderSet,
+    substeps: u32,
+    fixed_dt: f32,
+    delta_time: f32,   // Время кадра, для статистики
+    dropped_time: f32, // Отброшено при перегрузке
+}
+
+impl StepJob {
+    fn run(&mut self) {
+        for _ in 0..self.substeps {
+            self.simulation.step(&mut self.rigid_body_set, &mut self.collider_set, self.fixed_dt);
+        }
+    }
+}
+
+/// Рабочий поток физики: принимает задание, считает подшаги и возвращает его
+struct PhysicsWorker {
+    jobs: Sender<StepJob>,
+    results: Receiver<StepJob>,
+}
+
+impl PhysicsWorker {
+    fn spawn() -> Self {
+        let (jobs, job_receiver) = unbounded::<StepJob>();
+        let (result_sender, results) = unbounded();
+        std::thread::Builder::new()
+            .name("physics".to_string())
+            .spawn(move || {
+                while let Ok(mut job) = job_receiver.recv() {
+                    job.run();
+                    if result_sender.send(job).is_err() {
+                        break;
+                    }
+                }
+            })
+            .expect("не удалось запустить поток физики");
+        Self { jobs, results }
+    }
+}
+
+impl Simulation {
+    fn new() -> Self {
+        let (collision_sender, collision_receiver) = unbounded();
+        let (contact_force_sender, contact_force_receiver) = unbounded();
         Self {
             gravity: vector![0.0, -9.81, 0.0],
             integration_parameters: IntegrationParameters::default(),
@@ -60,12 +271,171 @@ impl PhysicsSystem {
             impulse_joint_set: ImpulseJointSet::new(),
             multibody_joint_set: MultibodyJointSet::new(),
             ccd_solver: CCDSolver::new(),
-            query_pipeline: QueryPipeline::new(),
+            event_collector: ChannelEventCollector::new(collision_sender, contact_force_sender),
+            collision_receiver,
+            _contact_force_receiver: contact_force_receiver,
+            pending_collisions: Vec::new(),
+            step_time: 0.0,
+        }
+    }
+    
+    /// Шаг симуляции rapier; наборы тел и коллайдеров на время шага взяты из ресурса мира
+    fn step(&mut self, rigid_body_set: &mut RigidBodySet, collider_set: &mut ColliderSet, delta_time: f32) {
+        self.integration_parameters.dt = delta_time;
+        
+        let started = std::time::Instant::now();
+        self.physics_pipeline.step(
+            &self.gravity,
+            &self.integration_parameters,
+            &mut self.island_manager,
+            &mut self.broad_phase,
+            &mut self.narrow_phase,
+            rigid_body_set,
+            collider_set,
+            &mut self.impulse_joint_set,
+            &mut self.multibody_joint_set,
+            &mut self.ccd_solver,
+            None, // Запросы к сцене обновляются раз в кадр, см. update_scene_query
+            &(),
+            &self.event_collector,
+        );
+        self.step_time += started.elapsed().as_secs_f32();
+        
+        // Точку и импульс берем после решателя, пока контакт еще есть в узкой фазе
+        while let Ok(event) = self.collision_receiver.try_recv() {
+            let (collider1, collider2) = (event.collider1(), event.collider2());
+            let phase = if event.started() { CollisionPhase::Started } else { CollisionPhase::Stopped };
+            
+            let mut point = None;
+            let mut normal = Vec3::ZERO;
+            let mut impulse = 0.0;
+            if let Some(pair) = self.narrow_phase.contact_pair(collider1, collider2) {
+                for manifold in &pair.manifolds {
+                    impulse += manifold.points.iter().map(|p| p.data.impulse).sum::<f32>();
+                    if let (None, Some(contact)) = (point, manifold.data.solver_contacts.first()) {
+                        point = Some(Vec3::new(contact.point.x, contact.point.y, contact.point.z));
+                        normal = Vec3::new(manifold.data.normal.x, manifold.data.normal.y, manifold.data.normal.z);
+                    }
+                }
+            }
+            
+            // Без активного контакта (разлет, сенсор) берем середину между коллайдерами
+            let point = point.unwrap_or_else(|| {
+                let center = |handle| {
+                    collider_set
+                        .get(handle)
+                        .map(|c| Vec3::new(c.translation().x, c.translation().y, c.translation().z))
+                        .unwrap_or(Vec3::ZERO)
+                };
+                (center(collider1) + center(collider2)) * 0.5
+            });
+            
+            self.pending_collisions.push(PendingCollision { collider1, collider2, phase, point, normal, impulse });
+        }
+    }
+}
+
+/// Столкновение из rapier с данными контакта, снятыми сразу после шага
+struct PendingCollision {
+    collider1: ColliderHandle,
+    collider2: ColliderHandle,
+    phase: CollisionPhase,
+    point: Vec3,
+    normal: Vec3,
+    impulse: f32,
+}
+
+/// Настройки шага физики с фиксированным временем
+#[derive(Debug, Clone, Copy)]
+pub struct PhysicsStepSettings {
+    pub fixed_dt: f32,
+    pub max_substeps: u32, // Больше шагов за кадр не делаем, даже если не успеваем
+}
+
+impl Default for PhysicsStepSettings {
+    fn default() -> Self {
+        Self {
+            fixed_dt: 1.0 / 120.0,
+            max_substeps: 8,
+        }
+    }
+}
+
+/// Статистика шагов физики за последний кадр
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PhysicsStepStats {
+    pub substeps: u32,
+    pub dropped_time: f32,       // Отброшенное время при перегрузке
+    pub time_scale: f32,         // Доля реального времени, которую удалось просчитать
+    pub slow_motion: bool,       // Кадр просчитан с замедлением
+    pub slow_motion_frames: u64, // Всего кадров с замедлением за сессию
+}
+
+/// Засыпание тел: неподвижные тела выпадают из решателя, пока их не разбудит контакт.
+/// Пороги применяются ко всем подвижным телам, в том числе созданным позже
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PhysicsSleepSettings {
+    pub enabled: bool,
+    pub linear_threshold: f32,  // м/с, медленнее тело может заснуть
+    pub angular_threshold: f32, // рад/с
+    pub time_until_sleep: f32,  // Сколько тело должно оставаться ниже порогов, с
+}
+
+impl Default for PhysicsSleepSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            linear_threshold: RigidBodyActivation::default_linear_threshold(),
+            angular_threshold: RigidBodyActivation::default_angular_threshold(),
+            time_until_sleep: RigidBodyActivation::default_time_until_sleep(),
+        }
+    }
+}
+
+/// Нагрузка физики за последний кадр: сколько тел и контактов реально считалось
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PhysicsIslandStats {
+    pub bodies: usize,
+    pub active_bodies: usize,   // Подвижные тела, которые не спят
+    pub sleeping_bodies: usize,
+    pub islands: usize,         // Группы бодрствующих тел, связанных контактами и шарнирами
+    pub contact_pairs: usize,   // Пары коллайдеров с касанием
+    pub solver_time: f32,       // Время в rapier за все подшаги кадра, с
+}
+
+/// Положение и скорости тела в снимке физики
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BodyState {
+    pub entity: u64, // Entity::to_bits, снимок действует в пределах сессии
+    pub position: [f32; 3],
+    pub rotation: [f32; 4], // x, y, z, w
+    pub linvel: [f32; 3],
+    pub angvel: [f32; 3],
+}
+
+/// Снимок физики для перемотки, отката при сетевой игре и сохранений на тренировке
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PhysicsSnapshot {
+    pub accumulator: f32, // Время, еще не просчитанное шагами
+    pub bodies: Vec<BodyState>,
+    pub cars: Vec<CarState>,
+}
+
+impl PhysicsSystem {
+    pub fn new() -> Self {
+        Self {
+            simulation: Some(Box::new(Simulation::new())),
+            worker: None,
+            accumulator: 0.0,
+            joints: HashMap::new(),
+            tracked_bodies: HashSet::new(),
+            tracked_colliders: HashSet::new(),
+            applied_sleep: None,
         }
     }
 
     pub fn set_gravity(&mut self, gravity: Vec3) {
-        self.gravity = vector![gravity.x, gravity.y, gravity.z];
+        self.simulation.as_mut().expect(STEP_IN_FLIGHT).gravity = vector![gravity.x, gravity.y, gravity.z];
     }
 
     pub fn create_rigid_body(&self, position: Vec3, _rotation: Quat, body_type: RigidBodyType) -> RigidBody {
@@ -84,16 +454,466 @@ impl PhysicsSystem {
         rb
     }
 
-    pub fn create_box_collider(&self, half_extents: Vec3, restitution: f32, friction: f32) -> Collider {
-        ColliderBuilder::cuboid(half_extents.x, half_extents.y, half_extents.z)
-            .restitution(restitution)
-            .friction(friction)
+    pub fn create_box_collider(&self, half_extents: Vec3, materials: &PhysicsMaterialRegistry, material: PhysicsMaterialId) -> Collider {
+        materials
+            .apply(material, ColliderBuilder::cuboid(half_extents.x, half_extents.y, half_extents.z))
             .build()
     }
 
-    // Публичный метод для обновления физики, который можно вызывать напрямую
-    pub fn process(&mut self, world: &mut World, delta_time: f32) {
-        // Содержимое такое же, как в функции update
+    /// Снимок подвижных тел и машин; неподвижная геометрия не меняется и не сохраняется
+    pub fn snapshot(&self, world: &World) -> PhysicsSnapshot {
+        let mut bodies = Vec::new();
+        if let Some((_, resource)) = world.query::<&Resource<(RigidBodySet, ColliderSet)>>().iter().next() {
+            let rigid_body_set = &resource.0 .0;
+            for (entity, rb) in world.query::<&RigidBodyComponent>().iter() {
+                let Some(body) = rigid_body_set.get(rb.handle).filter(|body| !body.is_fixed()) else {
+                    continue;
+                };
+                let (position, rotation) = (body.translation(), body.rotation());
+                let (linvel, angvel) = (body.linvel(), body.angvel());
+                bodies.push(BodyState {
+                    entity: entity.to_bits().get(),
+                    position: [position.x, position.y, position.z],
+                    rotation: [rotation.i, rotation.j, rotation.k, rotation.w],
+                    linvel: [linvel.x, linvel.y, linvel.z],
+                    angvel: [angvel.x, angvel.y, angvel.z],
+                });
+            }
+        }
+        PhysicsSnapshot {
+            accumulator: self.accumulator,
+            bodies,
+            cars: capture_car_states(world),
+        }
+    }
+    
+    /// Возвращает мир к снимку. Сущности, исчезнувшие после снимка, пропускаются;
+    /// события столкновений, накопленные после него, отбрасываются
+    pub fn restore(&mut self, world: &mut World, snapshot: &PhysicsSnapshot) -> Result<(), String> {
+        let handles: HashMap<hecs::Entity, RigidBodyHandle> = world
+            .query::<&RigidBodyComponent>()
+            .iter()
+            .map(|(entity, rb)| (entity, rb.handle))
+            .collect();
+        let Some((_, resource)) = world
+            .query_mut::<&mut Resource<(RigidBodySet, ColliderSet)>>()
+            .into_iter()
+            .next()
+        else {
+            return Err("Physics world is not created".to_string());
+        };
+        let rigid_body_set = &mut resource.0 .0;
+        
+        let mut transforms = Vec::new();
+        for state in &snapshot.bodies {
+            let Some(entity) = hecs::Entity::from_bits(state.entity) else {
+                continue;
+            };
+            let Some(body) = handles.get(&entity).and_then(|handle| rigid_body_set.get_mut(*handle)) else {
+                continue;
+            };
+            let [x, y, z] = state.position;
+            let [i, j, k, w] = state.rotation;
+            body.set_translation(vector![x, y, z], true);
+            body.set_rotation(rapier3d::na::UnitQuaternion::from_quaternion(rapier3d::na::Quaternion::new(w, i, j, k)), true);
+            body.set_linvel(vector![state.linvel[0], state.linvel[1], state.linvel[2]], true);
+            body.set_angvel(vector![state.angvel[0], state.angvel[1], state.angvel[2]], true);
+            transforms.push((entity, Vec3::from(state.position), Quat::from_array(state.rotation)));
+        }
+        
+        for (entity, position, rotation) in transforms {
+            if let Ok(transform) = world.query_one_mut::<&mut TransformComponent>(entity) {
+                transform.position = position;
+                transform.rotation = rotation;
+            }
+        }
+        restore_car_states(world, &snapshot.cars);
+        
+        self.accumulator = snapshot.accumulator;
+        let simulation = self.simulation.as_mut().expect(STEP_IN_FLIGHT);
+        simulation.pending_collisions.clear();
+        while simulation.collision_receiver.try_recv().is_ok() {}
+        Ok(())
+    }
+    
+    /// Убирает из rapier тела и коллайдеры, на которые больше не ссылается ни одна сущность:
+    /// сущность удалена или с нее сняли компонент. Вместе с телом уходят его коллайдеры и шарниры
+    fn remove_orphaned_bodies(&mut self, world: &mut World) {
+        let live_bodies: HashSet<RigidBodyHandle> = world
+            .query::<&RigidBodyComponent>()
+            .iter()
+            .map(|(_, rb)| rb.handle)
+            .collect();
+        let live_colliders: HashSet<ColliderHandle> = world
+            .query::<&ColliderComponent>()
+            .iter()
+            .map(|(_, collider)| collider.handle)
+            .collect();
+        
+        let orphaned_bodies: Vec<RigidBodyHandle> = self.tracked_bodies.difference(&live_bodies).copied().collect();
+        let orphaned_colliders: Vec<ColliderHandle> = self.tracked_colliders.difference(&live_colliders).copied().collect();
+        self.tracked_bodies = live_bodies;
+        self.tracked_colliders = live_colliders;
+        if orphaned_bodies.is_empty() && orphaned_colliders.is_empty() {
+            return;
+        }
+        
+        let Some((_, resource)) = world
+            .query_mut::<&mut Resource<(RigidBodySet, ColliderSet)>>()
+            .into_iter()
+            .next()
+        else {
+            return;
+        };
+        let (rigid_body_set, collider_set) = &mut resource.0;
+        let simulation = self.simulation.as_mut().expect(STEP_IN_FLIGHT);
+        for handle in orphaned_colliders {
+            collider_set.remove(handle, &mut simulation.island_manager, rigid_body_set, true);
+        }
+        for handle in orphaned_bodies {
+            rigid_body_set.remove(
+                handle,
+                &mut simulation.island_manager,
+                collider_set,
+                &mut simulation.impulse_joint_set,
+                &mut simulation.multibody_joint_set,
+                true,
+            );
+        }
+        // Шарниры удаленных тел уже сняты rapier, остается забыть их номера
+        self.joints.retain(|_, handle| simulation.impulse_joint_set.contains(*handle));
+    }
+    
+    /// Пороги засыпания из настроек; заново применяются при смене настроек или числа тел
+    fn apply_sleep_settings(&mut self, world: &mut World) {
+        let settings = world
+            .query::<&Resource<PhysicsSleepSettings>>()
+            .iter()
+            .next()
+            .map(|(_, res)| res.0)
+            .unwrap_or_default();
+        let Some((_, resource)) = world
+            .query_mut::<&mut Resource<(RigidBodySet, ColliderSet)>>()
+            .into_iter()
+            .next()
+        else {
+            return;
+        };
+        let rigid_body_set = &mut resource.0 .0;
+        if self.applied_sleep == Some((settings, rigid_body_set.len())) {
+            return;
+        }
+        
+        for (_, body) in rigid_body_set.iter_mut().filter(|(_, body)| body.is_dynamic()) {
+            let activation = body.activation_mut();
+            if settings.enabled {
+                activation.linear_threshold = settings.linear_threshold;
+                activation.angular_threshold = settings.angular_threshold;
+                activation.time_until_sleep = settings.time_until_sleep;
+            } else {
+                // Отрицательный порог rapier понимает как запрет засыпать
+                activation.linear_threshold = -1.0;
+                activation.angular_threshold = -1.0;
+            }
+            if !settings.enabled && body.is_sleeping() {
+                body.wake_up(true);
+            }
+        }
+        self.applied_sleep = Some((settings, rigid_body_set.len()));
+    }
+    
+    /// Счетчики тел, островов и контактов после шага
+    fn collect_island_stats(&mut self, world: &mut World) {
+        let stats = {
+            let mut physics = world.query::<&Resource<(RigidBodySet, ColliderSet)>>();
+            let Some((_, resource)) = physics.iter().next() else {
+                return;
+            };
+            let (rigid_body_set, collider_set) = &resource.0;
+            let simulation = self.simulation.as_ref().expect(STEP_IN_FLIGHT);
+            
+            let active: Vec<RigidBodyHandle> = simulation.island_manager.active_dynamic_bodies().to_vec();
+            let sleeping_bodies = rigid_body_set
+                .iter()
+                .filter(|(_, body)| body.is_dynamic() && body.is_sleeping())
+                .count();
+            
+            // Острова: бодрствующие тела, связанные касаниями и шарнирами (объединение множеств)
+            let index: HashMap<RigidBodyHandle, usize> = active.iter().enumerate().map(|(i, handle)| (*handle, i)).collect();
+            let mut parent: Vec<usize> = (0..active.len()).collect();
+            let mut link = |a: Option<RigidBodyHandle>, b: Option<RigidBodyHandle>| {
+                if let (Some(&a), Some(&b)) = (a.and_then(|a| index.get(&a)), b.and_then(|b| index.get(&b))) {
+                    let (ra, rb) = (island_root(&mut parent, a), island_root(&mut parent, b));
+                    parent[ra] = rb;
+                }
+            };
+            let mut contact_pairs = 0;
+            for pair in simulation.narrow_phase.contact_pairs().filter(|pair| pair.has_any_active_contact) {
+                contact_pairs += 1;
+                let body = |handle| collider_set.get(handle).and_then(|collider| collider.parent());
+                link(body(pair.collider1), body(pair.collider2));
+            }
+            for (_, joint) in simulation.impulse_joint_set.iter() {
+                link(Some(joint.body1), Some(joint.body2));
+            }
+            let islands = (0..active.len()).filter(|&i| island_root(&mut parent, i) == i).count();
+            
+            PhysicsIslandStats {
+                bodies: rigid_body_set.len(),
+                active_bodies: active.len(),
+                sleeping_bodies,
+                islands,
+                contact_pairs,
+                solver_time: simulation.step_time,
+            }
+        };
+        
+        match world.query_mut::<&mut Resource<PhysicsIslandStats>>().into_iter().next() {
+            Some((_, current)) => current.0 = stats,
+            None => {
+                world.spawn((Resource(stats),));
+            }
+        }
+    }
+    
+    /// Приводит шарниры rapier в соответствие с JointComponent: новые создаются, удаленные и
+    /// оторванные снимаются
+    fn sync_joints(&mut self, world: &mut World) {
+        let body_handles: HashMap<hecs::Entity, RigidBodyHandle> = world
+            .query::<&RigidBodyComponent>()
+            .iter()
+            .map(|(entity, rb)| (entity, rb.handle))
+            .collect();
+        let mut physics = world.query::<&Resource<(RigidBodySet, ColliderSet)>>();
+        let Some((_, resource)) = physics.iter().next() else {
+            return;
+        };
+        let rigid_body_set = &resource.0 .0;
+        
+        let mut alive = Vec::new();
+        let mut created = Vec::new();
+        for (entity, joint) in world.query::<&JointComponent>().iter() {
+            if joint.broken {
+                continue;
+            }
+            let bodies = (body_handles.get(&joint.body1), body_handles.get(&joint.body2));
+            let (Some(&body1), Some(&body2)) = bodies else {
+                continue;
+            };
+            if !rigid_body_set.contains(body1) || !rigid_body_set.contains(body2) {
+                continue;
+            }
+            alive.push(entity);
+            if !self.joints.contains_key(&entity) {
+                created.push((entity, body1, body2, joint.build()));
+            }
+        }
+        
+        // Сущность шарнира удалена, оторвана или потеряла одно из тел
+        let simulation = self.simulation.as_mut().expect(STEP_IN_FLIGHT);
+        let stale: Vec<hecs::Entity> = self.joints.keys().filter(|entity| !alive.contains(entity)).copied().collect();
+        for entity in stale {
+            if let Some(handle) = self.joints.remove(&entity) {
+                simulation.impulse_joint_set.remove(handle, true);
+            }
+        }
+        for (entity, body1, body2, data) in created {
+            let handle = simulation.impulse_joint_set.insert(body1, body2, data, true);
+            self.joints.insert(entity, handle);
+        }
+    }
+    
+    /// Отрывает шарниры, нагрузка на которые за последний подшаг превысила предел
+    fn break_joints(&mut self, world: &mut World) {
+        let simulation = self.simulation.as_ref().expect(STEP_IN_FLIGHT);
+        let dt = simulation.integration_parameters.dt.max(f32::EPSILON);
+        for (entity, joint) in world.query_mut::<&mut JointComponent>() {
+            let (Some(limit), Some(handle)) = (joint.break_force, self.joints.get(&entity)) else {
+                continue;
+            };
+            let Some(rapier_joint) = simulation.impulse_joint_set.get(*handle) else {
+                continue;
+            };
+            let impulses = rapier_joint.impulses;
+            let force = Vec3::new(impulses[0], impulses[1], impulses[2]).length() / dt;
+            if force > limit {
+                joint.broken = true;
+                eprintln!("Шарнир оторвался: нагрузка {:.0} Н при пределе {:.0} Н", force, limit);
+            }
+        }
+    }
+    
+    /// Публикует столкновения кадра в очередь событий мира; очередь живет один кадр
+    fn publish_collisions(&mut self, world: &mut World) {
+        let collider_entities: HashMap<ColliderHandle, hecs::Entity> = world
+            .query::<&ColliderComponent>()
+            .iter()
+            .map(|(entity, collider)| (collider.handle, entity))
+            .collect();
+        
+        let simulation = self.simulation.as_mut().expect(STEP_IN_FLIGHT);
+        let events: Vec<CollisionEvent> = simulation
+            .pending_collisions
+            .drain(..)
+            .filter_map(|pending| {
+                Some(CollisionEvent {
+                    entity1: *collider_entities.get(&pending.collider1)?,
+                    entity2: *collider_entities.get(&pending.collider2)?,
+                    phase: pending.phase,
+                    point: pending.point,
+                    normal: pending.normal,
+                    impulse: pending.impulse,
+                })
+            })
+            .collect();
+        
+        let queue = world
+            .query_mut::<&mut Resource<EventQueue<CollisionEvent>>>()
+            .into_iter()
+            .next()
+            .map(|(_, res)| &mut res.0);
+        
+        match queue {
+            Some(queue) => {
+                queue.clear();
+                for event in events {
+                    queue.publish(event);
+                }
+            }
+            None => {
+                let mut queue = EventQueue::new();
+                for event in events {
+                    queue.publish(event);
+                }
+                world.spawn((Resource(queue),));
+            }
+        }
+    }
+    
+    /// Снимок активных контактов узкой фазы с привязкой к сущностям
+    fn collect_contacts(&self, world: &mut World) {
+        let collider_entities: HashMap<ColliderHandle, hecs::Entity> = world
+            .query::<&ColliderComponent>()
+            .iter()
+            .map(|(entity, collider)| (collider.handle, entity))
+            .collect();
+        
+        let simulation = self.simulation.as_ref().expect(STEP_IN_FLIGHT);
+        let dt = simulation.integration_parameters.dt;
+        let mut contacts = Vec::new();
+        
+        for pair in simulation.narrow_phase.contact_pairs() {
+            if !pair.has_any_active_contact {
+                continue;
+            }
+            let (Some(&entity1), Some(&entity2)) = (
+                collider_entities.get(&pair.collider1),
+                collider_entities.get(&pair.collider2),
+            ) else {
+                continue;
+            };
+            
+            for manifold in &pair.manifolds {
+                let Some(contact) = manifold.data.solver_contacts.first() else {
+                    continue;
+                };
+                let normal_impulse: f32 = manifold.points.iter().map(|p| p.data.impulse).sum();
+                let normal = manifold.data.normal;
+                
+                contacts.push(ContactInfo {
+                    entity1,
+                    entity2,
+                    point: Vec3::new(contact.point.x, contact.point.y, contact.point.z),
+                    normal: Vec3::new(normal.x, normal.y, normal.z),
+                    normal_force: normal_impulse / dt,
+                });
+            }
+        }
+        
+        let resource = world
+            .query_mut::<&mut Resource<Vec<ContactInfo>>>()
+            .into_iter()
+            .next()
+            .map(|(_, res)| &mut res.0);
+        
+        match resource {
+            Some(active_contacts) => *active_contacts = contacts,
+            None => {
+                world.spawn((Resource(contacts),));
+            }
+        }
+    }
+
+    /// Фиксированные подшаги за кадр; при перегрузке лишнее время отбрасывается,
+    /// и замедление явно отмечается в статистике. Забирает решатель и наборы тел из мира
+    fn prepare_step(&mut self, world: &mut World, delta_time: f32) -> Option<StepJob> {
+        self.remove_orphaned_bodies(world);
+        self.apply_sleep_settings(world);
+        if delta_time <= 0.0 {
+            return None;
+        }
+        self.sync_joints(world);
+        
+        let settings = world
+            .query::<&Resource<PhysicsStepSettings>>()
+            .iter()
+            .next()
+            .map(|(_, res)| res.0)
+            .unwrap_or_default();
+        let fixed_dt = settings.fixed_dt.max(1.0 / 1000.0);
+        
+        self.accumulator += delta_time;
+        let needed = (self.accumulator / fixed_dt).floor() as u32;
+        let substeps = needed.min(settings.max_substeps);
+        self.accumulator -= substeps as f32 * fixed_dt;
+        
+        // Спираль смерти: не пытаемся догнать то, что не успели
+        let mut dropped_time = 0.0;
+        if needed > substeps {
+            dropped_time = self.accumulator - self.accumulator % fixed_dt;
+            self.accumulator -= dropped_time;
+        }
+        
+        let (_, resource) = world
+            .query_mut::<&mut Resource<(RigidBodySet, ColliderSet)>>()
+            .into_iter()
+            .next()?;
+        let (rigid_body_set, collider_set) = std::mem::take(&mut resource.0);
+        let mut simulation = self.simulation.take().expect(STEP_IN_FLIGHT);
+        simulation.step_time = 0.0;
+        Some(StepJob {
+            simulation,
+            rigid_body_set,
+            collider_set,
+            substeps,
+            fixed_dt,
+            delta_time,
+            dropped_time,
+        })
+    }
+    
+    /// Возвращает решатель и наборы тел после шага и публикует его результаты в мир
+    fn complete_step(&mut self, world: &mut World, job: Option<StepJob>) {
+        if let Some(job) = job {
+            if let Some((_, resource)) = world
+                .query_mut::<&mut Resource<(RigidBodySet, ColliderSet)>>()
+                .into_iter()
+                .next()
+            {
+                resource.0 = (job.rigid_body_set, job.collider_set);
+            }
+            self.simulation = Some(job.simulation);
+            
+            if job.substeps > 0 {
+                self.collect_contacts(world);
+            }
+            self.publish_collisions(world);
+            self.publish_step_stats(world, job.delta_time, job.substeps, job.dropped_time);
+            self.break_joints(world);
+            self.collect_island_stats(world);
+        }
+        update_scene_query(world);
+        
         // Обновляем трансформации после физического шага
         // Собираем данные о положении физических тел и компонентах
         let body_handles: Vec<(hecs::Entity, RigidBodyHandle)> = world
@@ -134,6 +954,70 @@ impl PhysicsSystem {
             }
         }
     }
+
+    /// Статистика шагов за кадр
+    fn publish_step_stats(&self, world: &mut World, delta_time: f32, substeps: u32, dropped_time: f32) {
+        let slow_motion = dropped_time > 0.0;
+        let time_scale = ((delta_time - dropped_time) / delta_time).clamp(0.0, 1.0);
+        
+        let resource = world
+            .query_mut::<&mut Resource<PhysicsStepStats>>()
+            .into_iter()
+            .next()
+            .map(|(_, res)| &mut res.0);
+        let previous_slow_frames = resource.as_ref().map_or(0, |stats| stats.slow_motion_frames);
+        let stats = PhysicsStepStats {
+            substeps,
+            dropped_time,
+            time_scale,
+            slow_motion,
+            slow_motion_frames: previous_slow_frames + slow_motion as u64,
+        };
+        
+        match resource {
+            Some(current) => *current = stats,
+            None => {
+                world.spawn((Resource(stats),));
+            }
+        }
+    }
+
+    /// Начинает шаг кадра в рабочем потоке и сразу возвращает управление: пока идет шаг,
+    /// главный поток может готовить и отправлять кадр по уже известным трансформациям.
+    /// До finish_step решатель и наборы тел принадлежат рабочему потоку, в ресурсе мира
+    /// лежат пустые наборы; системы, которым нужна физика, должны ждать finish_step
+    pub fn begin_step(&mut self, world: &mut World, delta_time: f32) {
+        if self.simulation.is_none() {
+            self.finish_step(world);
+        }
+        let Some(job) = self.prepare_step(world, delta_time) else {
+            self.complete_step(world, None);
+            return;
+        };
+        let worker = self.worker.get_or_insert_with(PhysicsWorker::spawn);
+        worker.jobs.send(job).expect("поток физики остановился");
+    }
+    
+    /// Дожидается шага из begin_step и переносит результаты в мир
+    pub fn finish_step(&mut self, world: &mut World) {
+        let Some(worker) = self.worker.as_ref().filter(|_| self.simulation.is_none()) else {
+            return;
+        };
+        let job = worker.results.recv().expect("поток физики остановился");
+        self.complete_step(world, Some(job));
+    }
+
+    // Публичный метод для обновления физики, который можно вызывать напрямую
+    pub fn process(&mut self, world: &mut World, delta_time: f32) {
+        if self.simulation.is_none() {
+            self.finish_step(world);
+        }
+        let mut job = self.prepare_step(world, delta_time);
+        if let Some(job) = job.as_mut() {
+            job.run();
+        }
+        self.complete_step(world, job);
+    }
 }
 
 // Используем отдельную реализацию System только для совместимости со старым кодом
@@ -143,7 +1027,346 @@ impl System for PhysicsSystem {
     }
 }
 
+/// Корень множества в объединении островов со сжатием пути
+fn island_root(parent: &mut [usize], mut i: usize) -> usize {
+    while parent[i] != i {
+        parent[i] = parent[parent[i]];
+        i = parent[i];
+    }
+    i
+}
+
+/// Мгновенный перенос тела сущности с обнулением скоростей (например, рестарт попытки)
+pub fn teleport_body(world: &mut World, entity: hecs::Entity, position: Vec3, rotation: Quat) {
+    let Some(handle) = world.get::<&RigidBodyComponent>(entity).ok().map(|rb| rb.handle) else {
+        return;
+    };
+    if let Some((_, resource)) = world
+        .query_mut::<&mut Resource<(RigidBodySet, ColliderSet)>>()
+        .into_iter()
+        .next()
+    {
+        if let Some(body) = resource.0 .0.get_mut(handle) {
+            body.set_translation([position.x, position.y, position.z].into(), true);
+            body.set_rotation(
+                rapier3d::na::UnitQuaternion::from_quaternion(rapier3d::na::Quaternion::new(
+                    rotation.w, rotation.x, rotation.y, rotation.z,
+                )),
+                true,
+            );
+            body.set_linvel(Default::default(), true);
+            body.set_angvel(Default::default(), true);
+        }
+    }
+    if let Ok(transform) = world.query_one_mut::<&mut TransformComponent>(entity) {
+        transform.position = position;
+        transform.rotation = rotation;
+    }
+}
+
+/// Попадание запроса к сцене; точка и нормаль в мировых координатах
+#[derive(Debug, Clone, Copy)]
+pub struct SceneHit {
+    pub entity: Option<hecs::Entity>, // Коллайдер без сущности (например, созданный напрямую в rapier)
+    pub point: Vec3,
+    pub normal: Vec3,
+    pub distance: f32,
+}
+
+/// Отбор коллайдеров для запросов к сцене
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SceneQueryFilter {
+    pub static_only: bool,              // Только неподвижная геометрия: дорога, отбойники, ландшафт
+    pub exclude: Option<hecs::Entity>,  // Тело, которое запрос не видит (обычно сам спрашивающий)
+    pub include_sensors: bool,
+}
+
+impl SceneQueryFilter {
+    pub fn static_only() -> Self {
+        Self {
+            static_only: true,
+            ..Default::default()
+        }
+    }
+
+    pub fn excluding(entity: hecs::Entity) -> Self {
+        Self {
+            exclude: Some(entity),
+            ..Default::default()
+        }
+    }
+}
+
+/// Запросы к физической сцене для игровой логики: лучи, заметание сферой и пересечения
+/// без прямого доступа к rapier. Ресурс обновляет физическая система после шага
+pub struct PhysicsQuery {
+    pipeline: QueryPipeline,
+    collider_entities: HashMap<ColliderHandle, hecs::Entity>,
+    body_entities: HashMap<hecs::Entity, RigidBodyHandle>,
+}
+
+impl PhysicsQuery {
+    pub fn new() -> Self {
+        Self {
+            pipeline: QueryPipeline::new(),
+            collider_entities: HashMap::new(),
+            body_entities: HashMap::new(),
+        }
+    }
+
+    fn rapier_filter(&self, filter: &SceneQueryFilter) -> QueryFilter<'static> {
+        let mut rapier_filter = if filter.static_only { QueryFilter::only_fixed() } else { QueryFilter::default() };
+        if !filter.include_sensors {
+            rapier_filter = rapier_filter.exclude_sensors();
+        }
+        if let Some(handle) = filter.exclude.and_then(|entity| self.body_entities.get(&entity)) {
+            rapier_filter = rapier_filter.exclude_rigid_body(*handle);
+        }
+        rapier_filter
+    }
+}
+
+impl Default for PhysicsQuery {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Пересобирает запросы к сцене по текущему положению тел и коллайдеров
+pub fn update_scene_query(world: &mut World) {
+    let mut body_entities: HashMap<hecs::Entity, RigidBodyHandle> = world
+        .query::<&RigidBodyComponent>()
+        .iter()
+        .map(|(entity, rb)| (entity, rb.handle))
+        .collect();
+    let mut collider_entities: HashMap<ColliderHandle, hecs::Entity> = world
+        .query::<&ColliderComponent>()
+        .iter()
+        .map(|(entity, collider)| (collider.handle, entity))
+        .collect();
+
+    let mut sets = world.query::<&Resource<(RigidBodySet, ColliderSet)>>();
+    let Some((_, resource)) = sets.iter().next() else {
+        return;
+    };
+    let (rigid_body_set, collider_set) = &resource.0;
+
+    // Коллайдер без своего компонента относится к сущности родительского тела
+    let body_owners: HashMap<RigidBodyHandle, hecs::Entity> =
+        body_entities.iter().map(|(entity, handle)| (*handle, *entity)).collect();
+    for (handle, collider) in collider_set.iter() {
+        if let Some(owner) = collider.parent().and_then(|body| body_owners.get(&body)) {
+            collider_entities.entry(handle).or_insert(*owner);
+        }
+    }
+    // Сущности только с коллайдером исключаются через тело, к которому он прикреплен
+    for (handle, entity) in &collider_entities {
+        if let Some(body) = collider_set.get(*handle).and_then(|collider| collider.parent()) {
+            body_entities.entry(*entity).or_insert(body);
+        }
+    }
+
+    let mut query = world.query::<&mut Resource<PhysicsQuery>>();
+    match query.iter().next() {
+        Some((_, scene)) => {
+            scene.0.pipeline.update(rigid_body_set, collider_set);
+            scene.0.collider_entities = collider_entities;
+            scene.0.body_entities = body_entities;
+        }
+        None => {
+            let mut scene = PhysicsQuery::new();
+            scene.pipeline.update(rigid_body_set, collider_set);
+            scene.collider_entities = collider_entities;
+            scene.body_entities = body_entities;
+            drop(query);
+            drop(sets);
+            world.spawn((Resource(scene),));
+        }
+    }
+}
+
+/// Ближайшее пересечение луча со сценой; направление нормализуется
+pub fn raycast(world: &World, origin: Vec3, direction: Vec3, max_distance: f32, filter: SceneQueryFilter) -> Option<SceneHit> {
+    let direction = direction.try_normalize()?;
+    let mut sets = world.query::<&Resource<(RigidBodySet, ColliderSet)>>();
+    let (_, resource) = sets.iter().next()?;
+    let (rigid_body_set, collider_set) = &resource.0;
+    let mut scenes = world.query::<&Resource<PhysicsQuery>>();
+    let (_, scene) = scenes.iter().next()?;
+    let scene = &scene.0;
+
+    let ray = Ray::new(point![origin.x, origin.y, origin.z], vector![direction.x, direction.y, direction.z]);
+    let (handle, intersection) = scene.pipeline.cast_ray_and_get_normal(
+        rigid_body_set,
+        collider_set,
+        &ray,
+        max_distance,
+        true,
+        scene.rapier_filter(&filter),
+    )?;
+    Some(SceneHit {
+        entity: scene.collider_entities.get(&handle).copied(),
+        point: origin + direction * intersection.toi,
+        normal: Vec3::new(intersection.normal.x, intersection.normal.y, intersection.normal.z),
+        distance: intersection.toi,
+    })
+}
+
+/// Заметание сферой вдоль направления: первое касание и пройденное до него расстояние
+pub fn sphere_cast(
+    world: &World,
+    origin: Vec3,
+    radius: f32,
+    direction: Vec3,
+    max_distance: f32,
+    filter: SceneQueryFilter,
+) -> Option<SceneHit> {
+    let direction = direction.try_normalize()?;
+    let mut sets = world.query::<&Resource<(RigidBodySet, ColliderSet)>>();
+    let (_, resource) = sets.iter().next()?;
+    let (rigid_body_set, collider_set) = &resource.0;
+    let mut scenes = world.query::<&Resource<PhysicsQuery>>();
+    let (_, scene) = scenes.iter().next()?;
+    let scene = &scene.0;
+
+    let (handle, toi) = scene.pipeline.cast_shape(
+        rigid_body_set,
+        collider_set,
+        &Isometry::translation(origin.x, origin.y, origin.z),
+        &vector![direction.x, direction.y, direction.z],
+        &Ball::new(radius),
+        max_distance,
+        true,
+        scene.rapier_filter(&filter),
+    )?;
+    // Сфера, уже касающаяся препятствия на старте, дает нулевое расстояние без точки контакта
+    let (point, normal) = match toi.status {
+        rapier3d::parry::query::TOIStatus::Penetrating => (origin, -direction),
+        _ => (
+            Vec3::new(toi.witness1.x, toi.witness1.y, toi.witness1.z),
+            Vec3::new(toi.normal1.x, toi.normal1.y, toi.normal1.z),
+        ),
+    };
+    Some(SceneHit {
+        entity: scene.collider_entities.get(&handle).copied(),
+        point,
+        normal,
+        distance: toi.toi,
+    })
+}
+
+/// Сущности, коллайдеры которых пересекают сферу
+pub fn overlap_sphere(world: &World, center: Vec3, radius: f32, filter: SceneQueryFilter) -> Vec<hecs::Entity> {
+    let mut sets = world.query::<&Resource<(RigidBodySet, ColliderSet)>>();
+    let Some((_, resource)) = sets.iter().next() else {
+        return Vec::new();
+    };
+    let (rigid_body_set, collider_set) = &resource.0;
+    let mut scenes = world.query::<&Resource<PhysicsQuery>>();
+    let Some((_, scene)) = scenes.iter().next() else {
+        return Vec::new();
+    };
+    let scene = &scene.0;
+
+    let mut entities = Vec::new();
+    scene.pipeline.intersections_with_shape(
+        rigid_body_set,
+        collider_set,
+        &Isometry::translation(center.x, center.y, center.z),
+        &Ball::new(radius),
+        scene.rapier_filter(&filter),
+        |handle| {
+            if let Some(entity) = scene.collider_entities.get(&handle) {
+                if !entities.contains(entity) {
+                    entities.push(*entity);
+                }
+            }
+            true
+        },
+    );
+    entities
+}
+
+/// Вид шарнира; оси задаются в локальных координатах первого тела
+#[derive(Debug, Clone, Copy)]
+pub enum JointKind {
+    Fixed,
+    Revolute { axis: Vec3 },  // Вращение вокруг оси: ворота, сцепка прицепа
+    Prismatic { axis: Vec3 }, // Скольжение вдоль оси
+}
+
+/// Шарнир между телами двух сущностей. Висит на отдельной сущности, чтобы у тела могло быть
+/// несколько шарниров; в rapier создается физической системой, удаление сущности снимает шарнир
+#[derive(Debug, Clone)]
+pub struct JointComponent {
+    pub body1: hecs::Entity,
+    pub body2: hecs::Entity,
+    pub kind: JointKind,
+    pub anchor1: Vec3,             // Точка крепления в локальных координатах первого тела
+    pub anchor2: Vec3,             // И второго
+    pub limits: Option<[f32; 2]>,  // Радианы для вращения, метры для скольжения
+    pub break_force: Option<f32>,  // Нагрузка, Н, при которой шарнир отрывается (бампер после удара)
+    pub broken: bool,
+}
+
+impl JointComponent {
+    pub fn new(body1: hecs::Entity, body2: hecs::Entity, kind: JointKind, anchor1: Vec3, anchor2: Vec3) -> Self {
+        Self {
+            body1,
+            body2,
+            kind,
+            anchor1,
+            anchor2,
+            limits: None,
+            break_force: None,
+            broken: false,
+        }
+    }
+
+    pub fn revolute(body1: hecs::Entity, body2: hecs::Entity, axis: Vec3, anchor1: Vec3, anchor2: Vec3) -> Self {
+        Self::new(body1, body2, JointKind::Revolute { axis }, anchor1, anchor2)
+    }
+
+    /// Пределы хода; для жесткого шарнира не действуют
+    pub fn with_limits(mut self, min: f32, max: f32) -> Self {
+        self.limits = Some([min, max]);
+        self
+    }
+
+    pub fn breakable(mut self, break_force: f32) -> Self {
+        self.break_force = Some(break_force);
+        self
+    }
+
+    fn build(&self) -> GenericJoint {
+        let anchor1 = point![self.anchor1.x, self.anchor1.y, self.anchor1.z];
+        let anchor2 = point![self.anchor2.x, self.anchor2.y, self.anchor2.z];
+        let axis = |axis: Vec3| {
+            let axis = axis.try_normalize().unwrap_or(Vec3::Y);
+            UnitVector::new_normalize(vector![axis.x, axis.y, axis.z])
+        };
+        match self.kind {
+            JointKind::Fixed => FixedJointBuilder::new().local_anchor1(anchor1).local_anchor2(anchor2).into(),
+            JointKind::Revolute { axis: joint_axis } => {
+                let builder = RevoluteJointBuilder::new(axis(joint_axis)).local_anchor1(anchor1).local_anchor2(anchor2);
+                match self.limits {
+                    Some(limits) => builder.limits(limits).into(),
+                    None => builder.into(),
+                }
+            }
+            JointKind::Prismatic { axis: joint_axis } => {
+                let builder = PrismaticJointBuilder::new(axis(joint_axis)).local_anchor1(anchor1).local_anchor2(anchor2);
+                match self.limits {
+                    Some(limits) => builder.limits(limits).into(),
+                    None => builder.into(),
+                }
+            }
+        }
+    }
+}
+
 /// Компонент трансформации
+#[derive(Debug, Clone, Copy)]
 pub struct TransformComponent {
     pub position: Vec3,
     pub rotation: Quat,
@@ -174,11 +1397,110 @@ impl Default for RigidBodyType {
     }
 }
 
-/// Пример данных для обработки столкновений
+/// Активный контакт между двумя сущностями на текущем шаге физики
+#[derive(Debug, Clone)]
+pub struct ContactInfo {
+    pub entity1: hecs::Entity,
+    pub entity2: hecs::Entity,
+    pub point: Vec3,
+    pub normal: Vec3,      // От первой сущности ко второй
+    pub normal_force: f32, // Импульс, деленный на шаг симуляции
+}
+
+/// Начало или конец касания двух коллайдеров
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CollisionPhase {
+    Started,
+    Stopped,
+}
+
+/// Столкновение двух сущностей; очередь `EventQueue<CollisionEvent>` обновляется каждый кадр
+#[derive(Debug, Clone)]
 pub struct CollisionEvent {
     pub entity1: hecs::Entity,
     pub entity2: hecs::Entity,
+    pub phase: CollisionPhase,
     pub point: Vec3,
     pub normal: Vec3,
     pub impulse: f32,
-} 
\ No newline at end of file
+} 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn physics_world() -> World {
+        let mut world = World::new();
+        world.spawn((Resource((RigidBodySet::new(), ColliderSet::new())),));
+        world
+    }
+
+    fn spawn_box(world: &mut World, position: Vec3, half_extents: Vec3, body_type: RigidBodyType) -> hecs::Entity {
+        let system = PhysicsSystem::new();
+        let body = system.create_rigid_body(position, Quat::IDENTITY, body_type);
+        let collider = system.create_box_collider(half_extents, &PhysicsMaterialRegistry::default(), PhysicsMaterialId::DEFAULT);
+        let (body_handle, collider_handle) = {
+            let (_, resource) = world.query_mut::<&mut Resource<(RigidBodySet, ColliderSet)>>().into_iter().next().unwrap();
+            let (rigid_body_set, collider_set) = &mut resource.0;
+            let body_handle = rigid_body_set.insert(body);
+            (body_handle, collider_set.insert_with_parent(collider, body_handle, rigid_body_set))
+        };
+        world.spawn((
+            RigidBodyComponent { handle: body_handle, body_type },
+            ColliderComponent { handle: collider_handle, shape_type: ColliderShapeType::Box },
+            TransformComponent { position, ..Default::default() },
+        ))
+    }
+
+    #[test]
+    fn raycast_skips_the_excluded_body() {
+        let mut world = physics_world();
+        let ground = spawn_box(&mut world, Vec3::ZERO, Vec3::new(10.0, 0.5, 10.0), RigidBodyType::Static);
+        let car = spawn_box(&mut world, Vec3::new(0.0, 2.0, 0.0), Vec3::splat(0.5), RigidBodyType::Dynamic);
+        update_scene_query(&mut world);
+
+        let origin = Vec3::new(0.0, 5.0, 0.0);
+        let hit = raycast(&world, origin, -Vec3::Y, 10.0, SceneQueryFilter::default()).unwrap();
+        assert_eq!(hit.entity, Some(car));
+
+        let hit = raycast(&world, origin, -Vec3::Y, 10.0, SceneQueryFilter::excluding(car)).unwrap();
+        assert_eq!(hit.entity, Some(ground));
+        assert!((hit.distance - 4.5).abs() < 1e-3);
+        assert!(hit.normal.abs_diff_eq(Vec3::Y, 1e-3));
+    }
+
+    #[test]
+    fn sync_joints_builds_every_joint_kind() {
+        let mut world = physics_world();
+        let base = spawn_box(&mut world, Vec3::ZERO, Vec3::splat(0.5), RigidBodyType::Static);
+        let kinds = [
+            JointKind::Fixed,
+            JointKind::Revolute { axis: Vec3::X },
+            JointKind::Prismatic { axis: Vec3::Y },
+        ];
+        let joints: Vec<hecs::Entity> = kinds
+            .iter()
+            .enumerate()
+            .map(|(index, &kind)| {
+                let body = spawn_box(&mut world, Vec3::new(index as f32 * 2.0, 1.0, 0.0), Vec3::splat(0.5), RigidBodyType::Dynamic);
+                let joint = JointComponent::new(base, body, kind, Vec3::Y, Vec3::ZERO).with_limits(-0.5, 0.5);
+                world.spawn((joint,))
+            })
+            .collect();
+
+        let mut system = PhysicsSystem::new();
+        system.sync_joints(&mut world);
+        assert_eq!(system.joints.len(), 3);
+        let joint_set = &system.simulation.as_ref().unwrap().impulse_joint_set;
+        let data = |entity: hecs::Entity| joint_set.get(system.joints[&entity]).unwrap().data;
+        assert_eq!(data(joints[0]).locked_axes, JointAxesMask::LOCKED_FIXED_AXES);
+        assert_eq!(data(joints[1]).locked_axes, JointAxesMask::LOCKED_REVOLUTE_AXES);
+        assert_eq!(data(joints[2]).locked_axes, JointAxesMask::LOCKED_PRISMATIC_AXES);
+        assert_eq!(data(joints[2]).limits(JointAxis::X).map(|limits| limits.max), Some(0.5));
+
+        // Удаленная сущность снимает свой шарнир
+        world.despawn(joints[0]).unwrap();
+        system.sync_joints(&mut world);
+        assert_eq!(system.joints.len(), 2);
+        assert_eq!(system.simulation.as_ref().unwrap().impulse_joint_set.len(), 2);
+    }
+}
@@ -1,5 +1,6 @@
 use crate::core::ecs::{System, Resource};
 use crate::core::physics::TransformComponent;
+use crate::core::text::{GlyphAtlas, GlyphInstance, HudText, HUD_FONT_PATH};
 use hecs::World;
 use wgpu::*;
 use wgpu::util::DeviceExt;
@@ -7,7 +8,10 @@ use winit::{
     event::*,
     window::{Window, WindowId},
 };
-use glam::{Mat4, Vec3};
+use glam::{Mat4, Quat, Vec3};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::time::{Duration, Instant};
 
 /// Компонент рендеринга
 #[derive(Clone, Copy)]
@@ -16,6 +20,7 @@ pub struct RenderComponent {
     pub material_id: usize,
     pub visible: bool,
     pub scale: Vec3,
+    pub opacity: f32, // 1.0 — непрозрачный объект
 }
 
 impl Default for RenderComponent {
@@ -25,15 +30,27 @@ impl Default for RenderComponent {
             material_id: 0,
             visible: true,
             scale: Vec3::new(1.0, 1.0, 1.0),
+            opacity: 1.0,
         }
     }
 }
 
+/// Карта смешивания слоев ландшафта и детальные текстуры слоев
+#[derive(Clone, Default)]
+pub struct TerrainSplatData {
+    pub width: u32,
+    pub depth: u32,
+    pub weights: Vec<[u8; 4]>, // Веса слоев в RGB на узел сетки, строками вдоль X
+    pub detail_size: u32,
+    pub details: Vec<u8>, // Слои подряд, detail_size * detail_size байт на слой
+}
+
 /// Менеджер ресурсов для рендеринга
 pub struct RenderResourceManager {
     pub mesh_data: Vec<MeshData>,
     pub material_data: Vec<MaterialData>,
     pub texture_paths: Vec<String>,
+    pub terrain_splat: Option<TerrainSplatData>,
 }
 
 impl RenderResourceManager {
@@ -42,6 +59,7 @@ impl RenderResourceManager {
             mesh_data: Vec::new(),
             material_data: Vec::new(),
             texture_paths: Vec::new(),
+            terrain_splat: None,
         }
     }
 
@@ -65,6 +83,24 @@ impl RenderResourceManager {
     
     // Добавляем простой куб в качестве меша
     pub fn add_simple_cube(&mut self) -> usize {
+        self.add_mesh_data(Self::cube_mesh_data())
+    }
+    
+    // Куб с помятой передней частью: передняя грань вдавлена, верхняя кромка опущена (стадии повреждений кузова)
+    pub fn add_dented_cube(&mut self, dent: f32) -> usize {
+        let mut mesh_data = Self::cube_mesh_data();
+        for vertex in &mut mesh_data.vertices {
+            if vertex.position[2] > 0.0 {
+                vertex.position[2] -= 0.3 * dent;
+                if vertex.position[1] > 0.0 {
+                    vertex.position[1] -= 0.25 * dent;
+                }
+            }
+        }
+        self.add_mesh_data(mesh_data)
+    }
+    
+    fn cube_mesh_data() -> MeshData {
         // Создадим вершины куба 1х1х1
         let vertices = vec![
             // Передняя грань (z+)
@@ -114,6 +150,58 @@ impl RenderResourceManager {
             20, 21, 22, 22, 23, 20, // левая грань
         ];
         
+        MeshData {
+            vertices,
+            indices: Some(indices),
+        }
+    }
+    
+    // Добавляем цилиндр 1х1х1 с осью вдоль X (колеса)
+    pub fn add_simple_cylinder(&mut self, segments: u16) -> usize {
+        let segments = segments.max(3);
+        let mut vertices = Vec::new();
+        let mut indices = Vec::new();
+        
+        let point = |i: u16| {
+            let angle = i as f32 / segments as f32 * std::f32::consts::TAU;
+            (angle.cos(), angle.sin())
+        };
+        
+        // Боковая поверхность
+        for i in 0..=segments {
+            let (y, z) = point(i);
+            let u = i as f32 / segments as f32;
+            vertices.push(Vertex { position: [-0.5, y * 0.5, z * 0.5], tex_coords: [u, 1.0], normal: [0.0, y, z] });
+            vertices.push(Vertex { position: [0.5, y * 0.5, z * 0.5], tex_coords: [u, 0.0], normal: [0.0, y, z] });
+        }
+        for i in 0..segments {
+            let a = i * 2;
+            indices.extend_from_slice(&[a, a + 2, a + 1, a + 1, a + 2, a + 3]);
+        }
+        
+        // Торцы
+        for side in [-1.0f32, 1.0] {
+            let center = vertices.len() as u16;
+            vertices.push(Vertex { position: [side * 0.5, 0.0, 0.0], tex_coords: [0.5, 0.5], normal: [side, 0.0, 0.0] });
+            for i in 0..segments {
+                let (y, z) = point(i);
+                vertices.push(Vertex {
+                    position: [side * 0.5, y * 0.5, z * 0.5],
+                    tex_coords: [0.5 + y * 0.5, 0.5 + z * 0.5],
+                    normal: [side, 0.0, 0.0],
+                });
+            }
+            for i in 0..segments {
+                let a = center + 1 + i;
+                let b = center + 1 + (i + 1) % segments;
+                if side > 0.0 {
+                    indices.extend_from_slice(&[center, a, b]);
+                } else {
+                    indices.extend_from_slice(&[center, b, a]);
+                }
+            }
+        }
+        
         let mesh_data = MeshData {
             vertices,
             indices: Some(indices),
@@ -122,6 +210,44 @@ impl RenderResourceManager {
         self.add_mesh_data(mesh_data)
     }
     
+    // Покрышка: труба 1х1х1 с осью вдоль X и отверстием под диск
+    pub fn add_tire(&mut self, segments: u16, inner_ratio: f32) -> usize {
+        let mut vertices = Vec::new();
+        let mut indices = Vec::new();
+        append_tube(&mut vertices, &mut indices, segments.max(3), 0.5 * inner_ratio, 0.5, -0.5, 0.5);
+        
+        self.add_mesh_data(MeshData {
+            vertices,
+            indices: Some(indices),
+        })
+    }
+    
+    // Колесный диск: обод, ступица и спицы, ось вдоль X, размер 1х1х1
+    pub fn add_wheel_rim(&mut self, segments: u16, spokes: u16) -> usize {
+        let mut vertices = Vec::new();
+        let mut indices = Vec::new();
+        
+        append_tube(&mut vertices, &mut indices, segments.max(3), 0.44, 0.5, -0.5, 0.5);
+        append_tube(&mut vertices, &mut indices, segments.max(3), 0.0, 0.1, 0.1, 0.5);
+        
+        // Спицы лежат у внешней плоскости диска
+        for i in 0..spokes {
+            let rotation = Quat::from_rotation_x(i as f32 / spokes.max(1) as f32 * std::f32::consts::TAU);
+            append_box(
+                &mut vertices,
+                &mut indices,
+                rotation * Vec3::new(0.35, 0.0, 0.27),
+                Vec3::new(0.15, 0.03, 0.18),
+                rotation,
+            );
+        }
+        
+        self.add_mesh_data(MeshData {
+            vertices,
+            indices: Some(indices),
+        })
+    }
+    
     // Создаем базовый материал с указанным цветом
     pub fn add_basic_material(&mut self, color: [f32; 4]) -> usize {
         let material_data = MaterialData {
@@ -130,11 +256,135 @@ impl RenderResourceManager {
             roughness: 0.5,
             albedo_texture_path: None,
             normal_texture_path: None,
+            terrain_layers: None,
+            emissive: [0.0; 3],
+        };
+        self.add_material_data(material_data)
+    }
+
+    // Создаем материал с текстурой из файла
+    pub fn add_textured_material(&mut self, texture_path: &str) -> usize {
+        self.add_texture_path(texture_path.to_string());
+        let material_data = MaterialData {
+            base_color: [1.0, 1.0, 1.0, 1.0],
+            metallic: 0.0,
+            roughness: 0.6,
+            albedo_texture_path: Some(texture_path.to_string()),
+            normal_texture_path: None,
+            terrain_layers: None,
+            emissive: [0.0; 3],
+        };
+        self.add_material_data(material_data)
+    }
+
+    // Создаем светящийся материал (фары, стоп-сигналы, экраны)
+    pub fn add_emissive_material(&mut self, color: [f32; 4], emissive: [f32; 3]) -> usize {
+        let material_data = MaterialData {
+            base_color: color,
+            metallic: 0.0,
+            roughness: 0.2,
+            albedo_texture_path: None,
+            normal_texture_path: None,
+            terrain_layers: None,
+            emissive,
+        };
+        self.add_material_data(material_data)
+    }
+
+    // Создаем материал ландшафта со смешиванием слоев (трава/грунт/асфальт)
+    pub fn add_terrain_material(&mut self, layer_colors: [[f32; 4]; 3]) -> usize {
+        let material_data = MaterialData {
+            base_color: [1.0, 1.0, 1.0, 1.0],
+            metallic: 0.0,
+            roughness: 0.9,
+            albedo_texture_path: None,
+            normal_texture_path: None,
+            terrain_layers: Some(layer_colors),
+            emissive: [0.0; 3],
         };
         self.add_material_data(material_data)
     }
 }
 
+/// Добавляет трубу вдоль оси X (при inner_radius = 0 - сплошной цилиндр)
+fn append_tube(
+    vertices: &mut Vec<Vertex>,
+    indices: &mut Vec<u16>,
+    segments: u16,
+    inner_radius: f32,
+    outer_radius: f32,
+    x0: f32,
+    x1: f32,
+) {
+    let point = |i: u16| {
+        let angle = i as f32 / segments as f32 * std::f32::consts::TAU;
+        (angle.cos(), angle.sin())
+    };
+    
+    // Внешняя и внутренняя поверхности
+    for (radius, facing) in [(outer_radius, 1.0f32), (inner_radius, -1.0)] {
+        if radius <= 0.0 {
+            continue;
+        }
+        let base = vertices.len() as u16;
+        for i in 0..=segments {
+            let (y, z) = point(i);
+            let u = i as f32 / segments as f32;
+            let normal = [0.0, y * facing, z * facing];
+            vertices.push(Vertex { position: [x0, y * radius, z * radius], tex_coords: [u, 1.0], normal });
+            vertices.push(Vertex { position: [x1, y * radius, z * radius], tex_coords: [u, 0.0], normal });
+        }
+        for i in 0..segments {
+            let a = base + i * 2;
+            if facing > 0.0 {
+                indices.extend_from_slice(&[a, a + 2, a + 1, a + 1, a + 2, a + 3]);
+            } else {
+                indices.extend_from_slice(&[a, a + 1, a + 2, a + 1, a + 3, a + 2]);
+            }
+        }
+    }
+    
+    // Торцевые кольца
+    for (x, side) in [(x0, -1.0f32), (x1, 1.0)] {
+        let base = vertices.len() as u16;
+        for i in 0..=segments {
+            let (y, z) = point(i);
+            vertices.push(Vertex { position: [x, y * inner_radius, z * inner_radius], tex_coords: [0.5, 0.5], normal: [side, 0.0, 0.0] });
+            vertices.push(Vertex { position: [x, y * outer_radius, z * outer_radius], tex_coords: [0.5 + y * 0.5, 0.5 + z * 0.5], normal: [side, 0.0, 0.0] });
+        }
+        for i in 0..segments {
+            let a = base + i * 2;
+            if side > 0.0 {
+                indices.extend_from_slice(&[a, a + 1, a + 2, a + 1, a + 3, a + 2]);
+            } else {
+                indices.extend_from_slice(&[a, a + 2, a + 1, a + 1, a + 2, a + 3]);
+            }
+        }
+    }
+}
+
+/// Добавляет повернутый параллелепипед
+fn append_box(vertices: &mut Vec<Vertex>, indices: &mut Vec<u16>, center: Vec3, half: Vec3, rotation: Quat) {
+    let faces = [Vec3::X, Vec3::NEG_X, Vec3::Y, Vec3::NEG_Y, Vec3::Z, Vec3::NEG_Z];
+    for normal in faces {
+        // Две оси в плоскости грани
+        let tangent = if normal.x.abs() > 0.5 { Vec3::Y } else { Vec3::X };
+        let bitangent = normal.cross(tangent);
+        let base = vertices.len() as u16;
+        for (u, v) in [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)] {
+            let local = (normal + tangent * u + bitangent * v) * half;
+            let position = center + rotation * local;
+            let world_normal = rotation * normal;
+            vertices.push(Vertex {
+                position: position.to_array(),
+                tex_coords: [(u + 1.0) * 0.5, (v + 1.0) * 0.5],
+                normal: world_normal.to_array(),
+            });
+        }
+        indices.extend_from_slice(&[base, base + 1, base + 2, base + 2, base + 3, base]);
+    }
+}
+
 /// Структура меша
 pub struct Mesh {
     pub vertex_buffer: Buffer,
@@ -144,6 +394,7 @@ pub struct Mesh {
 }
 
 /// Вспомогательная структура для хранения данных меша до создания буферов
+#[derive(Clone)]
 pub struct MeshData {
     pub vertices: Vec<Vertex>,
     pub indices: Option<Vec<u16>>,
@@ -166,6 +417,8 @@ pub struct MaterialData {
     pub roughness: f32,
     pub albedo_texture_path: Option<String>,
     pub normal_texture_path: Option<String>,
+    pub terrain_layers: Option<[[f32; 4]; 3]>, // Цвета слоев для смешивания по карте ландшафта
+    pub emissive: [f32; 3], // Собственное свечение (может быть больше 1)
 }
 
 /// Структура материала
@@ -173,6 +426,7 @@ pub struct Material {
     pub base_color: [f32; 4],
     pub metallic: f32,
     pub roughness: f32,
+    pub emissive: [f32; 3],
     pub albedo_texture: Option<usize>,
     pub normal_texture: Option<usize>,
     pub bind_group: Option<BindGroup>,
@@ -184,6 +438,7 @@ impl Default for Material {
             base_color: [1.0, 1.0, 1.0, 1.0],
             metallic: 0.0,
             roughness: 0.5,
+            emissive: [0.0; 3],
             albedo_texture: None,
             normal_texture: None,
             bind_group: None,
@@ -201,10 +456,10 @@ pub struct Texture {
 /// Определение формата вершины
 #[repr(C)]
 #[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
-struct Vertex {
-    position: [f32; 3],
-    tex_coords: [f32; 2],
-    normal: [f32; 3],
+pub struct Vertex {
+    pub position: [f32; 3],
+    pub tex_coords: [f32; 2],
+    pub normal: [f32; 3],
 }
 
 impl Vertex {
@@ -233,406 +488,1727 @@ impl Vertex {
     }
 }
 
-/// Система рендеринга
-pub struct RenderSystem<'window> {
-    instance: Instance,
-    surface: Option<Surface<'window>>,
-    device: Device,
-    queue: Queue,
-    config: SurfaceConfiguration,
-    pipeline: RenderPipeline,
-    depth_texture: Option<Texture>,
-    camera_bind_group: BindGroup,
-    model_bind_group: BindGroup,
-    light_bind_group: BindGroup,
-    vertex_buffer: Buffer,
-    index_buffer: Buffer,
-    num_indices: u32,
-    camera_buffer: Buffer,
-    model_buffer: Buffer,
-    material_buffer: Buffer,
+/// Данные экземпляра растительности для инстансинга
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct VegetationInstance {
+    pub position_scale: [f32; 4], // Позиция основания и масштаб
+    pub params: [f32; 4],         // Фаза ветра, гибкость, тип (0 - трава, 1 - дерево)
+    pub color: [f32; 4],
 }
 
-impl<'window> RenderSystem<'window> {
-    pub async fn new(window: &'window Window) -> Self {
-        let size = window.inner_size();
-
-        // Инициализация wgpu
-        let instance = Instance::new(&InstanceDescriptor::default());
-        
-        let surface = instance.create_surface(window).unwrap();
-        
-        let adapter = instance
-            .request_adapter(&RequestAdapterOptions {
-                power_preference: PowerPreference::HighPerformance,
-                compatible_surface: Some(&surface),
-                force_fallback_adapter: false,
-            })
-            .await
-            .unwrap();
-        
-        let (device, queue) = adapter
-            .request_device(
-                &DeviceDescriptor {
-                    label: None,
-                    required_features: Features::empty(),
-                    required_limits: Limits::default(),
-                    memory_hints: MemoryHints::default(),
-                    trace: Trace::default(),
+impl VegetationInstance {
+    fn desc() -> VertexBufferLayout<'static> {
+        VertexBufferLayout {
+            array_stride: std::mem::size_of::<VegetationInstance>() as BufferAddress,
+            step_mode: VertexStepMode::Instance,
+            attributes: &[
+                VertexAttribute {
+                    offset: 0,
+                    shader_location: 3,
+                    format: VertexFormat::Float32x4,
                 },
-            )
-            .await
-            .unwrap();
-        
-        let surface_caps = surface.get_capabilities(&adapter);
-        let surface_format = surface_caps
-            .formats
-            .iter()
-            .copied()
-            .find(|f| f.is_srgb())
-            .unwrap_or(surface_caps.formats[0]);
-        
-        let config = SurfaceConfiguration {
-            usage: TextureUsages::RENDER_ATTACHMENT,
-            format: surface_format,
-            width: size.width,
-            height: size.height,
-            present_mode: surface_caps.present_modes[0],
-            alpha_mode: surface_caps.alpha_modes[0],
-            view_formats: vec![],
-            desired_maximum_frame_latency: 2,
-        };
-        
-        surface.configure(&device, &config);
-
-        // Создаем пайплайн для рендеринга (упрощенный для примера)
-        let shader = device.create_shader_module(ShaderModuleDescriptor {
-            label: Some("Shader"),
-            source: ShaderSource::Wgsl(include_str!("../../assets/shaders/shader.wgsl").into()),
-        });
-
-        // Создаем bind group layout для камеры
-        let camera_bind_group_layout = device.create_bind_group_layout(&BindGroupLayoutDescriptor {
-            label: Some("Camera Bind Group Layout"),
-            entries: &[
-                BindGroupLayoutEntry {
-                    binding: 0,
-                    visibility: ShaderStages::VERTEX | ShaderStages::FRAGMENT,
-                    ty: BindingType::Buffer {
-                        ty: BufferBindingType::Uniform,
-                        has_dynamic_offset: false,
-                        min_binding_size: None,
-                    },
-                    count: None,
-                }
-            ],
-        });
-        
-        // Создаем bind group layout для модели и материала
-        let model_bind_group_layout = device.create_bind_group_layout(&BindGroupLayoutDescriptor {
-            label: Some("Model Bind Group Layout"),
-            entries: &[
-                // Матрица модели
-                BindGroupLayoutEntry {
-                    binding: 0,
-                    visibility: ShaderStages::VERTEX,
-                    ty: BindingType::Buffer {
-                        ty: BufferBindingType::Uniform,
-                        has_dynamic_offset: false,
-                        min_binding_size: None,
-                    },
-                    count: None,
+                VertexAttribute {
+                    offset: std::mem::size_of::<[f32; 4]>() as BufferAddress,
+                    shader_location: 4,
+                    format: VertexFormat::Float32x4,
                 },
-                // Материал
-                BindGroupLayoutEntry {
-                    binding: 1,
-                    visibility: ShaderStages::FRAGMENT,
-                    ty: BindingType::Buffer {
-                        ty: BufferBindingType::Uniform,
-                        has_dynamic_offset: false,
-                        min_binding_size: None,
-                    },
-                    count: None,
+                VertexAttribute {
+                    offset: std::mem::size_of::<[f32; 8]>() as BufferAddress,
+                    shader_location: 5,
+                    format: VertexFormat::Float32x4,
                 },
-                // Текстура
-                BindGroupLayoutEntry {
-                    binding: 2,
-                    visibility: ShaderStages::FRAGMENT,
-                    ty: BindingType::Texture {
-                        sample_type: TextureSampleType::Float { filterable: true },
-                        view_dimension: TextureViewDimension::D2,
-                        multisampled: false,
-                    },
-                    count: None,
+            ],
+        }
+    }
+}
+
+/// Ресурс с растительностью для отрисовки и параметрами ветра
+pub struct VegetationRenderData {
+    pub instances: Vec<VegetationInstance>,
+    pub revision: u64, // Увеличивается при изменении набора экземпляров
+    pub wind_direction: [f32; 2],
+    pub wind_strength: f32,
+    pub time: f32,
+}
+
+/// Данные экземпляра частицы для инстансинга
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct ParticleInstance {
+    pub position_size: [f32; 4], // Позиция центра и радиус
+    pub color: [f32; 4],         // Цвет и непрозрачность
+    pub params: [f32; 4],        // x: 1 - аддитивное свечение (искры), 0 - обычное смешивание
+}
+
+impl ParticleInstance {
+    fn desc() -> VertexBufferLayout<'static> {
+        VertexBufferLayout {
+            array_stride: std::mem::size_of::<ParticleInstance>() as BufferAddress,
+            step_mode: VertexStepMode::Instance,
+            attributes: &[
+                VertexAttribute {
+                    offset: 0,
+                    shader_location: 3,
+                    format: VertexFormat::Float32x4,
                 },
-                // Сэмплер
-                BindGroupLayoutEntry {
-                    binding: 3,
-                    visibility: ShaderStages::FRAGMENT,
-                    ty: BindingType::Sampler(SamplerBindingType::Filtering),
-                    count: None,
+                VertexAttribute {
+                    offset: std::mem::size_of::<[f32; 4]>() as BufferAddress,
+                    shader_location: 4,
+                    format: VertexFormat::Float32x4,
                 },
-            ],
-        });
-        
-        // Создаем bind group layout для источника света
-        let light_bind_group_layout = device.create_bind_group_layout(&BindGroupLayoutDescriptor {
-            label: Some("Light Bind Group Layout"),
-            entries: &[
-                BindGroupLayoutEntry {
-                    binding: 0,
-                    visibility: ShaderStages::FRAGMENT,
-                    ty: BindingType::Buffer {
-                        ty: BufferBindingType::Uniform,
-                        has_dynamic_offset: false,
-                        min_binding_size: None,
-                    },
-                    count: None,
+                VertexAttribute {
+                    offset: std::mem::size_of::<[f32; 8]>() as BufferAddress,
+                    shader_location: 5,
+                    format: VertexFormat::Float32x4,
                 },
             ],
-        });
+        }
+    }
+}
+
+/// Ресурс с живыми частицами, обновляется каждый кадр
+pub struct ParticleRenderData {
+    pub instances: Vec<ParticleInstance>,
+}
+
+/// Вершина отладочной линии
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct DebugLineVertex {
+    pub position: [f32; 3],
+    pub color: [f32; 4],
+}
+
+impl DebugLineVertex {
+    fn desc() -> VertexBufferLayout<'static> {
+        VertexBufferLayout {
+            array_stride: std::mem::size_of::<DebugLineVertex>() as BufferAddress,
+            step_mode: VertexStepMode::Vertex,
+            attributes: &[
+                VertexAttribute { offset: 0, shader_location: 0, format: VertexFormat::Float32x3 },
+                VertexAttribute { offset: 12, shader_location: 1, format: VertexFormat::Float32x4 },
+            ],
+        }
+    }
+}
+
+/// Отрезки одного слоя отладочной отрисовки
+#[derive(Debug, Clone, Default)]
+pub struct DebugLines {
+    vertices: Vec<DebugLineVertex>,
+}
+
+impl DebugLines {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.vertices.is_empty()
+    }
+
+    pub fn line(&mut self, start: Vec3, end: Vec3, color: [f32; 4]) {
+        self.vertices.push(DebugLineVertex { position: start.to_array(), color });
+        self.vertices.push(DebugLineVertex { position: end.to_array(), color });
+    }
+
+    /// Отрезок из точки вдоль вектора
+    pub fn ray(&mut self, origin: Vec3, vector: Vec3, color: [f32; 4]) {
+        self.line(origin, origin + vector, color);
+    }
+
+    /// Крестик из трех осей
+    pub fn cross(&mut self, point: Vec3, size: f32, color: [f32; 4]) {
+        for axis in [Vec3::X, Vec3::Y, Vec3::Z] {
+            self.line(point - axis * size, point + axis * size, color);
+        }
+    }
+
+    /// Ребра повернутого параллелепипеда
+    pub fn oriented_box(&mut self, center: Vec3, rotation: Quat, half_extents: Vec3, color: [f32; 4]) {
+        let corner = |i: usize| {
+            let sign = Vec3::new(
+                if i & 1 == 0 { -1.0 } else { 1.0 },
+                if i & 2 == 0 { -1.0 } else { 1.0 },
+                if i & 4 == 0 { -1.0 } else { 1.0 },
+            );
+            center + rotation * (half_extents * sign)
+        };
+        // Ребро соединяет углы, отличающиеся одним битом
+        for i in 0..8 {
+            for bit in [1, 2, 4] {
+                if i & bit == 0 {
+                    self.line(corner(i), corner(i | bit), color);
+                }
+            }
+        }
+    }
+
+    /// Окружность в плоскости с заданной нормалью
+    pub fn circle(&mut self, center: Vec3, normal: Vec3, radius: f32, color: [f32; 4]) {
+        const SEGMENTS: usize = 24;
+        let normal = normal.normalize_or_zero();
+        let tangent = normal.any_orthonormal_vector() * radius;
+        let bitangent = normal.cross(tangent);
+        let point = |i: usize| {
+            let angle = i as f32 / SEGMENTS as f32 * std::f32::consts::TAU;
+            center + tangent * angle.cos() + bitangent * angle.sin()
+        };
+        for i in 0..SEGMENTS {
+            self.line(point(i), point(i + 1), color);
+        }
+    }
+}
+
+/// Отладочная отрисовка линиями поверх сцены. Каждая система пишет свой слой
+/// и заменяет его целиком, поэтому очищать буфер между кадрами не нужно
+#[derive(Debug, Clone, Default)]
+pub struct DebugDraw {
+    layers: std::collections::BTreeMap<&'static str, DebugLines>,
+}
+
+impl DebugDraw {
+    pub fn set_layer(&mut self, layer: &'static str, lines: DebugLines) {
+        if lines.is_empty() {
+            self.layers.remove(layer);
+        } else {
+            self.layers.insert(layer, lines);
+        }
+    }
+
+    pub fn clear_layer(&mut self, layer: &'static str) {
+        self.layers.remove(layer);
+    }
+
+    /// Вершины всех слоев подряд, по две на отрезок
+    pub fn vertices(&self) -> Vec<DebugLineVertex> {
+        self.layers.values().flat_map(|lines| lines.vertices.iter().copied()).collect()
+    }
+}
+
+/// Форма метки в мире
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WorldMarkerShape {
+    Plate = 0, // Подложка таблички с именем
+    Arrow = 1, // Стрелка вниз
+    Diamond = 2,
+}
+
+/// Данные экземпляра метки в мире; рисуется билбордом с тестом глубины
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct WorldMarkerInstance {
+    pub position_size: [f32; 4], // Позиция центра и половина высоты
+    pub color: [f32; 4],         // Цвет и непрозрачность
+    pub params: [f32; 4],        // x: форма, y: отношение ширины к высоте
+}
+
+impl WorldMarkerInstance {
+    pub fn new(position: Vec3, half_height: f32, color: [f32; 4], shape: WorldMarkerShape, aspect: f32) -> Self {
+        Self {
+            position_size: [position.x, position.y, position.z, half_height],
+            color,
+            params: [shape as u32 as f32, aspect, 0.0, 0.0],
+        }
+    }
+
+    fn desc() -> VertexBufferLayout<'static> {
+        VertexBufferLayout {
+            array_stride: std::mem::size_of::<WorldMarkerInstance>() as BufferAddress,
+            step_mode: VertexStepMode::Instance,
+            attributes: &[
+                VertexAttribute { offset: 0, shader_location: 3, format: VertexFormat::Float32x4 },
+                VertexAttribute { offset: 16, shader_location: 4, format: VertexFormat::Float32x4 },
+                VertexAttribute { offset: 32, shader_location: 5, format: VertexFormat::Float32x4 },
+            ],
+        }
+    }
+}
+
+/// Ресурс с метками в мире, обновляется каждый кадр системой интерфейса
+pub struct WorldMarkerRenderData {
+    pub instances: Vec<WorldMarkerInstance>,
+}
+
+/// Экземпляр пятна тени под объектом (прямоугольник на земле с мягкими краями)
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct BlobShadowInstance {
+    pub center_opacity: [f32; 4], // Центр на земле и непрозрачность
+    pub axis_x: [f32; 4],         // Половина ширины вдоль земли
+    pub axis_z: [f32; 4],         // Половина длины вдоль земли
+}
+
+impl BlobShadowInstance {
+    fn desc() -> VertexBufferLayout<'static> {
+        VertexBufferLayout {
+            array_stride: std::mem::size_of::<BlobShadowInstance>() as BufferAddress,
+            step_mode: VertexStepMode::Instance,
+            attributes: &[
+                VertexAttribute { offset: 0, shader_location: 3, format: VertexFormat::Float32x4 },
+                VertexAttribute { offset: 16, shader_location: 4, format: VertexFormat::Float32x4 },
+                VertexAttribute { offset: 32, shader_location: 5, format: VertexFormat::Float32x4 },
+            ],
+        }
+    }
+}
+
+/// Ресурс с пятнами теней, обновляется каждый кадр
+pub struct BlobShadowRenderData {
+    pub instances: Vec<BlobShadowInstance>,
+}
+
+/// Параметры дистанционного тумана (заполняются системой погоды)
+#[derive(Debug, Clone, Copy)]
+pub struct FogSettings {
+    pub color: [f32; 3],
+    pub density: f32,        // Экспоненциальная плотность на метр
+    pub height_falloff: f32, // Насколько быстро туман редеет с высотой
+    pub base_height: f32,    // Высота, ниже которой туман максимален
+}
+
+impl Default for FogSettings {
+    fn default() -> Self {
+        Self {
+            color: [0.7, 0.75, 0.8],
+            density: 0.0,
+            height_falloff: 0.05,
+            base_height: 0.0,
+        }
+    }
+}
+
+/// Основной направленный свет (солнце или луна), задается временем суток
+#[derive(Debug, Clone, Copy)]
+pub struct SunLight {
+    pub direction: Vec3, // Направление на источник света
+    pub color: [f32; 3],
+    pub intensity: f32,
+}
+
+impl Default for SunLight {
+    fn default() -> Self {
+        Self {
+            direction: Vec3::new(0.0, 1.0, -1.0).normalize(),
+            color: [1.0, 1.0, 1.0],
+            intensity: 1.0,
+        }
+    }
+}
+
+/// Компонент прожектора (фары); направлен вдоль оси Z трансформации
+#[derive(Debug, Clone, Copy)]
+pub struct SpotLightComponent {
+    pub color: [f32; 3],
+    pub intensity: f32,
+    pub range: f32,
+    pub inner_angle: f32,      // Радианы, полная яркость
+    pub outer_angle: f32,      // Радианы, граница светового пятна
+    pub cone_visibility: f32,  // Яркость видимого конуса в воздухе
+    pub enabled: bool,
+}
+
+impl Default for SpotLightComponent {
+    fn default() -> Self {
+        Self {
+            color: [1.0, 0.95, 0.85],
+            intensity: 3.0,
+            range: 40.0,
+            inner_angle: 0.2,
+            outer_angle: 0.4,
+            cone_visibility: 0.15,
+            enabled: true,
+        }
+    }
+}
+
+/// Максимальное число прожекторов, учитываемых в шейдере
+pub const MAX_SPOT_LIGHTS: usize = 8;
+
+/// Параметры дождя: мокрое покрытие и капли на экране
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RainSettings {
+    pub surface_wetness: f32, // 0 - сухо, 1 - лужи
+    pub screen_droplets: f32, // Интенсивность капель на экране, 0 - выключено
+    pub time: f32,
+}
+
+/// Режим экспозиции HDR-кадра
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExposureMode {
+    Auto,
+    Manual,
+}
+
+/// Параметры экспозиции перед тональной компрессией
+#[derive(Debug, Clone, Copy)]
+pub struct ExposureSettings {
+    pub mode: ExposureMode,
+    pub manual_ev: f32,        // Поправка экспозиции в ступенях для ручного режима
+    pub key_value: f32,        // Целевая средняя яркость в автоматическом режиме
+    pub min_luminance: f32,    // Пределы адаптации (ночь и яркое солнце)
+    pub max_luminance: f32,
+    pub adaptation_speed: f32, // Скорость привыкания глаза, 1/с
+}
+
+impl Default for ExposureSettings {
+    fn default() -> Self {
+        Self {
+            mode: ExposureMode::Auto,
+            manual_ev: 0.0,
+            key_value: 0.18,
+            min_luminance: 0.03,
+            max_luminance: 8.0,
+            adaptation_speed: 1.5,
+        }
+    }
+}
+
+/// Файл настроек графики; создается автоопределением при первом запуске
+pub const GRAPHICS_SETTINGS_PATH: &str = "config/graphics.json";
+
+/// Бюджет кадра, под который подбирается качество, с
+const TARGET_FRAME_TIME: f32 = 1.0 / 60.0;
+
+/// Запас по времени кадра, который оставляет автоопределение
+const FRAME_HEADROOM: f32 = 1.2;
+
+const MIN_RESOLUTION_SCALE: f32 = 0.5;
+
+/// Набор графических эффектов
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GraphicsPreset {
+    Low,
+    Medium,
+    High,
+}
+
+impl GraphicsPreset {
+    pub fn vegetation(self) -> bool {
+        self != GraphicsPreset::Low
+    }
+
+    pub fn blob_shadows(self) -> bool {
+        self != GraphicsPreset::Low
+    }
+
+    pub fn light_cones(self) -> bool {
+        self == GraphicsPreset::High
+    }
+}
+
+/// Настройки графики, сохраняемые между запусками
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GraphicsSettings {
+    pub preset: GraphicsPreset,
+    pub resolution_scale: f32, // Доля разрешения окна для сцены; интерфейс и тональная компрессия в полном
+    pub auto_detected: bool,   // Подобрано замером при первом запуске
+    #[serde(default)]
+    pub exposure_ev: Option<f32>, // Ручная экспозиция в ступенях; без нее яркость подстраивается сама
+}
+
+impl Default for GraphicsSettings {
+    fn default() -> Self {
+        Self {
+            preset: GraphicsPreset::High,
+            resolution_scale: 1.0,
+            auto_detected: false,
+            exposure_ev: None,
+        }
+    }
+}
+
+impl GraphicsSettings {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+        serde_json::from_str(&text).map_err(|e| e.to_string())
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        let text = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        std::fs::write(path, text).map_err(|e| e.to_string())
+    }
+
+    /// Экспозиция кадра: ручная, если в настройках задана поправка, иначе автоматическая
+    pub fn exposure(&self) -> ExposureSettings {
+        match self.exposure_ev {
+            Some(manual_ev) => ExposureSettings {
+                mode: ExposureMode::Manual,
+                manual_ev,
+                ..Default::default()
+            },
+            None => ExposureSettings::default(),
+        }
+    }
+
+    /// Качество по замеру на высоком наборе в полном разрешении:
+    /// набор эффектов по общему времени кадра, масштаб разрешения по времени GPU
+    pub fn from_benchmark(result: &BenchmarkResult) -> Self {
+        let frame_time = result.cpu_frame_time.max(result.gpu_frame_time);
+        let headroom = TARGET_FRAME_TIME / frame_time.max(f32::EPSILON);
+        let preset = if headroom >= FRAME_HEADROOM * 1.5 {
+            GraphicsPreset::High
+        } else if headroom >= FRAME_HEADROOM {
+            GraphicsPreset::Medium
+        } else {
+            GraphicsPreset::Low
+        };
+
+        // Время GPU растет с числом пикселей, поэтому масштаб берется по корню
+        let gpu_headroom = TARGET_FRAME_TIME / (result.gpu_frame_time * FRAME_HEADROOM).max(f32::EPSILON);
+        let scale = gpu_headroom.sqrt().clamp(MIN_RESOLUTION_SCALE, 1.0);
+        Self {
+            preset,
+            resolution_scale: (scale * 20.0).floor() / 20.0,
+            auto_detected: true,
+            exposure_ev: None,
+        }
+    }
+}
+
+/// Итог замера производительности; средние времена кадра, с
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BenchmarkResult {
+    pub frames: u32,
+    pub cpu_frame_time: f32, // Подготовка и отправка кадра
+    pub gpu_frame_time: f32, // Ожидание выполнения кадра на GPU
+}
+
+/// Статистика последнего отрисованного кадра
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RenderStats {
+    pub draw_calls: u32,
+    pub visible_objects: u32, // Объекты сцены, прошедшие отбор видимости
+}
+
+/// Система рендеринга
+pub struct RenderSystem<'window> {
+    instance: Instance,
+    surface: Option<Surface<'window>>,
+    device: Device,
+    queue: Queue,
+    config: SurfaceConfiguration,
+    pipeline: RenderPipeline,
+    depth_texture: Option<Texture>,
+    hdr_texture: Texture,
+    exposure_pipeline: ComputePipeline,
+    exposure_bind_group_layout: BindGroupLayout,
+    exposure_bind_group: BindGroup,
+    exposure_params_buffer: Buffer,
+    exposure_state_buffer: Buffer,
+    tonemap_pipeline: RenderPipeline,
+    tonemap_bind_group_layout: BindGroupLayout,
+    tonemap_bind_group: BindGroup,
+    camera_bind_group: BindGroup,
+    model_bind_group_layout: BindGroupLayout,
+    model_bind_group: BindGroup,
+    light_bind_group: BindGroup,
+    default_texture_view: TextureView,
+    sampler: Sampler,
+    terrain_textures: TerrainTextures,
+    terrain_splat_uploaded: bool,
+    meshes: Vec<Mesh>,
+    object_uniform_stride: u64, // Шаг слотов объектов с учетом выравнивания динамических смещений
+    object_capacity: usize,
+    object_draw_count: u32,
+    camera_buffer: Buffer,
+    light_buffer: Buffer,
+    spot_light_buffer: Buffer,
+    light_cone_pipeline: RenderPipeline,
+    light_cone_vertex_buffer: Buffer,
+    light_cone_vertex_count: u32,
+    light_cone_instance_buffer: Buffer,
+    light_cone_instance_count: u32,
+    model_buffer: Buffer,
+    material_buffer: Buffer,
+    fog_buffer: Buffer,
+    rain_buffer: Buffer,
+    rain_overlay_pipeline: RenderPipeline,
+    rain_overlay_bind_group: BindGroup,
+    screen_droplets: f32,
+    clear_color: Color,
+    vegetation_pipeline: RenderPipeline,
+    vegetation_wind_buffer: Buffer,
+    vegetation_wind_bind_group: BindGroup,
+    vegetation_quad_buffer: Buffer,
+    vegetation_instance_buffer: Option<Buffer>,
+    vegetation_instance_count: u32,
+    vegetation_revision: Option<u64>,
+    blob_shadow_pipeline: RenderPipeline,
+    blob_shadow_instance_buffer: Option<Buffer>,
+    blob_shadow_instance_capacity: usize,
+    blob_shadow_instance_count: u32,
+    particle_pipeline: RenderPipeline,
+    particle_instance_buffer: Option<Buffer>,
+    particle_instance_capacity: usize,
+    particle_instance_count: u32,
+    world_marker_pipeline: RenderPipeline,
+    world_marker_instance_buffer: Option<Buffer>,
+    world_marker_instance_capacity: usize,
+    world_marker_instance_count: u32,
+    debug_line_pipeline: RenderPipeline,
+    debug_line_buffer: Option<Buffer>,
+    debug_line_capacity: usize,
+    debug_line_vertex_count: u32,
+    glyph_atlas: GlyphAtlas,
+    hud_text_pipeline: RenderPipeline,
+    hud_text_bind_group: BindGroup,
+    hud_text_screen_buffer: Buffer,
+    hud_text_instance_buffer: Option<Buffer>,
+    hud_text_instance_capacity: usize,
+    hud_text_instance_count: u32,
+    capture_pending: bool,
+    stats: RenderStats,
+    graphics: GraphicsSettings,
+}
+
+/// Цель отрисовки в отладочном описании кадра
+#[derive(Debug, Clone)]
+pub struct FrameGraphTarget {
+    pub name: &'static str,
+    pub width: u32,
+    pub height: u32,
+    pub format: TextureFormat,
+}
+
+/// Вызов отрисовки внутри прохода
+#[derive(Debug, Clone)]
+pub struct FrameGraphDraw {
+    pub pipeline: &'static str,
+    pub vertices: u32,
+    pub instances: u32,
+    pub enabled: bool, // Пропускается в текущем кадре
+}
+
+/// Проход рендеринга в отладочном описании кадра
+#[derive(Debug, Clone)]
+pub struct FrameGraphPass {
+    pub name: &'static str,
+    pub color_targets: Vec<FrameGraphTarget>,
+    pub depth_target: Option<FrameGraphTarget>,
+    pub draws: Vec<FrameGraphDraw>,
+}
+
+impl<'window> RenderSystem<'window> {
+    pub async fn new(window: &'window Window) -> Self {
+        let size = window.inner_size();
+
+        // Инициализация wgpu
+        let instance = Instance::new(&InstanceDescriptor::default());
+        
+        let surface = instance.create_surface(window).unwrap();
+        
+        let adapter = instance
+            .request_adapter(&RequestAdapterOptions {
+                power_preference: PowerPreference::HighPerformance,
+                compatible_surface: Some(&surface),
+                force_fallback_adapter: false,
+            })
+            .await
+            .unwrap();
+        
+        let (device, queue) = adapter
+            .request_device(
+                &DeviceDescriptor {
+                    label: None,
+                    required_features: Features::empty(),
+                    required_limits: Limits::default(),
+                    memory_hints: MemoryHints::default(),
+                    trace: Trace::default(),
+                },
+            )
+            .await
+            .unwrap();
+        
+        let surface_caps = surface.get_capabilities(&adapter);
+        let surface_format = surface_caps
+            .formats
+            .iter()
+            .copied()
+            .find(|f| f.is_srgb())
+            .unwrap_or(surface_caps.formats[0]);
+        
+        let config = SurfaceConfiguration {
+            usage: TextureUsages::RENDER_ATTACHMENT,
+            format: surface_format,
+            width: size.width,
+            height: size.height,
+            present_mode: surface_caps.present_modes[0],
+            alpha_mode: surface_caps.alpha_modes[0],
+            view_formats: vec![],
+            desired_maximum_frame_latency: 2,
+        };
+        
+        surface.configure(&device, &config);
+
+        // Создаем пайплайн для рендеринга (упрощенный для примера)
+        let shader = device.create_shader_module(ShaderModuleDescriptor {
+            label: Some("Shader"),
+            source: ShaderSource::Wgsl(include_str!("../../assets/shaders/shader.wgsl").into()),
+        });
+
+        // Создаем bind group layout для камеры
+        let camera_bind_group_layout = device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+            label: Some("Camera Bind Group Layout"),
+            entries: &[
+                BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: ShaderStages::VERTEX | ShaderStages::FRAGMENT,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                }
+            ],
+        });
+        
+        // Создаем bind group layout для модели и материала
+        let model_bind_group_layout = device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+            label: Some("Model Bind Group Layout"),
+            entries: &[
+                // Матрица модели (слот объекта выбирается динамическим смещением)
+                BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: ShaderStages::VERTEX,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Uniform,
+                        has_dynamic_offset: true,
+                        min_binding_size: BufferSize::new(std::mem::size_of::<ModelUniform>() as u64),
+                    },
+                    count: None,
+                },
+                // Материал (тот же слот объекта)
+                BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Uniform,
+                        has_dynamic_offset: true,
+                        min_binding_size: BufferSize::new(std::mem::size_of::<MaterialUniform>() as u64),
+                    },
+                    count: None,
+                },
+                // Текстура
+                BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Texture {
+                        sample_type: TextureSampleType::Float { filterable: true },
+                        view_dimension: TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                // Сэмплер
+                BindGroupLayoutEntry {
+                    binding: 3,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Sampler(SamplerBindingType::Filtering),
+                    count: None,
+                },
+                // Карта смешивания слоев ландшафта
+                BindGroupLayoutEntry {
+                    binding: 4,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Texture {
+                        sample_type: TextureSampleType::Float { filterable: true },
+                        view_dimension: TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                // Детальные текстуры слоев ландшафта
+                BindGroupLayoutEntry {
+                    binding: 5,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Texture {
+                        sample_type: TextureSampleType::Float { filterable: true },
+                        view_dimension: TextureViewDimension::D2Array,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                // Сэмплер ландшафта: линейный, с повтором
+                BindGroupLayoutEntry {
+                    binding: 6,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Sampler(SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+        });
+        
+        // Создаем bind group layout для источника света
+        let light_bind_group_layout = device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+            label: Some("Light Bind Group Layout"),
+            entries: &[
+                BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                // Туман
+                BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                // Дождь (мокрое покрытие)
+                BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                // Прожекторы (фары)
+                BindGroupLayoutEntry {
+                    binding: 3,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+        });
+
+        // Упрощенная настройка пайплайна
+        let pipeline_layout = device.create_pipeline_layout(&PipelineLayoutDescriptor {
+            label: Some("Render Pipeline Layout"),
+            bind_group_layouts: &[
+                &camera_bind_group_layout,
+                &model_bind_group_layout,
+                &light_bind_group_layout
+            ],
+            push_constant_ranges: &[],
+        });
+
+        // Создаем буфер для данных камеры
+        let camera_uniform = CameraUniform::new();
+        let camera_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Camera Buffer"),
+            contents: bytemuck::cast_slice(&[camera_uniform]),
+            usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
+        });
+
+        // Буферы матриц моделей и материалов: по слоту на объект, выбор слота динамическим смещением
+        let alignment = device.limits().min_uniform_buffer_offset_alignment as u64;
+        let object_size = std::mem::size_of::<ModelUniform>().max(std::mem::size_of::<MaterialUniform>()) as u64;
+        let object_uniform_stride = object_size.div_ceil(alignment) * alignment;
+        let object_capacity = INITIAL_OBJECT_CAPACITY;
+        let (model_buffer, material_buffer) = create_object_buffers(&device, object_uniform_stride, object_capacity);
+        
+        // Создаем буфер для источника света
+        let light_uniform = LightUniform::new();
+        let light_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Light Buffer"),
+            contents: bytemuck::cast_slice(&[light_uniform]),
+            usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
+        });
+        
+        // Создаем буфер для параметров тумана
+        let fog_uniform = FogUniform::from(FogSettings::default());
+        let fog_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Fog Buffer"),
+            contents: bytemuck::cast_slice(&[fog_uniform]),
+            usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
+        });
+        
+        // Создаем буфер для прожекторов
+        let spot_light_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Spot Light Buffer"),
+            contents: bytemuck::cast_slice(&[SpotLightsUniform::new()]),
+            usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
+        });
+        
+        // Создаем буфер для параметров дождя
+        let rain_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Rain Buffer"),
+            contents: bytemuck::cast_slice(&[RainUniform::new()]),
+            usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
+        });
+        
+        // Создаем временную (заглушку) текстуру 1x1
+        let temp_texture = device.create_texture(&TextureDescriptor {
+            label: Some("Temp Texture"),
+            size: Extent3d {
+                width: 1,
+                height: 1,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: TextureDimension::D2,
+            format: TextureFormat::Rgba8UnormSrgb,
+            usage: TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST,
+            view_formats: &[],
+        });
+        
+        let temp_texture_view = temp_texture.create_view(&TextureViewDescriptor::default());
+        
+        // Создаем сэмплер
+        let sampler = device.create_sampler(&SamplerDescriptor {
+            label: Some("Texture Sampler"),
+            address_mode_u: AddressMode::ClampToEdge,
+            address_mode_v: AddressMode::ClampToEdge,
+            address_mode_w: AddressMode::ClampToEdge,
+            mag_filter: FilterMode::Nearest, // Используем Nearest для более четких текстур
+            min_filter: FilterMode::Nearest,
+            mipmap_filter: FilterMode::Nearest,
+            ..Default::default()
+        });
+
+        // Белый пиксель не искажает цвет материала
+        let white_pixel: [u8; 4] = [255, 255, 255, 255];
+        queue.write_texture(
+            ImageCopyTexture {
+                texture: &temp_texture,
+                mip_level: 0,
+                origin: Origin3d::ZERO,
+                aspect: TextureAspect::All,
+            },
+            &white_pixel,
+            ImageDataLayout {
+                offset: 0,
+                bytes_per_row: Some(4),
+                rows_per_image: Some(1),
+            },
+            Extent3d {
+                width: 1,
+                height: 1,
+                depth_or_array_layers: 1,
+            },
+        );
+
+        // Создаем bind group для камеры
+        let camera_bind_group = device.create_bind_group(&BindGroupDescriptor {
+            layout: &camera_bind_group_layout,
+            entries: &[
+                BindGroupEntry {
+                    binding: 0,
+                    resource: camera_buffer.as_entire_binding(),
+                }
+            ],
+            label: Some("camera_bind_group"),
+        });
+        
+        // Создаем bind group для модели/материала; текстуры ландшафта появятся вместе с ним
+        let terrain_textures = create_terrain_textures(&device, &queue, None);
+        let model_bind_group = create_model_bind_group(
+            &device,
+            &model_bind_group_layout,
+            &model_buffer,
+            &material_buffer,
+            &temp_texture_view,
+            &sampler,
+            &terrain_textures,
+        );
+        
+        // Создаем bind group для источника света (заглушка)
+        let light_bind_group = device.create_bind_group(&BindGroupDescriptor {
+            layout: &light_bind_group_layout,
+            entries: &[
+                BindGroupEntry {
+                    binding: 0,
+                    resource: light_buffer.as_entire_binding(),
+                },
+                BindGroupEntry {
+                    binding: 1,
+                    resource: fog_buffer.as_entire_binding(),
+                },
+                BindGroupEntry {
+                    binding: 2,
+                    resource: rain_buffer.as_entire_binding(),
+                },
+                BindGroupEntry {
+                    binding: 3,
+                    resource: spot_light_buffer.as_entire_binding(),
+                },
+            ],
+            label: Some("light_bind_group"),
+        });
+
+        let pipeline = device.create_render_pipeline(&RenderPipelineDescriptor {
+            label: Some("Render Pipeline"),
+            layout: Some(&pipeline_layout),
+            vertex: VertexState {
+                module: &shader,
+                entry_point: Some("vs_main"),
+                buffers: &[Vertex::desc()],
+                compilation_options: Default::default(),
+            },
+            fragment: Some(FragmentState {
+                module: &shader,
+                entry_point: Some("fs_unlit"), // Используем упрощенный шейдер без освещения
+                targets: &[Some(ColorTargetState {
+                    format: HDR_FORMAT,
+                    blend: Some(BlendState::ALPHA_BLENDING), // Полупрозрачные объекты (кузов у камеры)
+                    write_mask: ColorWrites::ALL,
+                })],
+                compilation_options: Default::default(),
+            }),
+            primitive: PrimitiveState {
+                topology: PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+                front_face: FrontFace::Ccw,
+                cull_mode: None, // Отключаем culling для отображения всех граней
+                polygon_mode: PolygonMode::Fill,
+                unclipped_depth: false,
+                conservative: false,
+            },
+            depth_stencil: Some(depth_state(true, CompareFunction::Less)),
+            multisample: MultisampleState {
+                count: 1,
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+            multiview: None,
+            cache: Default::default(),
+        });
+
+        // Пайплайн инстансинга растительности
+        let vegetation_shader = device.create_shader_module(ShaderModuleDescriptor {
+            label: Some("Vegetation Shader"),
+            source: ShaderSource::Wgsl(include_str!("../../assets/shaders/vegetation.wgsl").into()),
+        });
+
+        let vegetation_wind_bind_group_layout = device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+            label: Some("Vegetation Wind Bind Group Layout"),
+            entries: &[
+                BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: ShaderStages::VERTEX,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                // Туман (общий буфер с основным пайплайном)
+                BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+        });
+
+        let vegetation_wind_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Vegetation Wind Buffer"),
+            contents: bytemuck::cast_slice(&[WindUniform::new()]),
+            usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
+        });
+
+        let vegetation_wind_bind_group = device.create_bind_group(&BindGroupDescriptor {
+            layout: &vegetation_wind_bind_group_layout,
+            entries: &[
+                BindGroupEntry {
+                    binding: 0,
+                    resource: vegetation_wind_buffer.as_entire_binding(),
+                },
+                BindGroupEntry {
+                    binding: 1,
+                    resource: fog_buffer.as_entire_binding(),
+                },
+            ],
+            label: Some("vegetation_wind_bind_group"),
+        });
+
+        let vegetation_pipeline_layout = device.create_pipeline_layout(&PipelineLayoutDescriptor {
+            label: Some("Vegetation Pipeline Layout"),
+            bind_group_layouts: &[
+                &camera_bind_group_layout,
+                &vegetation_wind_bind_group_layout,
+            ],
+            push_constant_ranges: &[],
+        });
+
+        let vegetation_pipeline = device.create_render_pipeline(&RenderPipelineDescriptor {
+            label: Some("Vegetation Pipeline"),
+            layout: Some(&vegetation_pipeline_layout),
+            vertex: VertexState {
+                module: &vegetation_shader,
+                entry_point: Some("vs_main"),
+                buffers: &[Vertex::desc(), VegetationInstance::desc()],
+                compilation_options: Default::default(),
+            },
+            fragment: Some(FragmentState {
+                module: &vegetation_shader,
+                entry_point: Some("fs_main"),
+                targets: &[Some(ColorTargetState {
+                    format: HDR_FORMAT,
+                    blend: Some(BlendState::REPLACE),
+                    write_mask: ColorWrites::ALL,
+                })],
+                compilation_options: Default::default(),
+            }),
+            primitive: PrimitiveState {
+                topology: PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+                front_face: FrontFace::Ccw,
+                cull_mode: None, // Билборд виден с обеих сторон
+                polygon_mode: PolygonMode::Fill,
+                unclipped_depth: false,
+                conservative: false,
+            },
+            depth_stencil: Some(depth_state(true, CompareFunction::Less)),
+            multisample: MultisampleState {
+                count: 1,
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+            multiview: None,
+            cache: Default::default(),
+        });
+
+        // Квад билборда: основание в y = 0, верх в y = 1
+        let vegetation_quad = [
+            Vertex { position: [-0.5, 0.0, 0.0], tex_coords: [0.0, 1.0], normal: [0.0, 0.0, 1.0] },
+            Vertex { position: [0.5, 0.0, 0.0], tex_coords: [1.0, 1.0], normal: [0.0, 0.0, 1.0] },
+            Vertex { position: [0.5, 1.0, 0.0], tex_coords: [1.0, 0.0], normal: [0.0, 0.0, 1.0] },
+            Vertex { position: [-0.5, 0.0, 0.0], tex_coords: [0.0, 1.0], normal: [0.0, 0.0, 1.0] },
+            Vertex { position: [0.5, 1.0, 0.0], tex_coords: [1.0, 0.0], normal: [0.0, 0.0, 1.0] },
+            Vertex { position: [-0.5, 1.0, 0.0], tex_coords: [0.0, 0.0], normal: [0.0, 0.0, 1.0] },
+        ];
+
+        let vegetation_quad_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Vegetation Quad Buffer"),
+            contents: bytemuck::cast_slice(&vegetation_quad),
+            usage: wgpu::BufferUsages::VERTEX,
+        });
+
+        // Пятна теней под машинами (затемнение с альфа-смешиванием)
+        let blob_shadow_shader = device.create_shader_module(ShaderModuleDescriptor {
+            label: Some("Blob Shadow Shader"),
+            source: ShaderSource::Wgsl(include_str!("../../assets/shaders/blob_shadow.wgsl").into()),
+        });
+
+        let blob_shadow_pipeline_layout = device.create_pipeline_layout(&PipelineLayoutDescriptor {
+            label: Some("Blob Shadow Pipeline Layout"),
+            bind_group_layouts: &[&camera_bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let blob_shadow_pipeline = device.create_render_pipeline(&RenderPipelineDescriptor {
+            label: Some("Blob Shadow Pipeline"),
+            layout: Some(&blob_shadow_pipeline_layout),
+            vertex: VertexState {
+                module: &blob_shadow_shader,
+                entry_point: Some("vs_main"),
+                buffers: &[Vertex::desc(), BlobShadowInstance::desc()],
+                compilation_options: Default::default(),
+            },
+            fragment: Some(FragmentState {
+                module: &blob_shadow_shader,
+                entry_point: Some("fs_main"),
+                targets: &[Some(ColorTargetState {
+                    format: HDR_FORMAT,
+                    blend: Some(BlendState::ALPHA_BLENDING),
+                    write_mask: ColorWrites::ALL,
+                })],
+                compilation_options: Default::default(),
+            }),
+            primitive: PrimitiveState {
+                topology: PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+                front_face: FrontFace::Ccw,
+                cull_mode: None,
+                polygon_mode: PolygonMode::Fill,
+                unclipped_depth: false,
+                conservative: false,
+            },
+            depth_stencil: Some(depth_state(false, CompareFunction::LessEqual)), // Пятно лежит на земле, глубину не пишет
+            multisample: MultisampleState::default(),
+            multiview: None,
+            cache: Default::default(),
+        });
+
+        // Пайплайн частиц: предумноженная альфа позволяет смешивать
+        // светящиеся (аддитивные) и обычные частицы в одном проходе
+        let particle_shader = device.create_shader_module(ShaderModuleDescriptor {
+            label: Some("Particle Shader"),
+            source: ShaderSource::Wgsl(include_str!("../../assets/shaders/particles.wgsl").into()),
+        });
+
+        let particle_pipeline_layout = device.create_pipeline_layout(&PipelineLayoutDescriptor {
+            label: Some("Particle Pipeline Layout"),
+            bind_group_layouts: &[&camera_bind_group_layout],
+            push_constant_ranges: &[],
+        });
 
-        // Упрощенная настройка пайплайна
-        let pipeline_layout = device.create_pipeline_layout(&PipelineLayoutDescriptor {
-            label: Some("Render Pipeline Layout"),
-            bind_group_layouts: &[
-                &camera_bind_group_layout,
-                &model_bind_group_layout,
-                &light_bind_group_layout
+        let particle_pipeline = device.create_render_pipeline(&RenderPipelineDescriptor {
+            label: Some("Particle Pipeline"),
+            layout: Some(&particle_pipeline_layout),
+            vertex: VertexState {
+                module: &particle_shader,
+                entry_point: Some("vs_main"),
+                buffers: &[Vertex::desc(), ParticleInstance::desc()],
+                compilation_options: Default::default(),
+            },
+            fragment: Some(FragmentState {
+                module: &particle_shader,
+                entry_point: Some("fs_main"),
+                targets: &[Some(ColorTargetState {
+                    format: HDR_FORMAT,
+                    blend: Some(BlendState::PREMULTIPLIED_ALPHA_BLENDING),
+                    write_mask: ColorWrites::ALL,
+                })],
+                compilation_options: Default::default(),
+            }),
+            primitive: PrimitiveState {
+                topology: PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+                front_face: FrontFace::Ccw,
+                cull_mode: None,
+                polygon_mode: PolygonMode::Fill,
+                unclipped_depth: false,
+                conservative: false,
+            },
+            depth_stencil: Some(depth_state(false, CompareFunction::Less)),
+            multisample: MultisampleState {
+                count: 1,
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+            multiview: None,
+            cache: Default::default(),
+        });
+
+        // Метки в мире: глубину проверяют, но не пишут, чтобы не перекрывать друг друга
+        let world_marker_shader = device.create_shader_module(ShaderModuleDescriptor {
+            label: Some("World Marker Shader"),
+            source: ShaderSource::Wgsl(include_str!("../../assets/shaders/world_markers.wgsl").into()),
+        });
+
+        let world_marker_pipeline = device.create_render_pipeline(&RenderPipelineDescriptor {
+            label: Some("World Marker Pipeline"),
+            layout: Some(&particle_pipeline_layout),
+            vertex: VertexState {
+                module: &world_marker_shader,
+                entry_point: Some("vs_main"),
+                buffers: &[Vertex::desc(), WorldMarkerInstance::desc()],
+                compilation_options: Default::default(),
+            },
+            fragment: Some(FragmentState {
+                module: &world_marker_shader,
+                entry_point: Some("fs_main"),
+                targets: &[Some(ColorTargetState {
+                    format: HDR_FORMAT,
+                    blend: Some(BlendState::PREMULTIPLIED_ALPHA_BLENDING),
+                    write_mask: ColorWrites::ALL,
+                })],
+                compilation_options: Default::default(),
+            }),
+            primitive: PrimitiveState {
+                topology: PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+                front_face: FrontFace::Ccw,
+                cull_mode: None,
+                polygon_mode: PolygonMode::Fill,
+                unclipped_depth: false,
+                conservative: false,
+            },
+            depth_stencil: Some(depth_state(false, CompareFunction::LessEqual)),
+            multisample: MultisampleState::default(),
+            multiview: None,
+            cache: Default::default(),
+        });
+
+        // Отладочные линии рисуются поверх геометрии, без теста глубины
+        let debug_line_shader = device.create_shader_module(ShaderModuleDescriptor {
+            label: Some("Debug Line Shader"),
+            source: ShaderSource::Wgsl(include_str!("../../assets/shaders/debug_lines.wgsl").into()),
+        });
+
+        let debug_line_pipeline = device.create_render_pipeline(&RenderPipelineDescriptor {
+            label: Some("Debug Line Pipeline"),
+            layout: Some(&particle_pipeline_layout),
+            vertex: VertexState {
+                module: &debug_line_shader,
+                entry_point: Some("vs_main"),
+                buffers: &[DebugLineVertex::desc()],
+                compilation_options: Default::default(),
+            },
+            fragment: Some(FragmentState {
+                module: &debug_line_shader,
+                entry_point: Some("fs_main"),
+                targets: &[Some(ColorTargetState {
+                    format: HDR_FORMAT,
+                    blend: Some(BlendState::ALPHA_BLENDING),
+                    write_mask: ColorWrites::ALL,
+                })],
+                compilation_options: Default::default(),
+            }),
+            primitive: PrimitiveState {
+                topology: PrimitiveTopology::LineList,
+                strip_index_format: None,
+                front_face: FrontFace::Ccw,
+                cull_mode: None,
+                polygon_mode: PolygonMode::Fill,
+                unclipped_depth: false,
+                conservative: false,
+            },
+            depth_stencil: Some(depth_state(false, CompareFunction::Always)),
+            multisample: MultisampleState::default(),
+            multiview: None,
+            cache: Default::default(),
+        });
+
+        // Полноэкранный проход с каплями дождя
+        let rain_overlay_shader = device.create_shader_module(ShaderModuleDescriptor {
+            label: Some("Rain Overlay Shader"),
+            source: ShaderSource::Wgsl(include_str!("../../assets/shaders/rain_overlay.wgsl").into()),
+        });
+
+        let rain_overlay_bind_group_layout = device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+            label: Some("Rain Overlay Bind Group Layout"),
+            entries: &[
+                BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+        });
+
+        let rain_overlay_bind_group = device.create_bind_group(&BindGroupDescriptor {
+            layout: &rain_overlay_bind_group_layout,
+            entries: &[
+                BindGroupEntry {
+                    binding: 0,
+                    resource: rain_buffer.as_entire_binding(),
+                },
             ],
+            label: Some("rain_overlay_bind_group"),
+        });
+
+        let rain_overlay_pipeline_layout = device.create_pipeline_layout(&PipelineLayoutDescriptor {
+            label: Some("Rain Overlay Pipeline Layout"),
+            bind_group_layouts: &[&rain_overlay_bind_group_layout],
             push_constant_ranges: &[],
         });
 
-        // Создаем буфер для данных камеры
-        let camera_uniform = CameraUniform::new();
-        let camera_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-            label: Some("Camera Buffer"),
-            contents: bytemuck::cast_slice(&[camera_uniform]),
-            usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
+        let rain_overlay_pipeline = device.create_render_pipeline(&RenderPipelineDescriptor {
+            label: Some("Rain Overlay Pipeline"),
+            layout: Some(&rain_overlay_pipeline_layout),
+            vertex: VertexState {
+                module: &rain_overlay_shader,
+                entry_point: Some("vs_main"),
+                buffers: &[],
+                compilation_options: Default::default(),
+            },
+            fragment: Some(FragmentState {
+                module: &rain_overlay_shader,
+                entry_point: Some("fs_main"),
+                targets: &[Some(ColorTargetState {
+                    format: HDR_FORMAT,
+                    blend: Some(BlendState::ALPHA_BLENDING),
+                    write_mask: ColorWrites::ALL,
+                })],
+                compilation_options: Default::default(),
+            }),
+            primitive: PrimitiveState::default(),
+            depth_stencil: Some(depth_state(false, CompareFunction::Always)), // Капли поверх всего кадра
+            multisample: MultisampleState::default(),
+            multiview: None,
+            cache: Default::default(),
         });
 
-        // Создаем буфер для матрицы модели
-        let model_uniform = ModelUniform::new();
-        let model_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-            label: Some("Model Buffer"),
-            contents: bytemuck::cast_slice(&[model_uniform]),
-            usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
+        // Видимые конусы света фар (аддитивно, поверх сцены)
+        let light_cone_shader = device.create_shader_module(ShaderModuleDescriptor {
+            label: Some("Light Cone Shader"),
+            source: ShaderSource::Wgsl(include_str!("../../assets/shaders/light_cones.wgsl").into()),
         });
-        
-        // Создаем буфер для материала
-        let material_uniform = MaterialUniform::new();
-        let material_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-            label: Some("Material Buffer"),
-            contents: bytemuck::cast_slice(&[material_uniform]),
-            usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
+
+        let light_cone_pipeline_layout = device.create_pipeline_layout(&PipelineLayoutDescriptor {
+            label: Some("Light Cone Pipeline Layout"),
+            bind_group_layouts: &[&camera_bind_group_layout],
+            push_constant_ranges: &[],
         });
-        
-        // Создаем буфер для источника света
-        let light_uniform = LightUniform::new();
-        let light_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-            label: Some("Light Buffer"),
-            contents: bytemuck::cast_slice(&[light_uniform]),
+
+        let light_cone_pipeline = device.create_render_pipeline(&RenderPipelineDescriptor {
+            label: Some("Light Cone Pipeline"),
+            layout: Some(&light_cone_pipeline_layout),
+            vertex: VertexState {
+                module: &light_cone_shader,
+                entry_point: Some("vs_main"),
+                buffers: &[Vertex::desc(), LightConeInstance::desc()],
+                compilation_options: Default::default(),
+            },
+            fragment: Some(FragmentState {
+                module: &light_cone_shader,
+                entry_point: Some("fs_main"),
+                targets: &[Some(ColorTargetState {
+                    format: HDR_FORMAT,
+                    blend: Some(BlendState {
+                        color: BlendComponent {
+                            src_factor: BlendFactor::One,
+                            dst_factor: BlendFactor::One,
+                            operation: BlendOperation::Add,
+                        },
+                        alpha: BlendComponent::OVER,
+                    }),
+                    write_mask: ColorWrites::ALL,
+                })],
+                compilation_options: Default::default(),
+            }),
+            primitive: PrimitiveState {
+                topology: PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+                front_face: FrontFace::Ccw,
+                cull_mode: None,
+                polygon_mode: PolygonMode::Fill,
+                unclipped_depth: false,
+                conservative: false,
+            },
+            depth_stencil: Some(depth_state(false, CompareFunction::Less)),
+            multisample: MultisampleState::default(),
+            multiview: None,
+            cache: Default::default(),
+        });
+
+        let light_cone_vertices = build_light_cone_vertices(16);
+        let light_cone_vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Light Cone Vertex Buffer"),
+            contents: bytemuck::cast_slice(&light_cone_vertices),
+            usage: BufferUsages::VERTEX,
+        });
+
+        let light_cone_instance_buffer = device.create_buffer(&BufferDescriptor {
+            label: Some("Light Cone Instance Buffer"),
+            size: (MAX_SPOT_LIGHTS * std::mem::size_of::<LightConeInstance>()) as BufferAddress,
+            usage: BufferUsages::VERTEX | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        // Буфер глубины под размер поверхности
+        let depth_texture = create_depth_texture(&device, config.width, config.height);
+
+        // HDR-цель сцены, экспозиция и тональная компрессия в формат экрана
+        let hdr_texture = create_hdr_texture(&device, config.width, config.height);
+
+        let exposure_shader = device.create_shader_module(ShaderModuleDescriptor {
+            label: Some("Exposure Shader"),
+            source: ShaderSource::Wgsl(include_str!("../../assets/shaders/exposure.wgsl").into()),
+        });
+
+        let exposure_bind_group_layout = device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+            label: Some("Exposure Bind Group Layout"),
+            entries: &[
+                BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: ShaderStages::COMPUTE,
+                    ty: BindingType::Texture {
+                        sample_type: TextureSampleType::Float { filterable: false },
+                        view_dimension: TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: ShaderStages::COMPUTE,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: ShaderStages::COMPUTE,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Storage { read_only: false },
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+        });
+
+        let exposure_params_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Exposure Params Buffer"),
+            contents: bytemuck::cast_slice(&[ExposureParamsUniform::from_settings(&ExposureSettings::default(), 0.0)]),
             usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
         });
-        
-        // Создаем временную (заглушку) текстуру 1x1
-        let temp_texture = device.create_texture(&TextureDescriptor {
-            label: Some("Temp Texture"),
-            size: Extent3d {
-                width: 1,
-                height: 1,
-                depth_or_array_layers: 1,
+
+        let exposure_state_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Exposure State Buffer"),
+            contents: bytemuck::cast_slice(&[ExposureStateUniform {
+                adapted_luminance: ExposureSettings::default().key_value,
+                exposure: 1.0,
+            }]),
+            usage: BufferUsages::STORAGE,
+        });
+
+        let exposure_bind_group = create_exposure_bind_group(
+            &device,
+            &exposure_bind_group_layout,
+            &hdr_texture,
+            &exposure_params_buffer,
+            &exposure_state_buffer,
+        );
+
+        let exposure_pipeline_layout = device.create_pipeline_layout(&PipelineLayoutDescriptor {
+            label: Some("Exposure Pipeline Layout"),
+            bind_group_layouts: &[&exposure_bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let exposure_pipeline = device.create_compute_pipeline(&ComputePipelineDescriptor {
+            label: Some("Exposure Pipeline"),
+            layout: Some(&exposure_pipeline_layout),
+            module: &exposure_shader,
+            entry_point: Some("cs_main"),
+            compilation_options: Default::default(),
+            cache: None,
+        });
+
+        let tonemap_shader = device.create_shader_module(ShaderModuleDescriptor {
+            label: Some("Tonemap Shader"),
+            source: ShaderSource::Wgsl(include_str!("../../assets/shaders/tonemap.wgsl").into()),
+        });
+
+        let tonemap_bind_group_layout = device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+            label: Some("Tonemap Bind Group Layout"),
+            entries: &[
+                BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Texture {
+                        sample_type: TextureSampleType::Float { filterable: true },
+                        view_dimension: TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Sampler(SamplerBindingType::Filtering),
+                    count: None,
+                },
+                BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Storage { read_only: true },
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+        });
+
+        let tonemap_bind_group = create_tonemap_bind_group(
+            &device,
+            &tonemap_bind_group_layout,
+            &hdr_texture,
+            &exposure_state_buffer,
+        );
+
+        let tonemap_pipeline_layout = device.create_pipeline_layout(&PipelineLayoutDescriptor {
+            label: Some("Tonemap Pipeline Layout"),
+            bind_group_layouts: &[&tonemap_bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let tonemap_pipeline = device.create_render_pipeline(&RenderPipelineDescriptor {
+            label: Some("Tonemap Pipeline"),
+            layout: Some(&tonemap_pipeline_layout),
+            vertex: VertexState {
+                module: &tonemap_shader,
+                entry_point: Some("vs_main"),
+                buffers: &[],
+                compilation_options: Default::default(),
             },
+            fragment: Some(FragmentState {
+                module: &tonemap_shader,
+                entry_point: Some("fs_main"),
+                targets: &[Some(ColorTargetState {
+                    format: config.format,
+                    blend: Some(BlendState::REPLACE),
+                    write_mask: ColorWrites::ALL,
+                })],
+                compilation_options: Default::default(),
+            }),
+            primitive: PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: MultisampleState::default(),
+            multiview: None,
+            cache: None,
+        });
+
+        // Надписи HUD рисуются в проходе тональной компрессии поверх готового кадра
+        let glyph_atlas = GlyphAtlas::load(Path::new(HUD_FONT_PATH)).unwrap_or_else(|e| {
+            eprintln!("Не удалось загрузить шрифт HUD {}: {}", HUD_FONT_PATH, e);
+            GlyphAtlas::empty()
+        });
+        let glyph_atlas_size = Extent3d {
+            width: glyph_atlas.width,
+            height: glyph_atlas.height,
+            depth_or_array_layers: 1,
+        };
+        let glyph_atlas_texture = device.create_texture(&TextureDescriptor {
+            label: Some("HUD Glyph Atlas"),
+            size: glyph_atlas_size,
             mip_level_count: 1,
             sample_count: 1,
             dimension: TextureDimension::D2,
-            format: TextureFormat::Rgba8UnormSrgb,
+            format: TextureFormat::R8Unorm,
             usage: TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST,
             view_formats: &[],
         });
-        
-        let temp_texture_view = temp_texture.create_view(&TextureViewDescriptor::default());
-        
-        // Создаем сэмплер
-        let sampler = device.create_sampler(&SamplerDescriptor {
-            label: Some("Texture Sampler"),
-            address_mode_u: AddressMode::ClampToEdge,
-            address_mode_v: AddressMode::ClampToEdge,
-            address_mode_w: AddressMode::ClampToEdge,
-            mag_filter: FilterMode::Nearest, // Используем Nearest для более четких текстур
-            min_filter: FilterMode::Nearest,
-            mipmap_filter: FilterMode::Nearest,
-            ..Default::default()
-        });
-
-        // Инициализируем временную текстуру красным пикселем для большей видимости
-        let red_pixel: [u8; 4] = [255, 0, 0, 255];
         queue.write_texture(
-            ImageCopyTexture {
-                texture: &temp_texture,
+            TexelCopyTextureInfo {
+                texture: &glyph_atlas_texture,
                 mip_level: 0,
                 origin: Origin3d::ZERO,
                 aspect: TextureAspect::All,
             },
-            &red_pixel,
-            ImageDataLayout {
+            &glyph_atlas.pixels,
+            TexelCopyBufferLayout {
                 offset: 0,
-                bytes_per_row: Some(4),
-                rows_per_image: Some(1),
-            },
-            Extent3d {
-                width: 1,
-                height: 1,
-                depth_or_array_layers: 1,
+                bytes_per_row: Some(glyph_atlas.width),
+                rows_per_image: Some(glyph_atlas.height),
             },
+            glyph_atlas_size,
         );
+        let glyph_atlas_view = glyph_atlas_texture.create_view(&TextureViewDescriptor::default());
+        let glyph_atlas_sampler = device.create_sampler(&SamplerDescriptor {
+            label: Some("HUD Glyph Sampler"),
+            mag_filter: FilterMode::Linear,
+            min_filter: FilterMode::Linear,
+            ..Default::default()
+        });
+        let hud_text_screen_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("HUD Text Screen Buffer"),
+            contents: bytemuck::cast_slice(&[config.width as f32, config.height as f32]),
+            usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
+        });
 
-        // Создаем bind group для камеры
-        let camera_bind_group = device.create_bind_group(&BindGroupDescriptor {
-            layout: &camera_bind_group_layout,
+        let hud_text_bind_group_layout = device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+            label: Some("HUD Text Bind Group Layout"),
             entries: &[
-                BindGroupEntry {
+                BindGroupLayoutEntry {
                     binding: 0,
-                    resource: camera_buffer.as_entire_binding(),
-                }
+                    visibility: ShaderStages::VERTEX,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Texture {
+                        sample_type: TextureSampleType::Float { filterable: true },
+                        view_dimension: TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Sampler(SamplerBindingType::Filtering),
+                    count: None,
+                },
             ],
-            label: Some("camera_bind_group"),
         });
-        
-        // Создаем bind group для модели/материала (заглушка)
-        let model_bind_group = device.create_bind_group(&BindGroupDescriptor {
-            layout: &model_bind_group_layout,
+
+        let hud_text_bind_group = device.create_bind_group(&BindGroupDescriptor {
+            label: Some("HUD Text Bind Group"),
+            layout: &hud_text_bind_group_layout,
             entries: &[
                 BindGroupEntry {
                     binding: 0,
-                    resource: model_buffer.as_entire_binding(),
+                    resource: hud_text_screen_buffer.as_entire_binding(),
                 },
                 BindGroupEntry {
                     binding: 1,
-                    resource: material_buffer.as_entire_binding(),
+                    resource: BindingResource::TextureView(&glyph_atlas_view),
                 },
                 BindGroupEntry {
                     binding: 2,
-                    resource: BindingResource::TextureView(&temp_texture_view),
-                },
-                BindGroupEntry {
-                    binding: 3,
-                    resource: BindingResource::Sampler(&sampler),
-                },
-            ],
-            label: Some("model_bind_group"),
-        });
-        
-        // Создаем bind group для источника света (заглушка)
-        let light_bind_group = device.create_bind_group(&BindGroupDescriptor {
-            layout: &light_bind_group_layout,
-            entries: &[
-                BindGroupEntry {
-                    binding: 0,
-                    resource: light_buffer.as_entire_binding(),
+                    resource: BindingResource::Sampler(&glyph_atlas_sampler),
                 },
             ],
-            label: Some("light_bind_group"),
         });
 
-        // Создаем трехмерный куб вместо плоского квадрата
-        let vertices = [
-            // Передняя грань (z+)
-            Vertex { position: [-1.0, -1.0, 1.0], tex_coords: [0.0, 1.0], normal: [0.0, 0.0, 1.0] },
-            Vertex { position: [1.0, -1.0, 1.0], tex_coords: [1.0, 1.0], normal: [0.0, 0.0, 1.0] },
-            Vertex { position: [1.0, 1.0, 1.0], tex_coords: [1.0, 0.0], normal: [0.0, 0.0, 1.0] },
-            Vertex { position: [-1.0, 1.0, 1.0], tex_coords: [0.0, 0.0], normal: [0.0, 0.0, 1.0] },
-            
-            // Задняя грань (z-)
-            Vertex { position: [1.0, -1.0, -1.0], tex_coords: [0.0, 1.0], normal: [0.0, 0.0, -1.0] },
-            Vertex { position: [-1.0, -1.0, -1.0], tex_coords: [1.0, 1.0], normal: [0.0, 0.0, -1.0] },
-            Vertex { position: [-1.0, 1.0, -1.0], tex_coords: [1.0, 0.0], normal: [0.0, 0.0, -1.0] },
-            Vertex { position: [1.0, 1.0, -1.0], tex_coords: [0.0, 0.0], normal: [0.0, 0.0, -1.0] },
-            
-            // Верхняя грань (y+)
-            Vertex { position: [-1.0, 1.0, -1.0], tex_coords: [0.0, 1.0], normal: [0.0, 1.0, 0.0] },
-            Vertex { position: [1.0, 1.0, -1.0], tex_coords: [1.0, 1.0], normal: [0.0, 1.0, 0.0] },
-            Vertex { position: [1.0, 1.0, 1.0], tex_coords: [1.0, 0.0], normal: [0.0, 1.0, 0.0] },
-            Vertex { position: [-1.0, 1.0, 1.0], tex_coords: [0.0, 0.0], normal: [0.0, 1.0, 0.0] },
-            
-            // Нижняя грань (y-)
-            Vertex { position: [-1.0, -1.0, 1.0], tex_coords: [0.0, 1.0], normal: [0.0, -1.0, 0.0] },
-            Vertex { position: [1.0, -1.0, 1.0], tex_coords: [1.0, 1.0], normal: [0.0, -1.0, 0.0] },
-            Vertex { position: [1.0, -1.0, -1.0], tex_coords: [1.0, 0.0], normal: [0.0, -1.0, 0.0] },
-            Vertex { position: [-1.0, -1.0, -1.0], tex_coords: [0.0, 0.0], normal: [0.0, -1.0, 0.0] },
-            
-            // Правая грань (x+)
-            Vertex { position: [1.0, -1.0, 1.0], tex_coords: [0.0, 1.0], normal: [1.0, 0.0, 0.0] },
-            Vertex { position: [1.0, -1.0, -1.0], tex_coords: [1.0, 1.0], normal: [1.0, 0.0, 0.0] },
-            Vertex { position: [1.0, 1.0, -1.0], tex_coords: [1.0, 0.0], normal: [1.0, 0.0, 0.0] },
-            Vertex { position: [1.0, 1.0, 1.0], tex_coords: [0.0, 0.0], normal: [1.0, 0.0, 0.0] },
-            
-            // Левая грань (x-)
-            Vertex { position: [-1.0, -1.0, -1.0], tex_coords: [0.0, 1.0], normal: [-1.0, 0.0, 0.0] },
-            Vertex { position: [-1.0, -1.0, 1.0], tex_coords: [1.0, 1.0], normal: [-1.0, 0.0, 0.0] },
-            Vertex { position: [-1.0, 1.0, 1.0], tex_coords: [1.0, 0.0], normal: [-1.0, 0.0, 0.0] },
-            Vertex { position: [-1.0, 1.0, -1.0], tex_coords: [0.0, 0.0], normal: [-1.0, 0.0, 0.0] },
-        ];
-        
-        let indices: &[u16] = &[
-            0, 1, 2, 2, 3, 0,     // передняя грань
-            4, 5, 6, 6, 7, 4,     // задняя грань
-            8, 9, 10, 10, 11, 8,  // верхняя грань
-            12, 13, 14, 14, 15, 12, // нижняя грань
-            16, 17, 18, 18, 19, 16, // правая грань
-            20, 21, 22, 22, 23, 20, // левая грань
-        ];
-        
-        let vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-            label: Some("Vertex Buffer"),
-            contents: bytemuck::cast_slice(&vertices),
-            usage: wgpu::BufferUsages::VERTEX,
+        let hud_text_shader = device.create_shader_module(ShaderModuleDescriptor {
+            label: Some("HUD Text Shader"),
+            source: ShaderSource::Wgsl(include_str!("../../assets/shaders/hud_text.wgsl").into()),
         });
-        
-        let index_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-            label: Some("Index Buffer"),
-            contents: bytemuck::cast_slice(indices),
-            usage: wgpu::BufferUsages::INDEX,
+
+        let hud_text_pipeline_layout = device.create_pipeline_layout(&PipelineLayoutDescriptor {
+            label: Some("HUD Text Pipeline Layout"),
+            bind_group_layouts: &[&hud_text_bind_group_layout],
+            push_constant_ranges: &[],
         });
-        
-        let num_indices = indices.len() as u32;
 
-        let pipeline = device.create_render_pipeline(&RenderPipelineDescriptor {
-            label: Some("Render Pipeline"),
-            layout: Some(&pipeline_layout),
+        let hud_text_pipeline = device.create_render_pipeline(&RenderPipelineDescriptor {
+            label: Some("HUD Text Pipeline"),
+            layout: Some(&hud_text_pipeline_layout),
             vertex: VertexState {
-                module: &shader,
+                module: &hud_text_shader,
                 entry_point: Some("vs_main"),
-                buffers: &[Vertex::desc()],
+                buffers: &[GlyphInstance::desc()],
                 compilation_options: Default::default(),
             },
             fragment: Some(FragmentState {
-                module: &shader,
-                entry_point: Some("fs_unlit"), // Используем упрощенный шейдер без освещения
+                module: &hud_text_shader,
+                entry_point: Some("fs_main"),
                 targets: &[Some(ColorTargetState {
                     format: config.format,
-                    blend: Some(BlendState::REPLACE),
+                    blend: Some(BlendState::ALPHA_BLENDING),
                     write_mask: ColorWrites::ALL,
                 })],
                 compilation_options: Default::default(),
             }),
-            primitive: PrimitiveState {
-                topology: PrimitiveTopology::TriangleList,
-                strip_index_format: None,
-                front_face: FrontFace::Ccw,
-                cull_mode: None, // Отключаем culling для отображения всех граней
-                polygon_mode: PolygonMode::Fill,
-                unclipped_depth: false,
-                conservative: false,
-            },
+            primitive: PrimitiveState::default(),
             depth_stencil: None,
-            multisample: MultisampleState {
-                count: 1,
-                mask: !0,
-                alpha_to_coverage_enabled: false,
-            },
+            multisample: MultisampleState::default(),
             multiview: None,
-            cache: Default::default(),
+            cache: None,
         });
 
         Self {
@@ -642,16 +2218,77 @@ impl<'window> RenderSystem<'window> {
             queue,
             config,
             pipeline,
-            depth_texture: None,
+            depth_texture: Some(depth_texture),
+            hdr_texture,
+            exposure_pipeline,
+            exposure_bind_group_layout,
+            exposure_bind_group,
+            exposure_params_buffer,
+            exposure_state_buffer,
+            tonemap_pipeline,
+            tonemap_bind_group_layout,
+            tonemap_bind_group,
+            capture_pending: false,
+            stats: RenderStats::default(),
+            graphics: GraphicsSettings::default(),
             camera_bind_group,
+            model_bind_group_layout,
             model_bind_group,
             light_bind_group,
-            vertex_buffer,
-            index_buffer,
-            num_indices,
+            default_texture_view: temp_texture_view,
+            sampler,
+            terrain_textures,
+            terrain_splat_uploaded: false,
+            meshes: Vec::new(),
+            object_uniform_stride,
+            object_capacity,
+            object_draw_count: 0,
             camera_buffer,
+            light_buffer,
+            spot_light_buffer,
+            light_cone_pipeline,
+            light_cone_vertex_buffer,
+            light_cone_vertex_count: light_cone_vertices.len() as u32,
+            light_cone_instance_buffer,
+            light_cone_instance_count: 0,
             model_buffer,
             material_buffer,
+            fog_buffer,
+            rain_buffer,
+            rain_overlay_pipeline,
+            rain_overlay_bind_group,
+            screen_droplets: 0.0,
+            clear_color: SKY_COLOR,
+            vegetation_pipeline,
+            vegetation_wind_buffer,
+            vegetation_wind_bind_group,
+            vegetation_quad_buffer,
+            vegetation_instance_buffer: None,
+            vegetation_instance_count: 0,
+            vegetation_revision: None,
+            blob_shadow_pipeline,
+            blob_shadow_instance_buffer: None,
+            blob_shadow_instance_capacity: 0,
+            blob_shadow_instance_count: 0,
+            particle_pipeline,
+            particle_instance_buffer: None,
+            particle_instance_capacity: 0,
+            particle_instance_count: 0,
+            world_marker_pipeline,
+            world_marker_instance_buffer: None,
+            world_marker_instance_capacity: 0,
+            world_marker_instance_count: 0,
+            debug_line_pipeline,
+            debug_line_buffer: None,
+            debug_line_capacity: 0,
+            debug_line_vertex_count: 0,
+            glyph_atlas,
+            hud_text_pipeline,
+            hud_text_bind_group,
+            hud_text_screen_buffer,
+            hud_text_instance_buffer: None,
+            hud_text_instance_capacity: 0,
+            hud_text_instance_count: 0,
         }
     }
 
@@ -671,24 +2308,109 @@ impl<'window> RenderSystem<'window> {
                 surface.configure(&self.device, &self.config);
             }
             
-            // Пересоздаем depth texture при изменении размера
-            // ...
+            self.recreate_render_targets();
+        }
+    }
+
+    /// Размер буферов сцены с учетом масштаба разрешения
+    fn render_size(&self) -> (u32, u32) {
+        let scale = self.graphics.resolution_scale.clamp(MIN_RESOLUTION_SCALE, 1.0);
+        (
+            ((self.config.width as f32 * scale) as u32).max(1),
+            ((self.config.height as f32 * scale) as u32).max(1),
+        )
+    }
+
+    /// Буферы глубины и HDR следуют за размером поверхности и масштабом разрешения;
+    /// тональная компрессия растягивает HDR на весь экран
+    fn recreate_render_targets(&mut self) {
+        let (width, height) = self.render_size();
+        self.depth_texture = Some(create_depth_texture(&self.device, width, height));
+        self.hdr_texture = create_hdr_texture(&self.device, width, height);
+        self.exposure_bind_group = create_exposure_bind_group(
+            &self.device,
+            &self.exposure_bind_group_layout,
+            &self.hdr_texture,
+            &self.exposure_params_buffer,
+            &self.exposure_state_buffer,
+        );
+        self.tonemap_bind_group = create_tonemap_bind_group(
+            &self.device,
+            &self.tonemap_bind_group_layout,
+            &self.hdr_texture,
+            &self.exposure_state_buffer,
+        );
+    }
+
+    pub fn graphics_settings(&self) -> &GraphicsSettings {
+        &self.graphics
+    }
+
+    pub fn apply_graphics_settings(&mut self, settings: &GraphicsSettings) {
+        let rescale = settings.resolution_scale != self.graphics.resolution_scale;
+        self.graphics = settings.clone();
+        if rescale {
+            self.recreate_render_targets();
+        }
+    }
+
+    /// Замер производительности: сцена мира рисуется в скрытую текстуру заданное время.
+    /// Перед каждым кадром ждем конца предыдущего, чтобы разделить время CPU и GPU
+    pub fn benchmark(&mut self, world: &World, duration: Duration) -> BenchmarkResult {
+        let target = self.device.create_texture(&TextureDescriptor {
+            label: Some("Benchmark Target"),
+            size: Extent3d {
+                width: self.config.width,
+                height: self.config.height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: TextureDimension::D2,
+            format: self.config.format,
+            usage: TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        });
+        let view = target.create_view(&TextureViewDescriptor::default());
+
+        let mut result = BenchmarkResult::default();
+        let (mut cpu_total, mut gpu_total) = (0.0, 0.0);
+        let start = Instant::now();
+        while start.elapsed() < duration {
+            let frame_start = Instant::now();
+            if let Err(e) = self.render_scene(world, TARGET_FRAME_TIME, Some(&view)) {
+                eprintln!("Ошибка рендеринга при замере: {:?}", e);
+                break;
+            }
+            let submitted = Instant::now();
+            if let Err(e) = self.device.poll(PollType::Wait) {
+                eprintln!("Ошибка ожидания GPU при замере: {:?}", e);
+                break;
+            }
+            cpu_total += submitted.duration_since(frame_start).as_secs_f32();
+            gpu_total += submitted.elapsed().as_secs_f32();
+            result.frames += 1;
         }
+        if result.frames > 0 {
+            result.cpu_frame_time = cpu_total / result.frames as f32;
+            result.gpu_frame_time = gpu_total / result.frames as f32;
+        }
+        result
     }
 
     // Публичный метод для рендеринга, который можно вызывать напрямую
-    pub fn render(&mut self, world: &World, _delta_time: f32) {
+    pub fn render(&mut self, world: &World, delta_time: f32) {
         // Обновляем и рендерим сцену
-        if let Err(e) = self.render_scene(world) {
+        if let Err(e) = self.render_scene(world, delta_time, None) {
             eprintln!("Ошибка рендеринга: {:?}", e);
         }
     }
 
-    fn render_scene(&mut self, world: &World) -> Result<(), SurfaceError> {
-    let surface = match &self.surface {
-        Some(surface) => surface,
-        None => return Ok(()),
-    };
+    /// Кадр в поверхность окна или, если задана цель, в нее без показа на экране
+    fn render_scene(&mut self, world: &World, delta_time: f32, target: Option<&TextureView>) -> Result<(), SurfaceError> {
+    if self.surface.is_none() {
+        return Ok(());
+    }
     
     // Получаем камеру из мира
     if let Some((_, camera)) = world.query::<&CameraComponent>().into_iter().next() {
@@ -725,71 +2447,806 @@ impl<'window> RenderSystem<'window> {
     self.queue.write_buffer(
         &self.model_buffer,
         0,
-        bytemuck::cast_slice(&[model_uniform])
+        bytemuck::cast_slice(&[model_uniform])
+    );
+    
+    // Обновляем материал - яркий красный для видимости
+    let material_uniform = MaterialUniform {
+        base_color: [1.0, 0.0, 0.0, 1.0], // Красный
+        metallic: 0.0,
+        roughness: 0.5,
+        ambient_occlusion: 1.0,
+        _padding: 0.0,
+        emissive: [0.0; 3],
+        terrain: 0.0,
+        layer_colors: [[1.0; 4]; 3],
+    };
+    
+    self.queue.write_buffer(
+        &self.material_buffer,
+        0,
+        bytemuck::cast_slice(&[material_uniform])
+    );
+    
+    // Обновляем экземпляры растительности и параметры ветра
+    if let Some((_, vegetation)) = world.query::<&Resource<VegetationRenderData>>().into_iter().next() {
+        let vegetation = &vegetation.0;
+        
+        if self.vegetation_revision != Some(vegetation.revision) {
+            self.vegetation_instance_buffer = if vegetation.instances.is_empty() {
+                None
+            } else {
+                Some(self.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                    label: Some("Vegetation Instance Buffer"),
+                    contents: bytemuck::cast_slice(&vegetation.instances),
+                    usage: BufferUsages::VERTEX,
+                }))
+            };
+            self.vegetation_instance_count = vegetation.instances.len() as u32;
+            self.vegetation_revision = Some(vegetation.revision);
+        }
+        
+        let wind_uniform = WindUniform {
+            direction: vegetation.wind_direction,
+            strength: vegetation.wind_strength,
+            time: vegetation.time,
+        };
+        
+        self.queue.write_buffer(
+            &self.vegetation_wind_buffer,
+            0,
+            bytemuck::cast_slice(&[wind_uniform])
+        );
+    }
+    
+    // Пятна теней следуют за машинами каждый кадр
+    self.blob_shadow_instance_count = 0;
+    if let Some((_, shadows)) = world.query::<&Resource<BlobShadowRenderData>>().into_iter().next() {
+        let instances = &shadows.0.instances;
+        
+        if !instances.is_empty() {
+            if instances.len() > self.blob_shadow_instance_capacity {
+                let capacity = instances.len().next_power_of_two();
+                self.blob_shadow_instance_buffer = Some(self.device.create_buffer(&BufferDescriptor {
+                    label: Some("Blob Shadow Instance Buffer"),
+                    size: (capacity * std::mem::size_of::<BlobShadowInstance>()) as BufferAddress,
+                    usage: BufferUsages::VERTEX | BufferUsages::COPY_DST,
+                    mapped_at_creation: false,
+                }));
+                self.blob_shadow_instance_capacity = capacity;
+            }
+            
+            if let Some(buffer) = &self.blob_shadow_instance_buffer {
+                self.queue.write_buffer(buffer, 0, bytemuck::cast_slice(instances));
+                self.blob_shadow_instance_count = instances.len() as u32;
+            }
+        }
+    }
+    
+    // Частицы меняются каждый кадр, буфер растет по мере необходимости
+    self.particle_instance_count = 0;
+    if let Some((_, particles)) = world.query::<&Resource<ParticleRenderData>>().into_iter().next() {
+        let instances = &particles.0.instances;
+        
+        if !instances.is_empty() {
+            if instances.len() > self.particle_instance_capacity {
+                let capacity = instances.len().next_power_of_two();
+                self.particle_instance_buffer = Some(self.device.create_buffer(&BufferDescriptor {
+                    label: Some("Particle Instance Buffer"),
+                    size: (capacity * std::mem::size_of::<ParticleInstance>()) as BufferAddress,
+                    usage: BufferUsages::VERTEX | BufferUsages::COPY_DST,
+                    mapped_at_creation: false,
+                }));
+                self.particle_instance_capacity = capacity;
+            }
+            
+            if let Some(buffer) = &self.particle_instance_buffer {
+                self.queue.write_buffer(buffer, 0, bytemuck::cast_slice(instances));
+                self.particle_instance_count = instances.len() as u32;
+            }
+        }
+    }
+    
+    // Метки в мире тоже пересобираются каждый кадр
+    self.world_marker_instance_count = 0;
+    if let Some((_, markers)) = world.query::<&Resource<WorldMarkerRenderData>>().into_iter().next() {
+        let instances = &markers.0.instances;
+        
+        if !instances.is_empty() {
+            if instances.len() > self.world_marker_instance_capacity {
+                let capacity = instances.len().next_power_of_two();
+                self.world_marker_instance_buffer = Some(self.device.create_buffer(&BufferDescriptor {
+                    label: Some("World Marker Instance Buffer"),
+                    size: (capacity * std::mem::size_of::<WorldMarkerInstance>()) as BufferAddress,
+                    usage: BufferUsages::VERTEX | BufferUsages::COPY_DST,
+                    mapped_at_creation: false,
+                }));
+                self.world_marker_instance_capacity = capacity;
+            }
+            
+            if let Some(buffer) = &self.world_marker_instance_buffer {
+                self.queue.write_buffer(buffer, 0, bytemuck::cast_slice(instances));
+                self.world_marker_instance_count = instances.len() as u32;
+            }
+        }
+    }
+    
+    // Отладочные линии собираются из всех слоев каждый кадр
+    self.debug_line_vertex_count = 0;
+    if let Some((_, debug_draw)) = world.query::<&Resource<DebugDraw>>().into_iter().next() {
+        let vertices = debug_draw.0.vertices();
+        
+        if !vertices.is_empty() {
+            if vertices.len() > self.debug_line_capacity {
+                let capacity = vertices.len().next_power_of_two();
+                self.debug_line_buffer = Some(self.device.create_buffer(&BufferDescriptor {
+                    label: Some("Debug Line Buffer"),
+                    size: (capacity * std::mem::size_of::<DebugLineVertex>()) as BufferAddress,
+                    usage: BufferUsages::VERTEX | BufferUsages::COPY_DST,
+                    mapped_at_creation: false,
+                }));
+                self.debug_line_capacity = capacity;
+            }
+            
+            if let Some(buffer) = &self.debug_line_buffer {
+                self.queue.write_buffer(buffer, 0, bytemuck::cast_slice(&vertices));
+                self.debug_line_vertex_count = vertices.len() as u32;
+            }
+        }
+    }
+    
+    // Надписи HUD раскладываются в пикселях экрана, а не сцены
+    self.hud_text_instance_count = 0;
+    if let Some((_, hud_text)) = world.query::<&Resource<HudText>>().into_iter().next() {
+        let (width, height) = (self.config.width as f32, self.config.height as f32);
+        let instances: Vec<GlyphInstance> = hud_text.0.lines()
+            .flat_map(|line| self.glyph_atlas.layout(line, width, height))
+            .collect();
+        
+        if !instances.is_empty() {
+            if instances.len() > self.hud_text_instance_capacity {
+                let capacity = instances.len().next_power_of_two();
+                self.hud_text_instance_buffer = Some(self.device.create_buffer(&BufferDescriptor {
+                    label: Some("HUD Text Instance Buffer"),
+                    size: (capacity * std::mem::size_of::<GlyphInstance>()) as BufferAddress,
+                    usage: BufferUsages::VERTEX | BufferUsages::COPY_DST,
+                    mapped_at_creation: false,
+                }));
+                self.hud_text_instance_capacity = capacity;
+            }
+            
+            if let Some(buffer) = &self.hud_text_instance_buffer {
+                self.queue.write_buffer(buffer, 0, bytemuck::cast_slice(&instances));
+                self.queue.write_buffer(&self.hud_text_screen_buffer, 0, bytemuck::cast_slice(&[width, height]));
+                self.hud_text_instance_count = instances.len() as u32;
+            }
+        }
+    }
+    
+    // Обновляем туман и цвет неба по данным погоды
+    let fog = world.query::<&Resource<FogSettings>>()
+        .into_iter()
+        .next()
+        .map(|(_, res)| res.0)
+        .unwrap_or_default();
+    
+    // Солнце или луна по времени суток
+    let sun = world.query::<&Resource<SunLight>>()
+        .into_iter()
+        .next()
+        .map(|(_, res)| res.0)
+        .unwrap_or_default();
+    
+    // Ночью небо и туман темнеют вместе с освещением
+    let sky_brightness = 0.05 + 0.95 * sun.intensity.clamp(0.0, 1.0);
+    let mut fog = fog;
+    fog.color = fog.color.map(|c| c * sky_brightness);
+    
+    self.queue.write_buffer(
+        &self.fog_buffer,
+        0,
+        bytemuck::cast_slice(&[FogUniform::from(fog)])
+    );
+    
+    let light_position = sun.direction * 1000.0;
+    self.queue.write_buffer(
+        &self.light_buffer,
+        0,
+        bytemuck::cast_slice(&[LightUniform {
+            position: light_position.to_array(),
+            _padding1: 0.0,
+            color: sun.color,
+            intensity: sun.intensity,
+        }])
+    );
+    
+    // Ближайшие к камере прожекторы
+    let camera_position = world.query::<&CameraComponent>()
+        .into_iter()
+        .next()
+        .map(|(_, camera)| camera.position)
+        .unwrap_or(Vec3::ZERO);
+    
+    let mut spot_lights: Vec<(SpotLightComponent, Vec3, Vec3)> = world
+        .query::<(&SpotLightComponent, &TransformComponent)>()
+        .iter()
+        .filter(|(_, (light, _))| light.enabled)
+        .map(|(_, (light, transform))| (*light, transform.position, transform.rotation * Vec3::Z))
+        .collect();
+    spot_lights.sort_by(|a, b| {
+        a.1.distance_squared(camera_position)
+            .total_cmp(&b.1.distance_squared(camera_position))
+    });
+    spot_lights.truncate(MAX_SPOT_LIGHTS);
+    
+    let mut spot_uniform = SpotLightsUniform::new();
+    let mut cone_instances = Vec::with_capacity(spot_lights.len());
+    // Конусы видны в темноте и сильнее в тумане
+    let darkness = 1.0 - sun.intensity.clamp(0.0, 1.0);
+    let cone_strength = darkness * (1.0 + fog.density * 100.0);
+    
+    for (i, (light, position, direction)) in spot_lights.iter().enumerate() {
+        spot_uniform.lights[i] = SpotLightUniform {
+            position_range: [position.x, position.y, position.z, light.range],
+            direction_cos_outer: [direction.x, direction.y, direction.z, light.outer_angle.cos()],
+            color_cos_inner: [
+                light.color[0] * light.intensity,
+                light.color[1] * light.intensity,
+                light.color[2] * light.intensity,
+                light.inner_angle.cos(),
+            ],
+        };
+        
+        if cone_strength > 0.0 && light.cone_visibility > 0.0 {
+            let radius = light.range * light.outer_angle.tan();
+            let model = Mat4::from_scale_rotation_translation(
+                Vec3::new(radius, radius, light.range),
+                glam::Quat::from_rotation_arc(Vec3::Z, direction.normalize_or_zero()),
+                *position,
+            );
+            cone_instances.push(LightConeInstance {
+                model: model.to_cols_array_2d(),
+                color: [light.color[0], light.color[1], light.color[2], light.cone_visibility * cone_strength],
+            });
+        }
+    }
+    spot_uniform.count = spot_lights.len() as u32;
+    
+    self.queue.write_buffer(&self.spot_light_buffer, 0, bytemuck::cast_slice(&[spot_uniform]));
+    if !cone_instances.is_empty() {
+        self.queue.write_buffer(&self.light_cone_instance_buffer, 0, bytemuck::cast_slice(&cone_instances));
+    }
+    self.light_cone_instance_count = cone_instances.len() as u32;
+    
+    // Мокрое покрытие и капли на экране
+    let rain = world.query::<&Resource<RainSettings>>()
+        .into_iter()
+        .next()
+        .map(|(_, res)| res.0)
+        .unwrap_or_default();
+    
+    self.screen_droplets = rain.screen_droplets;
+    self.queue.write_buffer(
+        &self.rain_buffer,
+        0,
+        bytemuck::cast_slice(&[RainUniform {
+            wetness: rain.surface_wetness,
+            droplets: rain.screen_droplets,
+            time: rain.time,
+            aspect: self.config.width as f32 / self.config.height.max(1) as f32,
+        }])
     );
     
-    // Обновляем материал - яркий красный для видимости
-    let material_uniform = MaterialUniform {
-        base_color: [1.0, 0.0, 0.0, 1.0], // Красный
-        metallic: 0.0,
-        roughness: 0.5,
-        ambient_occlusion: 1.0,
-        _padding: 0.0,
+    // Горизонт растворяется в тумане
+    let horizon_fog = (fog.density * 200.0).min(1.0) as f64;
+    let sky = sky_brightness as f64;
+    self.clear_color = Color {
+        r: SKY_COLOR.r * sky * (1.0 - horizon_fog) + fog.color[0] as f64 * horizon_fog,
+        g: SKY_COLOR.g * sky * (1.0 - horizon_fog) + fog.color[1] as f64 * horizon_fog,
+        b: SKY_COLOR.b * sky * (1.0 - horizon_fog) + fog.color[2] as f64 * horizon_fog,
+        a: 1.0,
     };
     
+    let object_draws = self.prepare_objects(world);
+    
+    let exposure = world
+        .query::<&Resource<ExposureSettings>>()
+        .iter()
+        .next()
+        .map(|(_, res)| res.0)
+        .unwrap_or_default();
     self.queue.write_buffer(
-        &self.material_buffer,
+        &self.exposure_params_buffer,
         0,
-        bytemuck::cast_slice(&[material_uniform])
+        bytemuck::cast_slice(&[ExposureParamsUniform::from_settings(&exposure, delta_time)]),
     );
     
-    let output = surface.get_current_texture()?;
-    let view = output.texture.create_view(&TextureViewDescriptor::default());
+    let Some(surface) = &self.surface else {
+        return Ok(());
+    };
+    let output = match target {
+        Some(_) => None,
+        None => Some(surface.get_current_texture()?),
+    };
+    let output_view = output
+        .as_ref()
+        .map(|output| output.texture.create_view(&TextureViewDescriptor::default()));
+    let Some(view) = target.or(output_view.as_ref()) else {
+        return Ok(());
+    };
+    
+    // Захват кадра во внешнем отладчике (RenderDoc), если он подключен
+    let capturing = std::mem::take(&mut self.capture_pending);
+    if capturing {
+        unsafe { self.device.start_graphics_debugger_capture() };
+    }
     
     let mut encoder = self.device.create_command_encoder(&CommandEncoderDescriptor {
         label: Some("Render Encoder"),
     });
+    let mut draw_calls = 0;
     
     {
         let mut render_pass = encoder.begin_render_pass(&RenderPassDescriptor {
             label: Some("Render Pass"),
             color_attachments: &[Some(RenderPassColorAttachment {
-                view: &view,
+                view: &self.hdr_texture.view,
                 resolve_target: None,
                 ops: Operations {
-                    load: LoadOp::Clear(Color {
-                        r: 0.5, // Более светлый синий, чтобы видеть изменения
-                        g: 0.5,
-                        b: 0.8,
-                        a: 1.0,
-                    }),
+                    load: LoadOp::Clear(self.clear_color),
                     store: StoreOp::Store,
                 },
             })],
-            depth_stencil_attachment: None,
+            depth_stencil_attachment: self.depth_texture.as_ref().map(|depth| RenderPassDepthStencilAttachment {
+                view: &depth.view,
+                depth_ops: Some(Operations {
+                    load: LoadOp::Clear(1.0),
+                    store: StoreOp::Store,
+                }),
+                stencil_ops: None,
+            }),
             timestamp_writes: None,
             occlusion_query_set: None,
         });
         
         render_pass.set_pipeline(&self.pipeline);
         render_pass.set_bind_group(0, &self.camera_bind_group, &[]);
-        render_pass.set_bind_group(1, &self.model_bind_group, &[]);
         render_pass.set_bind_group(2, &self.light_bind_group, &[]);
         
-        // Устанавливаем буферы вершин и индексов
-        render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
-        render_pass.set_index_buffer(self.index_buffer.slice(..), IndexFormat::Uint16);
+        // Объекты сцены: матрица и материал берутся из слота объекта
+        draw_calls += object_draws.len() as u32;
+        for draw in &object_draws {
+            let mesh = &self.meshes[draw.mesh_id];
+            let offset = (draw.slot as u64 * self.object_uniform_stride) as u32;
+            render_pass.set_bind_group(1, &self.model_bind_group, &[offset, offset]);
+            render_pass.set_vertex_buffer(0, mesh.vertex_buffer.slice(..));
+            match &mesh.index_buffer {
+                Some(index_buffer) => {
+                    render_pass.set_index_buffer(index_buffer.slice(..), IndexFormat::Uint16);
+                    render_pass.draw_indexed(0..mesh.num_indices, 0, 0..1);
+                }
+                None => render_pass.draw(0..mesh.num_vertices, 0..1),
+            }
+        }
+        
+        // Пятна теней лежат на земле, поэтому рисуются до растительности и частиц
+        if let Some(instance_buffer) = self
+            .blob_shadow_instance_buffer
+            .as_ref()
+            .filter(|_| self.blob_shadow_instance_count > 0 && self.graphics.preset.blob_shadows())
+        {
+            render_pass.set_pipeline(&self.blob_shadow_pipeline);
+            render_pass.set_bind_group(0, &self.camera_bind_group, &[]);
+            render_pass.set_vertex_buffer(0, self.vegetation_quad_buffer.slice(..));
+            render_pass.set_vertex_buffer(1, instance_buffer.slice(..));
+            render_pass.draw(0..6, 0..self.blob_shadow_instance_count);
+            draw_calls += 1;
+        }
+        
+        // Рисуем растительность одним инстансированным вызовом
+        if let Some(instance_buffer) = self.vegetation_instance_buffer.as_ref().filter(|_| self.graphics.preset.vegetation()) {
+            render_pass.set_pipeline(&self.vegetation_pipeline);
+            render_pass.set_bind_group(0, &self.camera_bind_group, &[]);
+            render_pass.set_bind_group(1, &self.vegetation_wind_bind_group, &[]);
+            render_pass.set_vertex_buffer(0, self.vegetation_quad_buffer.slice(..));
+            render_pass.set_vertex_buffer(1, instance_buffer.slice(..));
+            render_pass.draw(0..6, 0..self.vegetation_instance_count);
+            draw_calls += 1;
+        }
+        
+        // Частицы рисуются последними поверх сцены
+        if let Some(instance_buffer) = self.particle_instance_buffer.as_ref().filter(|_| self.particle_instance_count > 0) {
+            render_pass.set_pipeline(&self.particle_pipeline);
+            render_pass.set_bind_group(0, &self.camera_bind_group, &[]);
+            render_pass.set_vertex_buffer(0, self.vegetation_quad_buffer.slice(..));
+            render_pass.set_vertex_buffer(1, instance_buffer.slice(..));
+            render_pass.draw(0..6, 0..self.particle_instance_count);
+            draw_calls += 1;
+        }
+        
+        // Метки в мире поверх частиц, но за непрозрачной геометрией
+        if let Some(instance_buffer) = self.world_marker_instance_buffer.as_ref().filter(|_| self.world_marker_instance_count > 0) {
+            render_pass.set_pipeline(&self.world_marker_pipeline);
+            render_pass.set_bind_group(0, &self.camera_bind_group, &[]);
+            render_pass.set_vertex_buffer(0, self.vegetation_quad_buffer.slice(..));
+            render_pass.set_vertex_buffer(1, instance_buffer.slice(..));
+            render_pass.draw(0..6, 0..self.world_marker_instance_count);
+            draw_calls += 1;
+        }
+        
+        // Видимые конусы фар
+        if self.light_cone_instance_count > 0 && self.graphics.preset.light_cones() {
+            render_pass.set_pipeline(&self.light_cone_pipeline);
+            render_pass.set_bind_group(0, &self.camera_bind_group, &[]);
+            render_pass.set_vertex_buffer(0, self.light_cone_vertex_buffer.slice(..));
+            render_pass.set_vertex_buffer(1, self.light_cone_instance_buffer.slice(..));
+            render_pass.draw(0..self.light_cone_vertex_count, 0..self.light_cone_instance_count);
+            draw_calls += 1;
+        }
+        
+        // Отладочные линии поверх сцены, но под каплями на экране
+        if let Some(buffer) = self.debug_line_buffer.as_ref().filter(|_| self.debug_line_vertex_count > 0) {
+            render_pass.set_pipeline(&self.debug_line_pipeline);
+            render_pass.set_bind_group(0, &self.camera_bind_group, &[]);
+            render_pass.set_vertex_buffer(0, buffer.slice(..));
+            render_pass.draw(0..self.debug_line_vertex_count, 0..1);
+            draw_calls += 1;
+        }
+        
+        // Капли дождя поверх всего кадра
+        if self.screen_droplets > 0.0 {
+            render_pass.set_pipeline(&self.rain_overlay_pipeline);
+            render_pass.set_bind_group(0, &self.rain_overlay_bind_group, &[]);
+            render_pass.draw(0..3, 0..1);
+            draw_calls += 1;
+        }
+    }
+    
+    // Средняя яркость кадра и адаптация экспозиции целиком на GPU
+    {
+        let mut compute_pass = encoder.begin_compute_pass(&ComputePassDescriptor {
+            label: Some("Auto Exposure Pass"),
+            timestamp_writes: None,
+        });
+        compute_pass.set_pipeline(&self.exposure_pipeline);
+        compute_pass.set_bind_group(0, &self.exposure_bind_group, &[]);
+        compute_pass.dispatch_workgroups(1, 1, 1);
+    }
+    
+    // Тональная компрессия HDR в формат экрана
+    {
+        let mut tonemap_pass = encoder.begin_render_pass(&RenderPassDescriptor {
+            label: Some("Tonemap Pass"),
+            color_attachments: &[Some(RenderPassColorAttachment {
+                view,
+                resolve_target: None,
+                ops: Operations {
+                    load: LoadOp::Clear(Color::BLACK),
+                    store: StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+        tonemap_pass.set_pipeline(&self.tonemap_pipeline);
+        tonemap_pass.set_bind_group(0, &self.tonemap_bind_group, &[]);
+        tonemap_pass.draw(0..3, 0..1);
+        draw_calls += 1;
         
-        // Рисуем тестовый куб
-        render_pass.draw_indexed(0..self.num_indices, 0, 0..1);
+        if let Some(buffer) = self.hud_text_instance_buffer.as_ref().filter(|_| self.hud_text_instance_count > 0) {
+            tonemap_pass.set_pipeline(&self.hud_text_pipeline);
+            tonemap_pass.set_bind_group(0, &self.hud_text_bind_group, &[]);
+            tonemap_pass.set_vertex_buffer(0, buffer.slice(..));
+            tonemap_pass.draw(0..6, 0..self.hud_text_instance_count);
+            draw_calls += 1;
+        }
     }
+    self.stats = RenderStats {
+        draw_calls,
+        visible_objects: object_draws.len() as u32,
+    };
     
     self.queue.submit(std::iter::once(encoder.finish()));
-    output.present();
+    if capturing {
+        unsafe { self.device.stop_graphics_debugger_capture() };
+        eprintln!("Кадр передан в графический отладчик");
+    }
+    if let Some(output) = output {
+        output.present();
+    }
     
     Ok(())
 }
 
+    /// Загрузка на GPU мешей, добавленных в менеджер ресурсов с прошлого кадра
+    fn upload_new_meshes(&mut self, resources: &RenderResourceManager) {
+        for mesh_data in resources.mesh_data.iter().skip(self.meshes.len()) {
+            let vertex_buffer = self.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("Mesh Vertex Buffer"),
+                contents: bytemuck::cast_slice(&mesh_data.vertices),
+                usage: BufferUsages::VERTEX,
+            });
+            let index_buffer = mesh_data.indices.as_ref().map(|indices| {
+                self.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                    label: Some("Mesh Index Buffer"),
+                    contents: bytemuck::cast_slice(indices),
+                    usage: BufferUsages::INDEX,
+                })
+            });
+            self.meshes.push(Mesh {
+                vertex_buffer,
+                index_buffer,
+                num_vertices: mesh_data.vertices.len() as u32,
+                num_indices: mesh_data.indices.as_ref().map_or(0, |indices| indices.len() as u32),
+            });
+        }
+    }
+
+    /// Заполняет слоты объектов одной записью в буферы и возвращает порядок отрисовки
+    fn prepare_objects(&mut self, world: &World) -> Vec<ObjectDraw> {
+        let mut resources_query = world.query::<&Resource<RenderResourceManager>>();
+        let Some((_, resources)) = resources_query.iter().next() else {
+            self.object_draw_count = 0;
+            return Vec::new();
+        };
+        let resources = &resources.0;
+        self.upload_new_meshes(resources);
+        if let Some(splat) = resources.terrain_splat.as_ref().filter(|_| !self.terrain_splat_uploaded) {
+            self.terrain_textures = create_terrain_textures(&self.device, &self.queue, Some(splat));
+            self.model_bind_group = create_model_bind_group(
+                &self.device,
+                &self.model_bind_group_layout,
+                &self.model_buffer,
+                &self.material_buffer,
+                &self.default_texture_view,
+                &self.sampler,
+                &self.terrain_textures,
+            );
+            self.terrain_splat_uploaded = true;
+        }
+
+        let camera_position = world
+            .query::<&CameraComponent>()
+            .iter()
+            .next()
+            .map(|(_, camera)| camera.position)
+            .unwrap_or(Vec3::ZERO);
+
+        let mut objects: Vec<(ModelUniform, MaterialUniform, usize, f32, f32)> = world
+            .query::<(&RenderComponent, &TransformComponent)>()
+            .iter()
+            .filter(|(_, (render, _))| render.visible && render.opacity > 0.0 && render.mesh_id < self.meshes.len())
+            .map(|(_, (render, transform))| {
+                let model = Mat4::from_scale_rotation_translation(
+                    transform.scale * render.scale,
+                    transform.rotation,
+                    transform.position,
+                );
+                let mut material = resources
+                    .material_data
+                    .get(render.material_id)
+                    .map(MaterialUniform::from)
+                    .unwrap_or_else(MaterialUniform::new);
+                material.base_color[3] *= render.opacity;
+                let distance = transform.position.distance_squared(camera_position);
+                (
+                    ModelUniform { model: model.to_cols_array_2d() },
+                    material,
+                    render.mesh_id,
+                    material.base_color[3],
+                    distance,
+                )
+            })
+            .collect();
+
+        // Непрозрачные разбирает буфер глубины, их порядок не важен; прозрачные идут после них
+        // от дальних к ближним, иначе смешивание даст неверный цвет
+        objects.sort_by(|a, b| {
+            let a_opaque = a.3 >= 1.0;
+            let b_opaque = b.3 >= 1.0;
+            b_opaque.cmp(&a_opaque).then_with(|| {
+                if a_opaque {
+                    std::cmp::Ordering::Equal
+                } else {
+                    b.4.total_cmp(&a.4)
+                }
+            })
+        });
+
+        if objects.len() > self.object_capacity {
+            self.object_capacity = objects.len().next_power_of_two();
+            let (model_buffer, material_buffer) =
+                create_object_buffers(&self.device, self.object_uniform_stride, self.object_capacity);
+            self.model_bind_group = create_model_bind_group(
+                &self.device,
+                &self.model_bind_group_layout,
+                &model_buffer,
+                &material_buffer,
+                &self.default_texture_view,
+                &self.sampler,
+                &self.terrain_textures,
+            );
+            self.model_buffer = model_buffer;
+            self.material_buffer = material_buffer;
+        }
+
+        let stride = self.object_uniform_stride as usize;
+        let mut model_bytes = vec![0u8; objects.len() * stride];
+        let mut material_bytes = vec![0u8; objects.len() * stride];
+        let mut draws = Vec::with_capacity(objects.len());
+        for (slot, (model, material, mesh_id, _, _)) in objects.iter().enumerate() {
+            let start = slot * stride;
+            let model_data = bytemuck::bytes_of(model);
+            let material_data = bytemuck::bytes_of(material);
+            model_bytes[start..start + model_data.len()].copy_from_slice(model_data);
+            material_bytes[start..start + material_data.len()].copy_from_slice(material_data);
+            draws.push(ObjectDraw { mesh_id: *mesh_id, slot });
+        }
+        if !draws.is_empty() {
+            self.queue.write_buffer(&self.model_buffer, 0, &model_bytes);
+            self.queue.write_buffer(&self.material_buffer, 0, &material_bytes);
+        }
+
+        self.object_draw_count = draws.len() as u32;
+        draws
+    }
+
+    /// Запрос захвата следующего кадра через встроенный API RenderDoc
+    pub fn request_frame_capture(&mut self) {
+        self.capture_pending = true;
+    }
+
+    /// Структура кадра: проходы, цели и вызовы отрисовки в порядке исполнения
+    pub fn frame_graph(&self) -> Vec<FrameGraphPass> {
+        let swapchain = FrameGraphTarget {
+            name: "swapchain",
+            width: self.config.width,
+            height: self.config.height,
+            format: self.config.format,
+        };
+        let (render_width, render_height) = self.render_size();
+        let hdr = FrameGraphTarget {
+            name: "hdr",
+            width: render_width,
+            height: render_height,
+            format: HDR_FORMAT,
+        };
+
+        let draws = vec![
+            FrameGraphDraw {
+                pipeline: "Render Pipeline",
+                vertices: self.meshes.iter().map(|mesh| mesh.num_indices.max(mesh.num_vertices)).sum(),
+                instances: self.object_draw_count,
+                enabled: self.object_draw_count > 0,
+            },
+            FrameGraphDraw {
+                pipeline: "Blob Shadow Pipeline",
+                vertices: 6,
+                instances: self.blob_shadow_instance_count,
+                enabled: self.blob_shadow_instance_buffer.is_some() && self.blob_shadow_instance_count > 0 && self.graphics.preset.blob_shadows(),
+            },
+            FrameGraphDraw {
+                pipeline: "Vegetation Pipeline",
+                vertices: 6,
+                instances: self.vegetation_instance_count,
+                enabled: self.vegetation_instance_buffer.is_some() && self.graphics.preset.vegetation(),
+            },
+            FrameGraphDraw {
+                pipeline: "Particle Pipeline",
+                vertices: 6,
+                instances: self.particle_instance_count,
+                enabled: self.particle_instance_buffer.is_some() && self.particle_instance_count > 0,
+            },
+            FrameGraphDraw {
+                pipeline: "World Marker Pipeline",
+                vertices: 6,
+                instances: self.world_marker_instance_count,
+                enabled: self.world_marker_instance_buffer.is_some() && self.world_marker_instance_count > 0,
+            },
+            FrameGraphDraw {
+                pipeline: "Light Cone Pipeline",
+                vertices: self.light_cone_vertex_count,
+                instances: self.light_cone_instance_count,
+                enabled: self.light_cone_instance_count > 0 && self.graphics.preset.light_cones(),
+            },
+            FrameGraphDraw {
+                pipeline: "Debug Line Pipeline",
+                vertices: self.debug_line_vertex_count,
+                instances: 1,
+                enabled: self.debug_line_buffer.is_some() && self.debug_line_vertex_count > 0,
+            },
+            FrameGraphDraw {
+                pipeline: "Rain Overlay Pipeline",
+                vertices: 3,
+                instances: 1,
+                enabled: self.screen_droplets > 0.0,
+            },
+        ];
+
+        let depth = self.depth_texture.as_ref().map(|_| FrameGraphTarget {
+            name: "depth",
+            width: render_width,
+            height: render_height,
+            format: DEPTH_FORMAT,
+        });
+
+        vec![
+            FrameGraphPass {
+                name: "Render Pass",
+                color_targets: vec![hdr],
+                depth_target: depth,
+                draws,
+            },
+            FrameGraphPass {
+                name: "Auto Exposure Pass",
+                color_targets: Vec::new(),
+                depth_target: None,
+                draws: vec![FrameGraphDraw {
+                    pipeline: "Exposure Pipeline",
+                    vertices: 0,
+                    instances: 1,
+                    enabled: true,
+                }],
+            },
+            FrameGraphPass {
+                name: "Tonemap Pass",
+                color_targets: vec![swapchain],
+                depth_target: None,
+                draws: vec![
+                    FrameGraphDraw {
+                        pipeline: "Tonemap Pipeline",
+                        vertices: 3,
+                        instances: 1,
+                        enabled: true,
+                    },
+                    FrameGraphDraw {
+                        pipeline: "HUD Text Pipeline",
+                        vertices: 6,
+                        instances: self.hud_text_instance_count,
+                        enabled: self.hud_text_instance_buffer.is_some() && self.hud_text_instance_count > 0,
+                    },
+                ],
+            },
+        ]
+    }
+
+    /// Статистика последнего кадра для оверлея производительности
+    pub fn stats(&self) -> RenderStats {
+        self.stats
+    }
+
+    /// Текстовый дамп графа кадра для отладки новых проходов
+    pub fn frame_graph_dump(&self) -> String {
+        let mut dump = String::from("Граф кадра:\n");
+        for (index, pass) in self.frame_graph().iter().enumerate() {
+            dump.push_str(&format!("{}. {}\n", index + 1, pass.name));
+            for target in &pass.color_targets {
+                dump.push_str(&format!(
+                    "   color: {} {}x{} {:?}\n",
+                    target.name, target.width, target.height, target.format
+                ));
+            }
+            match &pass.depth_target {
+                Some(target) => dump.push_str(&format!(
+                    "   depth: {} {}x{} {:?}\n",
+                    target.name, target.width, target.height, target.format
+                )),
+                None => dump.push_str("   depth: нет\n"),
+            }
+            for draw in &pass.draws {
+                dump.push_str(&format!(
+                    "   {} {}: {} вершин x {} экземпляров\n",
+                    if draw.enabled { "+" } else { "-" },
+                    draw.pipeline,
+                    draw.vertices,
+                    draw.instances
+                ));
+            }
+        }
+        dump
+    }
+
     // Создание менеджера ресурсов для рендеринга
     pub fn create_resource_manager() -> RenderResourceManager {
         RenderResourceManager::new()
@@ -893,6 +3350,24 @@ struct MaterialUniform {
     roughness: f32,
     ambient_occlusion: f32,
     _padding: f32,
+    emissive: [f32; 3],
+    terrain: f32, // 1 - цвет смешивается из слоев ландшафта
+    layer_colors: [[f32; 4]; 3],
+}
+
+impl From<&MaterialData> for MaterialUniform {
+    fn from(data: &MaterialData) -> Self {
+        Self {
+            base_color: data.base_color,
+            metallic: data.metallic,
+            roughness: data.roughness,
+            ambient_occlusion: 1.0,
+            _padding: 0.0,
+            emissive: data.emissive,
+            terrain: if data.terrain_layers.is_some() { 1.0 } else { 0.0 },
+            layer_colors: data.terrain_layers.unwrap_or([[1.0; 4]; 3]),
+        }
+    }
 }
 
 impl MaterialUniform {
@@ -903,6 +3378,9 @@ impl MaterialUniform {
             roughness: 0.5,
             ambient_occlusion: 1.0,
             _padding: 0.0,
+            emissive: [0.0; 3],
+            terrain: 0.0,
+            layer_colors: [[1.0; 4]; 3],
         }
     }
 }
@@ -913,9 +3391,7 @@ struct LightUniform {
     position: [f32; 3],
     _padding1: f32,
     color: [f32; 3],
-    _padding2: f32,
-    intensity: f32,
-    _padding3: [f32; 3],
+    intensity: f32, // В WGSL f32 занимает хвост vec3 color
 }
 
 impl LightUniform {
@@ -924,9 +3400,467 @@ impl LightUniform {
             position: [0.0, 5.0, -5.0],
             _padding1: 0.0,
             color: [1.0, 1.0, 1.0],
-            _padding2: 0.0,
             intensity: 1.0,
-            _padding3: [0.0, 0.0, 0.0],
         }
     }
-} 
\ No newline at end of file
+}
+
+/// Цвет неба без тумана (более светлый синий, чтобы видеть изменения)
+const SKY_COLOR: Color = Color {
+    r: 0.5,
+    g: 0.5,
+    b: 0.8,
+    a: 1.0,
+};
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+struct FogUniform {
+    color: [f32; 3],
+    density: f32,
+    height_falloff: f32,
+    base_height: f32,
+    _padding: [f32; 2],
+}
+
+impl From<FogSettings> for FogUniform {
+    fn from(fog: FogSettings) -> Self {
+        Self {
+            color: fog.color,
+            density: fog.density,
+            height_falloff: fog.height_falloff,
+            base_height: fog.base_height,
+            _padding: [0.0; 2],
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, bytemuck::Pod, bytemuck::Zeroable)]
+struct SpotLightUniform {
+    position_range: [f32; 4],
+    direction_cos_outer: [f32; 4],
+    color_cos_inner: [f32; 4], // Цвет уже умножен на интенсивность
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+struct SpotLightsUniform {
+    lights: [SpotLightUniform; MAX_SPOT_LIGHTS],
+    count: u32,
+    _padding: [u32; 3],
+}
+
+impl SpotLightsUniform {
+    fn new() -> Self {
+        Self {
+            lights: [SpotLightUniform::default(); MAX_SPOT_LIGHTS],
+            count: 0,
+            _padding: [0; 3],
+        }
+    }
+}
+
+/// Экземпляр видимого конуса света
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+struct LightConeInstance {
+    model: [[f32; 4]; 4],
+    color: [f32; 4], // Альфа задает яркость конуса
+}
+
+impl LightConeInstance {
+    fn desc() -> VertexBufferLayout<'static> {
+        VertexBufferLayout {
+            array_stride: std::mem::size_of::<LightConeInstance>() as BufferAddress,
+            step_mode: VertexStepMode::Instance,
+            attributes: &[
+                VertexAttribute { offset: 0, shader_location: 3, format: VertexFormat::Float32x4 },
+                VertexAttribute { offset: 16, shader_location: 4, format: VertexFormat::Float32x4 },
+                VertexAttribute { offset: 32, shader_location: 5, format: VertexFormat::Float32x4 },
+                VertexAttribute { offset: 48, shader_location: 6, format: VertexFormat::Float32x4 },
+                VertexAttribute { offset: 64, shader_location: 7, format: VertexFormat::Float32x4 },
+            ],
+        }
+    }
+}
+
+/// Боковая поверхность конуса: вершина в начале координат, основание радиуса 1 в z = 1.
+/// tex_coords.x - расстояние от вершины (0..1)
+fn build_light_cone_vertices(segments: usize) -> Vec<Vertex> {
+    let mut vertices = Vec::with_capacity(segments * 3);
+    let ring = |i: usize| {
+        let angle = i as f32 / segments as f32 * std::f32::consts::TAU;
+        (angle.cos(), angle.sin())
+    };
+    for i in 0..segments {
+        let (x0, y0) = ring(i);
+        let (x1, y1) = ring(i + 1);
+        let apex_normal = Vec3::new((x0 + x1) * 0.5, (y0 + y1) * 0.5, -1.0).normalize();
+        vertices.push(Vertex { position: [0.0, 0.0, 0.0], tex_coords: [0.0, 0.0], normal: apex_normal.to_array() });
+        vertices.push(Vertex { position: [x0, y0, 1.0], tex_coords: [1.0, 0.0], normal: Vec3::new(x0, y0, -1.0).normalize().to_array() });
+        vertices.push(Vertex { position: [x1, y1, 1.0], tex_coords: [1.0, 1.0], normal: Vec3::new(x1, y1, -1.0).normalize().to_array() });
+    }
+    vertices
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+struct RainUniform {
+    wetness: f32,
+    droplets: f32,
+    time: f32,
+    aspect: f32,
+}
+
+impl RainUniform {
+    fn new() -> Self {
+        Self {
+            wetness: 0.0,
+            droplets: 0.0,
+            time: 0.0,
+            aspect: 1.0,
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+struct WindUniform {
+    direction: [f32; 2],
+    strength: f32,
+    time: f32,
+}
+
+impl WindUniform {
+    fn new() -> Self {
+        Self {
+            direction: [1.0, 0.0],
+            strength: 0.0,
+            time: 0.0,
+        }
+    }
+}
+
+/// Начальное число слотов объектов в буферах моделей и материалов
+const INITIAL_OBJECT_CAPACITY: usize = 256;
+
+/// Вызов отрисовки объекта сцены
+struct ObjectDraw {
+    mesh_id: usize,
+    slot: usize,
+}
+
+fn create_object_buffers(device: &Device, stride: u64, capacity: usize) -> (Buffer, Buffer) {
+    let model_buffer = device.create_buffer(&BufferDescriptor {
+        label: Some("Model Buffer"),
+        size: stride * capacity as u64,
+        usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    });
+    let material_buffer = device.create_buffer(&BufferDescriptor {
+        label: Some("Material Buffer"),
+        size: stride * capacity as u64,
+        usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    });
+    (model_buffer, material_buffer)
+}
+
+fn create_model_bind_group(
+    device: &Device,
+    layout: &BindGroupLayout,
+    model_buffer: &Buffer,
+    material_buffer: &Buffer,
+    texture_view: &TextureView,
+    sampler: &Sampler,
+    terrain: &TerrainTextures,
+) -> BindGroup {
+    device.create_bind_group(&BindGroupDescriptor {
+        layout,
+        entries: &[
+            BindGroupEntry {
+                binding: 0,
+                resource: BindingResource::Buffer(BufferBinding {
+                    buffer: model_buffer,
+                    offset: 0,
+                    size: BufferSize::new(std::mem::size_of::<ModelUniform>() as u64),
+                }),
+            },
+            BindGroupEntry {
+                binding: 1,
+                resource: BindingResource::Buffer(BufferBinding {
+                    buffer: material_buffer,
+                    offset: 0,
+                    size: BufferSize::new(std::mem::size_of::<MaterialUniform>() as u64),
+                }),
+            },
+            BindGroupEntry {
+                binding: 2,
+                resource: BindingResource::TextureView(texture_view),
+            },
+            BindGroupEntry {
+                binding: 3,
+                resource: BindingResource::Sampler(sampler),
+            },
+            BindGroupEntry {
+                binding: 4,
+                resource: BindingResource::TextureView(&terrain.splat_view),
+            },
+            BindGroupEntry {
+                binding: 5,
+                resource: BindingResource::TextureView(&terrain.detail_view),
+            },
+            BindGroupEntry {
+                binding: 6,
+                resource: BindingResource::Sampler(&terrain.sampler),
+            },
+        ],
+        label: Some("model_bind_group"),
+    })
+}
+
+/// Текстуры смешивания слоев ландшафта в группе объекта
+struct TerrainTextures {
+    splat_view: TextureView,
+    detail_view: TextureView,
+    sampler: Sampler,
+}
+
+/// Загружает карту смешивания и детальные текстуры; без ландшафта - заглушки 1x1
+fn create_terrain_textures(device: &Device, queue: &Queue, data: Option<&TerrainSplatData>) -> TerrainTextures {
+    let (width, depth, weights, detail_size, details) = match data {
+        Some(data) => (data.width, data.depth, data.weights.clone(), data.detail_size, data.details.clone()),
+        None => (1, 1, vec![[255, 0, 0, 0]], 1, vec![255; 3]),
+    };
+
+    let splat_size = Extent3d {
+        width,
+        height: depth,
+        depth_or_array_layers: 1,
+    };
+    let splat = device.create_texture(&TextureDescriptor {
+        label: Some("Terrain Splat Texture"),
+        size: splat_size,
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: TextureDimension::D2,
+        format: TextureFormat::Rgba8Unorm,
+        usage: TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST,
+        view_formats: &[],
+    });
+    queue.write_texture(
+        TexelCopyTextureInfo {
+            texture: &splat,
+            mip_level: 0,
+            origin: Origin3d::ZERO,
+            aspect: TextureAspect::All,
+        },
+        bytemuck::cast_slice(&weights),
+        TexelCopyBufferLayout {
+            offset: 0,
+            bytes_per_row: Some(4 * width),
+            rows_per_image: Some(depth),
+        },
+        splat_size,
+    );
+
+    let detail_extent = Extent3d {
+        width: detail_size,
+        height: detail_size,
+        depth_or_array_layers: 3,
+    };
+    let detail = device.create_texture(&TextureDescriptor {
+        label: Some("Terrain Detail Textures"),
+        size: detail_extent,
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: TextureDimension::D2,
+        format: TextureFormat::R8Unorm,
+        usage: TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST,
+        view_formats: &[],
+    });
+    queue.write_texture(
+        TexelCopyTextureInfo {
+            texture: &detail,
+            mip_level: 0,
+            origin: Origin3d::ZERO,
+            aspect: TextureAspect::All,
+        },
+        &details,
+        TexelCopyBufferLayout {
+            offset: 0,
+            bytes_per_row: Some(detail_size),
+            rows_per_image: Some(detail_size),
+        },
+        detail_extent,
+    );
+
+    TerrainTextures {
+        splat_view: splat.create_view(&TextureViewDescriptor::default()),
+        detail_view: detail.create_view(&TextureViewDescriptor {
+            dimension: Some(TextureViewDimension::D2Array),
+            ..Default::default()
+        }),
+        sampler: device.create_sampler(&SamplerDescriptor {
+            label: Some("Terrain Sampler"),
+            address_mode_u: AddressMode::Repeat,
+            address_mode_v: AddressMode::Repeat,
+            mag_filter: FilterMode::Linear,
+            min_filter: FilterMode::Linear,
+            ..Default::default()
+        }),
+    }
+}
+
+/// Формат буфера глубины
+const DEPTH_FORMAT: TextureFormat = TextureFormat::Depth32Float;
+
+fn depth_state(write: bool, compare: CompareFunction) -> DepthStencilState {
+    DepthStencilState {
+        format: DEPTH_FORMAT,
+        depth_write_enabled: write,
+        depth_compare: compare,
+        stencil: StencilState::default(),
+        bias: DepthBiasState::default(),
+    }
+}
+
+fn create_depth_texture(device: &Device, width: u32, height: u32) -> Texture {
+    let texture = device.create_texture(&TextureDescriptor {
+        label: Some("Depth Texture"),
+        size: Extent3d {
+            width: width.max(1),
+            height: height.max(1),
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: TextureDimension::D2,
+        format: DEPTH_FORMAT,
+        usage: TextureUsages::RENDER_ATTACHMENT | TextureUsages::TEXTURE_BINDING,
+        view_formats: &[],
+    });
+    let view = texture.create_view(&TextureViewDescriptor::default());
+    let sampler = device.create_sampler(&SamplerDescriptor {
+        label: Some("Depth Sampler"),
+        mag_filter: FilterMode::Linear,
+        min_filter: FilterMode::Linear,
+        ..Default::default()
+    });
+    Texture { texture, view, sampler }
+}
+
+/// Формат HDR-цели сцены
+const HDR_FORMAT: TextureFormat = TextureFormat::Rgba16Float;
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+struct ExposureParamsUniform {
+    manual_exposure: f32,
+    auto_exposure: f32,
+    key_value: f32,
+    adaptation: f32,
+    min_luminance: f32,
+    max_luminance: f32,
+    _padding: [f32; 2],
+}
+
+impl ExposureParamsUniform {
+    fn from_settings(settings: &ExposureSettings, delta_time: f32) -> Self {
+        Self {
+            manual_exposure: 2f32.powf(settings.manual_ev),
+            auto_exposure: if settings.mode == ExposureMode::Auto { 1.0 } else { 0.0 },
+            key_value: settings.key_value,
+            adaptation: 1.0 - (-settings.adaptation_speed * delta_time).exp(),
+            min_luminance: settings.min_luminance,
+            max_luminance: settings.max_luminance,
+            _padding: [0.0; 2],
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+struct ExposureStateUniform {
+    adapted_luminance: f32,
+    exposure: f32,
+}
+
+fn create_hdr_texture(device: &Device, width: u32, height: u32) -> Texture {
+    let texture = device.create_texture(&TextureDescriptor {
+        label: Some("HDR Texture"),
+        size: Extent3d {
+            width: width.max(1),
+            height: height.max(1),
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: TextureDimension::D2,
+        format: HDR_FORMAT,
+        usage: TextureUsages::RENDER_ATTACHMENT | TextureUsages::TEXTURE_BINDING,
+        view_formats: &[],
+    });
+    let view = texture.create_view(&TextureViewDescriptor::default());
+    let sampler = device.create_sampler(&SamplerDescriptor {
+        label: Some("HDR Sampler"),
+        mag_filter: FilterMode::Linear,
+        min_filter: FilterMode::Linear,
+        ..Default::default()
+    });
+    Texture { texture, view, sampler }
+}
+
+fn create_exposure_bind_group(
+    device: &Device,
+    layout: &BindGroupLayout,
+    hdr_texture: &Texture,
+    params_buffer: &Buffer,
+    state_buffer: &Buffer,
+) -> BindGroup {
+    device.create_bind_group(&BindGroupDescriptor {
+        layout,
+        entries: &[
+            BindGroupEntry {
+                binding: 0,
+                resource: BindingResource::TextureView(&hdr_texture.view),
+            },
+            BindGroupEntry {
+                binding: 1,
+                resource: params_buffer.as_entire_binding(),
+            },
+            BindGroupEntry {
+                binding: 2,
+                resource: state_buffer.as_entire_binding(),
+            },
+        ],
+        label: Some("exposure_bind_group"),
+    })
+}
+
+fn create_tonemap_bind_group(
+    device: &Device,
+    layout: &BindGroupLayout,
+    hdr_texture: &Texture,
+    state_buffer: &Buffer,
+) -> BindGroup {
+    device.create_bind_group(&BindGroupDescriptor {
+        layout,
+        entries: &[
+            BindGroupEntry {
+                binding: 0,
+                resource: BindingResource::TextureView(&hdr_texture.view),
+            },
+            BindGroupEntry {
+                binding: 1,
+                resource: BindingResource::Sampler(&hdr_texture.sampler),
+            },
+            BindGroupEntry {
+                binding: 2,
+                resource: state_buffer.as_entire_binding(),
+            },
+        ],
+        label: Some("tonemap_bind_group"),
+    })
+}
@@ -0,0 +1,269 @@
+use crate::core::ecs::Resource;
+use ab_glyph::{point, Font, FontVec, PxScale, ScaleFont};
+use hecs::World;
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use wgpu::{BufferAddress, VertexAttribute, VertexBufferLayout, VertexFormat, VertexStepMode};
+
+/// Моноширинный шрифт надписей HUD
+pub const HUD_FONT_PATH: &str = "assets/fonts/DejaVuSansMono.ttf";
+
+/// Высота строки в атласе, пикселей; надписи другого размера масштабируются
+const ATLAS_LINE_HEIGHT: f32 = 32.0;
+
+/// Сторона квадратного атласа глифов, пикселей
+const ATLAS_SIZE: u32 = 512;
+
+/// Отступ между глифами в атласе, чтобы фильтрация не захватывала соседей
+const ATLAS_PADDING: u32 = 1;
+
+/// Смещение тени под надписью в долях высоты строки
+const SHADOW_OFFSET: f32 = 0.06;
+
+/// Знаки HUD сверх латиницы и кириллицы: градусы, стрелки, столбики графика кадров
+const EXTRA_CHARS: &str = "°·—–±…№×→←↑↓▲▼▁▂▃▄▅▆▇█";
+
+/// Выравнивание строки относительно точки привязки
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TextAlign {
+    #[default]
+    Left,
+    Center,
+    Right,
+}
+
+/// Строка надписи HUD; положение в долях экрана от левого верхнего угла
+#[derive(Debug, Clone)]
+pub struct HudTextLine {
+    pub text: String,
+    pub position: [f32; 2],
+    pub size: f32, // Высота строки в долях высоты экрана
+    pub color: [f32; 4],
+    pub align: TextAlign,
+}
+
+impl HudTextLine {
+    pub fn new(text: impl Into<String>, position: [f32; 2], size: f32) -> Self {
+        Self {
+            text: text.into(),
+            position,
+            size,
+            color: [1.0, 1.0, 1.0, 1.0],
+            align: TextAlign::Left,
+        }
+    }
+
+    pub fn color(mut self, color: [f32; 4]) -> Self {
+        self.color = color;
+        self
+    }
+
+    pub fn align(mut self, align: TextAlign) -> Self {
+        self.align = align;
+        self
+    }
+}
+
+/// Надписи HUD поверх кадра. Как и в `DebugDraw`, каждая система пишет свой слой
+/// и заменяет его целиком
+#[derive(Debug, Clone, Default)]
+pub struct HudText {
+    layers: BTreeMap<&'static str, Vec<HudTextLine>>,
+}
+
+impl HudText {
+    pub fn set_layer(&mut self, layer: &'static str, lines: Vec<HudTextLine>) {
+        if lines.is_empty() {
+            self.layers.remove(layer);
+        } else {
+            self.layers.insert(layer, lines);
+        }
+    }
+
+    /// Строки всех слоев в порядке имен слоев
+    pub fn lines(&self) -> impl Iterator<Item = &HudTextLine> {
+        self.layers.values().flatten()
+    }
+}
+
+/// Заменяет слой надписей HUD; пустой список убирает слой
+pub fn set_hud_text(world: &mut World, layer: &'static str, lines: Vec<HudTextLine>) {
+    match world.query_mut::<&mut Resource<HudText>>().into_iter().next() {
+        Some((_, hud_text)) => hud_text.0.set_layer(layer, lines),
+        None => {
+            if !lines.is_empty() {
+                let mut hud_text = HudText::default();
+                hud_text.set_layer(layer, lines);
+                world.spawn((Resource(hud_text),));
+            }
+        }
+    }
+}
+
+/// Экземпляр глифа: прямоугольник на экране в пикселях, область атласа и цвет
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct GlyphInstance {
+    pub rect: [f32; 4], // x, y, ширина, высота
+    pub uv: [f32; 4],   // u0, v0, u1, v1
+    pub color: [f32; 4],
+}
+
+impl GlyphInstance {
+    pub(crate) fn desc() -> VertexBufferLayout<'static> {
+        VertexBufferLayout {
+            array_stride: std::mem::size_of::<GlyphInstance>() as BufferAddress,
+            step_mode: VertexStepMode::Instance,
+            attributes: &[
+                VertexAttribute {
+                    offset: 0,
+                    shader_location: 0,
+                    format: VertexFormat::Float32x4,
+                },
+                VertexAttribute {
+                    offset: std::mem::size_of::<[f32; 4]>() as BufferAddress,
+                    shader_location: 1,
+                    format: VertexFormat::Float32x4,
+                },
+                VertexAttribute {
+                    offset: std::mem::size_of::<[f32; 8]>() as BufferAddress,
+                    shader_location: 2,
+                    format: VertexFormat::Float32x4,
+                },
+            ],
+        }
+    }
+}
+
+/// Глиф в атласе; смещение от верхнего левого угла знакоместа, пиксели атласа
+#[derive(Debug, Clone, Copy)]
+struct AtlasGlyph {
+    uv: [f32; 4],
+    offset: [f32; 2],
+    size: [f32; 2],
+}
+
+/// Атлас глифов моноширинного шрифта: латиница, кириллица и знаки HUD растеризуются один раз
+pub struct GlyphAtlas {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>, // Покрытие, один байт на пиксель
+    glyphs: HashMap<char, AtlasGlyph>,
+    advance: f32, // Ширина знакоместа, пиксели атласа
+}
+
+impl GlyphAtlas {
+    /// Атлас без глифов: надписи не рисуются, если шрифт не загрузился
+    pub fn empty() -> Self {
+        Self {
+            width: 1,
+            height: 1,
+            pixels: vec![0],
+            glyphs: HashMap::new(),
+            advance: ATLAS_LINE_HEIGHT * 0.6,
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let bytes = std::fs::read(path).map_err(|e| e.to_string())?;
+        let font = FontVec::try_from_vec(bytes).map_err(|e| e.to_string())?;
+        Ok(Self::build(&font))
+    }
+
+    pub fn build(font: &impl Font) -> Self {
+        let scale = PxScale::from(ATLAS_LINE_HEIGHT);
+        let scaled = font.as_scaled(scale);
+        // Строка помещается в ATLAS_LINE_HEIGHT: базовая линия на высоте верхнего выноса
+        let ascent = scaled.ascent() * ATLAS_LINE_HEIGHT / scaled.height();
+
+        let chars = (' '..='~').chain('А'..='я').chain(['Ё', 'ё']).chain(EXTRA_CHARS.chars());
+        let mut atlas = Self {
+            width: ATLAS_SIZE,
+            height: ATLAS_SIZE,
+            pixels: vec![0; (ATLAS_SIZE * ATLAS_SIZE) as usize],
+            glyphs: HashMap::new(),
+            advance: scaled.h_advance(font.glyph_id('M')),
+        };
+        let (mut x, mut y, mut row_height) = (0u32, 0u32, 0u32);
+        for c in chars {
+            let glyph = font.glyph_id(c).with_scale_and_position(scale, point(0.0, ascent));
+            let Some(outline) = font.outline_glyph(glyph) else {
+                continue; // Пробел и другие знаки без контура
+            };
+            let bounds = outline.px_bounds();
+            let (w, h) = (bounds.width().ceil() as u32, bounds.height().ceil() as u32);
+            if x + w + ATLAS_PADDING > atlas.width {
+                x = 0;
+                y += row_height + ATLAS_PADDING;
+                row_height = 0;
+            }
+            if y + h > atlas.height {
+                eprintln!("Атлас шрифта переполнен, знак '{}' не поместился", c);
+                break;
+            }
+            let stride = atlas.width;
+            let pixels = &mut atlas.pixels;
+            outline.draw(|gx, gy, coverage| {
+                let index = ((y + gy) * stride + x + gx) as usize;
+                if let Some(pixel) = pixels.get_mut(index) {
+                    *pixel = (coverage.clamp(0.0, 1.0) * 255.0) as u8;
+                }
+            });
+            let size = atlas.width as f32;
+            atlas.glyphs.insert(
+                c,
+                AtlasGlyph {
+                    uv: [x as f32 / size, y as f32 / size, (x + w) as f32 / size, (y + h) as f32 / size],
+                    offset: [bounds.min.x, bounds.min.y],
+                    size: [w as f32, h as f32],
+                },
+            );
+            x += w + ATLAS_PADDING;
+            row_height = row_height.max(h);
+        }
+        atlas
+    }
+
+    /// Глифы строки с тенью для экрана заданного размера в пикселях
+    pub fn layout(&self, line: &HudTextLine, screen_width: f32, screen_height: f32) -> Vec<GlyphInstance> {
+        let line_height = line.size * screen_height;
+        let k = line_height / ATLAS_LINE_HEIGHT;
+        let shadow = line_height * SHADOW_OFFSET;
+        let shadow_color = [0.0, 0.0, 0.0, line.color[3] * 0.7];
+
+        let mut instances = Vec::new();
+        for (row, text) in line.text.lines().enumerate() {
+            let width = text.chars().count() as f32 * self.advance * k;
+            let left = line.position[0] * screen_width
+                - match line.align {
+                    TextAlign::Left => 0.0,
+                    TextAlign::Center => width / 2.0,
+                    TextAlign::Right => width,
+                };
+            let top = line.position[1] * screen_height + row as f32 * line_height;
+            let mut glyphs = Vec::new();
+            for (i, c) in text.chars().enumerate() {
+                let Some(glyph) = self.glyphs.get(&c).or_else(|| (c != ' ').then(|| self.glyphs.get(&'?')).flatten()) else {
+                    continue;
+                };
+                glyphs.push((left + i as f32 * self.advance * k + glyph.offset[0] * k, top + glyph.offset[1] * k, glyph));
+            }
+            // Сначала тени всей строки, затем сами знаки поверх
+            for (x, y, glyph) in &glyphs {
+                instances.push(GlyphInstance {
+                    rect: [x + shadow, y + shadow, glyph.size[0] * k, glyph.size[1] * k],
+                    uv: glyph.uv,
+                    color: shadow_color,
+                });
+            }
+            for (x, y, glyph) in glyphs {
+                instances.push(GlyphInstance {
+                    rect: [x, y, glyph.size[0] * k, glyph.size[1] * k],
+                    uv: glyph.uv,
+                    color: line.color,
+                });
+            }
+        }
+        instances
+    }
+}
@@ -0,0 +1,140 @@
+use crate::core::ecs::{System, Resource};
+use crate::core::physics::TransformComponent;
+use crate::core::renderer::{RenderComponent, RenderResourceManager};
+use glam::{Quat, Vec3};
+use hecs::World;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Описание одного рекламного щита в манифесте трассы
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AdBoardDefinition {
+    pub position: [f32; 3],
+    #[serde(default)]
+    pub rotation_y: f32,             // Градусы вокруг вертикали
+    pub size: [f32; 2],              // Ширина и высота щита, м
+    pub textures: Vec<String>,       // Кадры; больше одного - щит переключается
+    #[serde(default = "default_frame_time")]
+    pub frame_time: f32,             // Секунд на кадр
+}
+
+fn default_frame_time() -> f32 {
+    5.0
+}
+
+/// Манифест рекламы трассы (JSON рядом с файлом трассы)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AdvertisingManifest {
+    pub boards: Vec<AdBoardDefinition>,
+}
+
+impl AdvertisingManifest {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+        Self::parse(&text)
+    }
+
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let manifest: Self = serde_json::from_str(text).map_err(|e| e.to_string())?;
+        for (i, board) in manifest.boards.iter().enumerate() {
+            if board.textures.is_empty() {
+                return Err(format!("Ad board {} has no textures", i));
+            }
+            if board.size[0] <= 0.0 || board.size[1] <= 0.0 {
+                return Err(format!("Ad board {} has invalid size", i));
+            }
+        }
+        Ok(manifest)
+    }
+}
+
+/// Настройки показа рекламы ("чистый" режим без щитов)
+#[derive(Debug, Clone, Copy)]
+pub struct AdvertisingSettings {
+    pub show_ads: bool,
+}
+
+impl Default for AdvertisingSettings {
+    fn default() -> Self {
+        Self { show_ads: true }
+    }
+}
+
+/// Компонент рекламного щита с анимированной сменой изображений
+pub struct AdBoardComponent {
+    pub frame_materials: Vec<usize>,
+    pub frame_time: f32,
+    pub current_frame: usize,
+    pub timer: f32,
+}
+
+/// Создает щиты из манифеста; каждый кадр щита - отдельный материал
+pub fn create_ad_boards(
+    world: &mut World,
+    render_resources: &mut RenderResourceManager,
+    manifest: &AdvertisingManifest,
+) -> Vec<hecs::Entity> {
+    let mesh_id = render_resources.add_simple_cube();
+
+    manifest
+        .boards
+        .iter()
+        .map(|board| {
+            let frame_materials: Vec<usize> = board
+                .textures
+                .iter()
+                .map(|path| render_resources.add_textured_material(path))
+                .collect();
+
+            let transform = TransformComponent {
+                // Позиция задает середину нижнего края щита
+                position: Vec3::from(board.position) + Vec3::Y * board.size[1] / 2.0,
+                rotation: Quat::from_rotation_y(board.rotation_y.to_radians()),
+                ..Default::default()
+            };
+            let render = RenderComponent {
+                mesh_id,
+                material_id: frame_materials[0],
+                visible: true,
+                scale: Vec3::new(board.size[0], board.size[1], 0.1),
+                opacity: 1.0,
+            };
+            let ad_board = AdBoardComponent {
+                frame_materials,
+                frame_time: board.frame_time.max(0.1),
+                current_frame: 0,
+                timer: 0.0,
+            };
+
+            world.spawn((transform, render, ad_board))
+        })
+        .collect()
+}
+
+/// Система смены изображений на щитах и переключения "чистого" режима
+pub struct AdBoardSystem;
+
+impl System for AdBoardSystem {
+    fn update(&mut self, world: &mut World, delta_time: f32) {
+        let show_ads = world
+            .query::<&Resource<AdvertisingSettings>>()
+            .iter()
+            .next()
+            .map(|(_, res)| res.0.show_ads)
+            .unwrap_or(true);
+
+        for (_, (board, render)) in world.query_mut::<(&mut AdBoardComponent, &mut RenderComponent)>() {
+            render.visible = show_ads;
+            if !show_ads || board.frame_materials.len() < 2 {
+                continue;
+            }
+
+            board.timer += delta_time;
+            while board.timer >= board.frame_time {
+                board.timer -= board.frame_time;
+                board.current_frame = (board.current_frame + 1) % board.frame_materials.len();
+            }
+            render.material_id = board.frame_materials[board.current_frame];
+        }
+    }
+}
@@ -0,0 +1,79 @@
+use crate::core::ecs::{System, Resource};
+use crate::core::physics::TransformComponent;
+use crate::core::renderer::{BlobShadowInstance, BlobShadowRenderData};
+use crate::game_world::track::ground_at;
+use glam::{Vec2, Vec3};
+use hecs::World;
+
+/// Компонент дешевой тени-пятна под объектом
+pub struct BlobShadowComponent {
+    pub half_extents: Vec2,  // Половины ширины и длины пятна
+    pub opacity: f32,
+    pub rest_height: f32,    // Высота центра объекта над землей в покое
+    pub fade_height: f32,    // Подъем над покоем, при котором тень исчезает
+}
+
+impl Default for BlobShadowComponent {
+    fn default() -> Self {
+        Self {
+            half_extents: Vec2::new(1.1, 2.2),
+            opacity: 0.6,
+            rest_height: 0.6,
+            fade_height: 4.0,
+        }
+    }
+}
+
+/// Система, проецирующая пятна теней на землю под объектами
+pub struct BlobShadowSystem;
+
+impl System for BlobShadowSystem {
+    fn update(&mut self, world: &mut World, _delta_time: f32) {
+        let mut instances = Vec::new();
+
+        for (_, (shadow, transform)) in world.query::<(&BlobShadowComponent, &TransformComponent)>().iter() {
+            let Some((ground_height, normal)) = ground_at(world, transform.position) else {
+                continue;
+            };
+
+            // При отрыве от земли (прыжок, ход подвески) тень бледнеет и расплывается
+            let lift = (transform.position.y - ground_height - shadow.rest_height).max(0.0);
+            let fade = 1.0 - (lift / shadow.fade_height).clamp(0.0, 1.0);
+            if fade <= 0.0 {
+                continue;
+            }
+            let spread = 1.0 + 0.3 * (1.0 - fade);
+
+            // Оси кузова, спроецированные на плоскость земли
+            let project = |axis: Vec3, fallback: Vec3| {
+                let projected = axis - normal * axis.dot(normal);
+                projected.try_normalize().unwrap_or(fallback)
+            };
+            let forward = project(transform.rotation * Vec3::Z, Vec3::Z);
+            let right = normal.cross(forward).normalize_or_zero();
+
+            let center = Vec3::new(transform.position.x, ground_height, transform.position.z);
+            let axis_x = right * shadow.half_extents.x * spread;
+            let axis_z = forward * shadow.half_extents.y * spread;
+
+            instances.push(BlobShadowInstance {
+                center_opacity: [center.x, center.y, center.z, shadow.opacity * fade],
+                axis_x: [axis_x.x, axis_x.y, axis_x.z, 0.0],
+                axis_z: [axis_z.x, axis_z.y, axis_z.z, 0.0],
+            });
+        }
+
+        let render_data = world
+            .query_mut::<&mut Resource<BlobShadowRenderData>>()
+            .into_iter()
+            .next()
+            .map(|(_, res)| &mut res.0);
+
+        match render_data {
+            Some(data) => data.instances = instances,
+            None => {
+                world.spawn((Resource(BlobShadowRenderData { instances }),));
+            }
+        }
+    }
+}
@@ -0,0 +1,304 @@
+use crate::core::ecs::{System, Resource};
+use crate::core::input::InputAction;
+use crate::core::physics::{sphere_cast, SceneQueryFilter, TransformComponent};
+use crate::core::renderer::{CameraComponent, RenderComponent};
+use crate::game_world::track::ground_at;
+use crate::gameplay::celebration::celebration_active;
+use crate::gameplay::track_editor::track_editor_active;
+use crate::gameplay::race::{current_race, RacePhase};
+use crate::gameplay::rules::{current_rules, CameraRule};
+use glam::{Vec3, Quat};
+use hecs::World;
+use std::collections::HashMap;
+
+/// Вид камеры относительно автомобиля
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CameraView {
+    Chase,
+    Hood,
+    Cockpit,
+}
+
+impl CameraView {
+    pub fn next(self) -> Self {
+        match self {
+            CameraView::Chase => CameraView::Hood,
+            CameraView::Hood => CameraView::Cockpit,
+            CameraView::Cockpit => CameraView::Chase,
+        }
+    }
+
+    /// Смещения глаза и точки взгляда в локальных координатах автомобиля
+    pub fn offsets(self) -> (Vec3, Vec3) {
+        match self {
+            CameraView::Chase => (Vec3::new(0.0, 2.5, -7.0), Vec3::new(0.0, 1.0, 4.0)),
+            CameraView::Hood => (Vec3::new(0.0, 1.2, 1.0), Vec3::new(0.0, 1.0, 10.0)),
+            CameraView::Cockpit => (Vec3::new(-0.35, 1.05, 0.0), Vec3::new(-0.35, 1.0, 10.0)),
+        }
+    }
+}
+
+/// Сплайн Катмулла-Рома для пролетов камеры
+#[derive(Debug, Clone)]
+pub struct CameraSpline {
+    pub points: Vec<Vec3>,
+}
+
+impl CameraSpline {
+    pub fn new(points: Vec<Vec3>) -> Self {
+        Self { points }
+    }
+
+    /// Точка на сплайне, t от 0 (первая точка) до 1 (последняя)
+    pub fn sample(&self, t: f32) -> Vec3 {
+        match self.points.len() {
+            0 => return Vec3::ZERO,
+            1 => return self.points[0],
+            _ => {}
+        }
+        let segments = self.points.len() - 1;
+        let scaled = t.clamp(0.0, 1.0) * segments as f32;
+        let i = (scaled.floor() as usize).min(segments - 1);
+        let local = scaled - i as f32;
+
+        // Крайние сегменты используют продолженные концы
+        let p1 = self.points[i];
+        let p2 = self.points[i + 1];
+        let p0 = if i > 0 { self.points[i - 1] } else { p1 * 2.0 - p2 };
+        let p3 = if i + 2 < self.points.len() { self.points[i + 2] } else { p2 * 2.0 - p1 };
+
+        let t2 = local * local;
+        let t3 = t2 * local;
+        0.5 * (2.0 * p1
+            + (p2 - p0) * local
+            + (2.0 * p0 - 5.0 * p1 + 4.0 * p2 - p3) * t2
+            + (3.0 * p1 - p0 - 3.0 * p2 + p3) * t3)
+    }
+}
+
+/// Компонент, привязывающий камеру к автомобилю
+pub struct CameraRigComponent {
+    pub target: Option<hecs::Entity>,
+    pub view: CameraView,
+    pub follow_speed: f32,
+    pub toggle_held: bool,
+    pub rain_droplets: bool, // Капли дождя на экране (можно отключить в настройках)
+    pub collision: CameraCollision,
+}
+
+/// Настройки обхода препятствий внешней камерой
+#[derive(Debug, Clone)]
+pub struct CameraCollision {
+    pub enabled: bool,
+    pub radius: f32,         // Радиус сферы вокруг камеры, не дающий ближней плоскости резать стены
+    pub margin: f32,         // Зазор между камерой и препятствием
+    pub min_distance: f32,   // Ближе к машине камера не подходит
+    pub return_speed: f32,   // Скорость возврата на обычную дистанцию
+    pub fade_distance: f32,  // С этой дистанции кузов начинает становиться прозрачным
+    pub min_opacity: f32,
+    pub current_distance: f32, // Текущая допустимая дистанция от опорной точки
+}
+
+impl Default for CameraCollision {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            radius: 0.25,
+            margin: 0.1,
+            min_distance: 1.0,
+            return_speed: 2.5,
+            fade_distance: 2.5,
+            min_opacity: 0.2,
+            current_distance: f32::MAX,
+        }
+    }
+}
+
+/// Опорная точка внешней камеры над центром машины
+const CHASE_PIVOT: Vec3 = Vec3::new(0.0, 1.2, 0.0);
+
+impl Default for CameraRigComponent {
+    fn default() -> Self {
+        Self {
+            target: None,
+            view: CameraView::Chase,
+            follow_speed: 8.0,
+            toggle_held: false,
+            rain_droplets: true,
+            collision: CameraCollision::default(),
+        }
+    }
+}
+
+/// Система управления камерой
+pub struct CameraRigSystem;
+
+impl System for CameraRigSystem {
+    fn update(&mut self, world: &mut World, delta_time: f32) {
+        // Во время заставки камерой управляет система заставки
+        if matches!(current_race(world), Some((RacePhase::Intro, _))) {
+            return;
+        }
+        // Облетом камеры во время празднования после финиша управляет система празднования
+        if celebration_active(world) {
+            return;
+        }
+        // В редакторе трассы камерой облета управляет редактор
+        if track_editor_active(world) {
+            return;
+        }
+        
+        let toggle_pressed = world
+            .query::<&Resource<HashMap<InputAction, f32>>>()
+            .iter()
+            .next()
+            .map(|(_, res)| *res.0.get(&InputAction::ToggleCamera).unwrap_or(&0.0) > 0.5)
+            .unwrap_or(false);
+        let look_back = world
+            .query::<&Resource<HashMap<InputAction, f32>>>()
+            .iter()
+            .next()
+            .map(|(_, res)| *res.0.get(&InputAction::LookBack).unwrap_or(&0.0) > 0.5)
+            .unwrap_or(false);
+
+        let rules = current_rules(world);
+
+        // Сначала собираем положения целей, чтобы не заимствовать мир дважды
+        let targets: HashMap<hecs::Entity, (Vec3, Quat)> = world
+            .query::<&CameraRigComponent>()
+            .iter()
+            .filter_map(|(_, rig)| rig.target)
+            .filter_map(|target| {
+                world
+                    .get::<&TransformComponent>(target)
+                    .ok()
+                    .map(|t| (target, (t.position, t.rotation)))
+            })
+            .collect();
+
+        // Внешние камеры, которые нужно проверить на препятствия
+        let mut chase_cameras = Vec::new();
+
+        for (entity, (camera, rig)) in world.query_mut::<(&mut CameraComponent, &mut CameraRigComponent)>() {
+            if toggle_pressed && !rig.toggle_held {
+                rig.view = rig.view.next();
+            }
+            rig.toggle_held = toggle_pressed;
+
+            if rules.camera == CameraRule::CockpitOnly {
+                rig.view = CameraView::Cockpit;
+            }
+
+            let Some((position, rotation)) = rig.target.and_then(|t| targets.get(&t).copied()) else {
+                continue;
+            };
+
+            let (mut eye_offset, mut look_offset) = rig.view.offsets();
+            if look_back {
+                // Взгляд назад: отражаем смещения вдоль продольной оси машины
+                eye_offset.z = -eye_offset.z;
+                look_offset.z = -look_offset.z;
+            }
+            let desired_eye = position + rotation * eye_offset;
+            let desired_target = position + rotation * look_offset;
+
+            // Внешняя камера следует плавно, внутренние жестко закреплены;
+            // при взгляде назад камера переносится сразу, без пролета сквозь машину
+            if rig.view == CameraView::Chase && !look_back {
+                let t = (rig.follow_speed * delta_time).min(1.0);
+                camera.position = camera.position.lerp(desired_eye, t);
+            } else {
+                camera.position = desired_eye;
+            }
+            camera.target = desired_target;
+            camera.up = rotation * Vec3::Y;
+
+            if rig.view == CameraView::Chase && rig.collision.enabled {
+                chase_cameras.push((entity, position + rotation * CHASE_PIVOT, camera.position));
+            } else {
+                rig.collision.current_distance = f32::MAX;
+            }
+        }
+
+        // Подтягиваем камеру к машине, если между ними стена или рельеф
+        let mut corrections = Vec::new();
+        for (entity, pivot, eye) in chase_cameras {
+            let offset = eye - pivot;
+            let full_distance = offset.length();
+            if full_distance <= f32::EPSILON {
+                continue;
+            }
+            let direction = offset / full_distance;
+            let Ok(rig) = world.get::<&CameraRigComponent>(entity) else {
+                continue;
+            };
+            let collision = rig.collision.clone();
+            drop(rig);
+
+            // Сфера ведется от машины к камере, поэтому тонкие столбы и края стен не проскакивают между лучами;
+            // препятствием считается только неподвижная геометрия: дорога, отбойники, ландшафт
+            let allowed = sphere_cast(
+                world,
+                pivot,
+                collision.radius,
+                direction,
+                full_distance + collision.margin,
+                SceneQueryFilter::static_only(),
+            )
+            .map(|hit| (hit.distance - collision.margin).max(collision.min_distance))
+            .unwrap_or(full_distance);
+
+            // Приближаемся сразу, чтобы не заглядывать за стену, а отходим плавно
+            let distance = if allowed < collision.current_distance {
+                allowed
+            } else {
+                let t = (collision.return_speed * delta_time).min(1.0);
+                let current = collision.current_distance.min(full_distance);
+                current + (allowed - current) * t
+            };
+
+            let mut corrected = pivot + direction * distance.min(full_distance);
+            if let Some((ground, _)) = ground_at(world, corrected) {
+                corrected.y = corrected.y.max(ground + collision.radius + collision.margin);
+            }
+            corrections.push((entity, corrected, distance));
+        }
+
+        for (entity, corrected, distance) in corrections {
+            if let Ok((camera, rig)) = world.query_one_mut::<(&mut CameraComponent, &mut CameraRigComponent)>(entity) {
+                camera.position = corrected;
+                rig.collision.current_distance = distance;
+            }
+        }
+
+        self.fade_targets(world);
+    }
+}
+
+impl CameraRigSystem {
+    /// Делает кузов полупрозрачным, когда камера подошла к нему вплотную
+    fn fade_targets(&self, world: &mut World) {
+        let fades: Vec<(hecs::Entity, f32)> = world
+            .query::<(&CameraComponent, &CameraRigComponent)>()
+            .iter()
+            .filter_map(|(_, (camera, rig))| {
+                let target = rig.target?;
+                if rig.view != CameraView::Chase || !rig.collision.enabled {
+                    return Some((target, 1.0));
+                }
+                let car_position = world.get::<&TransformComponent>(target).ok()?.position;
+                let collision = &rig.collision;
+                let distance = camera.position.distance(car_position + CHASE_PIVOT);
+                let span = (collision.fade_distance - collision.min_distance).max(f32::EPSILON);
+                let opacity = ((distance - collision.min_distance) / span).clamp(collision.min_opacity, 1.0);
+                Some((target, opacity))
+            })
+            .collect();
+
+        for (target, opacity) in fades {
+            if let Ok(render) = world.query_one_mut::<&mut RenderComponent>(target) {
+                render.opacity = opacity;
+            }
+        }
+    }
+}
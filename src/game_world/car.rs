@@ -1,12 +1,162 @@
-use crate::core::ecs::{System, Resource};
-use crate::core::physics::{RigidBodyComponent, ColliderComponent, RigidBodyType, TransformComponent, ColliderShapeType};
-use crate::core::input::{InputAction};
+use crate::core::audio::{AudioEvent, AudioSourceComponent};
+use crate::core::ecs::{EventQueue, System, Resource};
+use crate::core::hierarchy::{child_world_transform, ParentComponent};
+use crate::core::physics::{physics_materials, teleport_body, RigidBodyComponent, TransformComponent};
+use crate::core::input::{ControlScheme, InputAction};
+use crate::core::renderer::{FogSettings, SpotLightComponent};
+use crate::game_world::damage::CarDamage;
+use crate::game_world::engine_thermal::{overheat_power, OPERATING_ENGINE_TEMPERATURE};
+use crate::game_world::forced_induction::ForcedInduction;
+use crate::game_world::environment::{wind_velocity, PuddleField, TimeOfDayComponent, WeatherComponent, WeatherType};
+use crate::game_world::hazards::{zones_ahead, HazardState, HazardZone};
+use crate::game_world::track::{nearest_track_pose, on_track, surface_at, CheckpointComponent, SurfaceType};
+use crate::gameplay::race::{current_race, RacePhase};
+use crate::gameplay::rules::{current_rules, TransmissionRule};
 use glam::{Vec3, Quat};
 use hecs::World;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use rapier3d::prelude::{RigidBodySet, ColliderSet, RigidBodyBuilder, ColliderBuilder};
+use std::path::Path;
+use rapier3d::prelude::{MassProperties, RigidBodySet, ColliderSet, QueryFilter, QueryPipeline, Ray};
 use rapier3d::math::Vector;
-use rapier3d::na::Vector3;
+use rapier3d::na::{Point3, Vector3};
+
+/// Схема привода: какие оси получают момент двигателя
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DrivetrainType {
+    Fwd,
+    #[default]
+    Rwd,
+    Awd { front_split: f32 }, // Доля момента на передней оси, 0..1
+}
+
+impl DrivetrainType {
+    /// Доля момента двигателя на передней или задней оси
+    pub fn axle_share(&self, front: bool) -> f32 {
+        let front_share = match self {
+            DrivetrainType::Fwd => 1.0,
+            DrivetrainType::Rwd => 0.0,
+            DrivetrainType::Awd { front_split } => front_split.clamp(0.0, 1.0),
+        };
+        if front { front_share } else { 1.0 - front_share }
+    }
+}
+
+/// Файл с настройками помощников водителя
+pub const DRIVER_ASSISTS_PATH: &str = "config/assists.json";
+
+/// Уровень помощника: чем выше, тем раньше он вмешивается
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum AssistLevel {
+    #[default]
+    Off,
+    Low,
+    High,
+}
+
+impl AssistLevel {
+    /// Проскальзывание колеса, выше которого помощник ослабляет тормоз или тягу
+    pub fn slip_limit(&self) -> Option<f32> {
+        match self {
+            AssistLevel::Off => None,
+            AssistLevel::Low => Some(0.3),
+            AssistLevel::High => Some(0.1),
+        }
+    }
+
+    /// Допустимое расхождение скорости рыскания с заданной рулем, рад/с
+    pub fn yaw_tolerance(&self) -> Option<f32> {
+        match self {
+            AssistLevel::Off => None,
+            AssistLevel::Low => Some(0.15),
+            AssistLevel::High => Some(0.05),
+        }
+    }
+}
+
+/// Помощники водителя: ABS не дает колесам блокироваться, контроль тяги — буксовать,
+/// стабилизация — уходить в занос или снос, лаунч-контроль помогает стартовать с места
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DriverAssists {
+    pub abs: AssistLevel,
+    pub traction_control: AssistLevel,
+    pub stability_control: AssistLevel,
+    pub launch_control: bool,
+}
+
+/// Состояние лаунч-контроля
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LaunchControlState {
+    #[default]
+    Inactive,
+    Armed,     // Стоит на тормозе с полным газом: обороты удерживаются, сцепление выжато
+    Launching, // Тормоз отпущен: сцепление держит обороты, контроль тяги ограничивает буксование
+}
+
+impl DriverAssists {
+    /// Все помощники на максимуме: так едет схема «только руль»
+    pub fn full() -> Self {
+        Self {
+            abs: AssistLevel::High,
+            traction_control: AssistLevel::High,
+            stability_control: AssistLevel::High,
+            launch_control: true,
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+        serde_json::from_str(&text).map_err(|e| e.to_string())
+    }
+}
+
+/// Дифференциал ведущей оси: сколько момента он передает с быстрого колеса на медленное
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DifferentialType {
+    #[default]
+    Open,
+    LimitedSlip { preload: f32, lock_ratio: f32 }, // Момент блокировки: преднатяг, Н·м, плюс доля входного момента
+    Locked,
+}
+
+impl DifferentialType {
+    /// Наибольший момент, который дифференциал удерживает между колесами, Н·м
+    pub fn locking_torque(&self, input_torque: f32) -> f32 {
+        match self {
+            DifferentialType::Open => 0.0,
+            DifferentialType::LimitedSlip { preload, lock_ratio } => preload + lock_ratio * input_torque.abs(),
+            DifferentialType::Locked => f32::INFINITY,
+        }
+    }
+
+    /// Делит тягу оси между колесами, Н. grip — предел тяги каждого колеса по сцеплению,
+    /// lock_force — сила, которая уравняла бы скорости колес (плюс, если первое быстрее).
+    /// Возвращает тягу колес и долю, на которую дифференциал сближает их скорости
+    pub fn split(&self, axle_force: f32, grip: [f32; 2], lock_force: f32, radius: f32) -> ([f32; 2], f32) {
+        let capacity = self.locking_torque(axle_force * radius) / radius.max(0.01);
+        let half = axle_force / 2.0;
+        let (weak, strong) = if grip[0] <= grip[1] { (0, 1) } else { (1, 0) };
+        let mut forces = [half, half];
+        
+        // Слабое колесо буксует: второе получает его реакцию и сверх нее не больше момента блокировки
+        if half.abs() > grip[weak] {
+            let strong_force = (grip[weak] + capacity).min(axle_force.abs() - grip[weak]) * axle_force.signum();
+            forces[strong] = strong_force;
+            forces[weak] = axle_force - strong_force;
+            return (forces, 0.0);
+        }
+        
+        // В повороте блокировка тормозит быстрое колесо и подгоняет медленное
+        let transfer = lock_force.clamp(-capacity, capacity);
+        forces[0] -= transfer / 2.0;
+        forces[1] += transfer / 2.0;
+        let lock = if lock_force.abs() > f32::EPSILON { transfer / lock_force } else { 1.0 };
+        (forces, lock)
+    }
+}
 
 /// Компонент автомобиля
 pub struct CarComponent {
@@ -14,28 +164,64 @@ pub struct CarComponent {
     pub mass: f32,
     pub max_engine_force: f32,
     pub max_brake_force: f32,
+    pub brake_bias: f32, // Доля тормозного усилия на передней оси
     pub max_steering_angle: f32,
     pub steering_speed: f32,
     pub wheel_base: f32,
     pub engine_position: Vec3,
-    pub center_of_mass: Vec3,
+    pub center_of_mass: Vec3, // Относительно центра кузова: задает развесовку по осям
+    pub inertia: Vec3,        // Главные моменты инерции вокруг осей X (тангаж), Y (рыскание), Z (крен), кг·м²
+    
+    // Аэродинамика
+    pub drag_coefficient: f32,      // Cx кузова
+    pub downforce_coefficient: f32, // Коэффициент прижимной силы
+    pub frontal_area: f32,          // Площадь миделя, м²
+    pub side_area: f32,             // Площадь борта для бокового ветра, м²
+    pub side_force_coefficient: f32,
+    
+    // Стабилизаторы поперечной устойчивости: жесткость в Н/м разницы сжатия левого и правого колеса оси.
+    // Жестче спереди — больше недостаточная поворачиваемость, жестче сзади — избыточная
+    pub front_anti_roll_stiffness: f32,
+    pub rear_anti_roll_stiffness: f32,
     
     // Текущие состояния
     pub current_speed: f32,
     pub current_rpm: f32,
-    pub current_gear: i32,
+    pub current_gear: i32, // -1 — задний ход, 0 — нейтраль, с 1 — передние передачи
     pub current_steering: f32,
     pub throttle: f32,
     pub brake: f32,
     pub handbrake: f32,
+    pub hydroplaning: f32, // 0..1, передняя ось всплывает на воде и руль теряет силу
+    pub assists: DriverAssists,
+    pub launch_control: LaunchControlState,
+    pub speed_limiter: bool, // Ограничитель скорости пит-лейна включен
+    pub refueling: bool,   // Стоит в боксе и заправляется
+    pub repairing: bool,   // Стоит в боксе, механики чинят повреждения
+    pub changing_tires: bool, // Стоит в боксе, механики меняют колеса
+    pub damage: CarDamage,
+    pub engine_temperature: f32, // Температура двигателя, °C
+    
+    // Топливо; масса кузова указана без него
+    pub fuel_capacity: f32, // Объем бака, л
+    pub fuel: f32,          // Остаток, л
     
     // Характеристики двигателя
-    pub torque_curve: Vec<(f32, f32)>, // RPM, torque
+    pub drivetrain: DrivetrainType,
+    pub front_differential: DifferentialType,
+    pub rear_differential: DifferentialType,
+    pub torque_curve: Vec<(f32, f32)>, // RPM, torque без наддува
+    pub forced_induction: Option<ForcedInduction>,
     pub gear_ratios: Vec<f32>,
+    pub reverse_gear_ratio: f32,
     pub final_drive_ratio: f32,
     pub idle_rpm: f32,
     pub max_rpm: f32,
     pub redline_rpm: f32,
+    pub shift_time: f32,       // Длительность переключения, с; момент на это время снимается
+    pub rev_match: bool,       // Перегазовка при переключении вниз
+    pub shift_timer: f32,      // Остаток текущего переключения, с
+    pub unmatched_shift: bool, // Переключение вниз без перегазовки: двигатель тормозит колеса
 }
 
 impl Default for CarComponent {
@@ -45,11 +231,22 @@ impl Default for CarComponent {
             mass: 1500.0,
             max_engine_force: 10000.0,
             max_brake_force: 15000.0,
+            brake_bias: 0.6,
             max_steering_angle: 0.5,
             steering_speed: 2.0,
             wheel_base: 2.5,
             engine_position: Vec3::new(0.0, 0.5, 1.5),
-            center_of_mass: Vec3::new(0.0, 0.5, 0.0),
+            center_of_mass: Vec3::new(0.0, -0.1, 0.1),
+            inertia: Vec3::new(2500.0, 2700.0, 550.0),
+            
+            drag_coefficient: 0.32,
+            downforce_coefficient: 0.5,
+            frontal_area: 2.0,
+            side_area: 4.0,
+            side_force_coefficient: 0.9,
+            
+            front_anti_roll_stiffness: 12000.0,
+            rear_anti_roll_stiffness: 8000.0,
             
             current_speed: 0.0,
             current_rpm: 800.0,
@@ -58,7 +255,22 @@ impl Default for CarComponent {
             throttle: 0.0,
             brake: 0.0,
             handbrake: 0.0,
+            hydroplaning: 0.0,
+            assists: DriverAssists::default(),
+            launch_control: LaunchControlState::Inactive,
+            speed_limiter: false,
+            refueling: false,
+            repairing: false,
+            changing_tires: false,
+            damage: CarDamage::default(),
+            engine_temperature: OPERATING_ENGINE_TEMPERATURE,
             
+            fuel_capacity: 60.0,
+            fuel: 60.0,
+            
+            drivetrain: DrivetrainType::Rwd,
+            front_differential: DifferentialType::Open,
+            rear_differential: DifferentialType::Open,
             torque_curve: vec![
                 (1000.0, 200.0),
                 (2000.0, 300.0),
@@ -69,16 +281,407 @@ impl Default for CarComponent {
                 (7000.0, 350.0),
                 (8000.0, 300.0),
             ],
+            forced_induction: None,
             gear_ratios: vec![3.5, 2.5, 1.8, 1.3, 1.0, 0.8],
+            reverse_gear_ratio: 3.4,
             final_drive_ratio: 3.7,
             idle_rpm: 800.0,
             max_rpm: 8000.0,
             redline_rpm: 7000.0,
+            shift_time: 0.15,
+            rev_match: true,
+            shift_timer: 0.0,
+            unmatched_shift: false,
+        }
+    }
+}
+
+impl CarComponent {
+    /// Крутящий момент двигателя по кривой, Н·м (линейная интерполяция)
+    pub fn engine_torque(&self, rpm: f32) -> f32 {
+        let Some(&(first_rpm, first_torque)) = self.torque_curve.first() else {
+            return 0.0;
+        };
+        if rpm <= first_rpm {
+            return first_torque;
+        }
+        for pair in self.torque_curve.windows(2) {
+            let ((rpm0, torque0), (rpm1, torque1)) = (pair[0], pair[1]);
+            if rpm <= rpm1 {
+                let t = (rpm - rpm0) / (rpm1 - rpm0).max(f32::EPSILON);
+                return torque0 + (torque1 - torque0) * t;
+            }
+        }
+        self.torque_curve.last().map(|&(_, torque)| torque).unwrap_or(0.0)
+    }
+    
+    /// Множители скоростного напора для сопротивления, прижимной и боковой силы: F = k·v²
+    pub fn aero_factors(&self) -> (f32, f32, f32) {
+        let pressure = 0.5 * AIR_DENSITY * self.frontal_area;
+        let (drag_scale, downforce_scale) = self.damage.aero_scale();
+        (
+            pressure * self.drag_coefficient * drag_scale,
+            pressure * self.downforce_coefficient * downforce_scale,
+            0.5 * AIR_DENSITY * self.side_area * self.side_force_coefficient,
+        )
+    }
+    
+    /// Доля жесткости стабилизаторов на передней оси: баланс поворачиваемости
+    pub fn anti_roll_balance(&self) -> f32 {
+        let total = self.front_anti_roll_stiffness + self.rear_anti_roll_stiffness;
+        if total > 0.0 { self.front_anti_roll_stiffness / total } else { 0.5 }
+    }
+    
+    /// Перераспределяет жесткость стабилизаторов между осями, не меняя суммарную
+    pub fn set_anti_roll_balance(&mut self, front_share: f32) {
+        let total = self.front_anti_roll_stiffness + self.rear_anti_roll_stiffness;
+        let front_share = front_share.clamp(0.0, 1.0);
+        self.front_anti_roll_stiffness = total * front_share;
+        self.rear_anti_roll_stiffness = total * (1.0 - front_share);
+    }
+    
+    /// Масса с топливом в баке, кг
+    pub fn total_mass(&self) -> f32 {
+        self.mass + self.fuel * FUEL_DENSITY
+    }
+
+    /// Масса, центр масс и инерция тела rapier с учетом топлива;
+    /// инерция растет вместе с массой при той же развесовке
+    pub fn mass_properties(&self) -> MassProperties {
+        let total_mass = self.total_mass();
+        let com = self.center_of_mass;
+        let inertia = self.inertia * (total_mass / self.mass.max(1.0));
+        MassProperties::new(Point3::new(com.x, com.y, com.z), total_mass, Vector3::new(inertia.x, inertia.y, inertia.z))
+    }
+
+    /// Доля топлива в баке, 0..1
+    pub fn fuel_level(&self) -> f32 {
+        if self.fuel_capacity > 0.0 {
+            (self.fuel / self.fuel_capacity).clamp(0.0, 1.0)
+        } else {
+            0.0
+        }
+    }
+
+    /// Пиковая мощность двигателя по кривой момента при полном наддуве, кВт
+    pub fn peak_power(&self) -> f32 {
+        let boost = self.forced_induction.map_or(1.0, |induction| induction.peak_torque_multiplier());
+        self.torque_curve
+            .iter()
+            .map(|&(rpm, torque)| torque * boost * rpm * std::f32::consts::TAU / 60.0 / 1000.0)
+            .fold(0.0, f32::max)
+    }
+
+    /// Множитель момента от текущего давления наддува
+    pub fn boost_multiplier(&self) -> f32 {
+        self.forced_induction.map_or(1.0, |induction| induction.torque_multiplier())
+    }
+
+    /// Доля мощности двигателя с учетом повреждений и перегрева
+    pub fn engine_power(&self) -> f32 {
+        self.damage.engine_power() * overheat_power(self.engine_temperature)
+    }
+    
+    /// Класс по мощности на тонну массы
+    pub fn class(&self) -> CarClass {
+        match self.peak_power() / (self.mass / 1000.0).max(f32::EPSILON) {
+            ratio if ratio < 100.0 => CarClass::D,
+            ratio if ratio < 150.0 => CarClass::C,
+            ratio if ratio < 200.0 => CarClass::B,
+            ratio if ratio < 300.0 => CarClass::A,
+            _ => CarClass::S,
+        }
+    }
+    
+    /// Высшая передняя передача
+    pub fn top_gear(&self) -> i32 {
+        self.gear_ratios.len() as i32
+    }
+    
+    /// Общее передаточное число текущей передачи с главной парой:
+    /// на заднем ходу отрицательное, в нейтрали ноль
+    pub fn drive_ratio(&self) -> f32 {
+        self.gear_drive_ratio(self.current_gear)
+    }
+
+    /// Общее передаточное число заданной передачи с главной парой
+    pub fn gear_drive_ratio(&self, gear: i32) -> f32 {
+        let gear_ratio = match gear {
+            gear if gear <= REVERSE_GEAR => -self.reverse_gear_ratio,
+            NEUTRAL_GEAR => 0.0,
+            gear => self.gear_ratios.get((gear.min(self.top_gear()) - 1) as usize).copied().unwrap_or(1.0),
+        };
+        gear_ratio * self.final_drive_ratio
+    }
+
+    /// Обороты наибольшего момента по кривой: на них лаунч-контроль держит двигатель
+    pub fn peak_torque_rpm(&self) -> f32 {
+        self.torque_curve
+            .iter()
+            .copied()
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map_or(self.idle_rpm, |(rpm, _)| rpm)
+            .min(self.redline_rpm)
+    }
+
+    /// Помощники с учетом старта: при разгоне лаунч-контролем контроль тяги держит колеса на пределе
+    pub fn effective_assists(&self) -> DriverAssists {
+        match self.launch_control {
+            LaunchControlState::Launching => DriverAssists { traction_control: AssistLevel::High, ..self.assists },
+            _ => self.assists,
+        }
+    }
+
+    /// Идет ли переключение: момент двигателя снят
+    pub fn is_shifting(&self) -> bool {
+        self.shift_timer > 0.0
+    }
+
+    /// Обороты, которые дадут колеса на другой передаче при текущей скорости;
+    /// из нейтрали неизвестны
+    pub fn rpm_in_gear(&self, gear: i32) -> Option<f32> {
+        let current_ratio = self.drive_ratio().abs();
+        (current_ratio > 0.0).then(|| self.current_rpm * self.gear_drive_ratio(gear).abs() / current_ratio)
+    }
+
+    /// Переключение на передачу; отклоняется во время другого переключения
+    /// и если двигатель на новой передаче ушел бы за красную зону
+    pub fn shift_to(&mut self, gear: i32) -> bool {
+        let gear = gear.clamp(REVERSE_GEAR, self.top_gear());
+        if gear == self.current_gear || self.is_shifting() {
+            return false;
+        }
+        let downshift = gear > NEUTRAL_GEAR && gear < self.current_gear;
+        if downshift && self.rpm_in_gear(gear).is_some_and(|rpm| rpm > self.redline_rpm) {
+            return false;
+        }
+        self.current_gear = gear;
+        self.shift_timer = self.shift_time;
+        self.unmatched_shift = downshift && !self.rev_match;
+        true
+    }
+}
+
+/// Передача заднего хода и нейтраль
+pub const REVERSE_GEAR: i32 = -1;
+pub const NEUTRAL_GEAR: i32 = 0;
+
+/// Класс машины по удельной мощности; рекорды трасс ведутся по классам
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum CarClass {
+    D,
+    C,
+    B,
+    A,
+    S,
+}
+
+impl CarClass {
+    pub fn label(self) -> &'static str {
+        match self {
+            CarClass::D => "D",
+            CarClass::C => "C",
+            CarClass::B => "B",
+            CarClass::A => "A",
+            CarClass::S => "S",
+        }
+    }
+}
+
+/// Плотность воздуха на уровне моря, кг/м³
+const AIR_DENSITY: f32 = 1.225;
+
+/// Центр бокового давления относительно центра масс: выше него и ближе к носу
+const SIDE_PRESSURE_OFFSET: Vec3 = Vec3::new(0.0, 0.3, 0.3);
+
+/// Маркер автомобиля, которым управляет игрок
+pub struct PlayerCarComponent;
+
+/// Автопилот вместо ввода игрока: держит скорость и ведет машину к ближайшему чекпоинту впереди
+pub struct AutopilotComponent;
+
+/// Скорость, которую держит автопилот, м/с
+const AUTOPILOT_SPEED: f32 = 20.0;
+
+/// Доля скорости, которую автопилот сбрасывает при полностью вывернутом руле
+const AUTOPILOT_CORNER_SLOWDOWN: f32 = 0.4;
+
+/// Замедление, на которое автопилот рассчитывает на сухом, м/с²
+const AUTOPILOT_DECELERATION: f32 = 6.0;
+
+/// Время реакции автопилота при расчете остановки в тумане, с
+const AUTOPILOT_REACTION_TIME: f32 = 1.0;
+
+/// Тормозной путь на полностью мокрой трассе относительно сухой
+const WET_BRAKING_SCALE: f32 = 1.6;
+
+/// Дистанция, на которой автопилот замечает опасные зоны, м
+const AUTOPILOT_HAZARD_LOOKAHEAD: f32 = 120.0;
+
+/// Запас до края опасной зоны, в котором автопилот останавливается, м
+const AUTOPILOT_HAZARD_MARGIN: f32 = 5.0;
+
+/// Скорость в повороте на полностью мокрой трассе относительно сухой
+const WET_CORNERING_SCALE: f32 = 0.75;
+
+/// Поправки на погоду для машин без водителя: на мокром они раньше тормозят и медленнее проходят
+/// повороты, в тумане едут не быстрее, чем успевают остановиться в пределах видимости.
+/// Это множители и предел, поэтому настройки сложности ложатся поверх них в любую погоду
+#[derive(Debug, Clone, Copy)]
+pub struct DrivingConditions {
+    pub braking_scale: f32,   // Во сколько раз длиннее тормозной путь
+    pub cornering_scale: f32, // Доля сухой скорости в повороте
+    pub max_speed: f32,       // Предел по видимости, м/с
+}
+
+impl DrivingConditions {
+    pub fn new(surface_wetness: f32, fog_density: f32) -> Self {
+        let wetness = surface_wetness.clamp(0.0, 1.0);
+        let braking_scale = 1.0 + (WET_BRAKING_SCALE - 1.0) * wetness;
+        // Видимость по порогу контраста 5%; на мокром замедление ниже, путь остановки длиннее
+        let visibility = if fog_density > 0.0 { 3.0 / fog_density } else { f32::MAX };
+        let deceleration = AUTOPILOT_DECELERATION / braking_scale;
+        let t = AUTOPILOT_REACTION_TIME;
+        let max_speed = deceleration * (-t + (t * t + 2.0 * visibility / deceleration).sqrt());
+        Self {
+            braking_scale,
+            cornering_scale: 1.0 + (WET_CORNERING_SCALE - 1.0) * wetness,
+            max_speed,
+        }
+    }
+}
+
+/// Условия для машин без водителя по текущей погоде и туману
+pub fn current_driving_conditions(world: &World) -> DrivingConditions {
+    let wetness = world
+        .query::<&WeatherComponent>()
+        .iter()
+        .map(|(_, weather)| weather.surface_wetness)
+        .fold(0.0, f32::max);
+    let fog_density = world
+        .query::<&Resource<FogSettings>>()
+        .iter()
+        .next()
+        .map(|(_, res)| res.0.density)
+        .unwrap_or(0.0);
+    DrivingConditions::new(wetness, fog_density)
+}
+
+/// Состояние машины для снимка физики; положение и скорости кузова хранятся вместе с телами
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CarState {
+    pub entity: u64, // Entity::to_bits, снимок действует в пределах сессии
+    pub current_speed: f32,
+    pub current_rpm: f32,
+    pub current_gear: i32,
+    pub current_steering: f32,
+    #[serde(default)]
+    pub fuel: Option<f32>,
+    #[serde(default)]
+    pub damage: Option<CarDamage>,
+    #[serde(default)]
+    pub boost: Option<f32>,
+    #[serde(default)]
+    pub engine_temperature: Option<f32>,
+    pub wheels: Vec<WheelState>,
+}
+
+/// Состояние колеса для снимка физики
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WheelState {
+    pub entity: u64,
+    pub grounded: bool,
+    pub suspension_length: f32,
+    pub wheel_speed: f32,
+    pub spin_angle: f32,
+    pub slip_ratio: f32,
+    pub slip_angle: f32,
+    pub brake_temperature: f32,
+}
+
+/// Состояния всех машин с колесами
+pub fn capture_car_states(world: &World) -> Vec<CarState> {
+    world
+        .query::<&CarWheelBindingComponent>()
+        .iter()
+        .filter_map(|(_, binding)| {
+            let car = world.get::<&CarComponent>(binding.car_entity).ok()?;
+            let wheels = binding
+                .wheel_entities
+                .iter()
+                .filter_map(|&wheel_entity| {
+                    let wheel = world.get::<&WheelComponent>(wheel_entity).ok()?;
+                    Some(WheelState {
+                        entity: wheel_entity.to_bits().get(),
+                        grounded: wheel.grounded,
+                        suspension_length: wheel.suspension_length,
+                        wheel_speed: wheel.wheel_speed,
+                        spin_angle: wheel.spin_angle,
+                        slip_ratio: wheel.slip_ratio,
+                        slip_angle: wheel.slip_angle,
+                        brake_temperature: wheel.brake_temperature,
+                    })
+                })
+                .collect();
+            Some(CarState {
+                entity: binding.car_entity.to_bits().get(),
+                current_speed: car.current_speed,
+                current_rpm: car.current_rpm,
+                current_gear: car.current_gear,
+                current_steering: car.current_steering,
+                fuel: Some(car.fuel),
+                damage: Some(car.damage),
+                boost: car.forced_induction.map(|induction| induction.boost),
+                engine_temperature: Some(car.engine_temperature),
+                wheels,
+            })
+        })
+        .collect()
+}
+
+/// Возвращает машинам и колесам сохраненное состояние; исчезнувшие сущности пропускаются
+pub fn restore_car_states(world: &mut World, states: &[CarState]) {
+    for state in states {
+        let Some(car_entity) = hecs::Entity::from_bits(state.entity) else {
+            continue;
+        };
+        if let Ok(mut car) = world.get::<&mut CarComponent>(car_entity) {
+            car.current_speed = state.current_speed;
+            car.current_rpm = state.current_rpm;
+            car.current_gear = state.current_gear;
+            car.current_steering = state.current_steering;
+            if let Some(fuel) = state.fuel {
+                car.fuel = fuel;
+            }
+            if let (Some(boost), Some(induction)) = (state.boost, car.forced_induction.as_mut()) {
+                induction.boost = boost;
+            }
+            if let Some(damage) = state.damage {
+                car.damage = damage;
+            }
+            if let Some(temperature) = state.engine_temperature {
+                car.engine_temperature = temperature;
+            }
+        }
+        for wheel_state in &state.wheels {
+            let Some(wheel_entity) = hecs::Entity::from_bits(wheel_state.entity) else {
+                continue;
+            };
+            if let Ok(mut wheel) = world.get::<&mut WheelComponent>(wheel_entity) {
+                wheel.grounded = wheel_state.grounded;
+                wheel.suspension_length = wheel_state.suspension_length;
+                wheel.wheel_speed = wheel_state.wheel_speed;
+                wheel.spin_angle = wheel_state.spin_angle;
+                wheel.slip_ratio = wheel_state.slip_ratio;
+                wheel.slip_angle = wheel_state.slip_angle;
+                wheel.brake_temperature = wheel_state.brake_temperature;
+            }
         }
     }
 }
 
 /// Компонент колеса
+#[derive(Debug, Clone)]
 pub struct WheelComponent {
     pub radius: f32,
     pub width: f32,
@@ -88,18 +691,29 @@ pub struct WheelComponent {
     pub suspension_damping: f32,
     pub suspension_travel: f32,
     pub friction: f32,
+    pub camber: f32,     // Развал, рад; отрицательный — верх колеса наклонен внутрь
     pub steering: bool,  // Управляемое ли колесо
     pub powered: bool,   // Ведущее ли колесо
     
     // Состояние
     pub grounded: bool,
+    pub surface_type: SurfaceType, // Поверхность в точке контакта
+    pub surface_grip: f32,         // Трение материала покрытия
+    pub surface_rolling_resistance: f32,
+    pub water_depth: f32,  // Глубина лужи под колесом, м
+    pub hydroplaning: f32, // 0..1, доля потерянного контакта с покрытием
     pub suspension_length: f32,
     pub suspension_force: f32,
-    pub wheel_speed: f32,
+    pub wheel_speed: f32,  // Угловая скорость, рад/с
+    pub spin_angle: f32,   // Накопленный угол вращения для отрисовки
     pub slip_ratio: f32,
     pub slip_angle: f32,
     pub lateral_force: f32,
     pub longitudinal_force: f32,
+    pub brake_temperature: f32, // Температура тормозного диска, °C
+    pub tire_wear: f32,         // Износ покрышки: 0 - новая, 1 - стерта до корда
+    pub brake_modulation: f32,  // Доля тормоза, оставленная ABS
+    pub drive_modulation: f32,  // Доля тяги, оставленная контролем тяги
 }
 
 impl Default for WheelComponent {
@@ -113,29 +727,240 @@ impl Default for WheelComponent {
             suspension_damping: 4500.0,
             suspension_travel: 0.15,
             friction: 1.0,
+            camber: 0.0,
             steering: false,
             powered: false,
             
             grounded: false,
+            surface_type: SurfaceType::Asphalt,
+            surface_grip: 1.0,
+            surface_rolling_resistance: 0.015,
+            water_depth: 0.0,
+            hydroplaning: 0.0,
             suspension_length: 0.3,
             suspension_force: 0.0,
             wheel_speed: 0.0,
+            spin_angle: 0.0,
             slip_ratio: 0.0,
             slip_angle: 0.0,
             lateral_force: 0.0,
             longitudinal_force: 0.0,
+            brake_temperature: AMBIENT_BRAKE_TEMPERATURE,
+            tire_wear: 0.0,
+            brake_modulation: 1.0,
+            drive_modulation: 1.0,
         }
     }
 }
 
+impl WheelComponent {
+    /// Доля сцепления, оставшаяся у изношенной покрышки
+    pub fn tire_grip(&self) -> f32 {
+        1.0 - TIRE_WEAR_GRIP_LOSS * self.tire_wear
+    }
+}
+
 /// Компонент, связывающий автомобиль с колесами
 pub struct CarWheelBindingComponent {
     pub car_entity: hecs::Entity,
     pub wheel_entities: Vec<hecs::Entity>,
 }
 
+/// Режим фар машины
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HeadlightMode {
+    #[default]
+    Off,
+    Low,
+    High,
+}
+
+/// Фары машины: режим, автовключение в сумерках и сущности прожекторов
+#[derive(Debug, Clone)]
+pub struct HeadlightComponent {
+    pub mode: HeadlightMode,
+    pub auto_on: bool,          // Включать ближний свет в сумерках и гасить на рассвете
+    pub lights: Vec<hecs::Entity>,
+    pub was_dark: Option<bool>, // Освещенность на прошлом шаге: автомат реагирует только на смену
+}
+
+impl HeadlightComponent {
+    pub fn new(lights: Vec<hecs::Entity>) -> Self {
+        Self {
+            mode: HeadlightMode::Off,
+            auto_on: true,
+            lights,
+            was_dark: None,
+        }
+    }
+
+    pub fn is_on(&self) -> bool {
+        self.mode != HeadlightMode::Off
+    }
+}
+
+/// Звуки машины по именам из набора звуков
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CarAudioSet {
+    pub engine: String,   // Петля двигателя, высота тона следует за оборотами
+    pub blow_off: String, // Хлопок перепускного клапана турбины
+}
+
+impl Default for CarAudioSet {
+    fn default() -> Self {
+        Self {
+            engine: "engine_loop".to_string(),
+            blow_off: "turbo_blow_off".to_string(),
+        }
+    }
+}
+
+/// Компонент фары, закрепленной на кузове автомобиля
+pub struct HeadlightMountComponent {
+    pub car_entity: hecs::Entity,
+    pub local_offset: Vec3,
+    pub local_rotation: Quat,
+}
+
+/// Полоса ниже лимита, в которой ограничитель плавно убирает газ, м/с
+const SPEED_LIMITER_BAND: f32 = 1.0;
+
+/// Скорость, которую держит схема «только руль» на прямой, м/с
+const STEERING_ONLY_MAX_SPEED: f32 = 40.0;
+
+/// Доля скорости, которую схема «только руль» сбрасывает при полном повороте руля
+const STEERING_ONLY_CORNER_SLOWDOWN: f32 = 0.6;
+
+/// Автоматические газ, тормоз и передачи для упрощенных схем управления
+fn apply_control_scheme(car: &mut CarComponent, scheme: ControlScheme, steer_input: f32) {
+    if scheme.auto_throttle() {
+        // Газ сбрасывается, только когда игрок тормозит
+        let braking = car.brake > 0.1 || car.handbrake > 0.1;
+        car.throttle = if braking { 0.0 } else { 1.0 };
+    }
+    
+    if scheme != ControlScheme::SteeringOnly {
+        return;
+    }
+    
+    // Скорость подбирается по углу руля: чем круче поворот, тем медленнее
+    let target_speed = STEERING_ONLY_MAX_SPEED * (1.0 - STEERING_ONLY_CORNER_SLOWDOWN * steer_input.abs());
+    let excess = car.current_speed.abs() - target_speed;
+    if excess > 0.0 {
+        car.throttle = 0.0;
+        car.brake = car.brake.max((excess / 10.0).min(1.0));
+    }
+    
+    auto_shift(car);
+}
+
+/// Автоматическая коробка по оборотам; задний ход и нейтраль она не трогает
+pub fn auto_shift(car: &mut CarComponent) {
+    if car.current_gear <= NEUTRAL_GEAR {
+        return;
+    }
+    if car.current_rpm > car.redline_rpm * 0.95 && car.current_gear < car.top_gear() {
+        car.shift_to(car.current_gear + 1);
+    } else if car.current_rpm < car.idle_rpm * 2.5 && car.current_gear > 1 {
+        car.shift_to(car.current_gear - 1);
+    }
+}
+
+/// Газ и тормоз, выше которых стоящая машина взводит лаунч-контроль
+const LAUNCH_ARM_INPUT: f32 = 0.9;
+
+/// Тормоз, ниже которого взведенный лаунч-контроль стартует
+const LAUNCH_RELEASE_BRAKE: f32 = 0.1;
+
+/// Скорость, после которой старт закончен и машина едет как обычно, м/с
+const LAUNCH_END_SPEED: f32 = 15.0;
+
+/// Взвод, старт и отключение лаунч-контроля по газу, тормозу и скорости
+pub fn update_launch_control(car: &mut CarComponent) {
+    if !car.assists.launch_control {
+        car.launch_control = LaunchControlState::Inactive;
+        return;
+    }
+    car.launch_control = match car.launch_control {
+        LaunchControlState::Inactive
+            if car.current_gear == 1
+                && car.current_speed.abs() < STANDSTILL_SPEED
+                && car.throttle >= LAUNCH_ARM_INPUT
+                && car.brake >= LAUNCH_ARM_INPUT =>
+        {
+            LaunchControlState::Armed
+        }
+        LaunchControlState::Armed if car.throttle < LAUNCH_ARM_INPUT => LaunchControlState::Inactive,
+        LaunchControlState::Armed if car.brake < LAUNCH_RELEASE_BRAKE => LaunchControlState::Launching,
+        LaunchControlState::Launching
+            if car.throttle < LAUNCH_ARM_INPUT || car.current_gear != 1 || car.current_speed > LAUNCH_END_SPEED =>
+        {
+            LaunchControlState::Inactive
+        }
+        state => state,
+    };
+}
+
+/// Газ, тормоз и руль автопилота; без чекпоинтов впереди машина просто едет прямо
+fn autopilot_controls(
+    car: &CarComponent,
+    transform: &TransformComponent,
+    checkpoints: &[Vec3],
+    hazards: &[HazardZone],
+    conditions: &DrivingConditions,
+) -> (f32, f32, f32) {
+    let target = checkpoints
+        .iter()
+        .map(|position| transform.rotation.inverse() * (*position - transform.position))
+        .filter(|local| local.z > 1.0)
+        .min_by(|a, b| a.length_squared().total_cmp(&b.length_squared()));
+    // Положительный угол руля уводит машину к -X в ее системе координат
+    let steer = target
+        .map(|local| ((-local.x).atan2(local.z) / car.max_steering_angle).clamp(-1.0, 1.0))
+        .unwrap_or(0.0);
+
+    // В повороте медленнее, на мокром еще медленнее; тормозит тем раньше, чем длиннее тормозной путь
+    let corner_share = (1.0 - AUTOPILOT_CORNER_SLOWDOWN * steer.abs())
+        * (1.0 + (conditions.cornering_scale - 1.0) * steer.abs());
+    let mut target_speed = AUTOPILOT_SPEED.min(conditions.max_speed) * corner_share;
+
+    // Перед действующей опасностью или той, что сработает, пока машина в зоне, автопилот
+    // сбавляет до скорости, с которой успеет остановиться у ее края
+    let forward = transform.rotation * Vec3::Z;
+    let speed = car.current_speed.abs().max(1.0);
+    for zone in zones_ahead(hazards, transform.position, forward, AUTOPILOT_HAZARD_LOOKAHEAD) {
+        let distance = (zone.position.distance(transform.position) - zone.radius).max(0.0);
+        let clear_time = (distance + zone.radius * 2.0) / speed;
+        if zone.state == HazardState::Active || zone.time_to_active < clear_time {
+            let stop_distance = (distance - AUTOPILOT_HAZARD_MARGIN).max(0.0);
+            target_speed = target_speed.min((2.0 * AUTOPILOT_DECELERATION / conditions.braking_scale * stop_distance).sqrt());
+        }
+    }
+    let excess = car.current_speed - target_speed;
+    if excess > 0.0 {
+        (0.0, (excess * conditions.braking_scale / 10.0).min(1.0), steer)
+    } else {
+        ((-excess / 5.0).min(1.0), 0.0, steer)
+    }
+}
+
 /// Система управления автомобилем
-pub struct CarControlSystem;
+pub struct CarControlSystem {
+    limiter_held: bool,
+}
+
+impl CarControlSystem {
+    pub fn new() -> Self {
+        Self { limiter_held: false }
+    }
+}
+
+impl Default for CarControlSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 impl System for CarControlSystem {
     fn update(&mut self, world: &mut World, delta_time: f32) {
@@ -153,8 +978,61 @@ impl System for CarControlSystem {
             }
         };
         
+        // Правила заезда определяют, нужно ли выжимать сцепление для переключения
+        let clutch_required = current_rules(world).transmission == TransmissionRule::ManualWithClutch;
+        let clutch_engaged = *input_states.get(&InputAction::Clutch).unwrap_or(&0.0) > 0.5;
+        let can_shift = !clutch_required || clutch_engaged;
+        // Ограничитель переключается нажатием; дирекция гонки включает его и сама на въезде в пит-лейн
+        let limiter_down = *input_states.get(&InputAction::SpeedLimiter).unwrap_or(&0.0) > 0.5;
+        let toggle_limiter = limiter_down && !self.limiter_held;
+        self.limiter_held = limiter_down;
+        let pit_speed_limit = current_rules(world).penalties.pit_speed_limit;
+        let control_scheme = world
+            .query::<&Resource<ControlScheme>>()
+            .iter()
+            .next()
+            .map(|(_, res)| res.0)
+            .unwrap_or_default();
+        
+        // До старта машины удерживаются на тормозе
+        let held_on_grid = matches!(
+            current_race(world),
+            Some((RacePhase::Intro | RacePhase::Countdown, _))
+        );
+        
+        let checkpoints: Vec<Vec3> = world
+            .query::<(&CheckpointComponent, &TransformComponent)>()
+            .iter()
+            .map(|(_, (_, transform))| transform.position)
+            .collect();
+        let conditions = current_driving_conditions(world);
+        let hazards: Vec<HazardZone> = world
+            .query::<&Resource<Vec<HazardZone>>>()
+            .iter()
+            .next()
+            .map(|(_, res)| res.0.clone())
+            .unwrap_or_default();
+        
+        // Помощники водителя выключены, если правила заезда их запрещают;
+        // схема «только руль» включает их все независимо от настроек
+        let assists_allowed = current_rules(world).assists_allowed;
+        let assists = if assists_allowed && control_scheme.requires_assists() {
+            DriverAssists::full()
+        } else if assists_allowed {
+            world
+                .query::<&Resource<DriverAssists>>()
+                .iter()
+                .next()
+                .map(|(_, res)| res.0)
+                .unwrap_or_default()
+        } else {
+            DriverAssists::default()
+        };
+        
         // Обработка ввода для всех автомобилей
-        for (_, car) in world.query_mut::<&mut CarComponent>() {
+        for (_, (car, transform, autopilot)) in
+            world.query_mut::<(&mut CarComponent, Option<&TransformComponent>, Option<&AutopilotComponent>)>()
+        {
             // Обновляем дроссель
             car.throttle = *input_states.get(&InputAction::Accelerate).unwrap_or(&0.0);
             
@@ -163,11 +1041,39 @@ impl System for CarControlSystem {
             
             // Обновляем ручной тормоз
             car.handbrake = *input_states.get(&InputAction::Handbrake).unwrap_or(&0.0);
+            car.assists = assists;
             
             // Рулевое управление
             let steer_left = *input_states.get(&InputAction::SteerLeft).unwrap_or(&0.0);
             let steer_right = *input_states.get(&InputAction::SteerRight).unwrap_or(&0.0);
-            let target_steering = (steer_right - steer_left) * car.max_steering_angle;
+            let mut steer_input = steer_right - steer_left;
+            
+            apply_control_scheme(car, control_scheme, steer_input);
+            
+            if let (Some(transform), Some(_)) = (transform, autopilot) {
+                let (throttle, brake, steer) = autopilot_controls(car, transform, &checkpoints, &hazards, &conditions);
+                car.throttle = throttle;
+                car.brake = brake;
+                car.handbrake = 0.0;
+                steer_input = steer;
+                auto_shift(car);
+            }
+            
+            if toggle_limiter && autopilot.is_none() {
+                car.speed_limiter = !car.speed_limiter;
+            }
+            // Ограничитель убирает газ, подходя к лимиту
+            if car.speed_limiter {
+                car.throttle *= ((pit_speed_limit - car.current_speed.abs()) / SPEED_LIMITER_BAND).clamp(0.0, 1.0);
+            }
+            
+            if held_on_grid {
+                car.throttle = 0.0;
+                car.brake = 1.0;
+            }
+            update_launch_control(car);
+            
+            let target_steering = steer_input * car.max_steering_angle * (1.0 - HYDROPLANE_STEERING_LOSS * car.hydroplaning);
             
             // Плавное изменение угла поворота руля
             if (target_steering - car.current_steering).abs() > 0.01 {
@@ -181,206 +1087,1340 @@ impl System for CarControlSystem {
             let shift_up = *input_states.get(&InputAction::ShiftUp).unwrap_or(&0.0) > 0.5;
             let shift_down = *input_states.get(&InputAction::ShiftDown).unwrap_or(&0.0) > 0.5;
             
-            if shift_up && car.current_gear < car.gear_ratios.len() as i32 - 1 {
-                car.current_gear += 1;
-            } else if shift_down && car.current_gear > 0 {
-                car.current_gear -= 1;
+            if !can_shift {
+                continue;
+            }
+            
+            // Вниз с первой передачи — нейтраль, затем задний ход
+            if shift_up {
+                car.shift_to(car.current_gear + 1);
+            } else if shift_down {
+                car.shift_to(car.current_gear - 1);
             }
         }
     }
 }
 
-/// Система физики автомобиля
-pub struct CarPhysicsSystem;
+/// Пиковый угол увода шины, рад: дальше боковая сила не растет
+const PEAK_SLIP_ANGLE: f32 = 0.15;
 
-impl System for CarPhysicsSystem {
-    fn update(&mut self, world: &mut World, _delta_time: f32) {
-        // Сначала соберем все данные, которые нам нужны
-        
-        // Получаем все связи автомобилей с колесами
-        let bindings: Vec<(hecs::Entity, Vec<hecs::Entity>)> = {
-            world.query_mut::<&CarWheelBindingComponent>()
-                .into_iter()
-                .map(|(_, binding)| (binding.car_entity, binding.wheel_entities.clone()))
-                .collect()
-        };
-        
-        // Обновляем каждый автомобиль и его колеса по отдельности
-        for (car_entity, wheel_entities) in bindings {
-            // Обновляем автомобиль
-            if let Ok((_car, _car_body)) = world.query_one_mut::<(&mut CarComponent, &RigidBodyComponent)>(car_entity) {
-                // Обновляем колеса
-                for wheel_entity in wheel_entities {
-                    if let Ok(wheel) = world.query_one_mut::<&mut WheelComponent>(wheel_entity) {
-                        // Обновляем углы поворота для управляемых колес
-                        if wheel.steering {
-                            // Применить текущий угол поворота руля
-                            // ...
-                        }
-                        
-                        // Обновляем вращение колеса и воздействие на автомобиль
-                        if wheel.powered {
-                            // Рассчитать крутящий момент двигателя
-                            // Применить к колесу
-                            // ...
-                        }
-                        
-                        // Обновляем суспензию и контакт с поверхностью
-                        // ...
-                    }
-                }
-                
-                // Обновляем RPM на основе скорости и передачи
-                // ...
-                
-                // Обновляем скорость из физической скорости тела
-                // ...
-            }
-        }
-    }
-}
+/// КПД трансмиссии
+const DRIVETRAIN_EFFICIENCY: f32 = 0.85;
 
-/// Создает полную сущность автомобиля с колесами
-pub fn create_car_entity(
-    world: &mut World,
-    model_name: &str,
-    position: Vec3,
-    rotation: Quat,
-) -> hecs::Entity {
-    // Создаем базовый компонент автомобиля
-    let car_component = CarComponent {
-        name: model_name.to_string(),
-        ..Default::default()
-    };
-    
-    // Создаем физический компонент и трансформацию
-    let transform = TransformComponent {
-        position,
-        rotation,
-        ..Default::default()
-    };
-    
-    // Получаем ресурс с физическими телами
-    let mut resource_query = world.query_mut::<&mut Resource<(RigidBodySet, ColliderSet)>>();
-    if let Some((_, resource)) = resource_query.into_iter().next() {
-        let (rigid_body_set, collider_set) = &mut resource.0;
-        
-        // Создаем физическое тело для автомобиля
-        let rb = RigidBodyBuilder::dynamic()
-            .translation(Vector3::new(position.x, position.y, position.z))
-            .build();
-        
-        // Создаем коллайдер (примерные размеры)
-        let collider = ColliderBuilder::cuboid(1.0, 0.5, 2.0)
-            .restitution(0.2)
-            .friction(0.7)
-            .build();
-        
-        // Добавляем в наборы
-        let rb_handle = rigid_body_set.insert(rb);
-        let collider_handle = collider_set.insert(collider);
-        
-        // Создаем компоненты
-        let rigid_body = RigidBodyComponent {
-            handle: rb_handle,
-            body_type: RigidBodyType::Dynamic,
-        };
-        
-        let collider_component = ColliderComponent {
-            handle: collider_handle,
-            shape_type: ColliderShapeType::Box,
+/// Торможение двигателем при переключении вниз без перегазовки в долях момента у красной зоны
+const UNMATCHED_DOWNSHIFT_BRAKING: f32 = 0.5;
+
+/// Прибавка угловой скорости колеса при полной пробуксовке, рад/с
+const WHEELSPIN_SPEED: f32 = 30.0;
+
+/// Предел силы подвески в долях от силы пружины на полном ходе
+const MAX_SUSPENSION_FORCE_FACTOR: f32 = 3.0;
+
+/// Падение коэффициента сцепления шины на каждую статическую нагрузку сверх своей
+const TIRE_LOAD_SENSITIVITY: f32 = 0.15;
+
+/// Ускорение свободного падения, м/с²
+const GRAVITY: f32 = 9.81;
+
+/// Высота центра крена в долях высоты центра масс над пятном контакта
+const ROLL_CENTER_SHARE: f32 = 0.6;
+
+/// Доля массы на колесо, боковое скольжение которой гасится за один кадр
+const LATERAL_CANCEL_SHARE: f32 = 0.5;
+
+/// Доля массы на колесо, разница скоростей колес оси которой выравнивается за кадр
+/// при полной блокировке дифференциала
+const DIFFERENTIAL_LOCK_SHARE: f32 = 0.5;
+
+/// Развал, при котором боковое сцепление наибольшее, рад
+const OPTIMAL_CAMBER: f32 = -0.035;
+
+/// Прибавка бокового сцепления при оптимальном развале и потеря продольного на радиан развала
+const CAMBER_LATERAL_GAIN: f32 = 0.06;
+const CAMBER_LONGITUDINAL_LOSS: f32 = 0.6;
+const CAMBER_MIN_GRIP: f32 = 0.85;
+
+/// Ручной тормоз сильнее этой доли блокирует задние колеса
+const HANDBRAKE_LOCK_INPUT: f32 = 0.5;
+
+/// Трение скольжения заблокированной шины в долях от сцепления
+const LOCKED_WHEEL_GRIP: f32 = 0.7;
+
+/// Машина медленнее этого без газа считается стоящей и удерживается тормозом, м/с
+const STANDSTILL_SPEED: f32 = 0.3;
+const STANDSTILL_THROTTLE: f32 = 0.05;
+
+/// Доля тормоза, удерживающая стоящую машину
+const STANDSTILL_HOLD_BRAKE: f32 = 0.3;
+
+/// Скорость, с которой помощники отпускают и возвращают тормоз или тягу, доля в секунду
+const ASSIST_RELEASE_RATE: f32 = 10.0;
+const ASSIST_APPLY_RATE: f32 = 4.0;
+
+/// Меньше этой доли помощник силу не убирает
+const ASSIST_MIN_SHARE: f32 = 0.2;
+
+/// Стабилизация не вмешивается медленнее этой скорости, м/с
+const STABILITY_MIN_SPEED: f32 = 5.0;
+
+/// Тормоз стабилизации на рад/с лишнего рыскания, Н на кг массы
+const STABILITY_BRAKE_GAIN: f32 = 8.0;
+
+/// Доля момента двигателя, убираемая при заносе на рад/с лишнего рыскания
+const STABILITY_TORQUE_CUT: f32 = 4.0;
+
+/// Состояние колеса, рассчитанное за кадр
+struct WheelUpdate {
+    entity: hecs::Entity,
+    grounded: bool,
+    suspension_length: f32,
+    suspension_force: f32,
+    wheel_speed: f32,
+    slip_ratio: f32,
+    slip_angle: f32,
+    lateral_force: f32,
+    longitudinal_force: f32,
+    brake_modulation: f32,
+    drive_modulation: f32,
+}
+
+/// Сила подвески колеса при данной длине и скорости сжатия, Н.
+/// Пружина и демпфер только толкают кузов и не бьют сильнее полного хода
+pub fn suspension_force(wheel: &WheelComponent, length: f32, compression_speed: f32) -> f32 {
+    let compression = wheel.suspension_rest_length - length;
+    let max_force = wheel.suspension_stiffness * wheel.suspension_travel * MAX_SUSPENSION_FORCE_FACTOR;
+    (wheel.suspension_stiffness * compression + wheel.suspension_damping * compression_speed).clamp(0.0, max_force)
+}
+
+/// Силы стабилизаторов по колесам, Н: разница сжатия колес одной оси догружает более сжатое
+/// и разгружает противоположное. Пара ищется по другой стороне кузова на той же оси
+pub fn anti_roll_forces(wheels: &[WheelComponent], lengths: &[f32], front_stiffness: f32, rear_stiffness: f32) -> Vec<f32> {
+    let compression = |i: usize| wheels[i].suspension_rest_length - lengths[i];
+    (0..wheels.len())
+        .map(|i| {
+            let wheel = &wheels[i];
+            let partner = (0..wheels.len())
+                .filter(|&j| j != i && wheels[j].position.x * wheel.position.x < 0.0)
+                .min_by(|&a, &b| {
+                    let da = (wheels[a].position.z - wheel.position.z).abs();
+                    let db = (wheels[b].position.z - wheel.position.z).abs();
+                    da.total_cmp(&db)
+                });
+            let Some(partner) = partner else {
+                return 0.0;
+            };
+            let stiffness = if wheel.position.z >= 0.0 { front_stiffness } else { rear_stiffness };
+            stiffness * (compression(i) - compression(partner))
+        })
+        .collect()
+}
+
+/// Момент двигателя на ведущих колесах, Н·м; у отсечки подача прекращается,
+/// поврежденный или перегретый двигатель отдает только часть момента, наддув добавляет.
+/// На время переключения и на взведенном лаунч-контроле момент снят,
+/// а без перегазовки вниз двигатель тормозит колеса
+pub fn drive_torque(car: &CarComponent) -> f32 {
+    if car.launch_control == LaunchControlState::Armed {
+        0.0
+    } else if car.is_shifting() {
+        if car.unmatched_shift {
+            -car.engine_torque(car.redline_rpm) * UNMATCHED_DOWNSHIFT_BRAKING * car.drive_ratio() * DRIVETRAIN_EFFICIENCY
+        } else {
+            0.0
+        }
+    } else if car.current_rpm < car.max_rpm && car.fuel > 0.0 {
+        car.engine_torque(car.current_rpm) * car.boost_multiplier() * car.engine_power() * car.drive_ratio() * DRIVETRAIN_EFFICIENCY * car.throttle
+    } else {
+        0.0
+    }
+}
+
+/// Расход топлива, л/с: мощность двигателя на удельный расход плюс холостой ход
+pub fn fuel_flow(car: &CarComponent) -> f32 {
+    let power = car.engine_torque(car.current_rpm) * car.throttle * car.current_rpm * std::f32::consts::TAU / 60.0 / 1000.0;
+    IDLE_FUEL_FLOW + power.max(0.0) * SPECIFIC_FUEL_CONSUMPTION / 3600.0 / FUEL_DENSITY
+}
+
+/// Доля тормоза с учетом удержания: стоящая машина без газа не катится под уклон
+pub fn standstill_brake(car: &CarComponent) -> f32 {
+    if car.throttle < STANDSTILL_THROTTLE && car.current_speed.abs() < STANDSTILL_SPEED {
+        car.brake.max(STANDSTILL_HOLD_BRAKE)
+    } else {
+        car.brake
+    }
+}
+
+/// Обороты двигателя по угловой скорости ведущих колес
+pub fn engine_rpm(car: &CarComponent, powered_wheel_speed: f32) -> f32 {
+    // В нейтрали двигатель свободно раскручивается газом
+    if car.current_gear == NEUTRAL_GEAR {
+        return car.idle_rpm + (car.redline_rpm - car.idle_rpm) * car.throttle;
+    }
+    let wheel_rpm = (powered_wheel_speed.abs() * car.drive_ratio().abs() * 60.0 / std::f32::consts::TAU).clamp(car.idle_rpm, car.max_rpm);
+    // Лаунч-контроль держит обороты наибольшего момента, пока сцепление не схватится
+    match car.launch_control {
+        LaunchControlState::Inactive => wheel_rpm,
+        LaunchControlState::Armed | LaunchControlState::Launching => wheel_rpm.max(car.peak_torque_rpm()),
+    }
+}
+
+/// Доли тормоза и тяги колеса после помощников по проскальзыванию прошлого шага:
+/// ABS отпускает тормоз блокирующегося колеса, контроль тяги — газ, пока колесо буксует
+pub fn assist_modulation(wheel: &WheelComponent, assists: DriverAssists, delta_time: f32) -> (f32, f32) {
+    let modulate = |current: f32, level: AssistLevel, slip: f32| match level.slip_limit() {
+        Some(limit) if slip > limit => (current - ASSIST_RELEASE_RATE * delta_time).max(ASSIST_MIN_SHARE),
+        Some(_) => (current + ASSIST_APPLY_RATE * delta_time).min(1.0),
+        None => 1.0,
+    };
+    (
+        modulate(wheel.brake_modulation, assists.abs, -wheel.slip_ratio),
+        modulate(wheel.drive_modulation, assists.traction_control, wheel.slip_ratio),
+    )
+}
+
+/// Вмешательство стабилизации: тормоз на колеса, Н, и доля оставленного момента двигателя.
+/// Скорость рыскания сравнивается с заданной рулем по велосипедной модели и ограниченной
+/// сцеплением; при заносе подтормаживается внешнее переднее колесо и убирается газ,
+/// при сносе — внутреннее заднее
+pub fn stability_control(
+    wheels: &[WheelComponent],
+    level: AssistLevel,
+    yaw_rate: f32,
+    forward_speed: f32,
+    steering: f32,
+    wheel_base: f32,
+    mass: f32,
+) -> (Vec<f32>, f32) {
+    let mut brakes = vec![0.0; wheels.len()];
+    let Some(tolerance) = level.yaw_tolerance() else {
+        return (brakes, 1.0);
+    };
+    if forward_speed.abs() < STABILITY_MIN_SPEED || wheels.is_empty() {
+        return (brakes, 1.0);
+    }
+    
+    // Руль поворачивает колеса вокруг Y в обратную сторону, туда же вращается кузов
+    let grip = wheels.iter().map(|wheel| wheel.friction * wheel.surface_grip * wheel.tire_grip()).sum::<f32>() / wheels.len() as f32;
+    let max_yaw = grip * GRAVITY / forward_speed.abs();
+    let reference = (-forward_speed * steering.tan() / wheel_base.max(0.1)).clamp(-max_yaw, max_yaw);
+    let error = yaw_rate - reference;
+    if error.abs() <= tolerance {
+        return (brakes, 1.0);
+    }
+    
+    // Тормоз колеса со стороны x дает момент рыскания со знаком x, нужен противоположный ошибке
+    let oversteer = yaw_rate.abs() > reference.abs() && yaw_rate * reference >= 0.0;
+    let excess = error.abs() - tolerance;
+    let force = excess * mass * STABILITY_BRAKE_GAIN;
+    if let Some((i, _)) = wheels
+        .iter()
+        .enumerate()
+        .filter(|(_, wheel)| wheel.steering == oversteer && wheel.position.x * error < 0.0)
+        .max_by(|a, b| a.1.position.x.abs().total_cmp(&b.1.position.x.abs()))
+    {
+        brakes[i] = force;
+    }
+    let torque_share = if oversteer { (1.0 - excess * STABILITY_TORQUE_CUT).max(0.0) } else { 1.0 };
+    (brakes, torque_share)
+}
+
+/// Условия в пятне контакта шины за шаг
+#[derive(Debug, Clone, Copy)]
+pub struct TireContact {
+    pub load: f32,          // Нормальная нагрузка от подвески, Н
+    pub forward_speed: f32, // Скорость пятна вдоль шины, м/с
+    pub side_speed: f32,    // Скорость пятна поперек шины, м/с
+    pub drive_force: f32,   // Тяга двигателя на этом колесе, Н
+    pub brake_force: f32,   // Тормоз и сопротивление качению, Н
+    pub mass_share: f32,    // Масса машины, приходящаяся на колесо, кг
+    pub downhill_force: f32, // Скатывающая сила веса вдоль шины, Н
+    pub locked: bool,        // Колесо заблокировано ручным тормозом и скользит
+}
+
+/// Силы шины и скольжение по результатам шага
+#[derive(Debug, Clone, Copy)]
+pub struct TireForces {
+    pub longitudinal: f32,
+    pub lateral: f32,
+    pub slip_ratio: f32,
+    pub slip_angle: f32,
+    pub wheel_speed: f32, // Угловая скорость колеса, рад/с
+}
+
+/// Модель шины: продольная и боковая силы в круге трения, пробуксовка и блокировка
+pub fn tire_forces(wheel: &WheelComponent, contact: &TireContact, delta_time: f32) -> TireForces {
+    // Сцепление растет медленнее нагрузки: перенос веса в повороте съедает сцепление оси,
+    // поэтому жесткость стабилизаторов сдвигает баланс поворачиваемости
+    let static_load = contact.mass_share * GRAVITY;
+    let load_factor = if static_load > 0.0 {
+        (1.0 - TIRE_LOAD_SENSITIVITY * (contact.load / static_load - 1.0)).clamp(0.5, 1.2)
+    } else {
+        1.0
+    };
+    let grip = contact.load * load_factor * wheel.friction * wheel.surface_grip * wheel.tire_grip() * (1.0 - HYDROPLANE_GRIP_LOSS * wheel.hydroplaning);
+
+    // Тормоз не разгоняет стоящую машину назад, но на уклоне держит и скатывающую силу
+    let next_speed = contact.forward_speed + contact.downhill_force * delta_time / contact.mass_share.max(1.0);
+    let stop_force = next_speed.abs() * contact.mass_share / delta_time;
+    let brake = contact.brake_force.min(stop_force) * next_speed.signum();
+    let demanded_long = contact.drive_force - brake;
+
+    // Боковая сила по углу увода, на малой скорости не больше гасящей боковое скольжение
+    let slip_angle = contact.side_speed.atan2(contact.forward_speed.abs().max(0.5));
+    
+    // Заблокированное колесо не катится: трение скольжения направлено против скорости пятна,
+    // поэтому на скорости почти вся сила уходит на торможение и ось теряет боковое сцепление
+    if contact.locked {
+        let slide_speed = (next_speed * next_speed + contact.side_speed * contact.side_speed).sqrt();
+        if slide_speed <= f32::EPSILON {
+            return TireForces { longitudinal: 0.0, lateral: 0.0, slip_ratio: -1.0, slip_angle, wheel_speed: 0.0 };
+        }
+        let force = (grip * LOCKED_WHEEL_GRIP).min(slide_speed * contact.mass_share / delta_time);
+        return TireForces {
+            longitudinal: -next_speed / slide_speed * force,
+            lateral: -contact.side_speed / slide_speed * force,
+            slip_ratio: -1.0,
+            slip_angle,
+            wheel_speed: 0.0,
         };
+    }
+    // Развал перераспределяет сцепление между поворотом и разгоном/торможением
+    let (long_grip, lat_grip) = camber_grip(wheel.camber);
+    let cancel_force = contact.side_speed.abs() * contact.mass_share * LATERAL_CANCEL_SHARE / delta_time;
+    let demanded_lat = ((-slip_angle / PEAK_SLIP_ANGLE).clamp(-1.0, 1.0) * grip * lat_grip).clamp(-cancel_force, cancel_force);
+
+    // Эллипс трения: суммарная сила не превышает сцепления по каждой оси шины
+    let usage = if grip > 0.0 {
+        ((demanded_long / long_grip).powi(2) + (demanded_lat / lat_grip).powi(2)).sqrt() / grip
+    } else {
+        f32::INFINITY
+    };
+    let scale = if usage > 1.0 { 1.0 / usage } else { 1.0 };
+    let longitudinal = demanded_long * scale;
+    let lateral = demanded_lat * scale;
+
+    // Пробуксовка при избытке тяги, блокировка при избытке тормоза
+    let excess = if demanded_long.abs() > 0.0 {
+        ((demanded_long.abs() - longitudinal.abs()) / demanded_long.abs()).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    let slip_ratio = if contact.drive_force > brake.abs() { excess } else { -excess };
+    let rolling_speed = contact.forward_speed / wheel.radius;
+    let wheel_speed = if slip_ratio >= 0.0 {
+        rolling_speed + slip_ratio * WHEELSPIN_SPEED
+    } else {
+        rolling_speed * (1.0 + slip_ratio)
+    };
+
+    TireForces { longitudinal, lateral, slip_ratio, slip_angle, wheel_speed }
+}
+
+/// Множители продольного и бокового сцепления шины от угла развала: умеренный
+/// отрицательный развал прибавляет в повороте, но пятно на прямой становится уже
+pub fn camber_grip(camber: f32) -> (f32, f32) {
+    let offset = (camber - OPTIMAL_CAMBER) / OPTIMAL_CAMBER;
+    let lateral = (1.0 + CAMBER_LATERAL_GAIN * (1.0 - offset * offset)).clamp(CAMBER_MIN_GRIP, 1.0 + CAMBER_LATERAL_GAIN);
+    let longitudinal = (1.0 - CAMBER_LONGITUDINAL_LOSS * camber.abs()).max(CAMBER_MIN_GRIP);
+    (longitudinal, lateral)
+}
+
+/// Система физики автомобиля: лучевая подвеска и силы шин, приложенные к кузову
+pub struct CarPhysicsSystem {
+    query_pipeline: QueryPipeline,
+}
+
+impl CarPhysicsSystem {
+    pub fn new() -> Self {
+        Self {
+            query_pipeline: QueryPipeline::new(),
+        }
+    }
+}
+
+impl Default for CarPhysicsSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl System for CarPhysicsSystem {
+    fn update(&mut self, world: &mut World, delta_time: f32) {
+        if delta_time <= 0.0 {
+            return;
+        }
         
-        // Создаем сущность автомобиля
-        let car_entity = world.spawn((car_component, transform, rigid_body, collider_component));
+        let wind = wind_velocity(world);
         
-        // Создаем колеса для автомобиля
-        let wheel_entities = create_wheels_for_car(world, car_entity);
+        // Сначала собираем данные машин и колес, чтобы затем заимствовать только физический мир
+        let bindings: Vec<(hecs::Entity, Vec<hecs::Entity>)> = world
+            .query::<&CarWheelBindingComponent>()
+            .iter()
+            .map(|(_, binding)| (binding.car_entity, binding.wheel_entities.clone()))
+            .collect();
         
-        // Создаем компонент связи между автомобилем и колесами
-        let binding = CarWheelBindingComponent {
-            car_entity,
-            wheel_entities,
-        };
+        let mut car_updates = Vec::new();
+        let mut wheel_updates = Vec::new();
         
-        world.spawn((binding,));
+        for (car_entity, wheel_entities) in bindings {
+            let car_state = world
+                .query_one_mut::<(&CarComponent, &RigidBodyComponent)>(car_entity)
+                .ok()
+                .map(|(car, body)| {
+                    (
+                        body.handle,
+                        car.current_steering + car.damage.steering_pull(),
+                        (drive_torque(car), car.drivetrain, [car.rear_differential, car.front_differential], car.effective_assists(), car.wheel_base),
+                        (standstill_brake(car) * car.max_brake_force, car.brake_bias),
+                        (car.handbrake * car.max_brake_force, car.handbrake >= HANDBRAKE_LOCK_INPUT),
+                        car.total_mass(),
+                        car.aero_factors(),
+                        (car.front_anti_roll_stiffness, car.rear_anti_roll_stiffness),
+                    )
+                });
+            let Some((body_handle, steering, (drive_torque, drivetrain, differentials, assists, wheel_base), (brake_force, brake_bias), (handbrake_force, handbrake_locked), mass, aero, anti_roll)) = car_state else {
+                continue;
+            };
+            
+            let wheels: Vec<(hecs::Entity, WheelComponent)> = wheel_entities
+                .iter()
+                .filter_map(|&entity| {
+                    world.get::<&WheelComponent>(entity).ok().map(|wheel| (entity, (*wheel).clone()))
+                })
+                .collect();
+            let wheel_count = wheels.len().max(1) as f32;
+            let powered_count = wheels.iter().filter(|(_, w)| w.powered).count().max(1) as f32;
+            
+            // Тормоз делится между осями по балансу, внутри оси поровну
+            let front_count = wheels.iter().filter(|(_, w)| w.steering).count();
+            let rear_count = wheels.len() - front_count;
+            let brake_bias = match (front_count, rear_count) {
+                (0, _) => 0.0,
+                (_, 0) => 1.0,
+                _ => brake_bias.clamp(0.0, 1.0),
+            };
+            let axle_brake_share = |front: bool| {
+                if front {
+                    brake_bias / front_count.max(1) as f32
+                } else {
+                    (1.0 - brake_bias) / rear_count.max(1) as f32
+                }
+            };
+            let mass_share = mass / wheel_count;
+            
+            let Some((_, resource)) = world
+                .query_mut::<&mut Resource<(RigidBodySet, ColliderSet)>>()
+                .into_iter()
+                .next()
+            else {
+                return;
+            };
+            let (rigid_body_set, collider_set) = &mut resource.0;
+            self.query_pipeline.update(rigid_body_set, collider_set);
+            
+            let Some(body) = rigid_body_set.get(body_handle) else {
+                continue;
+            };
+            let body_position = *body.position();
+            let car_rotation = {
+                let r = body_position.rotation;
+                Quat::from_xyzw(r.i, r.j, r.k, r.w)
+            };
+            let car_position = Vec3::new(body_position.translation.x, body_position.translation.y, body_position.translation.z);
+            let car_forward = car_rotation * Vec3::Z;
+            let center_of_mass = {
+                let c = body.center_of_mass();
+                Vec3::new(c.x, c.y, c.z)
+            };
+            let car_velocity = {
+                let v = body.linvel();
+                Vec3::new(v.x, v.y, v.z)
+            };
+            
+            // Стабилизация подтормаживает одно колесо против заноса или сноса
+            let wheel_models: Vec<WheelComponent> = wheels.iter().map(|(_, wheel)| wheel.clone()).collect();
+            let yaw_rate = {
+                let w = body.angvel();
+                Vec3::new(w.x, w.y, w.z).dot(car_rotation * Vec3::Y)
+            };
+            let (stability, stability_torque) = stability_control(
+                &wheel_models,
+                assists.stability_control,
+                yaw_rate,
+                car_velocity.dot(car_forward),
+                steering,
+                wheel_base,
+                mass,
+            );
+            
+            // Контроль тяги убирает момент двигателя, пока буксует любое ведущее колесо
+            let modulations: Vec<(f32, f32)> = wheels.iter().map(|(_, wheel)| assist_modulation(wheel, assists, delta_time)).collect();
+            let drive_modulation = wheels
+                .iter()
+                .zip(&modulations)
+                .filter(|((_, wheel), _)| wheel.powered)
+                .map(|(_, modulation)| modulation.1)
+                .fold(1.0, f32::min);
+            
+            // Ведущие колеса оси делят ее долю момента; передние — управляемые
+            let axle_torque = [false, true].map(|front| drive_torque * drive_modulation * stability_torque * drivetrain.axle_share(front));
+            
+            let filter = QueryFilter::default().exclude_rigid_body(body_handle).exclude_sensors();
+            let mut forces = Vec::new();
+            let mut powered_wheel_speed = 0.0;
+            
+            let down = car_rotation * -Vec3::Y;
+            
+            // Сначала все лучи подвески: стабилизаторам нужно сжатие обоих колес оси
+            let hits: Vec<_> = wheels
+                .iter()
+                .map(|(_, wheel)| {
+                    // Луч из точки крепления подвески вниз по оси кузова
+                    let mount = car_position + car_rotation * (wheel.position + Vec3::Y * wheel.suspension_rest_length);
+                    let max_length = wheel.suspension_rest_length + wheel.suspension_travel;
+                    let ray = Ray::new(Point3::new(mount.x, mount.y, mount.z), Vector3::new(down.x, down.y, down.z));
+                    let hit = self.query_pipeline.cast_ray_and_get_normal(
+                        rigid_body_set,
+                        collider_set,
+                        &ray,
+                        max_length + wheel.radius,
+                        true,
+                        filter,
+                    );
+                    let min_length = (wheel.suspension_rest_length - wheel.suspension_travel).max(0.0);
+                    // Колесо в воздухе висит на полном ходе отбоя
+                    let length = hit
+                        .map(|(_, intersection)| (intersection.toi - wheel.radius).clamp(min_length, max_length))
+                        .unwrap_or(max_length);
+                    (mount, hit.map(|(_, intersection)| intersection), length)
+                })
+                .collect();
+            let lengths: Vec<f32> = hits.iter().map(|(_, _, length)| *length).collect();
+            let anti_roll = anti_roll_forces(&wheel_models, &lengths, anti_roll.0, anti_roll.1);
+            
+            // Тяга колес: поровну на ось, у пары колес оси — через дифференциал.
+            // Сцепление оцениваем по нагрузке прошлого шага, разницу скоростей — по ступицам
+            let mut drive_forces = vec![0.0; wheels.len()];
+            let mut speed_locks = Vec::new();
+            for front in [false, true] {
+                let axle: Vec<usize> = (0..wheels.len()).filter(|&i| wheels[i].1.powered && wheels[i].1.steering == front).collect();
+                for &i in &axle {
+                    drive_forces[i] = axle_torque[front as usize] / wheels[i].1.radius / axle.len() as f32;
+                }
+                let &[a, b] = axle.as_slice() else {
+                    continue;
+                };
+                let grip = [a, b].map(|i| {
+                    let wheel = &wheels[i].1;
+                    let grounded = hits[i].1.is_some() as i32 as f32;
+                    grounded * wheel.suspension_force * wheel.friction * wheel.surface_grip * wheel.tire_grip() * (1.0 - HYDROPLANE_GRIP_LOSS * wheel.hydroplaning)
+                });
+                let hub_speed = [a, b].map(|i| {
+                    let mount = hits[i].0;
+                    let v = body.velocity_at_point(&Point3::new(mount.x, mount.y, mount.z));
+                    Vec3::new(v.x, v.y, v.z).dot(car_forward)
+                });
+                let radius = wheels[a].1.radius;
+                let lock_force = (hub_speed[0] - hub_speed[1]) * mass_share * DIFFERENTIAL_LOCK_SHARE / delta_time;
+                let (forces, lock) = differentials[front as usize].split(axle_torque[front as usize] / radius, grip, lock_force, radius);
+                drive_forces[a] = forces[0];
+                drive_forces[b] = forces[1];
+                speed_locks.push((wheels[a].0, wheels[b].0, lock));
+            }
+            
+            for ((((((wheel_entity, wheel), (mount, hit, length)), anti_roll_force), drive), &(brake_modulation, drive_modulation)), stability_brake) in
+                wheels.iter().zip(&hits).zip(&anti_roll).zip(&drive_forces).zip(&modulations).zip(&stability)
+            {
+                let (mount, length) = (*mount, *length);
+                let max_length = wheel.suspension_rest_length + wheel.suspension_travel;
+                
+                let Some(intersection) = hit else {
+                    wheel_updates.push(WheelUpdate {
+                        entity: *wheel_entity,
+                        grounded: false,
+                        suspension_length: max_length,
+                        suspension_force: 0.0,
+                        // В воздухе колесо постепенно замедляется
+                        wheel_speed: wheel.wheel_speed * (1.0 - 0.5 * delta_time).max(0.0),
+                        slip_ratio: 0.0,
+                        slip_angle: 0.0,
+                        lateral_force: 0.0,
+                        longitudinal_force: 0.0,
+                        brake_modulation: wheel.brake_modulation,
+                        drive_modulation: wheel.drive_modulation,
+                    });
+                    if wheel.powered {
+                        powered_wheel_speed += wheel.wheel_speed / powered_count;
+                    }
+                    continue;
+                };
+                
+                let normal = Vec3::new(intersection.normal.x, intersection.normal.y, intersection.normal.z);
+                let contact = mount + down * intersection.toi;
+                
+                let contact_velocity = {
+                    let v = body.velocity_at_point(&Point3::new(contact.x, contact.y, contact.z));
+                    Vec3::new(v.x, v.y, v.z)
+                };
+                
+                let suspension_force = (suspension_force(wheel, length, -contact_velocity.dot(normal)) + anti_roll_force).max(0.0);
+                
+                // Оси шины в плоскости контакта с учетом поворота руля
+                let steer = if wheel.steering { Quat::from_rotation_y(-steering) } else { Quat::IDENTITY };
+                let wheel_forward = (car_rotation * steer * Vec3::Z).reject_from_normalized(normal).normalize_or_zero();
+                let wheel_side = normal.cross(wheel_forward).normalize_or_zero();
+                
+                let forward_speed = contact_velocity.dot(wheel_forward);
+                let side_speed = contact_velocity.dot(wheel_side);
+                
+                // Продольная сила: тяга ведущих колес и тормоз против направления качения;
+                // сопротивление качению на траве, гравии и песке работает как слабый тормоз
+                let mut braking = (brake_force * axle_brake_share(wheel.steering) + stability_brake) * brake_modulation + suspension_force * wheel.surface_rolling_resistance;
+                if !wheel.steering {
+                    braking += handbrake_force / (wheel_count / 2.0).max(1.0);
+                }
+                let tire = tire_forces(
+                    wheel,
+                    &TireContact {
+                        load: suspension_force,
+                        forward_speed,
+                        side_speed,
+                        drive_force: *drive,
+                        brake_force: braking,
+                        mass_share,
+                        downhill_force: -GRAVITY * mass_share * wheel_forward.y,
+                        locked: handbrake_locked && !wheel.steering,
+                    },
+                    delta_time,
+                );
+                let TireForces { longitudinal: longitudinal_force, lateral: lateral_force, slip_ratio, slip_angle, wheel_speed } = tire;
+                if wheel.powered {
+                    powered_wheel_speed += wheel_speed / powered_count;
+                }
+                
+                // Продольная сила приложена на высоте центра масс, иначе кузов опрокидывается от рывка;
+                // боковая — на центре крена ниже него, и кузов кренится в повороте на пружинах и стабилизаторах
+                let center_height = (center_of_mass - contact).dot(normal);
+                let tire_point = contact + normal * center_height;
+                let roll_center = contact + normal * center_height * ROLL_CENTER_SHARE;
+                forces.push((normal * suspension_force, contact));
+                forces.push((wheel_forward * longitudinal_force, tire_point));
+                forces.push((wheel_side * lateral_force, roll_center));
+                
+                wheel_updates.push(WheelUpdate {
+                    entity: *wheel_entity,
+                    grounded: true,
+                    suspension_length: length,
+                    suspension_force,
+                    wheel_speed,
+                    slip_ratio,
+                    slip_angle,
+                    lateral_force,
+                    longitudinal_force,
+                    brake_modulation,
+                    drive_modulation,
+                });
+            }
+            
+            // Аэродинамика считается от скорости относительно воздуха: встречный ветер тормозит
+            // и прижимает, боковой давит на борт выше и впереди центра масс и уводит машину
+            let (drag_factor, downforce_factor, side_factor) = aero;
+            let airspeed = car_velocity - wind;
+            let forward_airspeed = airspeed.dot(car_forward);
+            let side_airspeed = airspeed.dot(car_rotation * Vec3::X);
+            forces.push((-car_forward * forward_airspeed * forward_airspeed.abs() * drag_factor, center_of_mass));
+            forces.push((car_rotation * -Vec3::Y * forward_airspeed * forward_airspeed * downforce_factor, center_of_mass));
+            forces.push((
+                car_rotation * -Vec3::X * side_airspeed * side_airspeed.abs() * side_factor,
+                center_of_mass + car_rotation * SIDE_PRESSURE_OFFSET,
+            ));
+            
+            // Силы действуют на протяжении всех подшагов физики этого кадра;
+            // моменты от приложения сил в точке сбрасываются отдельно
+            if let Some(body) = rigid_body_set.get_mut(body_handle) {
+                body.reset_forces(false);
+                body.reset_torques(false);
+                for (force, point) in forces {
+                    body.add_force_at_point(Vector3::new(force.x, force.y, force.z), Point3::new(point.x, point.y, point.z), true);
+                }
+            }
+            
+            // Заблокированный дифференциал вращает колеса оси вместе, открытый их не связывает
+            for (a, b, lock) in speed_locks {
+                let updates: Vec<usize> = wheel_updates
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, update)| update.grounded && (update.entity == a || update.entity == b))
+                    .map(|(i, _)| i)
+                    .collect();
+                if let &[i, j] = updates.as_slice() {
+                    let mean = (wheel_updates[i].wheel_speed + wheel_updates[j].wheel_speed) / 2.0;
+                    for k in [i, j] {
+                        wheel_updates[k].wheel_speed += (mean - wheel_updates[k].wheel_speed) * lock.clamp(0.0, 1.0);
+                    }
+                }
+            }
+            
+            car_updates.push((car_entity, car_velocity.dot(car_forward), powered_wheel_speed));
+        }
         
-        return car_entity;
-    } else {
-        // Если ресурс не найден, создаем сущность без физики
-        let rigid_body = RigidBodyComponent {
-            handle: Default::default(),
-            body_type: RigidBodyType::Dynamic,
-        };
+        for update in wheel_updates {
+            if let Ok(wheel) = world.query_one_mut::<&mut WheelComponent>(update.entity) {
+                wheel.grounded = update.grounded;
+                wheel.suspension_length = update.suspension_length;
+                wheel.suspension_force = update.suspension_force;
+                wheel.wheel_speed = update.wheel_speed;
+                wheel.slip_ratio = update.slip_ratio;
+                wheel.slip_angle = update.slip_angle;
+                wheel.lateral_force = update.lateral_force;
+                wheel.longitudinal_force = update.longitudinal_force;
+                wheel.brake_modulation = update.brake_modulation;
+                wheel.drive_modulation = update.drive_modulation;
+            }
+        }
         
-        let collider = ColliderComponent {
-            handle: Default::default(),
-            shape_type: Default::default(),
-        };
+        for (car_entity, speed, powered_wheel_speed) in car_updates {
+            if let Ok(car) = world.query_one_mut::<&mut CarComponent>(car_entity) {
+                car.current_speed = speed;
+                // Обороты двигателя следуют за ведущими колесами
+                car.current_rpm = engine_rpm(car, powered_wheel_speed);
+                car.shift_timer = (car.shift_timer - delta_time).max(0.0);
+            }
+        }
+    }
+}
+
+/// Покрытие за пределами трассы, если ландшафт не задан
+const OFF_TRACK_SURFACE: SurfaceType = SurfaceType::Grass;
+
+/// Скорость начала аквапланирования на воде опорной глубины, м/с; на глубокой воде раньше
+const HYDROPLANE_SPEED: f32 = 20.0;
+const HYDROPLANE_REFERENCE_DEPTH: f32 = 0.008;
+
+/// Мельче этого вода уходит в протектор, м
+const HYDROPLANE_MIN_DEPTH: f32 = 0.002;
+
+/// На сколько выше скорости начала колесо всплывает полностью (доля)
+const HYDROPLANE_RAMP: f32 = 0.3;
+
+/// Доля сцепления и поворота руля, теряемая при полном аквапланировании
+const HYDROPLANE_GRIP_LOSS: f32 = 0.85;
+const HYDROPLANE_STEERING_LOSS: f32 = 0.6;
+
+/// С этого уровня аквапланирование считается начавшимся
+const HYDROPLANE_EVENT_LEVEL: f32 = 0.5;
+
+/// Доля потерянного контакта колеса с покрытием на воде заданной глубины
+pub fn hydroplane_factor(water_depth: f32, speed: f32) -> f32 {
+    if water_depth < HYDROPLANE_MIN_DEPTH {
+        return 0.0;
+    }
+    let onset = HYDROPLANE_SPEED * (HYDROPLANE_REFERENCE_DEPTH / water_depth).sqrt();
+    ((speed.abs() - onset) / (onset * HYDROPLANE_RAMP)).clamp(0.0, 1.0)
+}
+
+/// Начало или конец аквапланирования машины, для интерфейса и звука;
+/// очередь `EventQueue<HydroplaneEvent>` обновляется каждый кадр
+#[derive(Debug, Clone)]
+pub struct HydroplaneEvent {
+    pub car: hecs::Entity,
+    pub active: bool,
+    pub speed: f32,
+}
+
+/// Система определения поверхности под колесами
+pub struct WheelSurfaceSystem;
+
+impl System for WheelSurfaceSystem {
+    fn update(&mut self, world: &mut World, _delta_time: f32) {
+        // В снегопад все, что не покрыто асфальтом или бетоном, засыпано снегом
+        let snowing = world
+            .query::<&WeatherComponent>()
+            .iter()
+            .any(|(_, weather)| matches!(weather.weather_type, WeatherType::Snow) && weather.intensity > 0.3);
         
-        // Создаем сущность автомобиля
-        let car_entity = world.spawn((car_component, transform, rigid_body, collider));
+        let materials = physics_materials(world);
+        let puddles = world
+            .query::<&Resource<PuddleField>>()
+            .iter()
+            .next()
+            .map(|(_, res)| res.0.clone())
+            .unwrap_or_default();
+        let mut surfaces = Vec::new();
+        let mut cars = Vec::new();
+        for (_, binding) in world.query::<&CarWheelBindingComponent>().iter() {
+            let Ok(car_transform) = world.get::<&TransformComponent>(binding.car_entity) else {
+                continue;
+            };
+            let speed = world.get::<&CarComponent>(binding.car_entity).map(|car| car.current_speed).unwrap_or(0.0);
+            let mut front = (0.0, 0.0); // Сумма и число управляемых колес
+
+            for &wheel_entity in &binding.wheel_entities {
+                let Ok(wheel) = world.get::<&WheelComponent>(wheel_entity) else {
+                    continue;
+                };
+                let contact = car_transform.position
+                    + car_transform.rotation * (wheel.position - Vec3::Y * wheel.radius);
+                // Вне сегментов и ландшафта колесо съехало на обочину
+                let (surface, material) = surface_at(world, contact)
+                    .unwrap_or((OFF_TRACK_SURFACE, OFF_TRACK_SURFACE.material()));
+                let (surface, material) = match surface {
+                    SurfaceType::Grass | SurfaceType::Dirt if snowing => (SurfaceType::Snow, SurfaceType::Snow.material()),
+                    _ => (surface, material),
+                };
+                let water_depth = puddles.depth_at(contact);
+                let hydroplaning = hydroplane_factor(water_depth, speed);
+                if wheel.steering {
+                    front = (front.0 + hydroplaning, front.1 + 1.0);
+                }
+                surfaces.push((wheel_entity, surface, materials.get(material).clone(), water_depth, hydroplaning));
+            }
+            cars.push((binding.car_entity, if front.1 > 0.0 { front.0 / front.1 } else { 0.0 }, speed));
+        }
         
-        // Создаем колеса для автомобиля
-        let wheel_entities = create_wheels_for_car(world, car_entity);
+        for (wheel_entity, surface, material, water_depth, hydroplaning) in surfaces {
+            if let Ok(mut wheel) = world.get::<&mut WheelComponent>(wheel_entity) {
+                wheel.surface_type = surface;
+                wheel.surface_grip = material.friction;
+                wheel.surface_rolling_resistance = material.rolling_resistance;
+                wheel.water_depth = water_depth;
+                wheel.hydroplaning = hydroplaning;
+            }
+        }
         
-        // Создаем компонент связи между автомобилем и колесами
-        let binding = CarWheelBindingComponent {
-            car_entity,
-            wheel_entities,
-        };
+        // Переход через уровень события в любую сторону
+        let mut events = Vec::new();
+        let mut splash = false;
+        for (car_entity, hydroplaning, speed) in cars {
+            let Ok(mut car) = world.get::<&mut CarComponent>(car_entity) else {
+                continue;
+            };
+            let was_active = car.hydroplaning >= HYDROPLANE_EVENT_LEVEL;
+            let active = hydroplaning >= HYDROPLANE_EVENT_LEVEL;
+            car.hydroplaning = hydroplaning;
+            drop(car);
+            if active != was_active {
+                splash |= active && world.get::<&PlayerCarComponent>(car_entity).is_ok();
+                events.push(HydroplaneEvent { car: car_entity, active, speed });
+            }
+        }
+        
+        match world.query_mut::<&mut Resource<EventQueue<HydroplaneEvent>>>().into_iter().next() {
+            Some((_, queue)) => {
+                queue.0.clear();
+                for event in events {
+                    queue.0.publish(event);
+                }
+            }
+            None => {
+                let mut queue = EventQueue::new();
+                for event in events {
+                    queue.publish(event);
+                }
+                world.spawn((Resource(queue),));
+            }
+        }
+        
+        // Всплеск из-под колес машины игрока
+        if splash {
+            let sound = AudioEvent::PlaySound {
+                name: "water_splash".to_string(),
+                volume: 0.7,
+                looping: false,
+            };
+            match world.query_mut::<&mut Resource<Vec<AudioEvent>>>().into_iter().next() {
+                Some((_, queue)) => queue.0.push(sound),
+                None => {
+                    world.spawn((Resource(vec![sound]),));
+                }
+            }
+        }
+    }
+}
+
+/// Система анимации колес: трансформации колес в мировых координатах
+/// с учетом хода подвески, поворота руля и вращения
+pub struct WheelAnimationSystem;
+
+impl System for WheelAnimationSystem {
+    fn update(&mut self, world: &mut World, delta_time: f32) {
+        let mut wheel_steering = Vec::new();
+        for (_, binding) in world.query::<&CarWheelBindingComponent>().iter() {
+            if let Ok(car) = world.get::<&CarComponent>(binding.car_entity) {
+                for &wheel_entity in &binding.wheel_entities {
+                    wheel_steering.push((wheel_entity, car.current_steering));
+                }
+            }
+        }
+        
+        // Мировую трансформацию колеса по трансформации кузова выводит система иерархии
+        for (wheel_entity, steering) in wheel_steering {
+            let Ok((wheel, link)) = world.query_one_mut::<(&mut WheelComponent, &mut ParentComponent)>(wheel_entity) else {
+                continue;
+            };
+            
+            wheel.spin_angle = (wheel.spin_angle + wheel.wheel_speed * delta_time) % std::f32::consts::TAU;
+            
+            // Сжатие подвески поднимает колесо относительно точки крепления
+            let travel = wheel.suspension_rest_length - wheel.suspension_length;
+            
+            // Положительный угол руля - поворот вправо (к -X)
+            let steer = if wheel.steering { Quat::from_rotation_y(-steering) } else { Quat::IDENTITY };
+            
+            link.local.position = wheel.position + Vec3::Y * travel;
+            link.local.rotation = steer * Quat::from_rotation_x(wheel.spin_angle);
+        }
+    }
+}
+
+/// Температура остывших тормозов, °C
+pub const AMBIENT_BRAKE_TEMPERATURE: f32 = 20.0;
+
+/// Сцепление, которое теряет полностью стертая покрышка
+const TIRE_WEAR_GRIP_LOSS: f32 = 0.3;
+
+/// Плотность бензина, кг/л
+const FUEL_DENSITY: f32 = 0.745;
+
+/// Удельный расход топлива двигателем, кг/(кВт·ч)
+const SPECIFIC_FUEL_CONSUMPTION: f32 = 0.3;
+
+/// Расход на холостом ходу, л/с
+const IDLE_FUEL_FLOW: f32 = 0.0003;
+
+/// Машина медленнее этого стоит в боксе, м/с
+pub const PIT_STOP_SPEED: f32 = 0.5;
+
+/// Масса тела rapier обновляется, когда топливо изменило ее хотя бы на столько, кг
+const FUEL_MASS_STEP: f32 = 0.5;
+
+/// Расход топлива; масса тела rapier следует за остатком в баке
+pub struct FuelSystem {
+    applied_mass: HashMap<hecs::Entity, f32>, // Масса, переданная телу в последний раз
+}
+
+impl FuelSystem {
+    pub fn new() -> Self {
+        Self {
+            applied_mass: HashMap::new(),
+        }
+    }
+}
+
+impl Default for FuelSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl System for FuelSystem {
+    fn update(&mut self, world: &mut World, delta_time: f32) {
+        let mut mass_updates = Vec::new();
+        for (entity, (car, body)) in world.query_mut::<(&mut CarComponent, &RigidBodyComponent)>() {
+            // Заправкой в боксе занимается система пит-стопа
+            if !car.refueling {
+                car.fuel = (car.fuel - fuel_flow(car) * delta_time).max(0.0);
+            }
+            
+            let total_mass = car.total_mass();
+            let applied = *self.applied_mass.entry(entity).or_insert(total_mass);
+            if (total_mass - applied).abs() >= FUEL_MASS_STEP {
+                mass_updates.push((entity, body.handle, total_mass, car.mass_properties()));
+            }
+        }
         
-        world.spawn((binding,));
+        if mass_updates.is_empty() {
+            return;
+        }
+        let Some((_, resource)) = world
+            .query_mut::<&mut Resource<(RigidBodySet, ColliderSet)>>()
+            .into_iter()
+            .next()
+        else {
+            return;
+        };
+        for (entity, handle, mass, properties) in mass_updates {
+            if let Some(body) = resource.0 .0.get_mut(handle) {
+                body.set_additional_mass_properties(properties, false);
+                self.applied_mass.insert(entity, mass);
+            }
+        }
+        self.applied_mass.retain(|entity, _| world.contains(*entity));
+    }
+}
+
+/// Простая тепловая модель тормозов: нагрев работой торможения, остывание потоком воздуха
+pub struct BrakeTemperatureSystem {
+    pub heat_capacity: f32,  // Дж/°C на один диск
+    pub base_cooling: f32,   // Доля перегрева, отводимая за секунду на месте
+    pub speed_cooling: f32,  // Дополнительное охлаждение на каждый м/с
+}
+
+impl BrakeTemperatureSystem {
+    pub fn new() -> Self {
+        Self {
+            heat_capacity: 4500.0,
+            base_cooling: 0.02,
+            speed_cooling: 0.004,
+        }
+    }
+}
+
+impl Default for BrakeTemperatureSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl System for BrakeTemperatureSystem {
+    fn update(&mut self, world: &mut World, delta_time: f32) {
+        let mut wheel_brakes = Vec::new();
+        for (_, binding) in world.query::<&CarWheelBindingComponent>().iter() {
+            if let Ok(car) = world.get::<&CarComponent>(binding.car_entity) {
+                // Тормозное усилие делится поровну между колесами
+                let force_per_wheel = car.brake * car.max_brake_force / binding.wheel_entities.len().max(1) as f32;
+                for &wheel_entity in &binding.wheel_entities {
+                    wheel_brakes.push((wheel_entity, force_per_wheel));
+                }
+            }
+        }
         
-        return car_entity;
+        for (wheel_entity, brake_force) in wheel_brakes {
+            let Ok(wheel) = world.query_one_mut::<&mut WheelComponent>(wheel_entity) else {
+                continue;
+            };
+            let surface_speed = (wheel.wheel_speed * wheel.radius).abs();
+            let heating = brake_force * surface_speed / self.heat_capacity;
+            let cooling = (wheel.brake_temperature - AMBIENT_BRAKE_TEMPERATURE)
+                * (self.base_cooling + self.speed_cooling * surface_speed);
+            wheel.brake_temperature = (wheel.brake_temperature + (heating - cooling) * delta_time)
+                .max(AMBIENT_BRAKE_TEMPERATURE);
+        }
     }
 }
 
-/// Создает колеса для автомобиля
-fn create_wheels_for_car(world: &mut World, _car_entity: hecs::Entity) -> Vec<hecs::Entity> {
-    let mut wheel_entities = Vec::new();
+/// Износ покрышек: резина стирается с пробегом и еще сильнее при скольжении под нагрузкой
+pub struct TireWearSystem {
+    pub rolling_wear: f32, // Износ на метр качения
+    pub sliding_wear: f32, // Износ на джоуль работы скольжения
+}
+
+impl TireWearSystem {
+    pub fn new() -> Self {
+        Self {
+            rolling_wear: 2e-6,
+            sliding_wear: 6e-8,
+        }
+    }
+}
+
+impl Default for TireWearSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl System for TireWearSystem {
+    fn update(&mut self, world: &mut World, delta_time: f32) {
+        for (_, wheel) in world.query_mut::<&mut WheelComponent>() {
+            if !wheel.grounded {
+                continue;
+            }
+            let surface_speed = (wheel.wheel_speed * wheel.radius).abs();
+            // Мощность скольжения: продольное проскальзывание и боковой увод пятна контакта
+            let sliding_power = (wheel.longitudinal_force * wheel.slip_ratio).abs() * surface_speed
+                + (wheel.lateral_force * wheel.slip_angle.tan()).abs() * surface_speed;
+            let wear = self.rolling_wear * surface_speed + self.sliding_wear * sliding_power;
+            wheel.tire_wear = (wheel.tire_wear + wear * delta_time).min(1.0);
+        }
+    }
+}
+
+/// Высота тона петли двигателя на холостых и громкость без газа
+const ENGINE_SOUND_IDLE_PITCH: f32 = 0.6;
+const ENGINE_SOUND_IDLE_VOLUME: f32 = 0.3;
+
+/// Звук двигателя: тон по оборотам, громкость по газу
+pub struct EngineSoundSystem;
+
+impl System for EngineSoundSystem {
+    fn update(&mut self, world: &mut World, _delta_time: f32) {
+        for (_, (car, source)) in world.query_mut::<(&CarComponent, &mut AudioSourceComponent)>() {
+            let rpm_share = (car.current_rpm / car.redline_rpm.max(1.0)).clamp(0.0, 1.2);
+            source.pitch = ENGINE_SOUND_IDLE_PITCH + rpm_share;
+            source.volume = ENGINE_SOUND_IDLE_VOLUME + (1.0 - ENGINE_SOUND_IDLE_VOLUME) * car.throttle.clamp(0.0, 1.0);
+        }
+    }
+}
+
+/// Скорость качения, на которой звук покрытия звучит в полную силу, м/с
+const SURFACE_SOUND_FULL_SPEED: f32 = 30.0;
+
+/// Звук покрытия под каждым колесом: поребрик, гравий, трава; громкость и тон по скорости качения
+pub struct WheelSurfaceSoundSystem;
+
+impl System for WheelSurfaceSoundSystem {
+    fn update(&mut self, world: &mut World, _delta_time: f32) {
+        let mut missing = Vec::new();
+        for (entity, (wheel, source)) in world.query_mut::<(&WheelComponent, Option<&mut AudioSourceComponent>)>() {
+            let sound = wheel.surface_type.rolling_sound().filter(|_| wheel.grounded).unwrap_or("");
+            let speed_share = ((wheel.wheel_speed * wheel.radius).abs() / SURFACE_SOUND_FULL_SPEED).clamp(0.0, 1.0);
+            let Some(source) = source else {
+                if !sound.is_empty() {
+                    missing.push(entity);
+                }
+                continue;
+            };
+            // Смена покрытия перезапускает звук: старый останавливается звуковой системой
+            if source.sound_name != sound {
+                source.sound_name = sound.to_string();
+                source.sound_id = None;
+            }
+            source.volume = speed_share;
+            source.pitch = 0.8 + 0.4 * speed_share;
+        }
+        for entity in missing {
+            let source = AudioSourceComponent {
+                volume: 0.0,
+                looping: true,
+                min_distance: 2.0,
+                max_distance: 40.0,
+                ..Default::default()
+            };
+            let _ = world.insert_one(entity, source);
+        }
+    }
+}
+
+/// Дальний свет бьет дальше и ярче, но уже ближнего
+const HIGH_BEAM_RANGE_SCALE: f32 = 2.0;
+const HIGH_BEAM_INTENSITY_SCALE: f32 = 1.5;
+const HIGH_BEAM_ANGLE_SCALE: f32 = 0.7;
+
+/// Система фар: режим по нажатиям игрока и времени суток, прожекторы следуют за кузовом
+pub struct CarLightsSystem {
+    held: HashMap<InputAction, bool>,
+}
+
+impl CarLightsSystem {
+    pub fn new() -> Self {
+        Self { held: HashMap::new() }
+    }
+
+    /// Срабатывание по нажатию, а не удержанию
+    fn pressed(&mut self, inputs: &HashMap<InputAction, f32>, action: InputAction) -> bool {
+        let down = *inputs.get(&action).unwrap_or(&0.0) > 0.5;
+        let was_down = self.held.insert(action, down).unwrap_or(false);
+        down && !was_down
+    }
+}
+
+impl Default for CarLightsSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl System for CarLightsSystem {
+    fn update(&mut self, world: &mut World, _delta_time: f32) {
+        let inputs = world
+            .query::<&Resource<HashMap<InputAction, f32>>>()
+            .iter()
+            .next()
+            .map(|(_, res)| res.0.clone())
+            .unwrap_or_default();
+        let toggle = self.pressed(&inputs, InputAction::Headlights);
+        let high_beam = self.pressed(&inputs, InputAction::HighBeam);
+        let dark = world.query::<&TimeOfDayComponent>().iter().next().map(|(_, time)| time.is_dark());
+
+        let mut lights: Vec<(hecs::Entity, Vec3, Quat, HeadlightMode)> = Vec::new();
+        for (_, (headlight, transform, player)) in
+            world.query_mut::<(&mut HeadlightComponent, &TransformComponent, Option<&PlayerCarComponent>)>()
+        {
+            // Автомат срабатывает только при смене освещенности, чтобы не спорить с игроком
+            if let Some(dark) = dark {
+                if headlight.auto_on && headlight.was_dark != Some(dark) {
+                    headlight.mode = if dark { HeadlightMode::Low } else { HeadlightMode::Off };
+                }
+                headlight.was_dark = Some(dark);
+            }
+            if player.is_some() {
+                if toggle {
+                    headlight.mode = if headlight.is_on() { HeadlightMode::Off } else { HeadlightMode::Low };
+                }
+                if high_beam {
+                    headlight.mode = if headlight.mode == HeadlightMode::High { HeadlightMode::Low } else { HeadlightMode::High };
+                }
+            }
+            lights.extend(
+                headlight
+                    .lights
+                    .iter()
+                    .map(|&light| (light, transform.position, transform.rotation, headlight.mode)),
+            );
+        }
+
+        let low_beam = SpotLightComponent::default();
+        for (light_entity, position, rotation, mode) in lights {
+            let Ok((mount, transform, light)) = world
+                .query_one_mut::<(&HeadlightMountComponent, &mut TransformComponent, &mut SpotLightComponent)>(light_entity)
+            else {
+                continue;
+            };
+            transform.position = position + rotation * mount.local_offset;
+            transform.rotation = rotation * mount.local_rotation;
+            let (range, intensity, angle) = if mode == HeadlightMode::High {
+                (HIGH_BEAM_RANGE_SCALE, HIGH_BEAM_INTENSITY_SCALE, HIGH_BEAM_ANGLE_SCALE)
+            } else {
+                (1.0, 1.0, 1.0)
+            };
+            light.enabled = mode != HeadlightMode::Off;
+            light.range = low_beam.range * range;
+            light.intensity = low_beam.intensity * intensity;
+            light.inner_angle = low_beam.inner_angle * angle;
+            light.outer_angle = low_beam.outer_angle * angle;
+        }
+    }
+}
+
+/// Машина с кузовом, наклоненным сильнее этого (косинус угла к вертикали), считается перевернутой
+const FLIPPED_UP_DOT: f32 = 0.5;
+
+/// Высота кузова над полотном при возврате на трассу, м
+const RESET_HEIGHT: f32 = 0.8;
+
+/// Возврат перевернутой или вылетевшей машины игрока на ближайший сегмент трассы
+pub struct CarResetSystem {
+    held: bool,
+}
+
+impl CarResetSystem {
+    pub fn new() -> Self {
+        Self { held: false }
+    }
+}
+
+impl Default for CarResetSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl System for CarResetSystem {
+    fn update(&mut self, world: &mut World, _delta_time: f32) {
+        let down = world
+            .query::<&Resource<HashMap<InputAction, f32>>>()
+            .iter()
+            .next()
+            .is_some_and(|(_, res)| *res.0.get(&InputAction::ResetCar).unwrap_or(&0.0) > 0.5);
+        let pressed = down && !self.held;
+        self.held = down;
+        if !pressed {
+            return;
+        }
+
+        let Some((car_entity, position, rotation)) = world
+            .query::<(&PlayerCarComponent, &TransformComponent)>()
+            .iter()
+            .next()
+            .map(|(entity, (_, transform))| (entity, transform.position, transform.rotation))
+        else {
+            return;
+        };
+        // На колесах и на трассе возврат не нужен
+        let flipped = (rotation * Vec3::Y).y < FLIPPED_UP_DOT;
+        if !flipped && on_track(world, position) {
+            return;
+        }
+        let Some((point, track_rotation)) = nearest_track_pose(world, position, rotation * Vec3::Z) else {
+            return;
+        };
+        teleport_body(world, car_entity, point + Vec3::Y * RESET_HEIGHT, track_rotation);
+
+        if let Ok(car) = world.query_one_mut::<&mut CarComponent>(car_entity) {
+            car.current_speed = 0.0;
+            car.current_steering = 0.0;
+        }
+        let wheels: Vec<hecs::Entity> = world
+            .query::<&CarWheelBindingComponent>()
+            .iter()
+            .filter(|(_, binding)| binding.car_entity == car_entity)
+            .flat_map(|(_, binding)| binding.wheel_entities.clone())
+            .collect();
+        for wheel_entity in wheels {
+            if let Ok(wheel) = world.query_one_mut::<&mut WheelComponent>(wheel_entity) {
+                wheel.wheel_speed = 0.0;
+                wheel.slip_ratio = 0.0;
+                wheel.slip_angle = 0.0;
+                wheel.suspension_length = wheel.suspension_rest_length;
+            }
+        }
+    }
+}
+
+/// Создает пару фар в передней части кузова и вешает на машину управление ими
+pub(crate) fn create_headlights_for_car(world: &mut World, car_entity: hecs::Entity, chassis_half_extents: Vec3) -> Vec<hecs::Entity> {
+    // Фары немного опущены вниз, чтобы освещать дорогу
+    let local_rotation = Quat::from_rotation_x(0.06);
+    let x = chassis_half_extents.x - 0.3;
     
-    // Получаем характеристики автомобиля для расположения колес
-    let wheel_base = 2.5;  // В реальном приложении берется из компонента автомобиля
+    let lights: Vec<hecs::Entity> = [-x, x]
+        .iter()
+        .map(|&x| {
+            let mount = HeadlightMountComponent {
+                car_entity,
+                local_offset: Vec3::new(x, 0.1, chassis_half_extents.z + 0.05),
+                local_rotation,
+            };
+            world.spawn((mount, TransformComponent::default(), SpotLightComponent::default()))
+        })
+        .collect();
+    if world.insert_one(car_entity, HeadlightComponent::new(lights.clone())).is_err() {
+        eprintln!("Машина для фар не найдена");
+    }
+    lights
+}
+
+/// Стандартная раскладка колес: переднее левое, переднее правое, заднее левое, заднее правое
+pub fn default_wheels(drivetrain: DrivetrainType) -> Vec<WheelComponent> {
+    let wheel_base = 2.5;
     let track_width = 1.8;
     
-    // Создаем 4 колеса: переднее левое, переднее правое, заднее левое, заднее правое
-    let wheel_positions = [
-        Vec3::new(-track_width/2.0, 0.0, wheel_base/2.0),
-        Vec3::new(track_width/2.0, 0.0, wheel_base/2.0),
-        Vec3::new(-track_width/2.0, 0.0, -wheel_base/2.0),
-        Vec3::new(track_width/2.0, 0.0, -wheel_base/2.0),
-    ];
+    // Центры колес при свободной подвеске ниже центра кузова, чтобы днище не касалось дороги
+    let wheel_height = -0.4;
     
-    for (i, position) in wheel_positions.iter().enumerate() {
+    [
+        Vec3::new(-track_width/2.0, wheel_height, wheel_base/2.0),
+        Vec3::new(track_width/2.0, wheel_height, wheel_base/2.0),
+        Vec3::new(-track_width/2.0, wheel_height, -wheel_base/2.0),
+        Vec3::new(track_width/2.0, wheel_height, -wheel_base/2.0),
+    ]
+    .iter()
+    .enumerate()
+    .map(|(i, position)| {
         let is_front = i < 2;
-        
-        let wheel = WheelComponent {
+        WheelComponent {
             position: *position,
             steering: is_front,
-            powered: !is_front,  // Задний привод
+            powered: drivetrain.axle_share(is_front) > 0.0,
             ..Default::default()
-        };
-        
-        let transform = TransformComponent {
-            position: *position,
-            ..Default::default()
-        };
-        
-        // Заглушки для физических компонентов
-        let rigid_body = RigidBodyComponent {
-            handle: Default::default(),
-            body_type: RigidBodyType::Dynamic,
-        };
-        
-        let collider = ColliderComponent {
-            handle: Default::default(),
-            shape_type: Default::default(),
-        };
-        
-        let wheel_entity = world.spawn((wheel, transform, rigid_body, collider));
-        wheel_entities.push(wheel_entity);
-    }
-    
-    wheel_entities
-} 
\ No newline at end of file
+        }
+    })
+    .collect()
+}
+
+/// Создает сущности колес автомобиля дочерними к кузову
+pub(crate) fn create_wheels_for_car(world: &mut World, car_entity: hecs::Entity, wheels: Vec<WheelComponent>) -> Vec<hecs::Entity> {
+    let car_transform = world.get::<&TransformComponent>(car_entity).map(|transform| *transform).unwrap_or_default();
+    wheels
+        .into_iter()
+        .map(|wheel| {
+            let local = TransformComponent {
+                position: wheel.position,
+                ..Default::default()
+            };
+            let transform = child_world_transform(&car_transform, &local);
+            world.spawn((wheel, transform, ParentComponent::new(car_entity, local)))
+        })
+        .collect()
+}
\ No newline at end of file
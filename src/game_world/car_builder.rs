@@ -0,0 +1,242 @@
+use crate::core::audio::AudioSourceComponent;
+use crate::core::ecs::Resource;
+use crate::core::physics::{physics_materials, ColliderComponent, ColliderShapeType, PhysicsMaterialId, RigidBodyComponent, RigidBodyType, TransformComponent};
+use crate::core::renderer::{RenderComponent, RenderResourceManager};
+use crate::game_world::blob_shadow::BlobShadowComponent;
+use crate::game_world::car::{
+    create_headlights_for_car, create_wheels_for_car, default_wheels, CarAudioSet, CarComponent, CarWheelBindingComponent,
+    HeadlightMountComponent, WheelComponent,
+};
+use crate::game_world::car_definition::{CarDefinition, CarRenderDefinition};
+use crate::game_world::wheel_parts::{create_wheel_parts, WheelPartAssets};
+use glam::{Quat, Vec3};
+use hecs::World;
+use rapier3d::na::{Quaternion, UnitQuaternion, Vector3};
+use rapier3d::prelude::{ActiveEvents, ColliderBuilder, ColliderSet, RigidBodyBuilder, RigidBodySet};
+
+/// Половины размеров кузова по умолчанию: ширина, высота и длина 2 × 1 × 4 м
+const DEFAULT_CHASSIS_HALF_EXTENTS: Vec3 = Vec3::new(1.0, 0.5, 2.0);
+
+/// Сборщик машины: проверяет конфигурацию и создает кузов, колеса, фары и их связи.
+/// Меши создаются только при наличии ресурсов рендера
+pub struct CarBuilder {
+    car: CarComponent,
+    wheels: Vec<WheelComponent>,
+    chassis_half_extents: Vec3,
+    render: Option<CarRenderDefinition>,
+    audio: CarAudioSet,
+    position: Vec3,
+    rotation: Quat,
+}
+
+impl CarBuilder {
+    /// Машина с заданными характеристиками и стандартной раскладкой колес
+    pub fn new(car: CarComponent) -> Self {
+        Self {
+            wheels: default_wheels(car.drivetrain),
+            car,
+            chassis_half_extents: DEFAULT_CHASSIS_HALF_EXTENTS,
+            render: None,
+            audio: CarAudioSet::default(),
+            position: Vec3::ZERO,
+            rotation: Quat::IDENTITY,
+        }
+    }
+
+    /// Машина по описанию: характеристики, колеса, внешний вид, звуки; кузов по размеру меша
+    pub fn from_definition(definition: &CarDefinition) -> Self {
+        Self::new(definition.car_component())
+            .wheels(definition.wheel_components())
+            .chassis(Vec3::from(definition.render.scale) * 0.5)
+            .render(definition.render.clone())
+            .audio(definition.audio.clone())
+    }
+
+    /// Половины размеров коробки кузова, м
+    pub fn chassis(mut self, half_extents: Vec3) -> Self {
+        self.chassis_half_extents = half_extents;
+        self
+    }
+
+    /// Раскладка колес; ведущие колеса при создании определяются схемой привода
+    pub fn wheels(mut self, wheels: Vec<WheelComponent>) -> Self {
+        self.wheels = wheels;
+        self
+    }
+
+    pub fn render(mut self, render: CarRenderDefinition) -> Self {
+        self.render = Some(render);
+        self
+    }
+
+    pub fn audio(mut self, audio: CarAudioSet) -> Self {
+        self.audio = audio;
+        self
+    }
+
+    pub fn at(mut self, position: Vec3, rotation: Quat) -> Self {
+        self.position = position;
+        self.rotation = rotation;
+        self
+    }
+
+    /// Проверка конфигурации до создания сущностей
+    pub fn validate(&self) -> Result<(), String> {
+        let name = &self.car.name;
+        if self.chassis_half_extents.min_element() <= 0.0 {
+            return Err(format!("Car '{}' has invalid chassis dimensions", name));
+        }
+        if self.car.mass <= 0.0 || self.car.inertia.min_element() <= 0.0 {
+            return Err(format!("Car '{}' has invalid mass or inertia", name));
+        }
+        if self.wheels.len() < 3 || self.wheels.iter().any(|wheel| wheel.radius <= 0.0 || wheel.width <= 0.0) {
+            return Err(format!("Car '{}' needs at least three valid wheels", name));
+        }
+        if self.wheels.iter().any(|wheel| wheel.suspension_rest_length <= 0.0 || wheel.suspension_stiffness <= 0.0) {
+            return Err(format!("Car '{}' has invalid suspension", name));
+        }
+        if !self.wheels.iter().any(|wheel| wheel.steering) {
+            return Err(format!("Car '{}' has no steering wheels", name));
+        }
+        if !self.wheels.iter().any(|wheel| self.car.drivetrain.axle_share(wheel.steering) > 0.0) {
+            return Err(format!("Car '{}' has no driven wheels for its drivetrain", name));
+        }
+        if self.audio.engine.is_empty() {
+            return Err(format!("Car '{}' has no engine sound", name));
+        }
+        Ok(())
+    }
+
+    /// Создает машину без мешей: для физики, симуляции и серверной стороны
+    pub fn spawn(self, world: &mut World) -> Result<hecs::Entity, String> {
+        self.validate()?;
+        let CarBuilder {
+            car,
+            mut wheels,
+            chassis_half_extents,
+            render: _,
+            audio,
+            position,
+            rotation,
+        } = self;
+        for wheel in &mut wheels {
+            wheel.powered = car.drivetrain.axle_share(wheel.steering) > 0.0;
+        }
+
+        let transform = TransformComponent {
+            position,
+            rotation,
+            ..Default::default()
+        };
+        let engine_sound = AudioSourceComponent {
+            sound_name: audio.engine.clone(),
+            volume: 0.0,
+            looping: true,
+            min_distance: 3.0,
+            max_distance: 80.0,
+            ..Default::default()
+        };
+
+        // Тело кузова в мире rapier; без физического мира сущность получает заглушки
+        let materials = physics_materials(world);
+        let (rigid_body, collider) = match world
+            .query_mut::<&mut Resource<(RigidBodySet, ColliderSet)>>()
+            .into_iter()
+            .next()
+        {
+            Some((_, resource)) => {
+                let (rigid_body_set, collider_set) = &mut resource.0;
+
+                // Масса, центр масс и инерция берутся из характеристик машины
+                let body = RigidBodyBuilder::dynamic()
+                    .translation(Vector3::new(position.x, position.y, position.z))
+                    .rotation(UnitQuaternion::from_quaternion(Quaternion::new(rotation.w, rotation.x, rotation.y, rotation.z)).scaled_axis())
+                    .additional_mass_properties(car.mass_properties())
+                    .build();
+
+                // Своей массы у коллайдера нет, иначе она сдвинет центр масс
+                let half = chassis_half_extents;
+                let shape = materials
+                    .apply(PhysicsMaterialId::CAR_BODY, ColliderBuilder::cuboid(half.x, half.y, half.z))
+                    .density(0.0)
+                    .active_events(ActiveEvents::COLLISION_EVENTS)
+                    .build();
+
+                let body_handle = rigid_body_set.insert(body);
+                let collider_handle = collider_set.insert_with_parent(shape, body_handle, rigid_body_set);
+                (
+                    RigidBodyComponent {
+                        handle: body_handle,
+                        body_type: RigidBodyType::Dynamic,
+                    },
+                    ColliderComponent {
+                        handle: collider_handle,
+                        shape_type: ColliderShapeType::Box,
+                    },
+                )
+            }
+            None => (
+                RigidBodyComponent {
+                    handle: Default::default(),
+                    body_type: RigidBodyType::Dynamic,
+                },
+                ColliderComponent {
+                    handle: Default::default(),
+                    shape_type: Default::default(),
+                },
+            ),
+        };
+
+        let car_entity = world.spawn((car, transform, rigid_body, collider, BlobShadowComponent::default(), audio, engine_sound));
+        let wheel_entities = create_wheels_for_car(world, car_entity, wheels);
+        create_headlights_for_car(world, car_entity, chassis_half_extents);
+        world.spawn((CarWheelBindingComponent {
+            car_entity,
+            wheel_entities,
+        },));
+        Ok(car_entity)
+    }
+
+    /// Создает машину вместе с мешами кузова, колес и стекол фар
+    pub fn spawn_rendered(self, world: &mut World, resources: &mut RenderResourceManager) -> Result<hecs::Entity, String> {
+        let render = self.render.clone().unwrap_or_default();
+        let car_entity = self.spawn(world)?;
+
+        // Кузов с помятыми вариантами для стадий повреждений
+        let (car_render, damage_visuals) = render.create(resources);
+        world.insert(car_entity, (car_render, damage_visuals)).map_err(|e| e.to_string())?;
+
+        // Колеса отрисовываются покрышкой, диском и тормозным диском
+        let wheel_part_assets = WheelPartAssets::new(resources);
+        let wheels: Vec<hecs::Entity> = world
+            .query::<&CarWheelBindingComponent>()
+            .iter()
+            .filter(|(_, binding)| binding.car_entity == car_entity)
+            .flat_map(|(_, binding)| binding.wheel_entities.clone())
+            .collect();
+        for wheel_entity in wheels {
+            create_wheel_parts(world, &wheel_part_assets, wheel_entity);
+        }
+
+        // Светящиеся стекла фар
+        let headlight_mesh_id = resources.add_simple_cube();
+        let headlight_material_id = resources.add_emissive_material([1.0, 1.0, 0.95, 1.0], [4.0, 3.8, 3.4]);
+        let headlights: Vec<hecs::Entity> = world
+            .query::<&HeadlightMountComponent>()
+            .iter()
+            .filter(|(_, mount)| mount.car_entity == car_entity)
+            .map(|(entity, _)| entity)
+            .collect();
+        for headlight in headlights {
+            let headlight_render = RenderComponent {
+                mesh_id: headlight_mesh_id,
+                material_id: headlight_material_id,
+                visible: true,
+                scale: Vec3::new(0.3, 0.15, 0.05),
+                opacity: 1.0,
+            };
+            world.insert_one(headlight, headlight_render).map_err(|e| e.to_string())?;
+        }
+        Ok(car_entity)
+    }
+}
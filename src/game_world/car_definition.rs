@@ -0,0 +1,475 @@
+use crate::core::renderer::{RenderComponent, RenderResourceManager};
+use crate::game_world::car::{default_wheels, CarAudioSet, CarComponent, DifferentialType, DrivetrainType, WheelComponent};
+use crate::game_world::damage::DamageVisualsComponent;
+use crate::game_world::forced_induction::ForcedInduction;
+use glam::Vec3;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Каталог с описаниями машин; каждая машина — отдельный JSON
+pub const CAR_DEFINITIONS_DIR: &str = "assets/cars";
+
+/// Подвеска, общая для всех колес машины
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SuspensionDefinition {
+    pub rest_length: f32,
+    pub stiffness: f32, // Н/м
+    pub damping: f32,   // Н·с/м
+    pub travel: f32,
+}
+
+/// Колесо в раскладке машины; ведущие колеса определяются схемой привода
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WheelDefinition {
+    pub position: [f32; 3], // Относительно центра кузова
+    #[serde(default)]
+    pub steering: bool,     // Управляемые колеса считаются передней осью
+    pub radius: f32,
+    pub width: f32,
+    #[serde(default = "default_tire_friction")]
+    pub friction: f32,
+}
+
+fn default_tire_friction() -> f32 {
+    1.0
+}
+
+/// Внешний вид кузова: меш по имени и материал
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CarRenderDefinition {
+    pub mesh: String, // Пока поддерживается только "cube"
+    pub color: [f32; 4],
+    #[serde(default)]
+    pub texture: Option<String>,
+    pub scale: [f32; 3],
+}
+
+impl Default for CarRenderDefinition {
+    fn default() -> Self {
+        Self {
+            mesh: "cube".to_string(),
+            color: [1.0, 0.0, 0.0, 1.0],
+            texture: None,
+            scale: [2.0, 1.0, 4.0],
+        }
+    }
+}
+
+impl CarRenderDefinition {
+    /// Создает меши и материал кузова; у куба есть помятые стадии повреждений
+    pub fn create(&self, resources: &mut RenderResourceManager) -> (RenderComponent, DamageVisualsComponent) {
+        if self.mesh != "cube" {
+            eprintln!("Неизвестный меш кузова '{}', используется куб", self.mesh);
+        }
+        let mesh_id = resources.add_simple_cube();
+        let material_id = match &self.texture {
+            Some(texture) => resources.add_textured_material(texture),
+            None => resources.add_basic_material(self.color),
+        };
+        let render = RenderComponent {
+            mesh_id,
+            material_id,
+            visible: true,
+            scale: Vec3::from(self.scale),
+            opacity: 1.0,
+        };
+        let visuals = DamageVisualsComponent {
+            stage_meshes: [mesh_id, resources.add_dented_cube(0.4), resources.add_dented_cube(1.0)],
+        };
+        (render, visuals)
+    }
+}
+
+/// Описание машины из файла: характеристики, раскладка колес и внешний вид.
+/// Пропущенные поля берутся из машины по умолчанию
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CarDefinition {
+    pub name: String,
+    pub mass: f32, // Без топлива, кг
+    pub max_brake_force: f32,
+    pub brake_bias: f32, // Доля тормоза на передней оси
+    pub max_steering_angle: f32,
+    pub steering_speed: f32,
+    pub center_of_mass: [f32; 3],
+    pub inertia: [f32; 3],
+
+    pub drag_coefficient: f32,
+    pub downforce_coefficient: f32,
+    pub frontal_area: f32,
+    pub side_area: f32,
+    pub side_force_coefficient: f32,
+    pub front_anti_roll_stiffness: f32,
+    pub rear_anti_roll_stiffness: f32,
+
+    pub fuel_capacity: f32,
+    pub drivetrain: DrivetrainType,
+    pub front_differential: DifferentialType,
+    pub rear_differential: DifferentialType,
+    pub torque_curve: Vec<(f32, f32)>, // RPM, torque
+    pub forced_induction: Option<ForcedInduction>,
+    pub gear_ratios: Vec<f32>,
+    pub reverse_gear_ratio: f32,
+    pub final_drive_ratio: f32,
+    pub idle_rpm: f32,
+    pub max_rpm: f32,
+    pub redline_rpm: f32,
+    pub shift_time: f32, // Длительность переключения, с
+    pub rev_match: bool,
+
+    pub suspension: SuspensionDefinition,
+    pub wheels: Vec<WheelDefinition>,
+    pub render: CarRenderDefinition,
+    pub audio: CarAudioSet,
+}
+
+impl Default for CarDefinition {
+    fn default() -> Self {
+        let car = CarComponent::default();
+        let wheels = default_wheels(car.drivetrain);
+        let suspension = wheels
+            .first()
+            .map(|wheel| SuspensionDefinition {
+                rest_length: wheel.suspension_rest_length,
+                stiffness: wheel.suspension_stiffness,
+                damping: wheel.suspension_damping,
+                travel: wheel.suspension_travel,
+            })
+            .expect("default layout has wheels");
+        Self {
+            name: car.name,
+            mass: car.mass,
+            max_brake_force: car.max_brake_force,
+            brake_bias: car.brake_bias,
+            max_steering_angle: car.max_steering_angle,
+            steering_speed: car.steering_speed,
+            center_of_mass: car.center_of_mass.to_array(),
+            inertia: car.inertia.to_array(),
+
+            drag_coefficient: car.drag_coefficient,
+            downforce_coefficient: car.downforce_coefficient,
+            frontal_area: car.frontal_area,
+            side_area: car.side_area,
+            side_force_coefficient: car.side_force_coefficient,
+            front_anti_roll_stiffness: car.front_anti_roll_stiffness,
+            rear_anti_roll_stiffness: car.rear_anti_roll_stiffness,
+
+            fuel_capacity: car.fuel_capacity,
+            drivetrain: car.drivetrain,
+            front_differential: car.front_differential,
+            rear_differential: car.rear_differential,
+            torque_curve: car.torque_curve,
+            forced_induction: car.forced_induction,
+            gear_ratios: car.gear_ratios,
+            reverse_gear_ratio: car.reverse_gear_ratio,
+            final_drive_ratio: car.final_drive_ratio,
+            idle_rpm: car.idle_rpm,
+            max_rpm: car.max_rpm,
+            redline_rpm: car.redline_rpm,
+            shift_time: car.shift_time,
+            rev_match: car.rev_match,
+
+            suspension,
+            wheels: wheels
+                .iter()
+                .map(|wheel| WheelDefinition {
+                    position: wheel.position.to_array(),
+                    steering: wheel.steering,
+                    radius: wheel.radius,
+                    width: wheel.width,
+                    friction: wheel.friction,
+                })
+                .collect(),
+            render: CarRenderDefinition::default(),
+            audio: CarAudioSet::default(),
+        }
+    }
+}
+
+impl CarDefinition {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+        Self::parse(&text)
+    }
+
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let definition: Self = serde_json::from_str(text).map_err(|e| e.to_string())?;
+        definition.validate()?;
+        Ok(definition)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.name.is_empty() {
+            return Err("Car has no name".to_string());
+        }
+        if !(0.0..=1.0).contains(&self.brake_bias) {
+            return Err(format!("Car '{}' brake bias must be within 0..1", self.name));
+        }
+        if self.mass <= 0.0 || self.inertia.iter().any(|i| *i <= 0.0) {
+            return Err(format!("Car '{}' has invalid mass or inertia", self.name));
+        }
+        if self.torque_curve.is_empty() || self.torque_curve.windows(2).any(|pair| pair[1].0 <= pair[0].0) {
+            return Err(format!("Car '{}' torque curve must have increasing rpm", self.name));
+        }
+        if self.gear_ratios.is_empty()
+            || self.gear_ratios.iter().any(|ratio| *ratio <= 0.0)
+            || self.reverse_gear_ratio <= 0.0
+            || self.final_drive_ratio <= 0.0
+        {
+            return Err(format!("Car '{}' has invalid gear ratios", self.name));
+        }
+        if !(self.idle_rpm < self.redline_rpm && self.redline_rpm <= self.max_rpm) {
+            return Err(format!("Car '{}' has invalid rpm limits", self.name));
+        }
+        if self.shift_time < 0.0 {
+            return Err(format!("Car '{}' has negative shift time", self.name));
+        }
+        if let Some(induction) = &self.forced_induction {
+            if induction.max_boost < 0.0 || induction.spool_time <= 0.0 || induction.full_boost_rpm <= self.idle_rpm {
+                return Err(format!("Car '{}' has invalid forced induction", self.name));
+            }
+        }
+        let suspension = &self.suspension;
+        if suspension.rest_length <= 0.0 || suspension.stiffness <= 0.0 || suspension.damping < 0.0 || suspension.travel <= 0.0 {
+            return Err(format!("Car '{}' has invalid suspension", self.name));
+        }
+        if self.wheels.len() < 3 || self.wheels.iter().any(|wheel| wheel.radius <= 0.0 || wheel.width <= 0.0) {
+            return Err(format!("Car '{}' needs at least three valid wheels", self.name));
+        }
+        if !self.wheels.iter().any(|wheel| wheel.steering) {
+            return Err(format!("Car '{}' has no steering wheels", self.name));
+        }
+        if !self.wheels.iter().any(|wheel| self.drivetrain.axle_share(wheel.steering) > 0.0) {
+            return Err(format!("Car '{}' has no driven wheels for its drivetrain", self.name));
+        }
+        if self.render.scale.iter().any(|size| *size <= 0.0) {
+            return Err(format!("Car '{}' has invalid body size", self.name));
+        }
+        Ok(())
+    }
+
+    /// Компонент машины с характеристиками из описания и полным баком
+    pub fn car_component(&self) -> CarComponent {
+        // База — расстояние между крайними осями
+        let (front, rear) = self
+            .wheels
+            .iter()
+            .map(|wheel| wheel.position[2])
+            .fold((f32::MIN, f32::MAX), |(front, rear), z| (front.max(z), rear.min(z)));
+        CarComponent {
+            name: self.name.clone(),
+            mass: self.mass,
+            max_brake_force: self.max_brake_force,
+            brake_bias: self.brake_bias,
+            max_steering_angle: self.max_steering_angle,
+            steering_speed: self.steering_speed,
+            wheel_base: front - rear,
+            center_of_mass: Vec3::from(self.center_of_mass),
+            inertia: Vec3::from(self.inertia),
+
+            drag_coefficient: self.drag_coefficient,
+            downforce_coefficient: self.downforce_coefficient,
+            frontal_area: self.frontal_area,
+            side_area: self.side_area,
+            side_force_coefficient: self.side_force_coefficient,
+            front_anti_roll_stiffness: self.front_anti_roll_stiffness,
+            rear_anti_roll_stiffness: self.rear_anti_roll_stiffness,
+
+            fuel_capacity: self.fuel_capacity,
+            fuel: self.fuel_capacity,
+            drivetrain: self.drivetrain,
+            front_differential: self.front_differential,
+            rear_differential: self.rear_differential,
+            torque_curve: self.torque_curve.clone(),
+            forced_induction: self.forced_induction,
+            gear_ratios: self.gear_ratios.clone(),
+            reverse_gear_ratio: self.reverse_gear_ratio,
+            final_drive_ratio: self.final_drive_ratio,
+            idle_rpm: self.idle_rpm,
+            max_rpm: self.max_rpm,
+            redline_rpm: self.redline_rpm,
+            shift_time: self.shift_time,
+            rev_match: self.rev_match,
+            current_rpm: self.idle_rpm,
+            ..Default::default()
+        }
+    }
+
+    /// Колеса из раскладки; ведущие — на осях, которым схема привода дает момент
+    pub fn wheel_components(&self) -> Vec<WheelComponent> {
+        self.wheels
+            .iter()
+            .map(|wheel| WheelComponent {
+                radius: wheel.radius,
+                width: wheel.width,
+                position: Vec3::from(wheel.position),
+                suspension_rest_length: self.suspension.rest_length,
+                suspension_stiffness: self.suspension.stiffness,
+                suspension_damping: self.suspension.damping,
+                suspension_travel: self.suspension.travel,
+                suspension_length: self.suspension.rest_length,
+                friction: wheel.friction,
+                steering: wheel.steering,
+                powered: self.drivetrain.axle_share(wheel.steering) > 0.0,
+                ..Default::default()
+            })
+            .collect()
+    }
+}
+
+/// Колеса на двух осях: передние управляемые
+fn two_axle_wheels(wheel_base: f32, track_width: f32, height: f32, radius: f32, width: f32, friction: f32) -> Vec<WheelDefinition> {
+    [(1.0, -1.0), (1.0, 1.0), (-1.0, -1.0), (-1.0, 1.0)]
+        .iter()
+        .map(|&(axle, side)| WheelDefinition {
+            position: [side * track_width / 2.0, height, axle * wheel_base / 2.0],
+            steering: axle > 0.0,
+            radius,
+            width,
+            friction,
+        })
+        .collect()
+}
+
+/// Встроенные машины разных классов: хэтчбек (D), раллийная (C), спорткар (B) и GT (A)
+pub fn builtin_car_definitions() -> Vec<CarDefinition> {
+    vec![
+        // Легкий переднеприводный хэтчбек с самоблоком: прощает ошибки, но недостаточно поворачивает
+        CarDefinition {
+            name: "Hatchback".to_string(),
+            mass: 1100.0,
+            max_brake_force: 11000.0,
+            brake_bias: 0.68,
+            center_of_mass: [0.0, -0.1, 0.3],
+            inertia: [1700.0, 1900.0, 400.0],
+            drag_coefficient: 0.34,
+            downforce_coefficient: 0.1,
+            frontal_area: 2.1,
+            front_anti_roll_stiffness: 14000.0,
+            rear_anti_roll_stiffness: 6000.0,
+            fuel_capacity: 45.0,
+            drivetrain: DrivetrainType::Fwd,
+            front_differential: DifferentialType::LimitedSlip { preload: 40.0, lock_ratio: 0.25 },
+            torque_curve: vec![(1000.0, 150.0), (2500.0, 200.0), (4000.0, 220.0), (5500.0, 185.0), (6500.0, 150.0), (7000.0, 120.0)],
+            gear_ratios: vec![3.3, 2.1, 1.5, 1.1, 0.9],
+            final_drive_ratio: 4.1,
+            max_rpm: 7000.0,
+            redline_rpm: 6500.0,
+            shift_time: 0.3,
+            rev_match: false,
+            suspension: SuspensionDefinition { rest_length: 0.3, stiffness: 28000.0, damping: 3800.0, travel: 0.15 },
+            wheels: two_axle_wheels(2.4, 1.7, -0.4, 0.31, 0.2, 1.0),
+            render: CarRenderDefinition {
+                color: [0.1, 0.3, 0.9, 1.0],
+                scale: [1.8, 1.0, 3.6],
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        // Полноприводная раллийная машина с турбиной: мягкая длинноходная подвеска, блокировки на обеих осях, короткие передачи
+        CarDefinition {
+            name: "Rally".to_string(),
+            mass: 1300.0,
+            max_brake_force: 13000.0,
+            brake_bias: 0.55,
+            center_of_mass: [0.0, -0.05, 0.15],
+            inertia: [2000.0, 2200.0, 480.0],
+            drag_coefficient: 0.38,
+            downforce_coefficient: 0.3,
+            front_anti_roll_stiffness: 8000.0,
+            rear_anti_roll_stiffness: 8000.0,
+            fuel_capacity: 70.0,
+            drivetrain: DrivetrainType::Awd { front_split: 0.45 },
+            front_differential: DifferentialType::LimitedSlip { preload: 60.0, lock_ratio: 0.3 },
+            rear_differential: DifferentialType::LimitedSlip { preload: 80.0, lock_ratio: 0.5 },
+            torque_curve: vec![(1000.0, 150.0), (2500.0, 230.0), (3500.0, 250.0), (5500.0, 250.0), (6500.0, 215.0), (7000.0, 185.0)],
+            forced_induction: Some(ForcedInduction::turbo(0.8, 3500.0)),
+            gear_ratios: vec![3.2, 2.3, 1.8, 1.4, 1.15, 0.95],
+            final_drive_ratio: 4.4,
+            max_rpm: 7000.0,
+            redline_rpm: 6600.0,
+            shift_time: 0.08,
+            suspension: SuspensionDefinition { rest_length: 0.35, stiffness: 25000.0, damping: 3500.0, travel: 0.22 },
+            wheels: two_axle_wheels(2.5, 1.75, -0.42, 0.33, 0.21, 1.0),
+            render: CarRenderDefinition {
+                color: [0.95, 0.95, 0.95, 1.0],
+                scale: [1.9, 1.1, 4.0],
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        // Заднеприводный спорткар: сбалансированная машина по умолчанию
+        CarDefinition {
+            name: "SportsCar".to_string(),
+            ..Default::default()
+        },
+        // Тяжелый мощный GT с приводным нагнетателем: прижимная сила, жесткая подвеска и самоблок сзади,
+        // требует аккуратного газа
+        CarDefinition {
+            name: "GT".to_string(),
+            mass: 1450.0,
+            max_brake_force: 20000.0,
+            brake_bias: 0.57,
+            max_steering_angle: 0.45,
+            center_of_mass: [0.0, -0.15, -0.1],
+            inertia: [2600.0, 2900.0, 520.0],
+            drag_coefficient: 0.36,
+            downforce_coefficient: 1.2,
+            frontal_area: 1.9,
+            front_anti_roll_stiffness: 18000.0,
+            rear_anti_roll_stiffness: 12000.0,
+            fuel_capacity: 100.0,
+            rear_differential: DifferentialType::LimitedSlip { preload: 100.0, lock_ratio: 0.4 },
+            torque_curve: vec![(1000.0, 260.0), (2500.0, 410.0), (4000.0, 510.0), (5500.0, 550.0), (6500.0, 510.0), (7500.0, 440.0)],
+            forced_induction: Some(ForcedInduction::supercharger(0.5, 5000.0)),
+            gear_ratios: vec![3.1, 2.2, 1.7, 1.35, 1.1, 0.9],
+            final_drive_ratio: 3.5,
+            max_rpm: 7500.0,
+            redline_rpm: 7000.0,
+            shift_time: 0.1,
+            suspension: SuspensionDefinition { rest_length: 0.25, stiffness: 50000.0, damping: 5500.0, travel: 0.1 },
+            wheels: two_axle_wheels(2.7, 1.9, -0.38, 0.34, 0.3, 1.1),
+            render: CarRenderDefinition {
+                color: [0.1, 0.1, 0.1, 1.0],
+                scale: [2.0, 0.9, 4.4],
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    ]
+}
+
+/// Описание машины по имени: файлы из каталога машин важнее встроенных
+pub fn find_car_definition(name: &str) -> Option<CarDefinition> {
+    let dir = Path::new(CAR_DEFINITIONS_DIR);
+    let from_files = if dir.exists() {
+        load_car_definitions(dir).unwrap_or_else(|e| {
+            eprintln!("Ошибка чтения каталога машин: {}", e);
+            Vec::new()
+        })
+    } else {
+        Vec::new()
+    };
+    from_files
+        .into_iter()
+        .chain(builtin_car_definitions())
+        .find(|definition| definition.name == name)
+}
+
+/// Все описания машин из каталога, по имени. Файлы с ошибками пропускаются
+pub fn load_car_definitions(dir: &Path) -> Result<Vec<CarDefinition>, String> {
+    let entries = std::fs::read_dir(dir).map_err(|e| e.to_string())?;
+    let mut definitions: Vec<CarDefinition> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|path| match CarDefinition::load(&path) {
+            Ok(definition) => Some(definition),
+            Err(e) => {
+                eprintln!("Ошибка загрузки машины {}: {}", path.display(), e);
+                None
+            }
+        })
+        .collect();
+    definitions.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(definitions)
+}
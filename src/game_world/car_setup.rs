@@ -0,0 +1,169 @@
+use crate::game_world::car::{CarComponent, CarWheelBindingComponent, WheelComponent};
+use crate::game_world::track::TrackComponent;
+use hecs::World;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Каталог настроек машин; файл на каждую пару машина - трасса
+pub const CAR_SETUPS_DIR: &str = "config/setups";
+
+/// Давление в шинах, при котором сцепление наибольшее, бар
+const OPTIMAL_TIRE_PRESSURE: f32 = 2.0;
+
+/// Потеря сцепления на квадрат отклонения давления, доля на бар²
+const TIRE_PRESSURE_GRIP_LOSS: f32 = 0.08;
+
+/// Прибавка Cx на единицу прибавки коэффициента прижимной силы: крыло с большим углом тормозит
+const DRAG_PER_DOWNFORCE: f32 = 0.1;
+
+/// Пределы изменения клиренса относительно базовой машины, м
+const MAX_RIDE_HEIGHT_OFFSET: f32 = 0.05;
+
+/// Настройка машины под трассу. Пустые поля оставляют значения базовой машины
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CarSetupComponent {
+    pub front_spring_rate: Option<f32>, // Н/м
+    pub rear_spring_rate: Option<f32>,
+    pub ride_height: Option<f32>,       // Смещение клиренса, м; плюс — выше
+    pub front_camber: Option<f32>,      // рад
+    pub rear_camber: Option<f32>,
+    pub gear_ratios: Option<Vec<f32>>,
+    pub final_drive_ratio: Option<f32>,
+    pub tire_pressure: Option<f32>,     // бар
+    pub downforce_coefficient: Option<f32>,
+    pub brake_bias: Option<f32>,
+    pub anti_roll_balance: Option<f32>,
+}
+
+impl CarSetupComponent {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+        let setup: Self = serde_json::from_str(&text).map_err(|e| e.to_string())?;
+        if setup.gear_ratios.as_ref().is_some_and(|ratios| ratios.is_empty() || ratios.iter().any(|r| *r <= 0.0))
+            || setup.final_drive_ratio.is_some_and(|ratio| ratio <= 0.0)
+            || setup.tire_pressure.is_some_and(|pressure| pressure <= 0.0)
+        {
+            return Err("Setup has invalid gearing or tire pressure".to_string());
+        }
+        Ok(setup)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        let text = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        std::fs::write(path, text).map_err(|e| e.to_string())
+    }
+
+    /// Файл настройки для машины на трассе
+    pub fn path(car_name: &str, track_name: &str) -> PathBuf {
+        Path::new(CAR_SETUPS_DIR).join(file_safe(car_name)).join(format!("{}.json", file_safe(track_name)))
+    }
+
+    /// Переносит настройку на характеристики машины
+    pub fn apply_to_car(&self, car: &mut CarComponent) {
+        if let Some(ratios) = &self.gear_ratios {
+            car.gear_ratios = ratios.clone();
+            car.current_gear = car.current_gear.min(car.top_gear());
+        }
+        if let Some(ratio) = self.final_drive_ratio {
+            car.final_drive_ratio = ratio;
+        }
+        if let Some(downforce) = self.downforce_coefficient {
+            let downforce = downforce.max(0.0);
+            car.drag_coefficient = (car.drag_coefficient + (downforce - car.downforce_coefficient) * DRAG_PER_DOWNFORCE).max(0.0);
+            car.downforce_coefficient = downforce;
+        }
+        if let Some(bias) = self.brake_bias {
+            car.brake_bias = bias.clamp(0.0, 1.0);
+        }
+        if let Some(balance) = self.anti_roll_balance {
+            car.set_anti_roll_balance(balance);
+        }
+    }
+
+    /// Переносит настройку на колесо; передние — управляемые
+    pub fn apply_to_wheel(&self, wheel: &mut WheelComponent) {
+        let (spring_rate, camber) = if wheel.steering {
+            (self.front_spring_rate, self.front_camber)
+        } else {
+            (self.rear_spring_rate, self.rear_camber)
+        };
+        if let Some(rate) = spring_rate {
+            // Демпфер следует за пружиной, чтобы сохранить долю критического демпфирования
+            let ratio = wheel.suspension_damping / wheel.suspension_stiffness.max(1.0);
+            wheel.suspension_stiffness = rate.max(1.0);
+            wheel.suspension_damping = wheel.suspension_stiffness * ratio;
+        }
+        if let Some(offset) = self.ride_height {
+            wheel.suspension_rest_length += offset.clamp(-MAX_RIDE_HEIGHT_OFFSET, MAX_RIDE_HEIGHT_OFFSET);
+            wheel.suspension_length = wheel.suspension_rest_length;
+        }
+        if let Some(camber) = camber {
+            wheel.camber = camber;
+        }
+        if let Some(pressure) = self.tire_pressure {
+            wheel.friction *= tire_pressure_grip(pressure);
+        }
+    }
+}
+
+/// Доля сцепления шины при данном давлении
+pub fn tire_pressure_grip(pressure: f32) -> f32 {
+    let offset = pressure - OPTIMAL_TIRE_PRESSURE;
+    (1.0 - TIRE_PRESSURE_GRIP_LOSS * offset * offset).clamp(0.5, 1.0)
+}
+
+fn file_safe(name: &str) -> String {
+    name.chars().map(|c| if c.is_alphanumeric() { c } else { '_' }).collect()
+}
+
+/// Применяет настройку к только что созданной машине и ее колесам и оставляет ее на машине
+pub fn apply_car_setup(world: &mut World, car_entity: hecs::Entity, setup: CarSetupComponent) {
+    if let Ok(mut car) = world.get::<&mut CarComponent>(car_entity) {
+        setup.apply_to_car(&mut car);
+    }
+    let wheels: Vec<hecs::Entity> = world
+        .query::<&CarWheelBindingComponent>()
+        .iter()
+        .filter(|(_, binding)| binding.car_entity == car_entity)
+        .flat_map(|(_, binding)| binding.wheel_entities.clone())
+        .collect();
+    for wheel_entity in wheels {
+        if let Ok(mut wheel) = world.get::<&mut WheelComponent>(wheel_entity) {
+            setup.apply_to_wheel(&mut wheel);
+        }
+    }
+    if world.insert_one(car_entity, setup).is_err() {
+        eprintln!("Машина для настройки не найдена");
+    }
+}
+
+/// Загружает сохраненную настройку машины для текущей трассы и применяет ее
+pub fn load_car_setup(world: &mut World, car_entity: hecs::Entity) {
+    let Some(path) = car_setup_path(world, car_entity) else {
+        return;
+    };
+    let setup = if path.exists() {
+        CarSetupComponent::load(&path).unwrap_or_else(|e| {
+            eprintln!("Ошибка загрузки настройки машины {}: {}", path.display(), e);
+            CarSetupComponent::default()
+        })
+    } else {
+        CarSetupComponent::default()
+    };
+    apply_car_setup(world, car_entity, setup);
+}
+
+/// Путь настройки машины на текущей трассе
+pub fn car_setup_path(world: &World, car_entity: hecs::Entity) -> Option<PathBuf> {
+    let car_name = world.get::<&CarComponent>(car_entity).ok()?.name.clone();
+    let track_name = world
+        .query::<&TrackComponent>()
+        .iter()
+        .next()
+        .map(|(_, track)| track.name.clone())?;
+    Some(CarSetupComponent::path(&car_name, &track_name))
+}
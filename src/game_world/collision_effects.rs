@@ -0,0 +1,214 @@
+use crate::core::audio::AudioSourceComponent;
+use crate::core::ecs::{System, Resource};
+use crate::core::physics::{collider_material, ColliderComponent, ContactInfo, PhysicsMaterialId, RigidBodyComponent, TransformComponent};
+use crate::game_world::car::CarComponent;
+use crate::game_world::particles::{ParticleEmitterComponent, ParticleKind};
+use crate::game_world::props::KnockablePropComponent;
+use glam::Vec3;
+use hecs::World;
+use rapier3d::prelude::{ColliderSet, RigidBodySet};
+use std::collections::HashMap;
+
+/// Материалы, скольжение по которым дает искры и скрежет
+const SCRAPING_MATERIALS: [PhysicsMaterialId; 2] = [PhysicsMaterialId::BARRIER, PhysicsMaterialId::STEEL];
+
+/// Настройки эффектов скольжения вдоль стен
+pub struct ScrapeEffectConfig {
+    pub min_contact_time: f32,  // Контакт короче этого считается ударом, а не скольжением
+    pub release_time: f32,      // Сколько эффект живет после потери контакта
+    pub min_slide_speed: f32,   // м/с вдоль стены
+    pub max_slide_speed: f32,   // Скорость, при которой эффект максимален
+    pub max_spark_rate: f32,    // Искр в секунду
+    pub reference_force: f32,   // Сила прижатия, при которой громкость максимальна, Н
+}
+
+impl Default for ScrapeEffectConfig {
+    fn default() -> Self {
+        Self {
+            min_contact_time: 0.1,
+            release_time: 0.15,
+            min_slide_speed: 2.0,
+            max_slide_speed: 30.0,
+            max_spark_rate: 400.0,
+            reference_force: 20000.0,
+        }
+    }
+}
+
+/// Состояние скольжения одной пары машина - стена
+struct ScrapeContact {
+    duration: f32,
+    since_contact: f32,
+    emitter: Option<hecs::Entity>,
+}
+
+/// Система искр и звука скрежета при скольжении машины вдоль барьеров
+pub struct ScrapeEffectSystem {
+    pub config: ScrapeEffectConfig,
+    contacts: HashMap<(hecs::Entity, hecs::Entity), ScrapeContact>,
+}
+
+impl ScrapeEffectSystem {
+    pub fn new() -> Self {
+        Self {
+            config: ScrapeEffectConfig::default(),
+            contacts: HashMap::new(),
+        }
+    }
+}
+
+impl System for ScrapeEffectSystem {
+    fn update(&mut self, world: &mut World, delta_time: f32) {
+        let contacts: Vec<ContactInfo> = world
+            .query::<&Resource<Vec<ContactInfo>>>()
+            .iter()
+            .next()
+            .map(|(_, res)| res.0.clone())
+            .unwrap_or_default();
+
+        // Контакты машин с металлом: отбойниками и стальными конструкциями по материалу коллайдера.
+        // Сбиваемые предметы отлетают, а не скребут. Нормаль направлена от машины к стене
+        let mut sets = world.query::<&Resource<(RigidBodySet, ColliderSet)>>();
+        let collider_set = sets.iter().next().map(|(_, res)| &res.0 .1);
+        let mut touching: HashMap<(hecs::Entity, hecs::Entity), (Vec3, Vec3, f32)> = HashMap::new();
+        for contact in &contacts {
+            let is_barrier = |e: hecs::Entity| {
+                if world.get::<&KnockablePropComponent>(e).is_ok() {
+                    return false;
+                }
+                let Ok(collider) = world.get::<&ColliderComponent>(e) else {
+                    return false;
+                };
+                collider_set
+                    .and_then(|set| set.get(collider.handle))
+                    .is_some_and(|collider| SCRAPING_MATERIALS.contains(&collider_material(collider)))
+            };
+            let is_car = |e: hecs::Entity| world.get::<&CarComponent>(e).is_ok();
+
+            let (car, barrier, normal) = if is_car(contact.entity1) && is_barrier(contact.entity2) {
+                (contact.entity1, contact.entity2, contact.normal)
+            } else if is_car(contact.entity2) && is_barrier(contact.entity1) {
+                (contact.entity2, contact.entity1, -contact.normal)
+            } else {
+                continue;
+            };
+
+            let entry = touching.entry((car, barrier)).or_insert((contact.point, normal, 0.0));
+            entry.2 += contact.normal_force;
+        }
+        drop(sets);
+
+        // Скорость машин из физического мира
+        let velocities: HashMap<hecs::Entity, Vec3> = {
+            let bodies = world
+                .query::<&Resource<(RigidBodySet, ColliderSet)>>()
+                .iter()
+                .next()
+                .map(|(_, res)| {
+                    touching
+                        .keys()
+                        .filter_map(|&(car, _)| {
+                            let handle = world.get::<&RigidBodyComponent>(car).ok()?.handle;
+                            let linvel = res.0 .0.get(handle)?.linvel();
+                            Some((car, Vec3::new(linvel.x, linvel.y, linvel.z)))
+                        })
+                        .collect()
+                });
+            bodies.unwrap_or_default()
+        };
+
+        let config = &self.config;
+        let mut to_despawn = Vec::new();
+        let mut to_spawn = Vec::new();
+
+        for (key, (point, normal, force)) in &touching {
+            let state = self.contacts.entry(*key).or_insert(ScrapeContact {
+                duration: 0.0,
+                since_contact: 0.0,
+                emitter: None,
+            });
+            state.duration += delta_time;
+            state.since_contact = 0.0;
+
+            // Скорость скольжения - проекция скорости на плоскость стены
+            let velocity = velocities.get(&key.0).copied().unwrap_or(Vec3::ZERO);
+            let slide_velocity = velocity - *normal * velocity.dot(*normal);
+            let slide_speed = slide_velocity.length();
+
+            let sliding = state.duration >= config.min_contact_time && slide_speed >= config.min_slide_speed;
+            let speed_factor = ((slide_speed - config.min_slide_speed)
+                / (config.max_slide_speed - config.min_slide_speed))
+                .clamp(0.0, 1.0);
+            let force_factor = (force / config.reference_force).clamp(0.0, 1.0);
+
+            match state.emitter {
+                Some(emitter) => {
+                    if let Ok(mut transform) = world.get::<&mut TransformComponent>(emitter) {
+                        transform.position = *point;
+                    }
+                    if let Ok(mut particles) = world.get::<&mut ParticleEmitterComponent>(emitter) {
+                        particles.active = sliding;
+                        particles.rate = config.max_spark_rate * speed_factor * (0.3 + 0.7 * force_factor);
+                        // Искры летят от стены и назад по ходу движения
+                        particles.direction = -*normal - slide_velocity.normalize_or_zero() * 0.5 + Vec3::Y * 0.3;
+                        particles.inherit_velocity = slide_velocity * 0.3;
+                    }
+                    if let Ok(mut audio) = world.get::<&mut AudioSourceComponent>(emitter) {
+                        audio.volume = if sliding { 0.2 + 0.8 * force_factor.max(speed_factor) } else { 0.0 };
+                        audio.pitch = 0.8 + 0.6 * speed_factor;
+                    }
+                }
+                None if sliding => to_spawn.push(*key),
+                None => {}
+            }
+        }
+
+        // Пары без контакта гаснут после короткой паузы
+        self.contacts.retain(|key, state| {
+            if touching.contains_key(key) {
+                return true;
+            }
+            state.since_contact += delta_time;
+            if state.since_contact < config.release_time {
+                return true;
+            }
+            if let Some(emitter) = state.emitter {
+                to_despawn.push(emitter);
+            }
+            false
+        });
+
+        for entity in to_despawn {
+            let _ = world.despawn(entity);
+        }
+
+        for key in to_spawn {
+            let Some(&(point, normal, _)) = touching.get(&key) else {
+                continue;
+            };
+            let mut emitter = ParticleEmitterComponent::new(ParticleKind::Sparks);
+            emitter.direction = -normal;
+            emitter.spread = 0.6;
+
+            let entity = world.spawn((
+                TransformComponent {
+                    position: point,
+                    ..Default::default()
+                },
+                emitter,
+                AudioSourceComponent {
+                    sound_name: "metal_scrape".to_string(),
+                    volume: 0.0,
+                    looping: true,
+                    min_distance: 3.0,
+                    max_distance: 60.0,
+                    ..Default::default()
+                },
+            ));
+
+            if let Some(state) = self.contacts.get_mut(&key) {
+                state.emitter = Some(entity);
+            }
+        }
+    }
+}
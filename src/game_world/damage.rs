@@ -0,0 +1,137 @@
+use crate::core::ecs::{EventQueue, Resource, System};
+use crate::core::physics::{CollisionEvent, CollisionPhase, TransformComponent};
+use crate::core::renderer::RenderComponent;
+use crate::game_world::car::CarComponent;
+use crate::gameplay::rules::{current_rules, DamageLevel};
+use glam::Vec3;
+use hecs::World;
+use serde::{Deserialize, Serialize};
+
+/// Число стадий внешних повреждений кузова: целый, помятый, разбитый
+pub const DAMAGE_STAGES: usize = 3;
+
+/// Удары слабее этого импульса не повреждают машину, Н·с
+const DAMAGE_IMPULSE_THRESHOLD: f32 = 1500.0;
+
+/// Повреждение на единицу импульса сверх порога при множителе 1
+const DAMAGE_PER_IMPULSE: f32 = 0.00004;
+
+/// Границы стадий кузова по накопленному урону
+const DAMAGE_STAGE_LEVELS: [f32; DAMAGE_STAGES - 1] = [0.15, 0.5];
+
+/// Потери при полном повреждении узла
+const ENGINE_POWER_LOSS: f32 = 0.6;
+const AERO_DRAG_GAIN: f32 = 0.4;
+const AERO_DOWNFORCE_LOSS: f32 = 0.7;
+const SUSPENSION_TOE_PULL: f32 = 0.06; // рад, увод колес погнутой подвеской
+
+/// Повреждения узлов машины, 0..1. Механика учитывается только при полном уровне урона,
+/// кузов мнется при любом включенном
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct CarDamage {
+    pub engine: f32,
+    pub suspension: f32,
+    pub aero: f32,
+    pub body: f32,
+}
+
+impl CarDamage {
+    /// Доля мощности, которую отдает поврежденный двигатель
+    pub fn engine_power(&self) -> f32 {
+        1.0 - ENGINE_POWER_LOSS * self.engine
+    }
+
+    /// Множители сопротивления и прижимной силы разбитого обвеса
+    pub fn aero_scale(&self) -> (f32, f32) {
+        (1.0 + AERO_DRAG_GAIN * self.aero, 1.0 - AERO_DOWNFORCE_LOSS * self.aero)
+    }
+
+    /// Постоянный увод управляемых колес, рад
+    pub fn steering_pull(&self) -> f32 {
+        SUSPENSION_TOE_PULL * self.suspension
+    }
+
+    /// Стадия внешних повреждений кузова, 0..DAMAGE_STAGES
+    pub fn visual_stage(&self) -> usize {
+        DAMAGE_STAGE_LEVELS.iter().filter(|&&level| self.body >= level).count()
+    }
+
+    /// Урон от удара в точке кузова (в локальных координатах машины)
+    fn apply_hit(&mut self, local_point: Vec3, amount: f32, mechanical: bool) {
+        self.body = (self.body + amount).min(1.0);
+        if !mechanical {
+            return;
+        }
+        // Спереди двигатель и обвес, сзади обвес, сбоку подвеска
+        let front = (local_point.z / 2.0).clamp(0.0, 1.0);
+        let rear = (-local_point.z / 2.0).clamp(0.0, 1.0);
+        let side = local_point.x.abs().clamp(0.0, 1.0);
+        self.engine = (self.engine + amount * front).min(1.0);
+        self.aero = (self.aero + amount * (front + rear) * 0.5).min(1.0);
+        self.suspension = (self.suspension + amount * side).min(1.0);
+    }
+
+    /// Урон от жесткого приземления: удар снизу приходится на подвеску
+    pub fn apply_landing(&mut self, amount: f32, mechanical: bool) {
+        self.body = (self.body + amount * 0.5).min(1.0);
+        if mechanical {
+            self.suspension = (self.suspension + amount).min(1.0);
+        }
+    }
+
+    /// Ремонт всех узлов на долю за шаг
+    pub fn repair(&mut self, amount: f32) {
+        for value in [&mut self.engine, &mut self.suspension, &mut self.aero, &mut self.body] {
+            *value = (*value - amount).max(0.0);
+        }
+    }
+
+    /// Есть ли что чинить
+    pub fn is_damaged(&self) -> bool {
+        self.engine > 0.0 || self.suspension > 0.0 || self.aero > 0.0 || self.body > 0.0
+    }
+}
+
+/// Меши кузова по стадиям повреждений
+pub struct DamageVisualsComponent {
+    pub stage_meshes: [usize; DAMAGE_STAGES],
+}
+
+/// Урон машин от столкновений, ремонт в боксе и смена меша кузова
+pub struct DamageSystem;
+
+impl System for DamageSystem {
+    fn update(&mut self, world: &mut World, _delta_time: f32) {
+        let rules = current_rules(world);
+        let scale = rules.damage_scale();
+        let mechanical = rules.damage == DamageLevel::Full;
+
+        let hits: Vec<(hecs::Entity, Vec3, f32)> = world
+            .query::<&Resource<EventQueue<CollisionEvent>>>()
+            .iter()
+            .next()
+            .map(|(_, queue)| {
+                queue
+                    .0
+                    .iter()
+                    .filter(|event| event.phase == CollisionPhase::Started && event.impulse > DAMAGE_IMPULSE_THRESHOLD)
+                    .flat_map(|event| [(event.entity1, event.point, event.impulse), (event.entity2, event.point, event.impulse)])
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        if scale > 0.0 {
+            for (entity, point, impulse) in hits {
+                if let Ok((car, transform)) = world.query_one_mut::<(&mut CarComponent, &TransformComponent)>(entity) {
+                    let local_point = transform.rotation.inverse() * (point - transform.position);
+                    let amount = (impulse - DAMAGE_IMPULSE_THRESHOLD) * DAMAGE_PER_IMPULSE * scale;
+                    car.damage.apply_hit(local_point, amount, mechanical);
+                }
+            }
+        }
+
+        for (_, (car, visuals, render)) in world.query_mut::<(&CarComponent, &DamageVisualsComponent, &mut RenderComponent)>() {
+            render.mesh_id = visuals.stage_meshes[car.damage.visual_stage()];
+        }
+    }
+}
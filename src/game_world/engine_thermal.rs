@@ -0,0 +1,140 @@
+use crate::core::ecs::System;
+use crate::core::physics::TransformComponent;
+use crate::game_world::car::CarComponent;
+use crate::game_world::particles::{ParticleEmitterComponent, ParticleKind};
+use glam::Vec3;
+use hecs::World;
+use std::collections::HashMap;
+
+/// Температура воздуха, ниже которой двигатель не остывает, °C
+pub const AMBIENT_ENGINE_TEMPERATURE: f32 = 20.0;
+
+/// Рабочая температура: термостат открывает радиатор только выше нее, °C
+pub const OPERATING_ENGINE_TEMPERATURE: f32 = 90.0;
+
+/// Перегрев: выше этой температуры двигатель теряет мощность и парит, °C
+pub const OVERHEAT_TEMPERATURE: f32 = 115.0;
+
+/// Температура, при которой потеря мощности наибольшая, °C
+pub const CRITICAL_TEMPERATURE: f32 = 135.0;
+
+/// Доля мощности, теряемая при критической температуре
+const OVERHEAT_POWER_LOSS: f32 = 0.4;
+
+/// Нагрузка двигателя на холостом ходу
+const IDLE_LOAD: f32 = 0.05;
+
+/// Поврежденный двигатель греется сильнее, а помятый радиатор хуже отводит тепло
+const DAMAGE_HEAT_GAIN: f32 = 1.5;
+const DAMAGE_RADIATOR_LOSS: f32 = 0.5;
+
+/// Наибольшая интенсивность пара, частиц в секунду
+const STEAM_RATE: f32 = 40.0;
+
+/// Точка выхода пара из-под капота в координатах машины
+const STEAM_OFFSET: Vec3 = Vec3::new(0.0, 0.7, 1.6);
+
+/// Степень перегрева: 0 до порога перегрева, 1 при критической температуре
+pub fn overheat_share(temperature: f32) -> f32 {
+    ((temperature - OVERHEAT_TEMPERATURE) / (CRITICAL_TEMPERATURE - OVERHEAT_TEMPERATURE)).clamp(0.0, 1.0)
+}
+
+/// Доля мощности при данной температуре двигателя
+pub fn overheat_power(temperature: f32) -> f32 {
+    1.0 - OVERHEAT_POWER_LOSS * overheat_share(temperature)
+}
+
+/// Тепловая модель двигателя: нагрев от оборотов под нагрузкой и повреждений,
+/// остывание через радиатор набегающим потоком; перегретый двигатель парит
+pub struct EngineTemperatureSystem {
+    pub full_load_heating: f32, // Нагрев на полной нагрузке, °C/с
+    pub base_cooling: f32,      // Доля перегрева над рабочей температурой, отводимая за секунду на месте
+    pub speed_cooling: f32,     // Дополнительное охлаждение на каждый м/с
+    emitters: HashMap<hecs::Entity, hecs::Entity>,
+}
+
+impl EngineTemperatureSystem {
+    pub fn new() -> Self {
+        Self {
+            full_load_heating: 0.3,
+            base_cooling: 0.003,
+            speed_cooling: 0.0005,
+            emitters: HashMap::new(),
+        }
+    }
+}
+
+impl Default for EngineTemperatureSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl System for EngineTemperatureSystem {
+    fn update(&mut self, world: &mut World, delta_time: f32) {
+        let mut steam = Vec::new();
+        for (entity, (car, transform)) in world.query_mut::<(&mut CarComponent, &TransformComponent)>() {
+            let rpm_share = (car.current_rpm / car.redline_rpm.max(1.0)).clamp(0.0, 1.0);
+            let load = IDLE_LOAD.max(car.throttle * rpm_share);
+            let heating = self.full_load_heating * load * (1.0 + DAMAGE_HEAT_GAIN * car.damage.engine);
+            // Закрытый термостат не отдает тепло радиатору, пока двигатель не прогрет
+            let radiator = 1.0 - DAMAGE_RADIATOR_LOSS * car.damage.engine;
+            let cooling = (car.engine_temperature - OPERATING_ENGINE_TEMPERATURE).max(0.0)
+                * (self.base_cooling + self.speed_cooling * car.current_speed.abs())
+                * radiator;
+            car.engine_temperature = (car.engine_temperature + (heating - cooling) * delta_time)
+                .max(AMBIENT_ENGINE_TEMPERATURE);
+
+            let position = transform.position + transform.rotation * STEAM_OFFSET;
+            steam.push((
+                entity,
+                position,
+                car.engine_temperature > OVERHEAT_TEMPERATURE,
+                overheat_share(car.engine_temperature),
+            ));
+        }
+
+        let mut to_spawn = Vec::new();
+        for (car_entity, position, overheating, intensity) in steam {
+            match self.emitters.get(&car_entity) {
+                Some(&emitter_entity) => {
+                    if let Ok(mut transform) = world.get::<&mut TransformComponent>(emitter_entity) {
+                        transform.position = position;
+                    }
+                    if let Ok(mut emitter) = world.get::<&mut ParticleEmitterComponent>(emitter_entity) {
+                        emitter.active = overheating;
+                        // Слабая струйка в начале перегрева, густые клубы у критической температуры
+                        emitter.rate = STEAM_RATE * (0.2 + 0.8 * intensity);
+                    }
+                }
+                None => to_spawn.push((car_entity, position)),
+            }
+        }
+
+        for (car_entity, position) in to_spawn {
+            let mut emitter = ParticleEmitterComponent::new(ParticleKind::Steam);
+            emitter.active = false;
+            emitter.spread = 0.3;
+            let entity = world.spawn((
+                TransformComponent {
+                    position,
+                    ..Default::default()
+                },
+                emitter,
+            ));
+            self.emitters.insert(car_entity, entity);
+        }
+
+        // Излучатели удаленных машин
+        let orphaned: Vec<(hecs::Entity, hecs::Entity)> = self
+            .emitters
+            .iter()
+            .filter(|(car, _)| !world.contains(**car))
+            .map(|(car, emitter)| (*car, *emitter))
+            .collect();
+        for (car, emitter) in orphaned {
+            self.emitters.remove(&car);
+            let _ = world.despawn(emitter);
+        }
+    }
+}
@@ -1,7 +1,37 @@
-use crate::core::ecs::{System};
-use crate::core::physics::{TransformComponent};
+use crate::core::ecs::{System, Resource};
+use crate::core::physics::{RigidBodyComponent, TransformComponent};
+use crate::core::renderer::{FogSettings, RainSettings, SunLight};
+use crate::core::random::SimpleRng;
+use crate::game_world::camera::{CameraRigComponent, CameraView};
+use crate::game_world::track::TrackSegmentComponent;
 use glam::{Vec3, Quat};
 use hecs::World;
+use serde::{Deserialize, Serialize};
+use rapier3d::na::Vector3;
+use rapier3d::prelude::{ColliderSet, RigidBodySet};
+
+/// Скорость ветра при силе 1.0 (шторм), м/с
+const MAX_WIND_SPEED: f32 = 20.0;
+
+/// Порывы в шторм добавляют до этой доли средней скорости; в слабый ветер почти не заметны
+const GUST_SHARE: f32 = 0.6;
+
+/// Наибольшее отклонение направления ветра в порыве, рад
+const GUST_VEER: f32 = 0.25;
+
+const AIR_DENSITY: f32 = 1.225;
+
+/// Более слабый ветер не будит уснувшие предметы, Н
+const WIND_MIN_FORCE: f32 = 1.0;
+
+/// Одна лужа примерно на столько метров полотна
+const PUDDLE_SPACING: f32 = 40.0;
+
+/// Лужи появляются, когда намокание покрытия выше этого значения
+const PUDDLE_WETNESS: f32 = 0.4;
+
+/// Глубина самой глубокой лужи на полностью залитой трассе, м
+const MAX_PUDDLE_DEPTH: f32 = 0.012;
 
 /// Компонент погоды
 pub struct WeatherComponent {
@@ -10,10 +40,72 @@ pub struct WeatherComponent {
     pub transition_time: f32,
     pub current_time: f32,
     pub target_weather: Option<WeatherType>,
+    pub wind_direction: Vec3, // Горизонтальное направление ветра
+    pub wind_strength: f32,   // 0..1, используется для покачивания растительности
+    pub surface_wetness: f32, // 0..1, покрытие намокает в дождь и постепенно высыхает
+}
+
+/// Ветер над трассой для физики: кузова и легкие предметы
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WindResource {
+    pub direction: Vec3, // Горизонтальное направление с учетом порыва
+    pub speed: f32,      // Средняя скорость, м/с
+    pub gust: f32,       // Добавка порыва сейчас, м/с
+}
+
+impl WindResource {
+    pub fn velocity(&self) -> Vec3 {
+        self.direction * (self.speed + self.gust)
+    }
+}
+
+/// Скорость ветра; без погоды штиль
+pub fn wind_velocity(world: &World) -> Vec3 {
+    world
+        .query::<&Resource<WindResource>>()
+        .iter()
+        .next()
+        .map(|(_, res)| res.0.velocity())
+        .unwrap_or(Vec3::ZERO)
+}
+
+/// Лужа на полотне трассы
+#[derive(Debug, Clone, Copy)]
+pub struct Puddle {
+    pub center: Vec3,
+    pub radius: f32,
+    pub depth: f32, // В центре, м; к краю сходит на нет
+}
+
+/// Лужи на трассе; растут и высыхают вместе с намоканием покрытия
+#[derive(Debug, Clone, Default)]
+pub struct PuddleField {
+    pub puddles: Vec<Puddle>,
+}
+
+impl PuddleField {
+    /// Глубина воды в точке, м
+    pub fn depth_at(&self, position: Vec3) -> f32 {
+        self.puddles
+            .iter()
+            .filter(|puddle| puddle.depth > 0.0)
+            .map(|puddle| {
+                let offset = Vec3::new(position.x - puddle.center.x, 0.0, position.z - puddle.center.z);
+                puddle.depth * (1.0 - offset.length() / puddle.radius).max(0.0)
+            })
+            .fold(0.0, f32::max)
+    }
+}
+
+/// Легкий предмет, который сдувает ветер (конусы, щиты, мусор)
+#[derive(Debug, Clone, Copy)]
+pub struct WindAffectedComponent {
+    pub area: f32,             // Площадь, подставленная ветру, м²
+    pub drag_coefficient: f32,
 }
 
 /// Типы погоды
-#[derive(Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum WeatherType {
     Clear,
     Cloudy,
@@ -29,6 +121,53 @@ impl Default for WeatherType {
     }
 }
 
+impl WeatherType {
+    /// Базовая сила ветра для типа погоды
+    pub fn base_wind_strength(&self) -> f32 {
+        match self {
+            WeatherType::Clear => 0.15,
+            WeatherType::Cloudy => 0.3,
+            WeatherType::Rain => 0.5,
+            WeatherType::Storm => 1.0,
+            WeatherType::Fog => 0.05,
+            WeatherType::Snow => 0.35,
+        }
+    }
+    
+    /// Сила дождя для типа погоды при интенсивности 1.0
+    pub fn rain_amount(&self) -> f32 {
+        match self {
+            WeatherType::Rain => 0.6,
+            WeatherType::Storm => 1.0,
+            _ => 0.0,
+        }
+    }
+    
+    /// Плотность тумана для типа погоды при интенсивности 1.0
+    pub fn fog_density(&self) -> f32 {
+        match self {
+            WeatherType::Clear => 0.001,
+            WeatherType::Cloudy => 0.002,
+            WeatherType::Rain => 0.006,
+            WeatherType::Storm => 0.012,
+            WeatherType::Fog => 0.035,
+            WeatherType::Snow => 0.008,
+        }
+    }
+    
+    /// Цвет тумана для типа погоды
+    pub fn fog_color(&self) -> [f32; 3] {
+        match self {
+            WeatherType::Clear => [0.7, 0.75, 0.8],
+            WeatherType::Cloudy => [0.65, 0.67, 0.7],
+            WeatherType::Rain => [0.5, 0.55, 0.6],
+            WeatherType::Storm => [0.35, 0.38, 0.42],
+            WeatherType::Fog => [0.75, 0.75, 0.75],
+            WeatherType::Snow => [0.85, 0.87, 0.9],
+        }
+    }
+}
+
 /// Компонент времени суток
 pub struct TimeOfDayComponent {
     pub hour: f32,           // 0-24
@@ -39,6 +178,16 @@ pub struct TimeOfDayComponent {
     pub moon_position: Vec3, // Текущая позиция луны
 }
 
+/// Высота солнца над горизонтом, ниже которой наступают сумерки
+const DUSK_SUN_ELEVATION: f32 = 0.1;
+
+impl TimeOfDayComponent {
+    /// Стемнело ли настолько, что нужны фары
+    pub fn is_dark(&self) -> bool {
+        self.sun_position.y < DUSK_SUN_ELEVATION
+    }
+}
+
 impl Default for TimeOfDayComponent {
     fn default() -> Self {
         Self {
@@ -92,10 +241,46 @@ pub enum EnvironmentObjectType {
 }
 
 /// Система управления погодой
-pub struct WeatherSystem;
+pub struct WeatherSystem {
+    time: f32,
+    puddle_sites: Option<Vec<(Vec3, f32, f32)>>, // Центр, радиус и доля глубины; места постоянны для трассы
+}
+
+impl WeatherSystem {
+    pub fn new() -> Self {
+        Self {
+            time: 0.0,
+            puddle_sites: None,
+        }
+    }
+    
+    /// Места луж разбрасываются по сегментам трассы с постоянным зерном, чтобы совпадать в повторах
+    fn scatter_puddles(world: &World) -> Vec<(Vec3, f32, f32)> {
+        let mut rng = SimpleRng::new(0x9D1E);
+        let mut sites = Vec::new();
+        for (_, (segment, transform)) in world.query::<(&TrackSegmentComponent, &TransformComponent)>().iter() {
+            let count = (segment.length / PUDDLE_SPACING).floor() as u32;
+            for _ in 0..count {
+                let local = Vec3::new(
+                    rng.range(-0.4, 0.4) * segment.width,
+                    0.0,
+                    rng.range(-0.5, 0.5) * segment.length,
+                );
+                sites.push((transform.position + transform.rotation * local, rng.range(1.5, 4.0), rng.range(0.4, 1.0)));
+            }
+        }
+        sites
+    }
+}
 
 impl System for WeatherSystem {
     fn update(&mut self, world: &mut World, delta_time: f32) {
+        let mut fog = None;
+        let mut rain_amount = 0.0;
+        let mut surface_wetness = 0.0;
+        let mut wind = None;
+        self.time += delta_time;
+        
         // Обновляем погоду
         for (_, weather) in world.query_mut::<&mut WeatherComponent>() {
             // Обрабатываем переход между типами погоды, если нужно
@@ -114,8 +299,145 @@ impl System for WeatherSystem {
                 }
             }
             
+            // Сила ветра плавно следует за текущей (или целевой) погодой
+            let target_wind = match &weather.target_weather {
+                Some(target) => {
+                    let progress = (weather.current_time / weather.transition_time).clamp(0.0, 1.0);
+                    weather.weather_type.base_wind_strength() * (1.0 - progress)
+                        + target.base_wind_strength() * progress
+                }
+                None => weather.weather_type.base_wind_strength(),
+            } * (0.5 + 0.5 * weather.intensity);
+            let t = (delta_time * 0.5).min(1.0);
+            weather.wind_strength += (target_wind - weather.wind_strength) * t;
+            
+            // Порывы из двух несоизмеримых синусоид: без повторяющегося ритма, но детерминированно для повторов
+            let strength = weather.wind_strength.clamp(0.0, 1.0);
+            let speed = strength * MAX_WIND_SPEED;
+            let wave = (self.time * 0.7).sin() * 0.6 + (self.time * 1.9 + 1.3).sin() * 0.4;
+            let veer = (self.time * 0.23).sin() * GUST_VEER * strength;
+            wind = Some(WindResource {
+                direction: Quat::from_rotation_y(veer) * weather.wind_direction,
+                speed,
+                gust: speed * GUST_SHARE * strength * wave.max(0.0),
+            });
+            
+            // Туман смешивается между текущей и целевой погодой
+            let (density, color) = match &weather.target_weather {
+                Some(target) => {
+                    let progress = (weather.current_time / weather.transition_time).clamp(0.0, 1.0);
+                    let from = weather.weather_type.fog_color();
+                    let to = target.fog_color();
+                    (
+                        weather.weather_type.fog_density() * (1.0 - progress) + target.fog_density() * progress,
+                        [
+                            from[0] + (to[0] - from[0]) * progress,
+                            from[1] + (to[1] - from[1]) * progress,
+                            from[2] + (to[2] - from[2]) * progress,
+                        ],
+                    )
+                }
+                None => (weather.weather_type.fog_density(), weather.weather_type.fog_color()),
+            };
+            
+            fog = Some(FogSettings {
+                color,
+                density: density * (0.5 + weather.intensity),
+                ..Default::default()
+            });
+            
+            // Дождь смешивается так же, покрытие намокает быстрее, чем сохнет
+            let target_rain = match &weather.target_weather {
+                Some(target) => {
+                    let progress = (weather.current_time / weather.transition_time).clamp(0.0, 1.0);
+                    weather.weather_type.rain_amount() * (1.0 - progress) + target.rain_amount() * progress
+                }
+                None => weather.weather_type.rain_amount(),
+            } * weather.intensity.min(1.0);
+            let rate = if target_rain > weather.surface_wetness { 1.0 / 20.0 } else { 1.0 / 90.0 };
+            weather.surface_wetness += (target_rain - weather.surface_wetness).clamp(-rate * delta_time, rate * delta_time);
+            
+            rain_amount = target_rain;
+            surface_wetness = weather.surface_wetness;
+            
             // Другие эффекты погоды...
         }
+        
+        let Some(fog) = fog else {
+            return;
+        };
+        
+        // Лужи наполняются, только когда покрытие уже сильно намокло
+        let has_track = world.query::<&TrackSegmentComponent>().iter().next().is_some();
+        if self.puddle_sites.is_none() && has_track {
+            self.puddle_sites = Some(Self::scatter_puddles(world));
+        }
+        let fill = ((surface_wetness - PUDDLE_WETNESS) / (1.0 - PUDDLE_WETNESS)).clamp(0.0, 1.0);
+        let puddles = PuddleField {
+            puddles: self
+                .puddle_sites
+                .iter()
+                .flatten()
+                .map(|&(center, radius, share)| Puddle {
+                    center,
+                    radius,
+                    depth: MAX_PUDDLE_DEPTH * share * fill,
+                })
+                .collect(),
+        };
+        match world.query_mut::<&mut Resource<PuddleField>>().into_iter().next() {
+            Some((_, res)) => res.0 = puddles,
+            None => {
+                world.spawn((Resource(puddles),));
+            }
+        }
+        
+        if let Some(wind) = wind {
+            match world.query_mut::<&mut Resource<WindResource>>().into_iter().next() {
+                Some((_, res)) => res.0 = wind,
+                None => {
+                    world.spawn((Resource(wind),));
+                }
+            }
+        }
+        
+        // Капли на экране видны только из кабины и с внешней камеры
+        let screen_droplets = world.query::<&CameraRigComponent>()
+            .iter()
+            .next()
+            .filter(|(_, rig)| rig.rain_droplets && matches!(rig.view, CameraView::Chase | CameraView::Cockpit))
+            .map(|_| rain_amount)
+            .unwrap_or(0.0);
+        
+        let rain = RainSettings {
+            surface_wetness,
+            screen_droplets,
+            time: self.time,
+        };
+        
+        let rain_resource = world.query_mut::<&mut Resource<RainSettings>>()
+            .into_iter()
+            .next()
+            .map(|(_, res)| &mut res.0);
+        
+        match rain_resource {
+            Some(settings) => *settings = rain,
+            None => {
+                world.spawn((Resource(rain),));
+            }
+        }
+        
+        let fog_resource = world.query_mut::<&mut Resource<FogSettings>>()
+            .into_iter()
+            .next()
+            .map(|(_, res)| &mut res.0);
+        
+        match fog_resource {
+            Some(settings) => *settings = fog,
+            None => {
+                world.spawn((Resource(fog),));
+            }
+        }
     }
 }
 
@@ -124,6 +446,8 @@ pub struct TimeOfDaySystem;
 
 impl System for TimeOfDaySystem {
     fn update(&mut self, world: &mut World, delta_time: f32) {
+        let mut sun_light = None;
+        
         // Обновляем время суток
         for (_, time) in world.query_mut::<&mut TimeOfDayComponent>() {
             // Вычисление новых часов и минут
@@ -146,19 +470,52 @@ impl System for TimeOfDaySystem {
             // Вычисление позиции солнца и луны на основе времени суток
             let day_angle = (time.hour / 24.0 + time.minute / (24.0 * 60.0)) * 2.0 * std::f32::consts::PI;
             
-            // Солнце
+            // Солнце (в полдень в зените)
             time.sun_position = Vec3::new(
                 day_angle.sin(),
-                day_angle.cos(),
+                -day_angle.cos(),
                 0.0,
             ).normalize();
             
             // Луна (противоположная сторона от солнца)
             time.moon_position = Vec3::new(
                 -day_angle.sin(),
-                -day_angle.cos(),
+                day_angle.cos(),
                 0.0,
             ).normalize();
+            
+            // Днем светит солнце (теплее у горизонта), ночью - слабый холодный свет луны
+            let elevation = time.sun_position.y;
+            let day = ((elevation + 0.1) / 0.3).clamp(0.0, 1.0);
+            let warmth = 1.0 - (elevation / 0.4).clamp(0.0, 1.0);
+            let sun_color = [1.0, 1.0 - 0.35 * warmth, 1.0 - 0.6 * warmth];
+            let moon_color = [0.55, 0.62, 0.9];
+            
+            sun_light = Some(SunLight {
+                direction: if day > 0.0 { time.sun_position } else { time.moon_position },
+                color: [
+                    moon_color[0] + (sun_color[0] - moon_color[0]) * day,
+                    moon_color[1] + (sun_color[1] - moon_color[1]) * day,
+                    moon_color[2] + (sun_color[2] - moon_color[2]) * day,
+                ],
+                intensity: 0.04 + 0.96 * day,
+            });
+        }
+        
+        let Some(sun_light) = sun_light else {
+            return;
+        };
+        
+        let resource = world.query_mut::<&mut Resource<SunLight>>()
+            .into_iter()
+            .next()
+            .map(|(_, res)| &mut res.0);
+        
+        match resource {
+            Some(light) => *light = sun_light,
+            None => {
+                world.spawn((Resource(sun_light),));
+            }
         }
     }
 }
@@ -205,14 +562,59 @@ impl System for DestructibleSystem {
     }
 }
 
+/// Система сноса легких предметов ветром: сопротивление воздуха от скорости ветра относительно тела
+pub struct WindForceSystem;
+
+impl System for WindForceSystem {
+    fn update(&mut self, world: &mut World, delta_time: f32) {
+        let wind = wind_velocity(world);
+        let props: Vec<(rapier3d::prelude::RigidBodyHandle, WindAffectedComponent)> = world
+            .query::<(&WindAffectedComponent, &RigidBodyComponent)>()
+            .iter()
+            .map(|(_, (affected, body))| (body.handle, *affected))
+            .collect();
+        if props.is_empty() || delta_time <= 0.0 {
+            return;
+        }
+        
+        let Some((_, resource)) = world
+            .query_mut::<&mut Resource<(RigidBodySet, ColliderSet)>>()
+            .into_iter()
+            .next()
+        else {
+            return;
+        };
+        let rigid_body_set = &mut resource.0 .0;
+        for (handle, affected) in props {
+            let Some(body) = rigid_body_set.get_mut(handle).filter(|body| body.is_dynamic()) else {
+                continue;
+            };
+            let linvel = body.linvel();
+            let relative = wind - Vec3::new(linvel.x, linvel.y, linvel.z);
+            // Импульс за кадр, а не сила: силы кузова сбрасываются каждый кадр, а у предметов хозяина нет
+            let force = relative * relative.length() * 0.5 * AIR_DENSITY * affected.area * affected.drag_coefficient;
+            if force.length() > WIND_MIN_FORCE {
+                let impulse = force * delta_time;
+                body.apply_impulse(Vector3::new(impulse.x, impulse.y, impulse.z), true);
+            }
+        }
+    }
+}
+
 /// Создает компонент погоды
 pub fn create_weather(world: &mut World, weather_type: WeatherType, intensity: f32) -> hecs::Entity {
+    let wind_strength = weather_type.base_wind_strength();
+    // Заезд под дождем начинается на уже мокрой трассе
+    let surface_wetness = weather_type.rain_amount() * intensity.min(1.0);
     let weather = WeatherComponent {
         weather_type,
         intensity,
         transition_time: 10.0,
         current_time: 0.0,
         target_weather: None,
+        wind_direction: Vec3::new(1.0, 0.0, 0.3).normalize(),
+        wind_strength,
+        surface_wetness,
     };
     
     world.spawn((weather,))
@@ -0,0 +1,153 @@
+use crate::core::audio::AudioEvent;
+use crate::core::ecs::{Resource, System};
+use crate::game_world::car::{CarAudioSet, CarComponent, PlayerCarComponent};
+use hecs::World;
+use serde::{Deserialize, Serialize};
+
+/// Спад давления после сброса газа, с: перепускной клапан стравливает быстрее, чем турбина раскручивается
+const BOOST_DECAY_TIME: f32 = 0.15;
+
+/// Доля полного давления, выше которой сброс газа слышен как хлопок перепускного клапана
+const BLOW_OFF_BOOST_SHARE: f32 = 0.4;
+
+/// Газ, ниже которого дроссель считается закрытым
+const CLOSED_THROTTLE: f32 = 0.2;
+
+/// Тип наддува
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InductionType {
+    Turbo,        // Давление растет с оборотами и запаздывает за газом
+    Supercharger, // Приводной нагнетатель: давление пропорционально оборотам без задержки
+}
+
+/// Наддув двигателя: характеристики и текущее давление
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ForcedInduction {
+    pub kind: InductionType,
+    pub max_boost: f32,      // Избыточное давление, бар
+    pub full_boost_rpm: f32, // Обороты, с которых доступно полное давление
+    pub spool_time: f32,     // Постоянная времени набора давления, с
+    pub torque_per_bar: f32, // Прибавка момента на бар давления, доля
+    #[serde(skip)]
+    pub boost: f32,          // Текущее давление, бар
+}
+
+impl ForcedInduction {
+    pub fn turbo(max_boost: f32, full_boost_rpm: f32) -> Self {
+        Self {
+            kind: InductionType::Turbo,
+            max_boost,
+            full_boost_rpm,
+            spool_time: 0.8,
+            torque_per_bar: 0.35,
+            boost: 0.0,
+        }
+    }
+
+    pub fn supercharger(max_boost: f32, full_boost_rpm: f32) -> Self {
+        Self {
+            kind: InductionType::Supercharger,
+            max_boost,
+            full_boost_rpm,
+            spool_time: 0.05,
+            torque_per_bar: 0.35,
+            boost: 0.0,
+        }
+    }
+
+    /// Давление, к которому стремится наддув на этих оборотах и газе
+    pub fn target_boost(&self, rpm: f32, idle_rpm: f32, throttle: f32) -> f32 {
+        let rpm_share = ((rpm - idle_rpm) / (self.full_boost_rpm - idle_rpm).max(1.0)).clamp(0.0, 1.0);
+        let rpm_share = match self.kind {
+            // Турбина почти не дует внизу и резко подхватывает ближе к рабочим оборотам
+            InductionType::Turbo => rpm_share * rpm_share * (3.0 - 2.0 * rpm_share),
+            InductionType::Supercharger => rpm_share,
+        };
+        self.max_boost * rpm_share * throttle.clamp(0.0, 1.0)
+    }
+
+    /// Шаг давления: набор с задержкой турбины, спад через перепускной клапан
+    pub fn update(&mut self, rpm: f32, idle_rpm: f32, throttle: f32, delta_time: f32) {
+        let target = self.target_boost(rpm, idle_rpm, throttle);
+        let time_constant = if target > self.boost { self.spool_time } else { BOOST_DECAY_TIME };
+        let blend = 1.0 - (-delta_time / time_constant.max(f32::EPSILON)).exp();
+        self.boost += (target - self.boost) * blend;
+    }
+
+    /// Множитель момента при текущем давлении
+    pub fn torque_multiplier(&self) -> f32 {
+        1.0 + self.boost.max(0.0) * self.torque_per_bar
+    }
+
+    /// Множитель момента при полном давлении: для мощности и класса машины
+    pub fn peak_torque_multiplier(&self) -> f32 {
+        1.0 + self.max_boost.max(0.0) * self.torque_per_bar
+    }
+
+    /// Доля от полного давления для стрелки указателя наддува, 0..1
+    pub fn boost_share(&self) -> f32 {
+        if self.max_boost > 0.0 {
+            (self.boost / self.max_boost).clamp(0.0, 1.0)
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Давление наддува машин и хлопок перепускного клапана у игрока
+pub struct ForcedInductionSystem {
+    player_throttle_open: bool,
+}
+
+impl ForcedInductionSystem {
+    pub fn new() -> Self {
+        Self { player_throttle_open: false }
+    }
+}
+
+impl Default for ForcedInductionSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl System for ForcedInductionSystem {
+    fn update(&mut self, world: &mut World, delta_time: f32) {
+        let mut blow_off = None;
+        for (_, (car, player, audio)) in world.query_mut::<(&mut CarComponent, Option<&PlayerCarComponent>, Option<&CarAudioSet>)>() {
+            let (rpm, idle_rpm, throttle) = (car.current_rpm, car.idle_rpm, car.throttle);
+            let Some(induction) = car.forced_induction.as_mut() else {
+                continue;
+            };
+            // Клапан срабатывает в момент сброса газа под давлением, только у турбины
+            let throttle_open = throttle >= CLOSED_THROTTLE;
+            if player.is_some() {
+                let blown = self.player_throttle_open
+                    && !throttle_open
+                    && induction.kind == InductionType::Turbo
+                    && induction.boost_share() > BLOW_OFF_BOOST_SHARE;
+                if blown {
+                    blow_off = Some(audio.cloned().unwrap_or_default().blow_off);
+                }
+                self.player_throttle_open = throttle_open;
+            }
+            induction.update(rpm, idle_rpm, throttle, delta_time);
+        }
+
+        let Some(name) = blow_off else {
+            return;
+        };
+        let sound = AudioEvent::PlaySound {
+            name,
+            volume: 0.6,
+            looping: false,
+        };
+        match world.query_mut::<&mut Resource<Vec<AudioEvent>>>().into_iter().next() {
+            Some((_, queue)) => queue.0.push(sound),
+            None => {
+                world.spawn((Resource(vec![sound]),));
+            }
+        }
+    }
+}
@@ -0,0 +1,693 @@
+use crate::core::ecs::{Resource, System};
+use crate::core::physics::{overlap_sphere, physics_materials, ColliderComponent, ColliderShapeType, PhysicsMaterialId, RigidBodyComponent, RigidBodyType, SceneQueryFilter, TransformComponent};
+use crate::core::random::SimpleRng;
+use crate::core::renderer::{RenderComponent, RenderResourceManager};
+use crate::game_world::car::CarComponent;
+use crate::game_world::track::{ObstacleComponent, ObstacleType};
+use glam::{Quat, Vec3};
+use hecs::World;
+use rapier3d::na::{Isometry3, Quaternion, Translation3, UnitQuaternion};
+use rapier3d::prelude::{Collider, ColliderBuilder, ColliderHandle, ColliderSet, RigidBody, RigidBodyBuilder, RigidBodyHandle, RigidBodySet};
+use serde::{Deserialize, Serialize};
+
+/// Параметры конкретного вида опасности в файле трассы
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum HazardKindDefinition {
+    /// Камнепад, срабатывающий при приближении машины к точке-триггеру
+    Rockfall {
+        trigger: [f32; 3],
+        trigger_radius: f32,
+        drop_point: [f32; 3],
+        #[serde(default = "default_rock_count")]
+        rocks: u32,
+        #[serde(default = "default_rock_radius")]
+        rock_radius: f32,
+        #[serde(default = "default_rearm_time")]
+        rearm_time: f32, // Через сколько секунд камни убираются и триггер взводится снова
+    },
+    /// Разводной мост, поднимающийся по таймеру
+    Drawbridge {
+        hinge: [f32; 3],
+        #[serde(default)]
+        rotation_y: f32, // Градусы; пролет лежит вдоль локальной +Z от шарнира
+        size: [f32; 3],  // Ширина, толщина и длина пролета
+        max_angle: f32,  // Градусы подъема
+        closed_time: f32,
+        open_time: f32,
+        swing_time: f32,
+    },
+    /// Поезд, пересекающий трассу по расписанию
+    Train {
+        start: [f32; 3],
+        end: [f32; 3],
+        speed: f32,
+        interval: f32,  // Пауза между проходами
+        size: [f32; 3], // Ширина, высота и длина состава
+    },
+}
+
+fn default_rock_count() -> u32 {
+    6
+}
+
+fn default_rock_radius() -> f32 {
+    0.6
+}
+
+fn default_rearm_time() -> f32 {
+    20.0
+}
+
+fn default_warning_time() -> f32 {
+    3.0
+}
+
+/// Описание опасности: вид, предупреждающий знак и время предупреждения
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HazardDefinition {
+    #[serde(flatten)]
+    pub kind: HazardKindDefinition,
+    #[serde(default)]
+    pub sign: Option<[f32; 3]>, // Позиция предупреждающего знака
+    #[serde(default = "default_warning_time")]
+    pub warning_time: f32,
+}
+
+impl HazardDefinition {
+    /// Размеры, скорости и количество камней должны быть положительными
+    pub fn is_valid(&self) -> bool {
+        match &self.kind {
+            HazardKindDefinition::Rockfall { trigger_radius, rocks, rock_radius, .. } => {
+                *trigger_radius > 0.0 && *rocks > 0 && *rock_radius > 0.0
+            }
+            HazardKindDefinition::Drawbridge { size, swing_time, .. } => size.iter().all(|s| *s > 0.0) && *swing_time > 0.0,
+            HazardKindDefinition::Train { speed, size, start, end, .. } => {
+                *speed > 0.0 && size.iter().all(|s| *s > 0.0) && start != end
+            }
+        }
+    }
+}
+
+/// Опасности из файла трассы, хранятся на ее сущности до создания тел и знаков
+#[derive(Debug, Clone, Default)]
+pub struct TrackHazardsComponent {
+    pub hazards: Vec<HazardDefinition>,
+}
+
+/// Вид опасности (для HUD и ИИ)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HazardType {
+    Rockfall,
+    Drawbridge,
+    Train,
+}
+
+/// Стадия цикла опасности
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HazardState {
+    Idle,
+    Warning, // Знаки горят, опасность вот-вот сработает
+    Active,
+}
+
+/// Рабочее состояние конкретного вида опасности
+pub enum HazardKind {
+    Rockfall {
+        trigger: Vec3,
+        trigger_radius: f32,
+        drop_point: Vec3,
+        rock_radius: f32,
+        rearm_time: f32,
+        rocks: Vec<hecs::Entity>, // Пул камней, неактивных до срабатывания
+    },
+    Drawbridge {
+        hinge: Vec3,
+        rotation: Quat,
+        length: f32,
+        max_angle: f32,
+        closed_time: f32,
+        open_time: f32,
+        swing_time: f32,
+        body: RigidBodyHandle,
+    },
+    Train {
+        start: Vec3,
+        end: Vec3,
+        speed: f32,
+        interval: f32,
+        length: f32,
+        body: RigidBodyHandle,
+    },
+}
+
+/// Компонент скриптовой опасности трассы
+pub struct HazardComponent {
+    pub kind: HazardKind,
+    pub state: HazardState,
+    pub timer: f32, // Время в текущей стадии
+    pub warning_time: f32,
+}
+
+impl HazardComponent {
+    pub fn hazard_type(&self) -> HazardType {
+        match self.kind {
+            HazardKind::Rockfall { .. } => HazardType::Rockfall,
+            HazardKind::Drawbridge { .. } => HazardType::Drawbridge,
+            HazardKind::Train { .. } => HazardType::Train,
+        }
+    }
+}
+
+/// Предупреждающий знак, загорающийся перед срабатыванием опасности
+pub struct HazardSignComponent {
+    pub hazard: hecs::Entity,
+    pub idle_material: usize,
+    pub lit_material: usize,
+}
+
+/// Опасная зона, которую должны объезжать или пережидать пилоты (игрок и ИИ)
+#[derive(Debug, Clone)]
+pub struct HazardZone {
+    pub hazard_type: HazardType,
+    pub state: HazardState,
+    pub position: Vec3,
+    pub radius: f32,
+    pub time_to_active: f32, // 0, если опасность уже действует
+}
+
+/// Опасные зоны впереди по курсу в пределах дистанции, ближайшие первыми
+pub fn hazard_zones_ahead(world: &World, position: Vec3, forward: Vec3, distance: f32) -> Vec<HazardZone> {
+    let zones = world
+        .query::<&Resource<Vec<HazardZone>>>()
+        .iter()
+        .next()
+        .map(|(_, res)| res.0.clone())
+        .unwrap_or_default();
+    zones_ahead(&zones, position, forward, distance)
+}
+
+/// То же по готовому списку зон: ИИ берет его один раз на все машины
+pub fn zones_ahead(zones: &[HazardZone], position: Vec3, forward: Vec3, distance: f32) -> Vec<HazardZone> {
+    let mut zones: Vec<HazardZone> = zones
+        .iter()
+        .filter(|zone| {
+            let offset = zone.position - position;
+            let along = offset.dot(forward);
+            along > -zone.radius && offset.length() - zone.radius < distance
+        })
+        .cloned()
+        .collect();
+    zones.sort_by(|a, b| a.position.distance(position).total_cmp(&b.position.distance(position)));
+    zones
+}
+
+fn isometry(position: Vec3, rotation: Quat) -> Isometry3<f32> {
+    Isometry3::from_parts(
+        Translation3::new(position.x, position.y, position.z),
+        UnitQuaternion::from_quaternion(Quaternion::new(rotation.w, rotation.x, rotation.y, rotation.z)),
+    )
+}
+
+/// Добавляет тело с коллайдером в физический мир
+fn insert_body(world: &mut World, body: RigidBody, collider: Collider) -> (RigidBodyHandle, ColliderHandle) {
+    let resource_query = world.query_mut::<&mut Resource<(RigidBodySet, ColliderSet)>>();
+    let Some((_, resource)) = resource_query.into_iter().next() else {
+        return (Default::default(), Default::default());
+    };
+    let (rigid_body_set, collider_set) = &mut resource.0;
+    let body_handle = rigid_body_set.insert(body);
+    let collider_handle = collider_set.insert_with_parent(collider, body_handle, rigid_body_set);
+    (body_handle, collider_handle)
+}
+
+/// Положение и поворот пролета моста при заданном угле подъема
+fn drawbridge_pose(hinge: Vec3, rotation: Quat, length: f32, angle: f32) -> (Vec3, Quat) {
+    let deck_rotation = rotation * Quat::from_rotation_x(-angle);
+    (hinge + deck_rotation * Vec3::new(0.0, 0.0, length / 2.0), deck_rotation)
+}
+
+/// Создает опасности трассы вместе с их физическими телами и знаками
+pub fn create_hazards(
+    world: &mut World,
+    render_resources: &mut RenderResourceManager,
+    definitions: &[HazardDefinition],
+) -> Vec<hecs::Entity> {
+    let cube_mesh = render_resources.add_simple_cube();
+    let rock_material = render_resources.add_basic_material([0.45, 0.4, 0.35, 1.0]);
+    let bridge_material = render_resources.add_basic_material([0.35, 0.3, 0.28, 1.0]);
+    let train_material = render_resources.add_basic_material([0.2, 0.25, 0.5, 1.0]);
+    let sign_material = render_resources.add_basic_material([0.9, 0.75, 0.1, 1.0]);
+    let sign_lit_material = render_resources.add_emissive_material([1.0, 0.3, 0.1, 1.0], [6.0, 1.5, 0.3]);
+    let materials = physics_materials(world);
+
+    let mut hazards = Vec::new();
+    for definition in definitions {
+        let (kind, transform, render) = match &definition.kind {
+            HazardKindDefinition::Rockfall { trigger, trigger_radius, drop_point, rocks, rock_radius, rearm_time } => {
+                let drop_point = Vec3::from(*drop_point);
+                let rock_entities = (0..*rocks)
+                    .map(|_| {
+                        let mut body = RigidBodyBuilder::dynamic()
+                            .translation([drop_point.x, drop_point.y, drop_point.z].into())
+                            .build();
+                        body.set_enabled(false);
+                        let collider = materials.apply(PhysicsMaterialId::ROCK, ColliderBuilder::ball(*rock_radius)).density(2600.0).build();
+                        let (body_handle, collider_handle) = insert_body(world, body, collider);
+                        world.spawn((
+                            TransformComponent {
+                                position: drop_point,
+                                ..Default::default()
+                            },
+                            RigidBodyComponent {
+                                handle: body_handle,
+                                body_type: RigidBodyType::Dynamic,
+                            },
+                            ColliderComponent {
+                                handle: collider_handle,
+                                shape_type: ColliderShapeType::Ball,
+                            },
+                            RenderComponent {
+                                mesh_id: cube_mesh,
+                                material_id: rock_material,
+                                visible: false,
+                                scale: Vec3::splat(rock_radius * 1.6),
+                                opacity: 1.0,
+                            },
+                            ObstacleComponent {
+                                obstacle_type: ObstacleType::Rock,
+                                destructible: false,
+                                health: 100.0,
+                            },
+                        ))
+                    })
+                    .collect();
+                let kind = HazardKind::Rockfall {
+                    trigger: Vec3::from(*trigger),
+                    trigger_radius: *trigger_radius,
+                    drop_point,
+                    rock_radius: *rock_radius,
+                    rearm_time: *rearm_time,
+                    rocks: rock_entities,
+                };
+                let transform = TransformComponent {
+                    position: drop_point,
+                    ..Default::default()
+                };
+                (kind, transform, None)
+            }
+            HazardKindDefinition::Drawbridge { hinge, rotation_y, size, max_angle, closed_time, open_time, swing_time } => {
+                let hinge = Vec3::from(*hinge);
+                let rotation = Quat::from_rotation_y(rotation_y.to_radians());
+                let (position, deck_rotation) = drawbridge_pose(hinge, rotation, size[2], 0.0);
+                let body = RigidBodyBuilder::kinematic_position_based()
+                    .position(isometry(position, deck_rotation))
+                    .build();
+                let collider = materials
+                    .apply(PhysicsMaterialId::STEEL, ColliderBuilder::cuboid(size[0] / 2.0, size[1] / 2.0, size[2] / 2.0))
+                    .build();
+                let (body_handle, collider_handle) = insert_body(world, body, collider);
+                let kind = HazardKind::Drawbridge {
+                    hinge,
+                    rotation,
+                    length: size[2],
+                    max_angle: max_angle.to_radians(),
+                    closed_time: *closed_time,
+                    open_time: *open_time,
+                    swing_time: *swing_time,
+                    body: body_handle,
+                };
+                let transform = TransformComponent {
+                    position,
+                    rotation: deck_rotation,
+                    ..Default::default()
+                };
+                let render = RenderComponent {
+                    mesh_id: cube_mesh,
+                    material_id: bridge_material,
+                    visible: true,
+                    scale: Vec3::from(*size),
+                    opacity: 1.0,
+                };
+                (kind, transform, Some((render, body_handle, collider_handle)))
+            }
+            HazardKindDefinition::Train { start, end, speed, interval, size } => {
+                let start = Vec3::from(*start);
+                let end = Vec3::from(*end);
+                let heading = (end - start).normalize();
+                let rotation = Quat::from_rotation_arc(Vec3::Z, heading);
+                // Между проходами состав убран с путей
+                let mut body = RigidBodyBuilder::kinematic_position_based()
+                    .position(isometry(start, rotation))
+                    .build();
+                body.set_enabled(false);
+                let collider = materials
+                    .apply(PhysicsMaterialId::DEFAULT, ColliderBuilder::cuboid(size[0] / 2.0, size[1] / 2.0, size[2] / 2.0))
+                    .build();
+                let (body_handle, collider_handle) = insert_body(world, body, collider);
+                let kind = HazardKind::Train {
+                    start,
+                    end,
+                    speed: *speed,
+                    interval: *interval,
+                    length: size[2],
+                    body: body_handle,
+                };
+                let transform = TransformComponent {
+                    position: start,
+                    rotation,
+                    ..Default::default()
+                };
+                let render = RenderComponent {
+                    mesh_id: cube_mesh,
+                    material_id: train_material,
+                    visible: false,
+                    scale: Vec3::from(*size),
+                    opacity: 1.0,
+                };
+                (kind, transform, Some((render, body_handle, collider_handle)))
+            }
+        };
+
+        let hazard = HazardComponent {
+            kind,
+            state: HazardState::Idle,
+            timer: 0.0,
+            warning_time: definition.warning_time.max(0.0),
+        };
+        let entity = match render {
+            Some((render, body_handle, collider_handle)) => world.spawn((
+                hazard,
+                transform,
+                render,
+                RigidBodyComponent {
+                    handle: body_handle,
+                    body_type: RigidBodyType::Kinematic,
+                },
+                ColliderComponent {
+                    handle: collider_handle,
+                    shape_type: ColliderShapeType::Box,
+                },
+            )),
+            None => world.spawn((hazard, transform)),
+        };
+
+        if let Some(sign) = definition.sign {
+            world.spawn((
+                TransformComponent {
+                    position: Vec3::from(sign) + Vec3::Y * 1.5,
+                    ..Default::default()
+                },
+                RenderComponent {
+                    mesh_id: cube_mesh,
+                    material_id: sign_material,
+                    visible: true,
+                    scale: Vec3::new(1.0, 1.0, 0.1),
+                    opacity: 1.0,
+                },
+                HazardSignComponent {
+                    hazard: entity,
+                    idle_material: sign_material,
+                    lit_material: sign_lit_material,
+                },
+            ));
+        }
+        hazards.push(entity);
+    }
+    hazards
+}
+
+/// Изменения физики, накопленные за кадр
+enum HazardCommand {
+    DropRocks { rocks: Vec<hecs::Entity>, drop_point: Vec3, spread: f32 },
+    ClearRocks(Vec<hecs::Entity>),
+    MoveBody { body: RigidBodyHandle, position: Vec3, rotation: Quat },
+    EnableBody { entity: hecs::Entity, body: RigidBodyHandle, enabled: bool },
+}
+
+/// Система опасностей: циклы срабатывания, физика, знаки и опасные зоны
+pub struct HazardSystem {
+    rng: SimpleRng,
+}
+
+impl HazardSystem {
+    pub fn new() -> Self {
+        Self { rng: SimpleRng::new(0x4A2A_02D5) }
+    }
+}
+
+impl Default for HazardSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl System for HazardSystem {
+    fn update(&mut self, world: &mut World, delta_time: f32) {
+        // Камнепад срабатывает, когда кузов машины входит в сферу у триггера
+        let triggers: Vec<(hecs::Entity, Vec3, f32)> = world
+            .query::<&HazardComponent>()
+            .iter()
+            .filter_map(|(entity, hazard)| match &hazard.kind {
+                HazardKind::Rockfall { trigger, trigger_radius, .. } => Some((entity, *trigger, *trigger_radius)),
+                _ => None,
+            })
+            .collect();
+        let triggered: Vec<hecs::Entity> = triggers
+            .into_iter()
+            .filter(|&(_, trigger, radius)| {
+                overlap_sphere(world, trigger, radius, SceneQueryFilter::default())
+                    .into_iter()
+                    .any(|entity| world.get::<&CarComponent>(entity).is_ok())
+            })
+            .map(|(entity, _, _)| entity)
+            .collect();
+
+        let mut commands = Vec::new();
+        let mut zones = Vec::new();
+
+        for (entity, hazard) in world.query_mut::<&mut HazardComponent>() {
+            hazard.timer += delta_time;
+            let warning_time = hazard.warning_time;
+            let hazard_type = hazard.hazard_type();
+
+            let zone = match &hazard.kind {
+                HazardKind::Rockfall { trigger, drop_point, rock_radius, rearm_time, rocks, .. } => {
+                    match hazard.state {
+                        HazardState::Idle if triggered.contains(&entity) => {
+                            hazard.state = HazardState::Warning;
+                            hazard.timer = 0.0;
+                        }
+                        HazardState::Warning if hazard.timer >= warning_time => {
+                            hazard.state = HazardState::Active;
+                            hazard.timer = 0.0;
+                            commands.push(HazardCommand::DropRocks {
+                                rocks: rocks.clone(),
+                                drop_point: *drop_point,
+                                spread: rock_radius * 3.0,
+                            });
+                        }
+                        HazardState::Active if hazard.timer >= *rearm_time => {
+                            hazard.state = HazardState::Idle;
+                            hazard.timer = 0.0;
+                            commands.push(HazardCommand::ClearRocks(rocks.clone()));
+                        }
+                        _ => {}
+                    }
+                    // Зона - место падения на уровне дороги
+                    let ground = Vec3::new(drop_point.x, trigger.y, drop_point.z);
+                    (hazard.state != HazardState::Idle).then_some((ground, rock_radius * 6.0))
+                }
+                HazardKind::Drawbridge { hinge, rotation, length, max_angle, closed_time, open_time, swing_time, body } => {
+                    let cycle = swing_time * 2.0 + open_time;
+                    match hazard.state {
+                        HazardState::Idle if hazard.timer >= (closed_time - warning_time).max(0.0) => {
+                            hazard.state = HazardState::Warning;
+                            hazard.timer = 0.0;
+                        }
+                        HazardState::Warning if hazard.timer >= warning_time => {
+                            hazard.state = HazardState::Active;
+                            hazard.timer = 0.0;
+                        }
+                        HazardState::Active if hazard.timer >= cycle => {
+                            hazard.state = HazardState::Idle;
+                            hazard.timer = 0.0;
+                        }
+                        _ => {}
+                    }
+                    // Подъем, пауза в верхнем положении и опускание
+                    let raise = if hazard.state == HazardState::Active {
+                        let t = hazard.timer;
+                        if t < *swing_time {
+                            t / swing_time
+                        } else if t < swing_time + open_time {
+                            1.0
+                        } else {
+                            1.0 - (t - swing_time - open_time) / swing_time
+                        }
+                    } else {
+                        0.0
+                    };
+                    let (position, deck_rotation) = drawbridge_pose(*hinge, *rotation, *length, max_angle * raise.clamp(0.0, 1.0));
+                    commands.push(HazardCommand::MoveBody { body: *body, position, rotation: deck_rotation });
+                    let center = *hinge + *rotation * Vec3::new(0.0, 0.0, length / 2.0);
+                    (hazard.state != HazardState::Idle).then_some((center, length / 2.0 + 2.0))
+                }
+                HazardKind::Train { start, end, speed, interval, length, body } => {
+                    let travel_time = (start.distance(*end) + length) / speed;
+                    match hazard.state {
+                        HazardState::Idle if hazard.timer >= (interval - warning_time).max(0.0) => {
+                            hazard.state = HazardState::Warning;
+                            hazard.timer = 0.0;
+                        }
+                        HazardState::Warning if hazard.timer >= warning_time => {
+                            hazard.state = HazardState::Active;
+                            hazard.timer = 0.0;
+                            commands.push(HazardCommand::EnableBody { entity, body: *body, enabled: true });
+                        }
+                        HazardState::Active if hazard.timer >= travel_time => {
+                            hazard.state = HazardState::Idle;
+                            hazard.timer = 0.0;
+                            commands.push(HazardCommand::EnableBody { entity, body: *body, enabled: false });
+                        }
+                        _ => {}
+                    }
+                    let heading = (*end - *start).normalize();
+                    let position = match hazard.state {
+                        HazardState::Active => *start + heading * (speed * hazard.timer).min(start.distance(*end)),
+                        _ => *start,
+                    };
+                    if hazard.state == HazardState::Active {
+                        commands.push(HazardCommand::MoveBody {
+                            body: *body,
+                            position,
+                            rotation: Quat::from_rotation_arc(Vec3::Z, heading),
+                        });
+                    }
+                    // Пока состав не вышел, опасен весь переезд
+                    let crossing = (*start + *end) / 2.0;
+                    match hazard.state {
+                        HazardState::Idle => None,
+                        HazardState::Warning => Some((crossing, *length)),
+                        HazardState::Active => Some((position, length / 2.0 + 2.0)),
+                    }
+                }
+            };
+
+            if let Some((position, radius)) = zone {
+                let time_to_active = match hazard.state {
+                    HazardState::Warning => (warning_time - hazard.timer).max(0.0),
+                    _ => 0.0,
+                };
+                zones.push(HazardZone {
+                    hazard_type,
+                    state: hazard.state,
+                    position,
+                    radius,
+                    time_to_active,
+                });
+            }
+        }
+
+        self.apply_commands(world, commands);
+
+        // Знаки горят, пока опасность предупреждает или действует
+        let lit: Vec<(hecs::Entity, bool)> = world
+            .query::<&HazardSignComponent>()
+            .iter()
+            .map(|(entity, sign)| {
+                let active = world
+                    .get::<&HazardComponent>(sign.hazard)
+                    .map(|hazard| hazard.state != HazardState::Idle)
+                    .unwrap_or(false);
+                (entity, active)
+            })
+            .collect();
+        for (entity, active) in lit {
+            if let Ok((sign, render)) = world.query_one_mut::<(&HazardSignComponent, &mut RenderComponent)>(entity) {
+                render.material_id = if active { sign.lit_material } else { sign.idle_material };
+            }
+        }
+
+        match world.query_mut::<&mut Resource<Vec<HazardZone>>>().into_iter().next() {
+            Some((_, current)) => current.0 = zones,
+            None => {
+                world.spawn((Resource(zones),));
+            }
+        }
+    }
+}
+
+impl HazardSystem {
+    fn apply_commands(&mut self, world: &mut World, commands: Vec<HazardCommand>) {
+        let mut visibility = Vec::new();
+        let mut body_updates = Vec::new();
+
+        for command in commands {
+            match command {
+                HazardCommand::DropRocks { rocks, drop_point, spread } => {
+                    for rock in rocks {
+                        let Some(handle) = world.get::<&RigidBodyComponent>(rock).ok().map(|rb| rb.handle) else {
+                            continue;
+                        };
+                        let offset = Vec3::new(
+                            self.rng.range(-spread, spread),
+                            self.rng.range(0.0, spread),
+                            self.rng.range(-spread, spread),
+                        );
+                        let velocity = Vec3::new(self.rng.range(-2.0, 2.0), 0.0, self.rng.range(-2.0, 2.0));
+                        body_updates.push((handle, Some((drop_point + offset, velocity))));
+                        visibility.push((rock, true));
+                    }
+                }
+                HazardCommand::ClearRocks(rocks) => {
+                    for rock in rocks {
+                        if let Some(handle) = world.get::<&RigidBodyComponent>(rock).ok().map(|rb| rb.handle) {
+                            body_updates.push((handle, None));
+                        }
+                        visibility.push((rock, false));
+                    }
+                }
+                HazardCommand::EnableBody { entity, body, enabled } => {
+                    visibility.push((entity, enabled));
+                    if let Some((_, resource)) = world.query_mut::<&mut Resource<(RigidBodySet, ColliderSet)>>().into_iter().next() {
+                        if let Some(rb) = resource.0 .0.get_mut(body) {
+                            rb.set_enabled(enabled);
+                        }
+                    }
+                }
+                HazardCommand::MoveBody { body, position, rotation } => {
+                    if let Some((_, resource)) = world.query_mut::<&mut Resource<(RigidBodySet, ColliderSet)>>().into_iter().next() {
+                        if let Some(rb) = resource.0 .0.get_mut(body) {
+                            rb.set_next_kinematic_position(isometry(position, rotation));
+                        }
+                    }
+                }
+            }
+        }
+
+        if let Some((_, resource)) = world.query_mut::<&mut Resource<(RigidBodySet, ColliderSet)>>().into_iter().next() {
+            let rigid_body_set = &mut resource.0 .0;
+            for (handle, drop) in body_updates {
+                let Some(rb) = rigid_body_set.get_mut(handle) else {
+                    continue;
+                };
+                match drop {
+                    Some((position, velocity)) => {
+                        rb.set_enabled(true);
+                        rb.set_translation([position.x, position.y, position.z].into(), true);
+                        rb.set_linvel([velocity.x, velocity.y, velocity.z].into(), true);
+                        rb.set_angvel(Default::default(), true);
+                    }
+                    None => rb.set_enabled(false),
+                }
+            }
+        }
+
+        for (entity, visible) in visibility {
+            if let Ok(render) = world.query_one_mut::<&mut RenderComponent>(entity) {
+                render.visible = visible;
+            }
+        }
+    }
+}
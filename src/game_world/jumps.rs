@@ -0,0 +1,135 @@
+use crate::core::audio::AudioEvent;
+use crate::core::ecs::{Resource, System};
+use crate::core::physics::TransformComponent;
+use crate::game_world::car::{CarComponent, CarWheelBindingComponent, PlayerCarComponent, WheelComponent};
+use crate::game_world::track::publish_frame_events;
+use crate::gameplay::rules::{current_rules, DamageLevel};
+use glam::Vec3;
+use hecs::{Entity, World};
+
+/// Полет короче этого - подскок на неровности, а не прыжок, с
+const MIN_JUMP_AIRTIME: f32 = 0.25;
+
+/// Вертикальная скорость приземления, которую подвеска гасит без последствий, м/с
+const LANDING_DAMAGE_SPEED: f32 = 8.0;
+
+/// Повреждение на каждый м/с приземления сверх безопасной скорости при множителе 1
+const LANDING_DAMAGE_PER_SPEED: f32 = 0.02;
+
+/// Вертикальная скорость, при которой удар о землю звучит в полную силу, м/с
+const LANDING_SOUND_FULL_SPEED: f32 = 12.0;
+
+/// Отрыв от земли или приземление после прыжка
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JumpPhase {
+    Takeoff,
+    Landing,
+}
+
+/// Прыжок машины; очередь `EventQueue<JumpEvent>` обновляется каждый кадр
+#[derive(Debug, Clone)]
+pub struct JumpEvent {
+    pub car_entity: Entity,
+    pub phase: JumpPhase,
+    pub airtime: f32,      // Время в воздухе, с; при Takeoff - сколько уже длится полет
+    pub impact_speed: f32, // Вертикальная скорость удара о землю, м/с; при Takeoff - 0
+}
+
+/// Полет машины; добавляется системой прыжков
+#[derive(Debug, Clone, Default)]
+pub struct AirtimeComponent {
+    pub airborne: bool,      // Ни одно колесо не касается земли
+    pub jumping: bool,       // Полет дольше подскока: о нем уже объявлено
+    pub airtime: f32,        // Длительность текущего полета, с
+    pub vertical_speed: f32, // м/с, вверх положительная
+    last_position: Option<Vec3>,
+}
+
+/// Прыжки: время в воздухе по касанию колес и удар при приземлении.
+/// Жесткое приземление повреждает подвеску и кузов по правилам урона
+pub struct JumpSystem;
+
+impl System for JumpSystem {
+    fn update(&mut self, world: &mut World, delta_time: f32) {
+        let rules = current_rules(world);
+        let damage_scale = rules.damage_scale();
+        let mechanical = rules.damage == DamageLevel::Full;
+
+        let cars: Vec<(Entity, bool)> = world
+            .query::<&CarWheelBindingComponent>()
+            .iter()
+            .map(|(_, binding)| {
+                let grounded = binding
+                    .wheel_entities
+                    .iter()
+                    .any(|&wheel| world.get::<&WheelComponent>(wheel).map(|wheel| wheel.grounded).unwrap_or(false));
+                (binding.car_entity, grounded)
+            })
+            .collect();
+
+        let mut events = Vec::new();
+        for (car_entity, grounded) in cars {
+            if world.get::<&AirtimeComponent>(car_entity).is_err() {
+                let _ = world.insert_one(car_entity, AirtimeComponent::default());
+            }
+            let Ok((airtime, transform)) = world.query_one_mut::<(&mut AirtimeComponent, &TransformComponent)>(car_entity) else {
+                continue;
+            };
+            // Скорость удара берется с прошлого кадра: в кадр касания подвеска уже гасит ее
+            let impact_speed = (-airtime.vertical_speed).max(0.0);
+            if let Some(last) = airtime.last_position {
+                if delta_time > 0.0 {
+                    airtime.vertical_speed = (transform.position.y - last.y) / delta_time;
+                }
+            }
+            airtime.last_position = Some(transform.position);
+
+            if !grounded {
+                airtime.airtime = if airtime.airborne { airtime.airtime + delta_time } else { 0.0 };
+                airtime.airborne = true;
+                if !airtime.jumping && airtime.airtime >= MIN_JUMP_AIRTIME {
+                    airtime.jumping = true;
+                    events.push(JumpEvent { car_entity, phase: JumpPhase::Takeoff, airtime: airtime.airtime, impact_speed: 0.0 });
+                }
+                continue;
+            }
+            if airtime.jumping {
+                events.push(JumpEvent { car_entity, phase: JumpPhase::Landing, airtime: airtime.airtime, impact_speed });
+            }
+            airtime.airborne = false;
+            airtime.jumping = false;
+            airtime.airtime = 0.0;
+        }
+
+        let mut thud = None;
+        for event in events.iter().filter(|event| event.phase == JumpPhase::Landing) {
+            let player = world.get::<&PlayerCarComponent>(event.car_entity).is_ok();
+            if player {
+                thud = Some((event.impact_speed / LANDING_SOUND_FULL_SPEED).clamp(0.2, 1.0));
+            }
+            let excess = event.impact_speed - LANDING_DAMAGE_SPEED;
+            if excess > 0.0 && damage_scale > 0.0 {
+                if let Ok(car) = world.query_one_mut::<&mut CarComponent>(event.car_entity) {
+                    car.damage.apply_landing(excess * LANDING_DAMAGE_PER_SPEED * damage_scale, mechanical);
+                }
+            }
+        }
+        publish_frame_events(world, events);
+
+        // Удар о землю после прыжка машины игрока
+        if let Some(volume) = thud {
+            let sound = AudioEvent::PlaySound {
+                name: "landing_thud".to_string(),
+                volume,
+                looping: false,
+            };
+            match world.query_mut::<&mut Resource<Vec<AudioEvent>>>().into_iter().next() {
+                Some((_, queue)) => queue.0.push(sound),
+                None => {
+                    world.spawn((Resource(vec![sound]),));
+                }
+            }
+        }
+    }
+}
+
@@ -1,9 +1,35 @@
 pub mod car;
+pub mod car_builder;
+pub mod car_definition;
+pub mod car_setup;
 pub mod track;
+pub mod track_file;
+pub mod track_generator;
+pub mod track_limits;
+pub mod jumps;
+pub mod track_spline;
 pub mod environment;
+pub mod terrain;
+pub mod camera;
+pub mod vegetation;
+pub mod particles;
+pub mod collision_effects;
+pub mod damage;
+pub mod pit_stop;
+pub mod props;
+pub mod engine_thermal;
+pub mod forced_induction;
+pub mod upgrades;
+pub mod blob_shadow;
+pub mod advertising;
+pub mod wheel_parts;
+pub mod hazards;
+pub mod physics_debug;
+pub mod vehicle_sim;
 
 use crate::core::ecs::{EcsManager, Resource};
-use crate::core::physics::PhysicsSystem;
+use crate::core::hierarchy::HierarchySystem;
+use crate::core::physics::{PhysicsMaterialRegistry, PhysicsSystem};
 use rapier3d::prelude::{RigidBodySet, ColliderSet};
 
 /// Менеджер игрового мира
@@ -26,6 +52,22 @@ impl GameWorldManager {
         
         // Добавляем их как ресурс в ECS мир
         ecs_manager.world.spawn((Resource((rigid_body_set, collider_set)),));
+        
+        // Реестр материалов; трассы добавляют свои покрытия из файла materials.json
+        ecs_manager.world.spawn((Resource(PhysicsMaterialRegistry::new()),));
+        
+        // Помощники водителя из настроек игрока
+        let assists_path = std::path::Path::new(car::DRIVER_ASSISTS_PATH);
+        let assists = match car::DriverAssists::load(assists_path) {
+            Ok(assists) => assists,
+            Err(e) => {
+                if assists_path.exists() {
+                    eprintln!("Не удалось прочитать настройки помощников: {}", e);
+                }
+                car::DriverAssists::default()
+            }
+        };
+        ecs_manager.world.spawn((Resource(assists),));
     }
     
     /// Регистрация всех необходимых систем в ECS-менеджере
@@ -33,8 +75,47 @@ impl GameWorldManager {
         // Физическую систему не регистрируем, будем вызывать напрямую
         
         // Регистрация систем для автомобилей
-        ecs_manager.register_system(car::CarControlSystem);
-        ecs_manager.register_system(car::CarPhysicsSystem);
+        ecs_manager.register_system(car::CarControlSystem::new());
+        ecs_manager.register_system(car::CarResetSystem::new());
+        ecs_manager.register_system(forced_induction::ForcedInductionSystem::new());
+        ecs_manager.register_system(car::EngineSoundSystem);
+        ecs_manager.register_system(car::WheelSurfaceSoundSystem);
+        ecs_manager.register_system(car::CarPhysicsSystem::new());
+        ecs_manager.register_system(car::WheelAnimationSystem);
+        ecs_manager.register_system(HierarchySystem);
+        ecs_manager.register_system(car::BrakeTemperatureSystem::new());
+        ecs_manager.register_system(car::TireWearSystem::new());
+        ecs_manager.register_system(engine_thermal::EngineTemperatureSystem::new());
+        ecs_manager.register_system(pit_stop::PitStopSystem::new());
+        ecs_manager.register_system(car::FuelSystem::new());
+        ecs_manager.register_system(damage::DamageSystem);
+        ecs_manager.register_system(wheel_parts::WheelPartSystem);
+        ecs_manager.register_system(car::CarLightsSystem::new());
+        ecs_manager.register_system(car::WheelSurfaceSystem);
+        ecs_manager.register_system(track_limits::TrackLimitsSystem);
+        ecs_manager.register_system(jumps::JumpSystem);
+        ecs_manager.register_system(blob_shadow::BlobShadowSystem);
+        
+        // Хронометраж: чекпоинты, круги и сектора
+        ecs_manager.register_system(track::TrackSystem);
+        
+        // Камера следует за автомобилем после обновления его физики
+        ecs_manager.register_system(camera::CameraRigSystem);
+        ecs_manager.register_system(physics_debug::PhysicsDebugSystem::new());
+        
+        // Системы окружения
+        ecs_manager.register_system(environment::TimeOfDaySystem);
+        ecs_manager.register_system(environment::WeatherSystem::new());
+        ecs_manager.register_system(environment::WindForceSystem);
+        ecs_manager.register_system(vegetation::VegetationSystem);
+        ecs_manager.register_system(advertising::AdBoardSystem);
+        ecs_manager.register_system(hazards::HazardSystem::new());
+        
+        // Эффекты столкновений и частицы
+        ecs_manager.register_system(collision_effects::ScrapeEffectSystem::new());
+        ecs_manager.register_system(props::KnockablePropSystem);
+        ecs_manager.register_system(particles::WheelParticleSystem::new());
+        ecs_manager.register_system(particles::ParticleSystem::new());
         
         // Здесь будут регистрироваться другие системы для трасс и окружения
     }
@@ -0,0 +1,406 @@
+use crate::core::ecs::{System, Resource};
+use crate::core::physics::{RigidBodyComponent, TransformComponent};
+use crate::core::random::SimpleRng;
+use crate::core::renderer::{ParticleInstance, ParticleRenderData};
+use crate::game_world::car::{CarWheelBindingComponent, WheelComponent};
+use crate::game_world::track::SurfaceType;
+use glam::Vec3;
+use hecs::World;
+use rapier3d::prelude::{ColliderSet, RigidBodySet};
+use std::collections::HashMap;
+
+/// Типы частиц
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParticleKind {
+    Sparks,
+    Gravel,
+    Dust,
+    GrassClippings,
+    SnowSpray,
+    TireSmoke, // Дым от пробуксовки на месте
+    Steam,     // Пар из-под капота перегретого двигателя
+}
+
+/// Физические и визуальные параметры типа частиц
+pub struct ParticleKindParams {
+    pub speed: (f32, f32),
+    pub lifetime: (f32, f32),
+    pub size: (f32, f32),
+    pub gravity: f32,   // Доля земного притяжения
+    pub drag: f32,      // Затухание скорости, 1/с
+    pub color: [f32; 4],
+    pub end_color: [f32; 4],
+    pub additive: bool, // Светящиеся частицы складываются с фоном
+}
+
+impl ParticleKind {
+    pub fn params(&self) -> ParticleKindParams {
+        match self {
+            ParticleKind::Sparks => ParticleKindParams {
+                speed: (3.0, 9.0),
+                lifetime: (0.2, 0.6),
+                size: (0.03, 0.07),
+                gravity: 1.0,
+                drag: 1.5,
+                color: [1.0, 0.85, 0.4, 1.0],
+                end_color: [1.0, 0.3, 0.05, 0.0],
+                additive: true,
+            },
+            ParticleKind::Gravel => ParticleKindParams {
+                speed: (2.0, 6.0),
+                lifetime: (0.4, 0.9),
+                size: (0.02, 0.05),
+                gravity: 1.0,
+                drag: 0.5,
+                color: [0.35, 0.32, 0.28, 0.9],
+                end_color: [0.35, 0.32, 0.28, 0.6],
+                additive: false,
+            },
+            ParticleKind::Dust => ParticleKindParams {
+                speed: (0.5, 2.0),
+                lifetime: (1.0, 2.5),
+                size: (0.3, 0.8),
+                gravity: -0.02, // Пыль медленно поднимается
+                drag: 1.2,
+                color: [0.5, 0.42, 0.3, 0.25],
+                end_color: [0.55, 0.48, 0.38, 0.0],
+                additive: false,
+            },
+            ParticleKind::GrassClippings => ParticleKindParams {
+                speed: (1.5, 4.0),
+                lifetime: (0.5, 1.2),
+                size: (0.02, 0.04),
+                gravity: 0.4, // Легкие травинки планируют
+                drag: 2.0,
+                color: [0.25, 0.5, 0.15, 0.9],
+                end_color: [0.3, 0.45, 0.2, 0.0],
+                additive: false,
+            },
+            ParticleKind::SnowSpray => ParticleKindParams {
+                speed: (1.5, 5.0),
+                lifetime: (0.6, 1.4),
+                size: (0.05, 0.15),
+                gravity: 0.3,
+                drag: 1.5,
+                color: [0.95, 0.97, 1.0, 0.6],
+                end_color: [0.95, 0.97, 1.0, 0.0],
+                additive: false,
+            },
+            ParticleKind::TireSmoke => ParticleKindParams {
+                speed: (0.5, 1.5),
+                lifetime: (2.0, 4.0),
+                size: (0.6, 1.6),
+                gravity: -0.05, // Густой дым клубится и поднимается
+                drag: 0.8,
+                color: [0.85, 0.85, 0.85, 0.5],
+                end_color: [0.9, 0.9, 0.9, 0.0],
+                additive: false,
+            },
+            ParticleKind::Steam => ParticleKindParams {
+                speed: (1.0, 2.5),
+                lifetime: (0.8, 1.6),
+                size: (0.2, 0.7),
+                gravity: -0.1, // Пар быстро поднимается и тает
+                drag: 1.5,
+                color: [0.95, 0.95, 0.95, 0.4],
+                end_color: [1.0, 1.0, 1.0, 0.0],
+                additive: false,
+            },
+        }
+    }
+
+    /// Частицы, которые колесо выбрасывает на поверхности (None - чистое покрытие)
+    pub fn for_surface(surface: SurfaceType) -> Option<Self> {
+        match surface {
+            SurfaceType::Gravel => Some(ParticleKind::Gravel),
+            SurfaceType::Dirt | SurfaceType::Sand => Some(ParticleKind::Dust),
+            SurfaceType::Grass => Some(ParticleKind::GrassClippings),
+            SurfaceType::Snow => Some(ParticleKind::SnowSpray),
+            SurfaceType::Asphalt | SurfaceType::Concrete | SurfaceType::Ice | SurfaceType::Curb => None,
+        }
+    }
+
+    /// Максимальная интенсивность выброса из-под колеса, частиц в секунду
+    pub fn wheel_emission_rate(&self) -> f32 {
+        match self {
+            ParticleKind::Sparks | ParticleKind::TireSmoke | ParticleKind::Steam => 0.0,
+            ParticleKind::Gravel => 120.0,
+            ParticleKind::Dust => 25.0,
+            ParticleKind::GrassClippings => 80.0,
+            ParticleKind::SnowSpray => 100.0,
+        }
+    }
+}
+
+/// Отдельная частица
+#[derive(Debug, Clone)]
+pub struct Particle {
+    pub kind: ParticleKind,
+    pub position: Vec3,
+    pub velocity: Vec3,
+    pub size: f32,
+    pub age: f32,
+    pub lifetime: f32,
+}
+
+/// Пул живых частиц
+pub struct ParticlePool {
+    pub particles: Vec<Particle>,
+    pub max_particles: usize,
+}
+
+impl ParticlePool {
+    pub fn new(max_particles: usize) -> Self {
+        Self {
+            particles: Vec::new(),
+            max_particles,
+        }
+    }
+}
+
+/// Излучатель частиц, привязанный к трансформации сущности
+pub struct ParticleEmitterComponent {
+    pub kind: ParticleKind,
+    pub rate: f32,        // Частиц в секунду
+    pub direction: Vec3,  // Основное направление вылета
+    pub spread: f32,      // 0 - строго по направлению, 1 - во все стороны
+    pub inherit_velocity: Vec3,
+    pub active: bool,
+    pub accumulator: f32,
+}
+
+impl ParticleEmitterComponent {
+    pub fn new(kind: ParticleKind) -> Self {
+        Self {
+            kind,
+            rate: 0.0,
+            direction: Vec3::Y,
+            spread: 0.5,
+            inherit_velocity: Vec3::ZERO,
+            active: true,
+            accumulator: 0.0,
+        }
+    }
+}
+
+/// Система частиц: излучение, симуляция и подготовка данных для рендера
+pub struct ParticleSystem {
+    rng: SimpleRng,
+}
+
+impl ParticleSystem {
+    pub fn new() -> Self {
+        Self {
+            rng: SimpleRng::new(0x5EED),
+        }
+    }
+
+    fn spawn_particle(&mut self, emitter: &ParticleEmitterComponent, origin: Vec3) -> Particle {
+        let params = emitter.kind.params();
+        let random_direction = Vec3::new(
+            self.rng.range(-1.0, 1.0),
+            self.rng.range(-1.0, 1.0),
+            self.rng.range(-1.0, 1.0),
+        );
+        let direction = (emitter.direction.normalize_or_zero() * (1.0 - emitter.spread)
+            + random_direction * emitter.spread)
+            .normalize_or_zero();
+
+        Particle {
+            kind: emitter.kind,
+            position: origin,
+            velocity: direction * self.rng.range(params.speed.0, params.speed.1) + emitter.inherit_velocity,
+            size: self.rng.range(params.size.0, params.size.1),
+            age: 0.0,
+            lifetime: self.rng.range(params.lifetime.0, params.lifetime.1),
+        }
+    }
+}
+
+impl System for ParticleSystem {
+    fn update(&mut self, world: &mut World, delta_time: f32) {
+        // Излучение новых частиц
+        let mut spawned = Vec::new();
+        for (_, (emitter, transform)) in world.query_mut::<(&mut ParticleEmitterComponent, &TransformComponent)>() {
+            if !emitter.active || emitter.rate <= 0.0 {
+                emitter.accumulator = 0.0;
+                continue;
+            }
+            emitter.accumulator += emitter.rate * delta_time;
+            while emitter.accumulator >= 1.0 {
+                emitter.accumulator -= 1.0;
+                spawned.push(self.spawn_particle(emitter, transform.position));
+            }
+        }
+
+        let pool = world
+            .query_mut::<&mut Resource<ParticlePool>>()
+            .into_iter()
+            .next()
+            .map(|(_, res)| &mut res.0);
+        let Some(pool) = pool else {
+            world.spawn((Resource(ParticlePool::new(4096)),));
+            return;
+        };
+
+        // Симуляция
+        let gravity = Vec3::new(0.0, -9.81, 0.0);
+        pool.particles.retain_mut(|particle| {
+            particle.age += delta_time;
+            if particle.age >= particle.lifetime {
+                return false;
+            }
+            let params = particle.kind.params();
+            particle.velocity += gravity * params.gravity * delta_time;
+            particle.velocity *= (1.0 - params.drag * delta_time).max(0.0);
+            particle.position += particle.velocity * delta_time;
+            true
+        });
+
+        let free = pool.max_particles.saturating_sub(pool.particles.len());
+        pool.particles.extend(spawned.into_iter().take(free));
+
+        // Данные для рендера: цвет и прозрачность меняются с возрастом
+        let instances: Vec<ParticleInstance> = pool
+            .particles
+            .iter()
+            .map(|particle| {
+                let params = particle.kind.params();
+                let t = particle.age / particle.lifetime;
+                let mut color = [0.0; 4];
+                for (i, c) in color.iter_mut().enumerate() {
+                    *c = params.color[i] + (params.end_color[i] - params.color[i]) * t;
+                }
+                ParticleInstance {
+                    position_size: [particle.position.x, particle.position.y, particle.position.z, particle.size],
+                    color,
+                    params: [if params.additive { 1.0 } else { 0.0 }, 0.0, 0.0, 0.0],
+                }
+            })
+            .collect();
+
+        let render_data = world
+            .query_mut::<&mut Resource<ParticleRenderData>>()
+            .into_iter()
+            .next()
+            .map(|(_, res)| &mut res.0);
+
+        match render_data {
+            Some(data) => data.instances = instances,
+            None => {
+                world.spawn((Resource(ParticleRenderData { instances }),));
+            }
+        }
+    }
+}
+
+/// Излучатель частиц из-под колеса; по нему система находит излучатель каждого колеса
+pub struct WheelEmitterComponent {
+    pub wheel: hecs::Entity,
+}
+
+/// Система выброса частиц из-под колес в зависимости от поверхности, скорости и проскальзывания
+pub struct WheelParticleSystem {
+    pub full_speed: f32, // Скорость, при которой выброс от скорости максимален, м/с
+}
+
+impl WheelParticleSystem {
+    pub fn new() -> Self {
+        Self { full_speed: 30.0 }
+    }
+}
+
+impl System for WheelParticleSystem {
+    fn update(&mut self, world: &mut World, _delta_time: f32) {
+        // Скорость кузова каждой машины из физического мира
+        let car_velocities: HashMap<hecs::Entity, Vec3> = world
+            .query::<&Resource<(RigidBodySet, ColliderSet)>>()
+            .iter()
+            .next()
+            .map(|(_, res)| {
+                world
+                    .query::<(&CarWheelBindingComponent,)>()
+                    .iter()
+                    .filter_map(|(_, (binding,))| {
+                        let handle = world.get::<&RigidBodyComponent>(binding.car_entity).ok()?.handle;
+                        let linvel = res.0 .0.get(handle)?.linvel();
+                        Some((binding.car_entity, Vec3::new(linvel.x, linvel.y, linvel.z)))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        // Точки контакта колес и параметры выброса
+        let mut wheel_states = Vec::new();
+        for (_, binding) in world.query::<&CarWheelBindingComponent>().iter() {
+            let Ok(car_transform) = world.get::<&TransformComponent>(binding.car_entity) else {
+                continue;
+            };
+            let velocity = car_velocities.get(&binding.car_entity).copied().unwrap_or(Vec3::ZERO);
+            for &wheel_entity in &binding.wheel_entities {
+                let Ok(wheel) = world.get::<&WheelComponent>(wheel_entity) else {
+                    continue;
+                };
+                let contact = car_transform.position
+                    + car_transform.rotation * (wheel.position - Vec3::Y * wheel.radius);
+                let kind = ParticleKind::for_surface(wheel.surface_type).filter(|_| wheel.grounded);
+                let slip = (wheel.slip_ratio.abs() + wheel.slip_angle.abs()).min(1.0);
+                wheel_states.push((wheel_entity, contact, velocity, kind, slip));
+            }
+        }
+
+        let emitters: HashMap<hecs::Entity, hecs::Entity> = world
+            .query::<&WheelEmitterComponent>()
+            .iter()
+            .map(|(entity, emitter)| (emitter.wheel, entity))
+            .collect();
+        let mut to_spawn = Vec::new();
+        for (wheel_entity, contact, velocity, kind, slip) in wheel_states {
+            let speed = velocity.length();
+            let intensity = (0.6 * slip + 0.4 * speed / self.full_speed).min(1.0);
+
+            match emitters.get(&wheel_entity) {
+                Some(&emitter_entity) => {
+                    if let Ok(mut transform) = world.get::<&mut TransformComponent>(emitter_entity) {
+                        transform.position = contact;
+                    }
+                    if let Ok(mut emitter) = world.get::<&mut ParticleEmitterComponent>(emitter_entity) {
+                        emitter.active = kind.is_some() && speed > 1.0;
+                        if let Some(kind) = kind {
+                            emitter.kind = kind;
+                            emitter.rate = kind.wheel_emission_rate() * intensity;
+                        }
+                        // Частицы летят назад относительно движения и вверх
+                        emitter.direction = -velocity.normalize_or_zero() + Vec3::Y * 0.8;
+                        emitter.inherit_velocity = velocity * 0.2;
+                    }
+                }
+                None => to_spawn.push((wheel_entity, contact)),
+            }
+        }
+
+        for (wheel_entity, contact) in to_spawn {
+            let mut emitter = ParticleEmitterComponent::new(ParticleKind::Dust);
+            emitter.active = false;
+            emitter.spread = 0.4;
+            world.spawn((
+                WheelEmitterComponent { wheel: wheel_entity },
+                TransformComponent {
+                    position: contact,
+                    ..Default::default()
+                },
+                emitter,
+            ));
+        }
+
+        // Излучатели удаленных колес
+        let orphaned: Vec<hecs::Entity> = emitters
+            .iter()
+            .filter(|(wheel, _)| !world.contains(**wheel))
+            .map(|(_, emitter)| *emitter)
+            .collect();
+        for emitter in orphaned {
+            let _ = world.despawn(emitter);
+        }
+    }
+}
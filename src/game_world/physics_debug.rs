@@ -0,0 +1,242 @@
+use crate::core::ecs::{Resource, System};
+use crate::core::input::InputAction;
+use crate::core::physics::{ContactInfo, JointComponent, JointKind, PhysicsIslandStats, TransformComponent};
+use crate::core::renderer::{DebugDraw, DebugLines};
+use crate::game_world::car::{CarWheelBindingComponent, WheelComponent};
+use glam::{Quat, Vec3};
+use hecs::World;
+use rapier3d::prelude::{ColliderSet, Isometry, Point, RigidBodySet};
+use std::collections::HashMap;
+
+/// Слой отладочной отрисовки физики
+const PHYSICS_DEBUG_LAYER: &str = "physics";
+
+/// Длина стрелки скорости: путь за это время, с
+const VELOCITY_ARROW_TIME: f32 = 0.1;
+
+/// Длина нормали контакта на 1 кН силы, м
+const CONTACT_NORMAL_PER_KN: f32 = 0.05;
+
+/// Как часто нагрузка физики пишется в лог при включенной отладке, с
+const STATS_REPORT_INTERVAL: f32 = 1.0;
+
+const STATIC_COLOR: [f32; 4] = [0.6, 0.6, 0.6, 0.6];
+const DYNAMIC_COLOR: [f32; 4] = [0.2, 1.0, 0.3, 1.0];
+const SLEEPING_COLOR: [f32; 4] = [0.3, 0.5, 1.0, 1.0];
+const VELOCITY_COLOR: [f32; 4] = [1.0, 0.9, 0.1, 1.0];
+const ANGULAR_VELOCITY_COLOR: [f32; 4] = [1.0, 0.3, 1.0, 1.0];
+const CONTACT_COLOR: [f32; 4] = [1.0, 0.2, 0.1, 1.0];
+const SUSPENSION_GROUNDED_COLOR: [f32; 4] = [0.1, 1.0, 1.0, 1.0];
+const SUSPENSION_AIRBORNE_COLOR: [f32; 4] = [1.0, 0.5, 0.0, 1.0];
+const JOINT_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
+
+fn to_vec3(point: &Point<f32>) -> Vec3 {
+    Vec3::new(point.x, point.y, point.z)
+}
+
+/// Точка коллайдера из локальных координат в мировые
+fn to_world(position: &Isometry<f32>, point: &Point<f32>) -> Vec3 {
+    to_vec3(&(position * point))
+}
+
+/// Система отладочной визуализации физики: каркасы коллайдеров, контакты, скорости, лучи подвески и шарниры
+pub struct PhysicsDebugSystem {
+    enabled: bool,
+    held: HashMap<InputAction, bool>,
+    report_timer: f32,
+}
+
+impl PhysicsDebugSystem {
+    pub fn new() -> Self {
+        Self {
+            enabled: false,
+            held: HashMap::new(),
+            report_timer: 0.0,
+        }
+    }
+
+    /// Срабатывание по нажатию, а не удержанию
+    fn pressed(&mut self, inputs: &HashMap<InputAction, f32>, action: InputAction) -> bool {
+        let down = *inputs.get(&action).unwrap_or(&0.0) > 0.5;
+        let was_down = self.held.insert(action, down).unwrap_or(false);
+        down && !was_down
+    }
+
+    /// Каркасы коллайдеров и стрелки скоростей динамических тел
+    fn draw_bodies(lines: &mut DebugLines, rigid_body_set: &RigidBodySet, collider_set: &ColliderSet) {
+        for (_, collider) in collider_set.iter() {
+            let body = collider.parent().and_then(|handle| rigid_body_set.get(handle));
+            let color = match body {
+                Some(body) if body.is_dynamic() && body.is_sleeping() => SLEEPING_COLOR,
+                Some(body) if body.is_dynamic() => DYNAMIC_COLOR,
+                _ => STATIC_COLOR,
+            };
+
+            let position = collider.position();
+            let center = Vec3::new(position.translation.x, position.translation.y, position.translation.z);
+            let rotation = {
+                let q = position.rotation;
+                Quat::from_xyzw(q.i, q.j, q.k, q.w)
+            };
+            let shape = collider.shape();
+            if let Some(cuboid) = shape.as_cuboid() {
+                let half = cuboid.half_extents;
+                lines.oriented_box(center, rotation, Vec3::new(half.x, half.y, half.z), color);
+            } else if let Some(ball) = shape.as_ball() {
+                for axis in [Vec3::X, Vec3::Y, Vec3::Z] {
+                    lines.circle(center, rotation * axis, ball.radius, color);
+                }
+            } else if let Some(capsule) = shape.as_capsule() {
+                let a = to_world(position, &capsule.segment.a);
+                let b = to_world(position, &capsule.segment.b);
+                let axis = (b - a).normalize_or_zero();
+                let side = axis.any_orthonormal_vector() * capsule.radius;
+                let other_side = axis.cross(side);
+                lines.circle(a, axis, capsule.radius, color);
+                lines.circle(b, axis, capsule.radius, color);
+                for offset in [side, -side, other_side, -other_side] {
+                    lines.line(a + offset, b + offset, color);
+                }
+            } else if let Some(trimesh) = shape.as_trimesh() {
+                for triangle in trimesh.triangles() {
+                    let a = to_world(position, &triangle.a);
+                    let b = to_world(position, &triangle.b);
+                    let c = to_world(position, &triangle.c);
+                    lines.line(a, b, color);
+                    lines.line(b, c, color);
+                    lines.line(c, a, color);
+                }
+            } else {
+                // Ландшафт и прочие сложные формы показываем габаритом
+                let aabb = collider.compute_aabb();
+                let (mins, maxs) = (to_vec3(&aabb.mins), to_vec3(&aabb.maxs));
+                lines.oriented_box((mins + maxs) / 2.0, Quat::IDENTITY, (maxs - mins) / 2.0, color);
+            }
+        }
+
+        for (_, body) in rigid_body_set.iter().filter(|(_, body)| body.is_dynamic()) {
+            let origin = {
+                let t = body.translation();
+                Vec3::new(t.x, t.y, t.z)
+            };
+            let linvel = body.linvel();
+            let angvel = body.angvel();
+            lines.ray(origin, Vec3::new(linvel.x, linvel.y, linvel.z) * VELOCITY_ARROW_TIME, VELOCITY_COLOR);
+            lines.ray(origin, Vec3::new(angvel.x, angvel.y, angvel.z), ANGULAR_VELOCITY_COLOR);
+        }
+    }
+
+    /// Лучи подвески: от точки крепления до пятна контакта колеса
+    fn draw_suspension(lines: &mut DebugLines, world: &World) {
+        for (_, binding) in world.query::<&CarWheelBindingComponent>().iter() {
+            let Ok(car_transform) = world.get::<&TransformComponent>(binding.car_entity) else {
+                continue;
+            };
+            let down = car_transform.rotation * -Vec3::Y;
+            for &wheel_entity in &binding.wheel_entities {
+                let Ok(wheel) = world.get::<&WheelComponent>(wheel_entity) else {
+                    continue;
+                };
+                let mount = car_transform.position
+                    + car_transform.rotation * (wheel.position + Vec3::Y * wheel.suspension_rest_length);
+                let hub = mount + down * wheel.suspension_length;
+                let color = if wheel.grounded { SUSPENSION_GROUNDED_COLOR } else { SUSPENSION_AIRBORNE_COLOR };
+                lines.line(mount, hub, color);
+                lines.line(hub, hub + down * wheel.radius, color);
+                lines.cross(hub, 0.05, color);
+            }
+        }
+    }
+
+    /// Шарниры: перемычка между точками крепления и ось вращения или скольжения
+    fn draw_joints(lines: &mut DebugLines, world: &World) {
+        for (_, joint) in world.query::<&JointComponent>().iter() {
+            if joint.broken {
+                continue;
+            }
+            let (Ok(transform1), Ok(transform2)) = (
+                world.get::<&TransformComponent>(joint.body1),
+                world.get::<&TransformComponent>(joint.body2),
+            ) else {
+                continue;
+            };
+            let anchor1 = transform1.position + transform1.rotation * joint.anchor1;
+            let anchor2 = transform2.position + transform2.rotation * joint.anchor2;
+            lines.line(anchor1, anchor2, JOINT_COLOR);
+            lines.cross(anchor1, 0.08, JOINT_COLOR);
+            if let JointKind::Revolute { axis } | JointKind::Prismatic { axis } = joint.kind {
+                let axis = transform1.rotation * axis.normalize_or_zero() * 0.5;
+                lines.line(anchor1 - axis, anchor1 + axis, JOINT_COLOR);
+            }
+        }
+    }
+}
+
+impl Default for PhysicsDebugSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl System for PhysicsDebugSystem {
+    fn update(&mut self, world: &mut World, delta_time: f32) {
+        let inputs = world
+            .query::<&Resource<HashMap<InputAction, f32>>>()
+            .iter()
+            .next()
+            .map(|(_, res)| res.0.clone())
+            .unwrap_or_default();
+        let toggled = self.pressed(&inputs, InputAction::TogglePhysicsDebug);
+        if toggled {
+            self.enabled = !self.enabled;
+        }
+        if !self.enabled && !toggled {
+            return;
+        }
+
+        let mut lines = DebugLines::new();
+        if self.enabled {
+            if let Some((_, physics)) = world.query::<&Resource<(RigidBodySet, ColliderSet)>>().iter().next() {
+                let (rigid_body_set, collider_set) = &physics.0;
+                Self::draw_bodies(&mut lines, rigid_body_set, collider_set);
+            }
+
+            if let Some((_, contacts)) = world.query::<&Resource<Vec<ContactInfo>>>().iter().next() {
+                for contact in &contacts.0 {
+                    lines.cross(contact.point, 0.1, CONTACT_COLOR);
+                    lines.ray(contact.point, contact.normal * contact.normal_force / 1000.0 * CONTACT_NORMAL_PER_KN, CONTACT_COLOR);
+                }
+            }
+
+            Self::draw_suspension(&mut lines, world);
+            Self::draw_joints(&mut lines, world);
+
+            self.report_timer -= delta_time;
+            if self.report_timer <= 0.0 {
+                self.report_timer = STATS_REPORT_INTERVAL;
+                if let Some((_, stats)) = world.query::<&Resource<PhysicsIslandStats>>().iter().next() {
+                    let stats = stats.0;
+                    eprintln!(
+                        "Физика: тел {} (активных {}, спят {}), островов {}, контактов {}, rapier {:.2} мс",
+                        stats.bodies,
+                        stats.active_bodies,
+                        stats.sleeping_bodies,
+                        stats.islands,
+                        stats.contact_pairs,
+                        stats.solver_time * 1000.0,
+                    );
+                }
+            }
+        }
+
+        // Выключение очищает слой один раз
+        match world.query_mut::<&mut Resource<DebugDraw>>().into_iter().next() {
+            Some((_, debug_draw)) => debug_draw.0.set_layer(PHYSICS_DEBUG_LAYER, lines),
+            None => {
+                let mut debug_draw = DebugDraw::default();
+                debug_draw.set_layer(PHYSICS_DEBUG_LAYER, lines);
+                world.spawn((Resource(debug_draw),));
+            }
+        }
+    }
+}
@@ -0,0 +1,113 @@
+use crate::core::ecs::System;
+use crate::core::physics::TransformComponent;
+use crate::game_world::car::{CarComponent, CarWheelBindingComponent, WheelComponent, PIT_STOP_SPEED};
+use crate::game_world::track::PitBoxComponent;
+use glam::Vec3;
+use hecs::{Entity, World};
+use std::collections::HashMap;
+
+/// Износ, начиная с которого механики меняют колеса
+const TIRE_CHANGE_WEAR: f32 = 0.05;
+
+/// Стоянка машины в боксе
+#[derive(Debug, Clone, Copy, Default)]
+struct PitStop {
+    elapsed: f32, // Время стоянки, с
+    tires_changed: bool,
+}
+
+/// Обслуживание в боксе: пока машина стоит, ее заправляют и чинят,
+/// а через tire_change_time ставят новые колеса
+pub struct PitStopSystem {
+    stops: HashMap<Entity, PitStop>,
+}
+
+impl PitStopSystem {
+    pub fn new() -> Self {
+        Self { stops: HashMap::new() }
+    }
+}
+
+impl Default for PitStopSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl System for PitStopSystem {
+    fn update(&mut self, world: &mut World, delta_time: f32) {
+        let pit_boxes: Vec<(Vec3, PitBoxComponent)> = world
+            .query::<(&PitBoxComponent, &TransformComponent)>()
+            .iter()
+            .map(|(_, (pit_box, transform))| (transform.position, pit_box.clone()))
+            .collect();
+        let tire_wear: HashMap<Entity, f32> = world
+            .query::<&CarWheelBindingComponent>()
+            .iter()
+            .map(|(_, binding)| {
+                let wear = binding
+                    .wheel_entities
+                    .iter()
+                    .filter_map(|&wheel| world.get::<&WheelComponent>(wheel).ok().map(|wheel| wheel.tire_wear))
+                    .fold(0.0, f32::max);
+                (binding.car_entity, wear)
+            })
+            .collect();
+
+        let mut tire_changes = Vec::new();
+        for (entity, (car, transform)) in world.query_mut::<(&mut CarComponent, &TransformComponent)>() {
+            let service = pit_boxes
+                .iter()
+                .find(|(position, pit_box)| {
+                    position.distance(transform.position) <= pit_box.radius
+                        && pit_box.assigned_car.is_none_or(|assigned| assigned == entity)
+                })
+                .map(|(_, pit_box)| pit_box)
+                .filter(|_| car.current_speed.abs() < PIT_STOP_SPEED);
+            let Some(service) = service else {
+                // Тронулась с места - стоянка закончена
+                self.stops.remove(&entity);
+                car.refueling = false;
+                car.repairing = false;
+                car.changing_tires = false;
+                continue;
+            };
+
+            let stop = self.stops.entry(entity).or_default();
+            stop.elapsed += delta_time;
+
+            car.refueling = car.fuel < car.fuel_capacity;
+            if car.refueling {
+                car.fuel = (car.fuel + service.refuel_rate * delta_time).min(car.fuel_capacity);
+            }
+            car.repairing = car.damage.is_damaged();
+            if car.repairing {
+                car.damage.repair(service.repair_rate * delta_time);
+            }
+
+            let worn = tire_wear.get(&entity).copied().unwrap_or(0.0) >= TIRE_CHANGE_WEAR;
+            car.changing_tires = worn && !stop.tires_changed;
+            if car.changing_tires && stop.elapsed >= service.tire_change_time {
+                stop.tires_changed = true;
+                car.changing_tires = false;
+                tire_changes.push(entity);
+            }
+        }
+
+        // Новые колеса: сбрасываем износ на всех колесах машины
+        for car_entity in tire_changes {
+            let wheels: Vec<Entity> = world
+                .query::<&CarWheelBindingComponent>()
+                .iter()
+                .filter(|(_, binding)| binding.car_entity == car_entity)
+                .flat_map(|(_, binding)| binding.wheel_entities.clone())
+                .collect();
+            for wheel_entity in wheels {
+                if let Ok(wheel) = world.query_one_mut::<&mut WheelComponent>(wheel_entity) {
+                    wheel.tire_wear = 0.0;
+                }
+            }
+        }
+        self.stops.retain(|entity, _| world.contains(*entity));
+    }
+}
@@ -0,0 +1,244 @@
+use crate::core::ecs::{EventQueue, Resource, System};
+use crate::core::physics::{
+    physics_materials, CollisionEvent, CollisionPhase, ColliderComponent, ColliderShapeType, JointComponent, PhysicsMaterialId,
+    RigidBodyComponent, RigidBodyType, TransformComponent,
+};
+use crate::core::renderer::{RenderComponent, RenderResourceManager};
+use crate::game_world::car::CarComponent;
+use crate::game_world::environment::WindAffectedComponent;
+use crate::game_world::track::{create_static_obstacle, publish_frame_events, ObstacleComponent, ObstacleType};
+use glam::{Quat, Vec3};
+use hecs::{Entity, World};
+use rapier3d::prelude::{ColliderBuilder, ColliderSet, RigidBodyBuilder, RigidBodySet};
+
+/// Сдвиг от места установки, после которого предмет считается сбитым, м
+const KNOCKED_DISTANCE: f32 = 0.3;
+
+/// Наклон, после которого предмет считается опрокинутым, рад
+const KNOCKED_TILT: f32 = 0.5;
+
+/// Нагрузка на петлю знака, после которой знак отрывается от стойки, Н
+const SIGN_HINGE_BREAK_FORCE: f32 = 4000.0;
+
+/// Масса сбиваемого предмета, кг; у шин - целой стопки
+fn prop_mass(obstacle_type: ObstacleType) -> f32 {
+    match obstacle_type {
+        ObstacleType::Cone => 4.0,
+        ObstacleType::Sign => 12.0,
+        ObstacleType::Tire => 60.0,
+        _ => 100.0,
+    }
+}
+
+/// Коэффициент сопротивления ветру; шины ветер не сдвигает
+fn wind_drag(obstacle_type: ObstacleType) -> Option<f32> {
+    match obstacle_type {
+        ObstacleType::Cone => Some(0.8),
+        ObstacleType::Sign => Some(1.2),
+        _ => None,
+    }
+}
+
+/// Цвет сбиваемого предмета: оранжевые конусы, белые знаки, черные шины
+fn prop_color(obstacle_type: ObstacleType) -> [f32; 4] {
+    match obstacle_type {
+        ObstacleType::Cone => [1.0, 0.4, 0.05, 1.0],
+        ObstacleType::Sign => [0.9, 0.9, 0.85, 1.0],
+        ObstacleType::Tire => [0.06, 0.06, 0.06, 1.0],
+        _ => [0.5, 0.5, 0.5, 1.0],
+    }
+}
+
+/// Сбиваемый предмет трассы: место установки и сбит ли он уже
+#[derive(Debug, Clone)]
+pub struct KnockablePropComponent {
+    pub home: Vec3, // Место установки; в файл трассы сохраняется оно, а не место, куда предмет отлетел
+    pub knocked: bool,
+    pub knocked_by: Option<Entity>, // Машина, сбившая предмет
+}
+
+/// Предмет сбит; очередь `EventQueue<PropKnockedEvent>` обновляется каждый кадр
+#[derive(Debug, Clone)]
+pub struct PropKnockedEvent {
+    pub obstacle_type: ObstacleType,
+    pub car_entity: Option<Entity>, // None - сдвинут не машиной: другим предметом или ветром
+}
+
+/// Предмет трассы: конусы, знаки и шины - подвижные тела, остальное неподвижно
+pub fn create_track_prop(world: &mut World, obstacle_type: ObstacleType, position: Vec3, half_extents: Vec3) -> Entity {
+    if obstacle_type.knockable() {
+        create_knockable_obstacle(world, obstacle_type, position, half_extents)
+    } else {
+        create_static_obstacle(world, obstacle_type, position, half_extents)
+    }
+}
+
+/// Создает сбиваемое препятствие-параллелепипед: подвижное тело с массой по виду предмета
+pub fn create_knockable_obstacle(world: &mut World, obstacle_type: ObstacleType, position: Vec3, half_extents: Vec3) -> Entity {
+    let volume = 8.0 * half_extents.x * half_extents.y * half_extents.z;
+    let materials = physics_materials(world);
+    let physics = world
+        .query_mut::<&mut Resource<(RigidBodySet, ColliderSet)>>()
+        .into_iter()
+        .next()
+        .map(|(_, resource)| {
+            let (rigid_body_set, collider_set) = &mut resource.0;
+            let body = RigidBodyBuilder::dynamic()
+                .translation([position.x, position.y, position.z].into())
+                .build();
+            let collider = materials
+                .apply(PhysicsMaterialId::BARRIER, ColliderBuilder::cuboid(half_extents.x, half_extents.y, half_extents.z))
+                .density(prop_mass(obstacle_type) / volume.max(f32::EPSILON))
+                .build();
+            let body_handle = rigid_body_set.insert(body);
+            let collider_handle = collider_set.insert_with_parent(collider, body_handle, rigid_body_set);
+            (body_handle, collider_handle)
+        });
+
+    let obstacle = ObstacleComponent {
+        obstacle_type,
+        destructible: false,
+        health: 100.0,
+    };
+    let transform = TransformComponent {
+        position,
+        rotation: Quat::IDENTITY,
+        scale: half_extents * 2.0,
+    };
+    let knockable = KnockablePropComponent {
+        home: position,
+        knocked: false,
+        knocked_by: None,
+    };
+    let entity = world.spawn((obstacle, transform, knockable));
+    if let Some(drag_coefficient) = wind_drag(obstacle_type) {
+        let wind = WindAffectedComponent {
+            area: 4.0 * half_extents.x.max(half_extents.z) * half_extents.y,
+            drag_coefficient,
+        };
+        let _ = world.insert_one(entity, wind);
+    }
+    match physics {
+        Some((body_handle, collider_handle)) => {
+            let rigid_body = RigidBodyComponent {
+                handle: body_handle,
+                body_type: RigidBodyType::Dynamic,
+            };
+            let collider = ColliderComponent {
+                handle: collider_handle,
+                shape_type: ColliderShapeType::Box,
+            };
+            let _ = world.insert(entity, (rigid_body, collider));
+            if obstacle_type == ObstacleType::Sign {
+                create_sign_hinge(world, entity, position, half_extents);
+            }
+        }
+        None => eprintln!("Препятствие без коллайдера: Physics world resource is missing"),
+    }
+    entity
+}
+
+/// Знак качается на петле у нижней кромки: неподвижная стойка без коллайдера и шарнир
+/// поперек знака; от сильного удара петля отрывается и знак отлетает
+fn create_sign_hinge(world: &mut World, sign: Entity, position: Vec3, half_extents: Vec3) {
+    let base = position - Vec3::Y * half_extents.y;
+    let Some((_, resource)) = world.query_mut::<&mut Resource<(RigidBodySet, ColliderSet)>>().into_iter().next() else {
+        return;
+    };
+    let body_handle = resource.0 .0.insert(RigidBodyBuilder::fixed().translation([base.x, base.y, base.z].into()).build());
+    let post = world.spawn((
+        TransformComponent {
+            position: base,
+            ..Default::default()
+        },
+        RigidBodyComponent {
+            handle: body_handle,
+            body_type: RigidBodyType::Static,
+        },
+    ));
+    let hinge = JointComponent::revolute(post, sign, Vec3::X, Vec3::ZERO, Vec3::new(0.0, -half_extents.y, 0.0))
+        .with_limits(-std::f32::consts::FRAC_PI_2, std::f32::consts::FRAC_PI_2)
+        .breakable(SIGN_HINGE_BREAK_FORCE);
+    let _ = world.insert_one(post, hinge);
+}
+
+/// Кубы по размеру коллайдера для сбиваемых предметов, которые еще не рисуются
+pub fn prop_renders(world: &World, resources: &mut RenderResourceManager) -> Vec<(Entity, RenderComponent)> {
+    let props: Vec<(Entity, ObstacleType)> = world
+        .query::<(&KnockablePropComponent, &ObstacleComponent)>()
+        .without::<&RenderComponent>()
+        .iter()
+        .map(|(entity, (_, obstacle))| (entity, obstacle.obstacle_type))
+        .collect();
+    if props.is_empty() {
+        return Vec::new();
+    }
+    let mesh_id = resources.add_simple_cube();
+    let mut materials: Vec<(ObstacleType, usize)> = Vec::new();
+    props
+        .into_iter()
+        .map(|(entity, obstacle_type)| {
+            let material_id = match materials.iter().find(|(kind, _)| *kind == obstacle_type) {
+                Some(&(_, material_id)) => material_id,
+                None => {
+                    let material_id = resources.add_basic_material(prop_color(obstacle_type));
+                    materials.push((obstacle_type, material_id));
+                    material_id
+                }
+            };
+            // Размер куба задает масштаб трансформа предмета
+            let render = RenderComponent {
+                mesh_id,
+                material_id,
+                visible: true,
+                scale: Vec3::ONE,
+                opacity: 1.0,
+            };
+            (entity, render)
+        })
+        .collect()
+}
+
+/// Сбитые предметы: задетый машиной, сдвинутый или опрокинутый предмет считается сбитым один раз
+pub struct KnockablePropSystem;
+
+impl System for KnockablePropSystem {
+    fn update(&mut self, world: &mut World, _delta_time: f32) {
+        let contacts: Vec<(Entity, Entity)> = world
+            .query::<&Resource<EventQueue<CollisionEvent>>>()
+            .iter()
+            .next()
+            .map(|(_, queue)| {
+                queue
+                    .0
+                    .iter()
+                    .filter(|event| event.phase == CollisionPhase::Started)
+                    .flat_map(|event| [(event.entity1, event.entity2), (event.entity2, event.entity1)])
+                    .filter(|&(_, other)| world.get::<&CarComponent>(other).is_ok())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let mut events = Vec::new();
+        for (entity, (prop, obstacle, transform)) in
+            world.query_mut::<(&mut KnockablePropComponent, &ObstacleComponent, &TransformComponent)>()
+        {
+            if prop.knocked {
+                continue;
+            }
+            let car_entity = contacts.iter().find(|(hit, _)| *hit == entity).map(|&(_, car)| car);
+            let displaced = transform.position.distance(prop.home) > KNOCKED_DISTANCE
+                || (transform.rotation * Vec3::Y).angle_between(Vec3::Y) > KNOCKED_TILT;
+            if car_entity.is_none() && !displaced {
+                continue;
+            }
+            prop.knocked = true;
+            prop.knocked_by = car_entity;
+            events.push(PropKnockedEvent {
+                obstacle_type: obstacle.obstacle_type,
+                car_entity,
+            });
+        }
+        publish_frame_events(world, events);
+    }
+}
@@ -0,0 +1,554 @@
+use crate::core::ecs::Resource;
+use crate::core::physics::{physics_materials, ColliderComponent, ColliderShapeType, PhysicsMaterialId, RigidBodyComponent, RigidBodyType, TransformComponent};
+use crate::core::renderer::{MeshData, RenderComponent, RenderResourceManager, TerrainSplatData, Vertex};
+use glam::{Quat, Vec3};
+use hecs::World;
+use rapier3d::na::{DMatrix, Vector3};
+use rapier3d::prelude::{ColliderBuilder, ColliderHandle, ColliderSet, RigidBodyBuilder, RigidBodyHandle, RigidBodySet};
+use std::path::Path;
+
+/// Слои материала ландшафта
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TerrainLayer {
+    Grass,
+    Dirt,
+    Asphalt,
+}
+
+impl TerrainLayer {
+    pub const COUNT: usize = 3;
+
+    pub fn index(&self) -> usize {
+        match self {
+            TerrainLayer::Grass => 0,
+            TerrainLayer::Dirt => 1,
+            TerrainLayer::Asphalt => 2,
+        }
+    }
+}
+
+/// Одноканальная карта значений 0..1 (карта высот или маска слоя)
+#[derive(Debug, Clone)]
+pub struct Heightmap {
+    pub width: usize,
+    pub depth: usize,
+    pub values: Vec<f32>,
+}
+
+impl Heightmap {
+    pub fn new(width: usize, depth: usize, values: Vec<f32>) -> Result<Self, String> {
+        if width < 2 || depth < 2 {
+            return Err(format!("Heightmap must be at least 2x2, got {}x{}", width, depth));
+        }
+        if values.len() != width * depth {
+            return Err(format!(
+                "Heightmap size mismatch: expected {} values, got {}",
+                width * depth,
+                values.len()
+            ));
+        }
+        Ok(Self { width, depth, values })
+    }
+
+    /// Создает карту из функции f(x, z) -> 0..1
+    pub fn from_fn(width: usize, depth: usize, f: impl Fn(usize, usize) -> f32) -> Result<Self, String> {
+        let mut values = Vec::with_capacity(width * depth);
+        for z in 0..depth {
+            for x in 0..width {
+                values.push(f(x, z).clamp(0.0, 1.0));
+            }
+        }
+        Self::new(width, depth, values)
+    }
+
+    /// Загрузка карты из изображения в градациях серого (PGM, P2/P5, 8 или 16 бит)
+    pub fn load_pgm(path: &Path) -> Result<Self, String> {
+        let bytes = std::fs::read(path).map_err(|e| e.to_string())?;
+        Self::parse_pgm(&bytes)
+    }
+
+    pub fn parse_pgm(bytes: &[u8]) -> Result<Self, String> {
+        let mut pos = 0;
+        let magic = next_pgm_token(bytes, &mut pos)?;
+        let binary = match magic.as_str() {
+            "P5" => true,
+            "P2" => false,
+            other => return Err(format!("Unsupported PGM format: {}", other)),
+        };
+
+        let parse_number = |token: String| token.parse::<usize>().map_err(|e| e.to_string());
+        let width = parse_number(next_pgm_token(bytes, &mut pos)?)?;
+        let depth = parse_number(next_pgm_token(bytes, &mut pos)?)?;
+        let max_value = parse_number(next_pgm_token(bytes, &mut pos)?)?;
+        if max_value == 0 || max_value > 65535 {
+            return Err(format!("Invalid PGM max value: {}", max_value));
+        }
+
+        let count = width * depth;
+        let mut values = Vec::with_capacity(count);
+
+        if binary {
+            // После заголовка идет ровно один пробельный символ
+            pos += 1;
+            let sample_size = if max_value > 255 { 2 } else { 1 };
+            let data = bytes
+                .get(pos..pos + count * sample_size)
+                .ok_or_else(|| "PGM data is truncated".to_string())?;
+
+            for sample in data.chunks_exact(sample_size) {
+                let raw = if sample_size == 2 {
+                    u16::from_be_bytes([sample[0], sample[1]]) as f32
+                } else {
+                    sample[0] as f32
+                };
+                values.push(raw / max_value as f32);
+            }
+        } else {
+            for _ in 0..count {
+                let raw = parse_number(next_pgm_token(bytes, &mut pos)?)?;
+                values.push(raw as f32 / max_value as f32);
+            }
+        }
+
+        Self::new(width, depth, values)
+    }
+
+    pub fn get(&self, x: usize, z: usize) -> f32 {
+        let x = x.min(self.width - 1);
+        let z = z.min(self.depth - 1);
+        self.values[z * self.width + x]
+    }
+
+    /// Билинейная выборка по нормализованным координатам 0..1
+    pub fn sample(&self, u: f32, v: f32) -> f32 {
+        let fx = u.clamp(0.0, 1.0) * (self.width - 1) as f32;
+        let fz = v.clamp(0.0, 1.0) * (self.depth - 1) as f32;
+        let x0 = fx.floor() as usize;
+        let z0 = fz.floor() as usize;
+        let tx = fx - x0 as f32;
+        let tz = fz - z0 as f32;
+
+        let top = self.get(x0, z0) * (1.0 - tx) + self.get(x0 + 1, z0) * tx;
+        let bottom = self.get(x0, z0 + 1) * (1.0 - tx) + self.get(x0 + 1, z0 + 1) * tx;
+        top * (1.0 - tz) + bottom * tz
+    }
+}
+
+/// Чтение следующего токена заголовка PGM с пропуском комментариев
+fn next_pgm_token(bytes: &[u8], pos: &mut usize) -> Result<String, String> {
+    loop {
+        while *pos < bytes.len() && bytes[*pos].is_ascii_whitespace() {
+            *pos += 1;
+        }
+        if *pos < bytes.len() && bytes[*pos] == b'#' {
+            while *pos < bytes.len() && bytes[*pos] != b'\n' {
+                *pos += 1;
+            }
+            continue;
+        }
+        break;
+    }
+
+    let start = *pos;
+    while *pos < bytes.len() && !bytes[*pos].is_ascii_whitespace() {
+        *pos += 1;
+    }
+
+    if start == *pos {
+        return Err("Unexpected end of PGM header".to_string());
+    }
+
+    Ok(String::from_utf8_lossy(&bytes[start..*pos]).into_owned())
+}
+
+/// Маски слоев материала; отсутствующие маски вычисляются по уклону
+#[derive(Default)]
+pub struct TerrainLayerMasks {
+    pub grass: Option<Heightmap>,
+    pub dirt: Option<Heightmap>,
+    pub asphalt: Option<Heightmap>,
+}
+
+/// Карта высот холмов вокруг трассы
+pub const TERRAIN_HEIGHTMAP_PATH: &str = "assets/terrain/hills.pgm";
+
+/// Сторона детальной текстуры слоя после пересэмплирования, пикселей
+const DETAIL_SIZE: usize = 128;
+
+/// Параметры построения ландшафта
+#[derive(Debug, Clone)]
+pub struct TerrainConfig {
+    pub origin: Vec3,          // Угол ландшафта с минимальными X/Z
+    pub size_x: f32,           // Размер по X в метрах
+    pub size_z: f32,           // Размер по Z в метрах
+    pub height_scale: f32,     // Высота при значении карты 1.0
+    pub chunk_cells: usize,    // Количество ячеек сетки в стороне чанка
+    pub dirt_slope: f32,       // Уклон (1 - normal.y), с которого трава сменяется грунтом
+    pub layer_colors: [[f32; 4]; TerrainLayer::COUNT],
+    pub layer_details: [&'static str; TerrainLayer::COUNT], // Детальные текстуры слоев (PGM), тайлятся по миру
+}
+
+impl Default for TerrainConfig {
+    fn default() -> Self {
+        Self {
+            origin: Vec3::new(-256.0, -0.5, -256.0),
+            size_x: 512.0,
+            size_z: 512.0,
+            height_scale: 40.0,
+            chunk_cells: 32,
+            dirt_slope: 0.25,
+            layer_colors: [
+                [0.25, 0.45, 0.18, 1.0], // Трава
+                [0.45, 0.35, 0.22, 1.0], // Грунт
+                [0.3, 0.3, 0.3, 1.0],    // Асфальт
+            ],
+            layer_details: [
+                "assets/terrain/grass_detail.pgm",
+                "assets/terrain/dirt_detail.pgm",
+                "assets/terrain/asphalt_detail.pgm",
+            ],
+        }
+    }
+}
+
+/// Компонент ландшафта
+pub struct TerrainComponent {
+    pub heightmap: Heightmap,
+    pub config: TerrainConfig,
+}
+
+impl TerrainComponent {
+    /// Высота ландшафта в мировых координатах (None за пределами ландшафта)
+    pub fn height_at(&self, x: f32, z: f32) -> Option<f32> {
+        let u = (x - self.config.origin.x) / self.config.size_x;
+        let v = (z - self.config.origin.z) / self.config.size_z;
+        if !(0.0..=1.0).contains(&u) || !(0.0..=1.0).contains(&v) {
+            return None;
+        }
+        Some(self.config.origin.y + self.heightmap.sample(u, v) * self.config.height_scale)
+    }
+
+    /// Нормаль поверхности в мировых координатах
+    pub fn normal_at(&self, x: f32, z: f32) -> Vec3 {
+        let step_x = self.config.size_x / (self.heightmap.width - 1) as f32;
+        let step_z = self.config.size_z / (self.heightmap.depth - 1) as f32;
+        let h = |x: f32, z: f32| self.height_at(x, z).unwrap_or(self.config.origin.y);
+
+        let dx = h(x + step_x, z) - h(x - step_x, z);
+        let dz = h(x, z + step_z) - h(x, z - step_z);
+        Vec3::new(-dx / (2.0 * step_x), 1.0, -dz / (2.0 * step_z)).normalize()
+    }
+
+    /// Преобладающий слой в точке (по уклону, как при построении без масок)
+    pub fn layer_at(&self, x: f32, z: f32) -> Option<TerrainLayer> {
+        self.height_at(x, z)?;
+        let slope = 1.0 - self.normal_at(x, z).y;
+        if slope >= self.config.dirt_slope * 0.5 {
+            Some(TerrainLayer::Dirt)
+        } else {
+            Some(TerrainLayer::Grass)
+        }
+    }
+}
+
+/// Чанк ландшафта; веса слоев общие для всего ландшафта и лежат в карте смешивания
+pub struct TerrainChunkComponent;
+
+/// Строит ландшафт: меши чанков, карту смешивания слоев и сущности для рендеринга
+pub fn create_terrain(
+    world: &mut World,
+    render_resources: &mut RenderResourceManager,
+    heightmap: Heightmap,
+    masks: &TerrainLayerMasks,
+    config: TerrainConfig,
+) -> Result<hecs::Entity, String> {
+    // Индексы u16: чанк не должен превышать 255x255 ячеек
+    if config.chunk_cells == 0 || config.chunk_cells > 255 {
+        return Err(format!("Invalid terrain chunk size: {}", config.chunk_cells));
+    }
+
+    let material_id = render_resources.add_terrain_material(config.layer_colors);
+    let details = config.layer_details.map(|path| {
+        Heightmap::load_pgm(Path::new(path)).unwrap_or_else(|e| {
+            eprintln!("Не удалось загрузить детальную текстуру слоя {}: {}", path, e);
+            Heightmap { width: 2, depth: 2, values: vec![1.0; 4] }
+        })
+    });
+
+    let terrain = TerrainComponent { heightmap, config };
+
+    let cells_x = terrain.heightmap.width - 1;
+    let cells_z = terrain.heightmap.depth - 1;
+    let chunk_cells = terrain.config.chunk_cells;
+    let chunks_x = cells_x.div_ceil(chunk_cells);
+    let chunks_z = cells_z.div_ceil(chunk_cells);
+
+    let mut chunk_data = Vec::with_capacity(chunks_x * chunks_z);
+    for chunk_z in 0..chunks_z {
+        for chunk_x in 0..chunks_x {
+            let x0 = chunk_x * chunk_cells;
+            let z0 = chunk_z * chunk_cells;
+            let x1 = (x0 + chunk_cells).min(cells_x);
+            let z1 = (z0 + chunk_cells).min(cells_z);
+            let mesh = build_chunk_mesh(&terrain, x0, z0, x1, z1);
+            chunk_data.push(render_resources.add_mesh_data(mesh));
+        }
+    }
+    render_resources.terrain_splat = Some(build_splat_data(&terrain, masks, &details));
+
+    let terrain_entity = world.spawn((TransformComponent::default(),));
+
+    for mesh_id in chunk_data {
+        let render = RenderComponent {
+            mesh_id,
+            material_id,
+            visible: true,
+            scale: Vec3::ONE,
+            opacity: 1.0,
+        };
+        let transform = TransformComponent {
+            position: Vec3::ZERO,
+            rotation: Quat::IDENTITY,
+            ..Default::default()
+        };
+        world.spawn((TerrainChunkComponent, transform, render));
+    }
+
+    // Без физического мира ландшафт остается только декорацией
+    match insert_heightfield_collider(world, &terrain.heightmap, &terrain.config) {
+        Ok((body_handle, collider_handle)) => {
+            let rigid_body = RigidBodyComponent {
+                handle: body_handle,
+                body_type: RigidBodyType::Static,
+            };
+            let collider = ColliderComponent {
+                handle: collider_handle,
+                shape_type: ColliderShapeType::Heightfield,
+            };
+            world.insert(terrain_entity, (rigid_body, collider)).map_err(|e| e.to_string())?;
+        }
+        Err(e) => eprintln!("Ландшафт без коллайдера: {}", e),
+    }
+
+    world.insert_one(terrain_entity, terrain).map_err(|e| e.to_string())?;
+
+    Ok(terrain_entity)
+}
+
+/// Добавляет неподвижный коллайдер-карту высот в ресурс физического мира;
+/// высоты совпадают с `TerrainComponent::height_at`
+pub fn insert_heightfield_collider(
+    world: &mut World,
+    heightmap: &Heightmap,
+    config: &TerrainConfig,
+) -> Result<(RigidBodyHandle, ColliderHandle), String> {
+    // Материал ландшафта задает трение для кузова; колеса берут сцепление из слоя поверхности
+    let materials = physics_materials(world);
+    let resource_query = world.query_mut::<&mut Resource<(RigidBodySet, ColliderSet)>>();
+    let (_, resource) = resource_query
+        .into_iter()
+        .next()
+        .ok_or_else(|| "Physics world resource is missing".to_string())?;
+    let (rigid_body_set, collider_set) = &mut resource.0;
+
+    // Строки карты высот в rapier идут вдоль Z, столбцы - вдоль X
+    let heights = DMatrix::from_fn(heightmap.depth, heightmap.width, |z, x| heightmap.get(x, z));
+    let scale = Vector3::new(config.size_x, config.height_scale, config.size_z);
+
+    // Коллайдер центрирован по X/Z, поэтому тело ставится в середину ландшафта
+    let center = config.origin + Vec3::new(config.size_x / 2.0, 0.0, config.size_z / 2.0);
+    let body = RigidBodyBuilder::fixed()
+        .translation(Vector3::new(center.x, center.y, center.z))
+        .build();
+    let collider = materials
+        .apply(PhysicsMaterialId::TERRAIN, ColliderBuilder::heightfield(heights, scale))
+        .build();
+
+    let body_handle = rigid_body_set.insert(body);
+    let collider_handle = collider_set.insert_with_parent(collider, body_handle, rigid_body_set);
+    Ok((body_handle, collider_handle))
+}
+
+/// Нормаль узла сетки по центральным разностям карты высот
+fn grid_normal(terrain: &TerrainComponent, x: usize, z: usize) -> Vec3 {
+    let config = &terrain.config;
+    let map = &terrain.heightmap;
+    let step_x = config.size_x / (map.width - 1) as f32;
+    let step_z = config.size_z / (map.depth - 1) as f32;
+
+    let left = map.get(x.saturating_sub(1), z);
+    let right = map.get(x + 1, z);
+    let down = map.get(x, z.saturating_sub(1));
+    let up = map.get(x, z + 1);
+    Vec3::new(
+        (left - right) * config.height_scale / (2.0 * step_x),
+        1.0,
+        (down - up) * config.height_scale / (2.0 * step_z),
+    )
+    .normalize()
+}
+
+/// Построение меша одного чанка в мировых координатах
+fn build_chunk_mesh(terrain: &TerrainComponent, x0: usize, z0: usize, x1: usize, z1: usize) -> MeshData {
+    let config = &terrain.config;
+    let map = &terrain.heightmap;
+    let step_x = config.size_x / (map.width - 1) as f32;
+    let step_z = config.size_z / (map.depth - 1) as f32;
+
+    let mut vertices = Vec::new();
+
+    for z in z0..=z1 {
+        for x in x0..=x1 {
+            let u = x as f32 / (map.width - 1) as f32;
+            let v = z as f32 / (map.depth - 1) as f32;
+            let position = Vec3::new(
+                config.origin.x + x as f32 * step_x,
+                config.origin.y + map.get(x, z) * config.height_scale,
+                config.origin.z + z as f32 * step_z,
+            );
+
+            vertices.push(Vertex {
+                position: position.to_array(),
+                tex_coords: [u, v],
+                normal: grid_normal(terrain, x, z).to_array(),
+            });
+        }
+    }
+
+    let row = (x1 - x0 + 1) as u16;
+    let mut indices = Vec::new();
+    for z in 0..(z1 - z0) as u16 {
+        for x in 0..(x1 - x0) as u16 {
+            let i = z * row + x;
+            indices.extend_from_slice(&[i, i + row, i + 1, i + 1, i + row, i + row + 1]);
+        }
+    }
+
+    MeshData {
+        vertices,
+        indices: Some(indices),
+    }
+}
+
+/// Карта смешивания на узлах сетки ландшафта и детальные текстуры слоев для рендерера
+fn build_splat_data(
+    terrain: &TerrainComponent,
+    masks: &TerrainLayerMasks,
+    details: &[Heightmap; TerrainLayer::COUNT],
+) -> TerrainSplatData {
+    let map = &terrain.heightmap;
+    let mut weights = Vec::with_capacity(map.width * map.depth);
+    for z in 0..map.depth {
+        for x in 0..map.width {
+            let u = x as f32 / (map.width - 1) as f32;
+            let v = z as f32 / (map.depth - 1) as f32;
+            let layer = layer_weights(masks, u, v, grid_normal(terrain, x, z), terrain.config.dirt_slope);
+            weights.push([
+                (layer[0] * 255.0).round() as u8,
+                (layer[1] * 255.0).round() as u8,
+                (layer[2] * 255.0).round() as u8,
+                0,
+            ]);
+        }
+    }
+
+    // Детальные текстуры приводятся к одному размеру, чтобы лечь в массив текстур
+    let mut detail_pixels = Vec::with_capacity(DETAIL_SIZE * DETAIL_SIZE * TerrainLayer::COUNT);
+    for detail in details {
+        for z in 0..DETAIL_SIZE {
+            for x in 0..DETAIL_SIZE {
+                let (u, v) = (x as f32 / (DETAIL_SIZE - 1) as f32, z as f32 / (DETAIL_SIZE - 1) as f32);
+                detail_pixels.push((detail.sample(u, v) * 255.0).round() as u8);
+            }
+        }
+    }
+
+    TerrainSplatData {
+        width: map.width as u32,
+        depth: map.depth as u32,
+        weights,
+        detail_size: DETAIL_SIZE as u32,
+        details: detail_pixels,
+    }
+}
+
+/// Нормализованные веса слоев для вершины
+fn layer_weights(
+    masks: &TerrainLayerMasks,
+    u: f32,
+    v: f32,
+    normal: Vec3,
+    dirt_slope: f32,
+) -> [f32; TerrainLayer::COUNT] {
+    let slope = 1.0 - normal.y;
+    let slope_dirt = (slope / dirt_slope.max(f32::EPSILON)).clamp(0.0, 1.0);
+
+    let asphalt = masks.asphalt.as_ref().map_or(0.0, |m| m.sample(u, v));
+    let dirt = masks.dirt.as_ref().map_or(slope_dirt, |m| m.sample(u, v));
+    let grass = masks.grass.as_ref().map_or(1.0 - dirt, |m| m.sample(u, v));
+
+    let mut weights = [0.0; TerrainLayer::COUNT];
+    weights[TerrainLayer::Grass.index()] = grass * (1.0 - asphalt);
+    weights[TerrainLayer::Dirt.index()] = dirt * (1.0 - asphalt);
+    weights[TerrainLayer::Asphalt.index()] = asphalt;
+
+    let sum: f32 = weights.iter().sum();
+    if sum > f32::EPSILON {
+        for w in &mut weights {
+            *w /= sum;
+        }
+    } else {
+        weights[TerrainLayer::Grass.index()] = 1.0;
+    }
+
+    weights
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_ascii_pgm_with_comments() {
+        let map = Heightmap::parse_pgm(b"P2\n# holmy\n2 2\n255\n0 255\n51 102\n").unwrap();
+        assert_eq!((map.width, map.depth), (2, 2));
+        assert_eq!(map.values, vec![0.0, 1.0, 0.2, 0.4]);
+    }
+
+    #[test]
+    fn parses_binary_pgm_8_and_16_bit() {
+        let map = Heightmap::parse_pgm(b"P5 2 2 255\n\x00\xff\x00\xff").unwrap();
+        assert_eq!(map.values, vec![0.0, 1.0, 0.0, 1.0]);
+
+        let map = Heightmap::parse_pgm(b"P5 2 2 65535\n\x00\x00\xff\xff\x80\x00\x00\x00").unwrap();
+        assert_eq!(map.get(1, 0), 1.0);
+        assert!((map.get(0, 1) - 0x8000 as f32 / 65535.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn rejects_bad_pgm() {
+        assert!(Heightmap::parse_pgm(b"P6 2 2 255\n").is_err());
+        assert!(Heightmap::parse_pgm(b"P5 2 2 0\n\x00\x00\x00\x00").is_err());
+        assert!(Heightmap::parse_pgm(b"P5 2 2 255\n\x00\x00").is_err());
+        assert!(Heightmap::parse_pgm(b"P2 1 1 255\n0").is_err());
+    }
+
+    #[test]
+    fn from_fn_checks_size_and_clamps() {
+        assert!(Heightmap::from_fn(1, 4, |_, _| 0.0).is_err());
+        let map = Heightmap::from_fn(3, 2, |x, _| x as f32 - 0.5).unwrap();
+        assert_eq!(map.values, vec![0.0, 0.5, 1.0, 0.0, 0.5, 1.0]);
+        assert!(Heightmap::new(2, 2, vec![0.0; 3]).is_err());
+    }
+
+    #[test]
+    fn sample_interpolates_bilinearly() {
+        let map = Heightmap::new(2, 2, vec![0.0, 1.0, 1.0, 0.0]).unwrap();
+        assert_eq!(map.sample(0.0, 0.0), 0.0);
+        assert_eq!(map.sample(1.0, 0.0), 1.0);
+        assert_eq!(map.sample(0.5, 0.5), 0.5);
+        assert_eq!(map.sample(0.5, 0.0), 0.5);
+        // Координаты за пределами карты прижимаются к краю
+        assert_eq!(map.sample(2.0, -1.0), 1.0);
+    }
+}
@@ -1,7 +1,19 @@
-use crate::core::ecs::{System};
-use crate::core::physics::{RigidBodyComponent, ColliderComponent, RigidBodyType, TransformComponent};
+use crate::core::ecs::{EventQueue, System, Resource};
+use crate::core::physics::{physics_materials, RigidBodyComponent, ColliderComponent, ColliderShapeType, PhysicsMaterialId, RigidBodyType, TransformComponent};
+use crate::core::renderer::{MeshData, Vertex};
 use glam::{Vec3, Quat};
+use crate::game_world::car::CarComponent;
+use crate::game_world::terrain::{TerrainComponent, TerrainLayer};
+use crate::gameplay::race::RaceState;
+use crate::game_world::track_file::TrackFile;
+use crate::game_world::track_limits::car_on_track;
+pub use crate::game_world::track_generator::{generate_random_track, TrackGenerationConfig};
+use crate::game_world::track_spline::{create_spline_segments, SplinePoint, TrackSpline, DEFAULT_GRID_SLOTS};
 use hecs::World;
+use serde::{Deserialize, Serialize};
+use rapier3d::na::{Point3, Vector3};
+use rapier3d::prelude::{RigidBodySet, ColliderSet, RigidBodyBuilder, ColliderBuilder, RigidBodyHandle, ColliderHandle};
+use std::path::Path;
 
 /// Компонент сегмента трассы
 pub struct TrackSegmentComponent {
@@ -9,9 +21,28 @@ pub struct TrackSegmentComponent {
     pub length: f32,
     pub width: f32,
     pub curvature: f32,
-    pub banking: f32,
+    pub banking: f32,        // Вираж в середине сегмента, рад; положительный поднимает край +X
+    pub banking_change: f32, // Изменение виража от начала к концу сегмента, рад
+    pub vertical_curvature: f32, // Изменение уклона вдоль сегмента, рад/м: плюс - впадина, минус - гребень
     pub surface_type: SurfaceType,
-    pub friction: f32,
+    pub material: PhysicsMaterialId, // Трение полотна, сцепление шин и сопротивление качению
+    pub surface_bands: Vec<SurfaceBand>, // Полосы покрытия поперек полотна; вне них действует surface_type
+    pub ramp_height: f32, // Высота кромки трамплина в конце сегмента, м; 0 - ровное полотно
+}
+
+/// Полоса покрытия поперек трассы: поребрик, гравийная ловушка, травяная обочина.
+/// Границы - смещения от осевой линии, м, положительные - к краю +X; полоса может выходить за полотно
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SurfaceBand {
+    pub from: f32,
+    pub to: f32,
+    pub surface: SurfaceType,
+}
+
+impl SurfaceBand {
+    pub fn contains(&self, lateral: f32) -> bool {
+        lateral >= self.from.min(self.to) && lateral <= self.from.max(self.to)
+    }
 }
 
 /// Типы сегментов трассы
@@ -25,6 +56,7 @@ pub enum TrackSegmentType {
 }
 
 /// Типы поверхностей
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum SurfaceType {
     Asphalt,
     Concrete,
@@ -34,6 +66,7 @@ pub enum SurfaceType {
     Snow,
     Ice,
     Sand,
+    Curb, // Поребрик на кромке поворота
 }
 
 impl Default for SurfaceType {
@@ -42,29 +75,383 @@ impl Default for SurfaceType {
     }
 }
 
-/// Коэффициенты трения для различных поверхностей
+impl TrackSegmentComponent {
+    /// Вираж в точке сегмента: полотно плавно закручивается, чтобы стыковаться с соседями без ступенек
+    pub fn banking_at(&self, local_z: f32) -> f32 {
+        self.banking + self.banking_change * (local_z / self.length.max(f32::EPSILON)).clamp(-0.5, 0.5)
+    }
+
+    /// Подъем осевой линии над серединой сегмента по вертикальному профилю, м
+    pub fn profile_height(&self, local_z: f32) -> f32 {
+        self.vertical_curvature * local_z * local_z / 2.0
+    }
+
+    /// Подъем полотна трамплина над осевой линией: от нуля в начале сегмента плавно круче к кромке в конце, м
+    pub fn ramp_lift(&self, local_z: f32) -> f32 {
+        let t = (local_z / self.length.max(f32::EPSILON) + 0.5).clamp(0.0, 1.0);
+        self.ramp_height * t * t
+    }
+
+    /// Угол подъема полотна трамплина в точке сегмента, рад; на кромке это угол отрыва
+    pub fn ramp_slope(&self, local_z: f32) -> f32 {
+        let t = (local_z / self.length.max(f32::EPSILON) + 0.5).clamp(0.0, 1.0);
+        (2.0 * self.ramp_height * t / self.length.max(f32::EPSILON)).atan()
+    }
+
+    /// Высота верха полотна над серединой сегмента без толщины дороги: профиль и трамплин, м
+    pub fn surface_height(&self, local_z: f32) -> f32 {
+        self.profile_height(local_z) + self.ramp_lift(local_z)
+    }
+
+    /// Точка осевой линии в локальных координатах сегмента: дуга по кривизне и подъем по профилю
+    pub fn centerline_at(&self, local_z: f32) -> Vec3 {
+        let heading = self.curvature * local_z;
+        let flat = if self.curvature.abs() > f32::EPSILON {
+            Vec3::new((1.0 - heading.cos()) / self.curvature, 0.0, heading.sin() / self.curvature)
+        } else {
+            Vec3::new(0.0, 0.0, local_z)
+        };
+        flat + Vec3::Y * self.profile_height(local_z)
+    }
+
+    /// Смещение точки от осевой линии поперек сегмента, м; точка в локальных координатах сегмента
+    pub fn lateral_offset(&self, local: Vec3) -> f32 {
+        local.x - self.centerline_at(local.z).x
+    }
+
+    /// Покрытие на смещении от осевой линии: полоса, если она там есть, иначе покрытие сегмента.
+    /// None - за кромкой полотна вне полос
+    pub fn surface_across(&self, lateral: f32) -> Option<(SurfaceType, PhysicsMaterialId)> {
+        if let Some(band) = self.surface_bands.iter().find(|band| band.contains(lateral)) {
+            return Some((band.surface, band.surface.material()));
+        }
+        (lateral.abs() <= self.width / 2.0).then_some((self.surface_type, self.material))
+    }
+
+    /// Наклон полотна в точке сегмента в его локальных координатах: уклон вдоль и вираж поперек
+    pub fn surface_rotation(&self, local_z: f32) -> Quat {
+        Quat::from_rotation_x(-self.vertical_curvature * local_z - self.ramp_slope(local_z)) * Quat::from_rotation_z(self.banking_at(local_z))
+    }
+}
+
+/// Встроенный физический материал покрытия: сцепление и сопротивление качению
 impl SurfaceType {
-    pub fn get_friction_coefficient(&self) -> f32 {
+    pub fn material(&self) -> PhysicsMaterialId {
         match self {
-            SurfaceType::Asphalt => 1.0,
-            SurfaceType::Concrete => 0.95,
-            SurfaceType::Dirt => 0.6,
-            SurfaceType::Gravel => 0.4,
-            SurfaceType::Grass => 0.3,
-            SurfaceType::Snow => 0.2,
-            SurfaceType::Ice => 0.1,
-            SurfaceType::Sand => 0.4,
+            SurfaceType::Asphalt => PhysicsMaterialId::ASPHALT,
+            SurfaceType::Concrete => PhysicsMaterialId::CONCRETE,
+            SurfaceType::Dirt => PhysicsMaterialId::DIRT,
+            SurfaceType::Gravel => PhysicsMaterialId::GRAVEL,
+            SurfaceType::Grass => PhysicsMaterialId::GRASS,
+            SurfaceType::Snow => PhysicsMaterialId::SNOW,
+            SurfaceType::Ice => PhysicsMaterialId::ICE,
+            SurfaceType::Sand => PhysicsMaterialId::SAND,
+            SurfaceType::Curb => PhysicsMaterialId::CURB,
+        }
+    }
+}
+
+impl SurfaceType {
+    /// Звук качения колеса по покрытию; на гладких покрытиях его нет
+    pub fn rolling_sound(&self) -> Option<&'static str> {
+        match self {
+            SurfaceType::Curb => Some("curb_rumble"),
+            SurfaceType::Gravel | SurfaceType::Sand => Some("gravel_roll"),
+            SurfaceType::Grass | SurfaceType::Dirt | SurfaceType::Snow => Some("grass_roll"),
+            SurfaceType::Asphalt | SurfaceType::Concrete | SurfaceType::Ice => None,
+        }
+    }
+}
+
+impl From<TerrainLayer> for SurfaceType {
+    fn from(layer: TerrainLayer) -> Self {
+        match layer {
+            TerrainLayer::Grass => SurfaceType::Grass,
+            TerrainLayer::Dirt => SurfaceType::Dirt,
+            TerrainLayer::Asphalt => SurfaceType::Asphalt,
+        }
+    }
+}
+
+/// Высота верха дорожного полотна над позицией сегмента
+pub const ROAD_SURFACE_HEIGHT: f32 = 0.05;
+
+/// Толщина дорожного полотна, м
+const ROAD_THICKNESS: f32 = 0.1;
+
+/// Шаг поперечных сечений меша сегмента вдоль трассы, м
+const MESH_ROW_SPACING: f32 = 5.0;
+
+/// Меш сегмента в его локальных координатах; по нему же построен коллайдер
+pub struct TrackMeshComponent {
+    pub mesh: MeshData,
+}
+
+/// Меш полотна сегмента: осевая линия изгибается по кривизне (положительная - к +X)
+/// и по вертикальному профилю, сечение наклоняется на угол виража, меняющийся вдоль сегмента.
+/// У трамплина верх поднимается к кромке, низ остается на месте, а кромка закрыта торцом
+pub fn build_segment_mesh(segment: &TrackSegmentComponent) -> MeshData {
+    let rows = ((segment.length / MESH_ROW_SPACING).ceil() as usize).max(1) + 1;
+    let half_width = segment.width / 2.0;
+    let top = ROAD_SURFACE_HEIGHT;
+    let bottom = ROAD_SURFACE_HEIGHT - ROAD_THICKNESS;
+
+    // Сечения: углы полотна, оси нормалей граней и нормаль верха
+    let sections: Vec<([Vec3; 4], Vec3, Vec3, Vec3)> = (0..rows)
+        .map(|row| {
+            let s = -segment.length / 2.0 + segment.length * row as f32 / (rows - 1) as f32;
+            let heading = segment.curvature * s;
+            let center = segment.centerline_at(s);
+            let flat_side = Vec3::new(heading.cos(), 0.0, -heading.sin());
+            let banking = segment.banking_at(s);
+            // Сечение поворачивается вокруг поперечной оси на местный уклон
+            let slope = Quat::from_axis_angle(flat_side, -segment.vertical_curvature * s);
+            let side = slope * (flat_side * banking.cos() + Vec3::Y * banking.sin());
+            let up = slope * (Vec3::Y * banking.cos() - flat_side * banking.sin());
+            let lift = up * segment.ramp_lift(s);
+            let top_normal = Quat::from_axis_angle(side, -segment.ramp_slope(s)) * up;
+            let corners = [
+                center + side * half_width + up * top + lift, // Верх слева (+X)
+                center - side * half_width + up * top + lift, // Верх справа
+                center + side * half_width + up * bottom,     // Низ слева
+                center - side * half_width + up * bottom,     // Низ справа
+            ];
+            (corners, side, up, top_normal)
+        })
+        .collect();
+
+    // Грани: пара ребер вдоль трассы и нормаль как (доля side, доля up);
+    // порядок ребер задает обход против часовой
+    let faces = [
+        (0, 1, 0.0, 1.0),  // Верх
+        (3, 2, 0.0, -1.0), // Низ
+        (2, 0, 1.0, 0.0),  // Левый борт
+        (1, 3, -1.0, 0.0), // Правый борт
+    ];
+
+    let mut vertices = Vec::with_capacity(rows * faces.len() * 2 + 4);
+    let mut indices = Vec::with_capacity((rows - 1) * faces.len() * 6 + 6);
+    for (face, (a, b, side_share, up_share)) in faces.into_iter().enumerate() {
+        let base = vertices.len() as u16;
+        for (row, (corners, side, up, top_normal)) in sections.iter().enumerate() {
+            let v = row as f32 / (rows - 1) as f32;
+            let normal = if face == 0 { *top_normal } else { *side * side_share + *up * up_share };
+            vertices.push(Vertex { position: corners[a].to_array(), tex_coords: [0.0, v], normal: normal.to_array() });
+            vertices.push(Vertex { position: corners[b].to_array(), tex_coords: [1.0, v], normal: normal.to_array() });
+        }
+        for row in 0..(rows - 1) as u16 {
+            let i = base + row * 2;
+            indices.extend_from_slice(&[i, i + 1, i + 2, i + 1, i + 3, i + 2]);
+        }
+    }
+
+    // Торец кромки трамплина смотрит вперед по ходу
+    if segment.ramp_height > 0.0 {
+        if let Some((corners, side, up, _)) = sections.last() {
+            let normal = side.cross(*up).normalize_or_zero().to_array();
+            let base = vertices.len() as u16;
+            for (corner, tex_coords) in [(3, [0.0, 1.0]), (2, [1.0, 1.0]), (0, [1.0, 0.0]), (1, [0.0, 0.0])] {
+                vertices.push(Vertex { position: corners[corner].to_array(), tex_coords, normal });
+            }
+            indices.extend_from_slice(&[base, base + 1, base + 2, base, base + 2, base + 3]);
         }
     }
+
+    MeshData {
+        vertices,
+        indices: Some(indices),
+    }
+}
+
+/// Создает сегмент трассы с мешем и совпадающим с ним коллайдером
+pub fn create_track_segment(world: &mut World, segment: TrackSegmentComponent, transform: TransformComponent) -> hecs::Entity {
+    let mesh = build_segment_mesh(&segment);
+    let physics = insert_static_trimesh(world, &mesh, transform.position, transform.rotation, segment.material);
+    let entity = world.spawn((segment, transform, TrackMeshComponent { mesh }));
+
+    match physics {
+        Ok((body_handle, collider_handle)) => {
+            let rigid_body = RigidBodyComponent {
+                handle: body_handle,
+                body_type: RigidBodyType::Static,
+            };
+            let collider = ColliderComponent {
+                handle: collider_handle,
+                shape_type: ColliderShapeType::Trimesh,
+            };
+            let _ = world.insert(entity, (rigid_body, collider));
+        }
+        Err(e) => eprintln!("Сегмент трассы без коллайдера: {}", e),
+    }
+    entity
+}
+
+/// Высота и нормаль земли под точкой: дорога, иначе ландшафт
+pub fn ground_at(world: &World, position: Vec3) -> Option<(f32, Vec3)> {
+    for (_, (segment, transform)) in world.query::<(&TrackSegmentComponent, &TransformComponent)>().iter() {
+        let local = transform.rotation.inverse() * (position - transform.position);
+        if local.x.abs() <= segment.width / 2.0 && local.z.abs() <= segment.length / 2.0 {
+            let normal = transform.rotation * segment.surface_rotation(local.z) * Vec3::Y;
+            // Высота на касательной плоскости полотна в точке (x, z)
+            let surface_point = transform.position
+                + transform.rotation * Vec3::new(0.0, segment.surface_height(local.z), local.z)
+                + normal * ROAD_SURFACE_HEIGHT;
+            let height = if normal.y.abs() > f32::EPSILON {
+                surface_point.y
+                    - (normal.x * (position.x - surface_point.x) + normal.z * (position.z - surface_point.z)) / normal.y
+            } else {
+                surface_point.y
+            };
+            return Some((height, normal));
+        }
+    }
+
+    world
+        .query::<&TerrainComponent>()
+        .iter()
+        .find_map(|(_, terrain)| {
+            terrain
+                .height_at(position.x, position.z)
+                .map(|height| (height, terrain.normal_at(position.x, position.z)))
+        })
+}
+
+/// Находится ли точка в границах одного из сегментов трассы
+pub fn on_track(world: &World, position: Vec3) -> bool {
+    world
+        .query::<(&TrackSegmentComponent, &TransformComponent)>()
+        .iter()
+        .any(|(_, (segment, transform))| {
+            let local = transform.rotation.inverse() * (position - transform.position);
+            local.x.abs() <= segment.width / 2.0 && local.z.abs() <= segment.length / 2.0
+        })
+}
+
+/// Расстояние по горизонтали от точки до края ближайшего сегмента (0 на полотне)
+/// и высота осевой линии дороги в ближайшем месте
+pub fn distance_to_track(world: &World, position: Vec3) -> Option<(f32, f32)> {
+    world
+        .query::<(&TrackSegmentComponent, &TransformComponent)>()
+        .iter()
+        .map(|(_, (segment, transform))| {
+            let flat = Vec3::new(position.x, transform.position.y, position.z);
+            let local = transform.rotation.inverse() * (flat - transform.position);
+            let outside_x = (local.x.abs() - segment.width / 2.0).max(0.0);
+            let outside_z = (local.z.abs() - segment.length / 2.0).max(0.0);
+            let along = local.z.clamp(-segment.length / 2.0, segment.length / 2.0);
+            let height = (transform.position + transform.rotation * Vec3::new(0.0, segment.surface_height(along), along)).y;
+            (outside_x.hypot(outside_z), height)
+        })
+        .min_by(|a, b| a.0.total_cmp(&b.0))
+}
+
+/// Пройденное вдоль трассы расстояние от начала первого сегмента до ближайшего к точке места, м
+pub fn track_progress(world: &World, position: Vec3) -> Option<f32> {
+    let segments = world.query::<&TrackComponent>().iter().next().map(|(_, track)| track.segments.clone())?;
+    let mut start = 0.0;
+    let mut nearest: Option<(f32, f32)> = None;
+    for entity in segments {
+        let (Ok(segment), Ok(transform)) = (world.get::<&TrackSegmentComponent>(entity), world.get::<&TransformComponent>(entity)) else {
+            continue;
+        };
+        let local = transform.rotation.inverse() * (position - transform.position);
+        let along = local.z.clamp(-segment.length / 2.0, segment.length / 2.0);
+        let clamped = Vec3::new(local.x.clamp(-segment.width / 2.0, segment.width / 2.0), local.y, along);
+        let distance = local.distance_squared(clamped);
+        if nearest.is_none_or(|(best, _)| distance < best) {
+            nearest = Some((distance, start + along + segment.length / 2.0));
+        }
+        start += segment.length;
+    }
+    nearest.map(|(_, progress)| progress)
+}
+
+/// Отступ точки возврата от края полотна, м
+const TRACK_RESPAWN_EDGE_MARGIN: f32 = 1.5;
+
+/// Точка на ближайшем сегменте трассы и поворот вдоль него, по ходу движения `heading`
+pub fn nearest_track_pose(world: &World, position: Vec3, heading: Vec3) -> Option<(Vec3, Quat)> {
+    let (point, rotation) = world
+        .query::<(&TrackSegmentComponent, &TransformComponent)>()
+        .iter()
+        .map(|(_, (segment, transform))| {
+            let local = transform.rotation.inverse() * (position - transform.position);
+            let half_width = (segment.width / 2.0 - TRACK_RESPAWN_EDGE_MARGIN).max(0.0);
+            let along = local.z.clamp(-segment.length / 2.0, segment.length / 2.0);
+            let clamped = Vec3::new(local.x.clamp(-half_width, half_width), segment.surface_height(along), along);
+            (transform.position + transform.rotation * clamped, transform.rotation * segment.surface_rotation(along))
+        })
+        .min_by(|a, b| a.0.distance_squared(position).total_cmp(&b.0.distance_squared(position)))?;
+    // Сегмент проходится в обе стороны: машина встает по направлению, в котором ехала
+    let rotation = if (rotation * Vec3::Z).dot(heading) < 0.0 {
+        rotation * Quat::from_rotation_y(std::f32::consts::PI)
+    } else {
+        rotation
+    };
+    let height = ground_at(world, point).map(|(height, _)| height).unwrap_or(point.y);
+    Some((Vec3::new(point.x, height, point.z), rotation))
+}
+
+/// Поверхность и ее материал в точке: сегменты трассы имеют приоритет над ландшафтом
+pub fn surface_at(world: &World, position: Vec3) -> Option<(SurfaceType, PhysicsMaterialId)> {
+    // Полосы за кромкой уступают полотну соседнего сегмента, которое они могут перекрывать в поворотах
+    let mut runoff = None;
+    for (_, (segment, transform)) in world.query::<(&TrackSegmentComponent, &TransformComponent)>().iter() {
+        let local = transform.rotation.inverse() * (position - transform.position);
+        if local.z.abs() > segment.length / 2.0 {
+            continue;
+        }
+        let lateral = segment.lateral_offset(local);
+        match segment.surface_across(lateral) {
+            Some(surface) if lateral.abs() <= segment.width / 2.0 => return Some(surface),
+            Some(surface) => runoff = runoff.or(Some(surface)),
+            None => {}
+        }
+    }
+    if runoff.is_some() {
+        return runoff;
+    }
+
+    world
+        .query::<&TerrainComponent>()
+        .iter()
+        .find_map(|(_, terrain)| terrain.layer_at(position.x, position.z))
+        .map(|layer| {
+            let surface = SurfaceType::from(layer);
+            (surface, surface.material())
+        })
 }
 
 /// Компонент трассы, объединяющий все сегменты
 pub struct TrackComponent {
     pub name: String,
+    pub layout: String, // Конфигурация трассы: одна трасса может иметь несколько вариантов
     pub length: f32,
     pub segments: Vec<hecs::Entity>,
     pub checkpoints: Vec<hecs::Entity>,
-    pub start_positions: Vec<Vec3>,
+    pub props: Vec<hecs::Entity>, // Неподвижные препятствия трассы: отбойники, шины, конусы
+    pub start_positions: Vec<GridSlot>,
+    pub pit_lane: Vec<hecs::Entity>, // Въезд, дорога и зона пит-лейна, боксы и выезд
+    pub point_to_point: bool,        // Спецучасток: старт и финиш в разных местах, кругов нет
+    pub metadata: TrackMetadata,
+}
+
+/// Описание трассы для меню и книги рекордов; название и длина хранятся на самой трассе
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TrackMetadata {
+    #[serde(default)]
+    pub author: String,
+    #[serde(default)]
+    pub country: String,
+    #[serde(default)]
+    pub thumbnail: Option<String>, // Путь к картинке-превью относительно каталога assets
+}
+
+/// Место на стартовой решетке: машина стоит по направлению трассы
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct GridSlot {
+    pub position: Vec3,
+    pub rotation: Quat,
 }
 
 /// Компонент чекпоинта на трассе
@@ -72,6 +459,66 @@ pub struct CheckpointComponent {
     pub index: usize,
     pub width: f32,
     pub is_finish_line: bool,
+    pub is_start_line: bool, // Старт спецучастка; на кольце старт совпадает с линией финиша
+}
+
+/// Въезд на пит-лейн; позиция и поворот берутся из TransformComponent
+pub struct PitEntryComponent {
+    pub width: f32,
+}
+
+/// Выезд с пит-лейна на трассу; позиция и поворот берутся из TransformComponent
+pub struct PitExitComponent {
+    pub width: f32,
+}
+
+/// Пит-лейн: прямоугольная зона вдоль TransformComponent, где действует ограничение скорости
+pub struct PitLaneComponent {
+    pub width: f32,
+    pub length: f32,
+}
+
+/// Находится ли точка на пит-лейне
+pub fn in_pit_lane(world: &World, position: Vec3) -> bool {
+    world
+        .query::<(&PitLaneComponent, &TransformComponent)>()
+        .iter()
+        .any(|(_, (lane, transform))| {
+            let local = transform.rotation.inverse() * (position - transform.position);
+            local.x.abs() <= lane.width / 2.0 && local.z.abs() <= lane.length / 2.0
+        })
+}
+
+/// Бокс на пит-лейне: остановившаяся в нем машина заправляется, ремонтируется и меняет колеса
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PitBoxComponent {
+    pub radius: f32,
+    pub refuel_rate: f32,      // л/с
+    pub repair_rate: f32,      // Доля повреждений, устраняемая за секунду
+    pub tire_change_time: f32, // Стоянка до смены колес, с
+    #[serde(skip)]
+    pub assigned_car: Option<hecs::Entity>, // None - бокс обслуживает любую машину
+}
+
+impl Default for PitBoxComponent {
+    fn default() -> Self {
+        Self {
+            radius: 3.0,
+            refuel_rate: 2.0,
+            repair_rate: 0.1,
+            tire_change_time: 4.0,
+            assigned_car: None,
+        }
+    }
+}
+
+/// Событие завершения сектора (публикуется системой хронометража)
+#[derive(Debug, Clone)]
+pub struct SectorCompletedEvent {
+    pub car_entity: hecs::Entity,
+    pub sector_time: f32,
+    pub clean: bool, // Без выездов за пределы трассы и столкновений
 }
 
 /// Компонент препятствия на трассе
@@ -82,6 +529,7 @@ pub struct ObstacleComponent {
 }
 
 /// Типы препятствий
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ObstacleType {
     Barrier,
     Cone,
@@ -90,15 +538,311 @@ pub enum ObstacleType {
     Rock,
     Car,
     Custom,
+    Sign,
 }
 
-/// Система управления трассой
+impl ObstacleType {
+    /// Легкий предмет, который машина сбивает, а не упирается в него
+    pub fn knockable(&self) -> bool {
+        matches!(self, ObstacleType::Cone | ObstacleType::Sign | ObstacleType::Tire)
+    }
+}
+
+/// Событие завершения круга машиной (публикуется системой хронометража)
+#[derive(Debug, Clone)]
+pub struct CarLapCompletedEvent {
+    pub car_entity: hecs::Entity,
+    pub lap_time: f32,
+    pub best: bool, // Лучший круг этой машины
+}
+
+/// Хронометраж машины: чекпоинты засчитываются только по порядку, круг закрывает линия финиша
+#[derive(Debug, Clone, Default)]
+pub struct LapTimingComponent {
+    pub laps_completed: u32,
+    pub next_checkpoint: usize,        // Индекс чекпоинта, который нужно пересечь следующим
+    pub current_lap_time: Option<f32>, // None до первого пересечения линии старта
+    pub last_lap_time: Option<f32>,
+    pub best_lap_time: Option<f32>,
+    pub sector_time: f32,
+    pub sector_splits: Vec<f32>,      // Времена пройденных секторов текущего круга
+    pub last_sector_splits: Vec<f32>, // Сектора прошлого круга
+    pub best_lap_splits: Vec<f32>,    // Сектора лучшего круга
+    pub best_sectors: Vec<f32>,       // Лучшее время каждого сектора за сессию
+    pub delta_to_best: Option<f32>,   // Разница с лучшим кругом в той же точке трассы, с; минус — быстрее
+    lap_trace: Vec<(f32, f32)>,       // (пройдено по кругу, м; время, с) текущего круга
+    best_trace: Vec<(f32, f32)>,
+    sector_clean: bool,
+    sector_damage: f32,           // Повреждения кузова в начале сектора
+    line_offset: Option<f32>,     // Продольное смещение относительно ожидаемого чекпоинта в прошлом кадре
+    last_position: Option<Vec3>,
+}
+
+impl LapTimingComponent {
+    /// Хронометраж заново, например для нового пилота: круг начнется с пересечения линии старта
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Текущий сектор, начиная с 0 от линии старта
+    pub fn sector(&self) -> usize {
+        self.sector_splits.len()
+    }
+
+    fn start_lap(&mut self, start_index: usize, damage: f32) {
+        self.current_lap_time = Some(0.0);
+        self.next_checkpoint = start_index + 1;
+        self.sector_splits.clear();
+        self.lap_trace = vec![(0.0, 0.0)];
+        self.delta_to_best = (!self.best_trace.is_empty()).then_some(0.0);
+        self.start_sector(damage);
+    }
+
+    /// Финиш спецучастка: часы стоят до нового пересечения линии старта
+    fn finish_stage(&mut self) {
+        self.current_lap_time = None;
+        self.delta_to_best = None;
+    }
+
+    fn complete_sector(&mut self) {
+        let sector = self.sector_splits.len();
+        self.sector_splits.push(self.sector_time);
+        match self.best_sectors.get_mut(sector) {
+            Some(best) => *best = best.min(self.sector_time),
+            None => self.best_sectors.push(self.sector_time),
+        }
+    }
+
+    fn complete_lap(&mut self, lap_time: f32) -> bool {
+        let best = self.best_lap_time.is_none_or(|best| lap_time < best);
+        self.laps_completed += 1;
+        self.last_lap_time = Some(lap_time);
+        self.last_sector_splits = std::mem::take(&mut self.sector_splits);
+        if best {
+            self.best_lap_time = Some(lap_time);
+            self.best_lap_splits = self.last_sector_splits.clone();
+            self.best_trace = std::mem::take(&mut self.lap_trace);
+        }
+        best
+    }
+
+    /// Продвижение по кругу: запись следа круга и разница с лучшим кругом в этой точке
+    fn record_progress(&mut self, distance: f32, lap_length: f32) {
+        let Some(time) = self.current_lap_time else {
+            return;
+        };
+        // Скачок почти на длину круга — шум у линии старта, а не продвижение
+        let last = self.lap_trace.last().map_or(0.0, |&(d, _)| d);
+        let forward = distance >= last && distance - last < lap_length / 2.0;
+        if forward && distance > last + LAP_TRACE_STEP {
+            self.lap_trace.push((distance, time));
+        }
+        let reached = if forward { distance } else { last };
+        self.delta_to_best = time_at_distance(&self.best_trace, reached).map(|best| time - best);
+    }
+
+    fn start_sector(&mut self, damage: f32) {
+        self.sector_time = 0.0;
+        self.sector_clean = true;
+        self.sector_damage = damage;
+        self.line_offset = None;
+    }
+}
+
+/// Шаг записи следа круга для разницы с лучшим кругом, м
+const LAP_TRACE_STEP: f32 = 5.0;
+
+/// Время на пройденной дистанции по следу круга; None за пределами следа
+fn time_at_distance(trace: &[(f32, f32)], distance: f32) -> Option<f32> {
+    let &(last_distance, last_time) = trace.last()?;
+    if distance > last_distance {
+        return None;
+    }
+    if trace.len() < 2 {
+        return Some(last_time);
+    }
+    let index = trace.partition_point(|&(d, _)| d < distance).clamp(1, trace.len() - 1);
+    let (d0, t0) = trace[index - 1];
+    let (d1, t1) = trace[index];
+    if d1 - d0 > f32::EPSILON {
+        Some(t0 + (t1 - t0) * ((distance - d0) / (d1 - d0)).clamp(0.0, 1.0))
+    } else {
+        Some(t1)
+    }
+}
+
+/// Смещение, дальше которого машина считается перенесенной (рестарт, возврат на трассу), м
+const TIMING_TELEPORT_DISTANCE: f32 = 20.0;
+
+/// Допуск от линии старта, при котором круг начинается сразу при постановке машины, м
+const STANDING_START_TOLERANCE: f32 = 1.0;
+
+/// Публикует события кадра, заменяя события прошлого кадра
+pub(crate) fn publish_frame_events<T: Send + Sync + 'static>(world: &mut World, events: Vec<T>) {
+    match world.query_mut::<&mut Resource<EventQueue<T>>>().into_iter().next() {
+        Some((_, queue)) => {
+            queue.0.clear();
+            for event in events {
+                queue.0.publish(event);
+            }
+        }
+        None => {
+            let mut queue = EventQueue::new();
+            for event in events {
+                queue.publish(event);
+            }
+            world.spawn((Resource(queue),));
+        }
+    }
+}
+
+/// Система хронометража: пересечения плоскостей чекпоинтов по порядку, круги на линии финиша,
+/// текущее, последнее и лучшее время круга каждой машины. На спецучастке время идет от линии старта
+/// до финиша один раз, чекпоинты не замыкаются в круг
 pub struct TrackSystem;
 
 impl System for TrackSystem {
-    fn update(&mut self, _world: &mut World, _delta_time: f32) {
-        // Обработка взаимодействий с трассой
-        // Например, проверка прохождения чекпоинтов, сбор телеметрии и т.д.
+    fn update(&mut self, world: &mut World, delta_time: f32) {
+        // Без гонки (симуляция, редактор) часы идут всегда
+        let running = world
+            .query::<&Resource<RaceState>>()
+            .iter()
+            .next()
+            .map(|(_, res)| res.0.running())
+            .unwrap_or(true);
+
+        // Чекпоинты по порядку индексов; без линии финиша круги не считаются
+        let mut checkpoints: Vec<(usize, bool, bool, f32, Vec3, Quat)> = world
+            .query::<(&CheckpointComponent, &TransformComponent)>()
+            .iter()
+            .map(|(_, (checkpoint, transform))| {
+                (
+                    checkpoint.index,
+                    checkpoint.is_finish_line,
+                    checkpoint.is_start_line,
+                    checkpoint.width,
+                    transform.position,
+                    transform.rotation,
+                )
+            })
+            .collect();
+        checkpoints.sort_by_key(|checkpoint| checkpoint.0);
+        let Some(finish_index) = checkpoints.iter().position(|checkpoint| checkpoint.1) else {
+            return;
+        };
+        let track = world.query::<&TrackComponent>().iter().next().map(|(_, track)| (track.length, track.point_to_point));
+        // Спецучасток начинается на своей линии старта, кольцо - на линии финиша
+        let stage = track.is_some_and(|(_, point_to_point)| point_to_point);
+        let start_index = checkpoints
+            .iter()
+            .position(|checkpoint| checkpoint.2)
+            .filter(|&index| stage && index < finish_index)
+            .unwrap_or(finish_index);
+        let stage = start_index != finish_index;
+        // Дистанция круга отсчитывается от линии старта; на спецучастке она не замыкается
+        let start_progress = track_progress(world, checkpoints[start_index].4);
+        let lap_geometry = if stage {
+            start_progress
+                .zip(track_progress(world, checkpoints[finish_index].4))
+                .map(|(start, finish)| (start, finish - start))
+        } else {
+            start_progress.zip(track.map(|(length, _)| length))
+        }
+        .filter(|&(_, length)| length > 0.0);
+
+        let cars: Vec<(hecs::Entity, Vec3, f32, bool)> = world
+            .query::<(&CarComponent, &TransformComponent, Option<&LapTimingComponent>)>()
+            .iter()
+            .map(|(entity, (car, transform, timing))| (entity, transform.position, car.damage.body, timing.is_none()))
+            .collect();
+
+        let mut sector_events = Vec::new();
+        let mut lap_events = Vec::new();
+        for (entity, position, damage, new) in cars {
+            let on_road = car_on_track(world, entity, position);
+            let lap_distance = lap_geometry.and_then(|(start, length)| {
+                track_progress(world, position).map(|progress| {
+                    let distance = if stage { (progress - start).max(0.0) } else { (progress - start).rem_euclid(length) };
+                    (distance, length)
+                })
+            });
+            if new {
+                // Машина, поставленная на линию старта, начинает круг сразу, иначе ждет пересечения
+                let (_, _, _, width, line_position, line_rotation) = checkpoints[start_index];
+                let local = line_rotation.inverse() * (position - line_position);
+                let mut timing = LapTimingComponent::default();
+                if local.x.abs() <= width / 2.0 && local.z.abs() <= STANDING_START_TOLERANCE {
+                    timing.start_lap(start_index, damage);
+                }
+                let _ = world.insert_one(entity, timing);
+            }
+            let Ok(mut timing) = world.get::<&mut LapTimingComponent>(entity) else {
+                continue;
+            };
+
+            if timing.last_position.is_some_and(|last| last.distance(position) > TIMING_TELEPORT_DISTANCE) {
+                timing.line_offset = None;
+            }
+            timing.last_position = Some(position);
+            if !running {
+                timing.line_offset = None;
+                continue;
+            }
+
+            if let Some(lap_time) = timing.current_lap_time {
+                timing.current_lap_time = Some(lap_time + delta_time);
+                timing.sector_time += delta_time;
+                timing.sector_clean &= on_road && damage <= timing.sector_damage;
+                if let Some((distance, length)) = lap_distance {
+                    timing.record_progress(distance, length);
+                }
+            }
+
+            // До начала круга ожидается только линия старта; на спецучастке за финишем чекпоинтов нет
+            let expected = match timing.current_lap_time {
+                Some(_) if stage => timing.next_checkpoint.min(finish_index),
+                Some(_) => timing.next_checkpoint % checkpoints.len(),
+                None => start_index,
+            };
+            let (_, is_finish_line, _, width, line_position, line_rotation) = checkpoints[expected];
+            let local = line_rotation.inverse() * (position - line_position);
+            let previous = timing.line_offset.replace(local.z);
+            let crossed = local.x.abs() <= width / 2.0 && previous.is_some_and(|prev| prev < 0.0 && local.z >= 0.0);
+            if !crossed {
+                continue;
+            }
+
+            let Some(lap_time) = timing.current_lap_time else {
+                timing.start_lap(expected, damage);
+                continue;
+            };
+            timing.complete_sector();
+            sector_events.push(SectorCompletedEvent {
+                car_entity: entity,
+                sector_time: timing.sector_time,
+                clean: timing.sector_clean,
+            });
+            if is_finish_line {
+                let best = timing.complete_lap(lap_time);
+                lap_events.push(CarLapCompletedEvent {
+                    car_entity: entity,
+                    lap_time,
+                    best,
+                });
+                // Спецучасток проехан: следующая попытка снова с линии старта
+                if stage {
+                    timing.finish_stage();
+                } else {
+                    timing.start_lap(expected, damage);
+                }
+            } else {
+                timing.next_checkpoint = expected + 1;
+                timing.start_sector(damage);
+            }
+        }
+
+        publish_frame_events(world, sector_events);
+        publish_frame_events(world, lap_events);
     }
 }
 
@@ -107,53 +851,52 @@ pub fn create_simple_track(world: &mut World, length: f32, width: f32) -> hecs::
     // Создаем основной компонент трассы
     let track_component = TrackComponent {
         name: "Simple Track".to_string(),
+        layout: "Full".to_string(),
         length,
         segments: Vec::new(),
         checkpoints: Vec::new(),
-        start_positions: vec![
-            Vec3::new(0.0, 0.0, 0.0),
-            Vec3::new(2.0, 0.0, 0.0),
-            Vec3::new(4.0, 0.0, 0.0),
-            Vec3::new(6.0, 0.0, 0.0),
-        ],
+        props: Vec::new(),
+        start_positions: Vec::new(),
+        pit_lane: Vec::new(),
+        point_to_point: false,
+        metadata: TrackMetadata::default(),
     };
     
     // Создаем сущность трассы
     let track_entity = world.spawn((track_component,));
     
-    // Создаем прямой сегмент
-    let segment = TrackSegmentComponent {
-        segment_type: TrackSegmentType::Straight,
-        length,
-        width,
-        curvature: 0.0,
-        banking: 0.0,
-        surface_type: SurfaceType::Asphalt,
-        friction: 1.0,
-    };
-    
-    let transform = TransformComponent {
-        position: Vec3::new(0.0, 0.0, 0.0),
-        rotation: Quat::IDENTITY,
-        ..Default::default()
-    };
-    
-    let rigid_body = RigidBodyComponent {
-        handle: Default::default(),
-        body_type: RigidBodyType::Static,
-    };
-    
-    let collider = ColliderComponent {
-        handle: Default::default(),
-        shape_type: Default::default(),
-    };
-    
-    // Создаем сегмент трассы
-    let segment_entity = world.spawn((segment, transform, rigid_body, collider));
+    // Прямая осевая линия режется на связанные сегменты; полотно каждого строится мешем,
+    // коллайдер повторяет его геометрию
+    let spline = TrackSpline::catmull_rom(
+        vec![
+            SplinePoint::new(Vec3::new(0.0, 0.0, -length / 2.0), width),
+            SplinePoint::new(Vec3::new(0.0, 0.0, length / 2.0), width),
+        ],
+        false,
+    );
+    match create_spline_segments(world, &spline) {
+        Ok(segments) => {
+            if let Ok(track) = world.query_one_mut::<&mut TrackComponent>(track_entity) {
+                track.segments = segments;
+                // Линия старта посередине прямой, решетка позади нее
+                track.start_positions = spline.start_grid(length / 2.0, DEFAULT_GRID_SLOTS);
+            }
+        }
+        Err(e) => eprintln!("Не удалось построить полотно трассы: {}", e),
+    }
+    // Осевая линия остается на трассе, чтобы трассу можно было сохранить в файл
+    let _ = world.insert_one(track_entity, spline);
     
-    // Добавляем ссылку на сегмент в компонент трассы
-    if let Ok(track) = world.query_one_mut::<&mut TrackComponent>(track_entity) {
-        track.segments.push(segment_entity);
+    // Отбойники вдоль обеих сторон дороги
+    for side in [-1.0, 1.0] {
+        let barrier = create_barrier(
+            world,
+            Vec3::new(side * (width / 2.0 + 0.25), 0.5, 0.0),
+            Vec3::new(0.25, 0.5, length / 2.0),
+        );
+        if let Ok(track) = world.query_one_mut::<&mut TrackComponent>(track_entity) {
+            track.props.push(barrier);
+        }
     }
     
     // Создаем стартовый/финишный чекпоинт
@@ -161,6 +904,7 @@ pub fn create_simple_track(world: &mut World, length: f32, width: f32) -> hecs::
         index: 0,
         width,
         is_finish_line: true,
+        is_start_line: false,
     };
     
     let transform = TransformComponent {
@@ -180,8 +924,122 @@ pub fn create_simple_track(world: &mut World, length: f32, width: f32) -> hecs::
     track_entity
 }
 
-/// Функция для загрузки трассы из файла (заглушка)
-pub fn load_track_from_file(_world: &mut World, _file_path: &str) -> Result<hecs::Entity, String> {
-    // Здесь будет код для загрузки и разбора файла трассы
-    Err("Not implemented yet".to_string())
-} 
\ No newline at end of file
+/// Добавляет неподвижное тело с коллайдером-треугольной сеткой по мешу
+fn insert_static_trimesh(
+    world: &mut World,
+    mesh: &MeshData,
+    position: Vec3,
+    rotation: Quat,
+    material: PhysicsMaterialId,
+) -> Result<(RigidBodyHandle, ColliderHandle), String> {
+    let vertices: Vec<Point3<f32>> = mesh
+        .vertices
+        .iter()
+        .map(|vertex| Point3::new(vertex.position[0], vertex.position[1], vertex.position[2]))
+        .collect();
+    let triangles: Vec<[u32; 3]> = mesh
+        .indices
+        .as_ref()
+        .ok_or_else(|| "Track mesh has no indices".to_string())?
+        .chunks_exact(3)
+        .map(|tri| [tri[0] as u32, tri[1] as u32, tri[2] as u32])
+        .collect();
+    let materials = physics_materials(world);
+
+    let resource_query = world.query_mut::<&mut Resource<(RigidBodySet, ColliderSet)>>();
+    let (_, resource) = resource_query
+        .into_iter()
+        .next()
+        .ok_or_else(|| "Physics world resource is missing".to_string())?;
+    let (rigid_body_set, collider_set) = &mut resource.0;
+
+    let (axis, angle) = rotation.to_axis_angle();
+    let body = RigidBodyBuilder::fixed()
+        .translation(Vector3::new(position.x, position.y, position.z))
+        .rotation(Vector3::new(axis.x, axis.y, axis.z) * angle)
+        .build();
+    let collider = materials.apply(material, ColliderBuilder::trimesh(vertices, triangles)).build();
+
+    let body_handle = rigid_body_set.insert(body);
+    let collider_handle = collider_set.insert_with_parent(collider, body_handle, rigid_body_set);
+    Ok((body_handle, collider_handle))
+}
+
+/// Добавляет неподвижное тело с коллайдером-параллелепипедом в физический мир
+fn insert_static_box(
+    world: &mut World,
+    position: Vec3,
+    half_extents: Vec3,
+    material: PhysicsMaterialId,
+) -> Result<(RigidBodyHandle, ColliderHandle), String> {
+    let materials = physics_materials(world);
+    let resource_query = world.query_mut::<&mut Resource<(RigidBodySet, ColliderSet)>>();
+    let (_, resource) = resource_query
+        .into_iter()
+        .next()
+        .ok_or_else(|| "Physics world resource is missing".to_string())?;
+    let (rigid_body_set, collider_set) = &mut resource.0;
+    
+    let body = RigidBodyBuilder::fixed()
+        .translation([position.x, position.y, position.z].into())
+        .build();
+    let collider = materials
+        .apply(material, ColliderBuilder::cuboid(half_extents.x, half_extents.y, half_extents.z))
+        .build();
+    
+    let body_handle = rigid_body_set.insert(body);
+    let collider_handle = collider_set.insert_with_parent(collider, body_handle, rigid_body_set);
+    Ok((body_handle, collider_handle))
+}
+
+/// Создает неразрушаемый отбойник
+pub fn create_barrier(world: &mut World, position: Vec3, half_extents: Vec3) -> hecs::Entity {
+    create_static_obstacle(world, ObstacleType::Barrier, position, half_extents)
+}
+
+/// Создает неподвижное неразрушаемое препятствие-параллелепипед
+pub fn create_static_obstacle(world: &mut World, obstacle_type: ObstacleType, position: Vec3, half_extents: Vec3) -> hecs::Entity {
+    let physics = insert_static_box(world, position, half_extents, PhysicsMaterialId::BARRIER);
+    
+    let obstacle = ObstacleComponent {
+        obstacle_type,
+        destructible: false,
+        health: 100.0,
+    };
+    
+    let transform = TransformComponent {
+        position,
+        rotation: Quat::IDENTITY,
+        scale: half_extents * 2.0,
+    };
+    
+    let entity = world.spawn((obstacle, transform));
+    match physics {
+        Ok((body_handle, collider_handle)) => {
+            let rigid_body = RigidBodyComponent {
+                handle: body_handle,
+                body_type: RigidBodyType::Static,
+            };
+            let collider = ColliderComponent {
+                handle: collider_handle,
+                shape_type: ColliderShapeType::Box,
+            };
+            let _ = world.insert(entity, (rigid_body, collider));
+        }
+        Err(e) => eprintln!("Препятствие без коллайдера: {}", e),
+    }
+    entity
+}
+
+/// Файл трассы, которую событие загружает, если другая не выбрана
+pub const DEFAULT_TRACK_PATH: &str = "assets/tracks/simple_track/track.json";
+
+/// Загружает трассу из файла и создает ее в мире
+pub fn load_track_from_file(world: &mut World, file_path: &str) -> Result<hecs::Entity, String> {
+    TrackFile::load(Path::new(file_path))?.spawn(world)
+}
+
+/// Сохраняет созданную трассу в файл
+pub fn save_track_to_file(world: &World, track_entity: hecs::Entity, file_path: &str) -> Result<(), String> {
+    TrackFile::from_world(world, track_entity)?.save(Path::new(file_path))
+}
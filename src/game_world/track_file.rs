@@ -0,0 +1,723 @@
+use crate::core::audio::GrandstandComponent;
+use crate::core::physics::TransformComponent;
+use crate::game_world::hazards::{HazardDefinition, TrackHazardsComponent};
+use crate::game_world::environment::{create_time_of_day, create_weather, TimeOfDayComponent, WeatherComponent, WeatherType};
+use crate::game_world::track::{
+    create_track_segment, CheckpointComponent, GridSlot, ObstacleComponent, ObstacleType, PitBoxComponent,
+    PitEntryComponent, PitExitComponent, PitLaneComponent, SurfaceBand, SurfaceType, TrackComponent, TrackMetadata,
+    TrackSegmentComponent, TrackSegmentType,
+};
+use crate::game_world::props::{create_track_prop, KnockablePropComponent};
+use crate::game_world::track_spline::{create_spline_segments, SplineKind, SplinePoint, TrackSpline, DEFAULT_GRID_SLOTS};
+use glam::{Quat, Vec3};
+use hecs::World;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Контрольная точка осевой линии в файле трассы
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrackPointFile {
+    pub position: [f32; 3],
+    pub width: f32,
+    #[serde(default)]
+    pub banking: f32, // рад
+    #[serde(default)]
+    pub surface: SurfaceType,
+    #[serde(default)]
+    pub bands: Vec<SurfaceBand>, // Поребрики, гравий и трава поперек трассы
+    #[serde(default)]
+    pub jump_height: f32, // Высота кромки трамплина, который кончается в этой точке, м
+}
+
+/// Осевая линия трассы в файле
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrackSplineFile {
+    pub kind: SplineKind,
+    #[serde(default)]
+    pub closed: bool,
+    pub points: Vec<TrackPointFile>,
+}
+
+/// Чекпоинт в файле; порядок в списке задает порядок прохождения
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CheckpointFile {
+    pub position: [f32; 3],
+    #[serde(default)]
+    pub heading: f32, // Курс, рад: 0 вдоль +Z
+    pub width: f32,
+    #[serde(default)]
+    pub is_finish_line: bool,
+    #[serde(default)]
+    pub is_start_line: bool, // Старт спецучастка: только на трассе без замыкания, перед линией финиша
+}
+
+/// Неподвижное препятствие трассы
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PropFile {
+    pub kind: ObstacleType,
+    pub position: [f32; 3],
+    pub half_extents: [f32; 3],
+}
+
+/// Трибуна со зрителями; гул слышен в радиусе трибуны
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GrandstandFile {
+    pub position: [f32; 3],
+    #[serde(flatten)]
+    pub stand: GrandstandComponent,
+}
+
+/// Въезд или выезд пит-лейна
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PitGateFile {
+    pub position: [f32; 3],
+    #[serde(default)]
+    pub heading: f32, // Курс, рад: 0 вдоль +Z
+    pub width: f32,
+}
+
+/// Бокс на пит-лейне; скорости обслуживания по умолчанию берутся из PitBoxComponent
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PitBoxFile {
+    pub position: [f32; 3],
+    #[serde(flatten)]
+    pub service: PitBoxComponent,
+}
+
+/// Пит-лейн: въезд, прямая зона ограничения скорости с боксами и выезд;
+/// въезд и выезд соединяются с концами зоны прямыми дорогами
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PitLaneFile {
+    pub entry: PitGateFile,
+    pub exit: PitGateFile,
+    pub position: [f32; 3], // Середина зоны ограничения скорости
+    #[serde(default)]
+    pub heading: f32,
+    pub width: f32,
+    pub length: f32,
+    #[serde(default)]
+    pub boxes: Vec<PitBoxFile>,
+}
+
+/// Погода и время суток при загрузке трассы
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TrackEnvironmentFile {
+    pub weather: WeatherType,
+    pub intensity: f32,
+    pub hour: f32,
+    pub minute: f32,
+}
+
+impl Default for TrackEnvironmentFile {
+    fn default() -> Self {
+        Self {
+            weather: WeatherType::Clear,
+            intensity: 0.0,
+            hour: 12.0,
+            minute: 0.0,
+        }
+    }
+}
+
+/// Файл трассы: осевая линия с покрытиями, чекпоинты, стартовая решетка, препятствия, трибуны,
+/// скриптовые опасности и окружение
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrackFile {
+    pub name: String,
+    #[serde(default = "default_layout")]
+    pub layout: String,
+    pub spline: TrackSplineFile,
+    #[serde(default)]
+    pub checkpoints: Vec<CheckpointFile>,
+    #[serde(default)]
+    pub start_grid: Vec<[f32; 3]>,
+    #[serde(default)]
+    pub props: Vec<PropFile>,
+    #[serde(default)]
+    pub pit_lane: Option<PitLaneFile>,
+    #[serde(default)]
+    pub grandstands: Vec<GrandstandFile>,
+    #[serde(default)]
+    pub hazards: Vec<HazardDefinition>, // Камнепады, разводные мосты и поезда
+    #[serde(default)]
+    pub environment: TrackEnvironmentFile,
+    #[serde(default)]
+    pub metadata: TrackMetadata, // Автор, страна и превью
+}
+
+fn default_layout() -> String {
+    "Full".to_string()
+}
+
+impl TrackFile {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+        Self::parse(&text)
+    }
+
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let file: Self = serde_json::from_str(text).map_err(|e| e.to_string())?;
+        file.validate()?;
+        Ok(file)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        let text = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        std::fs::write(path, text).map_err(|e| e.to_string())
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.name.is_empty() {
+            return Err("Track has no name".to_string());
+        }
+        self.spline().validate().map_err(|e| format!("Track '{}': {}", self.name, e))?;
+        let bands = self.spline.points.iter().flat_map(|point| &point.bands);
+        if bands.clone().any(|band| !band.from.is_finite() || !band.to.is_finite() || band.from == band.to) {
+            return Err(format!("Track '{}' has a surface band with invalid bounds", self.name));
+        }
+        if self.checkpoints.iter().any(|checkpoint| checkpoint.width <= 0.0) {
+            return Err(format!("Track '{}' has a checkpoint with invalid width", self.name));
+        }
+        if self.checkpoints.iter().filter(|checkpoint| checkpoint.is_finish_line).count() > 1 {
+            return Err(format!("Track '{}' has more than one finish line", self.name));
+        }
+        let start = self.checkpoints.iter().position(|checkpoint| checkpoint.is_start_line);
+        if self.checkpoints.iter().filter(|checkpoint| checkpoint.is_start_line).count() > 1 {
+            return Err(format!("Track '{}' has more than one start line", self.name));
+        }
+        if let Some(start) = start {
+            if self.spline.closed {
+                return Err(format!("Track '{}' is closed but has a separate start line", self.name));
+            }
+            let finish = self.checkpoints.iter().position(|checkpoint| checkpoint.is_finish_line);
+            if finish.is_none_or(|finish| finish <= start) {
+                return Err(format!("Track '{}' has no finish line after the start line", self.name));
+            }
+        }
+        if self.props.iter().any(|prop| prop.half_extents.iter().any(|size| *size <= 0.0)) {
+            return Err(format!("Track '{}' has a prop with invalid size", self.name));
+        }
+        if self.grandstands.iter().any(|stand| stand.stand.radius <= 0.0) {
+            return Err(format!("Track '{}' has a grandstand with invalid radius", self.name));
+        }
+        if let Some(index) = self.hazards.iter().position(|hazard| !hazard.is_valid()) {
+            return Err(format!("Track '{}' hazard {} has invalid parameters", self.name, index));
+        }
+        if let Some(pit_lane) = &self.pit_lane {
+            if pit_lane.width <= 0.0 || pit_lane.length <= 0.0 || pit_lane.entry.width <= 0.0 || pit_lane.exit.width <= 0.0 {
+                return Err(format!("Track '{}' has a pit lane with invalid size", self.name));
+            }
+            if pit_lane.boxes.iter().any(|pit_box| pit_box.service.radius <= 0.0 || pit_box.service.tire_change_time < 0.0) {
+                return Err(format!("Track '{}' has an invalid pit box", self.name));
+            }
+        }
+        Ok(())
+    }
+
+    /// Осевая линия для генератора сегментов
+    pub fn spline(&self) -> TrackSpline {
+        let points = self
+            .spline
+            .points
+            .iter()
+            .map(|point| SplinePoint {
+                position: Vec3::from(point.position),
+                width: point.width,
+                banking: point.banking,
+                surface: point.surface,
+                bands: point.bands.clone(),
+                jump_height: point.jump_height,
+            })
+            .collect();
+        match self.spline.kind {
+            SplineKind::CatmullRom => TrackSpline::catmull_rom(points, self.spline.closed),
+            SplineKind::Bezier => TrackSpline::bezier(points, self.spline.closed),
+        }
+    }
+
+    /// Спецучасток: трасса не замкнута, старт и финиш на разных линиях
+    pub fn point_to_point(&self) -> bool {
+        !self.spline.closed && self.checkpoints.iter().any(|checkpoint| checkpoint.is_start_line)
+    }
+
+    /// Места стартовой решетки по курсу трассы; без решетки в файле она строится за линией старта,
+    /// а на кольце - за линией финиша
+    fn start_positions(&self, spline: &TrackSpline) -> Vec<GridSlot> {
+        if self.start_grid.is_empty() {
+            let start_line = |checkpoint: &&CheckpointFile| {
+                if self.point_to_point() {
+                    checkpoint.is_start_line
+                } else {
+                    checkpoint.is_finish_line
+                }
+            };
+            let start_distance = self
+                .checkpoints
+                .iter()
+                .find(start_line)
+                .map_or(0.0, |checkpoint| spline.nearest_distance(Vec3::from(checkpoint.position)));
+            return spline.start_grid(start_distance, DEFAULT_GRID_SLOTS);
+        }
+        self.start_grid
+            .iter()
+            .map(|position| {
+                let position = Vec3::from(*position);
+                GridSlot {
+                    position,
+                    rotation: spline.grid_slot(spline.nearest_distance(position), 0.0).rotation,
+                }
+            })
+            .collect()
+    }
+
+    /// Создает трассу в мире и выставляет погоду и время суток
+    pub fn spawn(&self, world: &mut World) -> Result<hecs::Entity, String> {
+        let spline = self.spline();
+        let segments = create_spline_segments(world, &spline)?;
+
+        let checkpoints = self
+            .checkpoints
+            .iter()
+            .enumerate()
+            .map(|(index, checkpoint)| {
+                world.spawn((
+                    CheckpointComponent {
+                        index,
+                        width: checkpoint.width,
+                        is_finish_line: checkpoint.is_finish_line,
+                        is_start_line: checkpoint.is_start_line,
+                    },
+                    TransformComponent {
+                        position: Vec3::from(checkpoint.position),
+                        rotation: Quat::from_rotation_y(checkpoint.heading),
+                        ..Default::default()
+                    },
+                ))
+            })
+            .collect();
+        let props = self
+            .props
+            .iter()
+            .map(|prop| create_track_prop(world, prop.kind, Vec3::from(prop.position), Vec3::from(prop.half_extents)))
+            .collect();
+
+        let track = TrackComponent {
+            name: self.name.clone(),
+            layout: self.layout.clone(),
+            length: spline.length(),
+            segments,
+            checkpoints,
+            props,
+            start_positions: self.start_positions(&spline),
+            pit_lane: self.pit_lane.as_ref().map(|pit_lane| spawn_pit_lane(world, pit_lane)).unwrap_or_default(),
+            point_to_point: self.point_to_point(),
+            metadata: self.metadata.clone(),
+        };
+        for stand in &self.grandstands {
+            world.spawn((
+                stand.stand.clone(),
+                TransformComponent {
+                    position: Vec3::from(stand.position),
+                    ..Default::default()
+                },
+            ));
+        }
+        // Тела и знаки опасностей создаются вместе с рендером, пока описания лежат на трассе
+        let hazards = TrackHazardsComponent {
+            hazards: self.hazards.clone(),
+        };
+        let track_entity = world.spawn((track, spline, hazards));
+        apply_track_environment(world, &self.environment);
+        Ok(track_entity)
+    }
+
+    /// Файл по трассе, созданной в мире; осевая линия должна быть сохранена на трассе
+    pub fn from_world(world: &World, track_entity: hecs::Entity) -> Result<Self, String> {
+        let track = world
+            .get::<&TrackComponent>(track_entity)
+            .map_err(|_| "Track entity not found".to_string())?;
+        let spline = world
+            .get::<&TrackSpline>(track_entity)
+            .map_err(|_| format!("Track '{}' has no spline to save", track.name))?;
+
+        let checkpoints = track
+            .checkpoints
+            .iter()
+            .filter_map(|&entity| {
+                let checkpoint = world.get::<&CheckpointComponent>(entity).ok()?;
+                let transform = world.get::<&TransformComponent>(entity).ok()?;
+                let forward = transform.rotation * Vec3::Z;
+                Some(CheckpointFile {
+                    position: transform.position.to_array(),
+                    heading: forward.x.atan2(forward.z),
+                    width: checkpoint.width,
+                    is_finish_line: checkpoint.is_finish_line,
+                    is_start_line: checkpoint.is_start_line,
+                })
+            })
+            .collect();
+        let props = track
+            .props
+            .iter()
+            .filter_map(|&entity| {
+                let obstacle = world.get::<&ObstacleComponent>(entity).ok()?;
+                let transform = world.get::<&TransformComponent>(entity).ok()?;
+                // Сбитый предмет сохраняется на месте установки
+                let position = world.get::<&KnockablePropComponent>(entity).map_or(transform.position, |prop| prop.home);
+                Some(PropFile {
+                    kind: obstacle.obstacle_type,
+                    position: position.to_array(),
+                    half_extents: (transform.scale / 2.0).to_array(),
+                })
+            })
+            .collect();
+
+        let grandstands = world
+            .query::<(&GrandstandComponent, &TransformComponent)>()
+            .iter()
+            .map(|(_, (stand, transform))| GrandstandFile {
+                position: transform.position.to_array(),
+                stand: stand.clone(),
+            })
+            .collect();
+
+        let mut environment = TrackEnvironmentFile::default();
+        if let Some((_, weather)) = world.query::<&WeatherComponent>().iter().next() {
+            environment.weather = weather.weather_type.clone();
+            environment.intensity = weather.intensity;
+        }
+        if let Some((_, time)) = world.query::<&TimeOfDayComponent>().iter().next() {
+            environment.hour = time.hour;
+            environment.minute = time.minute;
+        }
+
+        Ok(Self {
+            name: track.name.clone(),
+            layout: track.layout.clone(),
+            spline: TrackSplineFile {
+                kind: spline.kind,
+                closed: spline.closed,
+                points: spline
+                    .points
+                    .iter()
+                    .map(|point| TrackPointFile {
+                        position: point.position.to_array(),
+                        width: point.width,
+                        banking: point.banking,
+                        surface: point.surface,
+                        bands: point.bands.clone(),
+                        jump_height: point.jump_height,
+                    })
+                    .collect(),
+            },
+            checkpoints,
+            start_grid: track.start_positions.iter().map(|slot| slot.position.to_array()).collect(),
+            props,
+            pit_lane: pit_lane_from_world(world, &track.pit_lane),
+            grandstands,
+            hazards: world
+                .get::<&TrackHazardsComponent>(track_entity)
+                .map(|hazards| hazards.hazards.clone())
+                .unwrap_or_default(),
+            environment,
+            metadata: track.metadata.clone(),
+        })
+    }
+}
+
+/// Поворот по курсу вокруг вертикали
+fn heading_rotation(heading: f32) -> Quat {
+    Quat::from_rotation_y(heading)
+}
+
+/// Курс по повороту: 0 вдоль +Z
+fn heading_of(rotation: Quat) -> f32 {
+    let forward = rotation * Vec3::Z;
+    forward.x.atan2(forward.z)
+}
+
+/// Прямой асфальтовый сегмент пит-лейна
+fn pit_road_segment(length: f32, width: f32) -> TrackSegmentComponent {
+    TrackSegmentComponent {
+        segment_type: TrackSegmentType::Straight,
+        length,
+        width,
+        curvature: 0.0,
+        banking: 0.0,
+        banking_change: 0.0,
+        vertical_curvature: 0.0,
+        surface_type: SurfaceType::Asphalt,
+        material: SurfaceType::Asphalt.material(),
+        surface_bands: Vec::new(),
+        ramp_height: 0.0,
+    }
+}
+
+/// Прямая дорога между двумя точками; слишком короткая не создается
+fn create_pit_road(world: &mut World, from: Vec3, to: Vec3, width: f32) -> Option<hecs::Entity> {
+    let direction = to - from;
+    let length = direction.length();
+    if length < 1.0 {
+        return None;
+    }
+    let pitch = (direction.y / length).clamp(-1.0, 1.0).asin();
+    let transform = TransformComponent {
+        position: (from + to) / 2.0,
+        rotation: Quat::from_rotation_y(direction.x.atan2(direction.z)) * Quat::from_rotation_x(-pitch),
+        ..Default::default()
+    };
+    Some(create_track_segment(world, pit_road_segment(length, width), transform))
+}
+
+/// Создает въезд, дорогу и зону пит-лейна, боксы и выезд
+fn spawn_pit_lane(world: &mut World, pit_lane: &PitLaneFile) -> Vec<hecs::Entity> {
+    let mut entities = Vec::new();
+    let entry = Vec3::from(pit_lane.entry.position);
+    let exit = Vec3::from(pit_lane.exit.position);
+    entities.push(world.spawn((
+        PitEntryComponent { width: pit_lane.entry.width },
+        TransformComponent {
+            position: entry,
+            rotation: heading_rotation(pit_lane.entry.heading),
+            ..Default::default()
+        },
+    )));
+
+    // Зона ограничения скорости лежит на собственном сегменте дороги
+    let center = Vec3::from(pit_lane.position);
+    let rotation = heading_rotation(pit_lane.heading);
+    let half = rotation * Vec3::Z * pit_lane.length / 2.0;
+    let lane = create_track_segment(
+        world,
+        pit_road_segment(pit_lane.length, pit_lane.width),
+        TransformComponent {
+            position: center,
+            rotation,
+            ..Default::default()
+        },
+    );
+    let _ = world.insert_one(
+        lane,
+        PitLaneComponent {
+            width: pit_lane.width,
+            length: pit_lane.length,
+        },
+    );
+    entities.push(lane);
+    entities.extend(create_pit_road(world, entry, center - half, pit_lane.entry.width.min(pit_lane.width)));
+    entities.extend(create_pit_road(world, center + half, exit, pit_lane.exit.width.min(pit_lane.width)));
+
+    for pit_box in &pit_lane.boxes {
+        entities.push(world.spawn((
+            pit_box.service.clone(),
+            TransformComponent {
+                position: Vec3::from(pit_box.position),
+                ..Default::default()
+            },
+        )));
+    }
+    entities.push(world.spawn((
+        PitExitComponent { width: pit_lane.exit.width },
+        TransformComponent {
+            position: exit,
+            rotation: heading_rotation(pit_lane.exit.heading),
+            ..Default::default()
+        },
+    )));
+    entities
+}
+
+/// Пит-лейн трассы для файла; без въезда, зоны или выезда трасса сохраняется без пит-лейна
+fn pit_lane_from_world(world: &World, entities: &[hecs::Entity]) -> Option<PitLaneFile> {
+    let gate = |entity: hecs::Entity, width: f32| {
+        let transform = world.get::<&TransformComponent>(entity).ok()?;
+        Some(PitGateFile {
+            position: transform.position.to_array(),
+            heading: heading_of(transform.rotation),
+            width,
+        })
+    };
+    let entry = entities
+        .iter()
+        .find_map(|&entity| gate(entity, world.get::<&PitEntryComponent>(entity).ok()?.width))?;
+    let exit = entities
+        .iter()
+        .find_map(|&entity| gate(entity, world.get::<&PitExitComponent>(entity).ok()?.width))?;
+    let (position, heading, width, length) = entities.iter().find_map(|&entity| {
+        let lane = world.get::<&PitLaneComponent>(entity).ok()?;
+        let transform = world.get::<&TransformComponent>(entity).ok()?;
+        Some((transform.position.to_array(), heading_of(transform.rotation), lane.width, lane.length))
+    })?;
+    let boxes = entities
+        .iter()
+        .filter_map(|&entity| {
+            let service = world.get::<&PitBoxComponent>(entity).ok()?;
+            let transform = world.get::<&TransformComponent>(entity).ok()?;
+            Some(PitBoxFile {
+                position: transform.position.to_array(),
+                service: (*service).clone(),
+            })
+        })
+        .collect();
+    Some(PitLaneFile {
+        entry,
+        exit,
+        position,
+        heading,
+        width,
+        length,
+        boxes,
+    })
+}
+
+/// Выставляет погоду и время суток трассы; без компонентов окружения создает их
+fn apply_track_environment(world: &mut World, environment: &TrackEnvironmentFile) {
+    match world.query_mut::<&mut WeatherComponent>().into_iter().next() {
+        Some((_, weather)) => {
+            weather.weather_type = environment.weather.clone();
+            weather.intensity = environment.intensity;
+            weather.target_weather = None;
+        }
+        None => {
+            create_weather(world, environment.weather.clone(), environment.intensity);
+        }
+    }
+    match world.query_mut::<&mut TimeOfDayComponent>().into_iter().next() {
+        Some((_, time)) => {
+            time.hour = environment.hour;
+            time.minute = environment.minute;
+        }
+        None => {
+            create_time_of_day(world, environment.hour, environment.minute);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MINIMAL: &str = r#"{
+        "name": "Test",
+        "spline": {
+            "kind": "catmull_rom",
+            "points": [
+                { "position": [0.0, 0.0, 0.0], "width": 10.0 },
+                { "position": [0.0, 0.0, 100.0], "width": 10.0 }
+            ]
+        },
+        "checkpoints": [{ "position": [0.0, 0.0, 50.0], "width": 10.0, "is_finish_line": true }]
+    }"#;
+
+    fn minimal() -> TrackFile {
+        TrackFile::parse(MINIMAL).unwrap()
+    }
+
+    #[test]
+    fn parses_minimal_track_with_defaults() {
+        let file = minimal();
+        assert_eq!(file.layout, "Full");
+        assert!(!file.spline.closed);
+        assert_eq!(file.spline.points.len(), 2);
+        assert_eq!(file.environment.hour, 12.0);
+        assert!(!file.point_to_point());
+    }
+
+    #[test]
+    fn parses_shipped_track() {
+        let text = std::fs::read_to_string(crate::game_world::track::DEFAULT_TRACK_PATH).unwrap();
+        let file = TrackFile::parse(&text).unwrap();
+        assert!(file.checkpoints.iter().any(|checkpoint| checkpoint.is_finish_line));
+        assert!(file.props.iter().any(|prop| prop.kind.knockable()));
+        assert!(!file.grandstands.is_empty());
+        assert!(!file.hazards.is_empty());
+    }
+
+    #[test]
+    fn parses_bezier_track() {
+        let text = r#"{
+            "name": "Bezier",
+            "spline": {
+                "kind": "bezier",
+                "points": [
+                    { "position": [0.0, 0.0, 0.0], "width": 10.0 },
+                    { "position": [0.0, 0.0, 40.0], "width": 10.0 },
+                    { "position": [40.0, 0.0, 60.0], "width": 10.0 },
+                    { "position": [40.0, 0.0, 100.0], "width": 10.0 }
+                ]
+            },
+            "checkpoints": [{ "position": [20.0, 0.0, 50.0], "width": 10.0, "is_finish_line": true }]
+        }"#;
+        let file = TrackFile::parse(text).unwrap();
+        let spline = file.spline();
+        assert_eq!(spline.kind, SplineKind::Bezier);
+        assert_eq!(spline.span_count(), 1);
+        assert!(spline.length() > 100.0);
+
+        // Кубическому пролету нужны две управляющие точки
+        let mut file = file;
+        file.spline.points.pop();
+        assert!(file.validate().is_err());
+    }
+
+    #[test]
+    fn open_track_grid_extends_behind_the_spline_start() {
+        let mut file = minimal();
+        file.checkpoints[0].position = [0.0, 0.0, 10.0];
+        let slots = file.start_positions(&file.spline());
+        assert_eq!(slots.len(), DEFAULT_GRID_SLOTS);
+        // Места за началом сплайна не сливаются, а уходят назад с тем же шагом
+        for pair in slots.windows(2) {
+            assert!((pair[0].position.z - pair[1].position.z - 6.0).abs() < 0.1);
+        }
+        assert!(slots.last().unwrap().position.z < -30.0);
+    }
+
+    #[test]
+    fn rejects_invalid_tracks() {
+        let mut file = minimal();
+        file.name.clear();
+        assert!(file.validate().is_err());
+
+        let mut file = minimal();
+        file.checkpoints[0].width = 0.0;
+        assert!(file.validate().is_err());
+
+        let mut file = minimal();
+        file.checkpoints.push(file.checkpoints[0].clone());
+        assert!(file.validate().is_err());
+
+        assert!(TrackFile::parse(&MINIMAL.replace("catmull_rom", "nurbs")).is_err());
+    }
+
+    #[test]
+    fn start_line_needs_a_later_finish_on_an_open_track() {
+        let mut file = minimal();
+        let mut start = file.checkpoints[0].clone();
+        start.is_finish_line = false;
+        start.is_start_line = true;
+        file.checkpoints.push(start.clone());
+        assert!(file.validate().is_err());
+
+        file.checkpoints.insert(0, start);
+        file.checkpoints.pop();
+        assert!(file.validate().is_ok());
+        assert!(file.point_to_point());
+
+        file.spline.closed = true;
+        assert!(file.validate().is_err());
+    }
+
+    #[test]
+    fn survives_a_save_round_trip() {
+        let file = minimal();
+        let text = serde_json::to_string(&file).unwrap();
+        let parsed = TrackFile::parse(&text).unwrap();
+        assert_eq!(parsed.name, file.name);
+        assert_eq!(parsed.checkpoints.len(), 1);
+    }
+}
@@ -0,0 +1,153 @@
+use crate::core::physics::TransformComponent;
+use crate::core::random::SimpleRng;
+use crate::game_world::track::{CheckpointComponent, TrackComponent, TrackMetadata};
+use crate::game_world::track_spline::{create_spline_segments, SplinePoint, TrackSpline, DEFAULT_GRID_SLOTS};
+use glam::{Quat, Vec3};
+use hecs::World;
+use serde::{Deserialize, Serialize};
+use std::f32::consts::TAU;
+
+/// Пределы числа контрольных точек кольца
+const MIN_CONTROL_POINTS: usize = 5;
+const MAX_CONTROL_POINTS: usize = 32;
+
+/// Разброс удаления контрольных точек от центра, доля радиуса
+const RADIAL_JITTER: f32 = 0.25;
+
+/// Разброс направления на контрольную точку, доля шага между точками
+const ANGULAR_JITTER: f32 = 0.3;
+
+/// Проходов подгонки масштаба кольца под желаемую длину
+const LENGTH_FIT_PASSES: usize = 3;
+
+/// Расстояние между чекпоинтами, м
+const CHECKPOINT_SPACING: f32 = 200.0;
+
+/// Настройки генератора кольцевых трасс
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TrackGenerationConfig {
+    pub length: f32,         // Желаемая длина круга, м
+    pub width: f32,          // Ширина полотна, м
+    pub corner_density: f32, // Поворотов на километр
+    pub elevation: f32,      // Наибольший перепад высот, м
+}
+
+impl Default for TrackGenerationConfig {
+    fn default() -> Self {
+        Self {
+            length: 1200.0,
+            width: 10.0,
+            corner_density: 6.0,
+            elevation: 8.0,
+        }
+    }
+}
+
+impl TrackGenerationConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.length < 300.0 {
+            return Err("Generated track must be at least 300 m long".to_string());
+        }
+        if self.width <= 0.0 {
+            return Err("Track width must be positive".to_string());
+        }
+        if self.corner_density <= 0.0 {
+            return Err("Corner density must be positive".to_string());
+        }
+        if self.elevation < 0.0 {
+            return Err("Elevation must not be negative".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Кольцевая осевая линия по зерну: контрольные точки обходят центр по кругу с разбросом
+/// направления и удаления, поэтому кольцо не пересекает само себя; высота меняется
+/// периодически по углу, чтобы круг замыкался без ступеньки
+pub fn generate_track_spline(seed: u64, config: &TrackGenerationConfig) -> Result<TrackSpline, String> {
+    config.validate()?;
+    let mut rng = SimpleRng::new(seed);
+    let count = ((config.length / 1000.0 * config.corner_density).round() as usize).clamp(MIN_CONTROL_POINTS, MAX_CONTROL_POINTS);
+    let step = TAU / count as f32;
+    let phases = [rng.range(0.0, TAU), rng.range(0.0, TAU)];
+    let shape: Vec<(f32, f32, f32)> = (0..count)
+        .map(|i| {
+            let angle = (i as f32 + rng.range(-ANGULAR_JITTER, ANGULAR_JITTER)) * step;
+            let radial = rng.range(1.0 - RADIAL_JITTER, 1.0 + RADIAL_JITTER);
+            let height = config.elevation
+                * (0.5 + 0.25 * (2.0 * angle + phases[0]).sin() + 0.25 * (3.0 * angle + phases[1]).sin());
+            (angle, radial, height)
+        })
+        .collect();
+
+    let build = |radius: f32| {
+        let points = shape
+            .iter()
+            .map(|&(angle, radial, height)| {
+                let position = Vec3::new(angle.cos() * radius * radial, height, angle.sin() * radius * radial);
+                SplinePoint::new(position, config.width)
+            })
+            .collect();
+        TrackSpline::catmull_rom(points, true)
+    };
+
+    // Длина кривой почти пропорциональна радиусу, несколько проходов дают точную подгонку
+    let mut radius = config.length / TAU;
+    let mut spline = build(radius);
+    for _ in 0..LENGTH_FIT_PASSES {
+        let length = spline.length();
+        if length <= f32::EPSILON {
+            return Err("Generated track spline has zero length".to_string());
+        }
+        radius *= config.length / length;
+        spline = build(radius);
+    }
+    Ok(spline)
+}
+
+/// Создает случайную кольцевую трассу по зерну: одно зерно всегда дает ту же трассу
+pub fn generate_random_track(world: &mut World, seed: u64, config: &TrackGenerationConfig) -> Result<hecs::Entity, String> {
+    let spline = generate_track_spline(seed, config)?;
+    let segments = create_spline_segments(world, &spline)?;
+    let length = spline.length();
+
+    // Чекпоинты через равные промежутки, первый — линия старта и финиша
+    let checkpoint_count = ((length / CHECKPOINT_SPACING).round() as usize).max(3);
+    let checkpoints = (0..checkpoint_count)
+        .map(|index| {
+            let sample = spline.sample_at_distance(length * index as f32 / checkpoint_count as f32);
+            world.spawn((
+                CheckpointComponent {
+                    index,
+                    width: config.width,
+                    is_finish_line: index == 0,
+                    is_start_line: false,
+                },
+                TransformComponent {
+                    position: sample.position,
+                    rotation: Quat::from_rotation_y(sample.tangent.x.atan2(sample.tangent.z)),
+                    ..Default::default()
+                },
+            ))
+        })
+        .collect();
+
+    // Стартовая решетка позади линии старта в начале кольца
+    let start_positions = spline.start_grid(0.0, DEFAULT_GRID_SLOTS);
+
+    let track = TrackComponent {
+        name: format!("Random {:08X}", seed),
+        layout: "Generated".to_string(),
+        length,
+        segments,
+        checkpoints,
+        props: Vec::new(),
+        start_positions,
+        pit_lane: Vec::new(),
+        point_to_point: false,
+        metadata: TrackMetadata::default(),
+    };
+    // Осевая линия остается на трассе, чтобы трассу можно было сохранить в файл
+    Ok(world.spawn((track, spline)))
+}
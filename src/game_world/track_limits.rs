@@ -0,0 +1,109 @@
+use crate::core::ecs::System;
+use crate::core::physics::TransformComponent;
+use crate::game_world::car::{CarWheelBindingComponent, WheelComponent};
+use crate::game_world::track::{on_track, publish_frame_events, SurfaceType};
+use crate::gameplay::rules::current_rules;
+use glam::Vec3;
+use hecs::{Entity, World};
+
+/// Начало или конец выезда за пределы трассы
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OffTrackPhase {
+    Started,
+    Ended,
+}
+
+/// Выезд машины за пределы трассы; очередь `EventQueue<OffTrackEvent>` обновляется каждый кадр
+#[derive(Debug, Clone)]
+pub struct OffTrackEvent {
+    pub car_entity: Entity,
+    pub phase: OffTrackPhase,
+    pub duration: f32, // Длительность выезда, с; при Started - 0
+}
+
+/// Положение машины относительно границ трассы; добавляется системой границ трассы
+#[derive(Debug, Clone, Default)]
+pub struct TrackLimitsComponent {
+    pub wheels_off: usize, // Колес за пределами полотна
+    pub off_track: bool,   // За пределами больше половины колес
+    pub off_time: f32,     // Длительность текущего выезда, с
+}
+
+/// Покрытия обочины, на которых правила могут дополнительно тормозить машину
+fn is_runoff(surface: SurfaceType) -> bool {
+    matches!(surface, SurfaceType::Grass | SurfaceType::Gravel | SurfaceType::Dirt | SurfaceType::Sand)
+}
+
+/// Границы трассы по пятнам контакта колес: машина за пределами, когда за краем полотна
+/// больше половины колес. Колеса на траве и гравии по правилам получают добавочное сопротивление
+pub struct TrackLimitsSystem;
+
+impl System for TrackLimitsSystem {
+    fn update(&mut self, world: &mut World, delta_time: f32) {
+        let slowdown = current_rules(world).off_track_slowdown.max(1.0);
+
+        let mut cars = Vec::new();
+        let mut runoff_wheels = Vec::new();
+        for (_, binding) in world.query::<&CarWheelBindingComponent>().iter() {
+            let Ok(car_transform) = world.get::<&TransformComponent>(binding.car_entity) else {
+                continue;
+            };
+            let mut wheels_off = 0;
+            for &wheel_entity in &binding.wheel_entities {
+                let Ok(wheel) = world.get::<&WheelComponent>(wheel_entity) else {
+                    continue;
+                };
+                let contact = car_transform.position + car_transform.rotation * (wheel.position - Vec3::Y * wheel.radius);
+                if !on_track(world, contact) {
+                    wheels_off += 1;
+                    if is_runoff(wheel.surface_type) {
+                        runoff_wheels.push(wheel_entity);
+                    }
+                }
+            }
+            cars.push((binding.car_entity, wheels_off, wheels_off * 2 > binding.wheel_entities.len()));
+        }
+
+        // Сопротивление покрытия выставляется заново каждый кадр, множитель не накапливается
+        if slowdown > 1.0 {
+            for wheel_entity in runoff_wheels {
+                if let Ok(wheel) = world.query_one_mut::<&mut WheelComponent>(wheel_entity) {
+                    wheel.surface_rolling_resistance *= slowdown;
+                }
+            }
+        }
+
+        let mut events = Vec::new();
+        for (car_entity, wheels_off, off_track) in cars {
+            if world.get::<&TrackLimitsComponent>(car_entity).is_err() {
+                let _ = world.insert_one(car_entity, TrackLimitsComponent::default());
+            }
+            let Ok(limits) = world.query_one_mut::<&mut TrackLimitsComponent>(car_entity) else {
+                continue;
+            };
+            limits.wheels_off = wheels_off;
+            match (limits.off_track, off_track) {
+                (false, true) => {
+                    limits.off_time = 0.0;
+                    events.push(OffTrackEvent { car_entity, phase: OffTrackPhase::Started, duration: 0.0 });
+                }
+                (true, true) => limits.off_time += delta_time,
+                (true, false) => {
+                    events.push(OffTrackEvent { car_entity, phase: OffTrackPhase::Ended, duration: limits.off_time });
+                    limits.off_time = 0.0;
+                }
+                (false, false) => {}
+            }
+            limits.off_track = off_track;
+        }
+        publish_frame_events(world, events);
+    }
+}
+
+/// Находится ли машина на трассе: по колесам, если их проверяет система границ, иначе по центру кузова
+pub fn car_on_track(world: &World, car_entity: Entity, position: Vec3) -> bool {
+    match world.get::<&TrackLimitsComponent>(car_entity) {
+        Ok(limits) => !limits.off_track,
+        Err(_) => on_track(world, position),
+    }
+}
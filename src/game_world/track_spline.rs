@@ -0,0 +1,387 @@
+use crate::core::physics::TransformComponent;
+use crate::game_world::track::{create_track_segment, GridSlot, SurfaceBand, SurfaceType, TrackSegmentComponent, TrackSegmentType};
+use glam::{Quat, Vec3};
+use hecs::World;
+use serde::{Deserialize, Serialize};
+
+/// Длина сегментов, на которые режется сплайн, м; последний сегмент короче
+pub const TRACK_SPLINE_SEGMENT_LENGTH: f32 = 20.0;
+
+/// Выборок на пролет сплайна при построении таблицы длин
+const SAMPLES_PER_SPAN: usize = 32;
+
+/// Вираж, начиная с которого сегмент считается виражом, рад
+const BANKED_SEGMENT_ANGLE: f32 = 0.02;
+
+/// Кривизна, ниже которой сегмент считается прямым, 1/м
+const STRAIGHT_SEGMENT_CURVATURE: f32 = 1e-4;
+
+/// Мест на стартовой решетке, если трасса не задает свои
+pub const DEFAULT_GRID_SLOTS: usize = 8;
+
+/// От линии старта до первого места решетки, м
+const GRID_FIRST_SLOT_OFFSET: f32 = 4.0;
+
+/// Шаг вдоль трассы между соседними местами решетки, м: машины стоят в шахматном порядке
+const GRID_SLOT_SPACING: f32 = 6.0;
+
+/// Длина разгона трамплина перед кромкой, м
+pub const JUMP_RAMP_LENGTH: f32 = 12.0;
+
+/// Стыки сегментов ближе этого сливаются, м
+const MIN_SEGMENT_LENGTH: f32 = 1.0;
+
+/// Вид кривой между контрольными точками
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SplineKind {
+    CatmullRom, // Кривая проходит через все точки
+    Bezier,     // Кубические кривые: опорная, две управляющие, опорная, ...
+}
+
+/// Контрольная точка трассы: ширина, вираж и покрытие действуют до следующей опорной точки
+#[derive(Debug, Clone)]
+pub struct SplinePoint {
+    pub position: Vec3,
+    pub width: f32,
+    pub banking: f32, // рад, положительный поднимает край полотна со стороны +X
+    pub surface: SurfaceType,
+    pub bands: Vec<SurfaceBand>, // Поребрики и обочины поперек трассы до следующей опорной точки
+    pub jump_height: f32, // Высота кромки трамплина, который заканчивается в этой опорной точке, м
+}
+
+impl SplinePoint {
+    pub fn new(position: Vec3, width: f32) -> Self {
+        Self {
+            position,
+            width,
+            banking: 0.0,
+            surface: SurfaceType::Asphalt,
+            bands: Vec::new(),
+            jump_height: 0.0,
+        }
+    }
+}
+
+/// Точка на сплайне
+#[derive(Debug, Clone, Copy)]
+pub struct SplineSample {
+    pub position: Vec3,
+    pub tangent: Vec3, // Единичное направление движения
+    pub width: f32,
+    pub banking: f32,
+    pub surface: SurfaceType,
+}
+
+/// Осевая линия трассы по контрольным точкам
+#[derive(Debug, Clone)]
+pub struct TrackSpline {
+    pub kind: SplineKind,
+    pub points: Vec<SplinePoint>,
+    pub closed: bool, // Кольцо: последний пролет возвращается к первой точке
+}
+
+impl TrackSpline {
+    pub fn catmull_rom(points: Vec<SplinePoint>, closed: bool) -> Self {
+        Self {
+            kind: SplineKind::CatmullRom,
+            points,
+            closed,
+        }
+    }
+
+    pub fn bezier(points: Vec<SplinePoint>, closed: bool) -> Self {
+        Self {
+            kind: SplineKind::Bezier,
+            points,
+            closed,
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        let count = self.points.len();
+        match self.kind {
+            SplineKind::CatmullRom if count < 2 || (self.closed && count < 3) => {
+                return Err("Catmull-Rom track needs at least two points, three when closed".to_string());
+            }
+            SplineKind::Bezier if count < 4 || !(count - usize::from(!self.closed)).is_multiple_of(3) => {
+                return Err("Bezier track needs 3n+1 points, 3n when closed".to_string());
+            }
+            _ => {}
+        }
+        if self.points.iter().any(|point| point.width <= 0.0) {
+            return Err("Track width must be positive".to_string());
+        }
+        if self.points.iter().any(|point| !(point.jump_height >= 0.0 && point.jump_height.is_finite())) {
+            return Err("Jump height must be non-negative".to_string());
+        }
+        Ok(())
+    }
+
+    /// Число пролетов кривой
+    pub fn span_count(&self) -> usize {
+        let count = self.points.len();
+        match (self.kind, self.closed) {
+            (SplineKind::CatmullRom, false) => count.saturating_sub(1),
+            (SplineKind::CatmullRom, true) => count,
+            (SplineKind::Bezier, _) => count / 3,
+        }
+    }
+
+    fn point(&self, index: usize) -> &SplinePoint {
+        &self.points[index % self.points.len()]
+    }
+
+    /// Опорные точки пролета, между которыми интерполируются ширина и вираж
+    fn span_anchors(&self, span: usize) -> (&SplinePoint, &SplinePoint) {
+        match self.kind {
+            SplineKind::CatmullRom => (self.point(span), self.point(span + 1)),
+            SplineKind::Bezier => (self.point(span * 3), self.point(span * 3 + 3)),
+        }
+    }
+
+    /// Положение на пролете, local от 0 до 1
+    fn span_position(&self, span: usize, local: f32) -> Vec3 {
+        match self.kind {
+            SplineKind::CatmullRom => {
+                let count = self.points.len();
+                let p1 = self.point(span).position;
+                let p2 = self.point(span + 1).position;
+                // Концы открытой трассы продолжаются по прямой
+                let p0 = if self.closed || span > 0 { self.point(span + count - 1).position } else { p1 * 2.0 - p2 };
+                let p3 = if self.closed || span + 2 < count { self.point(span + 2).position } else { p2 * 2.0 - p1 };
+
+                let t2 = local * local;
+                let t3 = t2 * local;
+                0.5 * (2.0 * p1
+                    + (p2 - p0) * local
+                    + (2.0 * p0 - 5.0 * p1 + 4.0 * p2 - p3) * t2
+                    + (3.0 * p1 - p0 - 3.0 * p2 + p3) * t3)
+            }
+            SplineKind::Bezier => {
+                let [p0, p1, p2, p3] = [0, 1, 2, 3].map(|i| self.point(span * 3 + i).position);
+                let u = 1.0 - local;
+                p0 * (u * u * u) + p1 * (3.0 * u * u * local) + p2 * (3.0 * u * local * local) + p3 * (local * local * local)
+            }
+        }
+    }
+
+    /// Точка на сплайне, t от 0 (начало) до 1 (конец трассы)
+    pub fn sample(&self, t: f32) -> SplineSample {
+        let spans = self.span_count().max(1);
+        let scaled = t.clamp(0.0, 1.0) * spans as f32;
+        let span = (scaled.floor() as usize).min(spans - 1);
+        let local = scaled - span as f32;
+
+        let position = self.span_position(span, local);
+        // Касательная по малому приращению внутри пролета
+        let step = 1e-3;
+        let tangent = if local + step <= 1.0 {
+            self.span_position(span, local + step) - position
+        } else {
+            position - self.span_position(span, local - step)
+        };
+        let (start, end) = self.span_anchors(span);
+        SplineSample {
+            position,
+            tangent: tangent.normalize_or_zero(),
+            width: start.width + (end.width - start.width) * local,
+            banking: start.banking + (end.banking - start.banking) * local,
+            surface: start.surface,
+        }
+    }
+
+    /// Полосы покрытия в точке сплайна: действуют полосы начальной опорной точки пролета
+    pub fn bands_at(&self, t: f32) -> &[SurfaceBand] {
+        let spans = self.span_count().max(1);
+        let span = ((t.clamp(0.0, 1.0) * spans as f32).floor() as usize).min(spans - 1);
+        &self.span_anchors(span).0.bands
+    }
+
+    /// Таблица (параметр, пройденная длина) по всей трассе
+    fn arc_length_table(&self) -> Vec<(f32, f32)> {
+        let samples = self.span_count().max(1) * SAMPLES_PER_SPAN;
+        let mut table = Vec::with_capacity(samples + 1);
+        let mut previous = self.sample(0.0).position;
+        let mut length = 0.0;
+        table.push((0.0, 0.0));
+        for i in 1..=samples {
+            let t = i as f32 / samples as f32;
+            let position = self.sample(t).position;
+            length += position.distance(previous);
+            previous = position;
+            table.push((t, length));
+        }
+        table
+    }
+
+    /// Кромки трамплинов: пройденная длина до опорной точки с трамплином и высота кромки.
+    /// Первая точка открытой трассы трамплина не получает: перед ней нет разгона
+    fn jump_lips(&self, table: &[(f32, f32)]) -> Vec<(f32, f32)> {
+        (1..=self.span_count())
+            .filter_map(|span| {
+                let anchor = self.span_anchors(span - 1).1;
+                let (_, distance) = table[(span * SAMPLES_PER_SPAN).min(table.len() - 1)];
+                (anchor.jump_height > 0.0).then_some((distance, anchor.jump_height))
+            })
+            .collect()
+    }
+
+    /// Длина осевой линии, м
+    pub fn length(&self) -> f32 {
+        self.arc_length_table().last().map_or(0.0, |&(_, length)| length)
+    }
+
+    /// Точка на пройденной от начала длине осевой линии, м
+    pub fn sample_at_distance(&self, distance: f32) -> SplineSample {
+        let table = self.arc_length_table();
+        self.sample(parameter_at(&table, distance))
+    }
+
+    /// Пройденная длина до ближайшей к точке выборки осевой линии, м
+    pub fn nearest_distance(&self, position: Vec3) -> f32 {
+        self.arc_length_table()
+            .into_iter()
+            .map(|(t, length)| (self.sample(t).position.distance_squared(position), length))
+            .min_by(|a, b| a.0.total_cmp(&b.0))
+            .map_or(0.0, |(_, length)| length)
+    }
+
+    /// Место решетки на пройденной длине со смещением влево (+X) от осевой линии, м;
+    /// машина повернута по курсу, уклону и виражу полотна
+    pub fn grid_slot(&self, distance: f32, offset: f32) -> GridSlot {
+        let sample = self.sample_at_distance(distance);
+        let rotation = Quat::from_rotation_y(yaw_of(sample.tangent))
+            * Quat::from_rotation_x(-slope_of(sample.tangent))
+            * Quat::from_rotation_z(sample.banking);
+        GridSlot {
+            position: sample.position + rotation * Vec3::X * offset,
+            rotation,
+        }
+    }
+
+    /// Шахматная решетка позади линии старта на пройденной длине start_distance:
+    /// места чередуются по сторонам, каждое следующее дальше от линии
+    pub fn start_grid(&self, start_distance: f32, slots: usize) -> Vec<GridSlot> {
+        let length = self.length();
+        (0..slots)
+            .map(|slot| {
+                let behind = start_distance - GRID_FIRST_SLOT_OFFSET - GRID_SLOT_SPACING * slot as f32;
+                // Кольцо продолжается за началом сплайна, открытая трасса - по прямой вдоль первой касательной
+                let distance = if self.closed { behind.rem_euclid(length) } else { behind.max(0.0) };
+                let side = if slot % 2 == 0 { 1.0 } else { -1.0 };
+                let width = self.sample_at_distance(distance).width;
+                let mut grid_slot = self.grid_slot(distance, side * width / 4.0);
+                if !self.closed && behind < 0.0 {
+                    grid_slot.position += grid_slot.rotation * Vec3::Z * behind;
+                }
+                grid_slot
+            })
+            .collect()
+    }
+}
+
+/// Параметр сплайна на пройденной длине по таблице длин
+fn parameter_at(table: &[(f32, f32)], distance: f32) -> f32 {
+    let index = table.partition_point(|&(_, length)| length < distance).clamp(1, table.len() - 1);
+    let (t0, l0) = table[index - 1];
+    let (t1, l1) = table[index];
+    if l1 - l0 > f32::EPSILON {
+        t0 + (t1 - t0) * ((distance - l0) / (l1 - l0)).clamp(0.0, 1.0)
+    } else {
+        t1
+    }
+}
+
+/// Курс по направлению в плоскости XZ: 0 вдоль +Z, рост к +X
+fn yaw_of(direction: Vec3) -> f32 {
+    direction.x.atan2(direction.z)
+}
+
+/// Уклон направления к горизонту, рад; положительный - подъем
+fn slope_of(direction: Vec3) -> f32 {
+    direction.y.clamp(-1.0, 1.0).asin()
+}
+
+/// Разность курсов, приведенная к -π..π
+fn wrap_angle(angle: f32) -> f32 {
+    (angle + std::f32::consts::PI).rem_euclid(std::f32::consts::TAU) - std::f32::consts::PI
+}
+
+/// Создает связанные сегменты трассы вдоль сплайна: каждый сегмент стоит серединой
+/// на осевой линии, изгибается по ее кривизне и наследует ширину, вираж и покрытие
+pub fn create_spline_segments(world: &mut World, spline: &TrackSpline) -> Result<Vec<hecs::Entity>, String> {
+    spline.validate()?;
+    let table = spline.arc_length_table();
+    let total_length = table.last().map_or(0.0, |&(_, length)| length);
+    if total_length <= f32::EPSILON {
+        return Err("Track spline has zero length".to_string());
+    }
+
+    // Стыки через равные промежутки; разгон каждого трамплина целиком лежит в одном сегменте,
+    // который кончается кромкой
+    let count = (total_length / TRACK_SPLINE_SEGMENT_LENGTH).ceil().max(1.0) as usize;
+    let lips = spline.jump_lips(&table);
+    let mut cuts: Vec<f32> = (1..count).map(|i| i as f32 * TRACK_SPLINE_SEGMENT_LENGTH).collect();
+    for &(lip, _) in &lips {
+        let ramp_start = lip - JUMP_RAMP_LENGTH;
+        cuts.retain(|&cut| cut <= ramp_start - MIN_SEGMENT_LENGTH || cut >= lip + MIN_SEGMENT_LENGTH);
+        cuts.extend([ramp_start, lip]);
+    }
+    cuts.retain(|&cut| cut >= MIN_SEGMENT_LENGTH && cut <= total_length - MIN_SEGMENT_LENGTH);
+    cuts.sort_by(f32::total_cmp);
+    cuts.dedup_by(|a, b| (*a - *b).abs() < MIN_SEGMENT_LENGTH);
+    let bounds: Vec<f32> = std::iter::once(0.0).chain(cuts).chain(std::iter::once(total_length)).collect();
+
+    let mut entities = Vec::with_capacity(bounds.len() - 1);
+    for pair in bounds.windows(2) {
+        let (start, end) = (pair[0], pair[1]);
+        let length = end - start;
+        let ramp_height = lips
+            .iter()
+            .find(|(lip, _)| (lip - end).abs() < MIN_SEGMENT_LENGTH)
+            .map_or(0.0, |&(_, height)| height);
+        let first = spline.sample(parameter_at(&table, start));
+        let middle_parameter = parameter_at(&table, (start + end) / 2.0);
+        let middle = spline.sample(middle_parameter);
+        let last = spline.sample(parameter_at(&table, end));
+
+        let curvature = wrap_angle(yaw_of(last.tangent) - yaw_of(first.tangent)) / length.max(f32::EPSILON);
+        let segment_type = if ramp_height > 0.0 {
+            TrackSegmentType::Jump
+        } else if middle.banking.abs().max(first.banking.abs()).max(last.banking.abs()) > BANKED_SEGMENT_ANGLE {
+            TrackSegmentType::Banked
+        } else if curvature > STRAIGHT_SEGMENT_CURVATURE {
+            TrackSegmentType::LeftCurve
+        } else if curvature < -STRAIGHT_SEGMENT_CURVATURE {
+            TrackSegmentType::RightCurve
+        } else {
+            TrackSegmentType::Straight
+        };
+        let segment = TrackSegmentComponent {
+            segment_type,
+            length,
+            width: middle.width,
+            curvature,
+            // Вираж по концам сегмента: соседние сегменты сходятся кромками без ступенек
+            banking: (first.banking + last.banking) / 2.0,
+            banking_change: last.banking - first.banking,
+            // Уклон меняется от начала к концу сегмента: гребни и впадины без изломов на стыках
+            vertical_curvature: (slope_of(last.tangent) - slope_of(first.tangent)) / length.max(f32::EPSILON),
+            surface_type: middle.surface,
+            material: middle.surface.material(),
+            surface_bands: spline.bands_at(middle_parameter).to_vec(),
+            ramp_height,
+        };
+
+        // Курс и уклон по касательной в середине сегмента
+        let pitch = slope_of(middle.tangent);
+        let transform = TransformComponent {
+            position: middle.position,
+            rotation: Quat::from_rotation_y(yaw_of(middle.tangent)) * Quat::from_rotation_x(-pitch),
+            ..Default::default()
+        };
+        entities.push(create_track_segment(world, segment, transform));
+    }
+    Ok(entities)
+}
@@ -0,0 +1,143 @@
+use crate::core::ecs::Resource;
+use crate::core::physics::RigidBodyComponent;
+use crate::game_world::car::{CarComponent, CarWheelBindingComponent, WheelComponent};
+use hecs::World;
+use rapier3d::prelude::{ColliderSet, RigidBodySet};
+use serde::{Deserialize, Serialize};
+
+/// Наибольший уровень каждого вида доработок
+pub const MAX_UPGRADE_LEVEL: u8 = 3;
+
+/// Прибавка на уровень: момент двигателя, сцепление шин, тормозное усилие и доля снятой массы
+const ENGINE_TORQUE_PER_STAGE: f32 = 0.08;
+const TIRE_GRIP_PER_LEVEL: f32 = 0.05;
+const BRAKE_FORCE_PER_LEVEL: f32 = 0.1;
+const WEIGHT_REDUCTION_PER_LEVEL: f32 = 0.04;
+
+/// Вид доработки машины
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum UpgradeCategory {
+    Engine,
+    Tires,
+    Brakes,
+    WeightReduction,
+}
+
+impl UpgradeCategory {
+    pub fn label(self) -> &'static str {
+        match self {
+            UpgradeCategory::Engine => "Двигатель",
+            UpgradeCategory::Tires => "Шины",
+            UpgradeCategory::Brakes => "Тормоза",
+            UpgradeCategory::WeightReduction => "Облегчение",
+        }
+    }
+
+    /// Цена перехода на уровень `level`; каждая ступень дороже предыдущей
+    pub fn price(self, level: u8) -> u32 {
+        let base = match self {
+            UpgradeCategory::Engine => 8000,
+            UpgradeCategory::Tires => 3000,
+            UpgradeCategory::Brakes => 4000,
+            UpgradeCategory::WeightReduction => 6000,
+        };
+        base * level as u32 * level as u32
+    }
+}
+
+/// Установленные доработки машины, уровни 0..=MAX_UPGRADE_LEVEL
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CarUpgrades {
+    pub engine: u8,
+    pub tires: u8,
+    pub brakes: u8,
+    pub weight_reduction: u8,
+}
+
+impl CarUpgrades {
+    pub fn level(&self, category: UpgradeCategory) -> u8 {
+        match category {
+            UpgradeCategory::Engine => self.engine,
+            UpgradeCategory::Tires => self.tires,
+            UpgradeCategory::Brakes => self.brakes,
+            UpgradeCategory::WeightReduction => self.weight_reduction,
+        }
+        .min(MAX_UPGRADE_LEVEL)
+    }
+
+    fn level_mut(&mut self, category: UpgradeCategory) -> &mut u8 {
+        match category {
+            UpgradeCategory::Engine => &mut self.engine,
+            UpgradeCategory::Tires => &mut self.tires,
+            UpgradeCategory::Brakes => &mut self.brakes,
+            UpgradeCategory::WeightReduction => &mut self.weight_reduction,
+        }
+    }
+
+    /// Следующий уровень доработки, если он есть
+    pub fn next_level(&self, category: UpgradeCategory) -> Option<u8> {
+        let level = self.level(category);
+        (level < MAX_UPGRADE_LEVEL).then_some(level + 1)
+    }
+
+    /// Повышает уровень доработки на одну ступень
+    pub fn raise(&mut self, category: UpgradeCategory) {
+        let level = self.level_mut(category);
+        *level = (*level + 1).min(MAX_UPGRADE_LEVEL);
+    }
+
+    /// Переносит доработки на характеристики базовой машины
+    pub fn apply_to_car(&self, car: &mut CarComponent) {
+        let torque_scale = 1.0 + ENGINE_TORQUE_PER_STAGE * self.level(UpgradeCategory::Engine) as f32;
+        for (_, torque) in car.torque_curve.iter_mut() {
+            *torque *= torque_scale;
+        }
+        car.max_brake_force *= 1.0 + BRAKE_FORCE_PER_LEVEL * self.level(UpgradeCategory::Brakes) as f32;
+        // Инерция уменьшается вместе с массой при той же развесовке
+        let mass_scale = 1.0 - WEIGHT_REDUCTION_PER_LEVEL * self.level(UpgradeCategory::WeightReduction) as f32;
+        car.mass *= mass_scale;
+        car.inertia *= mass_scale;
+    }
+
+    /// Переносит доработки на колесо
+    pub fn apply_to_wheel(&self, wheel: &mut WheelComponent) {
+        wheel.friction *= 1.0 + TIRE_GRIP_PER_LEVEL * self.level(UpgradeCategory::Tires) as f32;
+    }
+}
+
+/// Применяет доработки к только что созданной машине, ее колесам и телу rapier
+pub fn apply_car_upgrades(world: &mut World, car_entity: hecs::Entity, upgrades: &CarUpgrades) {
+    let body = world
+        .query_one_mut::<(&mut CarComponent, &RigidBodyComponent)>(car_entity)
+        .ok()
+        .map(|(car, body)| {
+            upgrades.apply_to_car(car);
+            (body.handle, car.mass_properties())
+        });
+    let wheels: Vec<hecs::Entity> = world
+        .query::<&CarWheelBindingComponent>()
+        .iter()
+        .filter(|(_, binding)| binding.car_entity == car_entity)
+        .flat_map(|(_, binding)| binding.wheel_entities.clone())
+        .collect();
+    for wheel_entity in wheels {
+        if let Ok(mut wheel) = world.get::<&mut WheelComponent>(wheel_entity) {
+            upgrades.apply_to_wheel(&mut wheel);
+        }
+    }
+
+    // Облегченный кузов должен сразу стать легче и для физики
+    let Some((handle, properties)) = body else {
+        return;
+    };
+    if let Some((_, resource)) = world
+        .query_mut::<&mut Resource<(RigidBodySet, ColliderSet)>>()
+        .into_iter()
+        .next()
+    {
+        if let Some(body) = resource.0 .0.get_mut(handle) {
+            body.set_additional_mass_properties(properties, true);
+        }
+    }
+}
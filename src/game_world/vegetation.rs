@@ -0,0 +1,133 @@
+use crate::core::ecs::{System, Resource};
+use crate::core::random::SimpleRng;
+use crate::core::renderer::{VegetationInstance, VegetationRenderData};
+use crate::game_world::environment::WeatherComponent;
+use crate::game_world::terrain::TerrainComponent;
+use glam::Vec3;
+use hecs::World;
+
+/// Типы растительности
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VegetationKind {
+    Grass,
+    Tree,
+}
+
+/// Параметры разброса одного типа растительности
+#[derive(Debug, Clone)]
+pub struct VegetationLayerConfig {
+    pub kind: VegetationKind,
+    pub density: f32,       // Экземпляров на квадратный метр
+    pub min_scale: f32,
+    pub max_scale: f32,
+    pub max_slope: f32,     // Максимальный уклон (1 - normal.y)
+    pub flexibility: f32,   // Насколько сильно растение гнется от ветра
+    pub color: [f32; 4],
+}
+
+impl VegetationLayerConfig {
+    pub fn grass() -> Self {
+        Self {
+            kind: VegetationKind::Grass,
+            density: 0.05,
+            min_scale: 0.4,
+            max_scale: 0.9,
+            max_slope: 0.3,
+            flexibility: 0.6,
+            color: [0.35, 0.6, 0.2, 1.0],
+        }
+    }
+
+    pub fn trees() -> Self {
+        Self {
+            kind: VegetationKind::Tree,
+            density: 0.002,
+            min_scale: 6.0,
+            max_scale: 11.0,
+            max_slope: 0.2,
+            flexibility: 0.08,
+            color: [0.15, 0.4, 0.12, 1.0],
+        }
+    }
+}
+
+/// Разбрасывает растительность по ландшафту; `exclude` отсекает точки (например, полотно трассы)
+pub fn scatter_vegetation(
+    terrain: &TerrainComponent,
+    layers: &[VegetationLayerConfig],
+    seed: u64,
+    exclude: impl Fn(Vec3) -> bool,
+) -> Vec<VegetationInstance> {
+    let mut rng = SimpleRng::new(seed);
+    let origin = terrain.config.origin;
+    let area = terrain.config.size_x * terrain.config.size_z;
+    let mut instances = Vec::new();
+
+    for layer in layers {
+        let count = (area * layer.density) as usize;
+        for _ in 0..count {
+            let x = origin.x + rng.range(0.0, terrain.config.size_x);
+            let z = origin.z + rng.range(0.0, terrain.config.size_z);
+            let scale = rng.range(layer.min_scale, layer.max_scale);
+            let phase = rng.range(0.0, std::f32::consts::TAU);
+
+            let Some(y) = terrain.height_at(x, z) else {
+                continue;
+            };
+            let position = Vec3::new(x, y, z);
+            if 1.0 - terrain.normal_at(x, z).y > layer.max_slope || exclude(position) {
+                continue;
+            }
+
+            let kind = match layer.kind {
+                VegetationKind::Grass => 0.0,
+                VegetationKind::Tree => 1.0,
+            };
+
+            instances.push(VegetationInstance {
+                position_scale: [x, y, z, scale],
+                params: [phase, layer.flexibility, kind, 0.0],
+                color: layer.color,
+            });
+        }
+    }
+
+    instances
+}
+
+/// Создает ресурс растительности для рендеринга
+pub fn create_vegetation(world: &mut World, instances: Vec<VegetationInstance>) -> hecs::Entity {
+    let data = VegetationRenderData {
+        instances,
+        revision: 0,
+        wind_direction: [1.0, 0.0],
+        wind_strength: 0.0,
+        time: 0.0,
+    };
+
+    world.spawn((Resource(data),))
+}
+
+/// Система анимации растительности: передает параметры ветра от погоды в рендер
+pub struct VegetationSystem;
+
+impl System for VegetationSystem {
+    fn update(&mut self, world: &mut World, delta_time: f32) {
+        let wind = world
+            .query::<&WeatherComponent>()
+            .iter()
+            .next()
+            .map(|(_, weather)| (weather.wind_direction, weather.wind_strength));
+
+        for (_, vegetation) in world.query_mut::<&mut Resource<VegetationRenderData>>() {
+            let vegetation = &mut vegetation.0;
+            vegetation.time += delta_time;
+
+            if let Some((direction, strength)) = wind {
+                let planar = Vec3::new(direction.x, 0.0, direction.z).normalize_or_zero();
+                vegetation.wind_direction = [planar.x, planar.z];
+                vegetation.wind_strength = strength;
+            }
+        }
+    }
+}
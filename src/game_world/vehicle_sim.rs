@@ -0,0 +1,237 @@
+use crate::core::ecs::{Resource, System};
+use crate::core::physics::{physics_materials, PhysicsMaterialRegistry, PhysicsSystem, TransformComponent};
+use crate::game_world::car::{auto_shift, update_launch_control, CarComponent, CarPhysicsSystem, CarWheelBindingComponent, WheelComponent, REVERSE_GEAR};
+use crate::game_world::car_builder::CarBuilder;
+use crate::game_world::car_definition::CarDefinition;
+use crate::game_world::track::SurfaceType;
+use glam::{Quat, Vec3};
+use hecs::World;
+use rapier3d::prelude::{ColliderBuilder, ColliderSet, RigidBodyBuilder, RigidBodySet};
+use serde::Serialize;
+
+/// Высота, с которой машина опускается на подвеску
+const SPAWN_HEIGHT: f32 = 1.1;
+
+/// Половина стороны ровной площадки под машиной, м
+const GROUND_HALF_EXTENT: f32 = 2000.0;
+
+/// Синтетический ввод на шаг симуляции
+#[derive(Debug, Clone, Copy, Default)]
+pub struct VehicleInputs {
+    pub throttle: f32,
+    pub brake: f32,
+    pub handbrake: f32,
+    pub steering: f32,     // -1..1, положительный уводит машину к -X; без сглаживания руля
+    pub gear: Option<i32>, // Без передачи коробка переключается автоматически
+}
+
+/// Состояние колеса после шага
+#[derive(Debug, Clone, Serialize)]
+pub struct WheelSample {
+    pub grounded: bool,
+    pub suspension_length: f32,
+    pub suspension_force: f32,
+    pub wheel_speed: f32,
+    pub slip_ratio: f32,
+    pub slip_angle: f32,
+    pub lateral_force: f32,
+    pub longitudinal_force: f32,
+}
+
+/// Состояние машины после шага; координаты массивами, чтобы сэмплы сохранялись в JSON
+#[derive(Debug, Clone, Serialize)]
+pub struct VehicleSample {
+    pub time: f32,
+    pub position: [f32; 3],
+    pub speed: f32, // Продольная скорость, м/с
+    pub rpm: f32,
+    pub gear: i32,
+    pub wheels: Vec<WheelSample>,
+}
+
+/// Машина на ровной площадке без окна и рендера: калькуляторы настроек,
+/// симуляторы круга и проверки шагают ту же модель, что и игра, синтетическим вводом.
+/// Отдельные модели двигателя, шины и подвески доступны в car: drive_torque, engine_rpm,
+/// tire_forces, suspension_force
+pub struct VehicleSimulation {
+    world: World,
+    physics: PhysicsSystem,
+    car_physics: CarPhysicsSystem,
+    car: hecs::Entity,
+    surface: SurfaceType,
+    time: f32,
+}
+
+impl VehicleSimulation {
+    /// Машина по описанию: с ее колесами и кузовом, как в игре
+    pub fn from_definition(definition: &CarDefinition, surface: SurfaceType) -> Self {
+        let mut world = World::new();
+        let mut rigid_body_set = RigidBodySet::new();
+        let mut collider_set = ColliderSet::new();
+        let ground = rigid_body_set.insert(RigidBodyBuilder::fixed().translation([0.0, -0.5, 0.0].into()).build());
+        collider_set.insert_with_parent(
+            PhysicsMaterialRegistry::new()
+                .apply(surface.material(), ColliderBuilder::cuboid(GROUND_HALF_EXTENT, 0.5, GROUND_HALF_EXTENT))
+                .build(),
+            ground,
+            &mut rigid_body_set,
+        );
+        world.spawn((Resource((rigid_body_set, collider_set)),));
+
+        let entity = CarBuilder::from_definition(definition)
+            .at(Vec3::Y * SPAWN_HEIGHT, Quat::IDENTITY)
+            .spawn(&mut world)
+            .expect("simulated car must be valid");
+
+        Self {
+            world,
+            physics: PhysicsSystem::new(),
+            car_physics: CarPhysicsSystem::new(),
+            car: entity,
+            surface,
+            time: 0.0,
+        }
+    }
+
+    /// Мир симуляции, например для своих колес или настроек подвески
+    pub fn world_mut(&mut self) -> &mut World {
+        &mut self.world
+    }
+
+    pub fn car_entity(&self) -> hecs::Entity {
+        self.car
+    }
+
+    /// Машина стоит без ввода, пока подвеска не успокоится
+    pub fn settle(&mut self, duration: f32, delta_time: f32) {
+        self.run(duration, delta_time, |_| VehicleInputs::default());
+        self.time = 0.0;
+    }
+
+    pub fn step(&mut self, inputs: &VehicleInputs, delta_time: f32) -> VehicleSample {
+        if let Ok(mut car) = self.world.get::<&mut CarComponent>(self.car) {
+            car.throttle = inputs.throttle.clamp(0.0, 1.0);
+            car.brake = inputs.brake.clamp(0.0, 1.0);
+            car.handbrake = inputs.handbrake.clamp(0.0, 1.0);
+            car.current_steering = inputs.steering.clamp(-1.0, 1.0) * car.max_steering_angle;
+            match inputs.gear {
+                Some(gear) => car.current_gear = gear.clamp(REVERSE_GEAR, car.top_gear()),
+                None => auto_shift(&mut car),
+            }
+            update_launch_control(&mut car);
+        }
+        let material = physics_materials(&self.world).get(self.surface.material()).clone();
+        for (_, wheel) in self.world.query_mut::<&mut WheelComponent>() {
+            wheel.surface_type = self.surface;
+            wheel.surface_grip = material.friction;
+            wheel.surface_rolling_resistance = material.rolling_resistance;
+        }
+
+        self.car_physics.update(&mut self.world, delta_time);
+        self.physics.process(&mut self.world, delta_time);
+        self.time += delta_time;
+        self.sample()
+    }
+
+    /// Прогон на заданное время; ввод выбирается по времени от начала прогона
+    pub fn run(&mut self, duration: f32, delta_time: f32, mut inputs: impl FnMut(f32) -> VehicleInputs) -> Vec<VehicleSample> {
+        let mut samples = Vec::new();
+        let start = self.time;
+        while self.time - start < duration {
+            let frame_inputs = inputs(self.time - start);
+            samples.push(self.step(&frame_inputs, delta_time));
+        }
+        samples
+    }
+
+    pub fn sample(&self) -> VehicleSample {
+        let position = self
+            .world
+            .get::<&TransformComponent>(self.car)
+            .map(|t| t.position)
+            .unwrap_or(Vec3::ZERO);
+        let wheels = self
+            .world
+            .query::<&CarWheelBindingComponent>()
+            .iter()
+            .filter(|(_, binding)| binding.car_entity == self.car)
+            .flat_map(|(_, binding)| binding.wheel_entities.clone())
+            .filter_map(|entity| {
+                let wheel = self.world.get::<&WheelComponent>(entity).ok()?;
+                Some(WheelSample {
+                    grounded: wheel.grounded,
+                    suspension_length: wheel.suspension_length,
+                    suspension_force: wheel.suspension_force,
+                    wheel_speed: wheel.wheel_speed,
+                    slip_ratio: wheel.slip_ratio,
+                    slip_angle: wheel.slip_angle,
+                    lateral_force: wheel.lateral_force,
+                    longitudinal_force: wheel.longitudinal_force,
+                })
+            })
+            .collect();
+        let (speed, rpm, gear) = self
+            .world
+            .get::<&CarComponent>(self.car)
+            .map(|car| (car.current_speed, car.current_rpm, car.current_gear))
+            .unwrap_or_default();
+
+        VehicleSample {
+            time: self.time,
+            position: position.to_array(),
+            speed,
+            rpm,
+            gear,
+            wheels,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game_world::car_definition::find_car_definition;
+
+    const DT: f32 = 1.0 / 120.0;
+
+    fn simulation() -> VehicleSimulation {
+        let definition = find_car_definition("SportsCar").unwrap();
+        let mut simulation = VehicleSimulation::from_definition(&definition, SurfaceType::Asphalt);
+        simulation.settle(1.0, DT);
+        simulation
+    }
+
+    #[test]
+    fn settles_on_its_wheels() {
+        let sample = simulation().sample();
+        assert_eq!(sample.time, 0.0);
+        assert!(sample.wheels.iter().all(|wheel| wheel.grounded));
+        assert!(sample.speed.abs() < 0.1);
+    }
+
+    #[test]
+    fn accelerates_and_shifts_under_full_throttle() {
+        let samples = simulation().run(8.0, DT, |_| VehicleInputs {
+            throttle: 1.0,
+            ..Default::default()
+        });
+        let last = samples.last().unwrap();
+        assert!(last.speed > 20.0, "speed after 8 s: {}", last.speed);
+        assert!(last.gear > 1);
+        assert!(last.position[2].abs() > 50.0);
+    }
+
+    #[test]
+    fn brakes_to_a_stop() {
+        let mut simulation = simulation();
+        simulation.run(4.0, DT, |_| VehicleInputs {
+            throttle: 1.0,
+            ..Default::default()
+        });
+        let samples = simulation.run(6.0, DT, |_| VehicleInputs {
+            brake: 1.0,
+            ..Default::default()
+        });
+        assert!(samples.last().unwrap().speed.abs() < 0.5);
+    }
+}
@@ -0,0 +1,191 @@
+use crate::core::ecs::System;
+use crate::core::physics::TransformComponent;
+use crate::core::renderer::{RenderComponent, RenderResourceManager};
+use crate::game_world::car::{WheelComponent, AMBIENT_BRAKE_TEMPERATURE};
+use glam::Vec3;
+use hecs::World;
+
+/// Часть колеса, отрисовываемая отдельным мешем
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WheelPart {
+    Tire,
+    Rim,
+    BrakeDisc,
+}
+
+/// Отображаемая часть колеса; следует за трансформацией колеса
+pub struct WheelPartComponent {
+    pub wheel_entity: hecs::Entity,
+    pub local_offset: Vec3, // Смещение вдоль оси колеса
+}
+
+/// Смазывание спиц диска на высоких оборотах
+pub struct RimBlurComponent {
+    pub sharp_mesh: usize,
+    pub sharp_material: usize,
+    pub blur_mesh: usize,
+    pub blur_material: usize,
+    pub blur_speed: f32, // Угловая скорость начала смазывания, рад/с
+}
+
+/// Свечение раскаленного тормозного диска
+pub struct BrakeGlowComponent {
+    pub ramp: Vec<usize>,     // Материалы от холодного к раскаленному
+    pub glow_start: f32,      // °C, с которой диск начинает светиться
+    pub glow_full: f32,       // °C, при которой свечение максимально
+}
+
+/// Общие меши и материалы частей колес
+pub struct WheelPartAssets {
+    pub tire_mesh: usize,
+    pub tire_material: usize,
+    pub rim_mesh: usize,
+    pub rim_material: usize,
+    pub rim_blur_mesh: usize,
+    pub rim_blur_material: usize,
+    pub disc_mesh: usize,
+    pub disc_ramp: Vec<usize>,
+}
+
+impl WheelPartAssets {
+    /// Число ступеней в рампе свечения диска
+    const GLOW_STEPS: usize = 8;
+
+    pub fn new(resources: &mut RenderResourceManager) -> Self {
+        let disc_ramp = (0..Self::GLOW_STEPS)
+            .map(|step| {
+                let t = step as f32 / (Self::GLOW_STEPS - 1) as f32;
+                // От темно-вишневого к оранжевому накалу
+                let emissive = [6.0 * t, 1.8 * t * t, 0.3 * t * t * t];
+                resources.add_emissive_material([0.35, 0.33, 0.32, 1.0], emissive)
+            })
+            .collect();
+
+        Self {
+            tire_mesh: resources.add_tire(24, 0.72),
+            tire_material: resources.add_basic_material([0.06, 0.06, 0.06, 1.0]),
+            rim_mesh: resources.add_wheel_rim(24, 5),
+            rim_material: resources.add_basic_material([0.75, 0.75, 0.78, 1.0]),
+            rim_blur_mesh: resources.add_simple_cylinder(24),
+            rim_blur_material: resources.add_basic_material([0.55, 0.55, 0.58, 0.85]),
+            disc_mesh: resources.add_simple_cylinder(24),
+            disc_ramp,
+        }
+    }
+}
+
+/// Создает покрышку, диск и тормозной диск для колеса
+pub fn create_wheel_parts(world: &mut World, assets: &WheelPartAssets, wheel_entity: hecs::Entity) -> Vec<hecs::Entity> {
+    let Ok(wheel) = world.get::<&WheelComponent>(wheel_entity).map(|w| (w.radius, w.width, w.position.x)) else {
+        return Vec::new();
+    };
+    let (radius, width, side_x) = wheel;
+    // Внешняя сторона колеса смотрит от центра машины
+    let outward = if side_x >= 0.0 { 1.0 } else { -1.0 };
+
+    let parts = [
+        (
+            WheelPart::Tire,
+            Vec3::ZERO,
+            Vec3::new(width, radius * 2.0, radius * 2.0),
+            assets.tire_mesh,
+            assets.tire_material,
+        ),
+        (
+            WheelPart::Rim,
+            Vec3::X * outward * width * 0.05,
+            Vec3::new(width * 0.8, radius * 1.44, radius * 1.44),
+            assets.rim_mesh,
+            assets.rim_material,
+        ),
+        (
+            WheelPart::BrakeDisc,
+            Vec3::X * -outward * width * 0.3,
+            Vec3::new(0.03, radius * 1.2, radius * 1.2),
+            assets.disc_mesh,
+            assets.disc_ramp[0],
+        ),
+    ];
+
+    let mut entities = Vec::new();
+    for (part, local_offset, local_scale, mesh_id, material_id) in parts {
+        let component = WheelPartComponent { wheel_entity, local_offset };
+        let render = RenderComponent {
+            mesh_id,
+            material_id,
+            visible: true,
+            scale: local_scale,
+            opacity: 1.0,
+        };
+        let entity = world.spawn((component, render, TransformComponent::default()));
+
+        match part {
+            WheelPart::Rim => {
+                let _ = world.insert_one(entity, RimBlurComponent {
+                    sharp_mesh: assets.rim_mesh,
+                    sharp_material: assets.rim_material,
+                    blur_mesh: assets.rim_blur_mesh,
+                    blur_material: assets.rim_blur_material,
+                    blur_speed: 25.0,
+                });
+            }
+            WheelPart::BrakeDisc => {
+                let _ = world.insert_one(entity, BrakeGlowComponent {
+                    ramp: assets.disc_ramp.clone(),
+                    glow_start: 350.0,
+                    glow_full: 800.0,
+                });
+            }
+            WheelPart::Tire => {}
+        }
+        entities.push(entity);
+    }
+    entities
+}
+
+/// Система частей колес: положение, смазывание спиц и накал дисков
+pub struct WheelPartSystem;
+
+impl System for WheelPartSystem {
+    fn update(&mut self, world: &mut World, _delta_time: f32) {
+        let mut updates = Vec::new();
+        for (entity, part) in world.query::<&WheelPartComponent>().iter() {
+            let Ok(transform) = world.get::<&TransformComponent>(part.wheel_entity) else {
+                continue;
+            };
+            let Ok(wheel) = world.get::<&WheelComponent>(part.wheel_entity) else {
+                continue;
+            };
+            updates.push((
+                entity,
+                transform.position + transform.rotation * part.local_offset,
+                transform.rotation,
+                wheel.wheel_speed.abs(),
+                wheel.brake_temperature,
+            ));
+        }
+
+        for (entity, position, rotation, wheel_speed, brake_temperature) in updates {
+            if let Ok(transform) = world.query_one_mut::<&mut TransformComponent>(entity) {
+                transform.position = position;
+                transform.rotation = rotation;
+            }
+
+            if let Ok((render, blur)) = world.query_one_mut::<(&mut RenderComponent, &RimBlurComponent)>(entity) {
+                let blurred = wheel_speed > blur.blur_speed;
+                render.mesh_id = if blurred { blur.blur_mesh } else { blur.sharp_mesh };
+                render.material_id = if blurred { blur.blur_material } else { blur.sharp_material };
+            }
+
+            if let Ok((render, glow)) = world.query_one_mut::<(&mut RenderComponent, &BrakeGlowComponent)>(entity) {
+                if glow.ramp.is_empty() {
+                    continue;
+                }
+                let start = glow.glow_start.max(AMBIENT_BRAKE_TEMPERATURE);
+                let t = ((brake_temperature - start) / (glow.glow_full - start).max(1.0)).clamp(0.0, 1.0);
+                let step = (t * (glow.ramp.len() - 1) as f32).round() as usize;
+                render.material_id = glow.ramp[step];
+            }
+        }
+    }
+}
@@ -0,0 +1,316 @@
+use crate::core::audio::AudioEvent;
+use crate::core::ecs::{Resource, System};
+use crate::core::physics::{RigidBodyComponent, TransformComponent};
+use crate::core::renderer::CameraComponent;
+use crate::game_world::car::{CarComponent, CarWheelBindingComponent, PlayerCarComponent, WheelComponent};
+use crate::game_world::particles::{ParticleEmitterComponent, ParticleKind};
+use crate::gameplay::race::RaceProgressComponent;
+use glam::Vec3;
+use hecs::World;
+use rapier3d::prelude::{ColliderSet, RigidBodySet};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::f32::consts::TAU;
+use std::path::Path;
+
+/// Файл со счетчиками празднований по профилям
+pub const CELEBRATION_STATS_PATH: &str = "saves/celebrations.json";
+
+/// Сколько после финиша длится свободная езда, с
+const FREE_DRIVE_WINDOW: f32 = 60.0;
+
+/// Бернаут: кузов почти стоит, а ведущие колеса крутятся быстрее этой окружной скорости, м/с
+const BURNOUT_MAX_SPEED: f32 = 2.0;
+const BURNOUT_MIN_WHEEL_SPEED: f32 = 5.0;
+const BURNOUT_HOLD_TIME: f32 = 1.5;
+
+/// Пончик: полный оборот кузова на ходу с постоянной скоростью рыскания, рад/с
+const DONUT_MIN_SPEED: f32 = 3.0;
+const DONUT_MIN_YAW_RATE: f32 = 1.2;
+const DONUT_GRACE: f32 = 0.5; // Короткий сбой вращения не обнуляет оборот
+
+/// Дым и облет камеры держатся еще немного после окончания
+const CELEBRATION_LINGER: f32 = 2.0;
+
+const SMOKE_RATE: f32 = 80.0;
+const SMOKE_OFFSET: Vec3 = Vec3::new(0.0, 0.3, -1.8); // Позади машины, у ведущих колес
+
+const ORBIT_RADIUS: f32 = 8.0;
+const ORBIT_HEIGHT: f32 = 3.0;
+const ORBIT_SPEED: f32 = 0.4; // рад/с
+
+/// Вид празднования
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CelebrationKind {
+    Burnout,
+    Donut,
+}
+
+/// Счетчики празднований одного профиля
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CelebrationStats {
+    pub burnouts: u32,
+    pub donuts: u32,
+}
+
+/// Счетчики празднований всех профилей
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CelebrationBook {
+    pub profiles: HashMap<String, CelebrationStats>,
+}
+
+impl CelebrationBook {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+        serde_json::from_str(&text).map_err(|e| e.to_string())
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        let text = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        std::fs::write(path, text).map_err(|e| e.to_string())
+    }
+
+    pub fn record(&mut self, profile: &str, kind: CelebrationKind) {
+        let stats = self.profiles.entry(profile.to_string()).or_default();
+        match kind {
+            CelebrationKind::Burnout => stats.burnouts += 1,
+            CelebrationKind::Donut => stats.donuts += 1,
+        }
+    }
+}
+
+/// Текущее празднование для камеры и интерфейса
+#[derive(Debug, Clone, Default)]
+pub struct CelebrationState {
+    pub active: Option<CelebrationKind>,
+    pub orbit_angle: f32,
+}
+
+/// Идет ли празднование: камерой в это время управляет система празднования
+pub fn celebration_active(world: &World) -> bool {
+    world
+        .query::<&Resource<CelebrationState>>()
+        .iter()
+        .any(|(_, res)| res.0.active.is_some())
+}
+
+/// Празднование после финиша: бернаут и пончики в окне свободной езды
+/// включают дым, шум трибун и облет камеры и попадают в статистику профиля
+pub struct CelebrationSystem {
+    free_drive_time: Option<f32>, // Время с финиша игрока
+    burnout_time: f32,
+    donut_angle: f32, // Накопленный поворот кузова со знаком направления
+    donut_grace: f32,
+    linger: f32,
+    state: CelebrationState,
+    smoke_emitter: Option<hecs::Entity>,
+    book: CelebrationBook,
+}
+
+impl CelebrationSystem {
+    pub fn new() -> Self {
+        Self {
+            free_drive_time: None,
+            burnout_time: 0.0,
+            donut_angle: 0.0,
+            donut_grace: 0.0,
+            linger: 0.0,
+            state: CelebrationState::default(),
+            smoke_emitter: None,
+            book: CelebrationBook::load(Path::new(CELEBRATION_STATS_PATH)).unwrap_or_default(),
+        }
+    }
+
+    fn reset(&mut self) {
+        self.burnout_time = 0.0;
+        self.donut_angle = 0.0;
+        self.donut_grace = 0.0;
+        self.linger = 0.0;
+        self.state.active = None;
+    }
+
+    /// Окружная скорость самого быстрого ведущего колеса на земле, м/с
+    fn driven_wheel_speed(world: &World, car_entity: hecs::Entity) -> f32 {
+        world
+            .query::<&CarWheelBindingComponent>()
+            .iter()
+            .filter(|(_, binding)| binding.car_entity == car_entity)
+            .flat_map(|(_, binding)| binding.wheel_entities.clone())
+            .filter_map(|wheel_entity| {
+                let wheel = world.get::<&WheelComponent>(wheel_entity).ok()?;
+                (wheel.powered && wheel.grounded).then(|| (wheel.wheel_speed * wheel.radius).abs())
+            })
+            .fold(0.0, f32::max)
+    }
+
+    fn yaw_rate(world: &World, car_entity: hecs::Entity) -> f32 {
+        let Ok(handle) = world.get::<&RigidBodyComponent>(car_entity).map(|rb| rb.handle) else {
+            return 0.0;
+        };
+        world
+            .query::<&Resource<(RigidBodySet, ColliderSet)>>()
+            .iter()
+            .next()
+            .and_then(|(_, res)| res.0 .0.get(handle).map(|body| body.angvel().y))
+            .unwrap_or(0.0)
+    }
+
+    /// Отслеживание бернаута и пончика; возвращает, что выполняется сейчас
+    fn detect(&mut self, speed: f32, wheel_speed: f32, yaw_rate: f32, delta_time: f32) -> Option<CelebrationKind> {
+        if speed < BURNOUT_MAX_SPEED && wheel_speed > BURNOUT_MIN_WHEEL_SPEED {
+            self.burnout_time += delta_time;
+        } else {
+            self.burnout_time = 0.0;
+        }
+
+        if speed > DONUT_MIN_SPEED && yaw_rate.abs() > DONUT_MIN_YAW_RATE {
+            // Смена направления вращения начинает оборот заново
+            if self.donut_angle * yaw_rate < 0.0 {
+                self.donut_angle = 0.0;
+            }
+            self.donut_angle += yaw_rate * delta_time;
+            self.donut_grace = DONUT_GRACE;
+        } else {
+            self.donut_grace -= delta_time;
+            if self.donut_grace <= 0.0 {
+                self.donut_angle = 0.0;
+            }
+        }
+
+        if self.donut_angle.abs() >= TAU {
+            Some(CelebrationKind::Donut)
+        } else if self.burnout_time >= BURNOUT_HOLD_TIME {
+            Some(CelebrationKind::Burnout)
+        } else {
+            None
+        }
+    }
+
+    fn start(&mut self, world: &mut World, profile: &str, kind: CelebrationKind) {
+        self.book.record(profile, kind);
+        if let Err(e) = self.book.save(Path::new(CELEBRATION_STATS_PATH)) {
+            eprintln!("Не удалось сохранить статистику празднований: {}", e);
+        }
+
+        let cheer = AudioEvent::PlaySound {
+            name: "crowd_cheer".to_string(),
+            volume: 0.8,
+            looping: false,
+        };
+        match world.query_mut::<&mut Resource<Vec<AudioEvent>>>().into_iter().next() {
+            Some((_, queue)) => queue.0.push(cheer),
+            None => {
+                world.spawn((Resource(vec![cheer]),));
+            }
+        }
+    }
+
+    fn update_smoke(&mut self, world: &mut World, position: Vec3, active: bool) {
+        let emitter_entity = match self.smoke_emitter.filter(|entity| world.contains(*entity)) {
+            Some(entity) => entity,
+            None if !active => return,
+            None => {
+                let mut emitter = ParticleEmitterComponent::new(ParticleKind::TireSmoke);
+                emitter.rate = SMOKE_RATE;
+                emitter.spread = 0.8;
+                let entity = world.spawn((
+                    TransformComponent {
+                        position,
+                        ..Default::default()
+                    },
+                    emitter,
+                ));
+                self.smoke_emitter = Some(entity);
+                entity
+            }
+        };
+        if let Ok(mut transform) = world.get::<&mut TransformComponent>(emitter_entity) {
+            transform.position = position;
+        }
+        if let Ok(mut emitter) = world.get::<&mut ParticleEmitterComponent>(emitter_entity) {
+            emitter.active = active;
+        }
+    }
+}
+
+impl Default for CelebrationSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl System for CelebrationSystem {
+    fn update(&mut self, world: &mut World, delta_time: f32) {
+        let player = world
+            .query::<(&PlayerCarComponent, &RaceProgressComponent, &CarComponent, &TransformComponent)>()
+            .iter()
+            .next()
+            .map(|(entity, (_, progress, car, transform))| {
+                (entity, progress.finished, progress.driver_name.clone(), car.current_speed.abs(), transform.position, transform.rotation)
+            });
+
+        // Окно свободной езды открывается на финише игрока
+        let in_window = match player {
+            Some((_, true, ..)) => {
+                let time = self.free_drive_time.get_or_insert(0.0);
+                *time += delta_time;
+                *time <= FREE_DRIVE_WINDOW
+            }
+            _ => {
+                self.free_drive_time = None;
+                false
+            }
+        };
+
+        let mut smoke = None;
+        match player {
+            Some((car_entity, _, profile, speed, position, rotation)) if in_window => {
+                let wheel_speed = Self::driven_wheel_speed(world, car_entity);
+                let yaw_rate = Self::yaw_rate(world, car_entity);
+                let detected = self.detect(speed, wheel_speed, yaw_rate, delta_time);
+
+                match detected {
+                    Some(kind) => {
+                        if self.state.active != Some(kind) {
+                            self.start(world, &profile, kind);
+                        }
+                        self.state.active = Some(kind);
+                        self.linger = CELEBRATION_LINGER;
+                    }
+                    None => {
+                        self.linger -= delta_time;
+                        if self.linger <= 0.0 {
+                            self.state.active = None;
+                        }
+                    }
+                }
+                smoke = Some((position + rotation * SMOKE_OFFSET, detected.is_some()));
+
+                if self.state.active.is_some() {
+                    self.state.orbit_angle = (self.state.orbit_angle + ORBIT_SPEED * delta_time) % TAU;
+                    let (sin, cos) = self.state.orbit_angle.sin_cos();
+                    let eye = position + Vec3::new(sin * ORBIT_RADIUS, ORBIT_HEIGHT, cos * ORBIT_RADIUS);
+                    for (_, camera) in world.query_mut::<&mut CameraComponent>() {
+                        camera.position = eye;
+                        camera.target = position + Vec3::Y * 0.5;
+                        camera.up = Vec3::Y;
+                    }
+                }
+            }
+            _ => self.reset(),
+        }
+        let (smoke_position, smoke_active) = smoke.unwrap_or((Vec3::ZERO, false));
+        self.update_smoke(world, smoke_position, smoke_active);
+
+        let state = self.state.clone();
+        match world.query_mut::<&mut Resource<CelebrationState>>().into_iter().next() {
+            Some((_, res)) => res.0 = state,
+            None => {
+                world.spawn((Resource(state),));
+            }
+        }
+    }
+}
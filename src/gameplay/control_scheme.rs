@@ -0,0 +1,120 @@
+use crate::core::ecs::{Resource, System};
+use crate::core::input::{ControlScheme, InputAction};
+use crate::gameplay::hot_seat::HotSeatSession;
+use crate::gameplay::rules::current_rules;
+use hecs::World;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Файл со схемами управления по профилям
+pub const CONTROL_SCHEMES_PATH: &str = "config/control_schemes.json";
+
+/// Профиль игрока вне режима поочередных заездов
+const DEFAULT_PROFILE: &str = "Player";
+
+/// Схемы управления, выбранные для профилей пилотов
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ControlSchemeSettings {
+    #[serde(default)]
+    pub default_scheme: ControlScheme,
+    #[serde(default)]
+    pub profiles: HashMap<String, ControlScheme>,
+}
+
+impl ControlSchemeSettings {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+        serde_json::from_str(&text).map_err(|e| e.to_string())
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        let text = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        std::fs::write(path, text).map_err(|e| e.to_string())
+    }
+
+    pub fn scheme_for(&self, profile: &str) -> ControlScheme {
+        self.profiles.get(profile).copied().unwrap_or(self.default_scheme)
+    }
+
+    pub fn set_scheme(&mut self, profile: &str, scheme: ControlScheme) {
+        self.profiles.insert(profile.to_string(), scheme);
+    }
+}
+
+/// Выбирает схему управления текущего пилота с учетом правил заезда;
+/// F1 переключает схему профиля и сохраняет выбор
+pub struct ControlSchemeSystem {
+    settings: ControlSchemeSettings,
+    warned_profile: Option<String>, // Чтобы не повторять предупреждение каждый кадр
+    cycle_held: bool,
+}
+
+impl ControlSchemeSystem {
+    pub fn new() -> Self {
+        Self {
+            settings: ControlSchemeSettings::load(Path::new(CONTROL_SCHEMES_PATH)).unwrap_or_default(),
+            warned_profile: None,
+            cycle_held: false,
+        }
+    }
+}
+
+impl Default for ControlSchemeSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl System for ControlSchemeSystem {
+    fn update(&mut self, world: &mut World, _delta_time: f32) {
+        // В поочередных заездах схема меняется вместе с пилотом
+        let profile = world
+            .query::<&Resource<HotSeatSession>>()
+            .iter()
+            .next()
+            .map(|(_, res)| res.0.current_driver().to_string())
+            .unwrap_or_else(|| DEFAULT_PROFILE.to_string());
+
+        let cycle_down = world
+            .query::<&Resource<HashMap<InputAction, f32>>>()
+            .iter()
+            .next()
+            .and_then(|(_, res)| res.0.get(&InputAction::CycleControlScheme).copied())
+            .unwrap_or(0.0)
+            > 0.5;
+        if cycle_down && !self.cycle_held {
+            let next = self.settings.scheme_for(&profile).next();
+            self.settings.set_scheme(&profile, next);
+            self.warned_profile = None;
+            eprintln!("Схема управления профиля {}: {:?}", profile, next);
+            if let Err(e) = self.settings.save(Path::new(CONTROL_SCHEMES_PATH)) {
+                eprintln!("Не удалось сохранить схемы управления: {}", e);
+            }
+        }
+        self.cycle_held = cycle_down;
+
+        let mut scheme = self.settings.scheme_for(&profile);
+        if scheme.requires_assists() && !current_rules(world).assists_allowed {
+            // Без помощников остается автоматический газ: руль и тормоз за игроком
+            if self.warned_profile.as_deref() != Some(profile.as_str()) {
+                eprintln!(
+                    "Схема {:?} профиля {} недоступна без помощников, включен автоматический газ",
+                    scheme, profile
+                );
+                self.warned_profile = Some(profile.clone());
+            }
+            scheme = ControlScheme::AutoThrottle;
+        }
+
+        match world.query_mut::<&mut Resource<ControlScheme>>().into_iter().next() {
+            Some((_, current)) => current.0 = scheme,
+            None => {
+                world.spawn((Resource(scheme),));
+            }
+        }
+    }
+}
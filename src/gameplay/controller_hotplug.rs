@@ -0,0 +1,155 @@
+use crate::core::ecs::{Resource, System};
+use crate::core::input::{GamepadStatus, InputAction, InputDevice};
+use crate::game_world::car::{AutopilotComponent, PlayerCarComponent};
+use crate::gameplay::race::{RacePhase, RaceState};
+use crate::gameplay::rules::current_event;
+use hecs::World;
+use std::collections::HashMap;
+
+/// Подсказка об отключенном геймпаде с выбором устройства, на котором продолжить
+#[derive(Debug, Clone, Default)]
+pub struct ControllerPrompt {
+    pub open: bool,
+    pub devices: Vec<InputDevice>, // Подключенные геймпады, клавиатура последней строкой
+    pub selected: usize,
+}
+
+impl ControllerPrompt {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn title(&self) -> &'static str {
+        "Геймпад отключен"
+    }
+
+    /// Строки списка устройств для отрисовки
+    pub fn device_labels(&self) -> Vec<String> {
+        self.devices
+            .iter()
+            .map(|device| match device {
+                InputDevice::Gamepad(id) => format!("Геймпад {}", id),
+                InputDevice::Keyboard | InputDevice::Mouse => "Клавиатура".to_string(),
+            })
+            .collect()
+    }
+}
+
+/// Отключение геймпада посреди заезда: одиночная гонка встает на паузу с выбором другого устройства,
+/// в онлайн-заезде машину ведет автопилот, пока ввод не вернется
+pub struct ControllerHotplugSystem {
+    held: HashMap<InputAction, bool>,
+    paused: bool, // Пауза поставлена этой системой, снимать ее тоже ей
+}
+
+impl ControllerHotplugSystem {
+    pub fn new() -> Self {
+        Self {
+            held: HashMap::new(),
+            paused: false,
+        }
+    }
+
+    /// Срабатывание по нажатию, а не удержанию
+    fn pressed(&mut self, inputs: &HashMap<InputAction, f32>, action: InputAction) -> bool {
+        let down = *inputs.get(&action).unwrap_or(&0.0) > 0.5;
+        let was_down = self.held.insert(action, down).unwrap_or(false);
+        down && !was_down
+    }
+
+    fn set_paused(&mut self, world: &mut World, paused: bool) {
+        self.paused = paused;
+        if let Some((_, state)) = world.query_mut::<&mut Resource<RaceState>>().into_iter().next() {
+            state.0.paused = paused;
+        }
+    }
+
+    fn set_autopilot(world: &mut World, engaged: bool) {
+        let cars: Vec<(hecs::Entity, bool)> = world
+            .query::<(&PlayerCarComponent, Option<&AutopilotComponent>)>()
+            .iter()
+            .map(|(entity, (_, autopilot))| (entity, autopilot.is_some()))
+            .collect();
+        for (entity, has_autopilot) in cars {
+            if engaged && !has_autopilot {
+                let _ = world.insert_one(entity, AutopilotComponent);
+                eprintln!("Геймпад отключен: машину ведет автопилот");
+            } else if !engaged && has_autopilot {
+                let _ = world.remove_one::<AutopilotComponent>(entity);
+                eprintln!("Ввод вернулся: автопилот отключен");
+            }
+        }
+    }
+}
+
+impl Default for ControllerHotplugSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl System for ControllerHotplugSystem {
+    fn update(&mut self, world: &mut World, _delta_time: f32) {
+        let inputs = world
+            .query::<&Resource<HashMap<InputAction, f32>>>()
+            .iter()
+            .next()
+            .map(|(_, res)| res.0.clone())
+            .unwrap_or_default();
+        let up = self.pressed(&inputs, InputAction::MenuUp);
+        let down = self.pressed(&inputs, InputAction::MenuDown);
+        let confirm = self.pressed(&inputs, InputAction::MenuConfirm);
+
+        let Some(status) = world
+            .query::<&Resource<GamepadStatus>>()
+            .iter()
+            .next()
+            .map(|(_, res)| res.0.clone())
+        else {
+            return;
+        };
+        let in_race = world
+            .query::<&Resource<RaceState>>()
+            .iter()
+            .any(|(_, res)| matches!(res.0.phase, RacePhase::Countdown | RacePhase::Racing));
+        let online = current_event(world).ranked;
+
+        // Онлайн-заезд не остановить: до возвращения ввода едет автопилот
+        Self::set_autopilot(world, status.lost && in_race && online);
+
+        let prompt_needed = status.lost && in_race && !online;
+        if prompt_needed != self.paused {
+            self.set_paused(world, prompt_needed);
+        }
+
+        let Some((_, prompt)) = world.query_mut::<&mut Resource<ControllerPrompt>>().into_iter().next() else {
+            return;
+        };
+        let prompt = &mut prompt.0;
+        prompt.open = prompt_needed;
+        if !prompt.open {
+            return;
+        }
+
+        // Список устройств обновляется на ходу: игрок может подключить другой геймпад прямо на паузе
+        prompt.devices = status.connected.iter().map(|id| InputDevice::Gamepad(*id)).collect();
+        prompt.devices.push(InputDevice::Keyboard);
+        let count = prompt.devices.len();
+        prompt.selected = prompt.selected.min(count - 1);
+        if up {
+            prompt.selected = (prompt.selected + count - 1) % count;
+        }
+        if down {
+            prompt.selected = (prompt.selected + 1) % count;
+        }
+        if !confirm {
+            return;
+        }
+
+        // Перенос привязок выполнит система ввода; пауза снимется, когда она сбросит признак потери
+        let device = prompt.devices[prompt.selected];
+        if let Some((_, status)) = world.query_mut::<&mut Resource<GamepadStatus>>().into_iter().next() {
+            status.0.reassign = Some(device);
+        }
+    }
+}
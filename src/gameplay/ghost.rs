@@ -0,0 +1,471 @@
+use crate::core::ecs::{EventQueue, Resource, System};
+use crate::core::physics::TransformComponent;
+use crate::core::renderer::RenderComponent;
+use crate::game_world::car::{CarComponent, PlayerCarComponent};
+use crate::game_world::track::{CheckpointComponent, TrackComponent};
+use crate::gameplay::hot_seat::HotSeatSession;
+use crate::gameplay::race::{RaceProgressComponent, RaceState};
+use crate::gameplay::save_manager::{SaveManager, SAVES_DIR};
+use glam::{Quat, Vec3};
+use hecs::World;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Каталог с призраками лучших кругов, по подкаталогу на комбинацию трассы и машины
+pub const GHOSTS_DIR: &str = "saves/ghosts";
+
+/// Сколько лучших призраков хранится для комбинации (личные рекорды профилей хранятся всегда)
+const GHOSTS_PER_COMBO: usize = 5;
+
+/// Интервал записи отсчетов призрака, с
+const GHOST_SAMPLE_INTERVAL: f32 = 0.05;
+
+/// Скачок положения за кадр, после которого круг считается прерванным (перенос на старт)
+const TELEPORT_DISTANCE: f32 = 25.0;
+
+/// Непрозрачность машины-призрака
+const GHOST_OPACITY: f32 = 0.35;
+
+/// Трасса, ее конфигурация и машина, к которым привязан призрак
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct GhostCombo {
+    pub track: String,
+    pub layout: String,
+    pub car: String,
+}
+
+impl GhostCombo {
+    fn dir_name(&self) -> String {
+        file_safe(&format!("{}_{}_{}", self.track, self.layout, self.car))
+    }
+}
+
+/// Отсчет призрака: положение машины и телеметрия пилота
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct GhostSample {
+    pub time: f32, // От начала круга
+    pub position: [f32; 3],
+    pub rotation: [f32; 4],
+    pub speed: f32,
+    pub throttle: f32,
+    pub brake: f32,
+    pub steering: f32,
+    pub gear: i32,
+    pub rpm: f32,
+}
+
+/// Записанный круг с телеметрией
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GhostLap {
+    pub profile: String,
+    pub combo: GhostCombo,
+    pub lap_time: f32,
+    pub recorded_at: u64, // Unix-время записи
+    pub samples: Vec<GhostSample>,
+}
+
+impl GhostLap {
+    /// Положение призрака на заданном времени круга; None до начала и после конца записи
+    pub fn pose_at(&self, time: f32) -> Option<(Vec3, Quat)> {
+        let next = self.samples.partition_point(|sample| sample.time <= time);
+        if next == 0 || next >= self.samples.len() {
+            return None;
+        }
+        let (a, b) = (&self.samples[next - 1], &self.samples[next]);
+        let t = ((time - a.time) / (b.time - a.time).max(f32::EPSILON)).clamp(0.0, 1.0);
+        let position = Vec3::from(a.position).lerp(Vec3::from(b.position), t);
+        let rotation = Quat::from_array(a.rotation).slerp(Quat::from_array(b.rotation), t);
+        Some((position, rotation))
+    }
+}
+
+/// Сохраненный призрак; время и профиль читаются из имени файла без разбора записи
+#[derive(Debug, Clone)]
+pub struct GhostEntry {
+    pub path: PathBuf,
+    pub profile: String,
+    pub lap_time: f32,
+}
+
+/// Хранилище призраков на диске
+#[derive(Debug, Clone)]
+pub struct GhostLibrary {
+    root: PathBuf,
+}
+
+impl GhostLibrary {
+    pub fn new(root: &Path) -> Self {
+        Self { root: root.to_path_buf() }
+    }
+
+    fn combo_dir(&self, combo: &GhostCombo) -> PathBuf {
+        self.root.join(combo.dir_name())
+    }
+
+    /// Призраки комбинации от быстрого к медленному
+    pub fn entries(&self, combo: &GhostCombo) -> Vec<GhostEntry> {
+        let Ok(dir) = std::fs::read_dir(self.combo_dir(combo)) else {
+            return Vec::new();
+        };
+        let mut entries: Vec<GhostEntry> = dir
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| {
+                let path = entry.path();
+                let stem = path.file_stem()?.to_str()?.to_string();
+                let (millis, profile) = stem.split_once('_')?;
+                Some(GhostEntry {
+                    lap_time: millis.parse::<u64>().ok()? as f32 / 1000.0,
+                    profile: profile.to_string(),
+                    path,
+                })
+            })
+            .collect();
+        entries.sort_by(|a, b| a.lap_time.total_cmp(&b.lap_time));
+        entries
+    }
+
+    pub fn personal_best(&self, profile: &str, combo: &GhostCombo) -> Option<GhostEntry> {
+        let profile = file_safe(profile);
+        self.entries(combo).into_iter().find(|entry| entry.profile == profile)
+    }
+
+    pub fn load(path: &Path) -> Result<GhostLap, String> {
+        let text = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+        serde_json::from_str(&text).map_err(|e| e.to_string())
+    }
+
+    /// Сохраняет призрак и убирает лишние записи комбинации
+    pub fn save(&self, lap: &GhostLap) -> Result<PathBuf, String> {
+        let dir = self.combo_dir(&lap.combo);
+        std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+        let millis = (lap.lap_time * 1000.0).round() as u64;
+        let path = dir.join(format!("{:09}_{}.json", millis, file_safe(&lap.profile)));
+        let text = serde_json::to_string(lap).map_err(|e| e.to_string())?;
+        std::fs::write(&path, text).map_err(|e| format!("{}: {}", path.display(), e))?;
+        self.prune(&lap.combo);
+        Ok(path)
+    }
+
+    /// Оставляет лучшие GHOSTS_PER_COMBO записей и личный рекорд каждого профиля
+    fn prune(&self, combo: &GhostCombo) {
+        let mut kept_profiles = Vec::new();
+        for (index, entry) in self.entries(combo).into_iter().enumerate() {
+            let profile_best = !kept_profiles.contains(&entry.profile);
+            if profile_best {
+                kept_profiles.push(entry.profile.clone());
+            }
+            if index < GHOSTS_PER_COMBO || profile_best {
+                continue;
+            }
+            if let Err(e) = std::fs::remove_file(&entry.path) {
+                eprintln!("Не удалось удалить призрак {}: {}", entry.path.display(), e);
+            }
+        }
+    }
+}
+
+/// Имя для файловой системы: все, кроме букв и цифр, заменяется подчеркиванием
+pub fn file_safe(name: &str) -> String {
+    name.chars().map(|c| if c.is_alphanumeric() { c } else { '_' }).collect()
+}
+
+/// Профиль пилота и комбинация для машины игрока
+pub fn current_ghost_combo(world: &World) -> Option<(hecs::Entity, String, GhostCombo)> {
+    let (car_entity, car_name, driver_name) = world
+        .query::<(&PlayerCarComponent, &CarComponent, Option<&RaceProgressComponent>)>()
+        .iter()
+        .next()
+        .map(|(entity, (_, car, progress))| (entity, car.name.clone(), progress.map(|p| p.driver_name.clone())))?;
+    let (track, layout) = world
+        .query::<&TrackComponent>()
+        .iter()
+        .next()
+        .map(|(_, track)| (track.name.clone(), track.layout.clone()))?;
+
+    // В поочередных заездах рекорд принадлежит текущему пилоту
+    let profile = world
+        .query::<&Resource<HotSeatSession>>()
+        .iter()
+        .next()
+        .map(|(_, res)| res.0.current_driver().to_string())
+        .or(driver_name)
+        .unwrap_or_else(|| "Player".to_string());
+
+    Some((car_entity, profile, GhostCombo { track, layout, car: car_name }))
+}
+
+/// Завершенный круг игрока; публикуется системой записи кругов, читается книгой рекордов
+#[derive(Debug, Clone)]
+pub struct LapCompletedEvent {
+    pub profile: String,
+    pub combo: GhostCombo,
+    pub lap_time: f32,
+}
+
+/// Время текущего круга игрока для синхронизации призрака; None вне круга
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GhostLapClock {
+    pub lap_time: Option<f32>,
+}
+
+/// Призрак, с которым едет игрок
+pub struct GhostRace {
+    pub lap: GhostLap,
+    pub entity: hecs::Entity,
+}
+
+/// Метка машины-призрака
+pub struct GhostCarComponent;
+
+/// Показывает призрак вместо текущего; внешний вид берется у машины игрока
+pub fn spawn_ghost(world: &mut World, lap: GhostLap) {
+    clear_ghost(world);
+    let render = world
+        .query::<(&PlayerCarComponent, &RenderComponent)>()
+        .iter()
+        .next()
+        .map(|(_, (_, render))| RenderComponent {
+            mesh_id: render.mesh_id,
+            material_id: render.material_id,
+            visible: false,
+            scale: render.scale,
+            opacity: GHOST_OPACITY,
+        })
+        .unwrap_or_default();
+    let entity = world.spawn((GhostCarComponent, TransformComponent::default(), render));
+    world.spawn((Resource(GhostRace { lap, entity }),));
+}
+
+/// Убирает призрак с трассы
+pub fn clear_ghost(world: &mut World) {
+    let races: Vec<(hecs::Entity, hecs::Entity)> = world
+        .query::<&Resource<GhostRace>>()
+        .iter()
+        .map(|(resource, race)| (resource, race.0.entity))
+        .collect();
+    for (resource, ghost) in races {
+        let _ = world.despawn(ghost);
+        let _ = world.despawn(resource);
+    }
+}
+
+/// Записываемый круг
+struct LapRecording {
+    lap_start: f32,
+    last_sample: f32,
+    samples: Vec<GhostSample>,
+}
+
+/// Запись кругов игрока; личный рекорд автоматически сохраняется призраком с телеметрией
+pub struct GhostRecorderSystem {
+    library: GhostLibrary,
+    clock: f32,
+    recording: Option<LapRecording>,
+    line_offset: Option<f32>, // Продольное смещение относительно финишной линии в прошлом кадре
+    last_position: Option<Vec3>,
+    best_times: HashMap<(String, GhostCombo), Option<f32>>, // Рекорды, уже прочитанные с диска
+}
+
+impl GhostRecorderSystem {
+    pub fn new() -> Self {
+        Self {
+            library: GhostLibrary::new(Path::new(GHOSTS_DIR)),
+            clock: 0.0,
+            recording: None,
+            line_offset: None,
+            last_position: None,
+            best_times: HashMap::new(),
+        }
+    }
+
+    fn reset(&mut self) {
+        self.recording = None;
+        self.line_offset = None;
+        self.last_position = None;
+    }
+
+    /// Сохраняет круг, если он быстрее личного рекорда; возвращает записанный призрак
+    fn finish_lap(&mut self, profile: &str, combo: &GhostCombo, lap_time: f32, samples: Vec<GhostSample>) -> Option<GhostLap> {
+        let key = (profile.to_string(), combo.clone());
+        let library = &self.library;
+        let best = self
+            .best_times
+            .entry(key)
+            .or_insert_with(|| library.personal_best(profile, combo).map(|entry| entry.lap_time));
+        if best.is_some_and(|best| lap_time >= best) {
+            return None;
+        }
+        *best = Some(lap_time);
+
+        let lap = GhostLap {
+            profile: profile.to_string(),
+            combo: combo.clone(),
+            lap_time,
+            recorded_at: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+            samples,
+        };
+        match self.library.save(&lap) {
+            Ok(path) => {
+                eprintln!("Личный рекорд {} ({:.3} с), призрак сохранен: {}", profile, lap_time, path.display());
+                if let Err(e) = SaveManager::new(Path::new(SAVES_DIR)).register_ghost(&lap, &path) {
+                    eprintln!("Не удалось обновить индекс сохранений: {}", e);
+                }
+            }
+            Err(e) => eprintln!("Не удалось сохранить призрак: {}", e),
+        }
+        Some(lap)
+    }
+}
+
+impl Default for GhostRecorderSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl System for GhostRecorderSystem {
+    fn update(&mut self, world: &mut World, delta_time: f32) {
+        let racing = world
+            .query::<&Resource<RaceState>>()
+            .iter()
+            .any(|(_, res)| res.0.running());
+        let player = current_ghost_combo(world);
+
+        let mut new_best = None;
+        let mut completed = None;
+        match (racing, player) {
+            (true, Some((car_entity, profile, combo))) => {
+                self.clock += delta_time;
+
+                let finish_line = world
+                    .query::<(&CheckpointComponent, &TransformComponent)>()
+                    .iter()
+                    .find(|(_, (checkpoint, _))| checkpoint.is_finish_line)
+                    .map(|(_, (checkpoint, transform))| (checkpoint.width, transform.position, transform.rotation));
+                let car = world
+                    .query_one::<(&CarComponent, &TransformComponent)>(car_entity)
+                    .ok()
+                    .and_then(|mut query| {
+                        query.get().map(|(car, transform)| {
+                            (transform.position, transform.rotation, car.current_speed, car.throttle, car.brake,
+                             car.current_steering, car.current_gear, car.current_rpm)
+                        })
+                    });
+
+                if let (Some((width, line_position, line_rotation)), Some(car)) = (finish_line, car) {
+                    let (position, rotation, speed, throttle, brake, steering, gear, rpm) = car;
+
+                    // Перенос машины (рестарт, смена пилота) прерывает круг
+                    if self.last_position.is_some_and(|last| last.distance(position) > TELEPORT_DISTANCE) {
+                        self.reset();
+                    }
+                    self.last_position = Some(position);
+
+                    let local = line_rotation.inverse() * (position - line_position);
+                    let on_line = local.x.abs() <= width / 2.0;
+                    let previous = self.line_offset.replace(local.z);
+                    let crossed_forward = on_line && previous.is_some_and(|prev| prev < 0.0 && local.z >= 0.0);
+                    let crossed_backward = on_line && previous.is_some_and(|prev| prev >= 0.0 && local.z < 0.0);
+
+                    if crossed_backward {
+                        // Проезд линии задним ходом не дает засчитать короткий круг
+                        self.recording = None;
+                    }
+                    if crossed_forward {
+                        if let Some(recording) = self.recording.take() {
+                            let lap_time = self.clock - recording.lap_start;
+                            completed = Some(LapCompletedEvent { profile: profile.clone(), combo: combo.clone(), lap_time });
+                            new_best = self.finish_lap(&profile, &combo, lap_time, recording.samples);
+                        }
+                        self.recording = Some(LapRecording {
+                            lap_start: self.clock,
+                            last_sample: f32::NEG_INFINITY,
+                            samples: Vec::new(),
+                        });
+                    }
+
+                    if let Some(recording) = &mut self.recording {
+                        if self.clock - recording.last_sample >= GHOST_SAMPLE_INTERVAL {
+                            recording.last_sample = self.clock;
+                            recording.samples.push(GhostSample {
+                                time: self.clock - recording.lap_start,
+                                position: position.to_array(),
+                                rotation: rotation.to_array(),
+                                speed,
+                                throttle,
+                                brake,
+                                steering,
+                                gear,
+                                rpm,
+                            });
+                        }
+                    }
+                }
+            }
+            _ => self.reset(),
+        }
+
+        let clock = GhostLapClock {
+            lap_time: self.recording.as_ref().map(|recording| self.clock - recording.lap_start),
+        };
+        match world.query_mut::<&mut Resource<GhostLapClock>>().into_iter().next() {
+            Some((_, current)) => current.0 = clock,
+            None => {
+                world.spawn((Resource(clock),));
+            }
+        }
+
+        if let Some(event) = completed {
+            match world.query_mut::<&mut Resource<EventQueue<LapCompletedEvent>>>().into_iter().next() {
+                Some((_, queue)) => queue.0.publish(event),
+                None => {
+                    let mut queue = EventQueue::new();
+                    queue.publish(event);
+                    world.spawn((Resource(queue),));
+                }
+            }
+        }
+
+        // Гонка с рекордом продолжается против только что установленного
+        if let Some(lap) = new_best {
+            if let Some((_, race)) = world.query_mut::<&mut Resource<GhostRace>>().into_iter().next() {
+                if race.0.lap.profile == lap.profile && race.0.lap.combo == lap.combo {
+                    race.0.lap = lap;
+                }
+            }
+        }
+    }
+}
+
+/// Перемещение призрака синхронно с кругом игрока
+pub struct GhostPlaybackSystem;
+
+impl System for GhostPlaybackSystem {
+    fn update(&mut self, world: &mut World, _delta_time: f32) {
+        let lap_time = world
+            .query::<&Resource<GhostLapClock>>()
+            .iter()
+            .next()
+            .and_then(|(_, res)| res.0.lap_time);
+        let Some((entity, pose)) = world
+            .query::<&Resource<GhostRace>>()
+            .iter()
+            .next()
+            .map(|(_, res)| (res.0.entity, lap_time.and_then(|time| res.0.lap.pose_at(time))))
+        else {
+            return;
+        };
+
+        if let Ok((transform, render)) = world.query_one_mut::<(&mut TransformComponent, &mut RenderComponent)>(entity) {
+            render.visible = pose.is_some();
+            if let Some((position, rotation)) = pose {
+                transform.position = position;
+                transform.rotation = rotation;
+            }
+        }
+    }
+}
@@ -0,0 +1,404 @@
+use crate::core::ecs::{Resource, System};
+use crate::core::input::InputAction;
+use crate::core::physics::{teleport_body, TransformComponent};
+use crate::game_world::car::{CarComponent, PlayerCarComponent};
+use crate::game_world::track::{CheckpointComponent, LapTimingComponent, TrackComponent};
+use crate::gameplay::race::{RacePhase, RaceProgressComponent, RaceState};
+use glam::{Quat, Vec3};
+use hecs::World;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Файл с личными рекордами кругов по профилям
+pub const BEST_LAPS_PATH: &str = "saves/best_laps.json";
+
+/// Лучший круг профиля на трассе и машине
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfileBestLap {
+    pub profile: String,
+    pub track: String,
+    pub car: String,
+    pub lap_time: f32,
+}
+
+/// Книга личных рекордов всех профилей
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BestLapBook {
+    pub laps: Vec<ProfileBestLap>,
+}
+
+impl BestLapBook {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+        serde_json::from_str(&text).map_err(|e| e.to_string())
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        let text = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        std::fs::write(path, text).map_err(|e| e.to_string())
+    }
+
+    pub fn best_for(&self, profile: &str, track: &str, car: &str) -> Option<f32> {
+        self.laps
+            .iter()
+            .find(|lap| lap.profile == profile && lap.track == track && lap.car == car)
+            .map(|lap| lap.lap_time)
+    }
+
+    /// Учет нового круга; true, если это личный рекорд
+    pub fn submit(&mut self, profile: &str, track: &str, car: &str, lap_time: f32) -> bool {
+        match self
+            .laps
+            .iter_mut()
+            .find(|lap| lap.profile == profile && lap.track == track && lap.car == car)
+        {
+            Some(lap) if lap.lap_time <= lap_time => false,
+            Some(lap) => {
+                lap.lap_time = lap_time;
+                true
+            }
+            None => {
+                self.laps.push(ProfileBestLap {
+                    profile: profile.to_string(),
+                    track: track.to_string(),
+                    car: car.to_string(),
+                    lap_time,
+                });
+                true
+            }
+        }
+    }
+}
+
+/// Этап попытки в режиме "передай геймпад"
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HotSeatPhase {
+    Handover,   // Смена пилота: машина на старте, идет отсчет
+    RunUp,      // Разгон до линии старта-финиша
+    FlyingLap,  // Быстрый круг с хода
+    Leaderboard, // Все попытки проведены
+}
+
+/// Результат одной попытки
+#[derive(Debug, Clone)]
+pub struct HotSeatLap {
+    pub driver: String,
+    pub attempt: u32,           // Номер попытки пилота, начиная с 1
+    pub lap_time: Option<f32>,  // None - круг не завершен за отведенное время
+    pub personal_best: bool,
+}
+
+/// Строка общей таблицы результатов
+#[derive(Debug, Clone)]
+pub struct HotSeatStanding {
+    pub driver: String,
+    pub best_lap: Option<f32>,
+    pub best_attempt: Option<u32>, // Попытка, в которой проехан лучший круг
+    pub gap: Option<f32>,          // Отставание от лидера
+    pub profile_best: Option<f32>,
+    pub new_profile_best: bool,    // В этой сессии пилот улучшил рекорд профиля
+}
+
+/// Сессия поочередных заездов на время
+#[derive(Debug, Clone)]
+pub struct HotSeatSession {
+    pub drivers: Vec<String>,
+    pub attempts_per_driver: u32,
+    pub current_attempt: u32, // Сквозной номер попытки, пилоты чередуются
+    pub phase: HotSeatPhase,
+    pub switch_pending: bool, // Нужно вернуть машину на старт и передать управление
+    pub handover_time: f32,
+    pub run_up_distance: f32,  // Отступ старта попытки перед линией
+    pub max_attempt_time: f32, // Ограничение на попытку, чтобы очередь не зависала
+    pub attempt_time: f32,
+    pub lap_time: f32,
+    pub laps: Vec<HotSeatLap>,
+    pub best_laps: BestLapBook,
+}
+
+impl HotSeatSession {
+    pub fn new(drivers: Vec<String>, attempts_per_driver: u32) -> Self {
+        let drivers = if drivers.is_empty() { vec!["Player".to_string()] } else { drivers };
+        Self {
+            drivers,
+            attempts_per_driver: attempts_per_driver.max(1),
+            current_attempt: 0,
+            phase: HotSeatPhase::Handover,
+            switch_pending: true,
+            handover_time: 3.0,
+            run_up_distance: 50.0,
+            max_attempt_time: 300.0,
+            attempt_time: 0.0,
+            lap_time: 0.0,
+            laps: Vec::new(),
+            best_laps: BestLapBook::load(Path::new(BEST_LAPS_PATH)).unwrap_or_default(),
+        }
+    }
+
+    pub fn total_attempts(&self) -> u32 {
+        self.drivers.len() as u32 * self.attempts_per_driver
+    }
+
+    pub fn current_driver(&self) -> &str {
+        &self.drivers[self.current_attempt as usize % self.drivers.len()]
+    }
+
+    /// Номер текущей попытки пилота, начиная с 1
+    pub fn driver_attempt(&self) -> u32 {
+        self.current_attempt / self.drivers.len() as u32 + 1
+    }
+
+    /// Таблица по лучшим кругам; пилоты без времени в конце
+    pub fn standings(&self, track: &str, car: &str) -> Vec<HotSeatStanding> {
+        let mut standings: Vec<HotSeatStanding> = self
+            .drivers
+            .iter()
+            .map(|driver| {
+                let laps = self.laps.iter().filter(|lap| &lap.driver == driver);
+                let best = laps
+                    .clone()
+                    .filter_map(|lap| lap.lap_time.map(|time| (time, lap.attempt)))
+                    .min_by(|a, b| a.0.total_cmp(&b.0));
+                HotSeatStanding {
+                    driver: driver.clone(),
+                    best_lap: best.map(|(time, _)| time),
+                    best_attempt: best.map(|(_, attempt)| attempt),
+                    gap: None,
+                    profile_best: self.best_laps.best_for(driver, track, car),
+                    new_profile_best: laps.clone().any(|lap| lap.personal_best),
+                }
+            })
+            .collect();
+        standings.sort_by(|a, b| match (a.best_lap, b.best_lap) {
+            (Some(a), Some(b)) => a.total_cmp(&b),
+            (Some(_), None) => std::cmp::Ordering::Less,
+            (None, Some(_)) => std::cmp::Ordering::Greater,
+            (None, None) => std::cmp::Ordering::Equal,
+        });
+        let leader = standings.first().and_then(|s| s.best_lap);
+        for standing in &mut standings {
+            standing.gap = standing.best_lap.zip(leader).map(|(time, best)| time - best);
+        }
+        standings
+    }
+
+    /// Новая сессия с теми же пилотами
+    pub fn restart(&mut self) {
+        self.current_attempt = 0;
+        self.laps.clear();
+        self.phase = HotSeatPhase::Handover;
+        self.switch_pending = true;
+    }
+}
+
+/// Трасса и машина, к которым привязываются рекорды
+pub fn hot_seat_combo(world: &World, car_entity: hecs::Entity) -> (String, String) {
+    let track = world
+        .query::<&TrackComponent>()
+        .iter()
+        .next()
+        .map(|(_, track)| track.name.clone())
+        .unwrap_or_default();
+    let car = world
+        .get::<&CarComponent>(car_entity)
+        .map(|car| car.name.clone())
+        .unwrap_or_default();
+    (track, car)
+}
+
+/// Система режима "передай геймпад": старт попыток, хронометраж и смена пилотов
+pub struct HotSeatSystem {
+    last_line_offset: Option<f32>, // Продольное смещение машины относительно линии в прошлом кадре
+    restart_held: bool,
+}
+
+impl HotSeatSystem {
+    pub fn new() -> Self {
+        Self {
+            last_line_offset: None,
+            restart_held: false,
+        }
+    }
+
+    /// Пересечение линии старта-финиша в направлении движения по трассе
+    fn crossed_line(&mut self, world: &World, car_position: Vec3) -> bool {
+        let line = world
+            .query::<(&CheckpointComponent, &TransformComponent)>()
+            .iter()
+            .find(|(_, (checkpoint, _))| checkpoint.is_finish_line)
+            .map(|(_, (checkpoint, transform))| (checkpoint.width, transform.position, transform.rotation));
+        let Some((width, position, rotation)) = line else {
+            return false;
+        };
+
+        let local = rotation.inverse() * (car_position - position);
+        let previous = self.last_line_offset.replace(local.z);
+        local.x.abs() <= width / 2.0 && matches!(previous, Some(prev) if prev < 0.0 && local.z >= 0.0)
+    }
+
+    /// Возврат машины на старт попытки и передача управления следующему пилоту
+    fn start_handover(&mut self, world: &mut World, car_entity: hecs::Entity) {
+        let Some(session) = world
+            .query::<&Resource<HotSeatSession>>()
+            .iter()
+            .next()
+            .map(|(_, res)| (res.0.current_driver().to_string(), res.0.handover_time, res.0.run_up_distance))
+        else {
+            return;
+        };
+        let (driver, handover_time, run_up_distance) = session;
+
+        let start = world
+            .query::<(&CheckpointComponent, &TransformComponent)>()
+            .iter()
+            .find(|(_, (checkpoint, _))| checkpoint.is_finish_line)
+            .map(|(_, (_, transform))| (transform.position, transform.rotation))
+            .unwrap_or((Vec3::ZERO, Quat::IDENTITY));
+        let (line_position, line_rotation) = start;
+        let position = line_position - line_rotation * Vec3::Z * run_up_distance + Vec3::Y * 0.8;
+        teleport_body(world, car_entity, position, line_rotation);
+        self.last_line_offset = None;
+
+        if let Ok(progress) = world.query_one_mut::<&mut RaceProgressComponent>(car_entity) {
+            progress.driver_name = driver;
+            progress.lap = 1;
+            progress.finished = false;
+        }
+        // Новый пилот начинает круг с разгона до линии старта
+        if let Ok(timing) = world.query_one_mut::<&mut LapTimingComponent>(car_entity) {
+            timing.reset();
+        }
+        // Отсчет гонки блокирует управление, пока пилоты меняются местами
+        if let Some((_, state)) = world.query_mut::<&mut Resource<RaceState>>().into_iter().next() {
+            state.0.start_countdown(handover_time);
+        }
+    }
+}
+
+impl Default for HotSeatSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl System for HotSeatSystem {
+    fn update(&mut self, world: &mut World, delta_time: f32) {
+        let Some(car_entity) = world
+            .query::<&PlayerCarComponent>()
+            .iter()
+            .next()
+            .map(|(entity, _)| entity)
+        else {
+            return;
+        };
+        let Some((phase, switch_pending)) = world
+            .query::<&Resource<HotSeatSession>>()
+            .iter()
+            .next()
+            .map(|(_, res)| (res.0.phase, res.0.switch_pending))
+        else {
+            return;
+        };
+
+        if switch_pending {
+            self.start_handover(world, car_entity);
+            if let Some((_, session)) = world.query_mut::<&mut Resource<HotSeatSession>>().into_iter().next() {
+                session.0.switch_pending = false;
+                session.0.phase = HotSeatPhase::Handover;
+                session.0.attempt_time = 0.0;
+                session.0.lap_time = 0.0;
+            }
+            return;
+        }
+
+        let restart_pressed = world
+            .query::<&Resource<HashMap<InputAction, f32>>>()
+            .iter()
+            .next()
+            .map(|(_, res)| *res.0.get(&InputAction::MenuConfirm).unwrap_or(&0.0) > 0.5)
+            .unwrap_or(false);
+        let restart = restart_pressed && !self.restart_held;
+        self.restart_held = restart_pressed;
+
+        let race_phase = world
+            .query::<&Resource<RaceState>>()
+            .iter()
+            .next()
+            .map(|(_, res)| res.0.phase);
+        let car_position = world
+            .get::<&TransformComponent>(car_entity)
+            .map(|t| t.position)
+            .unwrap_or(Vec3::ZERO);
+        let crossed = phase != HotSeatPhase::Leaderboard && self.crossed_line(world, car_position);
+        let (track, car) = hot_seat_combo(world, car_entity);
+
+        let Some((_, session)) = world.query_mut::<&mut Resource<HotSeatSession>>().into_iter().next() else {
+            return;
+        };
+        let session = &mut session.0;
+
+        match session.phase {
+            HotSeatPhase::Handover => {
+                if race_phase != Some(RacePhase::Countdown) {
+                    session.phase = HotSeatPhase::RunUp;
+                }
+                return;
+            }
+            HotSeatPhase::RunUp => {
+                session.attempt_time += delta_time;
+                if crossed {
+                    session.phase = HotSeatPhase::FlyingLap;
+                    session.lap_time = 0.0;
+                }
+            }
+            HotSeatPhase::FlyingLap => {
+                session.attempt_time += delta_time;
+                session.lap_time += delta_time;
+            }
+            HotSeatPhase::Leaderboard => {
+                if restart {
+                    session.restart();
+                }
+                return;
+            }
+        }
+
+        // Попытка завершается пересечением линии после быстрого круга или по таймауту
+        let completed = session.phase == HotSeatPhase::FlyingLap && crossed && session.lap_time > 0.0;
+        let timed_out = session.attempt_time >= session.max_attempt_time;
+        if !completed && !timed_out {
+            return;
+        }
+
+        let driver = session.current_driver().to_string();
+        let lap_time = completed.then_some(session.lap_time);
+        let personal_best = lap_time
+            .map(|time| session.best_laps.submit(&driver, &track, &car, time))
+            .unwrap_or(false);
+        session.laps.push(HotSeatLap {
+            driver,
+            attempt: session.driver_attempt(),
+            lap_time,
+            personal_best,
+        });
+        if personal_best {
+            if let Err(e) = session.best_laps.save(Path::new(BEST_LAPS_PATH)) {
+                eprintln!("Не удалось сохранить рекорды кругов: {}", e);
+            }
+        }
+
+        session.current_attempt += 1;
+        if session.current_attempt >= session.total_attempts() {
+            session.phase = HotSeatPhase::Leaderboard;
+            if let Some((_, state)) = world.query_mut::<&mut Resource<RaceState>>().into_iter().next() {
+                state.0.phase = RacePhase::Finished;
+            }
+        } else {
+            session.switch_pending = true;
+        }
+    }
+}
@@ -0,0 +1,337 @@
+use crate::core::ecs::{Resource, System};
+use crate::core::input::InputAction;
+use crate::core::physics::PhysicsStepSettings;
+use crate::game_world::car::PlayerCarComponent;
+use crate::gameplay::race::{RacePhase, RaceProgressComponent, RaceState};
+use crate::gameplay::results::SessionResults;
+use crate::gameplay::rules::current_event;
+use hecs::World;
+use std::collections::{HashMap, VecDeque};
+
+/// Больше пакетов ввода в секунду клиент отправлять не может
+const MAX_INPUT_RATE: usize = 250;
+
+/// Смен направления руля от упора до упора в секунду, недостижимых для человека
+const MAX_STEERING_REVERSALS: usize = 10;
+
+/// Размах поворота руля, который считается переходом от упора к упору
+const STEERING_REVERSAL_AMPLITUDE: f32 = 1.5;
+
+/// Самый длинный повторяющийся фрагмент, который ищем, в изменениях ввода
+const MACRO_MAX_PERIOD: usize = 8;
+
+/// Сколько раз подряд фрагмент должен повториться, чтобы считаться макросом
+const MACRO_REPEATS: usize = 6;
+
+/// То же для потока, снятого с шагом кадра: человек, ритмично стучащий по клавише,
+/// часто попадает в одинаковое число кадров, поэтому повторов нужно намного больше
+const MACRO_SAMPLED_REPEATS: usize = 20;
+
+/// Расхождение интервалов, при котором повторы еще считаются идеальными, с
+const MACRO_TIMING_TOLERANCE: f32 = 0.001;
+
+/// Повторный флаг того же вида не раньше, с
+const FLAG_COOLDOWN: f32 = 10.0;
+
+/// Один отсчет потока ввода пилота
+#[derive(Debug, Clone, Copy)]
+pub struct InputFrame {
+    pub time: f32,
+    pub throttle: f32,
+    pub brake: f32,
+    pub steering: f32, // -1 влево, 1 вправо
+}
+
+/// Вид нарушения в потоке ввода
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InputFlagKind {
+    RateLimit,    // Пакеты приходят чаще, чем позволяет клиент
+    AnalogRange,  // Значения вне допустимого диапазона
+    SteeringRate, // Физически неправдоподобная работа рулем
+    MacroPattern, // Идеально повторяющаяся последовательность
+}
+
+/// Отметка в отчете сессии; решение о санкциях остается за судьями
+#[derive(Debug, Clone)]
+pub struct InputFlag {
+    pub driver_name: String,
+    pub time: f32,
+    pub kind: InputFlagKind,
+    pub detail: String,
+}
+
+/// Проверка потока ввода одного пилота; не зависит от клиента и может работать на сервере
+#[derive(Debug, Clone, Default)]
+pub struct InputStreamValidator {
+    recent: VecDeque<InputFrame>,        // Отсчеты за последнюю секунду
+    changes: VecDeque<(f32, [f32; 3])>, // Интервал от прошлого изменения и новые значения
+    last_change_time: Option<f32>,
+    last_flagged: HashMap<InputFlagKind, f32>,
+    sampled: bool, // Отсчеты сняты локально с фиксированным шагом, а не пришли пакетами
+}
+
+impl InputStreamValidator {
+    /// Поток, который снимается с фиксированным шагом: частоту задает сам опрос,
+    /// а интервалы квантованы шагом
+    pub fn sampled() -> Self {
+        Self {
+            sampled: true,
+            ..Default::default()
+        }
+    }
+
+    fn macro_repeats(&self) -> usize {
+        if self.sampled {
+            MACRO_SAMPLED_REPEATS
+        } else {
+            MACRO_REPEATS
+        }
+    }
+
+    /// Добавляет отсчет и возвращает найденные нарушения
+    pub fn push(&mut self, frame: InputFrame) -> Vec<(InputFlagKind, String)> {
+        let mut findings = Vec::new();
+
+        let in_range = |value: f32, min: f32| value.is_finite() && (min..=1.0).contains(&value);
+        if !(in_range(frame.throttle, 0.0) && in_range(frame.brake, 0.0) && in_range(frame.steering, -1.0)) {
+            findings.push((
+                InputFlagKind::AnalogRange,
+                format!("throttle {:.3} brake {:.3} steering {:.3}", frame.throttle, frame.brake, frame.steering),
+            ));
+        }
+
+        let previous = self.recent.back().copied();
+        self.recent.push_back(frame);
+        while self.recent.front().is_some_and(|f| frame.time - f.time > 1.0) {
+            self.recent.pop_front();
+        }
+
+        if !self.sampled && self.recent.len() > MAX_INPUT_RATE {
+            findings.push((InputFlagKind::RateLimit, format!("{} samples/s", self.recent.len())));
+        }
+
+        let reversals = self.steering_reversals();
+        if reversals > MAX_STEERING_REVERSALS {
+            findings.push((InputFlagKind::SteeringRate, format!("{} full-lock reversals/s", reversals)));
+        }
+
+        let values = [frame.throttle, frame.brake, frame.steering];
+        let changed = previous.is_none_or(|p| [p.throttle, p.brake, p.steering] != values);
+        if changed {
+            let interval = self.last_change_time.map_or(0.0, |t| frame.time - t);
+            self.last_change_time = Some(frame.time);
+            self.changes.push_back((interval, values));
+            while self.changes.len() > MACRO_MAX_PERIOD * self.macro_repeats() {
+                self.changes.pop_front();
+            }
+            if let Some(period) = self.repeating_period() {
+                findings.push((
+                    InputFlagKind::MacroPattern,
+                    format!("{} changes repeated {} times", period, self.macro_repeats()),
+                ));
+            }
+        }
+
+        // Один и тот же вид нарушения не чаще раза в FLAG_COOLDOWN
+        findings.retain(|(kind, _)| {
+            let recent = self.last_flagged.get(kind).is_some_and(|&t| frame.time - t < FLAG_COOLDOWN);
+            if !recent {
+                self.last_flagged.insert(*kind, frame.time);
+            }
+            !recent
+        });
+        findings
+    }
+
+    /// Число перекладок руля от упора до упора за последнюю секунду
+    fn steering_reversals(&self) -> usize {
+        let mut reversals = 0;
+        let mut extreme: Option<f32> = None;
+        for frame in &self.recent {
+            if frame.steering.abs() < STEERING_REVERSAL_AMPLITUDE / 2.0 {
+                continue;
+            }
+            if extreme.is_some_and(|e| (frame.steering - e).abs() >= STEERING_REVERSAL_AMPLITUDE) {
+                reversals += 1;
+            }
+            extreme = Some(frame.steering);
+        }
+        reversals
+    }
+
+    /// Длина фрагмента, который повторился нужное число раз с одинаковыми интервалами
+    fn repeating_period(&self) -> Option<usize> {
+        let changes: Vec<&(f32, [f32; 3])> = self.changes.iter().collect();
+        let repeats = self.macro_repeats();
+        (2..=MACRO_MAX_PERIOD).find(|&period| {
+            let span = period * repeats;
+            if changes.len() < span {
+                return false;
+            }
+            let tail = &changes[changes.len() - span..];
+            (period..span).all(|i| {
+                let (interval, values) = tail[i];
+                let (earlier_interval, earlier_values) = tail[i - period];
+                values == earlier_values && (interval - earlier_interval).abs() <= MACRO_TIMING_TOLERANCE
+            })
+        })
+    }
+}
+
+/// Проверка ввода в рейтинговых заездах: нарушения попадают в отчет сессии, без исключения из гонки.
+/// Ввод опрашивается с шагом физики, поэтому число отсчетов не зависит от частоты кадров
+pub struct InputValidationSystem {
+    validators: HashMap<String, InputStreamValidator>,
+    accumulator: f32,
+}
+
+impl InputValidationSystem {
+    pub fn new() -> Self {
+        Self {
+            validators: HashMap::new(),
+            accumulator: 0.0,
+        }
+    }
+}
+
+impl Default for InputValidationSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl System for InputValidationSystem {
+    fn update(&mut self, world: &mut World, delta_time: f32) {
+        if !current_event(world).ranked {
+            return;
+        }
+        let Some(elapsed) = world
+            .query::<&Resource<RaceState>>()
+            .iter()
+            .next()
+            .filter(|(_, res)| res.0.phase == RacePhase::Racing)
+            .map(|(_, res)| res.0.elapsed)
+        else {
+            return;
+        };
+
+        // Пока сетевого клиента нет, проверяется поток локального пилота
+        let Some(driver_name) = world
+            .query::<(&PlayerCarComponent, &RaceProgressComponent)>()
+            .iter()
+            .next()
+            .map(|(_, (_, progress))| progress.driver_name.clone())
+        else {
+            return;
+        };
+        let Some(frame) = world
+            .query::<&Resource<HashMap<InputAction, f32>>>()
+            .iter()
+            .next()
+            .map(|(_, res)| {
+                let value = |action| *res.0.get(&action).unwrap_or(&0.0);
+                InputFrame {
+                    time: elapsed,
+                    throttle: value(InputAction::Accelerate),
+                    brake: value(InputAction::Brake),
+                    steering: value(InputAction::SteerRight) - value(InputAction::SteerLeft),
+                }
+            })
+        else {
+            return;
+        };
+
+        let step = world
+            .query::<&Resource<PhysicsStepSettings>>()
+            .iter()
+            .next()
+            .map(|(_, res)| res.0.fixed_dt)
+            .unwrap_or(PhysicsStepSettings::default().fixed_dt)
+            .max(1.0 / MAX_INPUT_RATE as f32);
+        self.accumulator += delta_time;
+        let validator = self.validators.entry(driver_name.clone()).or_insert_with(InputStreamValidator::sampled);
+        let mut findings = Vec::new();
+        while self.accumulator >= step {
+            self.accumulator -= step;
+            findings.extend(validator.push(InputFrame {
+                time: elapsed - self.accumulator,
+                ..frame
+            }));
+        }
+        if findings.is_empty() {
+            return;
+        }
+
+        let Some((_, results)) = world.query_mut::<&mut Resource<SessionResults>>().into_iter().next() else {
+            return;
+        };
+        for (kind, detail) in findings {
+            eprintln!("Ввод {} помечен ({:?}): {}", driver_name, kind, detail);
+            results.0.input_flags.push(InputFlag {
+                driver_name: driver_name.clone(),
+                time: elapsed,
+                kind,
+                detail,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::random::SimpleRng;
+
+    const FRAME: f32 = 1.0 / 60.0;
+
+    fn frame(time: f32, throttle: f32) -> InputFrame {
+        InputFrame {
+            time,
+            throttle,
+            brake: 0.0,
+            steering: 0.0,
+        }
+    }
+
+    /// Прогоняет постукивание по газу: длительности нажатий и пауз в кадрах по очереди
+    fn tap(validator: &mut InputStreamValidator, durations: impl Iterator<Item = usize>) -> Vec<InputFlagKind> {
+        let mut flags = Vec::new();
+        let mut index = 0;
+        for (i, frames) in durations.enumerate() {
+            let throttle = if i % 2 == 0 { 1.0 } else { 0.0 };
+            for _ in 0..frames {
+                flags.extend(validator.push(frame(index as f32 * FRAME, throttle)).into_iter().map(|(kind, _)| kind));
+                index += 1;
+            }
+        }
+        flags
+    }
+
+    #[test]
+    fn sampled_stream_skips_the_packet_rate_check() {
+        let mut packets = InputStreamValidator::default();
+        let mut sampled = InputStreamValidator::sampled();
+        let mut flags = Vec::new();
+        for i in 0..600 {
+            let sample = frame(i as f32 / 300.0, 0.5);
+            flags.extend(packets.push(sample).into_iter().map(|(kind, _)| kind));
+            assert!(sampled.push(sample).is_empty());
+        }
+        assert!(flags.contains(&InputFlagKind::RateLimit));
+    }
+
+    #[test]
+    fn frame_quantized_human_tapping_is_not_a_macro() {
+        // Ритмичное нажатие с разбросом ±12 мс, округленное до кадров при 60 Гц
+        let mut rng = SimpleRng::new(7);
+        let durations = (0..600).map(|_| ((0.13 + rng.range(-0.012, 0.012)) / FRAME).round() as usize);
+        let flags = tap(&mut InputStreamValidator::sampled(), durations);
+        assert!(!flags.contains(&InputFlagKind::MacroPattern));
+    }
+
+    #[test]
+    fn perfectly_repeated_taps_are_a_macro() {
+        let flags = tap(&mut InputStreamValidator::sampled(), std::iter::repeat_n(8, 100));
+        assert!(flags.contains(&InputFlagKind::MacroPattern));
+    }
+}
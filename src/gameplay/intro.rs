@@ -0,0 +1,192 @@
+use crate::core::ecs::{Resource, System};
+use crate::core::input::InputAction;
+use crate::core::physics::TransformComponent;
+use crate::core::renderer::CameraComponent;
+use crate::game_world::camera::CameraSpline;
+use crate::game_world::car::CarComponent;
+use crate::gameplay::race::{RacePhase, RaceProgressComponent, RaceState};
+use glam::{Quat, Vec3};
+use hecs::World;
+use std::collections::HashMap;
+
+/// Длительность обратного отсчета после заставки
+pub const COUNTDOWN_DURATION: f32 = 3.0;
+
+/// Пролет камеры вокруг машины в локальных координатах (вперед +Z)
+const SHOT_PATH: [Vec3; 4] = [
+    Vec3::new(3.0, 0.8, 6.0),
+    Vec3::new(5.0, 1.2, 2.0),
+    Vec3::new(4.5, 1.6, -3.0),
+    Vec3::new(1.5, 2.2, -7.0),
+];
+
+/// Участник, показываемый в заставке
+#[derive(Debug, Clone)]
+pub struct IntroShot {
+    pub car_entity: hecs::Entity,
+    pub driver_name: String,
+    pub car_name: String,
+    pub grid_position: usize,
+}
+
+/// Состояние заставки для интерфейса
+#[derive(Debug, Clone, Default)]
+pub struct IntroState {
+    pub active: bool,
+    pub current: Option<IntroShot>,
+    pub shot_progress: f32, // 0..1 внутри текущего пролета
+}
+
+/// Система заставки перед стартом: камера проходит вдоль стартовой решетки
+pub struct RaceIntroSystem {
+    shots: Vec<IntroShot>,
+    shot_index: usize,
+    shot_time: f32,
+    shot_duration: f32,
+    skip_delay: f32, // Ввод игнорируется в первые секунды, чтобы не пропустить заставку случайно
+    started: bool,
+}
+
+impl RaceIntroSystem {
+    pub fn new() -> Self {
+        Self {
+            shots: Vec::new(),
+            shot_index: 0,
+            shot_time: 0.0,
+            shot_duration: 3.5,
+            skip_delay: 0.5,
+            started: false,
+        }
+    }
+
+    /// Участники в порядке стартовой решетки, начиная с поула
+    fn collect_shots(world: &World) -> Vec<IntroShot> {
+        let mut shots: Vec<IntroShot> = world
+            .query::<(&RaceProgressComponent, &CarComponent)>()
+            .iter()
+            .map(|(entity, (progress, car))| IntroShot {
+                car_entity: entity,
+                driver_name: progress.driver_name.clone(),
+                car_name: car.name.clone(),
+                grid_position: progress.position,
+            })
+            .collect();
+        shots.sort_by_key(|shot| shot.grid_position);
+        shots
+    }
+
+    fn any_input(world: &World) -> bool {
+        world
+            .query::<&Resource<HashMap<InputAction, f32>>>()
+            .iter()
+            .next()
+            .map(|(_, res)| res.0.values().any(|&value| value > 0.5))
+            .unwrap_or(false)
+    }
+
+    fn finish(&mut self, world: &mut World) {
+        if let Some((_, state)) = world.query_mut::<&mut Resource<RaceState>>().into_iter().next() {
+            state.0.start_countdown(COUNTDOWN_DURATION);
+        }
+        write_intro_state(world, IntroState::default());
+        self.started = false;
+    }
+}
+
+impl Default for RaceIntroSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn write_intro_state(world: &mut World, intro: IntroState) {
+    let resource = world
+        .query_mut::<&mut Resource<IntroState>>()
+        .into_iter()
+        .next()
+        .map(|(_, res)| &mut res.0);
+
+    match resource {
+        Some(state) => *state = intro,
+        None => {
+            world.spawn((Resource(intro),));
+        }
+    }
+}
+
+impl System for RaceIntroSystem {
+    fn update(&mut self, world: &mut World, delta_time: f32) {
+        let in_intro = world
+            .query::<&Resource<RaceState>>()
+            .iter()
+            .next()
+            .map(|(_, res)| res.0.phase == RacePhase::Intro)
+            .unwrap_or(false);
+        if !in_intro {
+            return;
+        }
+
+        if !self.started {
+            self.shots = Self::collect_shots(world);
+            self.shot_index = 0;
+            self.shot_time = 0.0;
+            self.started = true;
+        }
+
+        let elapsed_total = self.shot_index as f32 * self.shot_duration + self.shot_time;
+        if elapsed_total > self.skip_delay && Self::any_input(world) {
+            self.finish(world);
+            return;
+        }
+
+        self.shot_time += delta_time;
+        if self.shot_time >= self.shot_duration {
+            self.shot_time = 0.0;
+            self.shot_index += 1;
+        }
+
+        // Машина могла исчезнуть во время заставки
+        while let Some(shot) = self.shots.get(self.shot_index) {
+            if world.get::<&TransformComponent>(shot.car_entity).is_ok() {
+                break;
+            }
+            self.shot_index += 1;
+        }
+
+        let Some(shot) = self.shots.get(self.shot_index).cloned() else {
+            self.finish(world);
+            return;
+        };
+
+        let (car_position, car_rotation) = world
+            .get::<&TransformComponent>(shot.car_entity)
+            .map(|t| (t.position, t.rotation))
+            .unwrap_or((Vec3::ZERO, Quat::IDENTITY));
+
+        let spline = CameraSpline::new(
+            SHOT_PATH
+                .iter()
+                .map(|&local| car_position + car_rotation * local)
+                .collect(),
+        );
+
+        // Плавный разгон и торможение камеры в пределах пролета
+        let progress = self.shot_time / self.shot_duration;
+        let eased = progress * progress * (3.0 - 2.0 * progress);
+
+        for (_, camera) in world.query_mut::<&mut CameraComponent>() {
+            camera.position = spline.sample(eased);
+            camera.target = car_position + car_rotation * Vec3::new(0.0, 0.5, 0.0);
+            camera.up = Vec3::Y;
+        }
+
+        write_intro_state(
+            world,
+            IntroState {
+                active: true,
+                current: Some(shot),
+                shot_progress: progress,
+            },
+        );
+    }
+}
@@ -1 +1,40 @@
-// Здесь будут реализованы режимы игры, ИИ и экономика 
\ No newline at end of file
+// Здесь будут реализованы режимы игры, ИИ и экономика
+
+pub mod rules;
+pub mod scoring;
+pub mod race;
+pub mod intro;
+pub mod music;
+pub mod hot_seat;
+pub mod results;
+pub mod control_scheme;
+pub mod input_validation;
+pub mod ghost;
+pub mod race_control;
+pub mod save_manager;
+pub mod controller_hotplug;
+pub mod celebration;
+pub mod records;
+pub mod profile;
+pub mod track_editor;
+
+use crate::core::ecs::EcsManager;
+
+/// Регистрация систем игровой логики
+pub fn register_systems(ecs_manager: &mut EcsManager) {
+    ecs_manager.register_system(intro::RaceIntroSystem::new());
+    ecs_manager.register_system(controller_hotplug::ControllerHotplugSystem::new());
+    ecs_manager.register_system(race::RaceStateSystem);
+    ecs_manager.register_system(track_editor::TrackEditorSystem::new());
+    ecs_manager.register_system(hot_seat::HotSeatSystem::new());
+    ecs_manager.register_system(control_scheme::ControlSchemeSystem::new());
+    ecs_manager.register_system(input_validation::InputValidationSystem::new());
+    ecs_manager.register_system(results::RaceResultsSystem::new());
+    ecs_manager.register_system(race_control::RaceControlSystem::new());
+    ecs_manager.register_system(celebration::CelebrationSystem::new());
+    ecs_manager.register_system(ghost::GhostRecorderSystem::new());
+    ecs_manager.register_system(records::RecordBookSystem::new());
+    ecs_manager.register_system(ghost::GhostPlaybackSystem);
+    ecs_manager.register_system(scoring::ScoringSystem::new());
+    ecs_manager.register_system(music::MusicIntensitySystem::new());
+}
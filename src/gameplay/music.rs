@@ -0,0 +1,136 @@
+use crate::core::audio::{AudioEvent, MusicIntensity, MusicStem};
+use crate::core::ecs::{System, Resource};
+use crate::core::physics::TransformComponent;
+use crate::game_world::car::{CarComponent, PlayerCarComponent};
+use crate::gameplay::race::{current_race, RacePhase, RaceProgressComponent};
+use hecs::World;
+
+/// Слои адаптивной музыки гонки; звуки загружаются из assets/music/<имя>.wav
+pub const MUSIC_STEMS: [(MusicStem, &str); 3] = [
+    (MusicStem::Base, "music_base"),
+    (MusicStem::Percussion, "music_percussion"),
+    (MusicStem::Lead, "music_lead"),
+];
+
+/// Веса метрик интенсивности гонки
+pub struct MusicIntensityConfig {
+    pub calm_level: f32,           // Интенсивность вне гонки
+    pub speed_weight: f32,
+    pub full_speed: f32,           // м/с, при которой вклад скорости максимален
+    pub battle_weight: f32,
+    pub battle_distance: f32,      // Дистанция до соперника, на которой начинается борьба
+    pub final_lap_boost: f32,
+    pub response: f32,             // Скорость реакции на изменения, 1/с
+    pub music_volume: f32,
+}
+
+impl Default for MusicIntensityConfig {
+    fn default() -> Self {
+        Self {
+            calm_level: 0.1,
+            speed_weight: 0.35,
+            full_speed: 60.0,
+            battle_weight: 0.4,
+            battle_distance: 30.0,
+            final_lap_boost: 0.3,
+            response: 1.0,
+            music_volume: 0.8,
+        }
+    }
+}
+
+/// Система расчета интенсивности музыки по ситуации в гонке; с началом заезда запускает адаптивную музыку,
+/// а с финишем или рестартом останавливает ее
+pub struct MusicIntensitySystem {
+    pub config: MusicIntensityConfig,
+    music_started: bool,
+}
+
+impl MusicIntensitySystem {
+    pub fn new() -> Self {
+        Self {
+            config: MusicIntensityConfig::default(),
+            music_started: false,
+        }
+    }
+
+    fn target_intensity(&self, world: &World) -> f32 {
+        let config = &self.config;
+
+        let Some((phase, total_laps)) = current_race(world) else {
+            return config.calm_level;
+        };
+        if phase != RacePhase::Racing {
+            return config.calm_level;
+        }
+
+        let mut query = world.query::<(&CarComponent, &TransformComponent, &PlayerCarComponent)>();
+        let Some((player, (car, transform, _))) = query.iter().next() else {
+            return config.calm_level;
+        };
+
+        let speed = (car.current_speed.abs() / config.full_speed).min(1.0);
+
+        // Борьба за позицию: насколько близко ближайший соперник
+        let nearest_rival = world
+            .query::<(&CarComponent, &TransformComponent)>()
+            .iter()
+            .filter(|(entity, _)| *entity != player)
+            .map(|(_, (_, t))| t.position.distance(transform.position))
+            .fold(f32::INFINITY, f32::min);
+        let battle = (1.0 - nearest_rival / config.battle_distance).clamp(0.0, 1.0);
+
+        let final_lap = world
+            .get::<&RaceProgressComponent>(player)
+            .map(|progress| progress.is_final_lap(total_laps))
+            .unwrap_or(false);
+
+        let mut intensity = config.calm_level + speed * config.speed_weight + battle * config.battle_weight;
+        if final_lap {
+            intensity += config.final_lap_boost;
+        }
+        intensity.clamp(0.0, 1.0)
+    }
+}
+
+impl System for MusicIntensitySystem {
+    fn update(&mut self, world: &mut World, delta_time: f32) {
+        // Музыка играет, пока идет заезд: стартует с ним и стихает на финише или рестарте
+        let racing = matches!(current_race(world), Some((RacePhase::Racing, _)));
+        let event = if racing && !self.music_started {
+            Some(AudioEvent::PlayAdaptiveMusic {
+                stems: MUSIC_STEMS.iter().map(|(stem, name)| (*stem, name.to_string())).collect(),
+                volume: self.config.music_volume,
+            })
+        } else if !racing && self.music_started {
+            Some(AudioEvent::StopAdaptiveMusic)
+        } else {
+            None
+        };
+        self.music_started = racing;
+        if let Some(event) = event {
+            match world.query_mut::<&mut Resource<Vec<AudioEvent>>>().into_iter().next() {
+                Some((_, queue)) => queue.0.push(event),
+                None => {
+                    world.spawn((Resource(vec![event]),));
+                }
+            }
+        }
+
+        let target = self.target_intensity(world);
+        let t = (self.config.response * delta_time).min(1.0);
+
+        let resource = world
+            .query_mut::<&mut Resource<MusicIntensity>>()
+            .into_iter()
+            .next()
+            .map(|(_, res)| &mut res.0);
+
+        match resource {
+            Some(intensity) => intensity.value += (target - intensity.value) * t,
+            None => {
+                world.spawn((Resource(MusicIntensity { value: target }),));
+            }
+        }
+    }
+}
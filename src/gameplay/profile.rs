@@ -0,0 +1,141 @@
+use crate::game_world::upgrades::{CarUpgrades, UpgradeCategory};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Файл профиля игрока
+pub const PLAYER_PROFILE_PATH: &str = "config/profile.json";
+
+/// Кредиты нового профиля на первые доработки
+const STARTING_CREDITS: u32 = 10000;
+
+/// Призовые кредиты за первые три места и за любой другой финиш
+const PODIUM_PRIZES: [u32; 3] = [5000, 3000, 2000];
+const FINISH_PRIZE: u32 = 500;
+
+/// Призовые кредиты за итоговое место, начиная с 1
+pub fn race_prize(position: usize) -> u32 {
+    position
+        .checked_sub(1)
+        .and_then(|index| PODIUM_PRIZES.get(index))
+        .copied()
+        .unwrap_or(FINISH_PRIZE)
+}
+
+/// Профиль игрока для карьеры: кредиты и доработки каждой машины
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PlayerProfile {
+    pub name: String,
+    pub credits: u32,
+    pub upgrades: HashMap<String, CarUpgrades>, // По имени машины
+}
+
+impl Default for PlayerProfile {
+    fn default() -> Self {
+        Self {
+            name: "Player".to_string(),
+            credits: STARTING_CREDITS,
+            upgrades: HashMap::new(),
+        }
+    }
+}
+
+impl PlayerProfile {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+        serde_json::from_str(&text).map_err(|e| e.to_string())
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        let text = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        std::fs::write(path, text).map_err(|e| e.to_string())
+    }
+
+    /// Доработки машины; у новой машины их нет
+    pub fn car_upgrades(&self, car_name: &str) -> CarUpgrades {
+        self.upgrades.get(car_name).copied().unwrap_or_default()
+    }
+
+    /// Покупка следующего уровня доработки; возвращает уплаченную цену
+    pub fn buy_upgrade(&mut self, car_name: &str, category: UpgradeCategory) -> Result<u32, String> {
+        let upgrades = self.car_upgrades(car_name);
+        let Some(level) = upgrades.next_level(category) else {
+            return Err(format!("{:?} upgrade is already at the top level", category));
+        };
+        let price = category.price(level);
+        if price > self.credits {
+            return Err(format!("Not enough credits: {} needed, {} available", price, self.credits));
+        }
+        self.credits -= price;
+        self.upgrades.entry(car_name.to_string()).or_default().raise(category);
+        Ok(price)
+    }
+
+    /// Начисление кредитов, например за место в гонке
+    pub fn award(&mut self, credits: u32) {
+        self.credits = self.credits.saturating_add(credits);
+    }
+}
+
+/// Профиль из файла; без файла — новый профиль
+pub fn load_player_profile() -> PlayerProfile {
+    let path = Path::new(PLAYER_PROFILE_PATH);
+    match PlayerProfile::load(path) {
+        Ok(profile) => profile,
+        Err(e) => {
+            if path.exists() {
+                eprintln!("Не удалось прочитать профиль игрока: {}", e);
+            }
+            PlayerProfile::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game_world::upgrades::MAX_UPGRADE_LEVEL;
+
+    #[test]
+    fn prizes_cover_the_podium_and_every_finish() {
+        assert_eq!(race_prize(1), 5000);
+        assert_eq!(race_prize(3), 2000);
+        assert_eq!(race_prize(4), FINISH_PRIZE);
+        assert_eq!(race_prize(0), FINISH_PRIZE);
+    }
+
+    #[test]
+    fn buying_an_upgrade_spends_its_price() {
+        let mut profile = PlayerProfile::default();
+        let price = profile.buy_upgrade("GT", UpgradeCategory::Tires).unwrap();
+        assert_eq!(price, UpgradeCategory::Tires.price(1));
+        assert_eq!(profile.credits, STARTING_CREDITS - price);
+        assert_eq!(profile.car_upgrades("GT").tires, 1);
+        assert_eq!(profile.car_upgrades("Hatch").tires, 0);
+    }
+
+    #[test]
+    fn upgrades_stop_at_the_top_level_and_need_credits() {
+        let mut profile = PlayerProfile {
+            credits: u32::MAX,
+            ..PlayerProfile::default()
+        };
+        for _ in 0..MAX_UPGRADE_LEVEL {
+            profile.buy_upgrade("GT", UpgradeCategory::Brakes).unwrap();
+        }
+        let credits = profile.credits;
+        assert!(profile.buy_upgrade("GT", UpgradeCategory::Brakes).is_err());
+        assert_eq!(profile.credits, credits);
+
+        let mut profile = PlayerProfile {
+            credits: 0,
+            ..PlayerProfile::default()
+        };
+        assert!(profile.buy_upgrade("GT", UpgradeCategory::Engine).is_err());
+        assert_eq!(profile.car_upgrades("GT"), CarUpgrades::default());
+    }
+}
@@ -0,0 +1,135 @@
+use crate::core::ecs::{Resource, System};
+use crate::game_world::track::TrackComponent;
+use hecs::World;
+
+/// Фаза гонки
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RacePhase {
+    Intro,
+    Countdown,
+    Racing,
+    Finished,
+}
+
+/// Общее состояние гонки
+pub struct RaceState {
+    pub phase: RacePhase,
+    pub total_laps: u32,
+    pub elapsed: f32,
+    pub countdown: f32, // Оставшееся время обратного отсчета
+    pub paused: bool,   // Часы гонки и физика стоят
+    pub stage: bool,    // Спецучасток: один заезд от старта до финиша вместо кругов
+}
+
+impl RaceState {
+    pub fn new(total_laps: u32) -> Self {
+        Self {
+            phase: RacePhase::Racing,
+            total_laps,
+            elapsed: 0.0,
+            countdown: 0.0,
+            paused: false,
+            stage: false,
+        }
+    }
+
+    /// Гонка, начинающаяся с заставки на стартовой решетке
+    pub fn with_intro(total_laps: u32) -> Self {
+        Self {
+            phase: RacePhase::Intro,
+            ..Self::new(total_laps)
+        }
+    }
+
+    /// Переход от заставки к обратному отсчету
+    pub fn start_countdown(&mut self, duration: f32) {
+        self.phase = RacePhase::Countdown;
+        self.countdown = duration;
+    }
+
+    /// Сколько нужно проехать до финиша: спецучасток проезжается один раз
+    pub fn laps(&self) -> u32 {
+        if self.stage {
+            1
+        } else {
+            self.total_laps
+        }
+    }
+
+    /// Идет гонка и она не на паузе
+    pub fn running(&self) -> bool {
+        self.phase == RacePhase::Racing && !self.paused
+    }
+}
+
+/// Прогресс автомобиля в гонке
+pub struct RaceProgressComponent {
+    pub driver_name: String,
+    pub lap: u32,        // Текущий круг, начиная с 1
+    pub position: usize, // Место в гонке, начиная с 1
+    pub finished: bool,
+}
+
+impl RaceProgressComponent {
+    pub fn new(driver_name: &str) -> Self {
+        Self {
+            driver_name: driver_name.to_string(),
+            lap: 1,
+            position: 1,
+            finished: false,
+        }
+    }
+
+    pub fn is_final_lap(&self, total_laps: u32) -> bool {
+        !self.finished && self.lap >= total_laps
+    }
+}
+
+/// Текущая фаза и число кругов (None, если гонка не создана)
+pub fn current_race(world: &World) -> Option<(RacePhase, u32)> {
+    world
+        .query::<&Resource<RaceState>>()
+        .iter()
+        .next()
+        .map(|(_, res)| (res.0.phase, res.0.laps()))
+}
+
+/// Гонка по устройству трассы: на спецучастке время этапа вместо кругов
+pub fn apply_track_topology(world: &mut World) {
+    let stage = world.query::<&TrackComponent>().iter().any(|(_, track)| track.point_to_point);
+    if let Some((_, state)) = world.query_mut::<&mut Resource<RaceState>>().into_iter().next() {
+        state.0.stage = stage;
+    }
+}
+
+/// Стоит ли гонка на паузе
+pub fn race_paused(world: &World) -> bool {
+    world.query::<&Resource<RaceState>>().iter().any(|(_, res)| res.0.paused)
+}
+
+/// Система фаз гонки: обратный отсчет и время заезда
+pub struct RaceStateSystem;
+
+impl System for RaceStateSystem {
+    fn update(&mut self, world: &mut World, delta_time: f32) {
+        let Some((_, state)) = world.query_mut::<&mut Resource<RaceState>>().into_iter().next() else {
+            return;
+        };
+        let state = &mut state.0;
+        if state.paused {
+            return;
+        }
+
+        match state.phase {
+            RacePhase::Countdown => {
+                state.countdown -= delta_time;
+                if state.countdown <= 0.0 {
+                    state.countdown = 0.0;
+                    state.phase = RacePhase::Racing;
+                }
+            }
+            RacePhase::Racing => state.elapsed += delta_time,
+            RacePhase::Intro | RacePhase::Finished => {}
+        }
+    }
+}
@@ -0,0 +1,410 @@
+use crate::core::ecs::{EventQueue, Resource, System};
+use crate::core::physics::{CollisionEvent, CollisionPhase, TransformComponent};
+use crate::game_world::car::CarComponent;
+use crate::game_world::environment::DestructibleComponent;
+use crate::game_world::props::PropKnockedEvent;
+use crate::game_world::track::{in_pit_lane, ObstacleType};
+use crate::game_world::track_limits::car_on_track;
+use crate::gameplay::race::{RaceProgressComponent, RaceState};
+use crate::gameplay::results::SessionResults;
+use crate::gameplay::rules::{current_rules, RulesProfile};
+use glam::Vec3;
+use hecs::World;
+use std::collections::{HashMap, HashSet};
+
+/// Урон разрушаемому объекту на единицу импульса удара при множителе 1
+const COLLISION_DAMAGE_PER_IMPULSE: f32 = 0.005;
+
+/// Превышение лимита пит-лейна, которое еще прощается, м/с
+const PIT_SPEED_TOLERANCE: f32 = 0.5;
+
+/// Флаг, который дирекция гонки показывает пилоту
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RaceFlag {
+    #[default]
+    Green,
+    Yellow, // Впереди остановившаяся машина
+    Blue,   // Пропустить догоняющего лидера
+}
+
+/// Штраф дирекции гонки
+#[derive(Debug, Clone)]
+pub struct Penalty {
+    pub driver_name: String,
+    pub time: f32,
+    pub reason: String,
+    pub seconds: f32,
+}
+
+/// Текущее состояние дирекции гонки для HUD
+#[derive(Debug, Clone, Default)]
+pub struct RaceControl {
+    pub flags: HashMap<String, RaceFlag>,
+    pub track_limit_violations: HashMap<String, u32>,
+}
+
+impl RaceControl {
+    pub fn flag_for(&self, driver_name: &str) -> RaceFlag {
+        self.flags.get(driver_name).copied().unwrap_or_default()
+    }
+}
+
+/// Участник гонки в текущем кадре
+struct Entrant {
+    entity: hecs::Entity,
+    driver_name: String,
+    position: Vec3,
+    forward: Vec3,
+    speed: f32,
+    lap: u32,
+    race_position: usize,
+    on_track: bool,
+    in_pit_lane: bool,
+}
+
+/// Дирекция гонки: применяет профиль правил события к границам трассы, флагам и урону
+pub struct RaceControlSystem {
+    off_track_time: HashMap<hecs::Entity, f32>,
+    counted: HashSet<hecs::Entity>,      // Текущий выезд уже засчитан как нарушение
+    in_pit_lane: HashSet<hecs::Entity>,
+    pit_speeding: HashSet<hecs::Entity>, // Превышение на текущем проезде пит-лейна уже наказано
+}
+
+impl RaceControlSystem {
+    pub fn new() -> Self {
+        Self {
+            off_track_time: HashMap::new(),
+            counted: HashSet::new(),
+            in_pit_lane: HashSet::new(),
+            pit_speeding: HashSet::new(),
+        }
+    }
+
+    /// Ограничитель включается на въезде в пит-лейн и выключается на выезде;
+    /// возвращает пилотов, превысивших лимит на текущем проезде
+    fn supervise_pit_lane(&mut self, world: &mut World, entrants: &[Entrant], rules: &RulesProfile) -> Vec<String> {
+        let mut speeding = Vec::new();
+        for entrant in entrants {
+            let was_in_pit = self.in_pit_lane.contains(&entrant.entity);
+            if entrant.in_pit_lane != was_in_pit {
+                if let Ok(car) = world.query_one_mut::<&mut CarComponent>(entrant.entity) {
+                    car.speed_limiter = entrant.in_pit_lane;
+                }
+            }
+            if !entrant.in_pit_lane {
+                self.in_pit_lane.remove(&entrant.entity);
+                self.pit_speeding.remove(&entrant.entity);
+                continue;
+            }
+            self.in_pit_lane.insert(entrant.entity);
+            if entrant.speed > rules.penalties.pit_speed_limit + PIT_SPEED_TOLERANCE && self.pit_speeding.insert(entrant.entity) {
+                speeding.push(entrant.driver_name.clone());
+            }
+        }
+        speeding
+    }
+
+    /// Урон разрушаемым объектам от ударов, с учетом уровня повреждений
+    fn apply_collision_damage(world: &mut World, rules: &RulesProfile) {
+        let scale = rules.damage_scale();
+        if scale <= 0.0 {
+            return;
+        }
+        let hits: Vec<(hecs::Entity, f32)> = world
+            .query::<&Resource<EventQueue<CollisionEvent>>>()
+            .iter()
+            .next()
+            .map(|(_, queue)| {
+                queue
+                    .0
+                    .iter()
+                    .filter(|event| event.phase == CollisionPhase::Started)
+                    .flat_map(|event| [(event.entity1, event.impulse), (event.entity2, event.impulse)])
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        for (entity, impulse) in hits {
+            if let Ok(destructible) = world.query_one_mut::<&mut DestructibleComponent>(entity) {
+                destructible.health = (destructible.health - impulse * COLLISION_DAMAGE_PER_IMPULSE * scale).max(0.0);
+            }
+        }
+    }
+
+    /// Флаги для каждого пилота: желтый важнее синего
+    fn assign_flags(entrants: &[Entrant], rules: &RulesProfile) -> HashMap<String, RaceFlag> {
+        let leader = entrants.iter().find(|e| e.race_position == 1);
+        entrants
+            .iter()
+            .map(|entrant| {
+                let yellow = rules.flags.yellow_flags
+                    && entrants.iter().any(|other| {
+                        other.entity != entrant.entity
+                            && other.on_track
+                            && other.speed < rules.flags.yellow_stopped_speed
+                            && other.position.distance(entrant.position) < rules.flags.yellow_distance
+                            && (other.position - entrant.position).dot(entrant.forward) > 0.0
+                    });
+                let blue = rules.flags.blue_flags
+                    && leader.is_some_and(|leader| {
+                        leader.entity != entrant.entity
+                            && leader.lap > entrant.lap
+                            && leader.position.distance(entrant.position) < rules.flags.blue_flag_distance
+                            && (leader.position - entrant.position).dot(entrant.forward) < 0.0
+                    });
+                let flag = if yellow {
+                    RaceFlag::Yellow
+                } else if blue {
+                    RaceFlag::Blue
+                } else {
+                    RaceFlag::Green
+                };
+                (entrant.driver_name.clone(), flag)
+            })
+            .collect()
+    }
+}
+
+impl Default for RaceControlSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl System for RaceControlSystem {
+    fn update(&mut self, world: &mut World, delta_time: f32) {
+        let rules = current_rules(world);
+        Self::apply_collision_damage(world, &rules);
+
+        let Some(elapsed) = world
+            .query::<&Resource<RaceState>>()
+            .iter()
+            .next()
+            .filter(|(_, res)| res.0.running())
+            .map(|(_, res)| res.0.elapsed)
+        else {
+            return;
+        };
+
+        let mut entrants: Vec<Entrant> = world
+            .query::<(&CarComponent, &RaceProgressComponent, &TransformComponent)>()
+            .iter()
+            .filter(|(_, (_, progress, _))| !progress.finished)
+            .map(|(entity, (car, progress, transform))| Entrant {
+                entity,
+                driver_name: progress.driver_name.clone(),
+                position: transform.position,
+                forward: transform.rotation * Vec3::Z,
+                speed: car.current_speed.abs(),
+                lap: progress.lap,
+                race_position: progress.position,
+                on_track: true,
+                in_pit_lane: false,
+            })
+            .collect();
+        for entrant in &mut entrants {
+            // Пит-лейн не считается выездом за границы трассы
+            entrant.in_pit_lane = in_pit_lane(world, entrant.position);
+            entrant.on_track = entrant.in_pit_lane || car_on_track(world, entrant.entity, entrant.position);
+        }
+        let pit_speeding = self.supervise_pit_lane(world, &entrants, &rules);
+
+        // Границы трассы: выезд дольше допустимого - нарушение, сверх предупреждений - штраф
+        let grace = rules.off_track_grace();
+        let mut violations = Vec::new();
+        for entrant in &entrants {
+            if entrant.on_track {
+                self.off_track_time.remove(&entrant.entity);
+                self.counted.remove(&entrant.entity);
+                continue;
+            }
+            let time = self.off_track_time.entry(entrant.entity).or_insert(0.0);
+            *time += delta_time;
+            if *time >= grace && self.counted.insert(entrant.entity) {
+                violations.push(entrant.driver_name.clone());
+            }
+        }
+
+        let flags = Self::assign_flags(&entrants, &rules);
+        if !world.query::<&Resource<RaceControl>>().iter().any(|_| true) {
+            world.spawn((Resource(RaceControl::default()),));
+        }
+        let Some((_, control)) = world.query_mut::<&mut Resource<RaceControl>>().into_iter().next() else {
+            return;
+        };
+        let control = &mut control.0;
+        control.flags = flags;
+
+        let mut penalties = Vec::new();
+        for driver_name in violations {
+            let count = control.track_limit_violations.entry(driver_name.clone()).or_insert(0);
+            *count += 1;
+            if *count <= rules.penalties.track_limit_warnings {
+                eprintln!(
+                    "Нарушение границ трассы: {} (предупреждение {}/{})",
+                    driver_name, count, rules.penalties.track_limit_warnings
+                );
+                continue;
+            }
+            eprintln!("Штраф {} с: {} за нарушение границ трассы", rules.penalties.track_limit_penalty, driver_name);
+            penalties.push(Penalty {
+                driver_name,
+                time: elapsed,
+                reason: "Track limits".to_string(),
+                seconds: rules.penalties.track_limit_penalty,
+            });
+        }
+
+        // Сбитые машинами конусы, знаки и шины
+        if rules.penalties.prop_hit_penalty > 0.0 {
+            let knocked: Vec<(hecs::Entity, ObstacleType)> = world
+                .query::<&Resource<EventQueue<PropKnockedEvent>>>()
+                .iter()
+                .next()
+                .map(|(_, queue)| queue.0.iter().filter_map(|event| Some((event.car_entity?, event.obstacle_type))).collect())
+                .unwrap_or_default();
+            for (car_entity, obstacle_type) in knocked {
+                let Some(entrant) = entrants.iter().find(|entrant| entrant.entity == car_entity) else {
+                    continue;
+                };
+                eprintln!("Штраф {} с: {} за сбитый предмет", rules.penalties.prop_hit_penalty, entrant.driver_name);
+                penalties.push(Penalty {
+                    driver_name: entrant.driver_name.clone(),
+                    time: elapsed,
+                    reason: format!("Knocked {}", format!("{:?}", obstacle_type).to_lowercase()),
+                    seconds: rules.penalties.prop_hit_penalty,
+                });
+            }
+        }
+
+        for driver_name in pit_speeding {
+            eprintln!("Штраф {} с: {} за превышение скорости на пит-лейне", rules.penalties.pit_speeding_penalty, driver_name);
+            penalties.push(Penalty {
+                driver_name,
+                time: elapsed,
+                reason: "Pit lane speeding".to_string(),
+                seconds: rules.penalties.pit_speeding_penalty,
+            });
+        }
+
+        if penalties.is_empty() {
+            return;
+        }
+        let Some((_, results)) = world.query_mut::<&mut Resource<SessionResults>>().into_iter().next() else {
+            return;
+        };
+        for penalty in penalties {
+            if let Some(driver) = results.0.drivers.iter_mut().find(|d| d.driver_name == penalty.driver_name) {
+                driver.penalty_time += penalty.seconds;
+            }
+            results.0.penalties.push(penalty);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game_world::track::PitLaneComponent;
+    use crate::game_world::track_limits::TrackLimitsComponent;
+    use crate::gameplay::results::DriverResult;
+
+    const DT: f32 = 0.1;
+
+    fn race_world(rules: RulesProfile) -> (World, hecs::Entity) {
+        let mut world = World::new();
+        world.spawn((Resource(rules),));
+        world.spawn((Resource(RaceState::new(3)),));
+        let mut results = SessionResults::new("Test", "Test");
+        results.drivers.push(DriverResult {
+            number: 1,
+            driver_name: "Player".to_string(),
+            car_name: "GT".to_string(),
+            laps: Vec::new(),
+            finished: false,
+            penalty_time: 0.0,
+        });
+        world.spawn((Resource(results),));
+        let car = world.spawn((
+            CarComponent::default(),
+            RaceProgressComponent::new("Player"),
+            TransformComponent::default(),
+            TrackLimitsComponent::default(),
+        ));
+        (world, car)
+    }
+
+    fn set_off_track(world: &mut World, car: hecs::Entity, off_track: bool) {
+        world.get::<&mut TrackLimitsComponent>(car).unwrap().off_track = off_track;
+    }
+
+    fn penalties(world: &World) -> (f32, Vec<String>) {
+        let results = world.query::<&Resource<SessionResults>>().iter().next().map(|(_, r)| r.0.clone()).unwrap();
+        (results.drivers[0].penalty_time, results.penalties.iter().map(|p| p.reason.clone()).collect())
+    }
+
+    #[test]
+    fn grace_depends_on_track_limits_rule() {
+        assert_eq!(RulesProfile::standard().off_track_grace(), 1.0);
+        assert_eq!(RulesProfile::sim().off_track_grace(), 0.0);
+        assert_eq!(RulesProfile::standard().damage_scale(), 0.5);
+    }
+
+    #[test]
+    fn track_limits_warn_before_penalizing() {
+        let (mut world, car) = race_world(RulesProfile::sim());
+        let mut system = RaceControlSystem::new();
+        for _ in 0..4 {
+            set_off_track(&mut world, car, true);
+            system.update(&mut world, DT);
+            // Долгий выезд засчитывается один раз
+            system.update(&mut world, DT);
+            set_off_track(&mut world, car, false);
+            system.update(&mut world, DT);
+        }
+        let control = world.query::<&Resource<RaceControl>>().iter().next().map(|(_, r)| r.0.clone()).unwrap();
+        assert_eq!(control.track_limit_violations["Player"], 4);
+        assert_eq!(penalties(&world), (5.0, vec!["Track limits".to_string()]));
+    }
+
+    #[test]
+    fn short_excursion_is_forgiven_under_relaxed_limits() {
+        let (mut world, car) = race_world(RulesProfile::standard());
+        let mut system = RaceControlSystem::new();
+        set_off_track(&mut world, car, true);
+        for _ in 0..5 {
+            system.update(&mut world, DT);
+        }
+        let control = world.query::<&Resource<RaceControl>>().iter().next().map(|(_, r)| r.0.clone()).unwrap();
+        assert!(!control.track_limit_violations.contains_key("Player"));
+    }
+
+    #[test]
+    fn pit_lane_speeding_is_penalized_once_per_pass() {
+        let (mut world, car) = race_world(RulesProfile::standard());
+        world.spawn((PitLaneComponent { width: 10.0, length: 100.0 }, TransformComponent::default()));
+        world.get::<&mut CarComponent>(car).unwrap().current_speed = 30.0;
+        let mut system = RaceControlSystem::new();
+        system.update(&mut world, DT);
+        system.update(&mut world, DT);
+        assert!(world.get::<&CarComponent>(car).unwrap().speed_limiter);
+        assert_eq!(penalties(&world), (5.0, vec!["Pit lane speeding".to_string()]));
+    }
+
+    #[test]
+    fn knocked_props_are_penalized_in_sim() {
+        let (mut world, car) = race_world(RulesProfile::sim());
+        let mut queue = EventQueue::new();
+        queue.publish(PropKnockedEvent {
+            obstacle_type: ObstacleType::Cone,
+            car_entity: Some(car),
+        });
+        queue.publish(PropKnockedEvent {
+            obstacle_type: ObstacleType::Tire,
+            car_entity: None,
+        });
+        world.spawn((Resource(queue),));
+        RaceControlSystem::new().update(&mut world, DT);
+        assert_eq!(penalties(&world), (2.0, vec!["Knocked cone".to_string()]));
+    }
+}
@@ -0,0 +1,374 @@
+use crate::core::ecs::{EventQueue, Resource, System};
+use crate::game_world::car::{CarClass, CarComponent, PlayerCarComponent};
+use crate::game_world::track::{TrackComponent, TrackMetadata};
+use crate::gameplay::ghost::{file_safe, LapCompletedEvent};
+use crate::gameplay::race::RaceState;
+use crate::gameplay::results::format_time;
+use hecs::World;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Каталог книг рекордов, по файлу на трассу и конфигурацию
+pub const RECORDS_DIR: &str = "saves/records";
+
+/// Сколько последних заездов хранится в истории трассы
+const RECENT_SESSIONS: usize = 10;
+
+/// Шаг рубежей времени круга, с: «первый круг быстрее 1:30», «быстрее 1:25»...
+const MILESTONE_STEP: f32 = 5.0;
+
+/// Сколько показывается уведомление о достижении, с
+const NOTICE_TIME: f32 = 4.0;
+
+/// Рекорд трассы в классе машин
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClassRecord {
+    pub class: CarClass,
+    pub profile: String,
+    pub car: String,
+    pub lap_time: f32,
+    pub set_at: u64, // Unix-время
+}
+
+/// Итоги одного заезда на трассе
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionSummary {
+    pub profile: String,
+    pub car: String,
+    pub class: CarClass,
+    pub started_at: u64,
+    pub laps: u32,
+    pub best_lap: f32,
+}
+
+/// Достижение профиля на трассе; ключ не дает повторить то же достижение
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Milestone {
+    pub profile: String,
+    pub key: String,
+    pub text: String,
+    pub achieved_at: u64,
+}
+
+/// Книга рекордов трассы: лучшие круги по классам, история заездов и достижения
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TrackRecordBook {
+    pub track: String,
+    pub layout: String,
+    #[serde(default)]
+    pub length: f32, // м
+    #[serde(default)]
+    pub metadata: TrackMetadata, // Описание трассы при последней загрузке
+    pub records: Vec<ClassRecord>,
+    pub personal_bests: HashMap<String, f32>, // Лучший круг профиля на любой машине
+    pub sessions: Vec<SessionSummary>,        // От новых к старым
+    pub milestones: Vec<Milestone>,
+}
+
+impl TrackRecordBook {
+    pub fn new(track: &str, layout: &str) -> Self {
+        Self {
+            track: track.to_string(),
+            layout: layout.to_string(),
+            ..Default::default()
+        }
+    }
+
+    pub fn path(dir: &Path, track: &str, layout: &str) -> PathBuf {
+        dir.join(format!("{}.json", file_safe(&format!("{}_{}", track, layout))))
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+        serde_json::from_str(&text).map_err(|e| e.to_string())
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        let text = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        std::fs::write(path, text).map_err(|e| e.to_string())
+    }
+
+    pub fn class_record(&self, class: CarClass) -> Option<&ClassRecord> {
+        self.records.iter().find(|record| record.class == class)
+    }
+
+    /// Учет круга; возвращает тексты новых достижений
+    pub fn record_lap(&mut self, profile: &str, car: &str, class: CarClass, lap_time: f32, now: u64) -> Vec<String> {
+        let mut reached = Vec::new();
+        let previous_best = self.personal_bests.get(profile).copied();
+
+        match previous_best {
+            None => reached.push(("first_lap".to_string(), "Первый круг на трассе".to_string())),
+            Some(best) => {
+                // Ближайший рубеж выше нового времени, который прежний рекорд не брал
+                let threshold = (lap_time / MILESTONE_STEP).floor() * MILESTONE_STEP + MILESTONE_STEP;
+                if best >= threshold {
+                    reached.push((
+                        format!("sub_{}", threshold as u32),
+                        format!("Первый круг быстрее {}", format_time(threshold)),
+                    ));
+                }
+            }
+        }
+        if previous_best.is_none_or(|best| lap_time < best) {
+            self.personal_bests.insert(profile.to_string(), lap_time);
+        }
+
+        let record = ClassRecord {
+            class,
+            profile: profile.to_string(),
+            car: car.to_string(),
+            lap_time,
+            set_at: now,
+        };
+        match self.records.iter_mut().find(|record| record.class == class) {
+            Some(current) if current.lap_time <= lap_time => {}
+            Some(current) => {
+                reached.push((
+                    format!("class_record_{}_{}", class.label(), now),
+                    format!("Рекорд трассы в классе {}: {}", class.label(), format_time(lap_time)),
+                ));
+                *current = record;
+            }
+            None => self.records.push(record),
+        }
+
+        let mut texts = Vec::new();
+        for (key, text) in reached {
+            if self.milestones.iter().any(|m| m.profile == profile && m.key == key) {
+                continue;
+            }
+            self.milestones.push(Milestone {
+                profile: profile.to_string(),
+                key,
+                text: text.clone(),
+                achieved_at: now,
+            });
+            texts.push(text);
+        }
+        texts
+    }
+
+    pub fn push_session(&mut self, session: SessionSummary) {
+        self.sessions.insert(0, session);
+        self.sessions.truncate(RECENT_SESSIONS);
+    }
+}
+
+/// Уведомление о достижении для интерфейса
+#[derive(Debug, Clone, Default)]
+pub struct MilestoneNotice {
+    pub text: Option<String>,
+    pub hold_time: f32,
+}
+
+fn unix_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Книга рекордов текущей трассы; None, если трасса не загружена
+pub fn current_record_book(world: &World) -> Option<TrackRecordBook> {
+    let (track, layout) = world
+        .query::<&TrackComponent>()
+        .iter()
+        .next()
+        .map(|(_, track)| (track.name.clone(), track.layout.clone()))?;
+    let loaded = world
+        .query::<&Resource<TrackRecordBook>>()
+        .iter()
+        .map(|(_, book)| book.0.clone())
+        .find(|book| book.track == track && book.layout == layout);
+    if loaded.is_some() {
+        return loaded;
+    }
+    let path = TrackRecordBook::path(Path::new(RECORDS_DIR), &track, &layout);
+    Some(TrackRecordBook::load(&path).unwrap_or_else(|_| TrackRecordBook::new(&track, &layout)))
+}
+
+/// Читает книгу рекордов загруженной трассы в ресурс мира и обновляет в ней описание трассы
+pub fn load_track_records(world: &mut World) {
+    let Some((length, metadata)) = world
+        .query::<&TrackComponent>()
+        .iter()
+        .next()
+        .map(|(_, track)| (track.length, track.metadata.clone()))
+    else {
+        return;
+    };
+    let Some(mut book) = current_record_book(world) else {
+        return;
+    };
+    book.length = length;
+    book.metadata = metadata;
+    match world.query_mut::<&mut Resource<TrackRecordBook>>().into_iter().next() {
+        Some((_, current)) => current.0 = book,
+        None => {
+            world.spawn((Resource(book),));
+        }
+    }
+}
+
+/// Ведение книги рекордов по завершенным кругам: рекорды классов, история заездов и достижения
+pub struct RecordBookSystem {
+    books: HashMap<(String, String), TrackRecordBook>, // Книги, уже прочитанные с диска
+    session: Option<((String, String), SessionSummary)>,
+}
+
+impl RecordBookSystem {
+    pub fn new() -> Self {
+        Self {
+            books: HashMap::new(),
+            session: None,
+        }
+    }
+
+    fn book(&mut self, track: &str, layout: &str) -> &mut TrackRecordBook {
+        self.books
+            .entry((track.to_string(), layout.to_string()))
+            .or_insert_with(|| {
+                TrackRecordBook::load(&TrackRecordBook::path(Path::new(RECORDS_DIR), track, layout))
+                    .unwrap_or_else(|_| TrackRecordBook::new(track, layout))
+            })
+    }
+
+    fn save(&mut self, track: &str, layout: &str) {
+        let path = TrackRecordBook::path(Path::new(RECORDS_DIR), track, layout);
+        if let Err(e) = self.book(track, layout).save(&path) {
+            eprintln!("Не удалось сохранить книгу рекордов {}: {}", path.display(), e);
+        }
+    }
+
+    /// Заезд заканчивается вместе с гонкой и попадает в историю трассы; true, если книга изменилась
+    fn close_session(&mut self) -> bool {
+        let Some(((track, layout), session)) = self.session.take() else {
+            return false;
+        };
+        self.book(&track, &layout).push_session(session);
+        self.save(&track, &layout);
+        true
+    }
+}
+
+impl Default for RecordBookSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl System for RecordBookSystem {
+    fn update(&mut self, world: &mut World, delta_time: f32) {
+        // Книга, прочитанная при загрузке трассы, вместе с описанием трассы
+        if let Some((_, loaded)) = world.query::<&Resource<TrackRecordBook>>().iter().next() {
+            let book = &loaded.0;
+            let cached = self.books.entry((book.track.clone(), book.layout.clone())).or_insert_with(|| book.clone());
+            if cached.metadata != book.metadata {
+                cached.metadata = book.metadata.clone();
+            }
+            cached.length = book.length;
+        }
+
+        let mut laps = Vec::new();
+        if let Some((_, queue)) = world
+            .query_mut::<&mut Resource<EventQueue<LapCompletedEvent>>>()
+            .into_iter()
+            .next()
+        {
+            queue.0.consume(|event| laps.push(event.clone()));
+        }
+        let class = world
+            .query::<(&PlayerCarComponent, &CarComponent)>()
+            .iter()
+            .next()
+            .map(|(_, (_, car))| car.class());
+
+        let mut changed = false;
+        let mut reached = Vec::new();
+        for lap in laps {
+            let Some(class) = class else {
+                continue;
+            };
+            let key = (lap.combo.track.clone(), lap.combo.layout.clone());
+            let now = unix_now();
+
+            // Смена пилота или машины начинает новый заезд
+            let same_session = self
+                .session
+                .as_ref()
+                .is_some_and(|(session_key, session)| *session_key == key && session.profile == lap.profile && session.car == lap.combo.car);
+            if !same_session {
+                // Книгу в мире все равно обновит записанный ниже круг
+                self.close_session();
+                self.session = Some((
+                    key.clone(),
+                    SessionSummary {
+                        profile: lap.profile.clone(),
+                        car: lap.combo.car.clone(),
+                        class,
+                        started_at: now,
+                        laps: 0,
+                        best_lap: lap.lap_time,
+                    },
+                ));
+            }
+            if let Some((_, session)) = &mut self.session {
+                session.laps += 1;
+                session.best_lap = session.best_lap.min(lap.lap_time);
+            }
+
+            let texts = self.book(&key.0, &key.1).record_lap(&lap.profile, &lap.combo.car, class, lap.lap_time, now);
+            for text in &texts {
+                eprintln!("Достижение {}: {}", lap.profile, text);
+            }
+            reached.extend(texts);
+            self.save(&key.0, &key.1);
+            changed = true;
+        }
+
+        let racing = world
+            .query::<&Resource<RaceState>>()
+            .iter()
+            .any(|(_, res)| res.0.running() || res.0.paused);
+        if !racing {
+            changed |= self.close_session();
+        }
+        // Книга текущей трассы в мире повторяет записанную на диск
+        if changed {
+            if let Some((_, current)) = world.query_mut::<&mut Resource<TrackRecordBook>>().into_iter().next() {
+                if let Some(book) = self.books.get(&(current.0.track.clone(), current.0.layout.clone())) {
+                    current.0 = book.clone();
+                }
+            }
+        }
+
+        let resource = world
+            .query_mut::<&mut Resource<MilestoneNotice>>()
+            .into_iter()
+            .next()
+            .map(|(_, res)| &mut res.0);
+        let Some(notice) = resource else {
+            let text = reached.pop();
+            let hold_time = if text.is_some() { NOTICE_TIME } else { 0.0 };
+            world.spawn((Resource(MilestoneNotice { text, hold_time }),));
+            return;
+        };
+        match reached.pop() {
+            Some(text) => {
+                notice.text = Some(text);
+                notice.hold_time = NOTICE_TIME;
+            }
+            None => {
+                notice.hold_time = (notice.hold_time - delta_time).max(0.0);
+                if notice.hold_time <= 0.0 {
+                    notice.text = None;
+                }
+            }
+        }
+    }
+}
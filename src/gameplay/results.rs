@@ -0,0 +1,552 @@
+use crate::core::ecs::{EventQueue, Resource, System};
+use crate::game_world::car::{CarComponent, PlayerCarComponent};
+use crate::game_world::track::{CarLapCompletedEvent, SectorCompletedEvent};
+use crate::gameplay::input_validation::InputFlag;
+use crate::gameplay::profile::{race_prize, PlayerProfile, PLAYER_PROFILE_PATH};
+use crate::gameplay::race_control::Penalty;
+use crate::gameplay::race::{RacePhase, RaceProgressComponent, RaceState};
+use hecs::World;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Каталог, куда выгружаются результаты завершенных заездов
+pub const RESULTS_EXPORT_DIR: &str = "exports";
+
+/// Разделитель полей, принятый в таблицах хронометража
+const CSV_DELIMITER: char = ';';
+
+/// Время круга участника с разбивкой по секторам
+#[derive(Debug, Clone)]
+pub struct LapResult {
+    pub lap: u32,
+    pub lap_time: f32,
+    pub sectors: Vec<f32>,
+    pub elapsed: f32, // Время гонки на момент пересечения линии
+}
+
+/// Итоги одного участника
+#[derive(Debug, Clone)]
+pub struct DriverResult {
+    pub number: u32, // Стартовый номер
+    pub driver_name: String,
+    pub car_name: String,
+    pub laps: Vec<LapResult>,
+    pub finished: bool,
+    pub penalty_time: f32, // Штрафное время, прибавляется к итоговому, с
+}
+
+impl DriverResult {
+    pub fn total_time(&self) -> f32 {
+        self.laps.last().map(|lap| lap.elapsed + self.penalty_time).unwrap_or(0.0)
+    }
+
+    /// Лучший круг и его номер
+    pub fn best_lap(&self) -> Option<(f32, u32)> {
+        self.laps
+            .iter()
+            .map(|lap| (lap.lap_time, lap.lap))
+            .min_by(|a, b| a.0.total_cmp(&b.0))
+    }
+}
+
+/// Протокол заезда: участники в порядке стартовых номеров
+#[derive(Debug, Clone, Default)]
+pub struct SessionResults {
+    pub event_name: String,
+    pub track_name: String,
+    pub drivers: Vec<DriverResult>,
+    pub input_flags: Vec<InputFlag>, // Подозрительный ввод в рейтинговых заездах
+    pub penalties: Vec<Penalty>,
+    pub exported: bool,
+}
+
+impl SessionResults {
+    pub fn new(event_name: &str, track_name: &str) -> Self {
+        Self {
+            event_name: event_name.to_string(),
+            track_name: track_name.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Классификация: больше кругов впереди, при равенстве - меньшее время
+    pub fn classification(&self) -> Vec<&DriverResult> {
+        let mut drivers: Vec<&DriverResult> = self.drivers.iter().collect();
+        drivers.sort_by(|a, b| {
+            b.laps
+                .len()
+                .cmp(&a.laps.len())
+                .then(a.total_time().total_cmp(&b.total_time()))
+                .then(a.number.cmp(&b.number))
+        });
+        drivers
+    }
+
+    /// Место участника по окончании круга среди тех, кто его завершил
+    fn position_after_lap(&self, driver: &DriverResult, lap: &LapResult) -> usize {
+        1 + self
+            .drivers
+            .iter()
+            .filter(|other| other.number != driver.number)
+            .filter_map(|other| other.laps.iter().find(|l| l.lap == lap.lap))
+            .filter(|other_lap| other_lap.elapsed < lap.elapsed)
+            .count()
+    }
+
+    /// Сведения о сессии отдельной таблицей, чтобы первой строкой остальных оставались заголовки
+    pub fn session_csv(&self) -> String {
+        [
+            csv_row(&["EVENT", "TRACK", "DRIVERS"]),
+            csv_row(&[&self.event_name, &self.track_name, &self.drivers.len().to_string()]),
+        ]
+        .concat()
+    }
+
+    /// Итоговая классификация: место, отставания и лучший круг
+    pub fn classification_csv(&self) -> String {
+        let mut rows = vec![csv_row(&[
+            "POSITION", "NUMBER", "DRIVER_NAME", "CAR", "STATUS", "LAPS", "TOTAL_TIME",
+            "GAP_FIRST", "GAP_PREVIOUS", "FL_LAPNUM", "FL_TIME",
+        ])];
+        let classification = self.classification();
+        let leader = classification.first().copied();
+        let mut previous: Option<&DriverResult> = None;
+        for (index, driver) in classification.iter().enumerate() {
+            let (best_time, best_lap) = driver
+                .best_lap()
+                .map(|(time, lap)| (format_time(time), lap.to_string()))
+                .unwrap_or_default();
+            rows.push(csv_row(&[
+                &(index + 1).to_string(),
+                &driver.number.to_string(),
+                &driver.driver_name,
+                &driver.car_name,
+                if driver.finished { "Classified" } else { "Not classified" },
+                &driver.laps.len().to_string(),
+                &format_time(driver.total_time()),
+                &leader.filter(|_| index > 0).map(|l| format_gap(driver, l)).unwrap_or_default(),
+                &previous.map(|p| format_gap(driver, p)).unwrap_or_default(),
+                &best_lap,
+                &best_time,
+            ]));
+            previous = Some(driver);
+        }
+        rows.concat()
+    }
+
+    /// Круг за кругом: время, улучшение (1 - личное, 2 - абсолютное) и место
+    pub fn laps_csv(&self) -> String {
+        let mut rows = vec![csv_row(&[
+            "NUMBER", "DRIVER_NAME", "LAP_NUMBER", "LAP_TIME", "LAP_IMPROVEMENT", "ELAPSED", "POSITION",
+        ])];
+        let session_best = self
+            .drivers
+            .iter()
+            .filter_map(|d| d.best_lap())
+            .map(|(time, _)| time)
+            .min_by(f32::total_cmp);
+        for driver in &self.drivers {
+            let mut personal_best = f32::INFINITY;
+            for lap in &driver.laps {
+                let improvement = improvement(lap.lap_time, &mut personal_best, session_best);
+                rows.push(csv_row(&[
+                    &driver.number.to_string(),
+                    &driver.driver_name,
+                    &lap.lap.to_string(),
+                    &format_time(lap.lap_time),
+                    &improvement.to_string(),
+                    &format_time(lap.elapsed),
+                    &self.position_after_lap(driver, lap).to_string(),
+                ]));
+            }
+        }
+        rows.concat()
+    }
+
+    /// Разбивка кругов по секторам с улучшениями в каждом секторе
+    pub fn sectors_csv(&self) -> String {
+        let sector_count = self
+            .drivers
+            .iter()
+            .flat_map(|d| d.laps.iter())
+            .map(|lap| lap.sectors.len())
+            .max()
+            .unwrap_or(0);
+        let mut header = vec!["NUMBER".to_string(), "DRIVER_NAME".to_string(), "LAP_NUMBER".to_string(), "LAP_TIME".to_string()];
+        for sector in 1..=sector_count {
+            header.push(format!("S{}", sector));
+            header.push(format!("S{}_IMPROVEMENT", sector));
+        }
+        let header: Vec<&str> = header.iter().map(String::as_str).collect();
+        let mut rows = vec![csv_row(&header)];
+
+        let session_best: Vec<Option<f32>> = (0..sector_count)
+            .map(|sector| {
+                self.drivers
+                    .iter()
+                    .flat_map(|d| d.laps.iter())
+                    .filter_map(|lap| lap.sectors.get(sector).copied())
+                    .min_by(f32::total_cmp)
+            })
+            .collect();
+        for driver in &self.drivers {
+            let mut personal_best = vec![f32::INFINITY; sector_count];
+            for lap in &driver.laps {
+                let mut fields = vec![
+                    driver.number.to_string(),
+                    driver.driver_name.clone(),
+                    lap.lap.to_string(),
+                    format_time(lap.lap_time),
+                ];
+                for sector in 0..sector_count {
+                    match lap.sectors.get(sector) {
+                        Some(&time) => {
+                            let code = improvement(time, &mut personal_best[sector], session_best[sector]);
+                            fields.push(format_time(time));
+                            fields.push(code.to_string());
+                        }
+                        None => fields.extend([String::new(), String::new()]),
+                    }
+                }
+                let fields: Vec<&str> = fields.iter().map(String::as_str).collect();
+                rows.push(csv_row(&fields));
+            }
+        }
+        rows.concat()
+    }
+
+    /// Отметки проверки ввода для разбора судьями
+    pub fn input_flags_csv(&self) -> String {
+        let mut rows = vec![csv_row(&["DRIVER_NAME", "ELAPSED", "FLAG", "DETAIL"])];
+        for flag in &self.input_flags {
+            rows.push(csv_row(&[
+                &flag.driver_name,
+                &format_time(flag.time),
+                &format!("{:?}", flag.kind),
+                &flag.detail,
+            ]));
+        }
+        rows.concat()
+    }
+
+    /// Штрафы дирекции гонки
+    pub fn penalties_csv(&self) -> String {
+        let mut rows = vec![csv_row(&["DRIVER_NAME", "ELAPSED", "REASON", "PENALTY"])];
+        for penalty in &self.penalties {
+            rows.push(csv_row(&[
+                &penalty.driver_name,
+                &format_time(penalty.time),
+                &penalty.reason,
+                &format!("{:.3}", penalty.seconds),
+            ]));
+        }
+        rows.concat()
+    }
+
+    /// Запись всех таблиц в каталог; возвращает пути созданных файлов
+    pub fn export_csv(&self, dir: &Path) -> Result<Vec<PathBuf>, String> {
+        std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        let mut files = vec![
+            ("session.csv", self.session_csv()),
+            ("classification.csv", self.classification_csv()),
+            ("laps.csv", self.laps_csv()),
+            ("sectors.csv", self.sectors_csv()),
+        ];
+        if !self.input_flags.is_empty() {
+            files.push(("input_flags.csv", self.input_flags_csv()));
+        }
+        if !self.penalties.is_empty() {
+            files.push(("penalties.csv", self.penalties_csv()));
+        }
+        let mut paths = Vec::new();
+        for (name, contents) in files {
+            let path = dir.join(name);
+            std::fs::write(&path, contents).map_err(|e| format!("{}: {}", path.display(), e))?;
+            paths.push(path);
+        }
+        Ok(paths)
+    }
+}
+
+/// Код улучшения: 2 - лучшее в сессии, 1 - личное, 0 - без улучшения
+fn improvement(time: f32, personal_best: &mut f32, session_best: Option<f32>) -> u8 {
+    if time >= *personal_best {
+        return 0;
+    }
+    *personal_best = time;
+    if session_best == Some(time) { 2 } else { 1 }
+}
+
+/// Время в формате m:ss.sss (h:mm:ss.sss для длинных заездов)
+pub fn format_time(seconds: f32) -> String {
+    let millis = (seconds.max(0.0) * 1000.0).round() as u64;
+    let (hours, minutes, secs, ms) = (millis / 3_600_000, millis / 60_000 % 60, millis / 1000 % 60, millis % 1000);
+    if hours > 0 {
+        format!("{}:{:02}:{:02}.{:03}", hours, minutes, secs, ms)
+    } else {
+        format!("{}:{:02}.{:03}", minutes, secs, ms)
+    }
+}
+
+/// Отставание от впереди идущего: время или число кругов
+fn format_gap(driver: &DriverResult, ahead: &DriverResult) -> String {
+    let lap_deficit = ahead.laps.len().saturating_sub(driver.laps.len());
+    match lap_deficit {
+        0 => format!("{:.3}", driver.total_time() - ahead.total_time()),
+        1 => "1 LAP".to_string(),
+        laps => format!("{} LAPS", laps),
+    }
+}
+
+/// Строка CSV; поля с разделителем, запятой или кавычками экранируются:
+/// таблицы с автоопределением разделителя иначе режут имя по запятой
+fn csv_row(fields: &[&str]) -> String {
+    let escaped: Vec<String> = fields
+        .iter()
+        .map(|field| {
+            if field.contains([CSV_DELIMITER, ',', '"', '\n']) {
+                format!("\"{}\"", field.replace('"', "\"\""))
+            } else {
+                field.to_string()
+            }
+        })
+        .collect();
+    escaped.join(&CSV_DELIMITER.to_string()) + "\n"
+}
+
+/// Участник протокола и сектора его текущего круга
+struct CarTiming {
+    driver_index: usize,
+    sectors: Vec<f32>,
+}
+
+/// Система протокола: круги и сектора по событиям хронометража трассы, выгрузка CSV по окончании гонки
+pub struct RaceResultsSystem {
+    timings: HashMap<hecs::Entity, CarTiming>,
+}
+
+impl RaceResultsSystem {
+    pub fn new() -> Self {
+        Self {
+            timings: HashMap::new(),
+        }
+    }
+}
+
+impl Default for RaceResultsSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl System for RaceResultsSystem {
+    fn update(&mut self, world: &mut World, _delta_time: f32) {
+        let Some((phase, total_laps, elapsed)) = world
+            .query::<&Resource<RaceState>>()
+            .iter()
+            .next()
+            .map(|(_, res)| (res.0.phase, res.0.laps(), res.0.elapsed))
+        else {
+            return;
+        };
+        if phase != RacePhase::Racing {
+            return;
+        }
+        if !world.query::<&Resource<SessionResults>>().iter().any(|_| true) {
+            return;
+        }
+
+        let sectors: Vec<SectorCompletedEvent> = world
+            .query::<&Resource<EventQueue<SectorCompletedEvent>>>()
+            .iter()
+            .flat_map(|(_, queue)| queue.0.iter().cloned().collect::<Vec<_>>())
+            .collect();
+        let laps: Vec<CarLapCompletedEvent> = world
+            .query::<&Resource<EventQueue<CarLapCompletedEvent>>>()
+            .iter()
+            .flat_map(|(_, queue)| queue.0.iter().cloned().collect::<Vec<_>>())
+            .collect();
+
+        let cars: Vec<(hecs::Entity, String, String, bool)> = world
+            .query::<(&RaceProgressComponent, &CarComponent)>()
+            .iter()
+            .map(|(entity, (progress, car))| (entity, progress.driver_name.clone(), car.name.clone(), progress.finished))
+            .collect();
+        let player = world.query::<&PlayerCarComponent>().iter().next().map(|(entity, _)| entity);
+
+        let Some((_, results)) = world.query_mut::<&mut Resource<SessionResults>>().into_iter().next() else {
+            return;
+        };
+        let results = &mut results.0;
+
+        let mut progress_updates = Vec::new();
+        for (entity, driver_name, car_name, finished) in cars {
+            let timing = self.timings.entry(entity).or_insert_with(|| {
+                results.drivers.push(DriverResult {
+                    number: results.drivers.len() as u32 + 1,
+                    driver_name: driver_name.clone(),
+                    car_name: car_name.clone(),
+                    laps: Vec::new(),
+                    finished: false,
+                    penalty_time: 0.0,
+                });
+                CarTiming {
+                    driver_index: results.drivers.len() - 1,
+                    sectors: Vec::new(),
+                }
+            });
+            if finished {
+                continue;
+            }
+
+            // Последний сектор круга закрывается той же линией финиша, что и круг
+            timing.sectors.extend(sectors.iter().filter(|event| event.car_entity == entity).map(|event| event.sector_time));
+            for lap in laps.iter().filter(|event| event.car_entity == entity) {
+                let driver = &mut results.drivers[timing.driver_index];
+                driver.laps.push(LapResult {
+                    lap: driver.laps.len() as u32 + 1,
+                    lap_time: lap.lap_time,
+                    sectors: std::mem::take(&mut timing.sectors),
+                    elapsed,
+                });
+                driver.finished = driver.laps.len() as u32 >= total_laps;
+                progress_updates.push((entity, driver.laps.len() as u32 + 1, driver.finished));
+            }
+        }
+
+        let all_finished = !results.drivers.is_empty() && results.drivers.iter().all(|driver| driver.finished);
+        let mut prize = None;
+        if all_finished && !results.exported {
+            results.exported = true;
+            // Призовые игроку по месту в итоговой классификации
+            let player_number = player
+                .and_then(|entity| self.timings.get(&entity))
+                .map(|timing| results.drivers[timing.driver_index].number);
+            prize = player_number
+                .and_then(|number| results.classification().iter().position(|driver| driver.number == number))
+                .map(|index| (index + 1, race_prize(index + 1)));
+            let stamp = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0);
+            let name: String = results
+                .event_name
+                .chars()
+                .map(|c| if c.is_alphanumeric() { c } else { '_' })
+                .collect();
+            let dir = Path::new(RESULTS_EXPORT_DIR).join(format!("{}_{}", name, stamp));
+            match results.export_csv(&dir) {
+                Ok(paths) => eprintln!("Результаты заезда выгружены ({} файла): {}", paths.len(), dir.display()),
+                Err(e) => eprintln!("Не удалось выгрузить результаты заезда: {}", e),
+            }
+        }
+
+        if let Some((position, credits)) = prize {
+            if let Some((_, profile)) = world.query_mut::<&mut Resource<PlayerProfile>>().into_iter().next() {
+                profile.0.award(credits);
+                eprintln!("Место {}: начислено {} кр., всего {}", position, credits, profile.0.credits);
+                if let Err(e) = profile.0.save(Path::new(PLAYER_PROFILE_PATH)) {
+                    eprintln!("Не удалось сохранить профиль игрока: {}", e);
+                }
+            }
+        }
+
+        for (entity, lap, finished) in progress_updates {
+            if let Ok(progress) = world.query_one_mut::<&mut RaceProgressComponent>(entity) {
+                progress.lap = lap.min(total_laps);
+                progress.finished = finished;
+            }
+        }
+        if all_finished {
+            if let Some((_, state)) = world.query_mut::<&mut Resource<RaceState>>().into_iter().next() {
+                state.0.phase = RacePhase::Finished;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Участник с кругами заданной длительности подряд
+    fn driver(number: u32, name: &str, lap_times: &[f32], finished: bool) -> DriverResult {
+        let mut elapsed = 0.0;
+        let laps = lap_times
+            .iter()
+            .enumerate()
+            .map(|(index, &lap_time)| {
+                elapsed += lap_time;
+                LapResult { lap: index as u32 + 1, lap_time, sectors: Vec::new(), elapsed }
+            })
+            .collect();
+        DriverResult {
+            number,
+            driver_name: name.to_string(),
+            car_name: "GT".to_string(),
+            laps,
+            finished,
+            penalty_time: 0.0,
+        }
+    }
+
+    fn session() -> SessionResults {
+        let mut results = SessionResults::new("Cup", "Simple Track");
+        results.drivers = vec![
+            driver(1, "Retired", &[60.0], false),
+            driver(2, "Second", &[61.0, 61.0, 61.5], true),
+            driver(3, "Lapped", &[70.0, 70.0], true),
+            driver(4, "Winner", &[60.0, 61.0, 60.5], true),
+        ];
+        results
+    }
+
+    fn rows(csv: &str) -> Vec<Vec<String>> {
+        csv.lines().map(|line| line.split(CSV_DELIMITER).map(str::to_string).collect()).collect()
+    }
+
+    #[test]
+    fn classification_orders_by_laps_then_time() {
+        let results = session();
+        let order: Vec<&str> = results.classification().iter().map(|d| d.driver_name.as_str()).collect();
+        assert_eq!(order, vec!["Winner", "Second", "Lapped", "Retired"]);
+
+        let mut penalized = session();
+        penalized.drivers[3].penalty_time = 5.0;
+        assert_eq!(penalized.classification()[0].driver_name, "Second");
+    }
+
+    #[test]
+    fn classification_csv_starts_with_the_header_and_formats_gaps() {
+        let rows = rows(&session().classification_csv());
+        assert_eq!(rows[0][0], "POSITION");
+        assert_eq!(rows.len(), 5);
+        // Место; имя; статус; отставание от лидера и от впереди идущего
+        let columns = |row: &Vec<String>| (row[0].clone(), row[2].clone(), row[4].clone(), row[7].clone(), row[8].clone());
+        assert_eq!(columns(&rows[1]), ("1".into(), "Winner".into(), "Classified".into(), "".into(), "".into()));
+        assert_eq!(columns(&rows[2]), ("2".into(), "Second".into(), "Classified".into(), "2.000".into(), "2.000".into()));
+        assert_eq!(columns(&rows[3]), ("3".into(), "Lapped".into(), "Classified".into(), "1 LAP".into(), "1 LAP".into()));
+        assert_eq!(columns(&rows[4]), ("4".into(), "Retired".into(), "Not classified".into(), "2 LAPS".into(), "1 LAP".into()));
+        assert_eq!((rows[1][9].as_str(), rows[1][10].as_str()), ("1", "1:00.000"));
+    }
+
+    #[test]
+    fn session_details_go_to_their_own_table() {
+        let rows = rows(&session().session_csv());
+        assert_eq!(rows, vec![vec!["EVENT", "TRACK", "DRIVERS"], vec!["Cup", "Simple Track", "4"]]);
+    }
+
+    #[test]
+    fn formats_times() {
+        assert_eq!(format_time(0.0), "0:00.000");
+        assert_eq!(format_time(61.2345), "1:01.235");
+        assert_eq!(format_time(3725.5), "1:02:05.500");
+        assert_eq!(format_time(-1.0), "0:00.000");
+    }
+
+    #[test]
+    fn quotes_fields_with_separators_and_quotes() {
+        assert_eq!(csv_row(&["Smith, J.", "plain"]), "\"Smith, J.\";plain\n");
+        assert_eq!(csv_row(&["The \"Flash\"", "a;b"]), "\"The \"\"Flash\"\"\";\"a;b\"\n");
+    }
+}
@@ -0,0 +1,277 @@
+use crate::core::ecs::Resource;
+use crate::game_world::track::{TrackGenerationConfig, DEFAULT_TRACK_PATH};
+use hecs::World;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Файл настроек события; правила в нем заменяют встроенные профили
+pub const EVENT_CONFIG_PATH: &str = "config/event.json";
+
+/// Уровень модели повреждений
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DamageLevel {
+    Off,
+    VisualOnly,
+    Full,
+}
+
+/// Ограничение на режим коробки передач
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TransmissionRule {
+    Any,
+    ManualWithClutch,
+}
+
+/// Объем информации на HUD
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HudLevel {
+    Full,
+    Limited,
+}
+
+/// Разрешенные виды камеры
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CameraRule {
+    Any,
+    CockpitOnly,
+}
+
+/// Строгость контроля границ трассы
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TrackLimitsRule {
+    Relaxed,
+    Strict,
+}
+
+/// Штрафы, которые выдает дирекция гонки
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PenaltyRules {
+    pub track_limit_warnings: u32, // Нарушений границ трассы без штрафа
+    pub track_limit_penalty: f32,  // Штраф за каждое следующее нарушение, с
+    pub off_track_grace: f32,      // Время за пределами трассы, которое прощается при Relaxed, с
+    pub pit_speed_limit: f32,      // Ограничение скорости на пит-лейне, м/с
+    pub pit_speeding_penalty: f32, // Штраф за превышение на пит-лейне, с
+    pub prop_hit_penalty: f32,     // Штраф за каждый сбитый конус, знак или шину, с; 0 - без штрафа
+}
+
+impl Default for PenaltyRules {
+    fn default() -> Self {
+        Self {
+            track_limit_warnings: 3,
+            track_limit_penalty: 5.0,
+            off_track_grace: 1.0,
+            pit_speed_limit: 60.0 / 3.6,
+            pit_speeding_penalty: 5.0,
+            prop_hit_penalty: 0.0,
+        }
+    }
+}
+
+/// Какие флаги показывает дирекция гонки
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FlagRules {
+    pub yellow_flags: bool,        // Желтый при машине, остановившейся на трассе
+    pub yellow_stopped_speed: f32, // Ниже этой скорости машина считается остановившейся, м/с
+    pub yellow_distance: f32,      // Желтый видят машины ближе этого расстояния, м
+    pub blue_flags: bool,          // Синий круговому, которого догоняет лидер
+    pub blue_flag_distance: f32,   // Дистанция до догоняющего лидера, м
+}
+
+impl Default for FlagRules {
+    fn default() -> Self {
+        Self {
+            yellow_flags: true,
+            yellow_stopped_speed: 2.0,
+            yellow_distance: 200.0,
+            blue_flags: true,
+            blue_flag_distance: 50.0,
+        }
+    }
+}
+
+/// Профиль правил заезда, который читают игровые системы.
+/// Недостающие в конфигурации поля берутся из стандартного профиля
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RulesProfile {
+    pub name: String,
+    pub assists_allowed: bool,
+    pub damage: DamageLevel,
+    pub damage_multiplier: f32, // Множитель урона от столкновений
+    pub transmission: TransmissionRule,
+    pub hud: HudLevel,
+    pub camera: CameraRule,
+    pub track_limits: TrackLimitsRule,
+    pub off_track_slowdown: f32, // Множитель сопротивления качению на траве и гравии за трассой; 1 - только физика покрытия
+    pub penalties: PenaltyRules,
+    pub flags: FlagRules,
+}
+
+impl RulesProfile {
+    /// Стандартные правила: все помощники и виды камеры доступны
+    pub fn standard() -> Self {
+        Self {
+            name: "Standard".to_string(),
+            assists_allowed: true,
+            damage: DamageLevel::VisualOnly,
+            damage_multiplier: 0.5,
+            transmission: TransmissionRule::Any,
+            hud: HudLevel::Full,
+            camera: CameraRule::Any,
+            track_limits: TrackLimitsRule::Relaxed,
+            off_track_slowdown: 2.0,
+            penalties: PenaltyRules::default(),
+            flags: FlagRules::default(),
+        }
+    }
+
+    /// "Хардкорный" режим симулятора
+    pub fn sim() -> Self {
+        Self {
+            name: "Sim".to_string(),
+            assists_allowed: false,
+            damage: DamageLevel::Full,
+            damage_multiplier: 1.0,
+            transmission: TransmissionRule::ManualWithClutch,
+            hud: HudLevel::Limited,
+            camera: CameraRule::CockpitOnly,
+            track_limits: TrackLimitsRule::Strict,
+            off_track_slowdown: 1.0,
+            penalties: PenaltyRules {
+                off_track_grace: 0.0,
+                prop_hit_penalty: 2.0,
+                ..PenaltyRules::default()
+            },
+            flags: FlagRules::default(),
+        }
+    }
+
+    /// Множитель урона с учетом уровня модели повреждений
+    pub fn damage_scale(&self) -> f32 {
+        match self.damage {
+            DamageLevel::Off => 0.0,
+            DamageLevel::VisualOnly | DamageLevel::Full => self.damage_multiplier.max(0.0),
+        }
+    }
+
+    /// Сколько можно находиться за пределами трассы, пока это не считается нарушением
+    pub fn off_track_grace(&self) -> f32 {
+        match self.track_limits {
+            TrackLimitsRule::Relaxed => self.penalties.off_track_grace,
+            TrackLimitsRule::Strict => 0.0,
+        }
+    }
+}
+
+impl Default for RulesProfile {
+    fn default() -> Self {
+        Self::standard()
+    }
+}
+
+/// Режим заезда
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RaceMode {
+    Circuit,
+    Arcade,
+    HotSeat, // Поочередные быстрые круги на одной машине
+}
+
+impl RaceMode {
+    /// Начисляются ли аркадные очки в этом режиме
+    pub fn has_arcade_scoring(&self) -> bool {
+        matches!(self, RaceMode::Arcade)
+    }
+}
+
+/// Настройки события (заезда)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventConfig {
+    pub name: String,
+    pub mode: RaceMode,
+    pub laps: u32,
+    pub sim_mode: bool,
+    #[serde(default)]
+    pub race_intro: bool, // Заставка со стартовой решеткой перед отсчетом
+    #[serde(default)]
+    pub hot_seat_drivers: Vec<String>, // Профили пилотов в порядке очереди
+    #[serde(default = "default_hot_seat_attempts")]
+    pub hot_seat_attempts: u32, // Попыток на каждого пилота
+    #[serde(default)]
+    pub ranked: bool, // Рейтинговый онлайн-заезд: ввод пилотов проверяется
+    #[serde(default)]
+    pub rules: Option<RulesProfile>, // Собственные правила события вместо профиля по sim_mode
+    #[serde(default)]
+    pub random_track: Option<TrackGenerationConfig>, // Быстрая гонка на сгенерированной кольцевой трассе
+    #[serde(default)]
+    pub random_track_seed: Option<u64>, // Без зерна трасса новая при каждом запуске
+    #[serde(default = "default_track")]
+    pub track: Option<String>, // Файл трассы события; без него - встроенная прямая
+}
+
+fn default_hot_seat_attempts() -> u32 {
+    3
+}
+
+fn default_track() -> Option<String> {
+    Some(DEFAULT_TRACK_PATH.to_string())
+}
+
+impl EventConfig {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+        serde_json::from_str(&text).map_err(|e| e.to_string())
+    }
+
+    pub fn rules_profile(&self) -> RulesProfile {
+        if let Some(rules) = &self.rules {
+            return rules.clone();
+        }
+        if self.sim_mode {
+            RulesProfile::sim()
+        } else {
+            RulesProfile::standard()
+        }
+    }
+}
+
+impl Default for EventConfig {
+    fn default() -> Self {
+        Self {
+            name: "Quick Race".to_string(),
+            mode: RaceMode::Circuit,
+            laps: 3,
+            sim_mode: false,
+            race_intro: true,
+            hot_seat_drivers: Vec::new(),
+            hot_seat_attempts: default_hot_seat_attempts(),
+            ranked: false,
+            rules: None,
+            random_track: None,
+            random_track_seed: None,
+            track: default_track(),
+        }
+    }
+}
+
+/// Текущий профиль правил из ресурса мира (стандартный, если ресурс не создан)
+pub fn current_rules(world: &World) -> RulesProfile {
+    world
+        .query::<&Resource<RulesProfile>>()
+        .iter()
+        .next()
+        .map(|(_, res)| res.0.clone())
+        .unwrap_or_default()
+}
+
+/// Текущие настройки события из ресурса мира
+pub fn current_event(world: &World) -> EventConfig {
+    world
+        .query::<&Resource<EventConfig>>()
+        .iter()
+        .next()
+        .map(|(_, res)| res.0.clone())
+        .unwrap_or_default()
+}
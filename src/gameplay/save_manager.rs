@@ -0,0 +1,215 @@
+use crate::gameplay::ghost::GhostLap;
+use glam::Vec3;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Корневой каталог сохранений
+pub const SAVES_DIR: &str = "saves";
+
+/// Имя файла индекса в каталоге сохранений
+const INDEX_FILE: &str = "index.json";
+
+/// Подкаталог миниатюр
+const THUMBNAILS_DIR: &str = "thumbnails";
+
+/// Сторона квадратной миниатюры, пикселей
+const THUMBNAIL_SIZE: usize = 96;
+
+/// Вид записи в каталоге сохранений
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SaveKind {
+    Save,
+    Replay,
+    Ghost,
+}
+
+/// Запись индекса: файл и все, что нужно браузеру без чтения самого файла
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SaveIndexEntry {
+    pub id: String,
+    pub kind: SaveKind,
+    pub path: PathBuf,
+    pub created_at: u64, // Unix-время
+    pub track: String,
+    pub layout: String,
+    pub car: String,
+    pub profile: String,
+    #[serde(default)]
+    pub best_lap: Option<f32>,
+    #[serde(default)]
+    pub thumbnail: Option<PathBuf>,
+}
+
+/// Индекс сохранений, повторов и призраков
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SaveIndex {
+    pub entries: Vec<SaveIndexEntry>,
+}
+
+impl SaveIndex {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+        serde_json::from_str(&text).map_err(|e| e.to_string())
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        let text = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        std::fs::write(path, text).map_err(|e| e.to_string())
+    }
+}
+
+/// Менеджер сохранений: ведет индекс и миниатюры. Индекс читается заново при каждой операции,
+/// чтобы запись призраков и браузер не расходились
+#[derive(Debug, Clone)]
+pub struct SaveManager {
+    root: PathBuf,
+}
+
+impl SaveManager {
+    pub fn new(root: &Path) -> Self {
+        Self { root: root.to_path_buf() }
+    }
+
+    fn index_path(&self) -> PathBuf {
+        self.root.join(INDEX_FILE)
+    }
+
+    /// Индекс без записей, чьи файлы удалены в обход менеджера (например, при чистке призраков)
+    pub fn index(&self) -> SaveIndex {
+        let mut index = SaveIndex::load(&self.index_path()).unwrap_or_default();
+        index.entries.retain(|entry| {
+            let exists = entry.path.exists();
+            if let Some(thumbnail) = entry.thumbnail.as_ref().filter(|_| !exists) {
+                let _ = std::fs::remove_file(thumbnail);
+            }
+            exists
+        });
+        index
+    }
+
+    /// Записи от новых к старым
+    pub fn entries(&self) -> Vec<SaveIndexEntry> {
+        let mut entries = self.index().entries;
+        entries.sort_by_key(|entry| std::cmp::Reverse(entry.created_at));
+        entries
+    }
+
+    /// Добавляет в индекс сохраненный призрак с миниатюрой-картой трассы
+    pub fn register_ghost(&self, lap: &GhostLap, path: &Path) -> Result<SaveIndexEntry, String> {
+        let id = uuid::Uuid::new_v4().to_string();
+        let points: Vec<Vec3> = lap.samples.iter().map(|sample| Vec3::from(sample.position)).collect();
+        let thumbnail_path = self.root.join(THUMBNAILS_DIR).join(format!("{}.ppm", id));
+        let thumbnail = match write_track_map(&points, &thumbnail_path) {
+            Ok(()) => Some(thumbnail_path),
+            Err(e) => {
+                eprintln!("Не удалось создать миниатюру призрака: {}", e);
+                None
+            }
+        };
+
+        let entry = SaveIndexEntry {
+            id,
+            kind: SaveKind::Ghost,
+            path: path.to_path_buf(),
+            created_at: lap.recorded_at,
+            track: lap.combo.track.clone(),
+            layout: lap.combo.layout.clone(),
+            car: lap.combo.car.clone(),
+            profile: lap.profile.clone(),
+            best_lap: Some(lap.lap_time),
+            thumbnail,
+        };
+        let mut index = self.index();
+        index.entries.retain(|existing| existing.path != entry.path);
+        index.entries.push(entry.clone());
+        index.save(&self.index_path())?;
+        Ok(entry)
+    }
+
+    /// Удаляет файл записи, ее миниатюру и строку индекса
+    pub fn delete(&self, id: &str) -> Result<(), String> {
+        let mut index = self.index();
+        let position = index
+            .entries
+            .iter()
+            .position(|entry| entry.id == id)
+            .ok_or_else(|| format!("Save {} is not in the index", id))?;
+        let entry = index.entries.remove(position);
+        std::fs::remove_file(&entry.path).map_err(|e| format!("{}: {}", entry.path.display(), e))?;
+        if let Some(thumbnail) = &entry.thumbnail {
+            let _ = std::fs::remove_file(thumbnail);
+        }
+        index.save(&self.index_path())
+    }
+
+    /// Копирует запись и миниатюру в каталог; возвращает путь копии
+    pub fn export(&self, id: &str, dir: &Path) -> Result<PathBuf, String> {
+        let entry = self
+            .index()
+            .entries
+            .into_iter()
+            .find(|entry| entry.id == id)
+            .ok_or_else(|| format!("Save {} is not in the index", id))?;
+        std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        let extension = entry.path.extension().and_then(|e| e.to_str()).unwrap_or("json");
+        let target = dir.join(format!("{:?}_{}.{}", entry.kind, entry.id, extension).to_lowercase());
+        std::fs::copy(&entry.path, &target).map_err(|e| format!("{}: {}", target.display(), e))?;
+        if let Some(thumbnail) = entry.thumbnail.as_ref().filter(|path| path.exists()) {
+            let _ = std::fs::copy(thumbnail, target.with_extension("ppm"));
+        }
+        Ok(target)
+    }
+}
+
+/// Миниатюра-карта трассы: траектория сверху, светлая линия на темном фоне, старт отмечен красным.
+/// Пишется в PPM (P6), чтобы не тянуть зависимость для кодирования изображений
+fn write_track_map(points: &[Vec3], path: &Path) -> Result<(), String> {
+    const BACKGROUND: [u8; 3] = [24, 26, 30];
+    const LINE: [u8; 3] = [220, 220, 220];
+    const START: [u8; 3] = [230, 40, 40];
+
+    let mut pixels = vec![BACKGROUND; THUMBNAIL_SIZE * THUMBNAIL_SIZE];
+    if points.len() >= 2 {
+        let (min, max) = points.iter().fold((Vec3::splat(f32::MAX), Vec3::splat(f32::MIN)), |(min, max), p| {
+            (min.min(*p), max.max(*p))
+        });
+        // Одинаковый масштаб по обеим осям и поля по краям
+        let extent = (max.x - min.x).max(max.z - min.z).max(1.0);
+        let margin = THUMBNAIL_SIZE as f32 * 0.1;
+        let scale = (THUMBNAIL_SIZE as f32 - 2.0 * margin) / extent;
+        let offset_x = margin + ((extent - (max.x - min.x)) * scale) / 2.0;
+        let offset_y = margin + ((extent - (max.z - min.z)) * scale) / 2.0;
+        let to_pixel = |p: Vec3| ((p.x - min.x) * scale + offset_x, (max.z - p.z) * scale + offset_y);
+        let mut plot = |x: f32, y: f32, color: [u8; 3]| {
+            let (x, y) = (x.round() as i64, y.round() as i64);
+            if (0..THUMBNAIL_SIZE as i64).contains(&x) && (0..THUMBNAIL_SIZE as i64).contains(&y) {
+                pixels[y as usize * THUMBNAIL_SIZE + x as usize] = color;
+            }
+        };
+
+        for pair in points.windows(2) {
+            let (a, b) = (to_pixel(pair[0]), to_pixel(pair[1]));
+            let steps = (b.0 - a.0).abs().max((b.1 - a.1).abs()).ceil().max(1.0) as usize;
+            for step in 0..=steps {
+                let t = step as f32 / steps as f32;
+                plot(a.0 + (b.0 - a.0) * t, a.1 + (b.1 - a.1) * t, LINE);
+            }
+        }
+        let start = to_pixel(points[0]);
+        for dy in -1..=1 {
+            for dx in -1..=1 {
+                plot(start.0 + dx as f32, start.1 + dy as f32, START);
+            }
+        }
+    }
+
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    let mut data = format!("P6\n{} {}\n255\n", THUMBNAIL_SIZE, THUMBNAIL_SIZE).into_bytes();
+    data.extend(pixels.iter().flatten());
+    std::fs::write(path, data).map_err(|e| format!("{}: {}", path.display(), e))
+}
@@ -0,0 +1,367 @@
+use crate::core::ecs::{EventQueue, Resource, System};
+use crate::core::physics::TransformComponent;
+use crate::game_world::car::{CarComponent, CarWheelBindingComponent, WheelComponent};
+use crate::game_world::jumps::{JumpEvent, JumpPhase};
+use crate::game_world::track::SectorCompletedEvent;
+use crate::gameplay::rules::current_event;
+use glam::Vec3;
+use hecs::World;
+use std::collections::HashMap;
+
+/// Виды трюков, за которые начисляются очки
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScoreKind {
+    NearMiss,
+    Jump,
+    Drift,
+    CleanSector,
+}
+
+/// Событие начисления очков
+#[derive(Debug, Clone)]
+pub struct ScoreEvent {
+    pub car_entity: hecs::Entity,
+    pub kind: ScoreKind,
+    pub points: u32,
+    pub multiplier: f32,
+}
+
+/// Настройки аркадного подсчета очков
+pub struct ScoringConfig {
+    pub near_miss_distance: f32,      // Максимальное расстояние между центрами машин
+    pub near_miss_min_distance: f32,  // Ближе этого уже столкновение
+    pub near_miss_min_speed: f32,     // Минимальная относительная скорость, м/с
+    pub near_miss_points: f32,        // Очков за каждый м/с относительной скорости
+    pub near_miss_cooldown: f32,      // Пауза перед повторным бонусом за ту же машину
+    pub min_airtime: f32,
+    pub jump_points_per_second: f32,
+    pub drift_min_angle: f32,         // Радианы между курсом и вектором скорости
+    pub drift_min_speed: f32,
+    pub drift_points_per_second: f32,
+    pub clean_sector_points: f32,
+    pub combo_window: f32,            // Время на следующий трюк для продолжения комбо
+    pub combo_step: f32,
+    pub max_multiplier: f32,
+}
+
+impl Default for ScoringConfig {
+    fn default() -> Self {
+        Self {
+            near_miss_distance: 4.5,
+            near_miss_min_distance: 2.2,
+            near_miss_min_speed: 8.0,
+            near_miss_points: 10.0,
+            near_miss_cooldown: 2.0,
+            min_airtime: 0.3,
+            jump_points_per_second: 250.0,
+            drift_min_angle: 0.26,
+            drift_min_speed: 8.0,
+            drift_points_per_second: 150.0,
+            clean_sector_points: 500.0,
+            combo_window: 3.0,
+            combo_step: 0.5,
+            max_multiplier: 5.0,
+        }
+    }
+}
+
+/// Компонент счета автомобиля в аркадном режиме
+pub struct ScoreComponent {
+    pub total: u32,
+    pub multiplier: f32,
+    pub combo_timer: f32,
+    pub drift_time: f32,
+    pub velocity: Vec3,
+    last_position: Option<Vec3>,
+    near_miss_cooldowns: HashMap<hecs::Entity, f32>,
+}
+
+impl Default for ScoreComponent {
+    fn default() -> Self {
+        Self {
+            total: 0,
+            multiplier: 1.0,
+            combo_timer: 0.0,
+            drift_time: 0.0,
+            velocity: Vec3::ZERO,
+            last_position: None,
+            near_miss_cooldowns: HashMap::new(),
+        }
+    }
+}
+
+impl ScoreComponent {
+    /// Начисление очков с учетом комбо; возвращает итоговое количество очков
+    fn award(&mut self, base_points: f32, config: &ScoringConfig) -> u32 {
+        let points = (base_points * self.multiplier).round() as u32;
+        self.total += points;
+        self.multiplier = (self.multiplier + config.combo_step).min(config.max_multiplier);
+        self.combo_timer = config.combo_window;
+        points
+    }
+}
+
+/// Система аркадных очков: близкие обгоны, прыжки, дрифт и чистые сектора
+pub struct ScoringSystem {
+    pub config: ScoringConfig,
+}
+
+impl ScoringSystem {
+    pub fn new() -> Self {
+        Self {
+            config: ScoringConfig::default(),
+        }
+    }
+}
+
+impl System for ScoringSystem {
+    fn update(&mut self, world: &mut World, delta_time: f32) {
+        if !current_event(world).mode.has_arcade_scoring() || delta_time <= 0.0 {
+            return;
+        }
+        let config = &self.config;
+
+        // Положение и курс всех машин для поиска близких обгонов
+        let cars: Vec<(hecs::Entity, Vec3, Vec3)> = world
+            .query::<(&CarComponent, &TransformComponent)>()
+            .iter()
+            .map(|(entity, (_, t))| (entity, t.position, t.rotation * Vec3::Z))
+            .collect();
+
+        // Касание земли хотя бы одним колесом
+        let mut grounded: HashMap<hecs::Entity, bool> = HashMap::new();
+        for (_, binding) in world.query::<&CarWheelBindingComponent>().iter() {
+            let any_grounded = binding
+                .wheel_entities
+                .iter()
+                .any(|&w| world.get::<&WheelComponent>(w).map(|wheel| wheel.grounded).unwrap_or(false));
+            grounded.insert(binding.car_entity, any_grounded);
+        }
+
+        // Время в воздухе приземлившихся машин считает система прыжков
+        let landings: HashMap<hecs::Entity, f32> = world
+            .query::<&Resource<EventQueue<JumpEvent>>>()
+            .iter()
+            .next()
+            .map(|(_, queue)| {
+                queue
+                    .0
+                    .iter()
+                    .filter(|event| event.phase == JumpPhase::Landing)
+                    .map(|event| (event.car_entity, event.airtime))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let mut sector_events = Vec::new();
+        if let Some((_, queue)) = world
+            .query_mut::<&mut Resource<EventQueue<SectorCompletedEvent>>>()
+            .into_iter()
+            .next()
+        {
+            queue.0.consume(|event| sector_events.push(event.clone()));
+        }
+
+        // Скорости прошлого кадра, чтобы считать относительную скорость
+        let velocities: HashMap<hecs::Entity, Vec3> = world
+            .query::<&ScoreComponent>()
+            .iter()
+            .map(|(entity, score)| (entity, score.velocity))
+            .collect();
+
+        let mut score_events = Vec::new();
+
+        for (entity, (score, transform)) in world.query_mut::<(&mut ScoreComponent, &TransformComponent)>() {
+            let position = transform.position;
+            if let Some(last) = score.last_position {
+                score.velocity = (position - last) / delta_time;
+            }
+            score.last_position = Some(position);
+
+            score.combo_timer -= delta_time;
+            if score.combo_timer <= 0.0 {
+                score.combo_timer = 0.0;
+                score.multiplier = 1.0;
+            }
+
+            let mut award = |score: &mut ScoreComponent, kind: ScoreKind, base: f32| {
+                let multiplier = score.multiplier;
+                let points = score.award(base, config);
+                score_events.push(ScoreEvent { car_entity: entity, kind, points, multiplier });
+            };
+
+            // Близкие обгоны
+            score.near_miss_cooldowns.retain(|_, t| {
+                *t -= delta_time;
+                *t > 0.0
+            });
+            for &(other, other_position, _) in &cars {
+                if other == entity || score.near_miss_cooldowns.contains_key(&other) {
+                    continue;
+                }
+                let distance = position.distance(other_position);
+                if distance < config.near_miss_min_distance || distance > config.near_miss_distance {
+                    continue;
+                }
+                let other_velocity = velocities.get(&other).copied().unwrap_or(Vec3::ZERO);
+                let relative_speed = (score.velocity - other_velocity).length();
+                if relative_speed >= config.near_miss_min_speed {
+                    score.near_miss_cooldowns.insert(other, config.near_miss_cooldown);
+                    award(score, ScoreKind::NearMiss, relative_speed * config.near_miss_points);
+                }
+            }
+
+            // Прыжки: очки начисляются при приземлении
+            let on_ground = grounded.get(&entity).copied().unwrap_or(true);
+            if let Some(&airtime) = landings.get(&entity).filter(|&&airtime| airtime >= config.min_airtime) {
+                award(score, ScoreKind::Jump, airtime * config.jump_points_per_second);
+            }
+
+            // Дрифт: угол между курсом и направлением движения; задним ходом занос не считается
+            let forward = cars
+                .iter()
+                .find(|(e, _, _)| *e == entity)
+                .map(|(_, _, f)| *f)
+                .unwrap_or(Vec3::Z);
+            let planar_velocity = Vec3::new(score.velocity.x, 0.0, score.velocity.z);
+            let speed = planar_velocity.length();
+            let drifting = on_ground
+                && speed >= config.drift_min_speed
+                && forward.dot(planar_velocity) > 0.0
+                && forward.angle_between(planar_velocity) >= config.drift_min_angle;
+            if drifting {
+                score.drift_time += delta_time;
+                // Пока машина в заносе, комбо не сбрасывается
+                score.combo_timer = score.combo_timer.max(config.combo_window);
+            } else if score.drift_time > 0.0 {
+                let drift_time = score.drift_time;
+                award(score, ScoreKind::Drift, drift_time * config.drift_points_per_second);
+                score.drift_time = 0.0;
+            }
+
+            // Чистые сектора
+            for _ in sector_events.iter().filter(|e| e.car_entity == entity && e.clean) {
+                award(score, ScoreKind::CleanSector, config.clean_sector_points);
+            }
+        }
+
+        if score_events.is_empty() {
+            return;
+        }
+
+        let queue = world
+            .query_mut::<&mut Resource<EventQueue<ScoreEvent>>>()
+            .into_iter()
+            .next()
+            .map(|(_, res)| &mut res.0);
+
+        match queue {
+            Some(queue) => score_events.into_iter().for_each(|e| queue.publish(e)),
+            None => {
+                let mut queue = EventQueue::new();
+                score_events.into_iter().for_each(|e| queue.publish(e));
+                world.spawn((Resource(queue),));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gameplay::rules::{EventConfig, RaceMode};
+
+    const DT: f32 = 0.1;
+
+    fn arcade_world() -> (World, hecs::Entity) {
+        let mut world = World::new();
+        world.spawn((Resource(EventConfig {
+            mode: RaceMode::Arcade,
+            ..EventConfig::default()
+        }),));
+        let car = world.spawn((ScoreComponent::default(), TransformComponent::default()));
+        (world, car)
+    }
+
+    /// Ведет машину курсом +Z с заданной скоростью несколько кадров
+    fn drive(world: &mut World, system: &mut ScoringSystem, car: hecs::Entity, velocity: Vec3, frames: usize) {
+        for _ in 0..frames {
+            world.get::<&mut TransformComponent>(car).unwrap().position += velocity * DT;
+            system.update(world, DT);
+        }
+    }
+
+    #[test]
+    fn award_grows_and_caps_the_multiplier() {
+        let config = ScoringConfig::default();
+        let mut score = ScoreComponent::default();
+        assert_eq!(score.award(100.0, &config), 100);
+        assert_eq!(score.award(100.0, &config), 150);
+        assert_eq!(score.total, 250);
+        assert_eq!(score.combo_timer, config.combo_window);
+        for _ in 0..20 {
+            score.award(1.0, &config);
+        }
+        assert_eq!(score.multiplier, config.max_multiplier);
+    }
+
+    #[test]
+    fn combo_resets_after_the_window() {
+        let (mut world, car) = arcade_world();
+        world.get::<&mut ScoreComponent>(car).unwrap().multiplier = 3.0;
+        let mut system = ScoringSystem::new();
+        system.update(&mut world, DT);
+        assert_eq!(world.get::<&ScoreComponent>(car).unwrap().multiplier, 1.0);
+    }
+
+    #[test]
+    fn drift_is_scored_when_it_ends() {
+        let (mut world, car) = arcade_world();
+        let mut system = ScoringSystem::new();
+        // Скорость под углом к курсу больше порога дрифта
+        drive(&mut world, &mut system, car, Vec3::new(6.0, 0.0, 10.0), 11);
+        assert!(world.get::<&ScoreComponent>(car).unwrap().drift_time > 0.9);
+        drive(&mut world, &mut system, car, Vec3::new(0.0, 0.0, 10.0), 1);
+        let score = world.get::<&ScoreComponent>(car).unwrap();
+        assert_eq!(score.drift_time, 0.0);
+        assert_eq!(score.total, 150);
+    }
+
+    #[test]
+    fn reversing_is_not_a_drift() {
+        let (mut world, car) = arcade_world();
+        let mut system = ScoringSystem::new();
+        drive(&mut world, &mut system, car, Vec3::new(6.0, 0.0, -10.0), 10);
+        let score = world.get::<&ScoreComponent>(car).unwrap();
+        assert_eq!(score.drift_time, 0.0);
+        assert_eq!(score.total, 0);
+    }
+
+    #[test]
+    fn jump_is_scored_from_the_landing_event() {
+        let (mut world, car) = arcade_world();
+        let landing = |airtime| JumpEvent {
+            car_entity: car,
+            phase: JumpPhase::Landing,
+            airtime,
+            impact_speed: 5.0,
+        };
+        crate::game_world::track::publish_frame_events(&mut world, vec![landing(0.2)]);
+        let mut system = ScoringSystem::new();
+        system.update(&mut world, DT);
+        assert_eq!(world.get::<&ScoreComponent>(car).unwrap().total, 0);
+
+        crate::game_world::track::publish_frame_events(&mut world, vec![landing(1.2)]);
+        system.update(&mut world, DT);
+        assert_eq!(world.get::<&ScoreComponent>(car).unwrap().total, 300);
+    }
+
+    #[test]
+    fn no_points_outside_arcade_mode() {
+        let mut world = World::new();
+        let car = world.spawn((ScoreComponent::default(), TransformComponent::default()));
+        let mut system = ScoringSystem::new();
+        drive(&mut world, &mut system, car, Vec3::new(6.0, 0.0, 10.0), 5);
+        assert_eq!(world.get::<&ScoreComponent>(car).unwrap().drift_time, 0.0);
+    }
+}
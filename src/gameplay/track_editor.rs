@@ -0,0 +1,772 @@
+use crate::core::ecs::{EventQueue, Resource, System};
+use crate::core::input::{InputAction, InputEvent};
+use crate::core::physics::{raycast, JointComponent, SceneQueryFilter};
+use crate::core::renderer::{CameraComponent, DebugDraw, DebugLines, RenderComponent, RenderResourceManager};
+use crate::game_world::props::prop_renders;
+use crate::game_world::track::{save_track_to_file, ObstacleType, SurfaceType, TrackComponent, TrackMeshComponent};
+use crate::game_world::track_file::{CheckpointFile, PropFile, TrackFile, TrackPointFile};
+use crate::game_world::track_spline::SplineKind;
+use crate::gameplay::race::{apply_track_topology, RaceState};
+use crate::gameplay::records::load_track_records;
+use glam::{Quat, Vec2, Vec3};
+use hecs::World;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use winit::event::MouseButton;
+
+/// Папка, куда редактор сохраняет трассы
+pub const TRACK_EDITOR_DIR: &str = "assets/tracks/custom";
+
+/// Слой отладочной отрисовки с превью трассы
+const TRACK_EDITOR_LAYER: &str = "track_editor";
+
+/// Шаги и пределы правки стрелками
+const WIDTH_STEP: f32 = 0.5;
+const MIN_WIDTH: f32 = 3.0;
+const MAX_WIDTH: f32 = 40.0;
+const BANKING_STEP: f32 = 0.0175; // 1°
+const MAX_BANKING: f32 = 0.5;
+const HEADING_STEP: f32 = std::f32::consts::PI / 12.0;
+const PROP_SCALE_STEP: f32 = 1.1;
+
+/// Радиус захвата ручки мышью: постоянная часть и рост с расстоянием до камеры
+const PICK_RADIUS: f32 = 1.5;
+const PICK_RADIUS_PER_METER: f32 = 0.02;
+
+/// Камера редактора: скорость панорамы (доля расстояния в секунду), облета (рад/с) и шаг приближения
+const PAN_SPEED: f32 = 1.0;
+const ORBIT_SPEED: f32 = 1.5;
+const ZOOM_STEP: f32 = 1.15;
+const MIN_CAMERA_DISTANCE: f32 = 10.0;
+const MAX_CAMERA_DISTANCE: f32 = 800.0;
+
+/// Выборок превью на пролет сплайна
+const PREVIEW_SAMPLES_PER_SPAN: usize = 16;
+
+const CENTER_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.6];
+const EDGE_COLOR: [f32; 4] = [0.2, 0.8, 1.0, 1.0];
+const POINT_COLOR: [f32; 4] = [1.0, 0.6, 0.1, 1.0];
+const SELECTED_COLOR: [f32; 4] = [1.0, 0.1, 0.1, 1.0];
+const CHECKPOINT_COLOR: [f32; 4] = [1.0, 0.9, 0.1, 1.0];
+const FINISH_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
+const START_COLOR: [f32; 4] = [0.2, 1.0, 0.3, 1.0];
+const PROP_COLOR: [f32; 4] = [0.6, 0.6, 0.6, 1.0];
+const GRID_COLOR: [f32; 4] = [0.3, 1.0, 0.3, 1.0];
+
+const SURFACES: [SurfaceType; 8] = [
+    SurfaceType::Asphalt,
+    SurfaceType::Concrete,
+    SurfaceType::Dirt,
+    SurfaceType::Gravel,
+    SurfaceType::Grass,
+    SurfaceType::Snow,
+    SurfaceType::Ice,
+    SurfaceType::Sand,
+];
+
+const PROP_KINDS: [ObstacleType; 6] = [
+    ObstacleType::Barrier,
+    ObstacleType::Cone,
+    ObstacleType::Sign,
+    ObstacleType::Tire,
+    ObstacleType::Rock,
+    ObstacleType::Tree,
+];
+
+/// Инструмент левой кнопки мыши
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EditorTool {
+    Select,        // Выбор и перетаскивание
+    AddPoint,      // Новая контрольная точка после выбранной
+    AddCheckpoint, // Чекпоинт поперек трассы
+    AddProp,       // Препятствие на земле
+}
+
+const TOOLS: [EditorTool; 4] = [EditorTool::Select, EditorTool::AddPoint, EditorTool::AddCheckpoint, EditorTool::AddProp];
+
+impl EditorTool {
+    pub fn label(&self) -> &'static str {
+        match self {
+            EditorTool::Select => "Выбор",
+            EditorTool::AddPoint => "Точка трассы",
+            EditorTool::AddCheckpoint => "Чекпоинт",
+            EditorTool::AddProp => "Препятствие",
+        }
+    }
+}
+
+/// Выбранный в редакторе объект: номер в списке файла трассы
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EditorSelection {
+    Point(usize),
+    Checkpoint(usize),
+    Prop(usize),
+}
+
+/// Размер нового препятствия по виду, половины сторон
+fn prop_half_extents(kind: ObstacleType) -> Vec3 {
+    match kind {
+        ObstacleType::Barrier => Vec3::new(0.25, 0.5, 2.0),
+        ObstacleType::Cone => Vec3::new(0.2, 0.4, 0.2),
+        ObstacleType::Sign => Vec3::new(0.6, 0.9, 0.05),
+        ObstacleType::Tire => Vec3::new(0.4, 0.3, 0.4),
+        ObstacleType::Tree => Vec3::new(0.5, 4.0, 0.5),
+        _ => Vec3::splat(0.5),
+    }
+}
+
+/// Следующий элемент списка по кругу
+fn next_in<T: Copy + PartialEq>(items: &[T], current: T) -> T {
+    let index = items.iter().position(|item| *item == current).map_or(0, |index| index + 1);
+    items[index % items.len()]
+}
+
+/// Редактор трассы: рабочая копия файла трассы, выбор и камера облета.
+/// Изменения видны превью линиями и переносятся в мир при сохранении и закрытии редактора
+#[derive(Debug, Clone)]
+pub struct TrackEditorState {
+    pub active: bool,
+    pub file: Option<TrackFile>,
+    pub tool: EditorTool,
+    pub prop_kind: ObstacleType,
+    pub selection: Option<EditorSelection>,
+    pub dirty: bool,    // Есть изменения, еще не перенесенные в мир
+    pub status: String, // Итог последнего действия для HUD
+    pub focus: Vec3,    // Точка, вокруг которой летает камера
+    pub yaw: f32,
+    pub pitch: f32,     // Наклон камеры вниз, рад
+    pub distance: f32,
+    pub cursor: Vec2,   // Курсор в нормализованных координатах окна
+    dragging: bool,
+    paused_before: bool, // Гонка стояла на паузе до открытия редактора
+}
+
+impl TrackEditorState {
+    pub fn new() -> Self {
+        Self {
+            active: false,
+            file: None,
+            tool: EditorTool::Select,
+            prop_kind: ObstacleType::Barrier,
+            selection: None,
+            dirty: false,
+            status: String::new(),
+            focus: Vec3::ZERO,
+            yaw: 0.0,
+            pitch: 0.9,
+            distance: 150.0,
+            cursor: Vec2::ZERO,
+            dragging: false,
+            paused_before: false,
+        }
+    }
+
+    /// Путь сохранения по имени трассы
+    pub fn save_path(&self) -> Option<PathBuf> {
+        let file = self.file.as_ref()?;
+        let slug: String = file
+            .name
+            .chars()
+            .map(|c| if c.is_alphanumeric() { c.to_ascii_lowercase() } else { '_' })
+            .collect();
+        Some(PathBuf::from(TRACK_EDITOR_DIR).join(format!("{}.json", slug)))
+    }
+
+    /// Положения ручек всех объектов, которые можно выбрать мышью
+    pub fn handles(&self) -> Vec<(EditorSelection, Vec3)> {
+        let Some(file) = &self.file else {
+            return Vec::new();
+        };
+        let points = file.spline.points.iter().enumerate().map(|(i, point)| (EditorSelection::Point(i), Vec3::from(point.position)));
+        let checkpoints = file
+            .checkpoints
+            .iter()
+            .enumerate()
+            .map(|(i, checkpoint)| (EditorSelection::Checkpoint(i), Vec3::from(checkpoint.position)));
+        let props = file.props.iter().enumerate().map(|(i, prop)| (EditorSelection::Prop(i), Vec3::from(prop.position)));
+        points.chain(checkpoints).chain(props).collect()
+    }
+
+    /// Ближайшая к камере ручка, которую задевает луч из-под курсора
+    pub fn pick(&self, origin: Vec3, direction: Vec3) -> Option<EditorSelection> {
+        let direction = direction.try_normalize()?;
+        self.handles()
+            .into_iter()
+            .filter_map(|(selection, position)| {
+                let along = (position - origin).dot(direction);
+                let miss = (origin + direction * along).distance(position);
+                (along > 0.0 && miss <= PICK_RADIUS + along * PICK_RADIUS_PER_METER).then_some((selection, along))
+            })
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(selection, _)| selection)
+    }
+
+    fn selection_position(&self) -> Option<Vec3> {
+        let selection = self.selection?;
+        self.handles().into_iter().find(|(handle, _)| *handle == selection).map(|(_, position)| position)
+    }
+
+    /// Переносит выбранный объект в точку
+    pub fn move_selection(&mut self, position: Vec3) {
+        let (Some(file), Some(selection)) = (&mut self.file, self.selection) else {
+            return;
+        };
+        let target = match selection {
+            EditorSelection::Point(i) => file.spline.points.get_mut(i).map(|point| &mut point.position),
+            EditorSelection::Checkpoint(i) => file.checkpoints.get_mut(i).map(|checkpoint| &mut checkpoint.position),
+            EditorSelection::Prop(i) => file.props.get_mut(i).map(|prop| &mut prop.position),
+        };
+        if let Some(target) = target {
+            *target = position.to_array();
+            self.dirty = true;
+        }
+    }
+
+    /// Новая контрольная точка после выбранной (без выбора - в конец) с ее шириной, виражом и покрытием
+    pub fn add_point(&mut self, position: Vec3) -> Result<(), String> {
+        let file = self.file.as_mut().ok_or_else(|| "No track is being edited".to_string())?;
+        if file.spline.kind != SplineKind::CatmullRom {
+            return Err("Points can only be added to Catmull-Rom tracks".to_string());
+        }
+        let index = match self.selection {
+            Some(EditorSelection::Point(i)) => (i + 1).min(file.spline.points.len()),
+            _ => file.spline.points.len(),
+        };
+        let template = file.spline.points.get(index.saturating_sub(1)).cloned().unwrap_or(TrackPointFile {
+            position: [0.0; 3],
+            width: 10.0,
+            banking: 0.0,
+            surface: SurfaceType::Asphalt,
+            bands: Vec::new(),
+            jump_height: 0.0,
+        });
+        file.spline.points.insert(index, TrackPointFile { position: position.to_array(), ..template });
+        self.selection = Some(EditorSelection::Point(index));
+        self.dirty = true;
+        Ok(())
+    }
+
+    /// Чекпоинт поперек трассы в ближайшем к точке месте; первый становится линией финиша
+    pub fn add_checkpoint(&mut self, position: Vec3) -> Result<(), String> {
+        let file = self.file.as_mut().ok_or_else(|| "No track is being edited".to_string())?;
+        let spline = file.spline();
+        spline.validate()?;
+        let sample = spline.sample_at_distance(spline.nearest_distance(position));
+        file.checkpoints.push(CheckpointFile {
+            position: sample.position.to_array(),
+            heading: sample.tangent.x.atan2(sample.tangent.z),
+            width: sample.width + 2.0,
+            is_finish_line: file.checkpoints.is_empty(),
+            is_start_line: false,
+        });
+        self.selection = Some(EditorSelection::Checkpoint(file.checkpoints.len() - 1));
+        self.dirty = true;
+        Ok(())
+    }
+
+    /// Препятствие текущего вида, стоящее на земле в точке
+    pub fn add_prop(&mut self, ground: Vec3) -> Result<(), String> {
+        let file = self.file.as_mut().ok_or_else(|| "No track is being edited".to_string())?;
+        let half_extents = prop_half_extents(self.prop_kind);
+        file.props.push(PropFile {
+            kind: self.prop_kind,
+            position: (ground + Vec3::Y * half_extents.y).to_array(),
+            half_extents: half_extents.to_array(),
+        });
+        self.selection = Some(EditorSelection::Prop(file.props.len() - 1));
+        self.dirty = true;
+        Ok(())
+    }
+
+    /// Удаляет выбранный объект; точку - только если трасса остается правильной
+    pub fn delete_selection(&mut self) -> Result<(), String> {
+        let (Some(file), Some(selection)) = (&mut self.file, self.selection) else {
+            return Ok(());
+        };
+        match selection {
+            EditorSelection::Point(i) if i < file.spline.points.len() => {
+                let removed = file.spline.points.remove(i);
+                if let Err(e) = file.spline().validate() {
+                    file.spline.points.insert(i, removed);
+                    return Err(e);
+                }
+            }
+            EditorSelection::Checkpoint(i) if i < file.checkpoints.len() => {
+                file.checkpoints.remove(i);
+            }
+            EditorSelection::Prop(i) if i < file.props.len() => {
+                file.props.remove(i);
+            }
+            _ => return Ok(()),
+        }
+        self.selection = None;
+        self.dirty = true;
+        Ok(())
+    }
+
+    /// Правка выбранного стрелками: вверх-вниз - ширина или размер, влево-вправо - вираж или курс
+    pub fn adjust(&mut self, horizontal: f32, vertical: f32) {
+        let (Some(file), Some(selection)) = (&mut self.file, self.selection) else {
+            return;
+        };
+        match selection {
+            EditorSelection::Point(i) => {
+                if let Some(point) = file.spline.points.get_mut(i) {
+                    point.width = (point.width + vertical * WIDTH_STEP).clamp(MIN_WIDTH, MAX_WIDTH);
+                    point.banking = (point.banking + horizontal * BANKING_STEP).clamp(-MAX_BANKING, MAX_BANKING);
+                }
+            }
+            EditorSelection::Checkpoint(i) => {
+                if let Some(checkpoint) = file.checkpoints.get_mut(i) {
+                    checkpoint.width = (checkpoint.width + vertical * WIDTH_STEP).clamp(MIN_WIDTH, MAX_WIDTH);
+                    checkpoint.heading += horizontal * HEADING_STEP;
+                }
+            }
+            EditorSelection::Prop(i) => {
+                if let Some(prop) = file.props.get_mut(i) {
+                    let scale = PROP_SCALE_STEP.powf(vertical);
+                    // Препятствие растет от земли: нижняя грань остается на месте
+                    let bottom = prop.position[1] - prop.half_extents[1];
+                    prop.half_extents = (Vec3::from(prop.half_extents) * scale).to_array();
+                    prop.position[1] = bottom + prop.half_extents[1];
+                }
+            }
+        }
+        self.dirty = true;
+    }
+
+    /// Перебор по подтверждению: покрытие точки, линия финиша (на незамкнутой трассе - финиш,
+    /// старт спецучастка, обычный чекпоинт), вид препятствия
+    pub fn cycle(&mut self) {
+        let Some(file) = &mut self.file else {
+            return;
+        };
+        match self.selection {
+            Some(EditorSelection::Point(i)) => {
+                if let Some(point) = file.spline.points.get_mut(i) {
+                    point.surface = next_in(&SURFACES, point.surface);
+                }
+            }
+            Some(EditorSelection::Checkpoint(i)) if i < file.checkpoints.len() => {
+                let selected = &file.checkpoints[i];
+                let (finish, start) = match (selected.is_finish_line, selected.is_start_line) {
+                    _ if file.spline.closed => (true, false),
+                    (false, false) => (true, false),
+                    (true, _) => (false, true),
+                    (false, true) => (false, false),
+                };
+                for (index, checkpoint) in file.checkpoints.iter_mut().enumerate() {
+                    if index == i {
+                        checkpoint.is_finish_line = finish;
+                        checkpoint.is_start_line = start;
+                    } else {
+                        checkpoint.is_finish_line &= !finish;
+                        checkpoint.is_start_line &= !start;
+                    }
+                }
+            }
+            Some(EditorSelection::Prop(i)) => {
+                if let Some(prop) = file.props.get_mut(i) {
+                    let bottom = prop.position[1] - prop.half_extents[1];
+                    prop.kind = next_in(&PROP_KINDS, prop.kind);
+                    prop.half_extents = prop_half_extents(prop.kind).to_array();
+                    prop.position[1] = bottom + prop.half_extents[1];
+                    self.prop_kind = prop.kind;
+                }
+            }
+            _ => {
+                self.prop_kind = next_in(&PROP_KINDS, self.prop_kind);
+                return;
+            }
+        }
+        self.dirty = true;
+    }
+
+    /// Положение камеры облета
+    fn eye(&self) -> Vec3 {
+        let (sin_yaw, cos_yaw) = self.yaw.sin_cos();
+        let (sin_pitch, cos_pitch) = self.pitch.sin_cos();
+        self.focus + Vec3::new(-sin_yaw * cos_pitch, sin_pitch, -cos_yaw * cos_pitch) * self.distance
+    }
+
+    /// Линии превью: кромки и ось трассы, контрольные точки, чекпоинты, препятствия и решетка
+    fn preview(&self) -> DebugLines {
+        let mut lines = DebugLines::new();
+        let Some(file) = &self.file else {
+            return lines;
+        };
+        let color = |selection: EditorSelection, normal: [f32; 4]| if self.selection == Some(selection) { SELECTED_COLOR } else { normal };
+
+        let spline = file.spline();
+        if spline.validate().is_ok() {
+            let samples = spline.span_count().max(1) * PREVIEW_SAMPLES_PER_SPAN;
+            let edges: Vec<(Vec3, Vec3, Vec3)> = (0..=samples)
+                .map(|i| {
+                    let sample = spline.sample(i as f32 / samples as f32);
+                    let flat_side = Vec3::Y.cross(sample.tangent).normalize_or_zero();
+                    let side = (flat_side * sample.banking.cos() + Vec3::Y * sample.banking.sin()) * sample.width / 2.0;
+                    (sample.position, sample.position + side, sample.position - side)
+                })
+                .collect();
+            for pair in edges.windows(2) {
+                lines.line(pair[0].0, pair[1].0, CENTER_COLOR);
+                lines.line(pair[0].1, pair[1].1, EDGE_COLOR);
+                lines.line(pair[0].2, pair[1].2, EDGE_COLOR);
+            }
+        }
+        for (i, point) in file.spline.points.iter().enumerate() {
+            let position = Vec3::from(point.position);
+            let point_color = color(EditorSelection::Point(i), POINT_COLOR);
+            lines.cross(position, 1.0, point_color);
+            lines.circle(position, Vec3::Y, PICK_RADIUS, point_color);
+        }
+        for (i, checkpoint) in file.checkpoints.iter().enumerate() {
+            let position = Vec3::from(checkpoint.position);
+            let side = Quat::from_rotation_y(checkpoint.heading) * Vec3::X * checkpoint.width / 2.0;
+            let normal = match (checkpoint.is_finish_line, checkpoint.is_start_line) {
+                (true, _) => FINISH_COLOR,
+                (_, true) => START_COLOR,
+                _ => CHECKPOINT_COLOR,
+            };
+            let checkpoint_color = color(EditorSelection::Checkpoint(i), normal);
+            lines.line(position - side, position + side, checkpoint_color);
+            lines.line(position - side + Vec3::Y * 2.0, position + side + Vec3::Y * 2.0, checkpoint_color);
+            lines.cross(position, 0.5, checkpoint_color);
+        }
+        for (i, prop) in file.props.iter().enumerate() {
+            lines.oriented_box(Vec3::from(prop.position), Quat::IDENTITY, Vec3::from(prop.half_extents), color(EditorSelection::Prop(i), PROP_COLOR));
+        }
+        for slot in &file.start_grid {
+            lines.circle(Vec3::from(*slot), Vec3::Y, 1.0, GRID_COLOR);
+        }
+        lines
+    }
+}
+
+impl Default for TrackEditorState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Открыт ли редактор трассы: камерой и паузой управляет он
+pub fn track_editor_active(world: &World) -> bool {
+    world.query::<&Resource<TrackEditorState>>().iter().any(|(_, res)| res.0.active)
+}
+
+/// Заменяет трассу в мире трассой из файла; полотно новой трассы рисуется материалом старой
+pub fn rebuild_track(world: &mut World, file: &TrackFile) -> Result<hecs::Entity, String> {
+    file.validate()?;
+    let mut doomed = Vec::new();
+    for (entity, track) in world.query::<&TrackComponent>().iter() {
+        doomed.extend(track.segments.iter().chain(&track.checkpoints).chain(&track.props).chain(&track.pit_lane).copied());
+        doomed.push(entity);
+    }
+    // Стойки с петлями знаков уходят вместе со знаками
+    let hinges: Vec<hecs::Entity> = world
+        .query::<&JointComponent>()
+        .iter()
+        .filter(|(_, joint)| doomed.contains(&joint.body2))
+        .map(|(entity, _)| entity)
+        .collect();
+    doomed.extend(hinges);
+    let road_material = doomed.iter().find_map(|&entity| {
+        world
+            .get::<&TrackMeshComponent>(entity)
+            .ok()
+            .and(world.get::<&RenderComponent>(entity).ok())
+            .map(|render| render.material_id)
+    });
+    for entity in doomed {
+        let _ = world.despawn(entity);
+    }
+
+    let track_entity = file.spawn(world)?;
+    apply_track_topology(world);
+    load_track_records(world);
+    let Some(resource_entity) = world.query::<&Resource<RenderResourceManager>>().iter().next().map(|(entity, _)| entity) else {
+        return Ok(track_entity);
+    };
+    let renders: Vec<(hecs::Entity, RenderComponent)> = {
+        let mut resources = world
+            .get::<&mut Resource<RenderResourceManager>>(resource_entity)
+            .map_err(|e| e.to_string())?;
+        let mut renders = prop_renders(world, &mut resources.0);
+        if let Some(material_id) = road_material {
+            for (entity, track_mesh) in world.query::<&TrackMeshComponent>().without::<&RenderComponent>().iter() {
+                let render = RenderComponent {
+                    mesh_id: resources.0.add_mesh_data(track_mesh.mesh.clone()),
+                    material_id,
+                    visible: true,
+                    scale: Vec3::ONE,
+                    opacity: 1.0,
+                };
+                renders.push((entity, render));
+            }
+        }
+        renders
+    };
+    for (entity, render) in renders {
+        let _ = world.insert_one(entity, render);
+    }
+    Ok(track_entity)
+}
+
+/// Пересечение луча с горизонтальной плоскостью на высоте height
+fn ray_plane(origin: Vec3, direction: Vec3, height: f32) -> Option<Vec3> {
+    if direction.y.abs() <= f32::EPSILON {
+        return None;
+    }
+    let distance = (height - origin.y) / direction.y;
+    (distance > 0.0).then(|| origin + direction * distance)
+}
+
+/// Система редактора трассы (F11): мышь выбирает, перетаскивает и добавляет объекты,
+/// стрелки меняют ширину, вираж и курс, Enter - покрытие, линию финиша и вид препятствия,
+/// Tab - инструмент, Delete - удаление, F2 - сохранение, WASD/QE и колесо - камера
+pub struct TrackEditorSystem {
+    held: HashMap<InputAction, bool>,
+}
+
+impl TrackEditorSystem {
+    pub fn new() -> Self {
+        Self { held: HashMap::new() }
+    }
+
+    /// Срабатывание по нажатию, а не удержанию
+    fn pressed(&mut self, inputs: &HashMap<InputAction, f32>, action: InputAction) -> bool {
+        let down = *inputs.get(&action).unwrap_or(&0.0) > 0.5;
+        let was_down = self.held.insert(action, down).unwrap_or(false);
+        down && !was_down
+    }
+
+    /// Открывает редактор с копией текущей трассы и ставит гонку на паузу
+    fn open(world: &mut World, state: &mut TrackEditorState) {
+        let Some(track_entity) = world.query::<&TrackComponent>().iter().next().map(|(entity, _)| entity) else {
+            eprintln!("Редактор трассы: трасса не загружена");
+            return;
+        };
+        let file = match TrackFile::from_world(world, track_entity) {
+            Ok(file) => file,
+            Err(e) => {
+                eprintln!("Редактор трассы недоступен: {}", e);
+                return;
+            }
+        };
+        state.focus = file.spline.points.first().map_or(Vec3::ZERO, |point| Vec3::from(point.position));
+        state.file = Some(file);
+        state.active = true;
+        state.selection = None;
+        state.dirty = false;
+        state.status = "Редактор трассы".to_string();
+        if let Some((_, race)) = world.query_mut::<&mut Resource<RaceState>>().into_iter().next() {
+            state.paused_before = race.0.paused;
+            race.0.paused = true;
+        }
+    }
+
+    /// Закрывает редактор: изменения переносятся в мир, пауза возвращается как была
+    fn close(world: &mut World, state: &mut TrackEditorState) {
+        state.active = false;
+        state.dragging = false;
+        if let (true, Some(file)) = (state.dirty, &state.file) {
+            if let Err(e) = rebuild_track(world, file) {
+                eprintln!("Не удалось перестроить трассу: {}", e);
+            }
+        }
+        state.dirty = false;
+        if let Some((_, race)) = world.query_mut::<&mut Resource<RaceState>>().into_iter().next() {
+            race.0.paused = state.paused_before;
+        }
+        if let Some((_, debug_draw)) = world.query_mut::<&mut Resource<DebugDraw>>().into_iter().next() {
+            debug_draw.0.clear_layer(TRACK_EDITOR_LAYER);
+        }
+    }
+
+    fn report(state: &mut TrackEditorState, result: Result<(), String>) {
+        if let Err(e) = result {
+            eprintln!("Редактор трассы: {}", e);
+            state.status = e;
+        }
+    }
+}
+
+impl Default for TrackEditorSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl System for TrackEditorSystem {
+    fn update(&mut self, world: &mut World, delta_time: f32) {
+        let inputs = world
+            .query::<&Resource<HashMap<InputAction, f32>>>()
+            .iter()
+            .next()
+            .map(|(_, res)| res.0.clone())
+            .unwrap_or_default();
+        let toggle = self.pressed(&inputs, InputAction::ToggleTrackEditor);
+        let next_tool = self.pressed(&inputs, InputAction::EditorNextTool);
+        let delete = self.pressed(&inputs, InputAction::EditorDelete);
+        let save = self.pressed(&inputs, InputAction::EditorSave);
+        let up = self.pressed(&inputs, InputAction::MenuUp);
+        let down = self.pressed(&inputs, InputAction::MenuDown);
+        let left = self.pressed(&inputs, InputAction::MenuLeft);
+        let right = self.pressed(&inputs, InputAction::MenuRight);
+        let confirm = self.pressed(&inputs, InputAction::MenuConfirm);
+        let axis = |negative: InputAction, positive: InputAction| {
+            inputs.get(&positive).copied().unwrap_or(0.0) - inputs.get(&negative).copied().unwrap_or(0.0)
+        };
+
+        // Состояние забирается из мира на время кадра, чтобы свободно обращаться к миру
+        let Some(mut state) = world
+            .query_mut::<&mut Resource<TrackEditorState>>()
+            .into_iter()
+            .next()
+            .map(|(_, res)| std::mem::take(&mut res.0))
+        else {
+            return;
+        };
+
+        if !state.active {
+            if toggle {
+                Self::open(world, &mut state);
+            }
+        } else if toggle {
+            Self::close(world, &mut state);
+        } else {
+            let mut wheel = 0.0;
+            let (mut clicked, mut released, mut moved) = (false, false, false);
+            if let Some((_, queue)) = world.query::<&Resource<EventQueue<InputEvent>>>().iter().next() {
+                for event in queue.0.iter() {
+                    match event {
+                        InputEvent::MouseMoved(x, y) => {
+                            state.cursor = Vec2::new(*x, *y);
+                            moved = true;
+                        }
+                        InputEvent::MouseWheel(clicks) => wheel += clicks,
+                        InputEvent::MousePressed(MouseButton::Left) => clicked = true,
+                        InputEvent::MouseReleased(MouseButton::Left) => released = true,
+                        _ => {}
+                    }
+                }
+            }
+
+            // Камера облета: панорама по земле в сторону взгляда, облет и приближение
+            let (sin_yaw, cos_yaw) = state.yaw.sin_cos();
+            let forward = Vec3::new(sin_yaw, 0.0, cos_yaw);
+            let left_dir = Vec3::new(cos_yaw, 0.0, -sin_yaw);
+            let pan = state.distance * PAN_SPEED * delta_time;
+            state.focus += forward * axis(InputAction::Brake, InputAction::Accelerate) * pan;
+            state.focus += left_dir * axis(InputAction::SteerRight, InputAction::SteerLeft) * pan;
+            state.yaw += axis(InputAction::ShiftDown, InputAction::ShiftUp) * ORBIT_SPEED * delta_time;
+            state.distance = (state.distance * ZOOM_STEP.powf(-wheel)).clamp(MIN_CAMERA_DISTANCE, MAX_CAMERA_DISTANCE);
+            let (eye, focus) = (state.eye(), state.focus);
+            let mut ray = None;
+            for (_, camera) in world.query_mut::<&mut CameraComponent>() {
+                camera.position = eye;
+                camera.target = focus;
+                camera.up = Vec3::Y;
+                let inverse = camera.build_view_projection_matrix().inverse();
+                let near = inverse.project_point3(state.cursor.extend(0.0));
+                let far = inverse.project_point3(state.cursor.extend(1.0));
+                ray = Some((near, (far - near).normalize_or_zero()));
+            }
+
+            if next_tool {
+                state.tool = next_in(&TOOLS, state.tool);
+                state.status = state.tool.label().to_string();
+            }
+            if delete {
+                let result = state.delete_selection();
+                Self::report(&mut state, result);
+            }
+            if up || down || left || right {
+                let vertical = f32::from(u8::from(up)) - f32::from(u8::from(down));
+                let horizontal = f32::from(u8::from(right)) - f32::from(u8::from(left));
+                state.adjust(horizontal, vertical);
+            }
+            if confirm {
+                state.cycle();
+            }
+
+            if let Some((origin, direction)) = ray {
+                if clicked {
+                    state.selection = state.pick(origin, direction);
+                    state.dragging = state.selection.is_some();
+                    if state.selection.is_none() && state.tool != EditorTool::Select {
+                        let ground = raycast(world, origin, direction, MAX_CAMERA_DISTANCE * 2.0, SceneQueryFilter::static_only())
+                            .map(|hit| hit.point)
+                            .or_else(|| ray_plane(origin, direction, 0.0));
+                        if let Some(ground) = ground {
+                            let result = match state.tool {
+                                EditorTool::AddPoint => state.add_point(ground),
+                                EditorTool::AddCheckpoint => state.add_checkpoint(ground),
+                                EditorTool::AddProp => state.add_prop(ground),
+                                EditorTool::Select => Ok(()),
+                            };
+                            Self::report(&mut state, result);
+                        }
+                    }
+                } else if state.dragging && moved {
+                    // Объект скользит по горизонтали, сохраняя свою высоту
+                    let target = state
+                        .selection_position()
+                        .and_then(|position| ray_plane(origin, direction, position.y));
+                    if let Some(target) = target {
+                        state.move_selection(target);
+                    }
+                }
+            }
+            if released {
+                state.dragging = false;
+            }
+
+            if save {
+                // Правки сначала переносятся в мир, и в файл пишется уже созданная трасса
+                let result = match (state.file.clone(), state.save_path()) {
+                    (Some(file), Some(path)) => {
+                        let track_entity = if state.dirty {
+                            rebuild_track(world, &file)
+                        } else {
+                            world
+                                .query::<&TrackComponent>()
+                                .iter()
+                                .next()
+                                .map(|(entity, _)| entity)
+                                .ok_or_else(|| "Track is not loaded".to_string())
+                        };
+                        track_entity
+                            .and_then(|track_entity| save_track_to_file(world, track_entity, &path.to_string_lossy()))
+                            .map(|_| {
+                                state.dirty = false;
+                                path
+                            })
+                    }
+                    _ => Err("No track is being edited".to_string()),
+                };
+                match result {
+                    Ok(path) => {
+                        eprintln!("Трасса сохранена: {}", path.display());
+                        state.status = format!("Сохранено: {}", path.display());
+                    }
+                    Err(e) => Self::report(&mut state, Err(e)),
+                }
+            }
+
+            let preview = state.preview();
+            match world.query_mut::<&mut Resource<DebugDraw>>().into_iter().next() {
+                Some((_, debug_draw)) => debug_draw.0.set_layer(TRACK_EDITOR_LAYER, preview),
+                None => {
+                    let mut debug_draw = DebugDraw::default();
+                    debug_draw.set_layer(TRACK_EDITOR_LAYER, preview);
+                    world.spawn((Resource(debug_draw),));
+                }
+            }
+        }
+
+        if let Some((_, res)) = world.query_mut::<&mut Resource<TrackEditorState>>().into_iter().next() {
+            res.0 = state;
+        }
+    }
+}
@@ -6,21 +6,51 @@ mod network;
 
 use core::{
     Engine,
-    input::InputSystem,
-    audio::AudioSystem,
-    renderer::{RenderSystem, RenderComponent, CameraComponent},
+    input::{InputAction, InputBehaviorSettings, InputSystem, INPUT_BEHAVIOR_PATH},
+    audio::{list_output_devices, AudioSettings, AudioSystem, AUDIO_SETTINGS_PATH, CROWD_SOUND},
+    renderer::{RenderSystem, RenderComponent, RenderStats, CameraComponent, GraphicsSettings, GRAPHICS_SETTINGS_PATH},
     ecs::{Resource, EventQueue},
     input::InputEvent,
+    physics::PhysicsMaterialRegistry,
 };
 
 use game_world::{
     GameWorldManager,
-    car::create_car_entity,
-    track::create_simple_track,
+    car_definition::find_car_definition,
+    car_setup::load_car_setup,
+    upgrades::apply_car_upgrades,
+    car::{CarAudioSet, PlayerCarComponent},
+    car_builder::CarBuilder,
+    track::{create_simple_track, distance_to_track, generate_random_track, load_track_from_file, TrackComponent, TrackMeshComponent},
     environment::{create_time_of_day, create_weather, WeatherType},
+    terrain::{create_terrain, Heightmap, TerrainConfig, TerrainLayerMasks, TERRAIN_HEIGHTMAP_PATH},
+    camera::CameraRigComponent,
+    terrain::TerrainComponent,
+    vegetation::{create_vegetation, scatter_vegetation, VegetationLayerConfig},
+    advertising::{create_ad_boards, AdvertisingManifest, AdvertisingSettings},
+    hazards::{create_hazards, TrackHazardsComponent},
+    props::prop_renders,
 };
 
-use glam::{Vec3, Quat};
+use gameplay::{
+    rules::{current_event, EventConfig, RaceMode, EVENT_CONFIG_PATH},
+    scoring::ScoreComponent,
+    race::{apply_track_topology, race_paused, RaceState, RaceProgressComponent},
+    records::load_track_records,
+    music::MUSIC_STEMS,
+    hot_seat::HotSeatSession,
+    results::SessionResults,
+    controller_hotplug::ControllerPrompt,
+    profile::load_player_profile,
+    track_editor::TrackEditorState,
+};
+use ui::audio_settings::AudioSettingsPage;
+use ui::save_browser::SaveBrowserPage;
+use ui::time_trial::TimeTrialMenuPage;
+use ui::tuning::TuningMenuPage;
+use ui::hud::ScoreTicker;
+
+use glam::Vec3;
 use winit::{
     event::*,
     event_loop::{EventLoopBuilder},
@@ -45,14 +75,28 @@ async fn run() {
     
     // Создание event loop и окна
     let event_loop = EventLoopBuilder::<()>::new().build().unwrap();
+    // Без настроек графики окно остается скрытым до замера производительности
+    let graphics_settings = match GraphicsSettings::load(std::path::Path::new(GRAPHICS_SETTINGS_PATH)) {
+        Ok(settings) => Some(settings),
+        Err(e) => {
+            if std::path::Path::new(GRAPHICS_SETTINGS_PATH).exists() {
+                eprintln!("Не удалось прочитать настройки графики: {}", e);
+            }
+            None
+        }
+    };
     let window = WindowBuilder::new()
         .with_title("Racing Simulator")
         .with_inner_size(winit::dpi::LogicalSize::new(1280.0, 720.0))
+        .with_visible(graphics_settings.is_some())
         .build(&event_loop)
         .unwrap();
     
     // Создание системы рендеринга с инициализацией графического контекста
-    let render_system = RenderSystem::new(&window).await;
+    let mut render_system = RenderSystem::new(&window).await;
+    if let Some(settings) = &graphics_settings {
+        render_system.apply_graphics_settings(settings);
+    }
     
     // Создание состояния окна
     let mut window_state = WindowState {
@@ -63,7 +107,46 @@ async fn run() {
     // Создание основных систем
     let mut engine = Engine::new();
     let mut input_system = InputSystem::new();
-    let mut audio_system = AudioSystem::new();
+    // Удержание или переключение для отдельных действий; без файла остаются значения по умолчанию
+    if let Ok(settings) = InputBehaviorSettings::load(std::path::Path::new(INPUT_BEHAVIOR_PATH)) {
+        input_system.apply_behavior_settings(&settings);
+    }
+    // Настройки звука; при отсутствии файла используются значения по умолчанию
+    let audio_settings = AudioSettings::load(std::path::Path::new(AUDIO_SETTINGS_PATH)).unwrap_or_default();
+    let mut audio_system = AudioSystem::with_settings(audio_settings.clone());
+    
+    // Звуки эффектов; отсутствие файла не мешает запуску
+    if let Err(e) = audio_system.load_sound("metal_scrape", std::path::Path::new("assets/sounds/metal_scrape.wav")) {
+        eprintln!("Не удалось загрузить звук скрежета: {}", e);
+    }
+    if let Err(e) = audio_system.load_sound("landing_thud", std::path::Path::new("assets/sounds/landing_thud.wav")) {
+        eprintln!("Не удалось загрузить звук приземления: {}", e);
+    }
+    if let Err(e) = audio_system.load_sound(CROWD_SOUND, &std::path::Path::new("assets/sounds").join(format!("{}.wav", CROWD_SOUND))) {
+        eprintln!("Не удалось загрузить гул зрителей: {}", e);
+    }
+    if let Err(e) = audio_system.load_sound("crowd_cheer", std::path::Path::new("assets/sounds/crowd_cheer.wav")) {
+        eprintln!("Не удалось загрузить звук трибун: {}", e);
+    }
+    // Хлопок перепускного клапана машин без собственного набора звуков
+    let blow_off = CarAudioSet::default().blow_off;
+    if let Err(e) = audio_system.load_sound(&blow_off, &std::path::Path::new("assets/sounds").join(format!("{}.wav", blow_off))) {
+        eprintln!("Не удалось загрузить звук перепускного клапана: {}", e);
+    }
+    for name in ["curb_rumble", "gravel_roll", "grass_roll"] {
+        if let Err(e) = audio_system.load_sound(name, &std::path::Path::new("assets/sounds").join(format!("{}.wav", name))) {
+            eprintln!("Не удалось загрузить звук покрытия {}: {}", name, e);
+        }
+    }
+    if let Err(e) = audio_system.load_sound("water_splash", std::path::Path::new("assets/sounds/water_splash.wav")) {
+        eprintln!("Не удалось загрузить звук брызг: {}", e);
+    }
+    // Слои адаптивной музыки; без них гонка идет без музыки
+    for (_, name) in MUSIC_STEMS {
+        if let Err(e) = audio_system.load_sound(name, &std::path::Path::new("assets/music").join(format!("{}.wav", name))) {
+            eprintln!("Не удалось загрузить слой музыки {}: {}", name, e);
+        }
+    }
     
     // Создание и инициализация игрового мира
     let mut game_world_manager = GameWorldManager::new();
@@ -74,11 +157,40 @@ async fn run() {
     // Регистрация систем из GameWorldManager
     game_world_manager.register_systems(&mut engine.ecs_manager);
     
+    // Регистрация систем игровой логики и интерфейса
+    gameplay::register_systems(&mut engine.ecs_manager);
+    ui::register_systems(&mut engine.ecs_manager);
+    
     // Инициализация физического мира
     game_world_manager.initialize_physics(&mut engine.ecs_manager);
     
     // Создание игрового мира
     create_game_world(&mut engine);
+    let exposure = graphics_settings.as_ref().map(GraphicsSettings::exposure).unwrap_or_default();
+    engine.ecs_manager.world.spawn((Resource(exposure),));
+    
+    // Первый запуск: несколько секунд стартовой сцены вне экрана подбирают качество графики
+    if graphics_settings.is_none() {
+        let result = window_state.render_system.benchmark(&engine.ecs_manager.world, Duration::from_secs(3));
+        let settings = if result.frames > 0 {
+            GraphicsSettings::from_benchmark(&result)
+        } else {
+            GraphicsSettings::default()
+        };
+        eprintln!(
+            "Автоопределение качества: {} кадров, CPU {:.1} мс, GPU {:.1} мс -> {:?}, разрешение {:.0}%",
+            result.frames,
+            result.cpu_frame_time * 1000.0,
+            result.gpu_frame_time * 1000.0,
+            settings.preset,
+            settings.resolution_scale * 100.0,
+        );
+        if let Err(e) = settings.save(std::path::Path::new(GRAPHICS_SETTINGS_PATH)) {
+            eprintln!("Не удалось сохранить настройки графики: {}", e);
+        }
+        window_state.render_system.apply_graphics_settings(&settings);
+        window.set_visible(true);
+    }
     
     // Время для расчета дельты
     let mut last_update_time = Instant::now();
@@ -88,8 +200,38 @@ async fn run() {
     let input_events = Resource(EventQueue::<InputEvent>::new());
     engine.ecs_manager.create_entity((input_events,));
     
+    // Страница настроек звука (F9)
+    engine.ecs_manager.create_entity((Resource(AudioSettingsPage::new(audio_settings, list_output_devices())),));
+    
+    // Меню заезда на время с гонкой против призрака рекорда (F7)
+    engine.ecs_manager.create_entity((Resource(TimeTrialMenuPage::new()),));
+    engine.ecs_manager.create_entity((Resource(TuningMenuPage::new()),));
+    engine.ecs_manager.create_entity((Resource(TrackEditorState::new()),));
+    
+    // Браузер сохранений, повторов и призраков (F8)
+    engine.ecs_manager.create_entity((Resource(SaveBrowserPage::new()),));
+    
+    // Подсказка об отключенном геймпаде с выбором другого устройства
+    engine.ecs_manager.create_entity((Resource(ControllerPrompt::new()),));
+    
+    // Отладочная клавиша захвата кадра срабатывает по нажатию, а не удержанию
+    let mut frame_capture_held = false;
+    
+    // Снимок физики для тренировки: F5 сохраняет, F6 возвращает к нему
+    let mut practice_state = None;
+    let mut practice_keys_held = (false, false);
+    
     // Главный цикл
     let _ = event_loop.run(move |event, elwt| {
+        // Передаем события окна и геймпадов системе ввода
+        if let Some((_, input_queue)) = engine.ecs_manager.world
+            .query_mut::<&mut Resource<EventQueue<InputEvent>>>()
+            .into_iter()
+            .next()
+        {
+            input_system.handle_event(&event, &mut input_queue.0);
+        }
+        
         match event {
             Event::WindowEvent {
                 ref event,
@@ -101,6 +243,14 @@ async fn run() {
                 WindowEvent::Resized(physical_size) => {
                     // Вызываем resize напрямую
                     window_state.render_system.resize(*physical_size);
+                    
+                    // Проекция камер должна следовать за соотношением сторон окна
+                    if physical_size.width > 0 && physical_size.height > 0 {
+                        let aspect = physical_size.width as f32 / physical_size.height as f32;
+                        for (_, camera) in engine.ecs_manager.world.query_mut::<&mut CameraComponent>() {
+                            camera.aspect = aspect;
+                        }
+                    }
                 },
                 WindowEvent::ScaleFactorChanged { .. } => {
                     // Обработка изменения масштабирования
@@ -118,11 +268,51 @@ async fn run() {
                 
                 // Обновление систем напрямую
                 input_system.process(&mut engine.ecs_manager.world, delta_time);
-                game_world_manager.physics_system.process(&mut engine.ecs_manager.world, delta_time);
+                // На паузе мир замирает, меню и ввод продолжают работать.
+                // Шаг физики идет в своем потоке, пока звук и рендер работают с прошлыми трансформациями
+                if !race_paused(&engine.ecs_manager.world) {
+                    game_world_manager.physics_system.begin_step(&mut engine.ecs_manager.world, delta_time);
+                }
                 audio_system.process(&mut engine.ecs_manager.world, delta_time);
                 
+                let capture_pressed = input_system.is_action_pressed(InputAction::DebugFrameCapture);
+                if capture_pressed && !frame_capture_held {
+                    eprintln!("{}", window_state.render_system.frame_graph_dump());
+                    window_state.render_system.request_frame_capture();
+                }
+                frame_capture_held = capture_pressed;
+                
                 // Обновление рендера напрямую вызывая метод render
                 window_state.render_system.render(&engine.ecs_manager.world, delta_time);
+                let render_stats = window_state.render_system.stats();
+                match engine.ecs_manager.world.query_mut::<&mut Resource<RenderStats>>().into_iter().next() {
+                    Some((_, stats)) => stats.0 = render_stats,
+                    None => {
+                        engine.ecs_manager.world.spawn((Resource(render_stats),));
+                    }
+                }
+                game_world_manager.physics_system.finish_step(&mut engine.ecs_manager.world);
+                
+                // В рейтинговых заездах снимки недоступны
+                let practice_keys = (
+                    input_system.is_action_pressed(InputAction::PracticeSaveState),
+                    input_system.is_action_pressed(InputAction::PracticeLoadState),
+                );
+                if !current_event(&engine.ecs_manager.world).ranked {
+                    let physics = &mut game_world_manager.physics_system;
+                    if practice_keys.0 && !practice_keys_held.0 {
+                        practice_state = Some(physics.snapshot(&engine.ecs_manager.world));
+                        eprintln!("Снимок тренировки сохранен");
+                    }
+                    if practice_keys.1 && !practice_keys_held.1 {
+                        if let Some(snapshot) = &practice_state {
+                            if let Err(e) = physics.restore(&mut engine.ecs_manager.world, snapshot) {
+                                eprintln!("Не удалось вернуться к снимку: {}", e);
+                            }
+                        }
+                    }
+                }
+                practice_keys_held = practice_keys;
                 
                 // Обработка времени кадра для стабильного FPS
                 let frame_time = current_time.elapsed();
@@ -146,18 +336,58 @@ async fn run() {
 
 /// Создание и инициализация игрового мира
 fn create_game_world(engine: &mut Engine) {
-    // Создаем трассу
-    let track_entity = create_simple_track(&mut engine.ecs_manager.world, 1000.0, 10.0);
-    
-    // Создаем автомобиль
-    let car_entity = create_car_entity(
-        &mut engine.ecs_manager.world, 
-        "SportsCar", 
-        Vec3::new(0.0, 0.5, 0.0), 
-        Quat::IDENTITY
-    );
+    // Правила заезда определяются настройками события
+    let event_config = match EventConfig::load(std::path::Path::new(EVENT_CONFIG_PATH)) {
+        Ok(config) => config,
+        Err(e) => {
+            if std::path::Path::new(EVENT_CONFIG_PATH).exists() {
+                eprintln!("Не удалось прочитать настройки события: {}", e);
+            }
+            EventConfig::default()
+        }
+    };
+    engine.ecs_manager.world.spawn((Resource(event_config.rules_profile()),));
+    let scoring_enabled = event_config.mode.has_arcade_scoring();
+    let hot_seat = event_config.mode == RaceMode::HotSeat;
+    let race_state = if event_config.race_intro && !hot_seat {
+        RaceState::with_intro(event_config.laps)
+    } else {
+        RaceState::new(event_config.laps)
+    };
+    engine.ecs_manager.world.spawn((Resource(race_state),));
+    if hot_seat {
+        let session = HotSeatSession::new(event_config.hot_seat_drivers.clone(), event_config.hot_seat_attempts);
+        engine.ecs_manager.world.spawn((Resource(session),));
+    }
+    let event_name = event_config.name.clone();
+    let track_path = event_config.track.clone();
+    // Без зерна в настройках каждая быстрая гонка идет по новой трассе
+    let random_track = event_config.random_track.clone().map(|config| {
+        let seed = event_config.random_track_seed.unwrap_or_else(|| {
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|elapsed| elapsed.as_nanos() as u64)
+                .unwrap_or_default()
+        });
+        (seed, config)
+    });
+    engine.ecs_manager.world.spawn((Resource(event_config),));
+    
+    // Свои покрытия трассы дополняют встроенные физические материалы
+    let materials_path = std::path::Path::new("assets/tracks/simple_track/materials.json");
+    if materials_path.exists() {
+        if let Some((_, materials)) = engine.ecs_manager.world
+            .query_mut::<&mut Resource<PhysicsMaterialRegistry>>()
+            .into_iter()
+            .next()
+        {
+            if let Err(e) = materials.0.load_file(materials_path) {
+                eprintln!("Ошибка загрузки материалов трассы: {}", e);
+            }
+        }
+    }
     
-    // Создаем компоненты окружения
+    // Создаем компоненты окружения; трасса из файла выставляет свои погоду и время суток
     let weather_entity = create_weather(
         &mut engine.ecs_manager.world,
         WeatherType::Clear,
@@ -170,38 +400,169 @@ fn create_game_world(engine: &mut Engine) {
         0.0   // 0 минут
     );
     
-    // Добавляем простой куб для визуализации дороги
+    // Создаем трассу: сгенерированное кольцо для быстрой гонки, иначе трассу события из файла,
+    // а если файла нет - прямую
+    let generated_track = random_track.and_then(|(seed, config)| {
+        match generate_random_track(&mut engine.ecs_manager.world, seed, &config) {
+            Ok(entity) => Some(entity),
+            Err(e) => {
+                eprintln!("Не удалось сгенерировать трассу (зерно {}): {}", seed, e);
+                None
+            }
+        }
+    });
+    let track_entity = generated_track
+        .or_else(|| {
+            let path = track_path.as_deref()?;
+            match load_track_from_file(&mut engine.ecs_manager.world, path) {
+                Ok(entity) => Some(entity),
+                Err(e) => {
+                    eprintln!("Не удалось загрузить трассу {}: {}", path, e);
+                    None
+                }
+            }
+        })
+        .unwrap_or_else(|| create_simple_track(&mut engine.ecs_manager.world, 1000.0, 10.0));
+    apply_track_topology(&mut engine.ecs_manager.world);
+    // Рекорды трассы читаются сразу, чтобы меню показывало их до первого круга
+    load_track_records(&mut engine.ecs_manager.world);
+    
+    // Машина игрока встает на поул; остальные места решетки по порядку достаются соперникам
+    let pole = engine.ecs_manager.world
+        .get::<&TrackComponent>(track_entity)
+        .ok()
+        .and_then(|track| track.start_positions.first().copied())
+        .unwrap_or_default();
+    
+    // Протокол кругов и секторов для выгрузки в CSV (в режиме "передай геймпад" своя таблица)
+    if !hot_seat {
+        let track_name = engine.ecs_manager.world
+            .get::<&TrackComponent>(track_entity)
+            .map(|track| track.name.clone())
+            .unwrap_or_default();
+        engine.ecs_manager.world.spawn((Resource(SessionResults::new(&event_name, &track_name)),));
+    }
+    
     let mut render_system = RenderSystem::create_resource_manager();
     
-    // Создаем меш куба для дороги
-    let road_mesh_id = render_system.add_simple_cube();
+    // Создаем автомобиль с мешами: встроенная модель или описание из assets/cars
+    let car_definition = find_car_definition("SportsCar").unwrap_or_default();
+    let car_entity = CarBuilder::from_definition(&car_definition)
+        .at(pole.position + pole.rotation * Vec3::Y * 0.8, pole.rotation)
+        .spawn_rendered(&mut engine.ecs_manager.world, &mut render_system)
+        .expect("Не удалось создать машину игрока");
+    // Купленные в карьере доработки, поверх них сохраненная настройка машины под эту трассу
+    let profile = load_player_profile();
+    apply_car_upgrades(&mut engine.ecs_manager.world, car_entity, &profile.car_upgrades(&car_definition.name));
+    engine.ecs_manager.world.spawn((Resource(profile),));
+    load_car_setup(&mut engine.ecs_manager.world, car_entity);
     
     // Создаем материал для дороги
     let road_material_id = render_system.add_basic_material([0.3, 0.3, 0.3, 1.0]); // Серый цвет
 
-    // Добавляем компонент рендеринга к трассе
-    let road_render = RenderComponent {
-        mesh_id: road_mesh_id,
-        material_id: road_material_id,
-        visible: true,
-        scale: Vec3::new(10.0, 0.1, 1000.0), // Длинная, плоская дорога
-    };
-    engine.ecs_manager.world.insert_one(track_entity, road_render).unwrap();
+    // Сегменты трассы рисуются тем же мешем, по которому построен их коллайдер
+    let road_meshes: Vec<(hecs::Entity, usize)> = engine
+        .ecs_manager
+        .world
+        .query::<&TrackMeshComponent>()
+        .iter()
+        .map(|(entity, track_mesh)| (entity, render_system.add_mesh_data(track_mesh.mesh.clone())))
+        .collect();
+    for (segment_entity, road_mesh_id) in road_meshes {
+        let road_render = RenderComponent {
+            mesh_id: road_mesh_id,
+            material_id: road_material_id,
+            visible: true,
+            scale: Vec3::ONE,
+            opacity: 1.0,
+        };
+        engine.ecs_manager.world.insert_one(segment_entity, road_render).unwrap();
+    }
+    // Конусы, знаки и шины видны на трассе
+    for (prop_entity, prop_render) in prop_renders(&engine.ecs_manager.world, &mut render_system) {
+        engine.ecs_manager.world.insert_one(prop_entity, prop_render).unwrap();
+    }
     
-    // Создаем меш для автомобиля (упрощенный)
-    let car_mesh_id = render_system.add_simple_cube();
+    engine.ecs_manager.world
+        .insert(car_entity, (PlayerCarComponent, RaceProgressComponent::new("Player")))
+        .unwrap();
     
-    // Создаем материал для автомобиля
-    let car_material_id = render_system.add_basic_material([1.0, 0.0, 0.0, 1.0]); // Красный цвет
+    // Аркадный счет и его отображение на HUD
+    if scoring_enabled {
+        engine.ecs_manager.world.insert_one(car_entity, ScoreComponent::default()).unwrap();
+        engine.ecs_manager.world.spawn((Resource(ScoreTicker::new(Some(car_entity))),));
+    }
     
-    // Добавляем компонент рендеринга к автомобилю
-    let car_render = RenderComponent {
-        mesh_id: car_mesh_id,
-        material_id: car_material_id,
-        visible: true,
-        scale: Vec3::new(2.0, 1.0, 4.0), // Масштаб автомобиля
-    };
-    engine.ecs_manager.world.insert_one(car_entity, car_render).unwrap();
+    // Создаем ландшафт вокруг трассы: холмы из карты высот, опущенные под дорогу вдоль всего полотна
+    let terrain_config = TerrainConfig::default();
+    let hills = Heightmap::load_pgm(std::path::Path::new(TERRAIN_HEIGHTMAP_PATH)).or_else(|e| {
+        eprintln!("Не удалось загрузить карту высот {}: {}, холмы строятся процедурно", TERRAIN_HEIGHTMAP_PATH, e);
+        Heightmap::from_fn(129, 129, |x, z| {
+            let (fx, fz) = (x as f32 / 128.0, z as f32 / 128.0);
+            0.5 + 0.25 * (fx * 12.0).sin() * (fz * 9.0).cos()
+        })
+    });
+    let heightmap = hills.and_then(|hills| {
+        let world = &engine.ecs_manager.world;
+        Heightmap::from_fn(hills.width, hills.depth, |x, z| {
+            let (fx, fz) = (x as f32 / (hills.width - 1) as f32, z as f32 / (hills.depth - 1) as f32);
+            let hills = hills.get(x, z);
+            let position = terrain_config.origin
+                + Vec3::new(fx * terrain_config.size_x, 0.0, fz * terrain_config.size_z);
+            match distance_to_track(world, position) {
+                Some((distance, road_height)) => {
+                    let road_level = ((road_height - terrain_config.origin.y - 0.5) / terrain_config.height_scale).max(0.0);
+                    let blend = ((distance - 5.0).max(0.0) / 60.0).min(1.0);
+                    road_level + (hills - road_level) * blend
+                }
+                None => hills,
+            }
+        })
+    });
+    match heightmap.and_then(|heightmap| {
+        create_terrain(
+            &mut engine.ecs_manager.world,
+            &mut render_system,
+            heightmap,
+            &TerrainLayerMasks::default(),
+            terrain_config,
+        )
+    }) {
+        Ok(terrain_entity) => {
+            // Трава и деревья вокруг трассы, но не на дороге
+            let instances = {
+                let world = &engine.ecs_manager.world;
+                let terrain = world.get::<&TerrainComponent>(terrain_entity).unwrap();
+                scatter_vegetation(
+                    &terrain,
+                    &[VegetationLayerConfig::grass(), VegetationLayerConfig::trees()],
+                    42,
+                    |position| distance_to_track(world, position).is_some_and(|(distance, _)| distance < 7.0),
+                )
+            };
+            create_vegetation(&mut engine.ecs_manager.world, instances);
+        }
+        Err(e) => eprintln!("Ошибка создания ландшафта: {}", e),
+    }
+    
+    // Рекламные щиты из манифеста трассы (если он есть); на сгенерированной трассе их нет
+    let ads_path = std::path::Path::new("assets/tracks/simple_track/advertising.json");
+    if generated_track.is_none() && ads_path.exists() {
+        match AdvertisingManifest::load(ads_path) {
+            Ok(manifest) => {
+                create_ad_boards(&mut engine.ecs_manager.world, &mut render_system, &manifest);
+            }
+            Err(e) => eprintln!("Ошибка загрузки рекламы трассы: {}", e),
+        }
+    }
+    engine.ecs_manager.world.spawn((Resource(AdvertisingSettings::default()),));
+    
+    // Скриптовые опасности трассы: камнепады, мосты, поезда
+    let hazards = engine.ecs_manager.world
+        .get::<&TrackHazardsComponent>(track_entity)
+        .map(|track_hazards| track_hazards.hazards.clone())
+        .unwrap_or_default();
+    create_hazards(&mut engine.ecs_manager.world, &mut render_system, &hazards);
     
     // Добавляем ресурс менеджера рендеринга в мир
     engine.ecs_manager.world.spawn((Resource(render_system),));
@@ -217,5 +578,10 @@ fn create_game_world(engine: &mut Engine) {
         zfar: 1000.0,
     };
     
-    engine.ecs_manager.world.spawn((camera,));
+    let camera_rig = CameraRigComponent {
+        target: Some(car_entity),
+        ..Default::default()
+    };
+    
+    engine.ecs_manager.world.spawn((camera, camera_rig));
 }
\ No newline at end of file
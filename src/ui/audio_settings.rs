@@ -0,0 +1,244 @@
+use crate::core::audio::{AudioBus, AudioEvent, AudioSettings, AUDIO_SETTINGS_PATH};
+use crate::core::ecs::{Resource, System};
+use crate::core::input::InputAction;
+use crate::core::text::{set_hud_text, HudTextLine, TextAlign};
+use hecs::World;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Строки страницы настроек звука
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AudioSettingsRow {
+    MasterVolume,
+    EffectsVolume,
+    MusicVolume,
+    OutputDevice,
+    SpeakerProfile,
+    TestTone(AudioBus),
+}
+
+const ROWS: [AudioSettingsRow; 8] = [
+    AudioSettingsRow::MasterVolume,
+    AudioSettingsRow::EffectsVolume,
+    AudioSettingsRow::MusicVolume,
+    AudioSettingsRow::OutputDevice,
+    AudioSettingsRow::SpeakerProfile,
+    AudioSettingsRow::TestTone(AudioBus::Master),
+    AudioSettingsRow::TestTone(AudioBus::Effects),
+    AudioSettingsRow::TestTone(AudioBus::Music),
+];
+
+/// Шаг изменения громкости стрелками
+const VOLUME_STEP: f32 = 0.05;
+
+/// Состояние страницы настроек звука
+#[derive(Debug, Clone)]
+pub struct AudioSettingsPage {
+    pub open: bool,
+    pub selected: usize,
+    pub settings: AudioSettings,
+    pub devices: Vec<String>, // Найденные устройства вывода
+}
+
+impl AudioSettingsPage {
+    pub fn new(settings: AudioSettings, devices: Vec<String>) -> Self {
+        Self {
+            open: false,
+            selected: 0,
+            settings,
+            devices,
+        }
+    }
+
+    pub fn selected_row(&self) -> AudioSettingsRow {
+        ROWS[self.selected.min(ROWS.len() - 1)]
+    }
+
+    /// Подписи строк для отрисовки: (название, значение)
+    pub fn rows(&self) -> Vec<(String, String)> {
+        ROWS.iter()
+            .map(|row| match row {
+                AudioSettingsRow::MasterVolume => ("Общая громкость".to_string(), percent(self.settings.master_volume)),
+                AudioSettingsRow::EffectsVolume => ("Эффекты".to_string(), percent(self.settings.effects_volume)),
+                AudioSettingsRow::MusicVolume => ("Музыка".to_string(), percent(self.settings.music_volume)),
+                AudioSettingsRow::OutputDevice => (
+                    "Устройство вывода".to_string(),
+                    self.settings.output_device.clone().unwrap_or_else(|| "По умолчанию".to_string()),
+                ),
+                AudioSettingsRow::SpeakerProfile => ("Профиль".to_string(), format!("{:?}", self.settings.speaker_profile)),
+                AudioSettingsRow::TestTone(bus) => (format!("Тестовый тон: {:?}", bus), String::new()),
+            })
+            .collect()
+    }
+
+    /// Изменение значения выбранной строки стрелками влево/вправо
+    fn adjust(&mut self, direction: f32) {
+        let step = VOLUME_STEP * direction;
+        match self.selected_row() {
+            AudioSettingsRow::MasterVolume => {
+                self.settings.master_volume = (self.settings.master_volume + step).clamp(0.0, 1.0);
+            }
+            AudioSettingsRow::EffectsVolume => {
+                self.settings.effects_volume = (self.settings.effects_volume + step).clamp(0.0, 1.0);
+            }
+            AudioSettingsRow::MusicVolume => {
+                self.settings.music_volume = (self.settings.music_volume + step).clamp(0.0, 1.0);
+            }
+            AudioSettingsRow::OutputDevice => {
+                // Перебор: по умолчанию, затем устройства по списку
+                let count = self.devices.len() as i32 + 1;
+                let current = self
+                    .settings
+                    .output_device
+                    .as_ref()
+                    .and_then(|name| self.devices.iter().position(|d| d == name))
+                    .map(|i| i as i32 + 1)
+                    .unwrap_or(0);
+                let next = (current + direction as i32).rem_euclid(count);
+                self.settings.output_device = if next == 0 {
+                    None
+                } else {
+                    Some(self.devices[next as usize - 1].clone())
+                };
+            }
+            AudioSettingsRow::SpeakerProfile => {
+                self.settings.speaker_profile = self.settings.speaker_profile.next();
+            }
+            AudioSettingsRow::TestTone(_) => {}
+        }
+    }
+}
+
+fn percent(value: f32) -> String {
+    format!("{}%", (value * 100.0).round() as u32)
+}
+
+/// Надписи открытой страницы настроек звука
+fn audio_settings_text(page: &AudioSettingsPage) -> Vec<HudTextLine> {
+    if !page.open {
+        return Vec::new();
+    }
+    let mut lines = vec![HudTextLine::new("Настройки звука", [0.5, 0.15], 0.04).align(TextAlign::Center)];
+    for (index, (label, value)) in page.rows().into_iter().enumerate() {
+        let color = if index == page.selected { [1.0, 0.85, 0.3, 1.0] } else { [1.0, 1.0, 1.0, 0.8] };
+        let y = 0.25 + index as f32 * 0.05;
+        lines.push(HudTextLine::new(label, [0.3, y], 0.03).color(color));
+        lines.push(HudTextLine::new(value, [0.7, y], 0.03).color(color).align(TextAlign::Right));
+    }
+    lines
+}
+
+/// Система страницы настроек звука
+pub struct AudioSettingsSystem {
+    held: HashMap<InputAction, bool>,
+}
+
+impl AudioSettingsSystem {
+    pub fn new() -> Self {
+        Self { held: HashMap::new() }
+    }
+
+    /// Срабатывание по нажатию, а не удержанию
+    fn pressed(&mut self, inputs: &HashMap<InputAction, f32>, action: InputAction) -> bool {
+        let down = *inputs.get(&action).unwrap_or(&0.0) > 0.5;
+        let was_down = self.held.insert(action, down).unwrap_or(false);
+        down && !was_down
+    }
+}
+
+impl Default for AudioSettingsSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AudioSettingsSystem {
+    fn navigate(&mut self, world: &mut World) {
+        let inputs = world
+            .query::<&Resource<HashMap<InputAction, f32>>>()
+            .iter()
+            .next()
+            .map(|(_, res)| res.0.clone())
+            .unwrap_or_default();
+
+        let toggle = self.pressed(&inputs, InputAction::ToggleAudioSettings);
+        let back = self.pressed(&inputs, InputAction::MenuBack);
+        let up = self.pressed(&inputs, InputAction::MenuUp);
+        let down = self.pressed(&inputs, InputAction::MenuDown);
+        let left = self.pressed(&inputs, InputAction::MenuLeft);
+        let right = self.pressed(&inputs, InputAction::MenuRight);
+        let confirm = self.pressed(&inputs, InputAction::MenuConfirm);
+
+        let Some((_, page)) = world.query_mut::<&mut Resource<AudioSettingsPage>>().into_iter().next() else {
+            return;
+        };
+        let page = &mut page.0;
+
+        let mut events = Vec::new();
+
+        if !page.open {
+            if toggle {
+                page.open = true;
+                page.selected = 0;
+            }
+            return;
+        }
+
+        if toggle || back {
+            // При закрытии настройки сохраняются на диск
+            page.open = false;
+            if let Err(e) = page.settings.save(Path::new(AUDIO_SETTINGS_PATH)) {
+                eprintln!("Не удалось сохранить настройки звука: {}", e);
+            }
+            return;
+        }
+
+        if up {
+            page.selected = (page.selected + ROWS.len() - 1) % ROWS.len();
+        }
+        if down {
+            page.selected = (page.selected + 1) % ROWS.len();
+        }
+
+        let before = page.settings.clone();
+        if left {
+            page.adjust(-1.0);
+        }
+        if right {
+            page.adjust(1.0);
+        }
+        if page.settings != before {
+            events.push(AudioEvent::ApplySettings { settings: page.settings.clone() });
+        }
+
+        if confirm {
+            if let AudioSettingsRow::TestTone(bus) = page.selected_row() {
+                events.push(AudioEvent::PlayTestTone { bus });
+            }
+        }
+
+        if events.is_empty() {
+            return;
+        }
+        match world.query_mut::<&mut Resource<Vec<AudioEvent>>>().into_iter().next() {
+            Some((_, queue)) => queue.0.extend(events),
+            None => {
+                world.spawn((Resource(events),));
+            }
+        }
+    }
+}
+
+impl System for AudioSettingsSystem {
+    fn update(&mut self, world: &mut World, _delta_time: f32) {
+        self.navigate(world);
+
+        let lines = world
+            .query::<&Resource<AudioSettingsPage>>()
+            .iter()
+            .next()
+            .map(|(_, page)| audio_settings_text(&page.0))
+            .unwrap_or_default();
+        set_hud_text(world, "audio_settings", lines);
+    }
+}
@@ -0,0 +1,1027 @@
+use crate::core::ecs::{EventQueue, Resource, System};
+use crate::core::physics::PhysicsStepStats;
+use crate::core::physics::TransformComponent;
+use crate::core::text::{set_hud_text, HudTextLine, TextAlign};
+use crate::game_world::car::{CarComponent, HydroplaneEvent, PlayerCarComponent};
+use crate::game_world::engine_thermal::{AMBIENT_ENGINE_TEMPERATURE, CRITICAL_TEMPERATURE, OVERHEAT_TEMPERATURE};
+use crate::game_world::hazards::{hazard_zones_ahead, HazardState, HazardType};
+use crate::game_world::track::{CarLapCompletedEvent, LapTimingComponent, TrackComponent};
+use crate::game_world::track_limits::{OffTrackEvent, OffTrackPhase};
+use glam::Vec3;
+use crate::gameplay::controller_hotplug::ControllerPrompt;
+use crate::gameplay::hot_seat::{hot_seat_combo, HotSeatPhase, HotSeatSession};
+use crate::gameplay::intro::IntroState;
+use crate::gameplay::race::RaceProgressComponent;
+use crate::gameplay::race_control::{RaceControl, RaceFlag};
+use crate::gameplay::records::MilestoneNotice;
+use crate::gameplay::rules::{current_rules, HudLevel};
+use crate::gameplay::scoring::{ScoreComponent, ScoreEvent, ScoreKind};
+use hecs::World;
+
+/// Всплывающая надпись о начисленных очках
+pub struct ScorePopup {
+    pub text: String,
+    pub age: f32,
+}
+
+/// Бегущий счетчик очков на HUD
+pub struct ScoreTicker {
+    pub car_entity: Option<hecs::Entity>,
+    pub displayed_score: f32,
+    pub target_score: u32,
+    pub multiplier: f32,
+    pub popups: Vec<ScorePopup>,
+    pub popup_lifetime: f32,
+    pub count_speed: f32, // Доля оставшейся разницы, догоняемая за секунду
+}
+
+impl ScoreTicker {
+    pub fn new(car_entity: Option<hecs::Entity>) -> Self {
+        Self {
+            car_entity,
+            displayed_score: 0.0,
+            target_score: 0,
+            multiplier: 1.0,
+            popups: Vec::new(),
+            popup_lifetime: 2.0,
+            count_speed: 6.0,
+        }
+    }
+
+    /// Текст счетчика для отрисовки
+    pub fn score_text(&self) -> String {
+        if self.multiplier > 1.0 {
+            format!("{} x{:.1}", self.displayed_score.round() as u32, self.multiplier)
+        } else {
+            format!("{}", self.displayed_score.round() as u32)
+        }
+    }
+}
+
+/// Ограниченный HUD правил события: без счета, дельты к лучшему кругу, предупреждений и миникарты
+pub(crate) fn hud_limited(world: &World) -> bool {
+    current_rules(world).hud == HudLevel::Limited
+}
+
+/// Цвета надписей HUD
+const TEXT_WARNING: [f32; 4] = [1.0, 0.3, 0.25, 1.0];
+const TEXT_HIGHLIGHT: [f32; 4] = [1.0, 0.85, 0.3, 1.0];
+const TEXT_GOOD: [f32; 4] = [0.35, 1.0, 0.45, 1.0];
+const TEXT_BLUE_FLAG: [f32; 4] = [0.35, 0.55, 1.0, 1.0];
+
+fn score_kind_label(kind: ScoreKind) -> &'static str {
+    match kind {
+        ScoreKind::NearMiss => "NEAR MISS",
+        ScoreKind::Jump => "AIR",
+        ScoreKind::Drift => "DRIFT",
+        ScoreKind::CleanSector => "CLEAN SECTOR",
+    }
+}
+
+/// Система обновления HUD
+pub struct HudSystem;
+
+impl System for HudSystem {
+    fn update(&mut self, world: &mut World, delta_time: f32) {
+        let mut events = Vec::new();
+        if let Some((_, queue)) = world
+            .query_mut::<&mut Resource<EventQueue<ScoreEvent>>>()
+            .into_iter()
+            .next()
+        {
+            queue.0.consume(|event| events.push(event.clone()));
+        }
+
+        let Some(ticker_car) = world
+            .query::<&Resource<ScoreTicker>>()
+            .iter()
+            .next()
+            .and_then(|(_, res)| res.0.car_entity)
+        else {
+            return;
+        };
+
+        let (total, multiplier) = world
+            .get::<&ScoreComponent>(ticker_car)
+            .map(|score| (score.total, score.multiplier))
+            .unwrap_or((0, 1.0));
+
+        let limited = hud_limited(world);
+        let Some((_, ticker)) = world.query_mut::<&mut Resource<ScoreTicker>>().into_iter().next() else {
+            return;
+        };
+        let ticker = &mut ticker.0;
+
+        ticker.target_score = total;
+        ticker.multiplier = multiplier;
+        let t = (ticker.count_speed * delta_time).min(1.0);
+        ticker.displayed_score += (total as f32 - ticker.displayed_score) * t;
+
+        // Множитель показан у очков, которые он увеличил
+        for event in events.iter().filter(|e| e.car_entity == ticker_car) {
+            let text = if event.multiplier > 1.0 {
+                format!("{} +{} x{:.1}", score_kind_label(event.kind), event.points, event.multiplier)
+            } else {
+                format!("{} +{}", score_kind_label(event.kind), event.points)
+            };
+            ticker.popups.push(ScorePopup { text, age: 0.0 });
+        }
+
+        let lifetime = ticker.popup_lifetime;
+        ticker.popups.retain_mut(|popup| {
+            popup.age += delta_time;
+            popup.age < lifetime
+        });
+
+        if limited {
+            set_hud_text(world, "score", Vec::new());
+            return;
+        }
+
+        // Счет в правом верхнем углу, свежие надписи всплывают под ним и гаснут
+        let mut lines = vec![HudTextLine::new(ticker.score_text(), [0.97, 0.03], 0.05).align(TextAlign::Right)];
+        for (index, popup) in ticker.popups.iter().rev().enumerate() {
+            let fade = 1.0 - popup.age / lifetime;
+            let y = 0.09 + index as f32 * 0.035 - popup.age * 0.01;
+            lines.push(
+                HudTextLine::new(popup.text.clone(), [0.97, y], 0.03)
+                    .color([TEXT_HIGHLIGHT[0], TEXT_HIGHLIGHT[1], TEXT_HIGHLIGHT[2], fade])
+                    .align(TextAlign::Right),
+            );
+        }
+        set_hud_text(world, "score", lines);
+    }
+}
+
+/// Титр в нижней трети экрана (имя пилота и машина во время заставки)
+#[derive(Debug, Clone, Default)]
+pub struct LowerThird {
+    pub title: String,
+    pub subtitle: String,
+    pub opacity: f32,
+}
+
+/// Система титров заставки
+pub struct LowerThirdSystem {
+    pub fade_time: f32, // Доля пролета на появление и исчезновение титра
+}
+
+impl LowerThirdSystem {
+    pub fn new() -> Self {
+        Self { fade_time: 0.15 }
+    }
+}
+
+impl Default for LowerThirdSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl System for LowerThirdSystem {
+    fn update(&mut self, world: &mut World, _delta_time: f32) {
+        let intro = world
+            .query::<&Resource<IntroState>>()
+            .iter()
+            .next()
+            .map(|(_, res)| res.0.clone())
+            .unwrap_or_default();
+
+        let caption = match intro.current.filter(|_| intro.active) {
+            Some(shot) => {
+                let p = intro.shot_progress;
+                let fade = (p / self.fade_time).min((1.0 - p) / self.fade_time).clamp(0.0, 1.0);
+                LowerThird {
+                    title: format!("P{}  {}", shot.grid_position, shot.driver_name),
+                    subtitle: shot.car_name,
+                    opacity: fade,
+                }
+            }
+            None => LowerThird::default(),
+        };
+
+        let lines = if caption.opacity > 0.0 {
+            vec![
+                HudTextLine::new(caption.title.clone(), [0.08, 0.72], 0.05).color([1.0, 1.0, 1.0, caption.opacity]),
+                HudTextLine::new(caption.subtitle.clone(), [0.08, 0.78], 0.035).color([
+                    TEXT_HIGHLIGHT[0],
+                    TEXT_HIGHLIGHT[1],
+                    TEXT_HIGHLIGHT[2],
+                    caption.opacity,
+                ]),
+            ]
+        } else {
+            Vec::new()
+        };
+        set_hud_text(world, "lower_third", lines);
+
+        let resource = world
+            .query_mut::<&mut Resource<LowerThird>>()
+            .into_iter()
+            .next()
+            .map(|(_, res)| &mut res.0);
+
+        match resource {
+            Some(lower_third) => *lower_third = caption,
+            None => {
+                world.spawn((Resource(caption),));
+            }
+        }
+    }
+}
+
+/// Предупреждение о замедлении физики на слабой машине
+#[derive(Debug, Clone, Default)]
+pub struct SlowMotionWarning {
+    pub text: Option<String>,
+    pub hold_time: f32, // Сколько еще показывать после последнего замедления
+}
+
+/// Система предупреждения о перегрузке симуляции
+pub struct SlowMotionWarningSystem {
+    pub display_time: f32,
+}
+
+impl SlowMotionWarningSystem {
+    pub fn new() -> Self {
+        Self { display_time: 2.0 }
+    }
+}
+
+impl Default for SlowMotionWarningSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl System for SlowMotionWarningSystem {
+    fn update(&mut self, world: &mut World, delta_time: f32) {
+        let stats = world
+            .query::<&Resource<PhysicsStepStats>>()
+            .iter()
+            .next()
+            .map(|(_, res)| res.0);
+
+        let resource = world
+            .query_mut::<&mut Resource<SlowMotionWarning>>()
+            .into_iter()
+            .next()
+            .map(|(_, res)| &mut res.0);
+        let Some(warning) = resource else {
+            world.spawn((Resource(SlowMotionWarning::default()),));
+            return;
+        };
+
+        match stats.filter(|stats| stats.slow_motion) {
+            Some(stats) => {
+                warning.hold_time = self.display_time;
+                // Сколько подшагов успели и сколько времени симуляции выброшено в этом кадре
+                warning.text = Some(format!(
+                    "PHYSICS SLOWDOWN {:.0}%  {} steps, {:.0} ms dropped",
+                    stats.time_scale * 100.0,
+                    stats.substeps,
+                    stats.dropped_time * 1000.0
+                ));
+            }
+            None => {
+                warning.hold_time = (warning.hold_time - delta_time).max(0.0);
+                if warning.hold_time <= 0.0 {
+                    warning.text = None;
+                }
+            }
+        }
+
+        let lines = warning
+            .text
+            .clone()
+            .map(|text| HudTextLine::new(text, [0.5, 0.96], 0.03).color(TEXT_WARNING).align(TextAlign::Center))
+            .into_iter()
+            .collect();
+        set_hud_text(world, "slow_motion", lines);
+    }
+}
+
+/// Время круга в формате м:сс.ттт
+pub fn format_lap_time(seconds: f32) -> String {
+    let minutes = (seconds / 60.0).floor() as u32;
+    format!("{}:{:06.3}", minutes, seconds - minutes as f32 * 60.0)
+}
+
+/// Общая таблица режима "передай геймпад"
+#[derive(Debug, Clone, Default)]
+pub struct HotSeatBoard {
+    pub visible: bool,
+    pub title: String,
+    pub rows: Vec<String>,
+}
+
+/// Система таблицы результатов: видна при смене пилота и после всех попыток
+pub struct HotSeatBoardSystem;
+
+impl System for HotSeatBoardSystem {
+    fn update(&mut self, world: &mut World, _delta_time: f32) {
+        let Some(car_entity) = world
+            .query::<&PlayerCarComponent>()
+            .iter()
+            .next()
+            .map(|(entity, _)| entity)
+        else {
+            return;
+        };
+        let (track, car) = hot_seat_combo(world, car_entity);
+
+        let board = {
+            let mut query = world.query::<&Resource<HotSeatSession>>();
+            let Some((_, session)) = query.iter().next() else {
+                return;
+            };
+            let session = &session.0;
+
+            let title = match session.phase {
+                HotSeatPhase::Leaderboard => "РЕЗУЛЬТАТЫ  (Enter - заново)".to_string(),
+                _ => format!(
+                    "ЗА РУЛЕМ: {}  попытка {}/{}",
+                    session.current_driver(),
+                    session.driver_attempt(),
+                    session.attempts_per_driver
+                ),
+            };
+            let rows = session
+                .standings(&track, &car)
+                .iter()
+                .enumerate()
+                .map(|(i, standing)| {
+                    let best = match (standing.best_lap, standing.best_attempt) {
+                        (Some(time), Some(attempt)) => format!("{} (#{})", format_lap_time(time), attempt),
+                        _ => "--:--.---".to_string(),
+                    };
+                    let gap = match standing.gap {
+                        Some(gap) if gap > 0.0 => format!("+{:.3}", gap),
+                        _ => String::new(),
+                    };
+                    // Рекорд профиля, отмеченный, если его побили в этой сессии
+                    let record = standing
+                        .profile_best
+                        .map(|time| {
+                            let new = if standing.new_profile_best { " NEW" } else { "" };
+                            format!("PB {}{}", format_lap_time(time), new)
+                        })
+                        .unwrap_or_default();
+                    format!("{}. {}  {}  {}  {}", i + 1, standing.driver, best, gap, record)
+                })
+                .collect();
+
+            HotSeatBoard {
+                visible: matches!(session.phase, HotSeatPhase::Handover | HotSeatPhase::Leaderboard),
+                title,
+                rows,
+            }
+        };
+
+        let mut lines = Vec::new();
+        if board.visible {
+            lines.push(HudTextLine::new(board.title.clone(), [0.5, 0.25], 0.045).color(TEXT_HIGHLIGHT).align(TextAlign::Center));
+            for (index, row) in board.rows.iter().enumerate() {
+                lines.push(HudTextLine::new(row.clone(), [0.3, 0.33 + index as f32 * 0.05], 0.035));
+            }
+        }
+        set_hud_text(world, "hot_seat", lines);
+
+        match world.query_mut::<&mut Resource<HotSeatBoard>>().into_iter().next() {
+            Some((_, current)) => current.0 = board,
+            None => {
+                world.spawn((Resource(board),));
+            }
+        }
+    }
+}
+
+/// Предупреждение об опасности впереди по курсу
+#[derive(Debug, Clone, Default)]
+pub struct HazardWarning {
+    pub visible: bool,
+    pub text: String,
+    pub urgent: bool, // Опасность уже действует
+}
+
+/// Система предупреждений об опасностях трассы
+pub struct HazardWarningSystem {
+    pub lookahead: f32,
+}
+
+impl HazardWarningSystem {
+    pub fn new() -> Self {
+        Self { lookahead: 200.0 }
+    }
+}
+
+impl Default for HazardWarningSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl System for HazardWarningSystem {
+    fn update(&mut self, world: &mut World, _delta_time: f32) {
+        let car = world
+            .query::<(&PlayerCarComponent, &TransformComponent)>()
+            .iter()
+            .next()
+            .map(|(_, (_, transform))| (transform.position, transform.rotation * Vec3::Z));
+        let Some((position, forward)) = car else {
+            return;
+        };
+
+        let warning = match hazard_zones_ahead(world, position, forward, self.lookahead)
+            .first()
+            .filter(|_| !hud_limited(world))
+        {
+            Some(zone) => {
+                let label = match zone.hazard_type {
+                    HazardType::Rockfall => "КАМНЕПАД",
+                    HazardType::Drawbridge => "РАЗВОДНОЙ МОСТ",
+                    HazardType::Train => "ПОЕЗД",
+                };
+                let distance = (zone.position.distance(position) - zone.radius).max(0.0);
+                HazardWarning {
+                    visible: true,
+                    text: format!("ВНИМАНИЕ: {} {:.0} м", label, distance),
+                    urgent: zone.state == HazardState::Active,
+                }
+            }
+            None => HazardWarning::default(),
+        };
+
+        let lines = if warning.visible {
+            let color = if warning.urgent { TEXT_WARNING } else { TEXT_HIGHLIGHT };
+            vec![HudTextLine::new(warning.text.clone(), [0.5, 0.2], 0.04).color(color).align(TextAlign::Center)]
+        } else {
+            Vec::new()
+        };
+        set_hud_text(world, "hazard", lines);
+
+        match world.query_mut::<&mut Resource<HazardWarning>>().into_iter().next() {
+            Some((_, current)) => current.0 = warning,
+            None => {
+                world.spawn((Resource(warning),));
+            }
+        }
+    }
+}
+
+/// Предупреждение об аквапланировании машины игрока: горит от всплытия шин до возврата сцепления.
+/// События приходят только при переходах, поэтому состояние хранится между кадрами
+#[derive(Default)]
+pub struct AquaplaningWarningSystem {
+    speed: Option<f32>, // Скорость, на которой шины всплыли, м/с
+}
+
+impl System for AquaplaningWarningSystem {
+    fn update(&mut self, world: &mut World, _delta_time: f32) {
+        let player = world.query::<&PlayerCarComponent>().iter().next().map(|(entity, _)| entity);
+        if let Some((_, queue)) = world.query::<&Resource<EventQueue<HydroplaneEvent>>>().iter().next() {
+            for event in queue.0.iter().filter(|event| Some(event.car) == player) {
+                self.speed = event.active.then_some(event.speed.abs());
+            }
+        }
+
+        let lines = self
+            .speed
+            .filter(|_| !hud_limited(world))
+            .map(|speed| {
+                HudTextLine::new(format!("АКВАПЛАНИРОВАНИЕ {:.0} км/ч", speed * 3.6), [0.5, 0.25], 0.035)
+                    .color(TEXT_WARNING)
+                    .align(TextAlign::Center)
+            })
+            .into_iter()
+            .collect();
+        set_hud_text(world, "aquaplaning", lines);
+    }
+}
+
+/// Сколько держится итог выезда за пределы трассы после возврата на полотно, с
+const OFF_TRACK_SUMMARY_TIME: f32 = 2.0;
+
+/// Выезд машины игрока за пределы трассы: надпись горит, пока машина вне полотна,
+/// после возврата ненадолго показывается длительность выезда
+#[derive(Default)]
+pub struct OffTrackWarningSystem {
+    off_track: bool,
+    summary: Option<(f32, f32)>, // Длительность последнего выезда и оставшееся время показа, с
+}
+
+impl System for OffTrackWarningSystem {
+    fn update(&mut self, world: &mut World, delta_time: f32) {
+        let player = world.query::<&PlayerCarComponent>().iter().next().map(|(entity, _)| entity);
+        if let Some((_, queue)) = world.query::<&Resource<EventQueue<OffTrackEvent>>>().iter().next() {
+            for event in queue.0.iter().filter(|event| Some(event.car_entity) == player) {
+                match event.phase {
+                    OffTrackPhase::Started => {
+                        self.off_track = true;
+                        self.summary = None;
+                    }
+                    OffTrackPhase::Ended => {
+                        self.off_track = false;
+                        self.summary = Some((event.duration, OFF_TRACK_SUMMARY_TIME));
+                    }
+                }
+            }
+        }
+        if let Some((_, remaining)) = &mut self.summary {
+            *remaining -= delta_time;
+        }
+        self.summary = self.summary.filter(|&(_, remaining)| remaining > 0.0);
+
+        let line = if hud_limited(world) {
+            None
+        } else if self.off_track {
+            Some(HudTextLine::new("ВНЕ ТРАССЫ", [0.5, 0.31], 0.035).color(TEXT_WARNING))
+        } else {
+            self.summary.map(|(duration, _)| {
+                HudTextLine::new(format!("Выезд за пределы {:.1} с", duration), [0.5, 0.31], 0.03).color(TEXT_HIGHLIGHT)
+            })
+        };
+        set_hud_text(world, "off_track", line.map(|line| line.align(TextAlign::Center)).into_iter().collect());
+    }
+}
+
+/// Флаг дирекции гонки, показанный пилоту машины игрока; зеленый не рисуется.
+/// Флаги видны и при ограниченном HUD
+pub struct RaceFlagSystem;
+
+impl System for RaceFlagSystem {
+    fn update(&mut self, world: &mut World, _delta_time: f32) {
+        let driver = world
+            .query::<(&PlayerCarComponent, &RaceProgressComponent)>()
+            .iter()
+            .next()
+            .map(|(_, (_, progress))| progress.driver_name.clone());
+        let flag = driver
+            .and_then(|driver| {
+                world
+                    .query::<&Resource<RaceControl>>()
+                    .iter()
+                    .next()
+                    .map(|(_, control)| control.0.flag_for(&driver))
+            })
+            .unwrap_or_default();
+
+        let line = match flag {
+            RaceFlag::Green => None,
+            RaceFlag::Yellow => Some(HudTextLine::new("ЖЕЛТЫЙ ФЛАГ", [0.5, 0.26], 0.04).color(TEXT_HIGHLIGHT)),
+            RaceFlag::Blue => Some(HudTextLine::new("СИНИЙ ФЛАГ: ПРОПУСТИТЕ ЛИДЕРА", [0.5, 0.26], 0.04).color(TEXT_BLUE_FLAG)),
+        };
+        set_hud_text(world, "race_flag", line.map(|line| line.align(TextAlign::Center)).into_iter().collect());
+    }
+}
+
+/// Уведомление о новом достижении из книги рекордов
+pub struct MilestoneNoticeSystem;
+
+impl System for MilestoneNoticeSystem {
+    fn update(&mut self, world: &mut World, _delta_time: f32) {
+        let text = world
+            .query::<&Resource<MilestoneNotice>>()
+            .iter()
+            .next()
+            .and_then(|(_, notice)| notice.0.text.clone());
+        let lines = text
+            .map(|text| HudTextLine::new(text, [0.5, 0.32], 0.035).color(TEXT_GOOD).align(TextAlign::Center))
+            .into_iter()
+            .collect();
+        set_hud_text(world, "milestone", lines);
+    }
+}
+
+/// Подсказка об отключенном геймпаде: список устройств, выбранное подсвечено
+pub struct ControllerPromptSystem;
+
+impl System for ControllerPromptSystem {
+    fn update(&mut self, world: &mut World, _delta_time: f32) {
+        let prompt = world
+            .query::<&Resource<ControllerPrompt>>()
+            .iter()
+            .next()
+            .map(|(_, res)| res.0.clone())
+            .unwrap_or_default();
+
+        let mut lines = Vec::new();
+        if prompt.open {
+            lines.push(HudTextLine::new(prompt.title(), [0.5, 0.3], 0.045).color(TEXT_WARNING).align(TextAlign::Center));
+            lines.push(HudTextLine::new("Выберите устройство", [0.5, 0.36], 0.03).align(TextAlign::Center));
+            for (index, label) in prompt.device_labels().into_iter().enumerate() {
+                let color = if index == prompt.selected { TEXT_HIGHLIGHT } else { [1.0, 1.0, 1.0, 0.8] };
+                lines.push(HudTextLine::new(label, [0.5, 0.42 + index as f32 * 0.04], 0.03).color(color).align(TextAlign::Center));
+            }
+        }
+        set_hud_text(world, "controller_prompt", lines);
+    }
+}
+
+/// Ниже этой доли бака указатель предупреждает о нехватке топлива
+const LOW_FUEL_LEVEL: f32 = 0.1;
+
+/// Указатель топлива машины игрока
+#[derive(Debug, Clone, Default)]
+pub struct FuelGauge {
+    pub visible: bool,
+    pub level: f32,  // Доля бака, 0..1
+    pub liters: f32,
+    pub low: bool,
+    pub refueling: bool,
+}
+
+/// Система указателя топлива
+pub struct FuelGaugeSystem;
+
+impl System for FuelGaugeSystem {
+    fn update(&mut self, world: &mut World, _delta_time: f32) {
+        let gauge = world
+            .query::<(&PlayerCarComponent, &CarComponent)>()
+            .iter()
+            .next()
+            .map(|(_, (_, car))| FuelGauge {
+                visible: true,
+                level: car.fuel_level(),
+                liters: car.fuel,
+                low: car.fuel_level() < LOW_FUEL_LEVEL,
+                refueling: car.refueling,
+            })
+            .unwrap_or_default();
+
+        let mut lines = Vec::new();
+        if gauge.visible {
+            let text = format!("ТОПЛИВО {:.1} л ({:.0}%)", gauge.liters, gauge.level * 100.0);
+            let color = if gauge.low { TEXT_WARNING } else { [1.0; 4] };
+            lines.push(HudTextLine::new(text, [0.02, 0.84], 0.03).color(color));
+            if gauge.refueling {
+                lines.push(HudTextLine::new("ЗАПРАВКА", [0.02, 0.8], 0.03).color(TEXT_GOOD));
+            }
+        }
+        set_hud_text(world, "fuel", lines);
+
+        match world.query_mut::<&mut Resource<FuelGauge>>().into_iter().next() {
+            Some((_, current)) => current.0 = gauge,
+            None => {
+                world.spawn((Resource(gauge),));
+            }
+        }
+    }
+}
+
+/// Указатель наддува машины игрока; у атмосферных машин скрыт
+#[derive(Debug, Clone, Default)]
+pub struct BoostGauge {
+    pub visible: bool,
+    pub boost: f32, // Избыточное давление, бар
+    pub share: f32, // Доля от полного давления, 0..1
+}
+
+/// Система указателя наддува
+pub struct BoostGaugeSystem;
+
+impl System for BoostGaugeSystem {
+    fn update(&mut self, world: &mut World, _delta_time: f32) {
+        let gauge = world
+            .query::<(&PlayerCarComponent, &CarComponent)>()
+            .iter()
+            .next()
+            .and_then(|(_, (_, car))| car.forced_induction)
+            .map(|induction| BoostGauge {
+                visible: true,
+                boost: induction.boost,
+                share: induction.boost_share(),
+            })
+            .unwrap_or_default();
+
+        let lines = if gauge.visible {
+            let text = format!("НАДДУВ {:.2} бар", gauge.boost);
+            let color = if gauge.share > 0.95 { TEXT_HIGHLIGHT } else { [1.0; 4] };
+            vec![HudTextLine::new(text, [0.02, 0.88], 0.03).color(color)]
+        } else {
+            Vec::new()
+        };
+        set_hud_text(world, "boost", lines);
+
+        match world.query_mut::<&mut Resource<BoostGauge>>().into_iter().next() {
+            Some((_, current)) => current.0 = gauge,
+            None => {
+                world.spawn((Resource(gauge),));
+            }
+        }
+    }
+}
+
+/// Указатель температуры двигателя машины игрока
+#[derive(Debug, Clone, Default)]
+pub struct EngineTemperatureGauge {
+    pub visible: bool,
+    pub temperature: f32, // °C
+    pub share: f32,       // Положение стрелки от холодного до критического, 0..1
+    pub overheating: bool,
+}
+
+/// Делений шкалы температуры на HUD
+const TEMPERATURE_SCALE_CELLS: usize = 8;
+
+/// Шкала температуры: закрашенные деления до стрелки, пустые после
+fn temperature_scale(share: f32) -> String {
+    let filled = (share.clamp(0.0, 1.0) * TEMPERATURE_SCALE_CELLS as f32).round() as usize;
+    "█".repeat(filled) + &"▁".repeat(TEMPERATURE_SCALE_CELLS - filled)
+}
+
+/// Система указателя температуры двигателя
+pub struct EngineTemperatureGaugeSystem;
+
+impl System for EngineTemperatureGaugeSystem {
+    fn update(&mut self, world: &mut World, _delta_time: f32) {
+        let gauge = world
+            .query::<(&PlayerCarComponent, &CarComponent)>()
+            .iter()
+            .next()
+            .map(|(_, (_, car))| EngineTemperatureGauge {
+                visible: true,
+                temperature: car.engine_temperature,
+                share: ((car.engine_temperature - AMBIENT_ENGINE_TEMPERATURE)
+                    / (CRITICAL_TEMPERATURE - AMBIENT_ENGINE_TEMPERATURE))
+                    .clamp(0.0, 1.0),
+                overheating: car.engine_temperature > OVERHEAT_TEMPERATURE,
+            })
+            .unwrap_or_default();
+
+        let lines = if gauge.visible {
+            let text = format!("ДВИГАТЕЛЬ {:.0}°C {}", gauge.temperature, temperature_scale(gauge.share));
+            let color = if gauge.overheating { TEXT_WARNING } else { [1.0; 4] };
+            vec![HudTextLine::new(text, [0.02, 0.92], 0.03).color(color)]
+        } else {
+            Vec::new()
+        };
+        set_hud_text(world, "engine_temperature", lines);
+
+        match world.query_mut::<&mut Resource<EngineTemperatureGauge>>().into_iter().next() {
+            Some((_, current)) => current.0 = gauge,
+            None => {
+                world.spawn((Resource(gauge),));
+            }
+        }
+    }
+}
+
+/// Сколько показывается отметка нового лучшего круга, с
+const BEST_LAP_FLASH_TIME: f32 = 3.0;
+
+/// Разница времени со знаком: зеленая, если быстрее лучшего
+fn delta_text(delta: f32) -> (String, [f32; 4]) {
+    let color = if delta <= 0.0 { TEXT_GOOD } else { TEXT_WARNING };
+    (format!("{:+.3}", delta), color)
+}
+
+/// Строки секундомера вверху по центру экрана
+fn lap_timer_text(timer: &LapTimer) -> Vec<HudTextLine> {
+    let mut lines = Vec::new();
+    if !timer.visible {
+        return lines;
+    }
+    let current = timer.current.map(format_lap_time).unwrap_or_else(|| "-:--.---".to_string());
+    let title = if timer.stage {
+        format!("ЭТАП  {}", current)
+    } else {
+        format!("КРУГ {}  {}", timer.lap, current)
+    };
+    lines.push(HudTextLine::new(title, [0.5, 0.02], 0.045).align(TextAlign::Center));
+
+    let last = timer.last.map(format_lap_time).unwrap_or_else(|| "-:--.---".to_string());
+    let best = timer.best.map(format_lap_time).unwrap_or_else(|| "-:--.---".to_string());
+    let best_color = if timer.new_best { TEXT_GOOD } else { [1.0, 1.0, 1.0, 0.8] };
+    lines.push(
+        HudTextLine::new(format!("ПОСЛЕДНИЙ {}  ЛУЧШИЙ {}", last, best), [0.5, 0.07], 0.025)
+            .color(best_color)
+            .align(TextAlign::Center),
+    );
+
+    // Разница с лучшим кругом подписана текущим сектором
+    if let Some(delta) = timer.delta {
+        let (text, color) = delta_text(delta);
+        let text = format!("S{} {}", timer.sector, text);
+        lines.push(HudTextLine::new(text, [0.5, 0.1], 0.035).color(color).align(TextAlign::Center));
+    }
+    for (i, split) in timer.splits.iter().enumerate() {
+        let split = format!("S{} {}", i + 1, format_lap_time(*split));
+        let line = match timer.split_deltas.get(i).copied().flatten() {
+            Some(delta) => {
+                let (text, color) = delta_text(delta);
+                HudTextLine::new(format!("{} {}", split, text), [0.02, 0.15 + i as f32 * 0.03], 0.025).color(color)
+            }
+            None => HudTextLine::new(split, [0.02, 0.15 + i as f32 * 0.03], 0.025),
+        };
+        lines.push(line);
+    }
+    lines
+}
+
+/// Секундомер круга машины игрока
+#[derive(Debug, Clone, Default)]
+pub struct LapTimer {
+    pub visible: bool,
+    pub lap: u32,    // Текущий круг, начиная с 1
+    pub stage: bool, // Спецучасток: вместо круга показывается время этапа
+    pub current: Option<f32>,
+    pub last: Option<f32>,
+    pub best: Option<f32>,
+    pub new_best: bool, // Только что проехан лучший круг
+    pub sector: usize,  // Текущий сектор, начиная с 1
+    pub splits: Vec<f32>,               // Сектора текущего круга
+    pub split_deltas: Vec<Option<f32>>, // Разница секторов с лучшим кругом, с
+    pub delta: Option<f32>,             // Разница с лучшим кругом в этой точке трассы, с
+}
+
+/// Система секундомера круга
+pub struct LapTimerSystem {
+    flash: f32,
+}
+
+impl LapTimerSystem {
+    pub fn new() -> Self {
+        Self { flash: 0.0 }
+    }
+}
+
+impl Default for LapTimerSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl System for LapTimerSystem {
+    fn update(&mut self, world: &mut World, delta_time: f32) {
+        let player = world
+            .query::<(&PlayerCarComponent, &LapTimingComponent)>()
+            .iter()
+            .next()
+            .map(|(entity, (_, timing))| (entity, timing.clone()));
+
+        self.flash = (self.flash - delta_time).max(0.0);
+        if let Some((player_entity, _)) = &player {
+            let best_lap = world
+                .query::<&Resource<EventQueue<CarLapCompletedEvent>>>()
+                .iter()
+                .any(|(_, queue)| queue.0.iter().any(|event| event.car_entity == *player_entity && event.best));
+            if best_lap {
+                self.flash = BEST_LAP_FLASH_TIME;
+            }
+        }
+
+        let stage = world.query::<&TrackComponent>().iter().any(|(_, track)| track.point_to_point);
+        let limited = hud_limited(world);
+        let timer = player
+            .map(|(_, timing)| LapTimer {
+                visible: true,
+                lap: timing.laps_completed + 1,
+                stage,
+                current: timing.current_lap_time,
+                last: timing.last_lap_time,
+                best: timing.best_lap_time,
+                new_best: self.flash > 0.0,
+                sector: timing.sector() + 1,
+                // Ограниченный HUD не показывает сектора и сравнение с лучшим кругом
+                split_deltas: if limited {
+                    Vec::new()
+                } else {
+                    timing
+                        .sector_splits
+                        .iter()
+                        .enumerate()
+                        .map(|(i, split)| timing.best_lap_splits.get(i).map(|best| split - best))
+                        .collect()
+                },
+                splits: if limited { Vec::new() } else { timing.sector_splits },
+                delta: timing.delta_to_best.filter(|_| !limited),
+            })
+            .unwrap_or_default();
+
+        set_hud_text(world, "lap_timer", lap_timer_text(&timer));
+
+        match world.query_mut::<&mut Resource<LapTimer>>().into_iter().next() {
+            Some((_, current)) => current.0 = timer,
+            None => {
+                world.spawn((Resource(timer),));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::text::HudText;
+    use crate::gameplay::intro::IntroShot;
+
+    fn hud_text(world: &World) -> Vec<String> {
+        world
+            .query::<&Resource<HudText>>()
+            .iter()
+            .next()
+            .map(|(_, res)| res.0.lines().map(|line| line.text.clone()).collect())
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn lower_third_draws_driver_and_car() {
+        let mut world = World::new();
+        let car_entity = world.spawn(());
+        world.spawn((Resource(IntroState {
+            active: true,
+            current: Some(IntroShot {
+                car_entity,
+                driver_name: "Player".to_string(),
+                car_name: "SportsCar".to_string(),
+                grid_position: 3,
+            }),
+            shot_progress: 0.5,
+        }),));
+
+        LowerThirdSystem::new().update(&mut world, 0.1);
+        let text = hud_text(&world);
+        assert!(text.contains(&"P3  Player".to_string()));
+        assert!(text.contains(&"SportsCar".to_string()));
+
+        for (_, intro) in world.query_mut::<&mut Resource<IntroState>>() {
+            intro.0.active = false;
+        }
+        LowerThirdSystem::new().update(&mut world, 0.1);
+        assert!(hud_text(&world).is_empty());
+    }
+
+    #[test]
+    fn aquaplaning_warning_lasts_until_grip_returns() {
+        let mut world = World::new();
+        let car = world.spawn((PlayerCarComponent,));
+        let event = |active| HydroplaneEvent { car, active, speed: 25.0 };
+        let mut system = AquaplaningWarningSystem::default();
+
+        crate::game_world::track::publish_frame_events(&mut world, vec![event(true)]);
+        system.update(&mut world, 0.1);
+        crate::game_world::track::publish_frame_events::<HydroplaneEvent>(&mut world, Vec::new());
+        system.update(&mut world, 0.1);
+        assert_eq!(hud_text(&world), vec!["АКВАПЛАНИРОВАНИЕ 90 км/ч".to_string()]);
+
+        crate::game_world::track::publish_frame_events(&mut world, vec![event(false)]);
+        system.update(&mut world, 0.1);
+        assert!(hud_text(&world).is_empty());
+    }
+
+    #[test]
+    fn temperature_scale_follows_the_needle() {
+        assert_eq!(temperature_scale(0.0), "▁▁▁▁▁▁▁▁");
+        assert_eq!(temperature_scale(0.5), "████▁▁▁▁");
+        assert_eq!(temperature_scale(1.5), "████████");
+    }
+
+    #[test]
+    fn lap_delta_shows_the_current_sector() {
+        let timer = LapTimer {
+            visible: true,
+            lap: 2,
+            sector: 3,
+            delta: Some(-0.25),
+            ..Default::default()
+        };
+        let lines = lap_timer_text(&timer);
+        let delta = lines.iter().find(|line| line.position == [0.5, 0.1]).unwrap();
+        assert_eq!(delta.text, "S3 -0.250");
+        assert_eq!(delta.color, TEXT_GOOD);
+    }
+
+    #[test]
+    fn off_track_warning_shows_excursion_duration() {
+        let mut world = World::new();
+        let car = world.spawn((PlayerCarComponent,));
+        let other = world.spawn((CarComponent::default(),));
+        let mut system = OffTrackWarningSystem::default();
+
+        crate::game_world::track::publish_frame_events(
+            &mut world,
+            vec![OffTrackEvent { car_entity: other, phase: OffTrackPhase::Started, duration: 0.0 }],
+        );
+        system.update(&mut world, 0.1);
+        assert!(hud_text(&world).is_empty());
+
+        crate::game_world::track::publish_frame_events(
+            &mut world,
+            vec![OffTrackEvent { car_entity: car, phase: OffTrackPhase::Started, duration: 0.0 }],
+        );
+        system.update(&mut world, 0.1);
+        assert_eq!(hud_text(&world), vec!["ВНЕ ТРАССЫ".to_string()]);
+
+        crate::game_world::track::publish_frame_events(
+            &mut world,
+            vec![OffTrackEvent { car_entity: car, phase: OffTrackPhase::Ended, duration: 1.4 }],
+        );
+        system.update(&mut world, 0.1);
+        assert_eq!(hud_text(&world), vec!["Выезд за пределы 1.4 с".to_string()]);
+
+        crate::game_world::track::publish_frame_events::<OffTrackEvent>(&mut world, Vec::new());
+        system.update(&mut world, OFF_TRACK_SUMMARY_TIME);
+        assert!(hud_text(&world).is_empty());
+    }
+}
@@ -0,0 +1,342 @@
+use crate::core::ecs::{Resource, System};
+use crate::core::physics::TransformComponent;
+use crate::core::text::{set_hud_text, HudTextLine, TextAlign};
+use crate::game_world::car::{CarComponent, PlayerCarComponent};
+use crate::game_world::track::{CheckpointComponent, TrackComponent, TrackSegmentComponent};
+use crate::ui::hud::hud_limited;
+use glam::{Vec2, Vec3};
+use hecs::World;
+
+/// Шаг точек контура вдоль осевой линии, м
+const OUTLINE_STEP: f32 = 5.0;
+
+/// Поле вокруг контура, доля стороны миникарты
+const MINIMAP_MARGIN: f32 = 0.05;
+
+/// Концы контура ближе этого считаются сомкнутыми, м
+const CLOSED_OUTLINE_GAP: f32 = 2.0 * OUTLINE_STEP;
+
+/// Рамка миникарты на экране: левый верхний угол и размер в долях экрана
+const MINIMAP_SCREEN_ORIGIN: [f32; 2] = [0.86, 0.76];
+const MINIMAP_SCREEN_SIZE: [f32; 2] = [0.12, 0.2];
+
+/// Больше точек контура не рисуется, длинная трасса прореживается
+const MAX_OUTLINE_DOTS: usize = 120;
+
+/// Цвета миникарты
+const OUTLINE_COLOR: [f32; 4] = [0.8, 0.8, 0.8, 0.7];
+const CHECKPOINT_COLOR: [f32; 4] = [0.6, 0.8, 1.0, 0.9];
+const PLAYER_COLOR: [f32; 4] = [1.0, 0.85, 0.3, 1.0];
+const OPPONENT_COLOR: [f32; 4] = [1.0, 0.3, 0.25, 1.0];
+
+/// Отметка чекпоинта на миникарте
+#[derive(Debug, Clone)]
+pub struct MinimapCheckpoint {
+    pub index: usize,
+    pub position: Vec2,
+    pub is_finish_line: bool,
+}
+
+/// Машина на миникарте
+#[derive(Debug, Clone)]
+pub struct MinimapCar {
+    pub position: Vec2,
+    pub direction: Vec2, // Единичное направление движения на карте
+    pub player: bool,
+}
+
+/// Миникарта трассы: вид сверху в координатах 0..1 по обеим осям, x вправо, y вниз;
+/// пропорции трассы сохраняются. Контур и чекпоинты строятся один раз на трассу, машины - каждый кадр
+#[derive(Debug, Clone, Default)]
+pub struct MinimapData {
+    pub visible: bool, // Ограниченный HUD прячет миникарту
+    pub track_name: String,
+    pub outline: Vec<Vec2>, // Осевая линия по порядку сегментов
+    pub closed: bool,
+    pub checkpoints: Vec<MinimapCheckpoint>,
+    pub cars: Vec<MinimapCar>,
+    origin: Vec2, // Угол рамки в плоскости карты до масштабирования
+    scale: f32,   // Доля стороны карты на метр
+}
+
+/// Точка мира на плоскости карты: сверху, +Z вверх, +X влево
+fn map_plane(position: Vec3) -> Vec2 {
+    Vec2::new(-position.x, -position.z)
+}
+
+impl MinimapData {
+    /// Контур и отметки трассы; None, если трасса не найдена или у нее нет полотна
+    pub fn from_track(world: &World, track_entity: hecs::Entity) -> Option<Self> {
+        let track = world.get::<&TrackComponent>(track_entity).ok()?;
+        let mut points = Vec::new();
+        for &entity in &track.segments {
+            let (Ok(segment), Ok(transform)) = (world.get::<&TrackSegmentComponent>(entity), world.get::<&TransformComponent>(entity)) else {
+                continue;
+            };
+            let steps = ((segment.length / OUTLINE_STEP).ceil() as usize).max(1);
+            // Начало сегмента совпадает с концом предыдущего, поэтому берется только у первого
+            let first = if points.is_empty() { 0 } else { 1 };
+            for step in first..=steps {
+                let s = -segment.length / 2.0 + segment.length * step as f32 / steps as f32;
+                points.push(transform.position + transform.rotation * segment.centerline_at(s));
+            }
+        }
+        if points.len() < 2 {
+            return None;
+        }
+
+        let plane: Vec<Vec2> = points.iter().map(|&point| map_plane(point)).collect();
+        let min = plane.iter().copied().fold(Vec2::splat(f32::MAX), Vec2::min);
+        let max = plane.iter().copied().fold(Vec2::splat(f32::MIN), Vec2::max);
+        let extent = (max - min).max_element().max(f32::EPSILON);
+        let scale = (1.0 - 2.0 * MINIMAP_MARGIN) / extent;
+        // Трасса по центру карты вдоль короткой стороны
+        let origin = (min + max) / 2.0 - Vec2::splat(0.5 / scale);
+
+        let mut minimap = Self {
+            visible: true,
+            track_name: track.name.clone(),
+            outline: Vec::new(),
+            closed: points[0].distance(points[points.len() - 1]) <= CLOSED_OUTLINE_GAP,
+            checkpoints: Vec::new(),
+            cars: Vec::new(),
+            origin,
+            scale,
+        };
+        minimap.outline = points.iter().map(|&point| minimap.project(point)).collect();
+        minimap.checkpoints = track
+            .checkpoints
+            .iter()
+            .filter_map(|&entity| {
+                let checkpoint = world.get::<&CheckpointComponent>(entity).ok()?;
+                let transform = world.get::<&TransformComponent>(entity).ok()?;
+                Some(MinimapCheckpoint {
+                    index: checkpoint.index,
+                    position: minimap.project(transform.position),
+                    is_finish_line: checkpoint.is_finish_line,
+                })
+            })
+            .collect();
+        minimap.checkpoints.sort_by_key(|checkpoint| checkpoint.index);
+        Some(minimap)
+    }
+
+    /// Точка мира на миникарте
+    pub fn project(&self, position: Vec3) -> Vec2 {
+        (map_plane(position) - self.origin) * self.scale
+    }
+
+    /// Направление в мире на миникарте
+    pub fn project_direction(&self, direction: Vec3) -> Vec2 {
+        map_plane(direction).normalize_or_zero()
+    }
+
+    /// Надписи HUD миникарты: название, контур точками, чекпоинты номерами, машины стрелками.
+    /// Концы открытой трассы отмечены крестиками, линия финиша - блоком
+    pub fn hud_lines(&self) -> Vec<HudTextLine> {
+        if !self.visible {
+            return Vec::new();
+        }
+        let [left, top] = MINIMAP_SCREEN_ORIGIN;
+        let [width, height] = MINIMAP_SCREEN_SIZE;
+        let mark = |text: &str, position: Vec2, size: f32, color: [f32; 4]| {
+            // Надпись привязана к верхнему краю строки, поэтому поднимается на половину высоты
+            HudTextLine::new(text, [left + position.x * width, top + position.y * height - size / 2.0], size)
+                .color(color)
+                .align(TextAlign::Center)
+        };
+
+        let mut lines = vec![HudTextLine::new(self.track_name.clone(), [left + width, top - 0.03], 0.022)
+            .color(OUTLINE_COLOR)
+            .align(TextAlign::Right)];
+        let stride = self.outline.len().div_ceil(MAX_OUTLINE_DOTS).max(1);
+        lines.extend(self.outline.iter().step_by(stride).map(|&point| mark("·", point, 0.02, OUTLINE_COLOR)));
+        if !self.closed {
+            for &end in [self.outline.first(), self.outline.last()].iter().flatten() {
+                lines.push(mark("×", *end, 0.022, OUTLINE_COLOR));
+            }
+        }
+        for checkpoint in &self.checkpoints {
+            lines.push(if checkpoint.is_finish_line {
+                mark("█", checkpoint.position, 0.018, [1.0, 1.0, 1.0, 1.0])
+            } else {
+                mark(&(checkpoint.index + 1).to_string(), checkpoint.position, 0.018, CHECKPOINT_COLOR)
+            });
+        }
+        // Машина игрока рисуется последней, поверх соперников
+        let mut cars: Vec<&MinimapCar> = self.cars.iter().collect();
+        cars.sort_by_key(|car| car.player);
+        for car in cars {
+            let color = if car.player { PLAYER_COLOR } else { OPPONENT_COLOR };
+            lines.push(mark(direction_arrow(car.direction), car.position, 0.026, color));
+        }
+        lines
+    }
+}
+
+/// Стрелка по направлению на карте, y вниз
+fn direction_arrow(direction: Vec2) -> &'static str {
+    if direction.x.abs() > direction.y.abs() {
+        if direction.x > 0.0 { "→" } else { "←" }
+    } else if direction.y > 0.0 {
+        "↓"
+    } else {
+        "↑"
+    }
+}
+
+/// Система миникарты: перестраивает контур при смене трассы и переносит на карту машины
+pub struct MinimapSystem {
+    track: Option<hecs::Entity>,
+}
+
+impl MinimapSystem {
+    pub fn new() -> Self {
+        Self { track: None }
+    }
+}
+
+impl Default for MinimapSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl System for MinimapSystem {
+    fn update(&mut self, world: &mut World, _delta_time: f32) {
+        let track = world.query::<&TrackComponent>().iter().next().map(|(entity, _)| entity);
+        let existing = world.query::<&Resource<MinimapData>>().iter().next().map(|(entity, _)| entity);
+        if track != self.track || existing.is_none() {
+            self.track = track;
+            let minimap = track.and_then(|entity| MinimapData::from_track(world, entity)).unwrap_or_default();
+            match existing {
+                Some(entity) => {
+                    if let Ok(mut resource) = world.get::<&mut Resource<MinimapData>>(entity) {
+                        resource.0 = minimap;
+                    }
+                }
+                None => {
+                    world.spawn((Resource(minimap),));
+                }
+            }
+        }
+
+        let cars: Vec<(Vec3, Vec3, bool)> = world
+            .query::<(&CarComponent, &TransformComponent, Option<&PlayerCarComponent>)>()
+            .iter()
+            .map(|(_, (_, transform, player))| (transform.position, transform.rotation * Vec3::Z, player.is_some()))
+            .collect();
+        let limited = hud_limited(world);
+        let Some((_, resource)) = world.query_mut::<&mut Resource<MinimapData>>().into_iter().next() else {
+            return;
+        };
+        let minimap = &mut resource.0;
+        minimap.visible = !limited && !minimap.outline.is_empty();
+        minimap.cars = if minimap.outline.is_empty() {
+            Vec::new()
+        } else {
+            cars.into_iter()
+                .map(|(position, forward, player)| MinimapCar {
+                    position: minimap.project(position),
+                    direction: minimap.project_direction(forward),
+                    player,
+                })
+                .collect()
+        };
+        let lines = minimap.hud_lines();
+        set_hud_text(world, "minimap", lines);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::physics::PhysicsMaterialId;
+    use crate::game_world::track::{SurfaceType, TrackMetadata, TrackSegmentType};
+    use glam::Quat;
+
+    /// Прямые по 100 м по сторонам квадрата против часовой стрелки сверху, начиная от нуля вдоль +Z
+    fn square_track(world: &mut World, sides: usize) -> hecs::Entity {
+        let corners = [Vec3::ZERO, Vec3::new(0.0, 0.0, 100.0), Vec3::new(100.0, 0.0, 100.0), Vec3::new(100.0, 0.0, 0.0)];
+        let segments = (0..sides)
+            .map(|side| {
+                let (start, end) = (corners[side], corners[(side + 1) % 4]);
+                let direction = (end - start).normalize();
+                world.spawn((
+                    TrackSegmentComponent {
+                        segment_type: TrackSegmentType::Straight,
+                        length: 100.0,
+                        width: 10.0,
+                        curvature: 0.0,
+                        banking: 0.0,
+                        banking_change: 0.0,
+                        vertical_curvature: 0.0,
+                        surface_type: SurfaceType::Asphalt,
+                        material: PhysicsMaterialId::ASPHALT,
+                        surface_bands: Vec::new(),
+                        ramp_height: 0.0,
+                    },
+                    TransformComponent {
+                        position: (start + end) / 2.0,
+                        rotation: Quat::from_rotation_y(direction.x.atan2(direction.z)),
+                        ..Default::default()
+                    },
+                ))
+            })
+            .collect();
+        let checkpoint = |world: &mut World, index, position, is_finish_line| {
+            world.spawn((
+                CheckpointComponent { index, width: 10.0, is_finish_line, is_start_line: false },
+                TransformComponent { position, ..Default::default() },
+            ))
+        };
+        let checkpoints = vec![
+            checkpoint(world, 1, Vec3::new(100.0, 0.0, 50.0), true),
+            checkpoint(world, 0, Vec3::new(0.0, 0.0, 50.0), false),
+        ];
+        world.spawn((TrackComponent {
+            name: "Square".to_string(),
+            layout: "Full".to_string(),
+            length: 100.0 * sides as f32,
+            segments,
+            checkpoints,
+            props: Vec::new(),
+            start_positions: Vec::new(),
+            pit_lane: Vec::new(),
+            point_to_point: sides < 4,
+            metadata: TrackMetadata::default(),
+        },))
+    }
+
+    fn assert_near(actual: Vec2, expected: Vec2) {
+        assert!(actual.abs_diff_eq(expected, 1e-3), "{} != {}", actual, expected);
+    }
+
+    #[test]
+    fn loop_track_is_closed_and_checkpoints_are_projected() {
+        let mut world = World::new();
+        let track = square_track(&mut world, 4);
+        let minimap = MinimapData::from_track(&world, track).unwrap();
+        assert!(minimap.closed);
+        assert_eq!(minimap.track_name, "Square");
+        // +X мира влево, +Z вверх, квадрат вписан в рамку с полями
+        assert_eq!(minimap.checkpoints.iter().map(|c| c.index).collect::<Vec<_>>(), vec![0, 1]);
+        assert_near(minimap.checkpoints[0].position, Vec2::new(0.95, 0.5));
+        assert_near(minimap.checkpoints[1].position, Vec2::new(0.05, 0.5));
+        assert!(minimap.checkpoints[1].is_finish_line);
+        assert_near(minimap.outline[0], Vec2::new(0.95, 0.95));
+    }
+
+    #[test]
+    fn point_to_point_track_stays_open() {
+        let mut world = World::new();
+        let track = square_track(&mut world, 2);
+        let minimap = MinimapData::from_track(&world, track).unwrap();
+        assert!(!minimap.closed);
+        // Две стороны квадрата занимают ту же рамку, что и весь квадрат
+        assert_near(minimap.checkpoints[0].position, Vec2::new(0.95, 0.5));
+        assert_near(*minimap.outline.last().unwrap(), Vec2::new(0.05, 0.05));
+        assert_near(minimap.project_direction(Vec3::Z), Vec2::new(0.0, -1.0));
+        assert_eq!(direction_arrow(minimap.project_direction(Vec3::X)), "←");
+    }
+}
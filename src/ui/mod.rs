@@ -1 +1,37 @@
-// Здесь будут реализованы меню, HUD, реплеи и настройки управления 
\ No newline at end of file
+// Здесь будут реализованы меню, HUD, реплеи и настройки управления
+
+pub mod audio_settings;
+pub mod hud;
+pub mod minimap;
+pub mod perf_overlay;
+pub mod save_browser;
+pub mod time_trial;
+pub mod tuning;
+pub mod world_ui;
+
+use crate::core::ecs::EcsManager;
+
+/// Регистрация систем пользовательского интерфейса
+pub fn register_systems(ecs_manager: &mut EcsManager) {
+    ecs_manager.register_system(hud::HudSystem);
+    ecs_manager.register_system(hud::LowerThirdSystem::new());
+    ecs_manager.register_system(hud::SlowMotionWarningSystem::new());
+    ecs_manager.register_system(hud::HotSeatBoardSystem);
+    ecs_manager.register_system(hud::HazardWarningSystem::new());
+    ecs_manager.register_system(hud::AquaplaningWarningSystem::default());
+    ecs_manager.register_system(hud::OffTrackWarningSystem::default());
+    ecs_manager.register_system(hud::RaceFlagSystem);
+    ecs_manager.register_system(hud::MilestoneNoticeSystem);
+    ecs_manager.register_system(hud::ControllerPromptSystem);
+    ecs_manager.register_system(hud::FuelGaugeSystem);
+    ecs_manager.register_system(hud::BoostGaugeSystem);
+    ecs_manager.register_system(hud::EngineTemperatureGaugeSystem);
+    ecs_manager.register_system(hud::LapTimerSystem::new());
+    ecs_manager.register_system(minimap::MinimapSystem::new());
+    ecs_manager.register_system(audio_settings::AudioSettingsSystem::new());
+    ecs_manager.register_system(time_trial::TimeTrialMenuSystem::new());
+    ecs_manager.register_system(save_browser::SaveBrowserSystem::new());
+    ecs_manager.register_system(tuning::TuningMenuSystem::new());
+    ecs_manager.register_system(world_ui::WorldUiSystem::new());
+    ecs_manager.register_system(perf_overlay::PerfOverlaySystem::new());
+}
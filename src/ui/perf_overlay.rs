@@ -0,0 +1,178 @@
+use crate::core::ecs::{Resource, System};
+use crate::core::input::InputAction;
+use crate::core::renderer::RenderStats;
+use crate::core::text::{set_hud_text, HudTextLine};
+use hecs::World;
+use rapier3d::prelude::{ColliderSet, RigidBodySet};
+use std::collections::{HashMap, VecDeque};
+
+/// Число кадров в графике времени кадра
+const FRAME_HISTORY: usize = 120;
+
+/// Символы столбцов графика от низкого к высокому
+const GRAPH_BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Уровень подробности оверлея производительности
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PerfOverlayLevel {
+    #[default]
+    Off,
+    Fps,      // Только частота кадров
+    Graph,    // Частота, время кадра и график
+    Detailed, // Плюс вызовы отрисовки, видимые объекты и физические тела
+}
+
+impl PerfOverlayLevel {
+    pub fn next(self) -> Self {
+        match self {
+            PerfOverlayLevel::Off => PerfOverlayLevel::Fps,
+            PerfOverlayLevel::Fps => PerfOverlayLevel::Graph,
+            PerfOverlayLevel::Graph => PerfOverlayLevel::Detailed,
+            PerfOverlayLevel::Detailed => PerfOverlayLevel::Off,
+        }
+    }
+}
+
+/// Оверлей производительности: легкая замена полноценному профайлеру
+#[derive(Debug, Clone, Default)]
+pub struct PerfOverlay {
+    pub level: PerfOverlayLevel,
+    pub frame_times: VecDeque<f32>, // Время последних кадров, с
+    pub render: RenderStats,
+    pub entity_count: u32,
+    pub physics_bodies: u32,
+    pub awake_bodies: u32,
+    pub lines: Vec<String>, // Готовые строки для отрисовки, пусто при выключенном оверлее
+}
+
+impl PerfOverlay {
+    /// Средний FPS по истории кадров
+    pub fn fps(&self) -> f32 {
+        let total: f32 = self.frame_times.iter().sum();
+        if total > 0.0 {
+            self.frame_times.len() as f32 / total
+        } else {
+            0.0
+        }
+    }
+
+    /// Среднее и худшее время кадра, мс
+    pub fn frame_time_ms(&self) -> (f32, f32) {
+        if self.frame_times.is_empty() {
+            return (0.0, 0.0);
+        }
+        let average = self.frame_times.iter().sum::<f32>() / self.frame_times.len() as f32;
+        let worst = self.frame_times.iter().copied().fold(0.0, f32::max);
+        (average * 1000.0, worst * 1000.0)
+    }
+
+    /// График времени кадра столбцами, масштаб по худшему кадру
+    pub fn frame_time_graph(&self) -> String {
+        let worst = self.frame_times.iter().copied().fold(f32::EPSILON, f32::max);
+        self.frame_times
+            .iter()
+            .map(|&time| {
+                let index = ((time / worst) * (GRAPH_BARS.len() - 1) as f32).round() as usize;
+                GRAPH_BARS[index.min(GRAPH_BARS.len() - 1)]
+            })
+            .collect()
+    }
+
+    fn rebuild_lines(&mut self) {
+        self.lines.clear();
+        if self.level == PerfOverlayLevel::Off {
+            return;
+        }
+        self.lines.push(format!("FPS {:.0}", self.fps()));
+        if self.level == PerfOverlayLevel::Fps {
+            return;
+        }
+        let (average, worst) = self.frame_time_ms();
+        self.lines.push(format!("FRAME {:.1} ms (max {:.1})", average, worst));
+        self.lines.push(self.frame_time_graph());
+        if self.level == PerfOverlayLevel::Graph {
+            return;
+        }
+        self.lines.push(format!("DRAW CALLS {}", self.render.draw_calls));
+        self.lines.push(format!("VISIBLE {} / ENTITIES {}", self.render.visible_objects, self.entity_count));
+        self.lines.push(format!("BODIES {} (awake {})", self.physics_bodies, self.awake_bodies));
+    }
+}
+
+/// Система оверлея производительности, уровень переключается горячей клавишей
+pub struct PerfOverlaySystem {
+    toggle_held: bool,
+}
+
+impl PerfOverlaySystem {
+    pub fn new() -> Self {
+        Self { toggle_held: false }
+    }
+}
+
+impl Default for PerfOverlaySystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl System for PerfOverlaySystem {
+    fn update(&mut self, world: &mut World, delta_time: f32) {
+        let toggle_down = world
+            .query::<&Resource<HashMap<InputAction, f32>>>()
+            .iter()
+            .next()
+            .map(|(_, res)| *res.0.get(&InputAction::TogglePerfOverlay).unwrap_or(&0.0) > 0.5)
+            .unwrap_or(false);
+        let toggle = toggle_down && !self.toggle_held;
+        self.toggle_held = toggle_down;
+
+        let render = world
+            .query::<&Resource<RenderStats>>()
+            .iter()
+            .next()
+            .map(|(_, res)| res.0)
+            .unwrap_or_default();
+        let (physics_bodies, awake_bodies) = world
+            .query::<&Resource<(RigidBodySet, ColliderSet)>>()
+            .iter()
+            .next()
+            .map(|(_, res)| {
+                let bodies = &res.0 .0;
+                (bodies.len() as u32, bodies.iter().filter(|(_, body)| !body.is_sleeping()).count() as u32)
+            })
+            .unwrap_or((0, 0));
+        let entity_count = world.len();
+
+        let Some((_, overlay)) = world.query_mut::<&mut Resource<PerfOverlay>>().into_iter().next() else {
+            world.spawn((Resource(PerfOverlay::default()),));
+            return;
+        };
+        let overlay = &mut overlay.0;
+
+        if toggle {
+            overlay.level = overlay.level.next();
+        }
+        // История копится и при выключенном оверлее, чтобы график сразу был полным
+        overlay.frame_times.push_back(delta_time);
+        while overlay.frame_times.len() > FRAME_HISTORY {
+            overlay.frame_times.pop_front();
+        }
+        overlay.render = render;
+        overlay.entity_count = entity_count;
+        overlay.physics_bodies = physics_bodies;
+        overlay.awake_bodies = awake_bodies;
+        overlay.rebuild_lines();
+
+        // Строки оверлея в левом верхнем углу, выключенный оверлей убирает слой
+        let lines = overlay
+            .lines
+            .iter()
+            .enumerate()
+            .map(|(index, line)| {
+                HudTextLine::new(line.clone(), [0.01, 0.01 + index as f32 * 0.028], 0.025).color([0.6, 1.0, 0.6, 1.0])
+            })
+            .collect();
+        set_hud_text(world, "perf", lines);
+    }
+}
@@ -0,0 +1,280 @@
+use crate::core::ecs::{Resource, System};
+use crate::core::input::InputAction;
+use crate::core::text::{set_hud_text, HudTextLine, TextAlign};
+use crate::gameplay::ghost::{spawn_ghost, GhostLibrary};
+use crate::gameplay::results::{format_time, RESULTS_EXPORT_DIR};
+use crate::gameplay::save_manager::{SaveIndexEntry, SaveKind, SaveManager, SAVES_DIR};
+use hecs::World;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Действие над выбранной записью
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SaveBrowserAction {
+    #[default]
+    Load,
+    Delete,
+    Export,
+}
+
+/// Число записей, видимых в списке одновременно
+const VISIBLE_ROWS: usize = 8;
+
+const ACTIONS: [SaveBrowserAction; 3] = [SaveBrowserAction::Load, SaveBrowserAction::Delete, SaveBrowserAction::Export];
+
+/// Строка списка для отрисовки
+#[derive(Debug, Clone)]
+pub struct SaveBrowserRow {
+    pub title: String,
+    pub details: String,
+    pub thumbnail: Option<PathBuf>,
+}
+
+/// Состояние экрана сохранений, повторов и призраков
+#[derive(Debug, Clone, Default)]
+pub struct SaveBrowserPage {
+    pub open: bool,
+    pub selected: usize,
+    pub action: SaveBrowserAction,
+    pub entries: Vec<SaveIndexEntry>,
+    pub confirm_delete: bool, // Удаление требует повторного подтверждения
+    pub status: Option<String>,
+}
+
+impl SaveBrowserPage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn selected_entry(&self) -> Option<&SaveIndexEntry> {
+        self.entries.get(self.selected)
+    }
+
+    pub fn rows(&self) -> Vec<SaveBrowserRow> {
+        self.entries
+            .iter()
+            .map(|entry| {
+                let kind = match entry.kind {
+                    SaveKind::Save => "Сохранение",
+                    SaveKind::Replay => "Повтор",
+                    SaveKind::Ghost => "Призрак",
+                };
+                let best_lap = entry.best_lap.map(format_time).unwrap_or_else(|| "-".to_string());
+                SaveBrowserRow {
+                    title: format!("{}: {} ({}), {}", kind, entry.track, entry.layout, entry.car),
+                    details: format!("{}  {}  лучший круг {}", format_date(entry.created_at), entry.profile, best_lap),
+                    thumbnail: entry.thumbnail.clone(),
+                }
+            })
+            .collect()
+    }
+
+    /// Подпись кнопки действия
+    pub fn action_label(&self) -> &'static str {
+        match self.action {
+            SaveBrowserAction::Load => "Загрузить",
+            SaveBrowserAction::Delete if self.confirm_delete => "Удалить? Подтвердите",
+            SaveBrowserAction::Delete => "Удалить",
+            SaveBrowserAction::Export => "Экспорт",
+        }
+    }
+}
+
+/// Дата по Unix-времени (UTC) в виде ГГГГ-ММ-ДД ЧЧ:ММ
+fn format_date(timestamp: u64) -> String {
+    let days = (timestamp / 86_400) as i64;
+    let (hour, minute) = (timestamp / 3600 % 24, timestamp / 60 % 60);
+    // Перевод числа дней в гражданскую дату (алгоритм Хиннанта)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!("{:04}-{:02}-{:02} {:02}:{:02}", year, month, day, hour, minute)
+}
+
+/// Браузер сохранений: список из индекса менеджера сохранений и действия над записями
+pub struct SaveBrowserSystem {
+    held: HashMap<InputAction, bool>,
+    saves: SaveManager,
+}
+
+impl SaveBrowserSystem {
+    pub fn new() -> Self {
+        Self {
+            held: HashMap::new(),
+            saves: SaveManager::new(Path::new(SAVES_DIR)),
+        }
+    }
+
+    /// Срабатывание по нажатию, а не удержанию
+    fn pressed(&mut self, inputs: &HashMap<InputAction, f32>, action: InputAction) -> bool {
+        let down = *inputs.get(&action).unwrap_or(&0.0) > 0.5;
+        let was_down = self.held.insert(action, down).unwrap_or(false);
+        down && !was_down
+    }
+}
+
+impl Default for SaveBrowserSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SaveBrowserSystem {
+    fn navigate(&mut self, world: &mut World) {
+        let inputs = world
+            .query::<&Resource<HashMap<InputAction, f32>>>()
+            .iter()
+            .next()
+            .map(|(_, res)| res.0.clone())
+            .unwrap_or_default();
+
+        let toggle = self.pressed(&inputs, InputAction::ToggleSaveBrowser);
+        let back = self.pressed(&inputs, InputAction::MenuBack);
+        let up = self.pressed(&inputs, InputAction::MenuUp);
+        let down = self.pressed(&inputs, InputAction::MenuDown);
+        let left = self.pressed(&inputs, InputAction::MenuLeft);
+        let right = self.pressed(&inputs, InputAction::MenuRight);
+        let confirm = self.pressed(&inputs, InputAction::MenuConfirm);
+
+        let Some((_, page)) = world.query_mut::<&mut Resource<SaveBrowserPage>>().into_iter().next() else {
+            return;
+        };
+        let page = &mut page.0;
+
+        if !page.open {
+            if toggle {
+                // Список перечитывается при каждом открытии: призраки могли добавиться за заезд
+                page.open = true;
+                page.selected = 0;
+                page.action = SaveBrowserAction::Load;
+                page.confirm_delete = false;
+                page.status = None;
+                page.entries = self.saves.entries();
+            }
+            return;
+        }
+
+        if toggle || back {
+            page.open = false;
+            return;
+        }
+
+        let count = page.entries.len().max(1);
+        if up || down || left || right {
+            page.confirm_delete = false;
+        }
+        if up {
+            page.selected = (page.selected + count - 1) % count;
+        }
+        if down {
+            page.selected = (page.selected + 1) % count;
+        }
+        let action_index = ACTIONS.iter().position(|a| *a == page.action).unwrap_or(0);
+        if left {
+            page.action = ACTIONS[(action_index + ACTIONS.len() - 1) % ACTIONS.len()];
+        }
+        if right {
+            page.action = ACTIONS[(action_index + 1) % ACTIONS.len()];
+        }
+
+        if !confirm {
+            return;
+        }
+        let Some(entry) = page.selected_entry().cloned() else {
+            return;
+        };
+
+        let mut ghost_to_load = None;
+        match page.action {
+            SaveBrowserAction::Load => match entry.kind {
+                SaveKind::Ghost => match GhostLibrary::load(&entry.path) {
+                    Ok(lap) => {
+                        page.status = Some(format!("Призрак {} загружен", entry.profile));
+                        page.open = false;
+                        ghost_to_load = Some(lap);
+                    }
+                    Err(e) => page.status = Some(format!("Не удалось загрузить: {}", e)),
+                },
+                SaveKind::Save | SaveKind::Replay => {
+                    page.status = Some("Загрузка этого вида записей пока не поддерживается".to_string());
+                }
+            },
+            SaveBrowserAction::Delete if !page.confirm_delete => page.confirm_delete = true,
+            SaveBrowserAction::Delete => {
+                page.confirm_delete = false;
+                page.status = Some(match self.saves.delete(&entry.id) {
+                    Ok(()) => "Запись удалена".to_string(),
+                    Err(e) => format!("Не удалось удалить: {}", e),
+                });
+                page.entries = self.saves.entries();
+                page.selected = page.selected.min(page.entries.len().saturating_sub(1));
+            }
+            SaveBrowserAction::Export => {
+                let dir = Path::new(RESULTS_EXPORT_DIR).join("saves");
+                page.status = Some(match self.saves.export(&entry.id, &dir) {
+                    Ok(path) => format!("Экспортировано: {}", path.display()),
+                    Err(e) => format!("Не удалось экспортировать: {}", e),
+                });
+            }
+        }
+        if let Some(status) = &page.status {
+            eprintln!("{}", status);
+        }
+
+        if let Some(lap) = ghost_to_load {
+            spawn_ghost(world, lap);
+        }
+    }
+}
+
+/// Строки HUD открытого браузера: заголовок, список записей, действие и статус
+fn browser_text(page: &SaveBrowserPage) -> Vec<HudTextLine> {
+    let mut lines = Vec::new();
+    if !page.open {
+        return lines;
+    }
+    lines.push(HudTextLine::new("Сохранения, повторы и призраки", [0.5, 0.12], 0.04).align(TextAlign::Center));
+    let rows = page.rows();
+    if rows.is_empty() {
+        lines.push(HudTextLine::new("Записей нет", [0.5, 0.2], 0.03).align(TextAlign::Center));
+    }
+    // Показываем окно записей вокруг выбранной
+    let first = page.selected.saturating_sub(VISIBLE_ROWS / 2).min(rows.len().saturating_sub(VISIBLE_ROWS));
+    for (slot, (index, row)) in rows.iter().enumerate().skip(first).take(VISIBLE_ROWS).enumerate() {
+        let y = 0.2 + slot as f32 * 0.07;
+        let selected = index == page.selected;
+        let color = if selected { [1.0, 0.85, 0.3, 1.0] } else { [1.0, 1.0, 1.0, 0.8] };
+        let marker = if selected { "→ " } else { "  " };
+        lines.push(HudTextLine::new(format!("{}{}", marker, row.title), [0.15, y], 0.03).color(color));
+        let mut details = row.details.clone();
+        if let Some(thumbnail) = row.thumbnail.as_ref().filter(|_| selected) {
+            details.push_str(&format!("  миниатюра {}", thumbnail.display()));
+        }
+        lines.push(HudTextLine::new(format!("  {}", details), [0.15, y + 0.032], 0.022).color([0.8, 0.8, 0.8, 0.8]));
+    }
+    lines.push(HudTextLine::new(format!("← {} →", page.action_label()), [0.5, 0.85], 0.035).align(TextAlign::Center));
+    if let Some(status) = &page.status {
+        lines.push(HudTextLine::new(status.clone(), [0.5, 0.91], 0.025).align(TextAlign::Center));
+    }
+    lines
+}
+
+impl System for SaveBrowserSystem {
+    fn update(&mut self, world: &mut World, _delta_time: f32) {
+        self.navigate(world);
+
+        let lines = world
+            .query::<&Resource<SaveBrowserPage>>()
+            .iter()
+            .next()
+            .map(|(_, page)| browser_text(&page.0))
+            .unwrap_or_default();
+        set_hud_text(world, "save_browser", lines);
+    }
+}
@@ -0,0 +1,225 @@
+use crate::core::ecs::{Resource, System};
+use crate::core::input::InputAction;
+use crate::core::text::{set_hud_text, HudTextLine, TextAlign};
+use crate::game_world::car::{CarClass, CarComponent, PlayerCarComponent};
+use crate::gameplay::ghost::{clear_ghost, current_ghost_combo, spawn_ghost, GhostEntry, GhostLibrary, GHOSTS_DIR};
+use crate::gameplay::records::{current_record_book, ClassRecord, SessionSummary};
+use crate::gameplay::results::format_time;
+use hecs::World;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Строки меню заезда на время
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeTrialRow {
+    RacePbGhost,
+    PersonalBest,
+    ClassRecord,
+    LastSession,
+    LastMilestone,
+}
+
+const ROWS: [TimeTrialRow; 5] = [
+    TimeTrialRow::RacePbGhost,
+    TimeTrialRow::PersonalBest,
+    TimeTrialRow::ClassRecord,
+    TimeTrialRow::LastSession,
+    TimeTrialRow::LastMilestone,
+];
+
+/// Состояние меню заезда на время
+#[derive(Debug, Clone, Default)]
+pub struct TimeTrialMenuPage {
+    pub open: bool,
+    pub selected: usize,
+    pub race_pb_ghost: bool,
+    pub personal_best: Option<GhostEntry>, // Рекорд текущего профиля на этой комбинации
+    pub class: Option<CarClass>,           // Класс машины игрока
+    pub class_record: Option<ClassRecord>, // Рекорд трассы в этом классе из книги рекордов
+    pub last_session: Option<SessionSummary>,
+    pub last_milestone: Option<String>,
+}
+
+impl TimeTrialMenuPage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn selected_row(&self) -> TimeTrialRow {
+        ROWS[self.selected.min(ROWS.len() - 1)]
+    }
+
+    /// Подписи строк для отрисовки: (название, значение)
+    pub fn rows(&self) -> Vec<(String, String)> {
+        ROWS.iter()
+            .map(|row| match row {
+                TimeTrialRow::RacePbGhost => (
+                    "Гонка с призраком рекорда".to_string(),
+                    if self.race_pb_ghost { "Вкл" } else { "Выкл" }.to_string(),
+                ),
+                TimeTrialRow::PersonalBest => (
+                    "Личный рекорд".to_string(),
+                    self.personal_best
+                        .as_ref()
+                        .map(|entry| format_time(entry.lap_time))
+                        .unwrap_or_else(|| "-".to_string()),
+                ),
+                TimeTrialRow::ClassRecord => (
+                    format!("Рекорд трассы, класс {}", self.class.map(|c| c.label()).unwrap_or("-")),
+                    self.class_record
+                        .as_ref()
+                        .map(|record| format!("{} {}", format_time(record.lap_time), record.profile))
+                        .unwrap_or_else(|| "-".to_string()),
+                ),
+                TimeTrialRow::LastSession => (
+                    "Прошлый заезд".to_string(),
+                    self.last_session
+                        .as_ref()
+                        .map(|s| format!("{}, кругов {}, лучший {}", s.profile, s.laps, format_time(s.best_lap)))
+                        .unwrap_or_else(|| "-".to_string()),
+                ),
+                TimeTrialRow::LastMilestone => (
+                    "Последнее достижение".to_string(),
+                    self.last_milestone.clone().unwrap_or_else(|| "-".to_string()),
+                ),
+            })
+            .collect()
+    }
+}
+
+/// Надписи открытого меню заезда на время
+fn time_trial_text(page: &TimeTrialMenuPage) -> Vec<HudTextLine> {
+    if !page.open {
+        return Vec::new();
+    }
+    let mut lines = vec![HudTextLine::new("Заезд на время", [0.5, 0.15], 0.04).align(TextAlign::Center)];
+    for (index, (label, value)) in page.rows().into_iter().enumerate() {
+        let color = if index == page.selected { [1.0, 0.85, 0.3, 1.0] } else { [1.0, 1.0, 1.0, 0.8] };
+        let y = 0.3 + index as f32 * 0.05;
+        lines.push(HudTextLine::new(label, [0.2, y], 0.03).color(color));
+        lines.push(HudTextLine::new(value, [0.8, y], 0.03).color(color).align(TextAlign::Right));
+    }
+    lines
+}
+
+/// Система меню заезда на время: выбор гонки с призраком личного рекорда
+pub struct TimeTrialMenuSystem {
+    held: HashMap<InputAction, bool>,
+}
+
+impl TimeTrialMenuSystem {
+    pub fn new() -> Self {
+        Self { held: HashMap::new() }
+    }
+
+    /// Срабатывание по нажатию, а не удержанию
+    fn pressed(&mut self, inputs: &HashMap<InputAction, f32>, action: InputAction) -> bool {
+        let down = *inputs.get(&action).unwrap_or(&0.0) > 0.5;
+        let was_down = self.held.insert(action, down).unwrap_or(false);
+        down && !was_down
+    }
+}
+
+impl Default for TimeTrialMenuSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TimeTrialMenuSystem {
+    fn navigate(&mut self, world: &mut World) {
+        let inputs = world
+            .query::<&Resource<HashMap<InputAction, f32>>>()
+            .iter()
+            .next()
+            .map(|(_, res)| res.0.clone())
+            .unwrap_or_default();
+
+        let toggle = self.pressed(&inputs, InputAction::ToggleTimeTrialMenu);
+        let back = self.pressed(&inputs, InputAction::MenuBack);
+        let up = self.pressed(&inputs, InputAction::MenuUp);
+        let down = self.pressed(&inputs, InputAction::MenuDown);
+        let left = self.pressed(&inputs, InputAction::MenuLeft);
+        let right = self.pressed(&inputs, InputAction::MenuRight);
+        let confirm = self.pressed(&inputs, InputAction::MenuConfirm);
+
+        let personal_best = current_ghost_combo(world)
+            .filter(|_| toggle)
+            .and_then(|(_, profile, combo)| GhostLibrary::new(Path::new(GHOSTS_DIR)).personal_best(&profile, &combo));
+        let class = world
+            .query::<(&PlayerCarComponent, &CarComponent)>()
+            .iter()
+            .next()
+            .map(|(_, (_, car))| car.class());
+        let book = if toggle { current_record_book(world) } else { None };
+
+        let Some((_, page)) = world.query_mut::<&mut Resource<TimeTrialMenuPage>>().into_iter().next() else {
+            return;
+        };
+        let page = &mut page.0;
+
+        if !page.open {
+            if toggle {
+                // Рекорд перечитывается при каждом открытии: он мог обновиться за заезд
+                page.open = true;
+                page.selected = 0;
+                page.personal_best = personal_best;
+                page.class = class;
+                page.class_record = book.as_ref().zip(class).and_then(|(book, class)| book.class_record(class).cloned());
+                page.last_session = book.as_ref().and_then(|book| book.sessions.first().cloned());
+                page.last_milestone = book.as_ref().and_then(|book| book.milestones.last().map(|m| format!("{}: {}", m.profile, m.text)));
+            }
+            return;
+        }
+
+        if toggle || back {
+            page.open = false;
+            return;
+        }
+
+        if up {
+            page.selected = (page.selected + ROWS.len() - 1) % ROWS.len();
+        }
+        if down {
+            page.selected = (page.selected + 1) % ROWS.len();
+        }
+
+        if page.selected_row() != TimeTrialRow::RacePbGhost || !(left || right || confirm) {
+            return;
+        }
+
+        if page.race_pb_ghost {
+            page.race_pb_ghost = false;
+            clear_ghost(world);
+            return;
+        }
+
+        let lap = match page.personal_best.as_ref().map(|entry| GhostLibrary::load(&entry.path)) {
+            Some(Ok(lap)) => lap,
+            Some(Err(e)) => {
+                eprintln!("Не удалось загрузить призрак рекорда: {}", e);
+                return;
+            }
+            None => {
+                eprintln!("Для этой трассы и машины еще нет личного рекорда");
+                return;
+            }
+        };
+        page.race_pb_ghost = true;
+        spawn_ghost(world, lap);
+    }
+}
+
+impl System for TimeTrialMenuSystem {
+    fn update(&mut self, world: &mut World, _delta_time: f32) {
+        self.navigate(world);
+
+        let lines = world
+            .query::<&Resource<TimeTrialMenuPage>>()
+            .iter()
+            .next()
+            .map(|(_, page)| time_trial_text(&page.0))
+            .unwrap_or_default();
+        set_hud_text(world, "time_trial", lines);
+    }
+}
@@ -0,0 +1,313 @@
+use crate::core::ecs::{Resource, System};
+use crate::core::input::InputAction;
+use crate::game_world::car::{CarComponent, PlayerCarComponent};
+use crate::core::text::{set_hud_text, HudTextLine, TextAlign};
+use crate::game_world::car_definition::find_car_definition;
+use crate::game_world::car_setup::{car_setup_path, CarSetupComponent};
+use crate::game_world::track::SurfaceType;
+use crate::game_world::upgrades::apply_car_upgrades;
+use crate::game_world::vehicle_sim::{VehicleInputs, VehicleSimulation};
+use crate::game_world::upgrades::{CarUpgrades, UpgradeCategory, MAX_UPGRADE_LEVEL};
+use crate::gameplay::profile::{PlayerProfile, PLAYER_PROFILE_PATH};
+use hecs::World;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Строки меню настройки машины
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TuningRow {
+    BrakeBias,
+    AntiRollBalance,
+    Upgrade(UpgradeCategory), // Покупка следующего уровня доработки за кредиты профиля
+}
+
+const ROWS: [TuningRow; 6] = [
+    TuningRow::BrakeBias,
+    TuningRow::AntiRollBalance,
+    TuningRow::Upgrade(UpgradeCategory::Engine),
+    TuningRow::Upgrade(UpgradeCategory::Tires),
+    TuningRow::Upgrade(UpgradeCategory::Brakes),
+    TuningRow::Upgrade(UpgradeCategory::WeightReduction),
+];
+
+/// Шаг изменения баланса стрелками
+const BALANCE_STEP: f32 = 0.01;
+
+/// Расчет разгона: шаг, время на успокоение подвески и предел прогона, с
+const LAUNCH_STEP: f32 = 1.0 / 120.0;
+const LAUNCH_SETTLE_TIME: f32 = 1.0;
+const LAUNCH_MAX_TIME: f32 = 20.0;
+
+/// 100 км/ч, м/с
+const LAUNCH_TARGET_SPEED: f32 = 100.0 / 3.6;
+
+/// Пределы баланса тормозов: за ними машина неуправляема при торможении
+const MIN_BRAKE_BIAS: f32 = 0.4;
+const MAX_BRAKE_BIAS: f32 = 0.8;
+
+/// Состояние меню настройки машины игрока
+#[derive(Debug, Clone, Default)]
+pub struct TuningMenuPage {
+    pub open: bool,
+    pub selected: usize,
+    pub brake_bias: f32,        // Доля тормоза спереди
+    pub anti_roll_balance: f32, // Доля жесткости стабилизаторов спереди
+    pub car_name: String,
+    pub credits: u32,
+    pub upgrades: CarUpgrades, // Купленные доработки; на машине появляются со следующего заезда
+    pub status: Option<String>,
+    pub launch_time: Option<f32>, // Разгон 0-100 км/ч с купленными доработками, с
+}
+
+impl TuningMenuPage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn selected_row(&self) -> TuningRow {
+        ROWS[self.selected.min(ROWS.len() - 1)]
+    }
+
+    /// Подписи строк для отрисовки: (название, значение)
+    pub fn rows(&self) -> Vec<(String, String)> {
+        ROWS.iter()
+            .map(|row| match row {
+                TuningRow::BrakeBias => ("Баланс тормозов".to_string(), front_rear(self.brake_bias)),
+                TuningRow::AntiRollBalance => ("Баланс стабилизаторов".to_string(), front_rear(self.anti_roll_balance)),
+                TuningRow::Upgrade(category) => {
+                    let level = self.upgrades.level(*category);
+                    let value = match self.upgrades.next_level(*category) {
+                        Some(next) => format!("{}/{}, следующий {} кр.", level, MAX_UPGRADE_LEVEL, category.price(next)),
+                        None => format!("{}/{}, максимум", level, MAX_UPGRADE_LEVEL),
+                    };
+                    (category.label().to_string(), value)
+                }
+            })
+            .collect()
+    }
+
+    /// Изменение значения выбранной строки стрелками влево/вправо
+    fn adjust(&mut self, direction: f32) {
+        let step = BALANCE_STEP * direction;
+        match self.selected_row() {
+            TuningRow::BrakeBias => {
+                self.brake_bias = (self.brake_bias + step).clamp(MIN_BRAKE_BIAS, MAX_BRAKE_BIAS);
+            }
+            TuningRow::AntiRollBalance => {
+                self.anti_roll_balance = (self.anti_roll_balance + step).clamp(0.0, 1.0);
+            }
+            TuningRow::Upgrade(_) => {}
+        }
+    }
+
+    /// Кредиты и доработки машины из профиля
+    fn sync_profile(&mut self, profile: &PlayerProfile) {
+        self.credits = profile.credits;
+        self.upgrades = profile.car_upgrades(&self.car_name);
+        self.launch_time = launch_time(&self.car_name, &self.upgrades);
+    }
+}
+
+/// Время разгона до 100 км/ч по асфальту: та же модель машины прогоняется без рендера
+fn launch_time(car_name: &str, upgrades: &CarUpgrades) -> Option<f32> {
+    let definition = find_car_definition(car_name)?;
+    let mut simulation = VehicleSimulation::from_definition(&definition, SurfaceType::Asphalt);
+    let car_entity = simulation.car_entity();
+    apply_car_upgrades(simulation.world_mut(), car_entity, upgrades);
+    simulation.settle(LAUNCH_SETTLE_TIME, LAUNCH_STEP);
+    let full_throttle = VehicleInputs {
+        throttle: 1.0,
+        ..Default::default()
+    };
+    simulation
+        .run(LAUNCH_MAX_TIME, LAUNCH_STEP, |_| full_throttle)
+        .iter()
+        .find(|sample| sample.speed >= LAUNCH_TARGET_SPEED)
+        .map(|sample| sample.time)
+}
+
+/// Строки HUD открытого меню: кредиты, строки настройки и статус покупки
+fn tuning_text(page: &TuningMenuPage) -> Vec<HudTextLine> {
+    if !page.open {
+        return Vec::new();
+    }
+    let mut lines = vec![
+        HudTextLine::new(format!("Настройка: {}", page.car_name), [0.5, 0.15], 0.04).align(TextAlign::Center),
+        HudTextLine::new(format!("Кредиты: {}", page.credits), [0.5, 0.21], 0.03).align(TextAlign::Center),
+    ];
+    for (index, (label, value)) in page.rows().into_iter().enumerate() {
+        let color = if index == page.selected { [1.0, 0.85, 0.3, 1.0] } else { [1.0, 1.0, 1.0, 0.8] };
+        let y = 0.3 + index as f32 * 0.05;
+        lines.push(HudTextLine::new(label, [0.3, y], 0.03).color(color));
+        lines.push(HudTextLine::new(value, [0.7, y], 0.03).color(color).align(TextAlign::Right));
+    }
+    let launch = match page.launch_time {
+        Some(time) => format!("Разгон 0-100 км/ч: {:.1} с", time),
+        None => "Разгон 0-100 км/ч: больше 20 с".to_string(),
+    };
+    lines.push(HudTextLine::new(launch, [0.5, 0.6], 0.025).align(TextAlign::Center));
+    if let Some(status) = &page.status {
+        lines.push(HudTextLine::new(status.clone(), [0.5, 0.65], 0.025).align(TextAlign::Center));
+    }
+    lines
+}
+
+/// Доля передней оси как "60 / 40"
+fn front_rear(front_share: f32) -> String {
+    let front = (front_share * 100.0).round() as u32;
+    format!("{} / {}", front, 100 - front.min(100))
+}
+
+/// Система меню настройки: изменения сразу применяются к машине игрока
+pub struct TuningMenuSystem {
+    held: HashMap<InputAction, bool>,
+}
+
+impl TuningMenuSystem {
+    pub fn new() -> Self {
+        Self { held: HashMap::new() }
+    }
+
+    /// Срабатывание по нажатию, а не удержанию
+    fn pressed(&mut self, inputs: &HashMap<InputAction, f32>, action: InputAction) -> bool {
+        let down = *inputs.get(&action).unwrap_or(&0.0) > 0.5;
+        let was_down = self.held.insert(action, down).unwrap_or(false);
+        down && !was_down
+    }
+}
+
+impl Default for TuningMenuSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TuningMenuSystem {
+    fn navigate(&mut self, world: &mut World) {
+        let inputs = world
+            .query::<&Resource<HashMap<InputAction, f32>>>()
+            .iter()
+            .next()
+            .map(|(_, res)| res.0.clone())
+            .unwrap_or_default();
+
+        let toggle = self.pressed(&inputs, InputAction::ToggleTuningMenu);
+        let back = self.pressed(&inputs, InputAction::MenuBack);
+        let up = self.pressed(&inputs, InputAction::MenuUp);
+        let down = self.pressed(&inputs, InputAction::MenuDown);
+        let left = self.pressed(&inputs, InputAction::MenuLeft);
+        let right = self.pressed(&inputs, InputAction::MenuRight);
+        let confirm = self.pressed(&inputs, InputAction::MenuConfirm);
+
+        let player_setup = world
+            .query::<(&PlayerCarComponent, &CarComponent)>()
+            .iter()
+            .next()
+            .map(|(_, (_, car))| (car.brake_bias, car.anti_roll_balance(), car.name.clone()));
+        let profile = world
+            .query::<&Resource<PlayerProfile>>()
+            .iter()
+            .next()
+            .map(|(_, res)| res.0.clone())
+            .unwrap_or_default();
+
+        let Some((_, page)) = world.query_mut::<&mut Resource<TuningMenuPage>>().into_iter().next() else {
+            return;
+        };
+        let page = &mut page.0;
+
+        if !page.open {
+            // Значения берутся у машины при каждом открытии
+            if let (true, Some((brake_bias, anti_roll_balance, car_name))) = (toggle, player_setup) {
+                page.open = true;
+                page.selected = 0;
+                page.brake_bias = brake_bias;
+                page.anti_roll_balance = anti_roll_balance;
+                page.car_name = car_name;
+                page.status = None;
+                page.sync_profile(&profile);
+            }
+            return;
+        }
+
+        if toggle || back {
+            // При закрытии баланс попадает в настройку машины под эту трассу
+            page.open = false;
+            let (brake_bias, anti_roll_balance) = (page.brake_bias, page.anti_roll_balance);
+            let Some((car_entity, setup)) = world
+                .query_mut::<(&PlayerCarComponent, &mut CarSetupComponent)>()
+                .into_iter()
+                .next()
+                .map(|(entity, (_, setup))| {
+                    setup.brake_bias = Some(brake_bias);
+                    setup.anti_roll_balance = Some(anti_roll_balance);
+                    (entity, setup.clone())
+                })
+            else {
+                return;
+            };
+            if let Some(path) = car_setup_path(world, car_entity) {
+                if let Err(e) = setup.save(&path) {
+                    eprintln!("Не удалось сохранить настройку машины: {}", e);
+                }
+            }
+            return;
+        }
+
+        if up {
+            page.selected = (page.selected + ROWS.len() - 1) % ROWS.len();
+        }
+        if down {
+            page.selected = (page.selected + 1) % ROWS.len();
+        }
+
+        // Покупка доработки списывает кредиты и сразу сохраняет профиль
+        if let (true, TuningRow::Upgrade(category)) = (confirm, page.selected_row()) {
+            let car_name = page.car_name.clone();
+            let Some((_, profile)) = world.query_mut::<&mut Resource<PlayerProfile>>().into_iter().next() else {
+                return;
+            };
+            let profile = &mut profile.0;
+            let status = match profile.buy_upgrade(&car_name, category) {
+                Ok(price) => {
+                    if let Err(e) = profile.save(Path::new(PLAYER_PROFILE_PATH)) {
+                        eprintln!("Не удалось сохранить профиль игрока: {}", e);
+                    }
+                    format!("{}: куплено за {} кр., установится к следующему заезду", category.label(), price)
+                }
+                Err(e) => format!("Покупка не удалась: {}", e),
+            };
+            let profile = profile.clone();
+            if let Some((_, page)) = world.query_mut::<&mut Resource<TuningMenuPage>>().into_iter().next() {
+                page.0.sync_profile(&profile);
+                page.0.status = Some(status);
+            }
+            return;
+        }
+
+        if !(left || right) {
+            return;
+        }
+        page.adjust(if right { 1.0 } else { -1.0 });
+
+        let (brake_bias, anti_roll_balance) = (page.brake_bias, page.anti_roll_balance);
+        for (_, (_, car)) in world.query_mut::<(&PlayerCarComponent, &mut CarComponent)>() {
+            car.brake_bias = brake_bias;
+            car.set_anti_roll_balance(anti_roll_balance);
+        }
+    }
+}
+
+impl System for TuningMenuSystem {
+    fn update(&mut self, world: &mut World, _delta_time: f32) {
+        self.navigate(world);
+
+        let lines = world
+            .query::<&Resource<TuningMenuPage>>()
+            .iter()
+            .next()
+            .map(|(_, page)| tuning_text(&page.0))
+            .unwrap_or_default();
+        set_hud_text(world, "tuning", lines);
+    }
+}
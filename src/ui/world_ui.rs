@@ -0,0 +1,206 @@
+use crate::core::ecs::{Resource, System};
+use crate::core::physics::TransformComponent;
+use crate::core::renderer::{CameraComponent, WorldMarkerInstance, WorldMarkerRenderData, WorldMarkerShape};
+use crate::core::text::{set_hud_text, HudTextLine, TextAlign};
+use crate::game_world::car::{CarComponent, PlayerCarComponent};
+use crate::game_world::track::{CheckpointComponent, PitEntryComponent};
+use crate::gameplay::race::RaceProgressComponent;
+use glam::{Mat4, Quat, Vec2, Vec3};
+use hecs::World;
+
+/// Высота таблички над центром машины, м
+const NAMEPLATE_HEIGHT: f32 = 2.2;
+
+/// Половина высоты таблички, м
+const NAMEPLATE_HALF_HEIGHT: f32 = 0.25;
+
+/// До этого расстояния табличка непрозрачна, м
+const NAMEPLATE_FADE_START: f32 = 40.0;
+
+/// Дальше этого расстояния табличка не видна, м
+const NAMEPLATE_FADE_END: f32 = 150.0;
+
+/// Высота стрелки над центром чекпоинта, м
+const CHECKPOINT_ARROW_HEIGHT: f32 = 4.0;
+
+/// Высота знака въезда в боксы, м
+const PIT_MARKER_HEIGHT: f32 = 3.0;
+
+/// Высота текста подписи в долях высоты экрана
+const LABEL_TEXT_SIZE: f32 = 0.025;
+
+/// Подпись, привязанная к точке в мире; текст рисует слой интерфейса
+#[derive(Debug, Clone)]
+pub struct WorldLabel {
+    pub text: String,
+    pub screen: Vec2, // Нормализованные координаты экрана, -1..1, y вверх
+    pub depth: f32,   // Глубина 0..1 для сортировки с другими подписями
+    pub opacity: f32,
+}
+
+/// Подписи в мире за текущий кадр, от дальних к ближним
+#[derive(Debug, Clone, Default)]
+pub struct WorldUiState {
+    pub labels: Vec<WorldLabel>,
+}
+
+/// Непрозрачность таблички в зависимости от расстояния до камеры
+fn distance_fade(distance: f32) -> f32 {
+    1.0 - ((distance - NAMEPLATE_FADE_START) / (NAMEPLATE_FADE_END - NAMEPLATE_FADE_START)).clamp(0.0, 1.0)
+}
+
+/// Проекция точки мира на экран; None, если точка позади камеры или вне кадра
+fn project(view_proj: &Mat4, point: Vec3) -> Option<(Vec2, f32)> {
+    let clip = *view_proj * point.extend(1.0);
+    if clip.w <= 0.0 {
+        return None;
+    }
+    let ndc = clip.truncate() / clip.w;
+    if ndc.x.abs() > 1.0 || ndc.y.abs() > 1.0 || !(0.0..=1.0).contains(&ndc.z) {
+        return None;
+    }
+    Some((Vec2::new(ndc.x, ndc.y), ndc.z))
+}
+
+/// Система меток в мире: таблички над соперниками, стрелка к следующему чекпоинту и въезд в боксы
+pub struct WorldUiSystem {
+    next_checkpoint: usize,        // Порядковый номер в списке чекпоинтов, отсортированном по индексу
+    previous_local_z: Option<f32>, // Положение игрока относительно плоскости следующего чекпоинта
+}
+
+impl WorldUiSystem {
+    pub fn new() -> Self {
+        Self {
+            next_checkpoint: 0,
+            previous_local_z: None,
+        }
+    }
+
+    /// Позиция следующего чекпоинта; переключается, когда игрок пересек его плоскость
+    fn advance_checkpoint(&mut self, world: &World, player_position: Option<Vec3>) -> Option<Vec3> {
+        let mut checkpoints: Vec<(usize, f32, Vec3, Quat)> = world
+            .query::<(&CheckpointComponent, &TransformComponent)>()
+            .iter()
+            .map(|(_, (checkpoint, transform))| (checkpoint.index, checkpoint.width, transform.position, transform.rotation))
+            .collect();
+        if checkpoints.is_empty() {
+            return None;
+        }
+        checkpoints.sort_by_key(|(index, _, _, _)| *index);
+        self.next_checkpoint %= checkpoints.len();
+
+        let (_, width, checkpoint_position, rotation) = checkpoints[self.next_checkpoint];
+        if let Some(position) = player_position {
+            let local = rotation.inverse() * (position - checkpoint_position);
+            let crossed = self.previous_local_z.is_some_and(|z| z < 0.0 && local.z >= 0.0);
+            if crossed && local.x.abs() <= width / 2.0 {
+                self.next_checkpoint = (self.next_checkpoint + 1) % checkpoints.len();
+                self.previous_local_z = None;
+                return Some(checkpoints[self.next_checkpoint].2);
+            }
+            self.previous_local_z = Some(local.z);
+        }
+        Some(checkpoint_position)
+    }
+}
+
+impl Default for WorldUiSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl System for WorldUiSystem {
+    fn update(&mut self, world: &mut World, _delta_time: f32) {
+        let Some((camera_position, view_proj)) = world
+            .query::<&CameraComponent>()
+            .iter()
+            .next()
+            .map(|(_, camera)| (camera.position, camera.build_view_projection_matrix()))
+        else {
+            return;
+        };
+
+        let player_position = world
+            .query::<(&PlayerCarComponent, &TransformComponent)>()
+            .iter()
+            .next()
+            .map(|(_, (_, transform))| transform.position);
+
+        let mut markers = Vec::new();
+        let mut labels = Vec::new();
+
+        // Таблички над соперниками: машины ИИ и других игроков
+        for (_, (_, progress, transform)) in world
+            .query::<(&CarComponent, &RaceProgressComponent, &TransformComponent)>()
+            .without::<&PlayerCarComponent>()
+            .iter()
+        {
+            let anchor = transform.position + Vec3::Y * NAMEPLATE_HEIGHT;
+            let opacity = distance_fade(anchor.distance(camera_position));
+            if opacity <= 0.0 {
+                continue;
+            }
+            let text = format!("P{} {}", progress.position, progress.driver_name);
+            let aspect = 0.5 + text.chars().count() as f32 * 0.25;
+            markers.push(WorldMarkerInstance::new(
+                anchor,
+                NAMEPLATE_HALF_HEIGHT,
+                [0.05, 0.05, 0.08, 0.6 * opacity],
+                WorldMarkerShape::Plate,
+                aspect,
+            ));
+            if let Some((screen, depth)) = project(&view_proj, anchor) {
+                labels.push(WorldLabel { text, screen, depth, opacity });
+            }
+        }
+
+        // Стрелка над следующим чекпоинтом
+        if let Some(position) = self.advance_checkpoint(world, player_position) {
+            markers.push(WorldMarkerInstance::new(
+                position + Vec3::Y * CHECKPOINT_ARROW_HEIGHT,
+                0.8,
+                [1.0, 0.85, 0.1, 0.9],
+                WorldMarkerShape::Arrow,
+                1.0,
+            ));
+        }
+
+        // Знаки въезда в боксы
+        for (_, (_, transform)) in world.query::<(&PitEntryComponent, &TransformComponent)>().iter() {
+            let anchor = transform.position + Vec3::Y * PIT_MARKER_HEIGHT;
+            markers.push(WorldMarkerInstance::new(anchor, 0.6, [0.1, 0.5, 1.0, 0.9], WorldMarkerShape::Diamond, 1.0));
+            if let Some((screen, depth)) = project(&view_proj, anchor) {
+                labels.push(WorldLabel { text: "PIT".to_string(), screen, depth, opacity: 1.0 });
+            }
+        }
+
+        // Ближние подписи рисуются последними, поверх дальних
+        labels.sort_by(|a, b| b.depth.total_cmp(&a.depth));
+
+        // Экранные координаты -1..1 с y вверх переводятся в доли экрана от левого верхнего угла
+        let text = labels
+            .iter()
+            .map(|label| {
+                let position = [(label.screen.x + 1.0) * 0.5, (1.0 - label.screen.y) * 0.5 - LABEL_TEXT_SIZE * 0.5];
+                HudTextLine::new(label.text.clone(), position, LABEL_TEXT_SIZE)
+                    .color([1.0, 1.0, 1.0, label.opacity])
+                    .align(TextAlign::Center)
+            })
+            .collect();
+        set_hud_text(world, "world_labels", text);
+
+        match world.query_mut::<&mut Resource<WorldMarkerRenderData>>().into_iter().next() {
+            Some((_, data)) => data.0.instances = markers,
+            None => {
+                world.spawn((Resource(WorldMarkerRenderData { instances: markers }),));
+            }
+        }
+        match world.query_mut::<&mut Resource<WorldUiState>>().into_iter().next() {
+            Some((_, state)) => state.0.labels = labels,
+            None => {
+                world.spawn((Resource(WorldUiState { labels }),));
+            }
+        }
+    }
+}